@@ -0,0 +1,102 @@
+//! Calling convention of PetraVM.
+//!
+//! Functions run in frames of the VROM, allocated by their caller and whose
+//! slots are addressed as `fp ^ slot`. The [`Abi`] describes how frames are
+//! laid out, for the front-ends and runtimes lowering calls, and can be
+//! encoded as JSON or CBOR with the [`Export`] trait for front-ends written in
+//! other languages:
+//!
+//! - the return PC and FP are written by `CALLI` and `CALLV` in the first slots
+//!   of the frame of the callee, copied by `TAILI` and `TAILV` and read by
+//!   `RET`. Their slots are fixed by these instructions;
+//! - the arguments, including the pointers to where results must be written,
+//!   follow from the first argument slot. Callers write them in the frame of
+//!   the callee before calling it, e.g. with `MVV.W @next_fp[slot], @src`;
+//! - frames are aligned to their size padded to a power of two, so that the
+//!   address `fp ^ slot` stays in the frame for any of its slots. Values
+//!   spanning several slots must be aligned to their number of slots, padded to
+//!   a power of two, as the VROM accesses them as a whole.
+//!
+//! The convention is fixed by the VM: the return slots by the call
+//! instructions, and the smallest frame size by the VROM allocator. The
+//! [`Abi`] describes it rather than configures it, and [`Abi::STANDARD`] is
+//! the only one followed by the assembler, the RISC-V and wasm front-ends and
+//! the runtimes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::Export;
+
+/// Layout of the frames of called functions, see the [module-level
+/// documentation](self).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Abi {
+    /// Slot holding the PC to return to.
+    pub return_pc_slot: u16,
+    /// Slot holding the FP of the caller.
+    pub return_fp_slot: u16,
+    /// Slot holding the first argument.
+    pub first_arg_slot: u16,
+    /// Smallest frame size, in slots. Frames being aligned to their size
+    /// padded to a power of two, it is also their smallest alignment.
+    pub min_frame_size: u16,
+}
+
+impl Abi {
+    /// The calling convention of the VM, the only one it supports.
+    pub const STANDARD: Self = Self {
+        return_pc_slot: 0,
+        return_fp_slot: 1,
+        first_arg_slot: 2,
+        min_frame_size: 2,
+    };
+
+    /// Returns the slot holding the argument `index`.
+    pub const fn arg_slot(&self, index: u16) -> u16 {
+        self.first_arg_slot + index
+    }
+
+    /// Returns the alignment of a frame of `frame_size` slots, which is also
+    /// the number of slots it takes in the VROM.
+    pub const fn frame_alignment(&self, frame_size: u32) -> u32 {
+        let padded = frame_size.next_power_of_two();
+        if padded < self.min_frame_size as u32 {
+            self.min_frame_size as u32
+        } else {
+            padded
+        }
+    }
+
+    /// Returns the alignment of the first slot of a value spanning `slots`
+    /// slots.
+    pub const fn value_alignment(slots: u32) -> u32 {
+        slots.next_power_of_two()
+    }
+}
+
+impl Default for Abi {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl Export for Abi {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abi() {
+        let abi = Abi::STANDARD;
+        assert_eq!(abi.arg_slot(3), 5);
+        assert_eq!(abi.frame_alignment(0), 2);
+        assert_eq!(abi.frame_alignment(10), 16);
+        assert_eq!(Abi::value_alignment(2), 2);
+        assert_eq!(Abi::value_alignment(9), 16);
+
+        let json = abi.to_json().unwrap();
+        assert!(json.contains("\"first_arg_slot\": 2"), "{json}");
+        assert_eq!(serde_json::from_str::<Abi>(&json).unwrap(), abi);
+    }
+}
@@ -0,0 +1,367 @@
+//! Control-flow and call graphs of assembled programs.
+//!
+//! The PROM of a program is split into basic blocks, straight-line sequences of
+//! instructions only entered at their first instruction and only left at their
+//! last one. Blocks are linked by the edges of branches and jumps to immediate
+//! targets, and grouped into functions: the entry point, the targets of `CALLI`
+//! and `TAILI`, and the labels with a frame size, which may be called through
+//! `CALLV` and `TAILV`.
+//!
+//! Blocks and functions are identified by the PROM index of their first
+//! instruction.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use binius_m3::builder::B32;
+
+use crate::{AssembledProgram, FieldPc, Opcode};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ControlFlowError {
+    #[error("Target {target} of the instruction at PROM index {index} is not in the PROM")]
+    UnknownTarget { index: usize, target: B32 },
+}
+
+/// How the execution leaves a basic block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockExit {
+    /// Falls through to the next block, which is a branch target.
+    FallThrough,
+    /// Jumps to the block at the given PROM index.
+    Jump(usize),
+    /// Branches to the block at the given PROM index, or falls through.
+    Branch(usize),
+    /// Calls the function at the given PROM index, then falls through.
+    Call(usize),
+    /// Tail calls the function at the given PROM index.
+    TailCall(usize),
+    /// Jumps to a target held in a slot.
+    IndirectJump,
+    /// Calls a function held in a slot, then falls through.
+    IndirectCall,
+    /// Tail calls a function held in a slot.
+    IndirectTailCall,
+    /// Returns to the caller.
+    Return,
+    /// Halts or aborts the execution.
+    Halt,
+}
+
+/// A straight-line sequence of instructions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// PROM index of the first instruction.
+    pub start: usize,
+    /// PROM index one past the last instruction.
+    pub end: usize,
+    pub exit: BlockExit,
+    /// Blocks the execution may continue with in the same function, by PROM
+    /// index.
+    pub successors: Vec<usize>,
+}
+
+/// A function of the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Function {
+    /// PROM index of the entry block.
+    pub entry: usize,
+    /// Label of the entry block, if any.
+    pub name: Option<String>,
+    /// Blocks reachable from the entry block, by PROM index.
+    pub blocks: BTreeSet<usize>,
+    /// Functions called or tail called with an immediate target, by PROM
+    /// index.
+    pub callees: BTreeSet<usize>,
+    /// Whether the function calls or tail calls functions held in slots.
+    pub indirect_calls: bool,
+}
+
+/// Basic blocks and functions of a program. See the [module-level
+/// documentation](self) for details.
+#[derive(Clone, Debug)]
+pub struct ControlFlowGraph {
+    blocks: BTreeMap<usize, BasicBlock>,
+    functions: BTreeMap<usize, Function>,
+}
+
+impl ControlFlowGraph {
+    /// Builds the control-flow graph of `program`.
+    pub fn from_program(program: &AssembledProgram) -> Result<Self, ControlFlowError> {
+        let prom = &program.prom;
+        let labels = program
+            .labels
+            .iter()
+            .map(|(name, (_, prom_index, _))| (*prom_index as usize, name.clone()))
+            .collect::<HashMap<_, _>>();
+
+        // Exits of the instructions ending a block, by PROM index.
+        let mut exits = BTreeMap::new();
+        let mut leaders = BTreeSet::new();
+        let mut functions = BTreeSet::new();
+        if !prom.is_empty() {
+            leaders.insert(0);
+            functions.insert(0);
+        }
+        // Labels with a frame size may be called through a slot.
+        for (field_pc, prom_index, _) in program.labels.values() {
+            if program.frame_sizes.contains_key(field_pc) {
+                functions.insert(*prom_index as usize);
+            }
+        }
+
+        for index in 0..prom.len() {
+            let exit = match prom[index].opcode() {
+                Opcode::Jumpi => BlockExit::Jump(target(program, index)?),
+                Opcode::Bnz | Opcode::Beq | Opcode::Bne | Opcode::Blt | Opcode::Bge => {
+                    BlockExit::Branch(target(program, index)?)
+                }
+                Opcode::Calli => BlockExit::Call(target(program, index)?),
+                Opcode::Taili => BlockExit::TailCall(target(program, index)?),
+                Opcode::Jumpv => BlockExit::IndirectJump,
+                Opcode::Callv => BlockExit::IndirectCall,
+                Opcode::Tailv => BlockExit::IndirectTailCall,
+                Opcode::Ret => BlockExit::Return,
+                Opcode::Halt | Opcode::Abort => BlockExit::Halt,
+                _ => continue,
+            };
+            match exit {
+                BlockExit::Jump(target) | BlockExit::Branch(target) => {
+                    leaders.insert(target);
+                }
+                BlockExit::Call(callee) | BlockExit::TailCall(callee) => {
+                    leaders.insert(callee);
+                    functions.insert(callee);
+                }
+                _ => {}
+            }
+            leaders.insert(index + 1);
+            exits.insert(index, exit);
+        }
+        leaders.extend(functions.iter().copied());
+        leaders.retain(|&leader| leader < prom.len());
+
+        let starts = leaders.into_iter().collect::<Vec<_>>();
+        let mut blocks = BTreeMap::new();
+        for (i, &start) in starts.iter().enumerate() {
+            let end = starts.get(i + 1).copied().unwrap_or(prom.len());
+            let exit = exits
+                .get(&(end - 1))
+                .copied()
+                .unwrap_or(BlockExit::FallThrough);
+            let fall_through = (end < prom.len()).then_some(end);
+            let successors = match exit {
+                BlockExit::FallThrough | BlockExit::Call(_) | BlockExit::IndirectCall => {
+                    fall_through.into_iter().collect()
+                }
+                BlockExit::Jump(target) => vec![target],
+                BlockExit::Branch(target) => {
+                    let mut successors = vec![target];
+                    successors.extend(fall_through.filter(|&next| next != target));
+                    successors
+                }
+                BlockExit::TailCall(_)
+                | BlockExit::IndirectJump
+                | BlockExit::IndirectTailCall
+                | BlockExit::Return
+                | BlockExit::Halt => vec![],
+            };
+            blocks.insert(
+                start,
+                BasicBlock {
+                    start,
+                    end,
+                    exit,
+                    successors,
+                },
+            );
+        }
+
+        let functions = functions
+            .into_iter()
+            .filter(|entry| blocks.contains_key(entry))
+            .map(|entry| {
+                let function = Function::new(entry, labels.get(&entry).cloned(), &blocks);
+                (entry, function)
+            })
+            .collect();
+
+        Ok(Self { blocks, functions })
+    }
+
+    /// Returns the basic blocks, by PROM index.
+    pub const fn blocks(&self) -> &BTreeMap<usize, BasicBlock> {
+        &self.blocks
+    }
+
+    /// Returns the basic block holding the instruction at `prom_index`.
+    pub fn block_of(&self, prom_index: usize) -> Option<&BasicBlock> {
+        self.blocks
+            .range(..=prom_index)
+            .next_back()
+            .map(|(_, block)| block)
+            .filter(|block| prom_index < block.end)
+    }
+
+    /// Returns the blocks the execution may come from into the block starting
+    /// at `start`, in the same function.
+    pub fn predecessors(&self, start: usize) -> Vec<usize> {
+        self.blocks
+            .values()
+            .filter(|block| block.successors.contains(&start))
+            .map(|block| block.start)
+            .collect()
+    }
+
+    /// Returns the functions, by PROM index of their entry.
+    pub const fn functions(&self) -> &BTreeMap<usize, Function> {
+        &self.functions
+    }
+
+    /// Returns the call graph: the functions called or tail called by each
+    /// function, by PROM index.
+    pub fn call_graph(&self) -> BTreeMap<usize, BTreeSet<usize>> {
+        self.functions
+            .iter()
+            .map(|(&entry, function)| (entry, function.callees.clone()))
+            .collect()
+    }
+
+    /// Returns the blocks reachable from the entry point, through branches and
+    /// calls. Functions with a frame size are considered reachable whenever
+    /// an indirect call is, as they may be called through a slot.
+    pub fn reachable_blocks(&self) -> BTreeSet<usize> {
+        let mut reachable = BTreeSet::new();
+        let mut visited = BTreeSet::new();
+        let mut queue = self
+            .functions
+            .keys()
+            .next()
+            .copied()
+            .into_iter()
+            .collect::<VecDeque<_>>();
+        let mut indirect = false;
+        while let Some(entry) = queue.pop_front() {
+            if !visited.insert(entry) {
+                continue;
+            }
+            let function = &self.functions[&entry];
+            reachable.extend(function.blocks.iter().copied());
+            queue.extend(function.callees.iter().copied());
+            if function.indirect_calls && !indirect {
+                indirect = true;
+                queue.extend(self.functions.keys().copied());
+            }
+        }
+        reachable
+    }
+}
+
+impl Function {
+    /// Collects the blocks reachable from `entry` and the functions they call.
+    fn new(entry: usize, name: Option<String>, blocks: &BTreeMap<usize, BasicBlock>) -> Self {
+        let mut function = Self {
+            entry,
+            name,
+            blocks: BTreeSet::new(),
+            callees: BTreeSet::new(),
+            indirect_calls: false,
+        };
+        let mut queue = VecDeque::from([entry]);
+        while let Some(start) = queue.pop_front() {
+            if !function.blocks.insert(start) {
+                continue;
+            }
+            let block = &blocks[&start];
+            match block.exit {
+                BlockExit::Call(callee) | BlockExit::TailCall(callee) => {
+                    function.callees.insert(callee);
+                }
+                BlockExit::IndirectCall | BlockExit::IndirectTailCall => {
+                    function.indirect_calls = true;
+                }
+                _ => {}
+            }
+            queue.extend(block.successors.iter().copied());
+        }
+        function
+    }
+}
+
+/// Returns the PROM index targeted by the branch, jump or call at `index`.
+fn target(program: &AssembledProgram, index: usize) -> Result<usize, ControlFlowError> {
+    let entry = &program.prom[index];
+    // The advice holds the PROM index of the target, when available.
+    if let Some((prom_index, _)) = entry.advice {
+        return Ok(prom_index as usize);
+    }
+    let [low, high, _] = entry.args();
+    let target = B32::new(low.val() as u32 | ((high.val() as u32) << 16));
+    program
+        .prom
+        .locate(FieldPc::from(target))
+        .map(|(prom_index, _)| prom_index as usize)
+        .ok_or(ControlFlowError::UnknownTarget { index, target })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assembler;
+
+    #[test]
+    fn test_func_call_cfg() {
+        let program = Assembler::from_code(include_str!("../../../examples/func_call.asm"))
+            .expect("The program should assemble");
+        let cfg = ControlFlowGraph::from_program(&program).unwrap();
+
+        let blocks = cfg
+            .blocks()
+            .values()
+            .map(|block| (block.start, block.end, block.exit))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [
+                (0, 4, BlockExit::Call(7)),
+                (4, 7, BlockExit::Return),
+                (7, 9, BlockExit::Return)
+            ]
+        );
+        assert_eq!(cfg.blocks()[&0].successors, [4]);
+        assert_eq!(cfg.predecessors(4), [0]);
+        assert_eq!(cfg.block_of(5).map(|block| block.start), Some(4));
+        assert!(cfg.block_of(9).is_none());
+
+        let call_graph = cfg.call_graph();
+        assert_eq!(call_graph[&0], BTreeSet::from([7]));
+        assert!(call_graph[&7].is_empty());
+        assert_eq!(cfg.functions()[&7].name.as_deref(), Some("add_two_numbers"));
+        assert_eq!(cfg.functions()[&0].blocks, BTreeSet::from([0, 4]));
+    }
+
+    #[test]
+    fn test_branches_and_dead_code() {
+        let code = "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #3
+            loop:
+                XORI @3, @2, #1
+                BNZ loop, @3
+                RET
+            dead:
+                RET
+            ";
+        let program = Assembler::from_code(code).unwrap();
+        let cfg = ControlFlowGraph::from_program(&program).unwrap();
+
+        let blocks = cfg.blocks();
+        assert_eq!(blocks.keys().copied().collect::<Vec<_>>(), [0, 1, 3, 4]);
+        assert_eq!(blocks[&0].exit, BlockExit::FallThrough);
+        assert_eq!(blocks[&1].exit, BlockExit::Branch(1));
+        assert_eq!(blocks[&1].successors, [1, 3]);
+        assert_eq!(cfg.predecessors(1), [0, 1]);
+
+        // The block after the return is never executed.
+        assert_eq!(cfg.reachable_blocks(), BTreeSet::from([0, 1, 3]));
+    }
+}
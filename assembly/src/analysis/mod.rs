@@ -0,0 +1,5 @@
+//! Static analyses of assembled programs.
+
+pub mod cfg;
+
+pub use cfg::{BasicBlock, BlockExit, ControlFlowError, ControlFlowGraph, Function};
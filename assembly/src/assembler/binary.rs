@@ -0,0 +1,330 @@
+//! Binary on-disk format of an [`AssembledProgram`].
+//!
+//! This allows assembling a program once and shipping the result, without
+//! parsing the source again. All integers are little-endian, and the layout is:
+//! - the magic bytes `PVMA` and a `u16` format version,
+//! - the PROM entries: the four `u16` of the instruction, in the layout of
+//!   [`INSTRUCTION_ENCODING_VERSION`](crate::INSTRUCTION_ENCODING_VERSION), the
+//!   `u32` field PC, a flag byte (bit 0: prover-only, bit 1: has advice) and
+//!   the two `u32` of the advice when present,
+//! - the labels: name, `u32` field PC, `u32` PROM index and `u32` PC,
+//! - the frame sizes: `u32` field PC and `u16` size,
+//! - the static data bytes,
+//! - the data labels: name and `u32` address,
+//! - the `u32` public slots, in declaration order.
+//!
+//! Every sequence is preceded by its `u32` length, names being UTF-8 bytes.
+//! Map entries are sorted by key, so that the encoding is deterministic.
+
+use std::collections::HashMap;
+
+use binius_m3::builder::B32;
+
+use super::{AssembledProgram, AssemblerError};
+use crate::execution::{Instruction, InstructionEncoding, InterpreterInstruction};
+
+const MAGIC: &[u8; 4] = b"PVMA";
+const VERSION: u16 = 3;
+
+const PROVER_ONLY_FLAG: u8 = 1;
+const ADVICE_FLAG: u8 = 1 << 1;
+
+impl AssembledProgram {
+    /// Encodes the program in its binary format.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes(MAGIC);
+        writer.u16(VERSION);
+
+        writer.len(self.prom.len());
+        for entry in &self.prom {
+            for word in entry.instruction.encode() {
+                writer.u16(word);
+            }
+            writer.u32(entry.field_pc.val());
+            let mut flags = 0;
+            if entry.prover_only {
+                flags |= PROVER_ONLY_FLAG;
+            }
+            if entry.advice.is_some() {
+                flags |= ADVICE_FLAG;
+            }
+            writer.u8(flags);
+            if let Some((prom_index, pc)) = entry.advice {
+                writer.u32(prom_index);
+                writer.u32(pc);
+            }
+        }
+
+        writer.len(self.labels.len());
+        for (name, (field_pc, prom_index, pc)) in sorted(&self.labels) {
+            writer.string(name);
+            writer.u32(field_pc.val());
+            writer.u32(*prom_index);
+            writer.u32(*pc);
+        }
+
+        writer.len(self.frame_sizes.len());
+        let mut frame_sizes = self.frame_sizes.iter().collect::<Vec<_>>();
+        frame_sizes.sort_by_key(|(field_pc, _)| field_pc.val());
+        for (field_pc, size) in frame_sizes {
+            writer.u32(field_pc.val());
+            writer.u16(*size);
+        }
+
+        writer.len(self.data.len());
+        writer.bytes(&self.data);
+
+        writer.len(self.data_labels.len());
+        for (name, addr) in sorted(&self.data_labels) {
+            writer.string(name);
+            writer.u32(*addr);
+        }
+
+        writer.len(self.public_slots.len());
+        for slot in &self.public_slots {
+            writer.u32(*slot);
+        }
+
+        writer.0
+    }
+
+    /// Decodes a program from its binary format.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, AssemblerError> {
+        let mut reader = Reader(bytes);
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(invalid("bad magic bytes"));
+        }
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(invalid(format!("unsupported version {version}")));
+        }
+
+        let prom = (0..reader.len()?)
+            .map(|_| {
+                let instruction = Instruction::decode([
+                    reader.u16()?,
+                    reader.u16()?,
+                    reader.u16()?,
+                    reader.u16()?,
+                ])
+                .map_err(|err| invalid(err.to_string()))?;
+                let field_pc = B32::new(reader.u32()?);
+                let flags = reader.u8()?;
+                if flags & !(PROVER_ONLY_FLAG | ADVICE_FLAG) != 0 {
+                    return Err(invalid(format!("unknown flags 0x{flags:x}")));
+                }
+                let advice = if flags & ADVICE_FLAG != 0 {
+                    Some((reader.u32()?, reader.u32()?))
+                } else {
+                    None
+                };
+                Ok(InterpreterInstruction::new(
+                    instruction,
+                    field_pc,
+                    advice,
+                    flags & PROVER_ONLY_FLAG != 0,
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let labels = (0..reader.len()?)
+            .map(|_| {
+                let name = reader.string()?;
+                Ok((
+                    name,
+                    (B32::new(reader.u32()?), reader.u32()?, reader.u32()?),
+                ))
+            })
+            .collect::<Result<_, AssemblerError>>()?;
+
+        let frame_sizes = (0..reader.len()?)
+            .map(|_| Ok((B32::new(reader.u32()?), reader.u16()?)))
+            .collect::<Result<_, AssemblerError>>()?;
+
+        let data_len = reader.len()?;
+        let data = reader.bytes(data_len)?.to_vec();
+
+        let data_labels = (0..reader.len()?)
+            .map(|_| Ok((reader.string()?, reader.u32()?)))
+            .collect::<Result<_, AssemblerError>>()?;
+
+        let public_slots = (0..reader.len()?)
+            .map(|_| reader.u32())
+            .collect::<Result<_, _>>()?;
+
+        if !reader.0.is_empty() {
+            return Err(invalid("trailing bytes"));
+        }
+
+        Ok(Self {
+            prom,
+            labels,
+            frame_sizes,
+            data,
+            data_labels,
+            public_slots,
+            debug_info: None,
+        })
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> AssemblerError {
+    AssemblerError::InvalidBinary(reason.into())
+}
+
+/// Returns the entries of `map`, sorted by name.
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(name, _)| *name);
+    entries
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(len.try_into().expect("Lengths fit in 32 bits"));
+    }
+
+    fn string(&mut self, string: &str) {
+        self.len(string.len());
+        self.bytes(string.as_bytes());
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], AssemblerError> {
+        if self.0.len() < len {
+            return Err(invalid("unexpected end of input"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], AssemblerError> {
+        Ok(self.bytes(N)?.try_into().expect("Slice has length N"))
+    }
+
+    fn u8(&mut self) -> Result<u8, AssemblerError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, AssemblerError> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, AssemblerError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn len(&mut self) -> Result<usize, AssemblerError> {
+        let len = self.u32()? as usize;
+        // Every element takes at least one byte, which bounds allocations on
+        // corrupted inputs.
+        if len > self.0.len() {
+            return Err(invalid("unexpected end of input"));
+        }
+        Ok(len)
+    }
+
+    fn string(&mut self) -> Result<String, AssemblerError> {
+        let len = self.len()?;
+        String::from_utf8(self.bytes(len)?.to_vec()).map_err(|_| invalid("invalid label name"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assembler;
+
+    #[test]
+    fn test_round_trip() {
+        let code = r#"
+            .data
+            message:
+                .ascii "hello"
+            .text
+
+            .public @2
+
+            #[framesize(0x10)]
+            _start:
+                ALLOCI! @4, #8
+                LDI.W @2, #-1
+                B32_MULI @3, @2, #65537
+                MVV.W @4[2], @2
+                CALLI double, @4
+                RET
+
+            double:
+                ADD @3, @2, @2
+                RET
+            "#;
+        let program = Assembler::from_code(code).unwrap();
+        let bytes = program.serialize();
+        assert_eq!(&bytes[..4], MAGIC);
+
+        let decoded = AssembledProgram::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.prom, program.prom);
+        assert_eq!(decoded.labels, program.labels);
+        assert_eq!(decoded.frame_sizes, program.frame_sizes);
+        assert_eq!(decoded.data, program.data);
+        assert_eq!(decoded.data_labels, program.data_labels);
+        assert_eq!(decoded.public_slots, program.public_slots);
+        // The encoding is deterministic.
+        assert_eq!(decoded.serialize(), bytes);
+    }
+
+    #[test]
+    fn test_invalid_binary() {
+        let program = Assembler::from_code("_start: RET").unwrap();
+        let bytes = program.serialize();
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 1;
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        // The opcode of the first PROM entry follows the magic bytes, the
+        // version and the PROM length.
+        let mut bad_opcode = bytes.clone();
+        bad_opcode[10..12].copy_from_slice(&0u16.to_le_bytes());
+
+        for bytes in [
+            bad_magic,
+            bad_version,
+            trailing,
+            bad_opcode,
+            bytes[..bytes.len() - 1].to_vec(),
+            vec![],
+        ] {
+            assert!(matches!(
+                AssembledProgram::deserialize(&bytes),
+                Err(AssemblerError::InvalidBinary(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Dead-code elimination.
+//!
+//! Instructions in no basic block reachable from the entry point, according to
+//! the [`ControlFlowGraph`] of the program, are dropped before laying out the
+//! PROM, along with the labels pointing to them. The program is then assembled
+//! again, so that the PROM is compacted and branch targets are updated.
+//!
+//! Jumps through `JUMPV` may target any label, so programs with a reachable
+//! `JUMPV` are left untouched.
+
+use std::collections::BTreeSet;
+
+use super::{Assembler, AssemblerError, AssemblerOptions};
+use crate::analysis::{BlockExit, ControlFlowGraph};
+use crate::parser::InstructionsWithLabels;
+
+/// Drops the code of `instructions` that is unreachable from the entry point.
+pub(crate) fn eliminate_dead_code(
+    instructions: Vec<InstructionsWithLabels>,
+) -> Result<Vec<InstructionsWithLabels>, AssemblerError> {
+    let program = Assembler::assemble(instructions.clone(), &AssemblerOptions::default())?;
+    let cfg = ControlFlowGraph::from_program(&program)?;
+
+    let mut reachable = BTreeSet::new();
+    for start in cfg.reachable_blocks() {
+        let block = &cfg.blocks()[&start];
+        if block.exit == BlockExit::IndirectJump {
+            return Ok(instructions);
+        }
+        reachable.extend(block.start..block.end);
+    }
+
+    // PROM index of each instruction, following the padding of `.align`
+    // directives in `pad_alignments`.
+    let mut prom_index = 0;
+    let mut padded_index = 0;
+    let mut live = Vec::with_capacity(instructions.len());
+    for instruction in &instructions {
+        match instruction {
+            InstructionsWithLabels::Label(..) | InstructionsWithLabels::Location(_) => {
+                live.push(None)
+            }
+            InstructionsWithLabels::Align(alignment) => {
+                let padding = padded_index.next_multiple_of(*alignment as usize) - padded_index;
+                padded_index += padding;
+                prom_index += padding;
+                live.push(Some(true));
+            }
+            instruction => {
+                let len = match instruction {
+                    InstructionsWithLabels::B32Muli { .. } => 2,
+                    _ => 1,
+                };
+                live.push(Some(
+                    (prom_index..prom_index + len).any(|index| reachable.contains(&index)),
+                ));
+                padded_index += 1;
+                prom_index += len;
+            }
+        }
+    }
+
+    // Labels and source locations point to the next instruction, and are kept
+    // along with it.
+    let mut next_live = false;
+    for is_live in live.iter_mut().rev() {
+        match is_live {
+            Some(is_live) => next_live = *is_live,
+            None => *is_live = Some(next_live),
+        }
+    }
+
+    Ok(instructions
+        .into_iter()
+        .zip(live)
+        .filter_map(|(instruction, is_live)| is_live.unwrap_or_default().then_some(instruction))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eliminate_dead_code() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #3
+                BNZ taken, @2
+                J done
+            unused:
+                LDI.W @3, #1
+                RET
+            taken:
+                CALLI helper, @4
+            done:
+                RET
+            #[framesize(0x4)]
+            helper:
+                RET
+            #[framesize(0x4)]
+            never_called:
+                LDI.W @2, #7
+                RET
+            ";
+        let options = AssemblerOptions {
+            eliminate_dead_code: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(code, &options).unwrap();
+        let full = Assembler::from_code(code).unwrap();
+        assert_eq!(full.prom.len(), 10);
+        assert_eq!(program.prom.len(), 6);
+
+        assert!(!program.labels.contains_key("unused"));
+        assert!(!program.labels.contains_key("never_called"));
+        // Branch targets follow the compacted PROM.
+        let cfg = ControlFlowGraph::from_program(&program).unwrap();
+        assert_eq!(cfg.blocks()[&0].exit, BlockExit::Branch(3));
+        assert_eq!(cfg.blocks()[&3].exit, BlockExit::Call(5));
+        assert_eq!(program.labels["helper"].1, 5);
+    }
+
+    #[test]
+    fn test_indirect_calls_keep_functions() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #0
+                CALLV @2, @3
+                RET
+            #[framesize(0x4)]
+            callback:
+                RET
+            ";
+        let options = AssemblerOptions {
+            eliminate_dead_code: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(code, &options).unwrap();
+        assert_eq!(program.prom.len(), 4);
+        assert!(program.labels.contains_key("callback"));
+    }
+}
@@ -0,0 +1,112 @@
+//! Source maps of assembled programs.
+//!
+//! When [`AssemblerOptions::debug_info`](super::AssemblerOptions::debug_info)
+//! is set, the assembler records the [`SourceLocation`] of every PROM
+//! instruction in the [`DebugInfo`] of the program: the source line it was
+//! assembled from, and, for code lowered from another language, its location
+//! in the original program, as given by the last `.loc "origin"` directive
+//! preceding it. The RISC-V front-end emits one of these per lowered
+//! instruction, holding its address in the executable, which tools such as
+//! `addr2line` map back to the Rust source.
+//!
+//! Instructions expanded from a macro or a jump table are located at the
+//! directive, and moves merged by the move scheduler at the first of them.
+//! The debug info is a separate artifact, encodable as JSON or CBOR with the
+//! [`Export`] trait, and is left out of the binary format of programs.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::Export;
+
+/// Location of an instruction in the source of a program.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Source file, when assembling files.
+    pub file: Option<PathBuf>,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Location in the program the source was lowered from, if any.
+    pub origin: Option<String>,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}", file.display(), self.line)?,
+            None => write!(f, "line {}", self.line)?,
+        }
+        if let Some(origin) = &self.origin {
+            write!(f, " ({origin})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Source locations of the instructions of a program. See the [module-level
+/// documentation](self) for details.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugInfo {
+    /// Location of each instruction, by PROM index.
+    pub locations: Vec<Option<SourceLocation>>,
+}
+
+impl DebugInfo {
+    /// Returns the location of the instruction at `prom_index`, if known.
+    pub fn location(&self, prom_index: u32) -> Option<&SourceLocation> {
+        self.locations.get(prom_index as usize)?.as_ref()
+    }
+}
+
+impl Export for DebugInfo {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Assembler, AssemblerOptions};
+
+    #[test]
+    fn test_debug_info() {
+        let code = "
+            #[macro] double(dst, src) {
+                ADD $dst, $src, $src
+            }
+
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #1
+            .loc \"lib.rs:3\"
+                B32_MULI @3, @2, #65537
+                double(@4, @3)
+                RET
+            ";
+        let options = AssemblerOptions {
+            debug_info: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(code, &options).unwrap();
+        let debug_info = program.debug_info.unwrap();
+        assert_eq!(debug_info.locations.len(), program.prom.len());
+
+        let line = |prom_index| debug_info.location(prom_index).map(|loc| loc.line);
+        assert_eq!(line(0), Some(8));
+        // Instructions spanning several PROM entries, and macro expansions,
+        // share the line of their source.
+        assert_eq!(line(1), Some(10));
+        assert_eq!(line(2), Some(10));
+        assert_eq!(line(3), Some(11));
+        assert_eq!(line(4), Some(12));
+        assert_eq!(debug_info.location(0).unwrap().origin, None);
+        assert_eq!(
+            debug_info.location(4).unwrap().to_string(),
+            "line 12 (lib.rs:3)"
+        );
+
+        // Without the option, the program is left unchanged.
+        let without = Assembler::from_code(code).unwrap();
+        assert!(without.debug_info.is_none());
+        assert_eq!(without.prom, program.prom);
+    }
+}
@@ -0,0 +1,242 @@
+//! Source locations of assembler errors.
+//!
+//! Errors raised on a given part of the source carry a [`SourceSpan`], locating
+//! the offending token, which [`AssemblerError::render`] prints along with the
+//! source line:
+//!
+//! ```text
+//! error: Bad argument: Bad slot argument: @x
+//!   --> fib.asm:12:14
+//!    |
+//! 12 |     ADDI @2, @x, #1
+//!    |              ^^
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+
+use super::AssemblerError;
+
+/// Location of the offending token of an assembler error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// Source file, when assembling files.
+    pub file: Option<PathBuf>,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column of the token, in characters, starting at 1.
+    pub column: usize,
+    /// Offending token.
+    pub token: String,
+    /// Source line holding the token.
+    pub source_line: String,
+}
+
+impl SourceSpan {
+    /// Locates the text of a pest span.
+    pub(crate) fn from_pest(span: pest::Span<'_>) -> Self {
+        let (line, column) = span.start_pos().line_col();
+        let source_line = span.start_pos().line_of().trim_end_matches(['\r', '\n']);
+        let token = span.as_str().lines().next().unwrap_or_default().trim_end();
+        Self {
+            file: None,
+            line,
+            column,
+            token: token.to_string(),
+            source_line: source_line.to_string(),
+        }
+    }
+
+    /// Locates a position, given by its line and column, in `code`. The token
+    /// is the word starting at this position.
+    pub(crate) fn at(code: &str, line: usize, column: usize) -> Self {
+        let source_line = code.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+        let token = source_line
+            .chars()
+            .skip(column.saturating_sub(1))
+            .take_while(|c| !c.is_whitespace())
+            .collect();
+        Self {
+            file: None,
+            line,
+            column,
+            token,
+            source_line: source_line.to_string(),
+        }
+    }
+
+    /// Returns the occurrences of `token` as a whole word in `code`, outside
+    /// of comments, with whether each of them is a label definition.
+    pub(crate) fn find_all(code: &str, token: &str) -> Vec<(Self, bool)> {
+        let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let mut occurrences = Vec::new();
+        if token.is_empty() {
+            return occurrences;
+        }
+        for (index, source_line) in code.lines().enumerate() {
+            let code_part = source_line.split(";;").next().unwrap_or_default();
+            for (start, _) in code_part.match_indices(token) {
+                let end = start + token.len();
+                let before = code_part[..start].chars().next_back();
+                let after = code_part[end..].chars().next();
+                if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+                    continue;
+                }
+                let span = Self {
+                    file: None,
+                    line: index + 1,
+                    column: code_part[..start].chars().count() + 1,
+                    token: token.to_string(),
+                    source_line: source_line.to_string(),
+                };
+                occurrences.push((span, after == Some(':')));
+            }
+        }
+        occurrences
+    }
+
+    /// Renders `message` as a diagnostic pointing at the token in its source
+    /// line.
+    pub fn render(&self, message: &str) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        // Keep the tabs of the source line so that the marker lines up.
+        let padding = self
+            .source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let available = self
+            .source_line
+            .chars()
+            .count()
+            .saturating_sub(self.column.saturating_sub(1));
+        let width = self.token.chars().count().min(available).max(1);
+
+        format!(
+            "error: {message}\n{gutter}--> {self}\n{gutter} |\n{line_number} | {}\n{gutter} | {padding}{}",
+            self.source_line,
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl AssemblerError {
+    /// Locates, in `code`, the label an error raised after parsing is about.
+    pub(crate) fn locate(self, code: &str) -> Self {
+        let (label, definition, nth) = match &self {
+            Self::LabelNotFound(label) | Self::FunctionNotFound(label) => (label, false, 0),
+            Self::FunctionHasNoFrameSize(label) => (label, true, 0),
+            // Point at the second definition of the label.
+            Self::DuplicateLabel(label) => (label, true, 1),
+            _ => return self,
+        };
+        let span = SourceSpan::find_all(code, label)
+            .into_iter()
+            .filter(|(_, is_definition)| *is_definition == definition)
+            .nth(nth);
+        match span {
+            Some((span, _)) => Self::WithSpan {
+                error: Box::new(self),
+                span,
+            },
+            None => self,
+        }
+    }
+
+    /// Returns the underlying error, without its source location.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::WithSpan { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// Returns the source location attached to this error, if any.
+    pub const fn span(&self) -> Option<&SourceSpan> {
+        match self {
+            Self::WithSpan { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+
+    /// Renders the error, pointing at the offending token in the source when
+    /// it is known.
+    pub fn render(&self) -> String {
+        match self {
+            Self::WithSpan { error, span } => span.render(&error.to_string()),
+            error => format!("error: {error}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Error as ParserError;
+    use crate::Assembler;
+
+    #[test]
+    fn test_parse_error_span() {
+        let code =
+            "#[framesize(0x10)]\n_start:\n    LDI.W @2, #1\n    ADDI @2, @2, #UNDEF\n    RET\n";
+        let err = Assembler::from_code(code).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AssemblerError::ParseError(ParserError::BadArgument(_))
+        ));
+
+        let span = err.span().expect("The error should be located");
+        assert_eq!((span.line, span.column), (4, 19));
+        assert_eq!(span.token, "UNDEF");
+        assert_eq!(span.source_line, "    ADDI @2, @2, #UNDEF");
+        assert!(err.to_string().starts_with("4:19: "), "{err}");
+
+        let unknown = "_start:\n    RET\n    FROB @2\n";
+        let err = Assembler::from_code(unknown).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AssemblerError::ParseError(ParserError::PestParse(_))
+        ));
+        assert_eq!(err.span().map(|span| span.line), Some(3));
+    }
+
+    #[test]
+    fn test_label_error_span() {
+        let code = "_start:\n    J lop ;; lop\nloop:\n    RET\n";
+        let err = Assembler::from_code(code).unwrap_err();
+        assert!(matches!(err.root(), AssemblerError::LabelNotFound(_)));
+        let span = err.span().expect("The error should be located");
+        assert_eq!((span.line, span.column), (2, 7));
+
+        let duplicate = "_start:\n    J end\nend:\n    RET\nend:\n    RET\n";
+        let err = Assembler::from_code(duplicate).unwrap_err();
+        assert!(matches!(err.root(), AssemblerError::DuplicateLabel(_)));
+        assert_eq!(err.span().map(|span| span.line), Some(5));
+    }
+
+    #[test]
+    fn test_render() {
+        let span = SourceSpan {
+            file: Some(PathBuf::from("prog.asm")),
+            line: 12,
+            column: 14,
+            token: "@x".to_string(),
+            source_line: "    ADDI @2, @x, #1".to_string(),
+        };
+        assert_eq!(
+            span.render("Bad slot"),
+            "error: Bad slot\n  --> prog.asm:12:14\n   |\n12 |     ADDI @2, @x, #1\n   |              ^^"
+        );
+    }
+}
@@ -0,0 +1,814 @@
+//! Renumbering of frame slots.
+//!
+//! The VROM is write-once, so a slot holds a single value for the whole
+//! activation of a function: two temporaries may only share a slot when no
+//! path through the function accesses both of them, e.g. when they are local
+//! to the two sides of a branch. The slots of each function are renumbered
+//! accordingly, each temporary taking the lowest index free along its paths,
+//! which also closes the gaps between the slots in use. The frame size of the
+//! renumbered functions is then shrunk to their new slots, and so are the
+//! `ALLOCI!` allocating their frames before calling them.
+//!
+//! The following slots keep their index:
+//! - slots 0 and 1, holding the return PC and FP;
+//! - the slots any instruction of the program accesses in another frame, as
+//!   `@x[k]`, which may be arguments or return values;
+//! - the slots that may be read before being written, which are inputs;
+//! - the slots accessed along with the next ones by wide instructions, whose
+//!   operands must stay contiguous and aligned.
+//!
+//! Functions whose frame may be accessed otherwise than through their slots
+//! are left untouched: the entry function, whose frame is read by the host,
+//! the functions taking addresses in their frame with `FP`, passing slots to
+//! `ECALL`, `MERKLE_VERIFY` or custom instructions, and the functions jumping
+//! to or from other functions. Programs with a `JUMPV` are left untouched
+//! altogether, as indirect jumps may land in any function. The frames of
+//! functions are assumed not to be accessed through pointers, by `MEMCPY` and
+//! `MEMSET`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+
+use super::frame_size::{function_starts, MIN_FRAME_SIZE};
+use crate::parser::{split_label_ref, Immediate, InstructionsWithLabels, Slot};
+
+/// Renumbers the slots of the functions of `instructions`, shrinking their
+/// frames.
+pub(crate) fn compact_frames(
+    mut instructions: Vec<InstructionsWithLabels>,
+) -> Vec<InstructionsWithLabels> {
+    if instructions
+        .iter()
+        .any(|instruction| matches!(instruction, InstructionsWithLabels::Jumpv { .. }))
+    {
+        return instructions;
+    }
+
+    let starts = function_starts(&instructions);
+    let functions = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| start..starts.get(i + 1).copied().unwrap_or(instructions.len()))
+        .collect::<Vec<_>>();
+    let entry = instructions
+        .iter()
+        .position(|instruction| matches!(instruction, InstructionsWithLabels::Label(..)));
+    let closed = closed_functions(&instructions, &functions);
+    let frame_offsets = frame_offsets(&instructions);
+
+    // Previous and new frame size of the renumbered functions.
+    let mut resized = HashMap::new();
+    for (function, closed) in functions.iter().zip(closed) {
+        if !closed || Some(function.start) == entry {
+            continue;
+        }
+        if let Some((name, sizes)) =
+            compact_function(&mut instructions[function.clone()], &frame_offsets)
+        {
+            resized.insert(name, sizes);
+        }
+    }
+
+    for function in functions {
+        resize_allocations(&mut instructions[function], &resized);
+    }
+    instructions
+}
+
+/// Returns whether each of the `functions` is only entered at its start, by
+/// calls, and only left through calls and terminators.
+fn closed_functions(
+    instructions: &[InstructionsWithLabels],
+    functions: &[Range<usize>],
+) -> Vec<bool> {
+    let mut owners = HashMap::new();
+    for (i, function) in functions.iter().enumerate() {
+        for instruction in &instructions[function.clone()] {
+            if let InstructionsWithLabels::Label(name, _) = instruction {
+                owners.insert(name.as_str(), i);
+            }
+        }
+    }
+    let is_start = |name: &str, owner: usize| {
+        matches!(
+            &instructions[functions[owner].start],
+            InstructionsWithLabels::Label(start, _) if start == name
+        )
+    };
+
+    let mut closed = vec![true; functions.len()];
+    for (i, function) in functions.iter().enumerate() {
+        let code = &instructions[function.clone()];
+        // The execution must not fall through to the next function.
+        let last = code.iter().rev().find(|instruction| {
+            !matches!(
+                instruction,
+                InstructionsWithLabels::Label(..)
+                    | InstructionsWithLabels::Location(_)
+                    | InstructionsWithLabels::Align(_)
+            )
+        });
+        if last.is_some_and(|instruction| !instruction.is_terminator()) {
+            closed[i] = false;
+        }
+
+        for instruction in code {
+            let is_call = matches!(
+                instruction,
+                InstructionsWithLabels::Calli { .. }
+                    | InstructionsWithLabels::Taili { .. }
+                    | InstructionsWithLabels::LdiLabel { .. }
+            );
+            for label in instruction.label_refs() {
+                let (name, offset) = split_label_ref(label);
+                let Some(&owner) = owners.get(name) else {
+                    continue;
+                };
+                let is_local_branch = !is_call && owner == i;
+                let is_function_ref = is_call && is_start(name, owner);
+                if offset != 0 || !(is_local_branch || is_function_ref) {
+                    closed[owner] = false;
+                    closed[i] &= is_call;
+                }
+            }
+        }
+    }
+    closed
+}
+
+/// Returns the slots accessed in other frames by `instructions`, as `@x[k]`.
+fn frame_offsets(instructions: &[InstructionsWithLabels]) -> BTreeSet<u32> {
+    instructions
+        .iter()
+        .flat_map(|instruction| {
+            let (dst, width) = match instruction {
+                InstructionsWithLabels::Mvvw { dst, .. }
+                | InstructionsWithLabels::Mvvh { dst, .. }
+                | InstructionsWithLabels::Mvih { dst, .. }
+                | InstructionsWithLabels::Mvib { dst, .. } => (dst, 1),
+                InstructionsWithLabels::Mvvl { dst, .. } => (dst, 4),
+                _ => return 0..0,
+            };
+            let offset = dst.offset() as u32;
+            offset..offset + width
+        })
+        .collect()
+}
+
+/// Renumbers the slots of the function `code`, returning its name with its
+/// previous and new frame size if any slot was renumbered.
+fn compact_function(
+    code: &mut [InstructionsWithLabels],
+    frame_offsets: &BTreeSet<u32>,
+) -> Option<(String, (u32, u32))> {
+    if code.iter().any(|instruction| {
+        matches!(
+            instruction,
+            InstructionsWithLabels::Fp { .. }
+                | InstructionsWithLabels::Ecall { .. }
+                | InstructionsWithLabels::MerkleVerify { .. }
+                | InstructionsWithLabels::Custom { .. }
+                | InstructionsWithLabels::JumpTable { .. }
+        )
+    }) {
+        return None;
+    }
+    let InstructionsWithLabels::Label(name, declared) = &code[0] else {
+        unreachable!("Functions start with a label");
+    };
+    let extent = frame_extent(code);
+    let size = declared.map_or(extent, u32::from);
+    // Functions already accessing slots beyond their frame are left as is.
+    if size < extent {
+        return None;
+    }
+
+    let successors = successors(code);
+    let accessed = code
+        .iter()
+        .map(|instruction| slot_indices(instruction.accessed_slots()))
+        .collect::<Vec<_>>();
+
+    let mut pinned = (0..MIN_FRAME_SIZE)
+        .chain(frame_offsets.range(..size).copied())
+        .chain(inputs(code, &successors))
+        .collect::<BTreeSet<_>>();
+    for instruction in code.iter() {
+        for (slot, width) in instruction.accessed_slots() {
+            if width > 1 {
+                pinned.extend(slot.index()..slot.index() + width);
+            }
+        }
+    }
+
+    // Each temporary takes the lowest slot not pinned, nor taken by another
+    // temporary on a same path.
+    let conflicts = conflicts(&accessed, &successors);
+    let mut renumbering = BTreeMap::new();
+    let mut taken = BTreeMap::<u32, Vec<u32>>::new();
+    for (&slot, conflicting) in conflicts.iter() {
+        if pinned.contains(&slot) {
+            continue;
+        }
+        let index = (MIN_FRAME_SIZE..)
+            .find(|index| {
+                !pinned.contains(index)
+                    && taken.get(index).is_none_or(|temporaries| {
+                        temporaries.iter().all(|other| !conflicting.contains(other))
+                    })
+            })
+            .expect("A slot is free beyond the ones in use");
+        taken.entry(index).or_default().push(slot);
+        renumbering.insert(slot, index);
+    }
+    if renumbering.iter().all(|(slot, index)| slot == index) {
+        return None;
+    }
+
+    let name = name.clone();
+    for instruction in code.iter_mut() {
+        for (slot, _) in instruction.accessed_slots_mut() {
+            if let Some(&index) = renumbering.get(&slot.index()) {
+                *slot = Slot::new(index);
+            }
+        }
+    }
+    let new_size = pinned
+        .iter()
+        .map(|slot| slot + 1)
+        .fold(frame_extent(code), u32::max);
+    let InstructionsWithLabels::Label(_, frame_size) = &mut code[0] else {
+        unreachable!("Functions start with a label");
+    };
+    *frame_size = Some(new_size as u16);
+    Some((name, (size, new_size)))
+}
+
+/// Returns the frame size needed by the instructions of `code`.
+fn frame_extent(code: &[InstructionsWithLabels]) -> u32 {
+    code.iter()
+        .map(InstructionsWithLabels::frame_extent)
+        .fold(MIN_FRAME_SIZE, u32::max)
+}
+
+/// Returns the indices of the words spanned by `slots`.
+fn slot_indices(slots: Vec<(Slot, u32)>) -> BTreeSet<u32> {
+    slots
+        .into_iter()
+        .flat_map(|(slot, width)| slot.index()..slot.index() + width)
+        .collect()
+}
+
+/// Returns the indices in `code` of the instructions that may follow each one
+/// in a function, branches being local to it.
+fn successors(code: &[InstructionsWithLabels]) -> Vec<Vec<usize>> {
+    let labels = code
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instruction)| match instruction {
+            InstructionsWithLabels::Label(name, _) => Some((name.as_str(), i)),
+            _ => None,
+        })
+        .collect::<HashMap<_, _>>();
+
+    code.iter()
+        .enumerate()
+        .map(|(i, instruction)| {
+            let mut next = Vec::new();
+            if !instruction.is_terminator() && i + 1 < code.len() {
+                next.push(i + 1);
+            }
+            let is_call = matches!(
+                instruction,
+                InstructionsWithLabels::Calli { .. } | InstructionsWithLabels::Taili { .. }
+            );
+            if let Some(&target) = instruction
+                .target_label()
+                .filter(|_| !is_call)
+                .and_then(|label| labels.get(label))
+            {
+                next.push(target);
+            }
+            next
+        })
+        .collect()
+}
+
+/// Returns the slots that the function `code` may read before writing them.
+fn inputs(code: &[InstructionsWithLabels], successors: &[Vec<usize>]) -> BTreeSet<u32> {
+    let reads_writes = code
+        .iter()
+        .map(|instruction| {
+            let mut accessed = instruction.accessed_slots();
+            let written = instruction.written_slots();
+            if let Some(position) = written.and_then(|(written, _)| {
+                accessed
+                    .iter()
+                    .position(|(slot, _)| slot.index() == written.index())
+            }) {
+                accessed.remove(position);
+            }
+            (
+                slot_indices(accessed),
+                slot_indices(written.into_iter().collect()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Slots written on every path to each reachable instruction.
+    let mut written_before = vec![None::<BTreeSet<u32>>; code.len()];
+    written_before[0] = Some(BTreeSet::new());
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..code.len() {
+            let Some(before) = &written_before[i] else {
+                continue;
+            };
+            let after = before
+                .union(&reads_writes[i].1)
+                .copied()
+                .collect::<BTreeSet<_>>();
+            for &next in &successors[i] {
+                let merged = match &written_before[next] {
+                    Some(written) => written.intersection(&after).copied().collect(),
+                    None => after.clone(),
+                };
+                if written_before[next].as_ref() != Some(&merged) {
+                    written_before[next] = Some(merged);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    written_before
+        .iter()
+        .zip(&reads_writes)
+        .filter_map(|(before, (reads, _))| Some(reads.difference(before.as_ref()?)))
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Returns, for each slot accessed by a function, the slots accessed on a same
+/// path through it, given the slots `accessed` by each instruction.
+fn conflicts(
+    accessed: &[BTreeSet<u32>],
+    successors: &[Vec<usize>],
+) -> BTreeMap<u32, BTreeSet<u32>> {
+    let mut predecessors = vec![Vec::new(); accessed.len()];
+    for (i, next) in successors.iter().enumerate() {
+        for &next in next {
+            predecessors[next].push(i);
+        }
+    }
+    let before = reached_slots(accessed, &predecessors);
+    let after = reached_slots(accessed, successors);
+
+    let mut conflicts = BTreeMap::<u32, BTreeSet<u32>>::new();
+    for (i, slots) in accessed.iter().enumerate() {
+        for &slot in slots {
+            conflicts
+                .entry(slot)
+                .or_default()
+                .extend(before[i].iter().chain(slots).chain(&after[i]));
+        }
+    }
+    conflicts
+}
+
+/// Returns the slots accessed by the instructions reachable from each one
+/// along `edges`, itself excluded.
+fn reached_slots(accessed: &[BTreeSet<u32>], edges: &[Vec<usize>]) -> Vec<BTreeSet<u32>> {
+    let mut reached = vec![BTreeSet::new(); accessed.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..accessed.len() {
+            let mut slots = reached[i].clone();
+            for &next in &edges[i] {
+                slots.extend(&accessed[next]);
+                slots.extend(&reached[next]);
+            }
+            if slots.len() != reached[i].len() {
+                reached[i] = slots;
+                changed = true;
+            }
+        }
+    }
+    reached
+}
+
+/// Shrinks the `ALLOCI!` of the function `code` allocating the frames of the
+/// `resized` functions, when they allocate their previous frame size.
+fn resize_allocations(code: &mut [InstructionsWithLabels], resized: &HashMap<String, (u32, u32)>) {
+    // Function called with the frame of each slot, if always the same.
+    let mut callees = HashMap::<u32, Option<String>>::new();
+    for instruction in code.iter() {
+        let (next_fp, callee) = match instruction {
+            InstructionsWithLabels::Calli { label, next_fp }
+            | InstructionsWithLabels::Taili { label, next_fp } => (next_fp, Some(label)),
+            InstructionsWithLabels::Callv { next_fp, .. }
+            | InstructionsWithLabels::Tailv { next_fp, .. } => (next_fp, None),
+            _ => continue,
+        };
+        callees
+            .entry(next_fp.index())
+            .and_modify(|known| {
+                if known.as_ref() != callee {
+                    *known = None;
+                }
+            })
+            .or_insert_with(|| callee.cloned());
+    }
+
+    for instruction in code.iter_mut() {
+        let InstructionsWithLabels::Alloci { dst, imm } = instruction else {
+            continue;
+        };
+        let sizes = callees
+            .get(&dst.index())
+            .and_then(Option::as_ref)
+            .and_then(|callee| resized.get(callee));
+        if let Some(&(size, new_size)) = sizes {
+            if imm.value() == size {
+                *imm = Immediate::new(new_size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binius_field::PackedField;
+
+    use super::*;
+    use crate::{
+        execution::{InterpreterError, G},
+        groestl256_initial_chaining_value,
+        isa::GenericISA,
+        parser::parse_program,
+        runtime::heap::link_heap_runtime,
+        util::{bytes_to_u32, groestl256_pad},
+        AssembledProgram, Assembler, AssemblerOptions, BoundaryValues, GenerationOptions, Memory,
+        PetraTrace, ValueRom,
+    };
+
+    fn compacted(code: &str) -> AssembledProgram {
+        let options = AssemblerOptions {
+            compact_frames: true,
+            ..Default::default()
+        };
+        Assembler::from_code_with_options(code, &options).unwrap().0
+    }
+
+    fn frame_size(program: &AssembledProgram, label: &str) -> u16 {
+        program.frame_sizes[&program.labels[label].0]
+    }
+
+    fn to_strings(instructions: &[InstructionsWithLabels]) -> Vec<String> {
+        instructions.iter().map(ToString::to_string).collect()
+    }
+
+    fn run(
+        program: &AssembledProgram,
+        init_values: &[u32],
+        options: GenerationOptions<'_>,
+    ) -> Result<(PetraTrace, BoundaryValues), InterpreterError> {
+        let memory = Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(init_values),
+        )
+        .with_ram_data(&program.data);
+        PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            program.frame_sizes.clone(),
+            options,
+        )
+    }
+
+    const CODE: &str = "
+        #[framesize(0x10)]
+        _start:
+            ALLOCI! @3, #16
+            MVV.W @3[2], @2
+            CALLI select, @3
+            MVV.W @3[3], @4
+            RET
+
+        #[framesize(0x10)]
+        select:
+            BNZ nonzero, @2
+            LDI.W @8, #7
+            ADDI @9, @8, #1
+            ADDI @3, @9, #0
+            RET
+        nonzero:
+            LDI.W @12, #4
+            ADD @13, @12, @2
+            ADDI @3, @13, #0
+            RET
+        ";
+
+    #[test]
+    fn test_compact_frames() {
+        let options = AssemblerOptions {
+            compact_frames: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(CODE, &options).unwrap();
+        let full = Assembler::from_code(CODE).unwrap();
+
+        // The temporaries of both branches share slots 4 and 5, right after the
+        // argument and the return value.
+        let frame_size =
+            |program: &AssembledProgram, label: &str| program.frame_sizes[&program.labels[label].0];
+        assert_eq!(frame_size(&full, "select"), 0x10);
+        assert_eq!(frame_size(&program, "select"), 6);
+        assert_eq!(frame_size(&program, "_start"), 0x10);
+
+        let compacted = compact_frames(parse_program(CODE).unwrap())
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert!(compacted[1].starts_with("ALLOCI! @3 #6"), "{compacted:?}");
+        assert!(compacted[9].starts_with("ADDI @5 @4"), "{compacted:?}");
+        assert!(compacted[14].starts_with("ADD @5 @4 @2"), "{compacted:?}");
+
+        for (input, output) in [(0, 8), (5, 9)] {
+            let run = |program: AssembledProgram| {
+                let memory =
+                    Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0, input]));
+                let (trace, _) = PetraTrace::generate_with_options(
+                    Box::new(GenericISA),
+                    memory,
+                    program.frame_sizes,
+                    GenerationOptions::default().frame_checks(),
+                )
+                .unwrap();
+                trace.vrom().read::<u32>(4).unwrap()
+            };
+            assert_eq!(run(program.clone()), output);
+            assert_eq!(run(full.clone()), output);
+        }
+    }
+
+    #[test]
+    fn test_address_taken_frames_untouched() {
+        let code =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/fib.asm"))
+                .unwrap();
+        let instructions = parse_program(&code).unwrap();
+        let compacted = compact_frames(instructions.clone());
+        assert_eq!(
+            compacted
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            instructions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_fp_frames_untouched() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                ALLOCI! @3, #16
+                CALLI callee, @3
+                RET
+
+            #[framesize(0x10)]
+            callee:
+                LDI.W @12, #4
+                FP @13, #12
+                RET
+            ";
+        let instructions = parse_program(code).unwrap();
+        assert_eq!(
+            to_strings(&compact_frames(instructions.clone())),
+            to_strings(&instructions)
+        );
+        assert_eq!(frame_size(&compacted(code), "callee"), 0x10);
+    }
+
+    #[test]
+    fn test_jumpv_programs_untouched() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                ALLOCI! @3, #16
+                CALLI callee, @3
+                RET
+
+            #[framesize(0x10)]
+            callee:
+                LDI.W @12, #4
+                ADDI @13, @12, #1
+                RET
+
+            #[framesize(0x10)]
+            dispatch:
+                J @2
+            ";
+        let instructions = parse_program(code).unwrap();
+        assert_eq!(
+            to_strings(&compact_frames(instructions.clone())),
+            to_strings(&instructions)
+        );
+
+        // Without the indirect jump, the callee is compacted.
+        let direct = code.replace("J @2", "RET");
+        assert_eq!(frame_size(&compacted(&direct), "callee"), 4);
+    }
+
+    #[test]
+    fn test_cross_frame_slots_pinned() {
+        // The callee takes its argument in slot 7 and returns its result in
+        // slot 2, both accessed by the caller as `@3[k]`.
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                ALLOCI! @3, #16
+                MVV.W @3[7], @2
+                CALLI callee, @3
+                MVV.W @3[2], @4
+                RET
+
+            #[framesize(0x10)]
+            callee:
+                LDI.W @12, #5
+                ADD @2, @12, @7
+                RET
+            ";
+        let program = compacted(code);
+        assert_eq!(frame_size(&program, "callee"), 8);
+
+        let (trace, _) = run(
+            &program,
+            &[0, 0, 10],
+            GenerationOptions::default().frame_checks(),
+        )
+        .unwrap();
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), 15);
+    }
+
+    #[test]
+    fn test_inputs_pinned() {
+        // Slot 9 is read before being written on the path skipping the
+        // branch, so it is an input of the callee and keeps its index, while
+        // the temporary in slot 12 moves down.
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                ALLOCI! @3, #16
+                CALLI callee, @3
+                RET
+
+            #[framesize(0x10)]
+            callee:
+                BNZ skip, @2
+                LDI.W @9, #1
+            skip:
+                ADDI @12, @9, #1
+                ADDI @3, @12, #0
+                RET
+            ";
+        let instructions = to_strings(&compact_frames(parse_program(code).unwrap()));
+        assert!(
+            instructions
+                .iter()
+                .any(|line| line.starts_with("ADDI @4 @9")),
+            "{instructions:?}"
+        );
+        assert_eq!(frame_size(&compacted(code), "callee"), 10);
+    }
+
+    #[test]
+    fn test_examples_compact_frames() {
+        let example = |file: &str| {
+            std::fs::read_to_string(format!("{}/../examples/{file}", env!("CARGO_MANIFEST_DIR")))
+                .unwrap()
+        };
+        let mut groestl_init = vec![0; 16];
+        groestl_init.extend(bytes_to_u32(&groestl256_initial_chaining_value()));
+        groestl_init.extend(
+            groestl256_pad(&(0..100).collect::<Vec<u8>>())
+                .iter()
+                .flat_map(|block| bytes_to_u32(block)),
+        );
+
+        // Every example, linked with the code it depends on, along with its
+        // initial VROM values.
+        let examples = [
+            ("add.asm", example("add.asm"), vec![0, 0]),
+            (
+                "bezout.asm",
+                example("bezout.asm") + &example("div.asm"),
+                vec![0, 0, 56, 15],
+            ),
+            ("bit_ops.asm", example("bit_ops.asm"), vec![0, 0]),
+            ("bit_shifts.asm", example("bit_shifts.asm"), vec![0, 0]),
+            ("branch.asm", example("branch.asm"), vec![0, 0, 3]),
+            (
+                "branch_cmp.asm",
+                example("branch_cmp.asm"),
+                vec![0, 0, -1i32 as u32, 1],
+            ),
+            ("collatz.asm", example("collatz.asm"), vec![0, 0, 7]),
+            ("div.asm", example("div.asm"), vec![0, 0, 20, 6]),
+            (
+                "fib.asm",
+                example("fib.asm"),
+                vec![0, 0, G.pow(10).val(), 4, 55],
+            ),
+            ("func_call.asm", example("func_call.asm"), vec![0, 0]),
+            ("groestl.asm", example("groestl.asm"), groestl_init),
+            (
+                "heap.asm",
+                link_heap_runtime(&example("heap.asm")),
+                vec![0, 0, 42, 7],
+            ),
+            (
+                "linked_list.asm",
+                example("linked_list.asm"),
+                vec![0, 0, 3, 20],
+            ),
+            ("mul.asm", example("mul.asm"), vec![0, 0]),
+            (
+                "non_tail_long_div.asm",
+                example("non_tail_long_div.asm"),
+                vec![0, 0, 15, 6],
+            ),
+            ("opcodes.asm", example("opcodes.asm"), vec![0, 0, 0]),
+            (
+                "static_int_list_sum_ram.asm",
+                example("static_int_list_sum_ram.asm"),
+                vec![0, 0, 0, 0, 7],
+            ),
+            (
+                "tail_long_div.asm",
+                example("tail_long_div.asm"),
+                vec![0, 0, 15, 6],
+            ),
+        ];
+        let mut files = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|file| file.ends_with(".asm"))
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            examples.iter().map(|(file, ..)| *file).collect::<Vec<_>>(),
+            files
+        );
+
+        let mut any_compacted = false;
+        for (file, code, init_values) in examples {
+            let full = Assembler::from_code(&code).unwrap();
+            let program = compacted(&code);
+            any_compacted |= program.frame_sizes != full.frame_sizes;
+
+            let (full_trace, full_values) =
+                run(&full, &init_values, GenerationOptions::default()).unwrap();
+            let (trace, values) = run(&program, &init_values, GenerationOptions::default())
+                .unwrap_or_else(|err| panic!("{file}: {err}"));
+            assert_eq!(values.exit_code, full_values.exit_code, "{file}");
+            assert_eq!(values.outputs, full_values.outputs, "{file}");
+            assert_eq!(values.final_pc, full_values.final_pc, "{file}");
+            assert_eq!(values.timestamp, full_values.timestamp, "{file}");
+
+            // The entry frame, read by the host, holds the same values.
+            let entry_size = full.frame_sizes[&full.prom[0].field_pc] as u32;
+            for addr in 0..entry_size {
+                assert_eq!(
+                    trace.vrom().read::<u32>(addr).ok(),
+                    full_trace.vrom().read::<u32>(addr).ok(),
+                    "{file}: slot {addr} of the entry frame"
+                );
+            }
+
+            // The compacted frames hold all the slots their functions access.
+            if run(
+                &full,
+                &init_values,
+                GenerationOptions::default().frame_checks(),
+            )
+            .is_ok()
+            {
+                run(
+                    &program,
+                    &init_values,
+                    GenerationOptions::default().frame_checks(),
+                )
+                .unwrap_or_else(|err| panic!("{file}: {err}"));
+            }
+        }
+        assert!(any_compacted);
+    }
+}
@@ -0,0 +1,69 @@
+//! Frame size inference.
+//!
+//! Every function needs a frame size, so that a frame can be allocated when it
+//! is called. Functions are the entry label, the targets of `CALLI` and `TAILI`
+//! and any label with a `#[framesize(...)]` annotation, and span all
+//! instructions up to the next function. Unless annotated, the frame size of a
+//! function is the smallest one covering all the slots its instructions access.
+//! An explicit annotation overrides the inferred size, with a warning if it is
+//! smaller.
+
+use std::collections::HashSet;
+
+use tracing::warn;
+
+use crate::{abi::Abi, parser::InstructionsWithLabels};
+
+/// Smallest frame size, holding the return PC and FP.
+pub(super) const MIN_FRAME_SIZE: u32 = Abi::STANDARD.min_frame_size as u32;
+
+/// Returns the index of the label starting each function of `instructions`,
+/// in increasing order. Each function spans the instructions up to the next
+/// one.
+pub(super) fn function_starts(instructions: &[InstructionsWithLabels]) -> Vec<usize> {
+    let mut called = HashSet::new();
+    for instruction in instructions.iter() {
+        if let InstructionsWithLabels::Calli { label, .. }
+        | InstructionsWithLabels::Taili { label, .. } = instruction
+        {
+            called.insert(label.clone());
+        }
+    }
+
+    instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instruction)| match instruction {
+            InstructionsWithLabels::Label(name, frame_size)
+                if i == 0 || frame_size.is_some() || called.contains(name) =>
+            {
+                Some(i)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Fills in the frame size of every function that has no explicit one.
+pub(crate) fn infer_frame_sizes(instructions: &mut [InstructionsWithLabels]) {
+    let starts = function_starts(instructions);
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(instructions.len());
+        let required = instructions[start..end]
+            .iter()
+            .map(InstructionsWithLabels::frame_extent)
+            .fold(MIN_FRAME_SIZE, u32::max);
+
+        let InstructionsWithLabels::Label(name, frame_size) = &mut instructions[start] else {
+            unreachable!("Functions start with a label");
+        };
+        match frame_size {
+            Some(declared) if (*declared as u32) < required => warn!(
+                "Frame size 0x{declared:x} of function {name} is too small, its instructions \
+                 access up to 0x{required:x} slots"
+            ),
+            Some(_) => (),
+            None => *frame_size = Some(required.min(u16::MAX as u32) as u16),
+        }
+    }
+}
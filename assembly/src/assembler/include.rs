@@ -0,0 +1,232 @@
+//! Multi-file assembly.
+//!
+//! A source file can pull in other files with an `.include "path"` directive,
+//! where `path` is relative to the including file. Included files are appended
+//! after the including one, regardless of where the directive appears, so that
+//! execution always starts at the first label of the main file. Each file is
+//! included at most once, however many times it is referenced.
+//!
+//! All files share a single label namespace: a label defined in two different
+//! files is rejected with [`AssemblerError::DuplicateLabel`], located in the
+//! file holding the second definition.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::{AssemblerError, DebugInfo};
+
+const INCLUDE_DIRECTIVE: &str = ".include";
+
+/// Merges source files into a single program.
+#[derive(Debug, Default)]
+pub(crate) struct SourceFiles {
+    /// Canonical paths of the files merged so far.
+    visited: HashSet<PathBuf>,
+    /// Merged files, with the number of lines of the merged code before them.
+    files: Vec<(PathBuf, usize)>,
+    /// Number of lines of the merged code.
+    lines: usize,
+    /// Merged source code.
+    code: String,
+}
+
+impl SourceFiles {
+    /// Adds `file` and, recursively, the files it includes.
+    pub(crate) fn add(&mut self, path: &Path) -> Result<(), AssemblerError> {
+        let file = path.canonicalize().map_err(AssemblerError::FileReadError)?;
+        if !self.visited.insert(file.clone()) {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(&file).map_err(AssemblerError::FileReadError)?;
+        self.files.push((path.to_path_buf(), self.lines));
+        let mut includes = Vec::new();
+        for line in content.lines() {
+            match parse_include(line)? {
+                Some(included) => includes.push(included),
+                None => self.code.push_str(line),
+            }
+            self.code.push('\n');
+            self.lines += 1;
+        }
+
+        let dir = file.parent().unwrap_or_else(|| Path::new("."));
+        for included in includes {
+            self.add(&dir.join(included))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the merged source code.
+    pub(crate) fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Rewrites the source location of `err`, given in the merged code, as a
+    /// location in the file holding it.
+    pub(crate) fn relocate(&self, err: AssemblerError) -> AssemblerError {
+        let AssemblerError::WithSpan { error, mut span } = err else {
+            return err;
+        };
+        if let Some((file, line)) = self.locate(span.line) {
+            span.file = Some(file.to_path_buf());
+            span.line = line;
+        }
+        AssemblerError::WithSpan { error, span }
+    }
+
+    /// Rewrites the source locations of `debug_info`, given in the merged code,
+    /// as locations in the files holding them.
+    pub(crate) fn relocate_debug_info(&self, debug_info: &mut DebugInfo) {
+        for location in debug_info.locations.iter_mut().flatten() {
+            if let Some((file, line)) = self.locate(location.line) {
+                location.file = Some(file.to_path_buf());
+                location.line = line;
+            }
+        }
+    }
+
+    /// Returns the file holding `line` of the merged code, with its line
+    /// number in that file.
+    fn locate(&self, line: usize) -> Option<(&Path, usize)> {
+        self.files
+            .iter()
+            .rev()
+            .find(|(_, lines_before)| *lines_before < line)
+            .map(|(file, lines_before)| (file.as_path(), line - lines_before))
+    }
+}
+
+/// Returns the path of the file included by `line`, if it is an include
+/// directive.
+fn parse_include(line: &str) -> Result<Option<&str>, AssemblerError> {
+    let Some(rest) = line.trim_start().strip_prefix(INCLUDE_DIRECTIVE) else {
+        return Ok(None);
+    };
+    // Drop any trailing comment.
+    let rest = rest.split(";;").next().unwrap_or_default().trim();
+
+    rest.strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .filter(|path| !path.is_empty())
+        .map(Some)
+        .ok_or_else(|| AssemblerError::InvalidInclude(line.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assembler;
+
+    /// Temporary directory, removed with its content when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// Writes `files` to a fresh temporary directory.
+        fn with_files(test_name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = TempDir(std::env::temp_dir().join(format!(
+                "petravm_include_{test_name}_{}",
+                std::process::id()
+            )));
+            for (name, content) in files {
+                let path = dir.join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, content).unwrap();
+            }
+            dir
+        }
+
+        fn join(&self, path: &str) -> PathBuf {
+            self.0.join(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_include() {
+        let dir = TempDir::with_files(
+            "test_include",
+            &[
+                (
+                    "main.asm",
+                    ".include \"lib/double.asm\"\n\
+                     .include \"lib/triple.asm\" ;; Some comment\n\
+                     #[framesize(0x10)]\n\
+                     _start:\n\
+                         MVV.W @4[2], @2\n\
+                         CALLI double, @4\n\
+                         MVV.W @5[2], @2\n\
+                         CALLI triple, @5\n\
+                         RET\n",
+                ),
+                (
+                    "lib/double.asm",
+                    "#[framesize(0x4)]\n\
+                     double:\n\
+                         ADD @3, @2, @2\n\
+                         RET\n",
+                ),
+                (
+                    "lib/triple.asm",
+                    ".include \"double.asm\"\n\
+                     #[framesize(0x8)]\n\
+                     triple:\n\
+                         MVV.W @4[2], @2\n\
+                         MVV.W @4[3], @5\n\
+                         CALLI double, @4\n\
+                         ADD @3, @2, @5\n\
+                         RET\n",
+                ),
+            ],
+        );
+
+        let program = Assembler::assemble_files(&[dir.join("main.asm")]).unwrap();
+        // `double.asm` is only included once.
+        assert_eq!(program.labels.len(), 3);
+        // Execution starts in the main file.
+        assert_eq!(program.labels["_start"].1, 0);
+
+        // Passing the files explicitly gives the same program.
+        let explicit = Assembler::assemble_files(&[
+            dir.join("main.asm"),
+            dir.join("lib/triple.asm"),
+            dir.join("lib/double.asm"),
+        ])
+        .unwrap();
+        assert_eq!(explicit.prom.len(), program.prom.len());
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let dir = TempDir::with_files(
+            "test_include_errors",
+            &[
+                ("bad.asm", ".include double.asm\n_start: RET\n"),
+                ("missing.asm", ".include \"nowhere.asm\"\n_start: RET\n"),
+                ("dup.asm", ".include \"dup_lib.asm\"\n_start: RET\n"),
+                ("dup_lib.asm", "_start: RET\n"),
+            ],
+        );
+
+        assert!(matches!(
+            Assembler::assemble_files(&[dir.join("bad.asm")]),
+            Err(AssemblerError::InvalidInclude(_))
+        ));
+        assert!(matches!(
+            Assembler::assemble_files(&[dir.join("missing.asm")]),
+            Err(AssemblerError::FileReadError(_))
+        ));
+        let err = Assembler::assemble_files(&[dir.join("dup.asm")]).unwrap_err();
+        assert!(matches!(err.root(), AssemblerError::DuplicateLabel(_)));
+        // The duplicate is located in the included file.
+        let span = err.span().expect("The error should be located");
+        let file_name = span.file.as_deref().and_then(Path::file_name);
+        assert_eq!(file_name, Some("dup_lib.asm".as_ref()));
+        assert_eq!((span.line, span.column), (1, 1));
+    }
+}
@@ -0,0 +1,169 @@
+//! Lowering of jump tables.
+//!
+//! `.jumptable table, @index, @scratch, [case0, case1, ...]` jumps to the case
+//! of index `FP[index]`, and falls through to the next instruction when the
+//! index is out of bounds. The field PCs of the cases are laid out in the
+//! `.data` section under the data label `table`, and the directive is lowered
+//! to a bounds check followed by a `JUMPV` through the table:
+//!
+//! ```text
+//!     SLTIU @scratch, @index, #N
+//!     BNZ table.dispatch, @scratch
+//!     J table.dispatch+3
+//! table.dispatch:
+//!     SLLI @scratch+1, @index, #2
+//!     LW @scratch+2, @scratch+1, #table
+//!     J @scratch+2
+//! ```
+//!
+//! The directive thus writes the three slots from `scratch`, and must be
+//! followed by an instruction.
+
+use super::{AssemblerError, DataLabels};
+use crate::parser::{DataDirective, Immediate, InstructionsWithLabels, Slot};
+
+/// Returns the data items holding the tables of the jump tables of
+/// `instructions`.
+pub(crate) fn table_data(instructions: &[InstructionsWithLabels]) -> Vec<InstructionsWithLabels> {
+    instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            InstructionsWithLabels::JumpTable { name, cases, .. } => Some([
+                InstructionsWithLabels::DataLabel(name.clone()),
+                InstructionsWithLabels::Data(DataDirective::Labels(cases.clone())),
+            ]),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Lowers the jump tables of `instructions`, whose tables were laid out at
+/// `data_labels`.
+pub(crate) fn lower_jump_tables(
+    instructions: Vec<InstructionsWithLabels>,
+    data_labels: &DataLabels,
+) -> Result<Vec<InstructionsWithLabels>, AssemblerError> {
+    let mut lowered = Vec::with_capacity(instructions.len());
+    for instruction in instructions {
+        let (name, index, scratch, cases) = match instruction {
+            InstructionsWithLabels::JumpTable {
+                name,
+                index,
+                scratch,
+                cases,
+            } => (name, index, scratch, cases),
+            instruction => {
+                lowered.push(instruction);
+                continue;
+            }
+        };
+
+        // The bound and the address of the table are 16-bit immediates.
+        let address = data_labels[&name];
+        if cases.len() > u16::MAX as usize || address > u16::MAX as u32 {
+            return Err(AssemblerError::JumpTableOutOfRange(name));
+        }
+
+        let dispatch = format!("{name}.dispatch");
+        let [in_bounds, offset, target] = [0, 1, 2].map(|i| Slot::new(scratch.index() + i));
+        lowered.extend([
+            InstructionsWithLabels::Sltiu {
+                dst: in_bounds,
+                src: index,
+                imm: Immediate::new(cases.len() as u32),
+                prover_only: false,
+            },
+            InstructionsWithLabels::Bnz {
+                label: dispatch.clone(),
+                src: in_bounds,
+            },
+            InstructionsWithLabels::Jumpi {
+                label: format!("{dispatch}+3"),
+            },
+            InstructionsWithLabels::Label(dispatch, None),
+            InstructionsWithLabels::Slli {
+                dst: offset,
+                src1: index,
+                imm: Immediate::new(2),
+                prover_only: false,
+            },
+            InstructionsWithLabels::Lw {
+                dst: target,
+                base: offset,
+                offset: Immediate::new(address),
+                prover_only: false,
+            },
+            InstructionsWithLabels::Jumpv { offset: target },
+        ]);
+    }
+    Ok(lowered)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{isa::GenericISA, Assembler, AssemblerError, Memory, PetraTrace, ValueRom};
+
+    const CODE: &str = "
+        #[framesize(0x10)]
+        _start:
+            .jumptable table, @2, @4, [zero, one, two]
+            LDI.W @3, #100
+            RET
+        zero:
+            LDI.W @3, #10
+            RET
+        one:
+            LDI.W @3, #11
+            RET
+        two:
+            LDI.W @3, #12
+            RET
+        ";
+
+    /// Returns the value of `@3` after dispatching on `index`.
+    fn dispatch(index: u32) -> u32 {
+        let program = Assembler::from_code(CODE).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0, index]))
+            .with_ram_data(&program.data);
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+        let value = trace.vrom().read::<u32>(3).unwrap();
+        trace.validate(boundary_values);
+        value
+    }
+
+    #[test]
+    fn test_jump_table() {
+        let program = Assembler::from_code(CODE).unwrap();
+        assert_eq!(program.data_labels["table"], 0);
+        assert_eq!(
+            program.data,
+            ["zero", "one", "two"]
+                .iter()
+                .flat_map(|case| program.labels[*case].0.val().to_le_bytes())
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(dispatch(0), 10);
+        assert_eq!(dispatch(1), 11);
+        assert_eq!(dispatch(2), 12);
+        // Out-of-bounds indices fall through.
+        assert_eq!(dispatch(3), 100);
+        assert_eq!(dispatch(u32::MAX), 100);
+    }
+
+    #[test]
+    fn test_jump_table_errors() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                .jumptable table, @2, @4, [missing]
+                RET
+            ";
+        assert!(matches!(
+            Assembler::from_code(code).unwrap_err().root(),
+            AssemblerError::LabelNotFound(label) if label == "missing"
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! Lints of assembly programs.
+//!
+//! Programs with these issues still assemble, but they are likely mistakes:
+//! - labels that no instruction refers to,
+//! - instructions following an unconditional jump, tail call or return with no
+//!   label in between, which can never be executed,
+//! - writes to the same slot of the current frame twice between two labels,
+//!   which fail at runtime as the VROM is write-once,
+//! - functions without a `#[framesize(...)]` annotation, whose frame size is
+//!   inferred.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::parser::{split_label_ref, InstructionsWithLabels};
+
+/// A likely mistake found in a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssemblerWarning {
+    /// The label is neither a function nor the target of any instruction.
+    UnusedLabel(String),
+    /// The instruction follows `after`, which never falls through, and has no
+    /// label.
+    UnreachableCode {
+        function: String,
+        after: String,
+        instruction: String,
+    },
+    /// The instruction writes to a slot that was already written since the
+    /// last label.
+    SlotWrittenTwice {
+        function: String,
+        slot: u32,
+        instruction: String,
+    },
+    /// The function has no frame size annotation.
+    MissingFrameSize(String),
+}
+
+impl fmt::Display for AssemblerWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnusedLabel(label) => write!(f, "Label {label} is never used"),
+            Self::UnreachableCode {
+                function,
+                after,
+                instruction,
+            } => write!(
+                f,
+                "Unreachable instruction `{instruction}` after `{after}` in function {function}"
+            ),
+            Self::SlotWrittenTwice {
+                function,
+                slot,
+                instruction,
+            } => write!(
+                f,
+                "Slot @{slot} is written twice in function {function}, by `{instruction}`"
+            ),
+            Self::MissingFrameSize(function) => write!(
+                f,
+                "Function {function} has no #[framesize(...)] annotation, its frame size is inferred"
+            ),
+        }
+    }
+}
+
+/// Returns the warnings about `instructions`, as parsed.
+pub(crate) fn lint(instructions: &[InstructionsWithLabels]) -> Vec<AssemblerWarning> {
+    let mut called = HashSet::new();
+    let mut targets = HashSet::new();
+    for instruction in instructions {
+        if let InstructionsWithLabels::Calli { label, .. }
+        | InstructionsWithLabels::Taili { label, .. } = instruction
+        {
+            called.insert(label.as_str());
+        }
+        targets.extend(
+            instruction
+                .label_refs()
+                .into_iter()
+                .map(|label| split_label_ref(label).0),
+        );
+    }
+
+    // Data sections and directives may precede the entry label.
+    let entry = instructions
+        .iter()
+        .find_map(|instruction| match instruction {
+            InstructionsWithLabels::Label(name, _) => Some(name.as_str()),
+            _ => None,
+        });
+
+    let mut warnings = Vec::new();
+    let mut function = "";
+    // Last instruction, if it never falls through.
+    let mut terminator: Option<&InstructionsWithLabels> = None;
+    // Whether unreachable code was already reported since the last label.
+    let mut reported = false;
+    // Slots written since the last label, with the number of words written.
+    let mut written = Vec::<(u32, u32)>::new();
+
+    for instruction in instructions {
+        match instruction {
+            InstructionsWithLabels::Label(name, frame_size) => {
+                if entry == Some(name.as_str())
+                    || frame_size.is_some()
+                    || called.contains(name.as_str())
+                {
+                    function = name;
+                    if frame_size.is_none() {
+                        warnings.push(AssemblerWarning::MissingFrameSize(name.clone()));
+                    }
+                } else if !targets.contains(name.as_str()) {
+                    warnings.push(AssemblerWarning::UnusedLabel(name.clone()));
+                }
+                terminator = None;
+                reported = false;
+                written.clear();
+            }
+            InstructionsWithLabels::DataLabel(_)
+            | InstructionsWithLabels::Data(_)
+            | InstructionsWithLabels::Public(_)
+            | InstructionsWithLabels::Align(_)
+            | InstructionsWithLabels::Location(_) => {}
+            instruction => {
+                if let Some(after) = terminator {
+                    if !reported {
+                        warnings.push(AssemblerWarning::UnreachableCode {
+                            function: function.to_string(),
+                            after: after.to_string(),
+                            instruction: instruction.to_string(),
+                        });
+                        reported = true;
+                    }
+                }
+                if instruction.is_terminator() {
+                    terminator = Some(instruction);
+                }
+
+                if let Some((slot, width)) = instruction.written_slots() {
+                    let (start, end) = (slot.index(), slot.index() + width);
+                    if written.iter().any(|&(s, e)| start < e && s < end) {
+                        warnings.push(AssemblerWarning::SlotWrittenTwice {
+                            function: function.to_string(),
+                            slot: start,
+                            instruction: instruction.to_string(),
+                        });
+                    }
+                    written.push((start, end));
+                }
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assembler;
+
+    #[test]
+    fn test_lints() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #1
+                ADDI @2, @2, #1
+                CALLI helper, @3
+                BNZ used, @2
+            used:
+                J done
+                LDI.W @4, #1
+                LDI.W @5, #1
+            unused:
+                LDI.W @4, #2
+            done:
+                RET
+            helper:
+                RET
+            ";
+        let (_, warnings) = Assembler::from_code_with_warnings(code).unwrap();
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert!(matches!(
+            &warnings[0],
+            AssemblerWarning::SlotWrittenTwice { function, slot: 2, instruction }
+                if function == "_start" && instruction.starts_with("ADDI")
+        ));
+        assert!(matches!(
+            &warnings[1],
+            AssemblerWarning::UnreachableCode { after, instruction, .. }
+                if after == "J done" && instruction.starts_with("LDI @4")
+        ));
+        assert_eq!(
+            warnings[2],
+            AssemblerWarning::UnusedLabel("unused".to_string())
+        );
+        assert_eq!(
+            warnings[3],
+            AssemblerWarning::MissingFrameSize("helper".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_lints() {
+        let (_, warnings) =
+            Assembler::from_code_with_warnings(include_str!("../../../examples/fib.asm")).unwrap();
+        assert_eq!(warnings, []);
+    }
+}
@@ -1,12 +1,32 @@
+mod binary;
+mod dce;
+pub mod debug_info;
+pub mod diagnostic;
+mod frame_layout;
+mod frame_size;
+mod include;
+mod jump_table;
+mod lint;
+mod schedule;
+
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use binius_field::{ExtensionField, Field, PackedField};
 use binius_m3::builder::{B16, B32};
+pub use debug_info::{DebugInfo, SourceLocation};
+pub use diagnostic::SourceSpan;
+pub use lint::AssemblerWarning;
 use tracing::instrument;
 
-use crate::parser::{parse_program, Error as ParserError, InstructionsWithLabels};
+use crate::parser::{
+    parse_program_with_locations, split_label_ref, DataDirective, Error as ParserError,
+    InstructionsWithLabels,
+};
 use crate::{
-    execution::{InterpreterInstruction, G},
+    analysis::ControlFlowError,
+    event::{pack_slot_pair, RANGE_CHECK_WIDTHS},
+    execution::{FieldPc, InterpreterInstruction, CUSTOM_OPCODE_BASE},
     memory::ProgramRom,
     opcodes::Opcode,
 };
@@ -25,12 +45,24 @@ pub enum AssemblerError {
     #[error("File read error: {0}")]
     FileReadError(std::io::Error),
 
+    #[error("Invalid include directive: {0}")]
+    InvalidInclude(String),
+
+    #[error("Invalid program binary: {0}")]
+    InvalidBinary(String),
+
     #[error("Failed to parse program: {0}")]
-    ParseError(#[from] ParserError),
+    ParseError(ParserError),
 
     #[error("Duplicate label: {0}")]
     DuplicateLabel(String),
 
+    #[error("RAM image at {0:#x} overlaps the static data, ending at {1:#x}")]
+    RamImageOverlap(u32, usize),
+
+    #[error("Slot @{0} is declared public more than once")]
+    DuplicatePublicSlot(u32),
+
     #[error("Empty label")]
     EmptyLabel,
 
@@ -43,8 +75,49 @@ pub enum AssemblerError {
     #[error("Label or function {0} not found")]
     LabelNotFound(String),
 
+    #[error("Label offset {0} points outside of the program")]
+    LabelOffsetOutOfRange(String),
+
+    #[error("Jump table {0} must have less than 65536 cases and lie in the first 64 KiB of RAM")]
+    JumpTableOutOfRange(String),
+
+    #[error("Packed operand slots of {0} must be less than 256")]
+    PackedSlotOutOfRange(String),
+
+    #[error("Byte immediate of {0} must be less than 256")]
+    ByteImmediateOutOfRange(String),
+
+    #[error("Unsupported range check width in {0}, expected one of {RANGE_CHECK_WIDTHS:?}")]
+    UnsupportedRangeCheckWidth(String),
+
+    #[error("Invalid custom instruction {0}, expected an opcode of at least {CUSTOM_OPCODE_BASE:#x} and 16-bit operands")]
+    InvalidCustomInstruction(String),
+
+    #[error("Control-flow analysis failed: {0}")]
+    ControlFlow(#[from] ControlFlowError),
+
     #[error("Something went wrong: {0}")]
     BadError(String),
+
+    #[error("{span}: {error}")]
+    WithSpan {
+        /// The error raised on the source.
+        error: Box<AssemblerError>,
+        /// Location of the offending token.
+        span: SourceSpan,
+    },
+}
+
+impl From<ParserError> for AssemblerError {
+    fn from(err: ParserError) -> Self {
+        match err {
+            ParserError::WithSpan { error, span } => Self::WithSpan {
+                error: Box::new(Self::ParseError(*error)),
+                span,
+            },
+            err => Self::ParseError(err),
+        }
+    }
 }
 
 /// Labels hold the labels in the code, with their associated binary field PCs
@@ -52,46 +125,194 @@ pub enum AssemblerError {
 type Labels = HashMap<String, (B32, u32, u32)>;
 /// Binary field PC as the key. Values are: frame size.
 pub type LabelsFrameSizes = HashMap<B32, u16>;
-// Gives the field PC associated to an integer PC. Only contains the PCs that
-// can be called by the PROM.
-pub(crate) type PCFieldToInt = HashMap<B32, (u32, u32)>;
+/// Data labels with their associated RAM address.
+pub type DataLabels = HashMap<String, u32>;
 
 #[derive(Clone, Debug)]
 pub struct AssembledProgram {
     pub prom: ProgramRom,
     pub labels: Labels,
-    pub pc_field_to_index_pc: PCFieldToInt,
     pub frame_sizes: LabelsFrameSizes,
+    /// Static data of the `.data` sections, followed by the RAM images loaded
+    /// with [`AssembledProgram::load_ram_image`], to be loaded in RAM from
+    /// address 0.
+    pub data: Vec<u8>,
+    /// RAM addresses of the labels of the `.data` sections.
+    pub data_labels: DataLabels,
+    /// Slots of the entry frame exposed as public values, in declaration
+    /// order. As the entry frame starts at address 0, they are also VROM
+    /// addresses.
+    pub public_slots: Vec<u32>,
+    /// Source locations of the instructions, when assembled with
+    /// [`AssemblerOptions::debug_info`]. They are not part of the binary
+    /// format.
+    pub debug_info: Option<DebugInfo>,
+}
+
+impl AssembledProgram {
+    /// Loads `image` in RAM from `base_addr`, so that the guest starts with
+    /// preloaded data structures instead of writing them at startup.
+    ///
+    /// The image must come after the static data, the gap between them being
+    /// zeroed.
+    pub fn load_ram_image(&mut self, image: &[u8], base_addr: u32) -> Result<(), AssemblerError> {
+        if (base_addr as usize) < self.data.len() {
+            return Err(AssemblerError::RamImageOverlap(base_addr, self.data.len()));
+        }
+        self.data.resize(base_addr as usize, 0);
+        self.data.extend_from_slice(image);
+        Ok(())
+    }
+
+    /// Returns the field PCs of the function entry points of the program, i.e.
+    /// of its labels with a frame size, in increasing order.
+    ///
+    /// These are the only valid targets of the indirect calls (`CALLV` and
+    /// `TAILV`) of a proven execution.
+    pub fn call_targets(&self) -> Vec<B32> {
+        let mut targets = self.frame_sizes.keys().copied().collect::<Vec<_>>();
+        targets.sort_by_key(|target| target.val());
+        targets
+    }
+}
+
+/// Optional transformations applied to programs when assembling them.
+#[derive(Clone, Debug, Default)]
+pub struct AssemblerOptions {
+    /// Drops the code unreachable from the entry point, compacting the PROM.
+    pub eliminate_dead_code: bool,
+    /// Sorts the moves populating the frame of a call and merges them into
+    /// `MVV.L` where possible.
+    pub schedule_moves: bool,
+    /// Renumbers the slots of the functions, temporaries accessed on distinct
+    /// paths sharing a slot, and shrinks their frame sizes and the `ALLOCI!`
+    /// of their frames accordingly.
+    pub compact_frames: bool,
+    /// Records the source location of every PROM instruction in
+    /// [`AssembledProgram::debug_info`].
+    pub debug_info: bool,
 }
 
 pub struct Assembler;
 
 impl Assembler {
     pub fn from_file(file: std::path::PathBuf) -> Result<AssembledProgram, AssemblerError> {
-        let file_content = std::fs::read_to_string(file).map_err(AssemblerError::FileReadError)?;
-        Assembler::from_code(&file_content)
+        Assembler::assemble_files(&[file])
+    }
+
+    /// Assembles several source files into a single program.
+    ///
+    /// The files are merged in order, followed by the files they include
+    /// through `.include` directives. Each file is only merged once, and
+    /// execution starts at the first label of the first file. Labels are not
+    /// scoped by file: defining the same label in two files is an
+    /// [`AssemblerError::DuplicateLabel`].
+    pub fn assemble_files<P: AsRef<Path>>(files: &[P]) -> Result<AssembledProgram, AssemblerError> {
+        Assembler::assemble_files_with_warnings(files).map(|(program, _)| program)
+    }
+
+    /// Assembles several source files like [`Assembler::assemble_files`], also
+    /// returning the warnings about the program.
+    pub fn assemble_files_with_warnings<P: AsRef<Path>>(
+        files: &[P],
+    ) -> Result<(AssembledProgram, Vec<AssemblerWarning>), AssemblerError> {
+        Assembler::assemble_files_with_options(files, &AssemblerOptions::default())
+    }
+
+    /// Assembles several source files like
+    /// [`Assembler::assemble_files_with_warnings`], applying the
+    /// transformations enabled in `options`.
+    pub fn assemble_files_with_options<P: AsRef<Path>>(
+        files: &[P],
+        options: &AssemblerOptions,
+    ) -> Result<(AssembledProgram, Vec<AssemblerWarning>), AssemblerError> {
+        let mut sources = include::SourceFiles::default();
+        for file in files {
+            sources.add(file.as_ref())?;
+        }
+        let (mut program, warnings) = Assembler::from_code_with_options(sources.code(), options)
+            .map_err(|err| sources.relocate(err))?;
+        if let Some(debug_info) = &mut program.debug_info {
+            sources.relocate_debug_info(debug_info);
+        }
+        Ok((program, warnings))
     }
 
     pub fn from_code(code: &str) -> Result<AssembledProgram, AssemblerError> {
-        let instructions = parse_program(code)?;
-        Assembler::assemble(instructions)
+        Assembler::from_code_with_warnings(code).map(|(program, _)| program)
+    }
+
+    /// Assembles `code` like [`Assembler::from_code`], also returning the
+    /// warnings about the program. See [`AssemblerWarning`] for the lints.
+    pub fn from_code_with_warnings(
+        code: &str,
+    ) -> Result<(AssembledProgram, Vec<AssemblerWarning>), AssemblerError> {
+        Assembler::from_code_with_options(code, &AssemblerOptions::default())
+    }
+
+    /// Assembles `code` like [`Assembler::from_code_with_warnings`], applying
+    /// the transformations enabled in `options`.
+    pub fn from_code_with_options(
+        code: &str,
+        options: &AssemblerOptions,
+    ) -> Result<(AssembledProgram, Vec<AssemblerWarning>), AssemblerError> {
+        let instructions = parse_program_with_locations(code, options.debug_info)?;
+        let warnings = lint::lint(&instructions);
+        let program = Assembler::assemble(instructions, options).map_err(|err| err.locate(code))?;
+        Ok((program, warnings))
     }
 
     #[instrument(level = "debug", skip_all)]
     fn assemble(
         instructions: Vec<InstructionsWithLabels>,
+        options: &AssemblerOptions,
     ) -> Result<AssembledProgram, AssemblerError> {
-        if !matches!(
-            instructions.first(),
-            Some(InstructionsWithLabels::Label(_, _))
-        ) {
+        // Set the data sections and public slots apart from the code
+        let (public_items, instructions): (Vec<_>, Vec<_>) = instructions
+            .into_iter()
+            .partition(|instruction| matches!(instruction, InstructionsWithLabels::Public(_)));
+        let (mut data_items, instructions): (Vec<_>, Vec<_>) =
+            instructions.into_iter().partition(|instruction| {
+                matches!(
+                    instruction,
+                    InstructionsWithLabels::DataLabel(_) | InstructionsWithLabels::Data(_)
+                )
+            });
+        data_items.extend(jump_table::table_data(&instructions));
+        let (mut data, data_labels, label_words) = layout_data(data_items)?;
+        let instructions = jump_table::lower_jump_tables(instructions, &data_labels)?;
+        let public_slots = public_slots(public_items)?;
+        let instructions = if options.eliminate_dead_code {
+            dce::eliminate_dead_code(instructions)?
+        } else {
+            instructions
+        };
+        let instructions = if options.schedule_moves {
+            schedule::schedule_moves(instructions)
+        } else {
+            instructions
+        };
+        let instructions = if options.compact_frames {
+            frame_layout::compact_frames(instructions)
+        } else {
+            instructions
+        };
+        let mut instructions = pad_alignments(instructions);
+
+        // Source locations don't take part in the layout of the code.
+        let code = instructions
+            .iter()
+            .filter(|instruction| !matches!(instruction, InstructionsWithLabels::Location(_)))
+            .collect::<Vec<_>>();
+
+        if !matches!(code.first(), Some(InstructionsWithLabels::Label(_, _))) {
             return Err(AssemblerError::NoStartLabelFound);
         }
 
         // Make sure there's one and only one label to identify a target
-        if instructions
+        if code
             .iter()
-            .zip(instructions.iter().skip(1))
+            .zip(code.iter().skip(1))
             .any(|(instr, next_instr)| {
                 matches!(instr, InstructionsWithLabels::Label(_, _))
                     && matches!(next_instr, InstructionsWithLabels::Label(_, _))
@@ -101,38 +322,164 @@ impl Assembler {
         }
 
         // Edge case: if the last instruction is a label, just error out.
-        if matches!(
-            instructions.last(),
-            Some(InstructionsWithLabels::Label(_, _))
-        ) {
+        if matches!(code.last(), Some(InstructionsWithLabels::Label(_, _))) {
             return Err(AssemblerError::EmptyLabel);
         }
 
-        let (labels, pc_field_to_index_pc, frame_sizes) = get_labels(&instructions)?;
-        let mut prom = ProgramRom::new();
-        let mut field_pc = B32::ONE;
+        frame_size::infer_frame_sizes(&mut instructions);
+        let data_refs = label_words
+            .iter()
+            .map(|(_, label)| label.as_str())
+            .collect::<Vec<_>>();
+        let (mut labels, frame_sizes) = get_labels(&instructions, &data_refs)?;
+        if let Some(label) = data_labels.keys().find(|label| labels.contains_key(*label)) {
+            return Err(AssemblerError::DuplicateLabel(label.clone()));
+        }
+
+        // Data words referring to labels hold their field PC.
+        for (offset, label) in &label_words {
+            let (target, _, _) = labels
+                .get(label)
+                .ok_or_else(|| AssemblerError::LabelNotFound(label.clone()))?;
+            data[*offset..*offset + 4].copy_from_slice(&target.val().to_le_bytes());
+        }
+
+        let mut prom = ProgramRom::default();
+        let mut field_pc = FieldPc::START;
+        let mut locations = Vec::new();
+        let mut location = None;
 
         for instruction in instructions.iter() {
+            if let InstructionsWithLabels::Location(next) = instruction {
+                location = Some(next.clone());
+            }
             get_prom_inst_from_inst_with_label(&mut prom, &labels, &mut field_pc, instruction)?;
+            locations.resize(prom.len(), location.clone());
         }
+        let debug_info = options.debug_info.then_some(DebugInfo { locations });
+
+        // Label offsets are only resolved for the assembly.
+        labels.retain(|label, _| split_label_ref(label).0 == label);
 
         Ok(AssembledProgram {
             prom,
             labels,
-            pc_field_to_index_pc,
             frame_sizes,
+            data,
+            data_labels,
+            public_slots,
+            debug_info,
         })
     }
 }
 
+/// Collects the public slots of a program, in declaration order.
+fn public_slots(items: Vec<InstructionsWithLabels>) -> Result<Vec<u32>, AssemblerError> {
+    let mut slots = Vec::with_capacity(items.len());
+    for item in items {
+        let InstructionsWithLabels::Public(slot) = item else {
+            unreachable!("Only public slots are collected");
+        };
+        if slots.contains(&slot.index()) {
+            return Err(AssemblerError::DuplicatePublicSlot(slot.index()));
+        }
+        slots.push(slot.index());
+    }
+    Ok(slots)
+}
+
+/// Replaces the `.align` directives of a program with the NOPs padding the
+/// PROM up to a multiple of their alignment.
+///
+/// Labels preceding a directive point to the aligned instruction.
+fn pad_alignments(instructions: Vec<InstructionsWithLabels>) -> Vec<InstructionsWithLabels> {
+    let mut padded = Vec::with_capacity(instructions.len());
+    let mut pending_labels = Vec::new();
+    let mut prom_index = 0;
+
+    for instruction in instructions {
+        match instruction {
+            InstructionsWithLabels::Label(..) | InstructionsWithLabels::Location(_) => {
+                pending_labels.push(instruction)
+            }
+            InstructionsWithLabels::Align(alignment) => {
+                while prom_index % alignment != 0 {
+                    padded.push(InstructionsWithLabels::Nop);
+                    prom_index += 1;
+                }
+            }
+            instruction => {
+                padded.append(&mut pending_labels);
+                padded.push(instruction);
+                prom_index += 1;
+            }
+        }
+    }
+    padded.append(&mut pending_labels);
+
+    padded
+}
+
+/// Lays out the data items of a program in RAM, starting from address 0.
+///
+/// Each directive is placed right after the previous one, after padding to its
+/// alignment, and data labels point to the directive that follows them.
+fn layout_data(
+    items: Vec<InstructionsWithLabels>,
+) -> Result<(Vec<u8>, DataLabels, Vec<(usize, String)>), AssemblerError> {
+    let mut data = Vec::new();
+    let mut data_labels = DataLabels::new();
+    let mut pending_labels = Vec::new();
+    let mut label_words = Vec::new();
+
+    let mut bind_labels = |pending_labels: &mut Vec<String>, addr: usize| {
+        for label in pending_labels.drain(..) {
+            if data_labels.insert(label.clone(), addr as u32).is_some() {
+                return Err(AssemblerError::DuplicateLabel(label));
+            }
+        }
+        Ok(())
+    };
+
+    for item in items {
+        match item {
+            InstructionsWithLabels::DataLabel(label) => pending_labels.push(label),
+            InstructionsWithLabels::Data(directive) => {
+                data.resize(data.len().next_multiple_of(directive.alignment()), 0);
+                bind_labels(&mut pending_labels, data.len())?;
+                if let DataDirective::Labels(labels) = &directive {
+                    label_words.extend(
+                        labels
+                            .iter()
+                            .enumerate()
+                            .map(|(i, label)| (data.len() + 4 * i, label.clone())),
+                    );
+                }
+                data.extend(directive.to_bytes());
+            }
+            _ => unreachable!("Only data items are laid out"),
+        }
+    }
+    bind_labels(&mut pending_labels, data.len())?;
+
+    Ok((data, data_labels, label_words))
+}
+
 // converts instructions into binary field elements
 pub fn get_prom_inst_from_inst_with_label(
     prom: &mut ProgramRom,
     labels: &Labels,
-    field_pc: &mut B32,
+    field_pc: &mut FieldPc,
     instruction: &InstructionsWithLabels,
 ) -> Result<(), AssemblerError> {
     match instruction {
+        InstructionsWithLabels::DataLabel(_)
+        | InstructionsWithLabels::Data(_)
+        | InstructionsWithLabels::Public(_)
+        | InstructionsWithLabels::Align(_)
+        | InstructionsWithLabels::JumpTable { .. } => {
+            return Err(AssemblerError::InvalidInstruction(instruction.to_string()));
+        }
         InstructionsWithLabels::Label(s, _) => {
             if labels.get(s).is_none() {
                 return Err(AssemblerError::BadError(format!(
@@ -140,6 +487,7 @@ pub fn get_prom_inst_from_inst_with_label(
                 )));
             }
         }
+        InstructionsWithLabels::Location(_) => {}
         InstructionsWithLabels::Fp {
             dst,
             imm,
@@ -153,13 +501,13 @@ pub fn get_prom_inst_from_inst_with_label(
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
         InstructionsWithLabels::B32Mul {
@@ -177,13 +525,13 @@ pub fn get_prom_inst_from_inst_with_label(
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
         InstructionsWithLabels::B32Muli {
@@ -201,13 +549,13 @@ pub fn get_prom_inst_from_inst_with_label(
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
 
             let instruction = [
@@ -218,47 +566,45 @@ pub fn get_prom_inst_from_inst_with_label(
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::B128Add {
+        InstructionsWithLabels::B32Inv {
             dst,
-            src1,
-            src2,
+            src,
             prover_only,
         } => {
             let instruction = [
-                Opcode::B128Add.get_field_elt(),
+                Opcode::B32Inv.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                src.get_16bfield_val(),
+                B16::zero(),
             ];
-
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::B128Mul {
+        InstructionsWithLabels::B64Mul {
             dst,
             src1,
             src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::B128Mul.get_field_elt(),
+                Opcode::B64Mul.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
                 src2.get_16bfield_val(),
@@ -266,23 +612,23 @@ pub fn get_prom_inst_from_inst_with_label(
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Groestl256Compress {
+        InstructionsWithLabels::B128Add {
             dst,
             src1,
             src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Groestl256Compress.get_field_elt(),
+                Opcode::B128Add.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
                 src2.get_16bfield_val(),
@@ -290,21 +636,23 @@ pub fn get_prom_inst_from_inst_with_label(
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
-            *field_pc *= G;
+            if !*prover_only {
+                field_pc.incr();
+            }
         }
-        InstructionsWithLabels::Groestl256Output {
+        InstructionsWithLabels::B128Mul {
             dst,
             src1,
             src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Groestl256Output.get_field_elt(),
+                Opcode::B128Mul.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
                 src2.get_16bfield_val(),
@@ -312,904 +660,2044 @@ pub fn get_prom_inst_from_inst_with_label(
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
-            *field_pc *= G;
+            if !*prover_only {
+                field_pc.incr();
+            }
         }
-        InstructionsWithLabels::Mvih {
+        InstructionsWithLabels::B128Inv {
             dst,
-            imm,
+            src,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Mvih.get_field_elt(),
-                dst.get_slot_16bfield_val(),
-                dst.get_offset_field_val(),
-                imm.get_field_val(),
+                Opcode::B128Inv.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Mvvw {
+        InstructionsWithLabels::Groestl256Compress {
             dst,
-            src,
+            src1,
+            src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Mvvw.get_field_elt(),
-                dst.get_slot_16bfield_val(),
-                dst.get_offset_field_val(),
-                src.get_16bfield_val(),
+                Opcode::Groestl256Compress.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
             ];
+
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Mvvl {
+        InstructionsWithLabels::Groestl256Output {
             dst,
-            src,
+            src1,
+            src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Mvvl.get_field_elt(),
-                dst.get_slot_16bfield_val(),
-                dst.get_offset_field_val(),
-                src.get_16bfield_val(),
+                Opcode::Groestl256Output.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
             ];
+
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
-            }
-        }
-        InstructionsWithLabels::Taili { label, next_fp } => {
-            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
-                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
-                let instruction = [
-                    Opcode::Taili.get_field_elt(),
-                    targets_16b[0],
-                    targets_16b[1],
-                    next_fp.get_16bfield_val(),
-                ];
-
-                prom.push(InterpreterInstruction::new(
-                    instruction,
-                    *field_pc,
-                    Some((*prom_index_advice, *pc_advice)),
-                    false,
-                ));
-            } else {
-                return Err(AssemblerError::FunctionNotFound(label.to_string()));
+                field_pc.incr();
             }
-
-            *field_pc *= G;
         }
-        InstructionsWithLabels::Tailv { offset, next_fp } => {
+        InstructionsWithLabels::Sha256Compress {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
             let instruction = [
-                Opcode::Tailv.get_field_elt(),
-                offset.get_16bfield_val(),
-                next_fp.get_16bfield_val(),
-                B16::zero(),
+                Opcode::Sha256Compress.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
             ];
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                false,
+                *prover_only,
             ));
 
-            *field_pc *= G;
-        }
-        InstructionsWithLabels::Calli { label, next_fp } => {
-            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
-                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
-                let instruction = [
-                    Opcode::Calli.get_field_elt(),
-                    targets_16b[0],
-                    targets_16b[1],
-                    next_fp.get_16bfield_val(),
-                ];
-
-                prom.push(InterpreterInstruction::new(
-                    instruction,
-                    *field_pc,
-                    Some((*prom_index_advice, *pc_advice)),
-                    false,
-                ));
-            } else {
-                return Err(AssemblerError::FunctionNotFound(label.to_string()));
+            if !*prover_only {
+                field_pc.incr();
             }
-
-            *field_pc *= G;
         }
-        InstructionsWithLabels::Callv { offset, next_fp } => {
+        InstructionsWithLabels::Blake3Compress {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
             let instruction = [
-                Opcode::Callv.get_field_elt(),
-                offset.get_16bfield_val(),
-                next_fp.get_16bfield_val(),
-                B16::zero(),
+                Opcode::Blake3Compress.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
             ];
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                false,
+                *prover_only,
             ));
 
-            *field_pc *= G;
-        }
-        InstructionsWithLabels::Jumpi { label } => {
-            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
-                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
-                let instruction = [
-                    Opcode::Jumpi.get_field_elt(),
-                    targets_16b[0],
-                    targets_16b[1],
-                    B16::zero(),
-                ];
-
-                prom.push(InterpreterInstruction::new(
-                    instruction,
-                    *field_pc,
-                    Some((*prom_index_advice, *pc_advice)),
-                    false,
-                ));
-            } else {
-                return Err(AssemblerError::LabelNotFound(label.to_string()));
+            if !*prover_only {
+                field_pc.incr();
             }
-            *field_pc *= G;
         }
-        InstructionsWithLabels::Jumpv { offset } => {
+        InstructionsWithLabels::BigMulMod {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
             let instruction = [
-                Opcode::Jumpv.get_field_elt(),
-                offset.get_16bfield_val(),
-                B16::zero(),
-                B16::zero(),
+                Opcode::BigMulMod.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
             ];
 
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                false,
+                *prover_only,
             ));
 
-            *field_pc *= G;
+            if !*prover_only {
+                field_pc.incr();
+            }
         }
-        InstructionsWithLabels::Ldi {
+        InstructionsWithLabels::Mvih {
             dst,
             imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Ldi.get_field_elt(),
-                dst.get_16bfield_val(),
+                Opcode::Mvih.get_field_elt(),
+                dst.get_slot_16bfield_val(),
+                dst.get_offset_field_val(),
                 imm.get_field_val(),
-                imm.get_high_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Xor {
+        InstructionsWithLabels::Mvvw {
             dst,
-            src1,
+            src,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Mvvw.get_field_elt(),
+                dst.get_slot_16bfield_val(),
+                dst.get_offset_field_val(),
+                src.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Mvvl {
+            dst,
+            src,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Mvvl.get_field_elt(),
+                dst.get_slot_16bfield_val(),
+                dst.get_offset_field_val(),
+                src.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Mvvh {
+            dst,
+            src,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Mvvh.get_field_elt(),
+                dst.get_slot_16bfield_val(),
+                dst.get_offset_field_val(),
+                src.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Mvib {
+            dst,
+            imm,
+            prover_only,
+        } => {
+            let imm = imm
+                .get_byte_field_val()
+                .ok_or_else(|| AssemblerError::ByteImmediateOutOfRange(instruction.to_string()))?;
+            let instruction = [
+                Opcode::Mvib.get_field_elt(),
+                dst.get_slot_16bfield_val(),
+                dst.get_offset_field_val(),
+                imm,
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Memcpy {
+            dst,
+            src,
+            len,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Memcpy.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                len.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Memset {
+            dst,
+            src,
+            len,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Memset.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                len.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Cmov {
+            dst,
+            cond,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            // Both source slots are packed in the third argument.
+            let srcs = pack_slot_pair(src1.index(), src2.index())
+                .ok_or_else(|| AssemblerError::PackedSlotOutOfRange(instruction.to_string()))?;
+            let instruction = [
+                Opcode::Cmov.get_field_elt(),
+                dst.get_16bfield_val(),
+                cond.get_16bfield_val(),
+                B16::new(srcs),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Taili { label, next_fp } => {
+            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
+                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
+                let instruction = [
+                    Opcode::Taili.get_field_elt(),
+                    targets_16b[0],
+                    targets_16b[1],
+                    next_fp.get_16bfield_val(),
+                ];
+
+                prom.push(InterpreterInstruction::new(
+                    instruction,
+                    field_pc.field(),
+                    Some((*prom_index_advice, *pc_advice)),
+                    false,
+                ));
+            } else {
+                return Err(AssemblerError::FunctionNotFound(label.to_string()));
+            }
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Tailv { offset, next_fp } => {
+            let instruction = [
+                Opcode::Tailv.get_field_elt(),
+                offset.get_16bfield_val(),
+                next_fp.get_16bfield_val(),
+                B16::zero(),
+            ];
+
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Calli { label, next_fp } => {
+            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
+                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
+                let instruction = [
+                    Opcode::Calli.get_field_elt(),
+                    targets_16b[0],
+                    targets_16b[1],
+                    next_fp.get_16bfield_val(),
+                ];
+
+                prom.push(InterpreterInstruction::new(
+                    instruction,
+                    field_pc.field(),
+                    Some((*prom_index_advice, *pc_advice)),
+                    false,
+                ));
+            } else {
+                return Err(AssemblerError::FunctionNotFound(label.to_string()));
+            }
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Callv { offset, next_fp } => {
+            let instruction = [
+                Opcode::Callv.get_field_elt(),
+                offset.get_16bfield_val(),
+                next_fp.get_16bfield_val(),
+                B16::zero(),
+            ];
+
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Jumpi { label } => {
+            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
+                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
+                let instruction = [
+                    Opcode::Jumpi.get_field_elt(),
+                    targets_16b[0],
+                    targets_16b[1],
+                    B16::zero(),
+                ];
+
+                prom.push(InterpreterInstruction::new(
+                    instruction,
+                    field_pc.field(),
+                    Some((*prom_index_advice, *pc_advice)),
+                    false,
+                ));
+            } else {
+                return Err(AssemblerError::LabelNotFound(label.to_string()));
+            }
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Jumpv { offset } => {
+            let instruction = [
+                Opcode::Jumpv.get_field_elt(),
+                offset.get_16bfield_val(),
+                B16::zero(),
+                B16::zero(),
+            ];
+
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Ldi {
+            dst,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Ldi.get_field_elt(),
+                dst.get_16bfield_val(),
+                imm.get_field_val(),
+                imm.get_high_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::LdiLabel {
+            dst,
+            imm,
+            prover_only,
+        } => {
+            let Some((target, _, _)) = labels.get(imm.label()) else {
+                return Err(AssemblerError::LabelNotFound(imm.label().to_string()));
+            };
+            let value = imm.value(*target);
+            let instruction = [
+                Opcode::Ldi.get_field_elt(),
+                dst.get_16bfield_val(),
+                B16::new(value as u16),
+                B16::new((value >> 16) as u16),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Xor {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Xor.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Xori {
+            dst,
+            src,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Xori.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Bnz { label, src } => {
+            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
+                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
+                let instruction = [
+                    Opcode::Bnz.get_field_elt(),
+                    targets_16b[0],
+                    targets_16b[1],
+                    src.get_16bfield_val(),
+                ];
+
+                prom.push(InterpreterInstruction::new(
+                    instruction,
+                    field_pc.field(),
+                    Some((*prom_index_advice, *pc_advice)),
+                    false,
+                ));
+            } else {
+                return Err(AssemblerError::LabelNotFound(label.to_string()));
+            }
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Beq { label, src1, src2 }
+        | InstructionsWithLabels::Bne { label, src1, src2 }
+        | InstructionsWithLabels::Blt { label, src1, src2 }
+        | InstructionsWithLabels::Bge { label, src1, src2 } => {
+            let opcode = match instruction {
+                InstructionsWithLabels::Beq { .. } => Opcode::Beq,
+                InstructionsWithLabels::Bne { .. } => Opcode::Bne,
+                InstructionsWithLabels::Blt { .. } => Opcode::Blt,
+                _ => Opcode::Bge,
+            };
+            // Both operand slots are packed in the third argument.
+            let srcs = pack_slot_pair(src1.index(), src2.index())
+                .ok_or_else(|| AssemblerError::PackedSlotOutOfRange(instruction.to_string()))?;
+            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
+                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
+                let instruction = [
+                    opcode.get_field_elt(),
+                    targets_16b[0],
+                    targets_16b[1],
+                    B16::new(srcs),
+                ];
+
+                prom.push(InterpreterInstruction::new(
+                    instruction,
+                    field_pc.field(),
+                    Some((*prom_index_advice, *pc_advice)),
+                    false,
+                ));
+            } else {
+                return Err(AssemblerError::LabelNotFound(label.to_string()));
+            }
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Add {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Add.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Addi {
+            dst,
+            src1,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Addi.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Or {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Or.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Ori {
+            dst,
+            src1,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Ori.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sub {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sub.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sle {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sle.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Slei {
+            dst,
+            src,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Slei.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sleu {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sleu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sleiu {
+            dst,
+            src,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sleiu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Slt {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Slt.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Slti {
+            dst,
+            src,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Slti.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sltu {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sltu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sltiu {
+            dst,
+            src,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sltiu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sll {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sll.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Srl {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Srl.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sra {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sra.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Rol {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Rol.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Ror {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Ror.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Div {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Div.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Divu {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Divu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Rem {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Rem.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Remu {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Remu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Add64 {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Add64.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sub64 {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sub64.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Slt64 {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Slt64.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Andi {
+            dst,
+            src1,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Andi.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                imm.get_field_val(),
+            ];
+
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::And {
+            dst,
+            src1,
             src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Xor.get_field_elt(),
+                Opcode::And.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Muli {
+            dst,
+            src1,
+            imm,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Muli.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                imm.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Xori {
+        InstructionsWithLabels::Mul {
             dst,
-            src,
-            imm,
+            src1,
+            src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Xori.get_field_elt(),
+                Opcode::Mul.get_field_elt(),
                 dst.get_16bfield_val(),
-                src.get_16bfield_val(),
-                imm.get_field_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Bnz { label, src } => {
-            if let Some((target, prom_index_advice, pc_advice)) = labels.get(label) {
-                let targets_16b = ExtensionField::<B16>::iter_bases(target).collect::<Vec<_>>();
-                let instruction = [
-                    Opcode::Bnz.get_field_elt(),
-                    targets_16b[0],
-                    targets_16b[1],
-                    src.get_16bfield_val(),
-                ];
+        InstructionsWithLabels::Mulu {
+            dst,
+            src1,
+            src2,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Mulu.get_field_elt(),
+                dst.get_16bfield_val(),
+                src1.get_16bfield_val(),
+                src2.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
 
-                prom.push(InterpreterInstruction::new(
-                    instruction,
-                    *field_pc,
-                    Some((*prom_index_advice, *pc_advice)),
-                    false,
-                ));
-            } else {
-                return Err(AssemblerError::LabelNotFound(label.to_string()));
+            if !*prover_only {
+                field_pc.incr();
             }
-            *field_pc *= G;
         }
-        InstructionsWithLabels::Add {
+        InstructionsWithLabels::Mulsu {
             dst,
             src1,
             src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Add.get_field_elt(),
+                Opcode::Mulsu.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
                 src2.get_16bfield_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Addi {
+        InstructionsWithLabels::Srli {
             dst,
             src1,
             imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Addi.get_field_elt(),
+                Opcode::Srli.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
                 imm.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Or {
+        InstructionsWithLabels::Slli {
             dst,
             src1,
-            src2,
+            imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Or.get_field_elt(),
+                Opcode::Slli.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                imm.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Ori {
+        InstructionsWithLabels::Srai {
             dst,
             src1,
             imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Ori.get_field_elt(),
+                Opcode::Srai.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
                 imm.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sub {
+        InstructionsWithLabels::Roli {
             dst,
             src1,
-            src2,
+            imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Sub.get_field_elt(),
+                Opcode::Roli.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                imm.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sle {
+        InstructionsWithLabels::Rori {
             dst,
             src1,
-            src2,
+            imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Sle.get_field_elt(),
+                Opcode::Rori.get_field_elt(),
                 dst.get_16bfield_val(),
                 src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                imm.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Slei {
+        InstructionsWithLabels::Clz {
             dst,
             src,
-            imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Slei.get_field_elt(),
+                Opcode::Clz.get_field_elt(),
                 dst.get_16bfield_val(),
                 src.get_16bfield_val(),
-                imm.get_field_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sleu {
+        InstructionsWithLabels::Ctz {
             dst,
-            src1,
-            src2,
+            src,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Sleu.get_field_elt(),
+                Opcode::Ctz.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                src.get_16bfield_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sleiu {
+        InstructionsWithLabels::Popcnt {
             dst,
             src,
-            imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Sleiu.get_field_elt(),
+                Opcode::Popcnt.get_field_elt(),
                 dst.get_16bfield_val(),
                 src.get_16bfield_val(),
-                imm.get_field_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Slt {
+        InstructionsWithLabels::Bswap {
             dst,
-            src1,
-            src2,
+            src,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Slt.get_field_elt(),
+                Opcode::Bswap.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                src.get_16bfield_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Slti {
+        InstructionsWithLabels::Sextb {
             dst,
             src,
-            imm,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Slti.get_field_elt(),
+                Opcode::Sextb.get_field_elt(),
                 dst.get_16bfield_val(),
                 src.get_16bfield_val(),
-                imm.get_field_val(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                *prover_only,
+            ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
+        }
+        InstructionsWithLabels::Sexth {
+            dst,
+            src,
+            prover_only,
+        } => {
+            let instruction = [
+                Opcode::Sexth.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sltu {
-            dst,
+        InstructionsWithLabels::Ret => {
+            let instruction = [
+                Opcode::Ret.get_field_elt(),
+                B16::zero(),
+                B16::zero(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Nop => {
+            let instruction = [
+                Opcode::Nop.get_field_elt(),
+                B16::zero(),
+                B16::zero(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Halt { exit_code } => {
+            let instruction = [
+                Opcode::Halt.get_field_elt(),
+                exit_code.get_field_val(),
+                B16::zero(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Alloci { dst, imm } => {
+            let instruction = [
+                Opcode::Alloci.get_field_elt(),
+                dst.get_16bfield_val(),
+                imm.get_field_val(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                true,
+            ));
+        }
+        InstructionsWithLabels::Allocv { src, dst } => {
+            let instruction = [
+                Opcode::Allocv.get_field_elt(),
+                dst.get_16bfield_val(),
+                src.get_16bfield_val(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                true,
+            ));
+        }
+        InstructionsWithLabels::Alloch { dst, size } => {
+            let instruction = [
+                Opcode::Alloch.get_field_elt(),
+                dst.get_16bfield_val(),
+                size.get_16bfield_val(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                true,
+            ));
+        }
+        InstructionsWithLabels::Free { ptr } => {
+            let instruction = [
+                Opcode::Free.get_field_elt(),
+                ptr.get_16bfield_val(),
+                B16::zero(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                true,
+            ));
+        }
+        InstructionsWithLabels::Commit { src } => {
+            let instruction = [
+                Opcode::Commit.get_field_elt(),
+                src.get_16bfield_val(),
+                B16::zero(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::MerkleVerify { root, leaf, path } => {
+            let instruction = [
+                Opcode::MerkleVerify.get_field_elt(),
+                root.get_16bfield_val(),
+                leaf.get_16bfield_val(),
+                path.get_16bfield_val(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::Abort { ptr, len } => {
+            let instruction = [
+                Opcode::Abort.get_field_elt(),
+                ptr.get_16bfield_val(),
+                len.get_16bfield_val(),
+                B16::zero(),
+            ];
+            prom.push(InterpreterInstruction::new(
+                instruction,
+                field_pc.field(),
+                None,
+                false,
+            ));
+
+            field_pc.incr();
+        }
+        InstructionsWithLabels::AssertEq {
             src1,
             src2,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Sltu.get_field_elt(),
-                dst.get_16bfield_val(),
+                Opcode::AssertEq.get_field_elt(),
                 src1.get_16bfield_val(),
                 src2.get_16bfield_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sltiu {
-            dst,
-            src,
-            imm,
-            prover_only,
-        } => {
+        InstructionsWithLabels::AssertZero { src, prover_only } => {
             let instruction = [
-                Opcode::Sltiu.get_field_elt(),
-                dst.get_16bfield_val(),
+                Opcode::AssertZero.get_field_elt(),
                 src.get_16bfield_val(),
-                imm.get_field_val(),
+                B16::zero(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sll {
-            dst,
-            src1,
-            src2,
+        InstructionsWithLabels::RangeCheck {
+            src,
+            bits,
             prover_only,
         } => {
+            if bits.get_high_field_val().val() != 0
+                || !RANGE_CHECK_WIDTHS.contains(&bits.get_field_val().val())
+            {
+                return Err(AssemblerError::UnsupportedRangeCheckWidth(
+                    instruction.to_string(),
+                ));
+            }
             let instruction = [
-                Opcode::Sll.get_field_elt(),
-                dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                Opcode::RangeCheck.get_field_elt(),
+                src.get_16bfield_val(),
+                bits.get_field_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Srl {
-            dst,
-            src1,
-            src2,
+        InstructionsWithLabels::Custom {
+            opcode,
+            args,
             prover_only,
         } => {
+            let invalid = || AssemblerError::InvalidCustomInstruction(instruction.to_string());
+            if opcode.get_high_field_val().val() != 0
+                || opcode.get_field_val().val() < CUSTOM_OPCODE_BASE
+            {
+                return Err(invalid());
+            }
+            let [arg0, arg1, arg2] = args.map(|arg| arg.get_field_val());
             let instruction = [
-                Opcode::Srl.get_field_elt(),
-                dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                opcode.get_field_val(),
+                arg0.ok_or_else(invalid)?,
+                arg1.ok_or_else(invalid)?,
+                arg2.ok_or_else(invalid)?,
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Sra {
-            dst,
-            src1,
-            src2,
-            prover_only,
-        } => {
+        InstructionsWithLabels::Ecall { id, base } => {
             let instruction = [
-                Opcode::Sra.get_field_elt(),
-                dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                Opcode::Ecall.get_field_elt(),
+                id.get_field_val(),
+                base.get_16bfield_val(),
+                B16::zero(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                *prover_only,
+                true,
             ));
-
-            if !*prover_only {
-                *field_pc *= G;
-            }
         }
-        InstructionsWithLabels::Andi {
-            dst,
-            src1,
-            imm,
-            prover_only,
-        } => {
+        InstructionsWithLabels::ReadAdvice { dst } => {
             let instruction = [
-                Opcode::Andi.get_field_elt(),
+                Opcode::ReadAdvice.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                imm.get_field_val(),
+                B16::zero(),
+                B16::zero(),
             ];
-
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                *prover_only,
+                true,
             ));
-
-            if !*prover_only {
-                *field_pc *= G;
-            }
         }
-        InstructionsWithLabels::And {
-            dst,
-            src1,
-            src2,
-            prover_only,
-        } => {
+        InstructionsWithLabels::Debug { src } => {
             let instruction = [
-                Opcode::And.get_field_elt(),
-                dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                Opcode::Debug.get_field_elt(),
+                src.get_16bfield_val(),
+                B16::zero(),
+                B16::zero(),
             ];
-
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                *prover_only,
+                true,
             ));
-
-            if !*prover_only {
-                *field_pc *= G;
-            }
         }
-        InstructionsWithLabels::Muli {
+        InstructionsWithLabels::Lw {
             dst,
-            src1,
-            imm,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Muli.get_field_elt(),
+                Opcode::Lw.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                imm.get_field_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Mul {
+        InstructionsWithLabels::Lb {
             dst,
-            src1,
-            src2,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Mul.get_field_elt(),
+                Opcode::Lb.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Mulu {
+        InstructionsWithLabels::Lbu {
             dst,
-            src1,
-            src2,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Mulu.get_field_elt(),
+                Opcode::Lbu.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Mulsu {
+        InstructionsWithLabels::Lh {
             dst,
-            src1,
-            src2,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Mulsu.get_field_elt(),
+                Opcode::Lh.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                src2.get_16bfield_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Srli {
+        InstructionsWithLabels::Lhu {
             dst,
-            src1,
-            imm,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Srli.get_field_elt(),
+                Opcode::Lhu.get_field_elt(),
                 dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                imm.get_field_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Slli {
-            dst,
-            src1,
-            imm,
+        InstructionsWithLabels::Sw {
+            src,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Slli.get_field_elt(),
-                dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                imm.get_field_val(),
+                Opcode::Sw.get_field_elt(),
+                src.get_16bfield_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Srai {
-            dst,
-            src1,
-            imm,
+        InstructionsWithLabels::Sb {
+            src,
+            base,
+            offset,
             prover_only,
         } => {
             let instruction = [
-                Opcode::Srai.get_field_elt(),
-                dst.get_16bfield_val(),
-                src1.get_16bfield_val(),
-                imm.get_field_val(),
+                Opcode::Sb.get_field_elt(),
+                src.get_16bfield_val(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
                 *prover_only,
             ));
 
             if !*prover_only {
-                *field_pc *= G;
+                field_pc.incr();
             }
         }
-        InstructionsWithLabels::Ret => {
-            let instruction = [
-                Opcode::Ret.get_field_elt(),
-                B16::zero(),
-                B16::zero(),
-                B16::zero(),
-            ];
-            prom.push(InterpreterInstruction::new(
-                instruction,
-                *field_pc,
-                None,
-                false,
-            ));
-
-            *field_pc *= G;
-        }
-        InstructionsWithLabels::Alloci { dst, imm } => {
-            let instruction = [
-                Opcode::Alloci.get_field_elt(),
-                dst.get_16bfield_val(),
-                imm.get_field_val(),
-                B16::zero(),
-            ];
-            prom.push(InterpreterInstruction::new(
-                instruction,
-                *field_pc,
-                None,
-                true,
-            ));
-        }
-        InstructionsWithLabels::Allocv { src, dst } => {
+        InstructionsWithLabels::Sh {
+            src,
+            base,
+            offset,
+            prover_only,
+        } => {
             let instruction = [
-                Opcode::Allocv.get_field_elt(),
-                dst.get_16bfield_val(),
+                Opcode::Sh.get_field_elt(),
                 src.get_16bfield_val(),
-                B16::zero(),
+                base.get_16bfield_val(),
+                offset.get_field_val(),
             ];
             prom.push(InterpreterInstruction::new(
                 instruction,
-                *field_pc,
+                field_pc.field(),
                 None,
-                true,
+                *prover_only,
             ));
+
+            if !*prover_only {
+                field_pc.incr();
+            }
         }
     }
     Ok(())
@@ -1223,85 +2711,83 @@ const fn incr_pc(pc: u32) -> u32 {
     pc + 1
 }
 
-fn insert_if_empty<K, V>(map: &mut HashMap<K, V>, key: K, value: V)
-where
-    K: Eq + std::hash::Hash,
-{
-    if map.get(&key).is_none() {
-        map.insert(key, value);
-    }
-}
-
 fn get_labels(
     instructions: &[InstructionsWithLabels],
-) -> Result<(Labels, PCFieldToInt, LabelsFrameSizes), AssemblerError> {
+    data_refs: &[&str],
+) -> Result<(Labels, LabelsFrameSizes), AssemblerError> {
     let mut labels = HashMap::new();
-    let mut pc_field_to_index_pc = HashMap::new();
     let mut frame_sizes = HashMap::new();
-    let mut field_pc = B32::ONE;
+    let mut field_pc = FieldPc::START;
     let mut prom_index = 0;
     let mut pc = 1;
     let mut functions = HashSet::new();
-
-    let first_label = instructions.first().unwrap();
-    match first_label {
-        InstructionsWithLabels::Label(name, _) => {
-            functions.insert(name.as_str());
-        }
-        _ => unreachable!(),
-    }
-
-    // Insert first label into discrete log map
-    pc_field_to_index_pc.insert(field_pc, (prom_index, pc));
+    // Start of each instruction executed by the verifier, including the
+    // prover-only instructions preceding it, and index of the instruction
+    // following each label.
+    let mut positions = Vec::new();
+    let mut pending_position = None;
+    let mut label_positions = HashMap::new();
+
+    let Some(InstructionsWithLabels::Label(name, _)) = instructions.first() else {
+        return Err(AssemblerError::NoStartLabelFound);
+    };
+    functions.insert(name.as_str());
 
     // Identify functions from the labels and check if they have valid frame sizes.
     for instruction in instructions {
-        match instruction {
-            InstructionsWithLabels::Label(s, frame_size) => {
-                if labels
-                    .insert(s.clone(), (field_pc, prom_index, pc))
-                    .is_some()
-                {
-                    return Err(AssemblerError::DuplicateLabel(s.clone()));
-                }
-
-                // If we have a frame size for this label, add it to our frame_sizes map
-                if let Some(size) = frame_size {
-                    frame_sizes.insert(field_pc, *size);
-                }
+        if let InstructionsWithLabels::Label(s, frame_size) = instruction {
+            if labels
+                .insert(s.clone(), (field_pc.field(), prom_index, pc))
+                .is_some()
+            {
+                return Err(AssemblerError::DuplicateLabel(s.clone()));
+            }
+            label_positions.insert(s.as_str(), positions.len());
 
-                // We do not increment the PROM index or PC if we found a label.
-                continue;
+            // If we have a frame size for this label, add it to our frame_sizes map
+            if let Some(size) = frame_size {
+                frame_sizes.insert(field_pc.field(), *size);
             }
+
+            // We do not increment the PROM index or PC if we found a label.
+            continue;
+        }
+        if let InstructionsWithLabels::Location(_) = instruction {
+            continue;
+        }
+
+        let position = *pending_position.get_or_insert((field_pc.field(), prom_index, pc));
+        if !instruction.prover_only() {
+            positions.push(position);
+            pending_position = None;
+        }
+
+        match instruction {
             InstructionsWithLabels::B32Muli { prover_only, .. } => {
                 prom_index += 1;
                 if !*prover_only {
-                    field_pc *= G;
-                    pc = incr_pc(pc);
-                    insert_if_empty(&mut pc_field_to_index_pc, field_pc, (prom_index, pc));
-                    field_pc *= G;
-                    pc = incr_pc(pc);
+                    field_pc.advance(2);
+                    pc = incr_pc(incr_pc(pc));
                 }
             }
             InstructionsWithLabels::Taili { label, .. } => {
                 functions.insert(label.as_str());
-                field_pc *= G;
+                field_pc.incr();
                 pc = incr_pc(pc);
             }
             InstructionsWithLabels::Calli { label, .. } => {
                 functions.insert(label.as_str());
-                field_pc *= G;
+                field_pc.incr();
                 pc = incr_pc(pc);
             }
             instruction => {
                 if !instruction.prover_only() {
-                    field_pc *= G;
+                    field_pc.incr();
                     pc = incr_pc(pc);
                 }
             }
         }
         prom_index += 1;
-        insert_if_empty(&mut pc_field_to_index_pc, field_pc, (prom_index, pc));
     }
 
     for function in functions {
@@ -1313,7 +2799,30 @@ fn get_labels(
         }
     }
 
-    Ok((labels, pc_field_to_index_pc, frame_sizes))
+    // Resolve the references to labels with an offset, counted in
+    // instructions, e.g. `loop+2`.
+    let label_refs = instructions
+        .iter()
+        .flat_map(InstructionsWithLabels::label_refs)
+        .chain(data_refs.iter().copied());
+    for label_ref in label_refs {
+        let (label, offset) = split_label_ref(label_ref);
+        if label == label_ref || labels.contains_key(label_ref) {
+            continue;
+        }
+        let index = label_positions
+            .get(label)
+            .ok_or_else(|| AssemblerError::LabelNotFound(label.to_string()))?;
+        let position = i64::try_from(*index)
+            .ok()
+            .and_then(|index| index.checked_add(offset))
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| positions.get(index))
+            .ok_or_else(|| AssemblerError::LabelOffsetOutOfRange(label_ref.to_string()))?;
+        labels.insert(label_ref.to_string(), *position);
+    }
+
+    Ok((labels, frame_sizes))
 }
 
 #[cfg(test)]
@@ -1321,30 +2830,387 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_function_with_no_frame_size() {
-        let programs = [
-            r#"
-        #[framesize(0x10)]
+    fn test_frame_size_inference() {
+        let program = r#"
             start:
-                CALLI some_function, @3
+                MVV.W @4[2], @2
+                CALLI wide, @4
+                ALLOCI! @5, #16
+                MVV.W @5[2], @2
+                TAILI explicit, @5
+
+            wide:
+                B128_ADD @4, @8, @12
+            loop:
+                BNZ loop, @2
+                RET
+
+            #[framesize(0x20)]
+            explicit:
+                MUL @3, @2, @2
+                RET
+
+            #[framesize(0x2)]
+            too_small:
+                LDI.W @3, #1
+                RET
+            "#;
+
+        let out = Assembler::from_code(program).unwrap();
+        let frame_size = |label: &str| out.frame_sizes[&out.labels[label].0];
+        assert_eq!(frame_size("start"), 6);
+        assert_eq!(frame_size("wide"), 16);
+        // Explicit annotations take precedence, even when too small.
+        assert_eq!(frame_size("explicit"), 0x20);
+        assert_eq!(frame_size("too_small"), 2);
+        assert_eq!(out.frame_sizes.len(), 4);
+
+        // The functions are the valid targets of indirect calls, unlike the
+        // inner labels.
+        let mut targets = ["start", "wide", "explicit", "too_small"].map(|l| out.labels[l].0);
+        targets.sort_by_key(|target| target.val());
+        assert_eq!(out.call_targets(), targets);
+    }
+
+    #[test]
+    fn test_data_section() {
+        let program = r#"
+            .data
+            greeting:
+                .ascii "hi\n"
+            table:
+                .word 0xdeadbeef, -1
+                .bytes 1, 0xff, -2
+            .text
+
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #4
+                LW @3, @2, #0
+                LW @4, @2, #4
                 RET
 
-            some_function:
-                MVV.W @3[1], @1
+            .data
+            empty:
+            "#;
+
+        let out = Assembler::from_code(program).unwrap();
+        assert_eq!(
+            out.data,
+            [
+                b"hi\n\0".as_slice(),
+                &0xdeadbeefu32.to_le_bytes(),
+                &u32::MAX.to_le_bytes(),
+                &[1, 0xff, 0xfe],
+            ]
+            .concat()
+        );
+        assert_eq!(out.data_labels["greeting"], 0);
+        assert_eq!(out.data_labels["table"], 4);
+        assert_eq!(out.data_labels["empty"], 15);
+        assert_eq!(out.prom.len(), 4);
+
+        let memory = crate::Memory::new(out.prom, crate::ValueRom::new_with_init_vals(&[0, 0]))
+            .with_ram_data(&out.data);
+        let (trace, _) =
+            crate::PetraTrace::generate(Box::new(crate::isa::GenericISA), memory, out.frame_sizes)
+                .unwrap();
+        assert_eq!(trace.vrom().read::<u32>(3).unwrap(), 0xdeadbeef);
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), u32::MAX);
+    }
+
+    #[test]
+    fn test_ram_image() {
+        let program = r#"
+            .data
+            header:
+                .word 7
+            .text
+
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #0x100
+                LW @3, @2, #0
+                LW @4, @2, #4
+                LDI.W @5, #0
+                LW @6, @5, #0
                 RET
-            "#,
-            r#"
+            "#;
+
+        let mut out = Assembler::from_code(program).unwrap();
+        let image = [0x11223344u32, 0x55667788]
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            out.load_ram_image(&image, 2),
+            Err(AssemblerError::RamImageOverlap(2, 4))
+        ));
+        out.load_ram_image(&image, 0x100).unwrap();
+        assert_eq!(out.data.len(), 0x108);
+
+        let memory = crate::Memory::new(out.prom, crate::ValueRom::new_with_init_vals(&[0, 0]))
+            .with_ram_data(&out.data);
+        let (trace, _) =
+            crate::PetraTrace::generate(Box::new(crate::isa::GenericISA), memory, out.frame_sizes)
+                .unwrap();
+        assert_eq!(trace.vrom().read::<u32>(3).unwrap(), 0x11223344);
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), 0x55667788);
+        assert_eq!(trace.vrom().read::<u32>(6).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_data_label_collision() {
+        let program = r#"
+            .data
+            start:
+                .word 1
+            .text
             start:
                 RET
-            "#,
-        ];
+            "#;
+
+        assert!(matches!(
+            Assembler::from_code(program).unwrap_err().root(),
+            AssemblerError::DuplicateLabel(_)
+        ));
+    }
+
+    #[test]
+    fn test_public_slots() {
+        let program = "
+            .public @4
+            _start:
+                LDI.W @2, #1
+                .public @2 ;; Declared after the entry label
+                LDI.W @4, #2
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        assert_eq!(out.public_slots, [4, 2]);
+        assert_eq!(out.prom.len(), 3);
+
+        let duplicate = "
+            .public @2
+            _start:
+            .public @2
+                RET
+            ";
+        assert!(matches!(
+            Assembler::from_code(duplicate),
+            Err(AssemblerError::DuplicatePublicSlot(2))
+        ));
+    }
+
+    #[test]
+    fn test_packed_slot_range() {
+        let program = "
+            #[framesize(0x200)]
+            _start:
+                BEQ _start, @2, @255
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        assert_eq!(out.prom[0].instruction[3].val(), 2 | (255 << 8));
+
+        let out_of_range = "
+            #[framesize(0x200)]
+            _start:
+                BLT _start, @256, @2
+                RET
+            ";
+        assert!(matches!(
+            Assembler::from_code(out_of_range),
+            Err(AssemblerError::PackedSlotOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_byte_immediate_range() {
+        let program = "
+            #[framesize(0x10)]
+            _start:
+                MVI.B @2[3], #255
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        assert_eq!(out.prom[0].instruction[3].val(), 255);
+
+        let out_of_range = "
+            #[framesize(0x10)]
+            _start:
+                MVI.B @2[3], #256
+                RET
+            ";
+        assert!(matches!(
+            Assembler::from_code(out_of_range),
+            Err(AssemblerError::ByteImmediateOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_align() {
+        let program = "
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #1
+                .align 4
+            aligned:
+                J end
+                .align 2 ;; Already aligned
+            end:
+                .align 4
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        let opcodes: Vec<_> = out
+            .prom
+            .iter()
+            .map(|instr| instr.instruction[0].val())
+            .collect();
+        let nop = Opcode::Nop as u16;
+        assert_eq!(
+            opcodes,
+            [
+                Opcode::Ldi as u16,
+                nop,
+                nop,
+                nop,
+                Opcode::Jumpi as u16,
+                nop,
+                nop,
+                nop,
+                Opcode::Ret as u16
+            ]
+        );
+        assert_eq!(out.labels["aligned"].1, 4);
+        assert_eq!(out.labels["end"].1, 8);
+
+        let memory = crate::Memory::new(out.prom, crate::ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, boundary_values) =
+            crate::PetraTrace::generate(Box::new(crate::isa::GenericISA), memory, out.frame_sizes)
+                .unwrap();
+        assert_eq!(trace.nop.len(), 3);
+        trace.validate(boundary_values);
+
+        let err = Assembler::from_code("_start:\nRET\n.align 0").unwrap_err();
+        assert!(matches!(
+            err.root(),
+            AssemblerError::ParseError(ParserError::InvalidAlignment(_))
+        ));
+        assert_eq!(
+            err.span().map(|span| (span.line, span.column)),
+            Some((3, 8))
+        );
+    }
+
+    #[test]
+    fn test_range_check_width() {
+        let program = "
+            #[framesize(0x10)]
+            _start:
+                RANGE_CHECK @2, #16
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        assert_eq!(out.prom[0].instruction[2].val(), 16);
+
+        let unsupported = "
+            #[framesize(0x10)]
+            _start:
+                RANGE_CHECK @2, #12
+                RET
+            ";
+        assert!(matches!(
+            Assembler::from_code(unsupported),
+            Err(AssemblerError::UnsupportedRangeCheckWidth(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_instruction() {
+        let program = "
+            #[framesize(0x10)]
+            _start:
+                CUSTOM #0x8003, @2, #7, @3
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        assert_eq!(
+            out.prom[0].instruction.map(|arg| arg.val()),
+            [0x8003, 2, 7, 3]
+        );
+
+        for invalid in [
+            "CUSTOM #0x7fff, @2, @2, @2",
+            "CUSTOM #0x8000, @2, #0x10000, @2",
+        ] {
+            let program = format!("#[framesize(0x10)]\n_start:\n{invalid}\nRET");
+            assert!(matches!(
+                Assembler::from_code(&program),
+                Err(AssemblerError::InvalidCustomInstruction(_))
+            ));
+        }
+    }
 
-        for program in programs {
-            let out = Assembler::from_code(program);
+    #[test]
+    fn test_label_offsets() {
+        let program = "
+            .data
+            table:
+                .word case0, case1+1, 7
+            .text
+
+            #[framesize(0x10)]
+            _start:
+                LDI.W @2, #pc(case1)
+                LDI.W @3, #lo(case1+1)
+                LDI.W @4, #hi(case1+1)
+                BNZ case0+1, @2
+            case0:
+                LDI.W @5, #1
+                LDI.W @6, #2
+            case1:
+                LDI.W @7, #3
+                RET
+            ";
+        let out = Assembler::from_code(program).unwrap();
+        let case0 = out.labels["case0"].0.val();
+        let case1 = out.labels["case1"].0.val();
+        let ret = out.prom[7].field_pc.val();
+        assert!(!out.labels.contains_key("case0+1"));
+        assert_eq!(out.prom[3].advice, Some((5, 6)));
+        assert_eq!(
+            out.data,
+            [case0, ret, 7]
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect::<Vec<_>>()
+        );
+
+        let memory = crate::Memory::new(out.prom, crate::ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, boundary_values) =
+            crate::PetraTrace::generate(Box::new(crate::isa::GenericISA), memory, out.frame_sizes)
+                .unwrap();
+        let vrom = trace.vrom();
+        assert_eq!(vrom.read::<u32>(2).unwrap(), case1);
+        assert_eq!(vrom.read::<u32>(3).unwrap(), ret & 0xffff);
+        assert_eq!(vrom.read::<u32>(4).unwrap(), ret >> 16);
+        // The branch skips the first instruction of `case0`.
+        assert!(vrom.peek::<u32>(5).is_err());
+        assert_eq!(vrom.read::<u32>(6).unwrap(), 2);
+        trace.validate(boundary_values);
+
+        for (code, expected) in [("J end+1", "end+1"), ("J end-2", "end-2")] {
+            let program = format!("#[framesize(0x10)]\n_start:\nRET\nend:\n{code}");
             assert!(matches!(
-                out,
-                Err(AssemblerError::FunctionHasNoFrameSize(_))
+                Assembler::from_code(&program).unwrap_err().root(),
+                AssemblerError::LabelOffsetOutOfRange(label) if label == expected
             ));
         }
+        assert!(matches!(
+            Assembler::from_code("#[framesize(0x10)]\n_start:\nJ nowhere+1").unwrap_err().root(),
+            AssemblerError::LabelNotFound(label) if label == "nowhere"
+        ));
     }
 }
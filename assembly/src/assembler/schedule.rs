@@ -0,0 +1,239 @@
+//! Scheduling of the moves populating the frame of a call.
+//!
+//! Calls are typically preceded by a run of `MVV.W` and `MVI.H` writing the
+//! arguments into the next frame. When the source slots of the moves have been
+//! written earlier in the same basic block, the moves are independent: they are
+//! then sorted by destination offset, and every four `MVV.W` copying aligned
+//! consecutive slots are merged into a single `MVV.L`.
+//!
+//! A move whose source may be unset copies the value the other way around, so
+//! it is left in place and the moves around it are scheduled separately.
+
+use std::collections::HashSet;
+
+use crate::parser::InstructionsWithLabels;
+
+/// Number of slots moved by `MVV.L`.
+const MVVL_SLOTS: u32 = 4;
+
+/// Schedules the runs of moves preceding calls in `instructions`.
+pub(crate) fn schedule_moves(
+    instructions: Vec<InstructionsWithLabels>,
+) -> Vec<InstructionsWithLabels> {
+    let mut scheduled = Vec::with_capacity(instructions.len());
+    let mut run = Vec::new();
+    // Slots of the current frame written since the last label.
+    let mut written = HashSet::new();
+
+    for instruction in instructions {
+        let in_run = match &instruction {
+            InstructionsWithLabels::Location(_) => !run.is_empty(),
+            instruction => move_target(instruction).is_some(),
+        };
+        if in_run {
+            run.push(instruction);
+            continue;
+        }
+
+        let next_fp = match &instruction {
+            InstructionsWithLabels::Calli { next_fp, .. }
+            | InstructionsWithLabels::Callv { next_fp, .. }
+            | InstructionsWithLabels::Taili { next_fp, .. }
+            | InstructionsWithLabels::Tailv { next_fp, .. } => Some(next_fp.index()),
+            _ => None,
+        };
+        let frame_moves = next_fp.is_some()
+            && run
+                .iter()
+                .filter(|instruction| !matches!(instruction, InstructionsWithLabels::Location(_)))
+                .all(|instruction| move_target(instruction).map(|(frame, _)| frame) == next_fp);
+        if frame_moves {
+            // The scheduled moves are located at the first of them, and the
+            // call at its own location.
+            let location = match run.last() {
+                Some(InstructionsWithLabels::Location(_)) => run.pop(),
+                _ => None,
+            };
+            schedule_run(&mut scheduled, std::mem::take(&mut run), &written);
+            scheduled.extend(location);
+        } else {
+            scheduled.append(&mut run);
+        }
+
+        if let InstructionsWithLabels::Label(..) = instruction {
+            written.clear();
+        } else if let Some((slot, width)) = instruction.written_slots() {
+            written.extend(slot.index()..slot.index() + width);
+        }
+        scheduled.push(instruction);
+    }
+    scheduled.append(&mut run);
+
+    scheduled
+}
+
+/// Returns the slot holding the target frame and the target offset of a move
+/// that may be scheduled.
+fn move_target(instruction: &InstructionsWithLabels) -> Option<(u32, u16)> {
+    match instruction {
+        InstructionsWithLabels::Mvvw {
+            dst,
+            prover_only: false,
+            ..
+        }
+        | InstructionsWithLabels::Mvvl {
+            dst,
+            prover_only: false,
+            ..
+        }
+        | InstructionsWithLabels::Mvih {
+            dst,
+            prover_only: false,
+            ..
+        } => Some((dst.slot().index(), dst.offset())),
+        _ => None,
+    }
+}
+
+/// Returns whether a move only copies values from slots in `written`.
+fn is_independent(instruction: &InstructionsWithLabels, written: &HashSet<u32>) -> bool {
+    let (src, width) = match instruction {
+        InstructionsWithLabels::Mvvw { src, .. } => (src.index(), 1),
+        InstructionsWithLabels::Mvvl { src, .. } => (src.index(), MVVL_SLOTS),
+        _ => return true,
+    };
+    (src..src + width).all(|slot| written.contains(&slot))
+}
+
+/// Appends the moves of `run` to `scheduled`, sorting and merging the
+/// independent ones.
+fn schedule_run(
+    scheduled: &mut Vec<InstructionsWithLabels>,
+    run: Vec<InstructionsWithLabels>,
+    written: &HashSet<u32>,
+) {
+    let mut independent = Vec::new();
+    for instruction in run {
+        if let InstructionsWithLabels::Location(_) = instruction {
+            continue;
+        }
+        if is_independent(&instruction, written) {
+            independent.push(instruction);
+        } else {
+            append_merged(scheduled, std::mem::take(&mut independent));
+            scheduled.push(instruction);
+        }
+    }
+    append_merged(scheduled, independent);
+}
+
+/// Appends `moves` to `scheduled` by destination offset, merging aligned
+/// groups of `MVV.W` into `MVV.L`.
+fn append_merged(
+    scheduled: &mut Vec<InstructionsWithLabels>,
+    mut moves: Vec<InstructionsWithLabels>,
+) {
+    moves.sort_by_key(|instruction| move_target(instruction).map(|(_, offset)| offset));
+
+    let mut moves = moves.into_iter().peekable();
+    while let Some(instruction) = moves.next() {
+        let head = match &instruction {
+            InstructionsWithLabels::Mvvw { dst, src, .. } => Some((*dst, *src)),
+            _ => None,
+        };
+        let Some((dst, src)) = head else {
+            scheduled.push(instruction);
+            continue;
+        };
+        let aligned = dst.offset() as u32 % MVVL_SLOTS == 0 && src.index() % MVVL_SLOTS == 0;
+        let mut group = vec![instruction];
+        while aligned && group.len() < MVVL_SLOTS as usize {
+            let next = group.len() as u32;
+            let Some(InstructionsWithLabels::Mvvw {
+                dst: next_dst,
+                src: next_src,
+                ..
+            }) = moves.peek()
+            else {
+                break;
+            };
+            if next_dst.offset() as u32 != dst.offset() as u32 + next
+                || next_src.index() != src.index() + next
+            {
+                break;
+            }
+            group.extend(moves.next());
+        }
+
+        if group.len() == MVVL_SLOTS as usize {
+            scheduled.push(InstructionsWithLabels::Mvvl {
+                dst,
+                src,
+                prover_only: false,
+            });
+        } else {
+            scheduled.append(&mut group);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, AssemblerOptions, Memory, PetraTrace, ValueRom};
+
+    #[test]
+    fn test_schedule_moves() {
+        let code = "
+            #[framesize(0x10)]
+            _start:
+                LDI.W @4, #1
+                LDI.W @5, #2
+                LDI.W @6, #3
+                LDI.W @7, #4
+                ALLOCI! @8, #16
+                MVI.H @8[3], #9
+                MVI.H @8[2], #9
+                MVV.W @8[8], @2
+                MVV.W @8[7], @7
+                MVV.W @8[5], @5
+                MVV.W @8[6], @6
+                MVV.W @8[4], @4
+                CALLI callee, @8
+                RET
+            #[framesize(0x10)]
+            callee:
+                RET
+            ";
+        let options = AssemblerOptions {
+            schedule_moves: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(code, &options).unwrap();
+        let full = Assembler::from_code(code).unwrap();
+        assert_eq!(program.prom.len(), full.prom.len() - 3);
+
+        // The merged moves populate the same slots of the next frame.
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0, 42]));
+        let (trace, _) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+        let next_fp = trace.vrom().read::<u32>(8).unwrap();
+        let args = (4..9)
+            .map(|offset| trace.vrom().read::<u32>(next_fp ^ offset).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(args, [1, 2, 3, 4, 42]);
+
+        let instructions = crate::parser::parse_program(code).unwrap();
+        let scheduled = schedule_moves(instructions)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        // The move from @2, which may be unset, splits the run.
+        let moves = &scheduled[6..11];
+        assert!(moves[0].starts_with("MVI.H @8[2]"), "{moves:?}");
+        assert!(moves[1].starts_with("MVI.H @8[3]"), "{moves:?}");
+        assert_eq!(moves[2], "MVV.W @8[8] @2");
+        assert_eq!(moves[3], "MVV.L @8[4] @4");
+        assert!(moves[4].starts_with("CALLI"), "{moves:?}");
+    }
+}
@@ -0,0 +1,195 @@
+//! Arithmetic over 256-bit unsigned integers, used by the BIGMULMOD
+//! instruction and the elliptic curve precompiles.
+//!
+//! Integers are represented as 32-bit limbs, least significant limb first,
+//! which is also how they are laid out in consecutive VROM slots.
+
+/// Number of 32-bit limbs of a 256-bit integer.
+pub const LIMBS: usize = 8;
+
+/// A 256-bit unsigned integer.
+pub type U256 = [u32; LIMBS];
+
+/// A 512-bit unsigned integer, e.g. the full product of two [`U256`].
+pub type U512 = [u32; 2 * LIMBS];
+
+pub const ZERO: U256 = [0; LIMBS];
+pub const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// Returns whether `a < b`.
+pub fn lt<const N: usize>(a: &[u32; N], b: &[u32; N]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()).is_lt()
+}
+
+/// Returns `a + b` modulo `2^256`, and whether the addition overflowed.
+pub fn add(a: &U256, b: &U256) -> (U256, bool) {
+    let mut sum = ZERO;
+    let mut carry = false;
+    for (i, limb) in sum.iter_mut().enumerate() {
+        let (s, c0) = a[i].overflowing_add(b[i]);
+        let (s, c1) = s.overflowing_add(carry as u32);
+        *limb = s;
+        carry = c0 || c1;
+    }
+    (sum, carry)
+}
+
+/// Returns `a - b` modulo `2^256`, and whether the subtraction underflowed.
+pub fn sub(a: &U256, b: &U256) -> (U256, bool) {
+    let mut diff = ZERO;
+    let mut borrow = false;
+    for (i, limb) in diff.iter_mut().enumerate() {
+        let (d, b0) = a[i].overflowing_sub(b[i]);
+        let (d, b1) = d.overflowing_sub(borrow as u32);
+        *limb = d;
+        borrow = b0 || b1;
+    }
+    (diff, borrow)
+}
+
+/// Returns the full product `a * b`.
+pub fn mul(a: &U256, b: &U256) -> U512 {
+    mul_add(a, b, &ZERO)
+}
+
+/// Returns `a * b + c`, which always fits in 512 bits.
+pub fn mul_add(a: &U256, b: &U256, c: &U256) -> U512 {
+    let mut out = [0; 2 * LIMBS];
+    out[..LIMBS].copy_from_slice(c);
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + out[i + j] as u64 + carry;
+            out[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        // The limbs above `i + LIMBS` have not been written to yet.
+        out[i + LIMBS] = carry as u32;
+    }
+    out
+}
+
+/// Returns the quotient and remainder of the division of `x` by `m`.
+///
+/// Returns `None` if `m` is zero.
+pub fn div_rem(x: &U512, m: &U256) -> Option<(U512, U256)> {
+    if *m == ZERO {
+        return None;
+    }
+
+    // Schoolbook binary long division. The remainder is always less than `2 *
+    // m`, so the bit shifted out of it is enough to detect that it exceeds `m`.
+    let mut quotient = [0; 2 * LIMBS];
+    let mut remainder = ZERO;
+    for i in (0..32 * 2 * LIMBS).rev() {
+        let overflow = remainder[LIMBS - 1] >> 31 == 1;
+        for j in (1..LIMBS).rev() {
+            remainder[j] = (remainder[j] << 1) | (remainder[j - 1] >> 31);
+        }
+        remainder[0] = (remainder[0] << 1) | ((x[i / 32] >> (i % 32)) & 1);
+        if overflow || !lt(&remainder, m) {
+            remainder = sub(&remainder, m).0;
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+    Some((quotient, remainder))
+}
+
+/// Returns `a + b mod m`, for `a` and `b` less than `m`.
+pub fn add_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (sum, carry) = add(a, b);
+    if carry || !lt(&sum, m) {
+        sub(&sum, m).0
+    } else {
+        sum
+    }
+}
+
+/// Returns `a - b mod m`, for `a` and `b` less than `m`.
+pub fn sub_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (diff, borrow) = sub(a, b);
+    if borrow {
+        add(&diff, m).0
+    } else {
+        diff
+    }
+}
+
+/// Returns `a * b mod m`, or `None` if `m` is zero.
+pub fn mul_mod(a: &U256, b: &U256, m: &U256) -> Option<U256> {
+    div_rem(&mul(a, b), m).map(|(_, remainder)| remainder)
+}
+
+/// Returns `a^e mod m`, or `None` if `m` is zero.
+pub fn pow_mod(a: &U256, e: &U256, m: &U256) -> Option<U256> {
+    // Start from `1 mod m`, which is zero when `m` is one.
+    let mut acc = div_rem(&mul(&ONE, &ONE), m)?.1;
+    for i in (0..32 * LIMBS).rev() {
+        acc = mul_mod(&acc, &acc, m)?;
+        if (e[i / 32] >> (i % 32)) & 1 == 1 {
+            acc = mul_mod(&acc, a, m)?;
+        }
+    }
+    Some(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_div_rem() {
+        // (2^256 - 1)^2 = (2^256 - 2) * 2^256 + 1.
+        let max = [u32::MAX; LIMBS];
+        let mut max_minus_one = max;
+        max_minus_one[0] -= 1;
+        let (quotient, remainder) = div_rem(&mul(&max, &max), &max_minus_one).unwrap();
+        assert_eq!(quotient[..LIMBS], ZERO);
+        assert_eq!(quotient[LIMBS..], ONE);
+        assert_eq!(remainder, ONE);
+
+        assert_eq!(div_rem(&mul(&max, &max), &ZERO), None);
+    }
+
+    #[test]
+    fn test_random_mul_div_rem() {
+        for _ in 0..100 {
+            let a: U256 = rand::random();
+            let b: U256 = rand::random();
+            // Shorten the modulus to get a quotient of more than 256 bits.
+            let mut m: U256 = rand::random();
+            m[LIMBS - 1] = 0;
+            m[0] |= 1;
+
+            let product = mul(&a, &b);
+            let (quotient, remainder) = div_rem(&product, &m).unwrap();
+            assert!(lt(&remainder, &m));
+
+            // quotient * m + remainder = product, where the quotient is split in
+            // two halves as `mul_add` only takes 256-bit operands.
+            let (low, high) = quotient.split_at(LIMBS);
+            let mut expected = mul_add(low.try_into().unwrap(), &m, &remainder);
+            let high = mul(high.try_into().unwrap(), &m);
+            assert_eq!(high[LIMBS..], ZERO);
+            let (upper, carry) = add(
+                expected[LIMBS..].try_into().unwrap(),
+                &high[..LIMBS].try_into().unwrap(),
+            );
+            assert!(!carry);
+            expected[LIMBS..].copy_from_slice(&upper);
+            assert_eq!(expected, product);
+        }
+    }
+
+    #[test]
+    fn test_add_sub() {
+        for _ in 0..100 {
+            let a: U256 = rand::random();
+            let b: U256 = rand::random();
+            let (sum, carry) = add(&a, &b);
+            let (diff, borrow) = sub(&sum, &b);
+            assert_eq!(diff, a);
+            assert_eq!(carry, borrow);
+        }
+    }
+}
@@ -0,0 +1,394 @@
+//! Conversion of a [`ProgramRom`] back into assembly text.
+//!
+//! The output can be assembled again into the same PROM, which makes it useful
+//! both to inspect assembled programs and to test the assembler. Branch targets
+//! are given the names of the original labels when those are provided, and
+//! synthetic `L<index>` names otherwise.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use binius_m3::builder::{B16, B32};
+
+use crate::{
+    assembler::LabelsFrameSizes, event::unpack_slot_pair, execution::CUSTOM_OPCODE_BASE,
+    AssembledProgram, FieldPc, Opcode, ProgramRom,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DisassemblerError {
+    #[error("Invalid opcode 0x{opcode:x} at PROM index {index}")]
+    InvalidOpcode { index: usize, opcode: u16 },
+
+    #[error("Missing upper immediate half of the instruction at PROM index {0}")]
+    MissingImmediate(usize),
+
+    #[error("Branch target {target} of the instruction at PROM index {index} is not in the PROM")]
+    UnknownTarget { index: usize, target: B32 },
+}
+
+/// Converts a PROM, and optionally its labels and frame sizes, into assembly
+/// text.
+#[derive(Debug)]
+pub struct Disassembler<'a> {
+    prom: &'a ProgramRom,
+    /// Label names, by PROM index.
+    labels: HashMap<usize, String>,
+    frame_sizes: LabelsFrameSizes,
+}
+
+impl<'a> Disassembler<'a> {
+    /// Creates a disassembler for `prom`, without any label or frame size.
+    pub fn new(prom: &'a ProgramRom) -> Self {
+        Self {
+            prom,
+            labels: HashMap::new(),
+            frame_sizes: LabelsFrameSizes::new(),
+        }
+    }
+
+    /// Creates a disassembler for an assembled program, using its labels and
+    /// frame sizes.
+    pub fn from_program(program: &'a AssembledProgram) -> Self {
+        Self::new(&program.prom)
+            .with_labels(&program.labels)
+            .with_frame_sizes(&program.frame_sizes)
+    }
+
+    /// Names the PROM entries after `labels`, given as in
+    /// [`AssembledProgram::labels`].
+    pub fn with_labels(mut self, labels: &HashMap<String, (B32, u32, u32)>) -> Self {
+        for (name, (_, prom_index, _)) in labels {
+            self.labels.insert(*prom_index as usize, name.clone());
+        }
+        self
+    }
+
+    /// Annotates functions with the given frame sizes.
+    pub fn with_frame_sizes(mut self, frame_sizes: &LabelsFrameSizes) -> Self {
+        self.frame_sizes = frame_sizes.clone();
+        self
+    }
+
+    /// Returns the assembly text of the PROM.
+    pub fn disassemble(&self) -> Result<String, DisassemblerError> {
+        let labels = self.resolve_labels()?;
+
+        let mut out = String::new();
+        let mut index = 0;
+        while index < self.prom.len() {
+            let entry = &self.prom[index];
+            if let Some(label) = labels.get(&index) {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                if let Some(size) = self.frame_sizes.get(&entry.field_pc) {
+                    writeln!(out, "#[framesize(0x{size:x})]").expect("Writing to a String");
+                }
+                writeln!(out, "{label}:").expect("Writing to a String");
+            }
+
+            let (text, len) = self.instruction_text(index, &labels)?;
+            writeln!(out, "    {text}").expect("Writing to a String");
+            index += len;
+        }
+        Ok(out)
+    }
+
+    /// Returns the assembly text of each instruction of the PROM, without its
+    /// labels, along with its PROM index.
+    ///
+    /// Instructions spanning several PROM entries are listed once, at the
+    /// index of their first entry.
+    pub fn instructions(&self) -> Result<Vec<(usize, String)>, DisassemblerError> {
+        let labels = self.resolve_labels()?;
+
+        let mut instructions = Vec::new();
+        let mut index = 0;
+        while index < self.prom.len() {
+            let (text, len) = self.instruction_text(index, &labels)?;
+            instructions.push((index, text));
+            index += len;
+        }
+        Ok(instructions)
+    }
+
+    /// Returns the label names, by PROM index, of the entry point, the branch
+    /// targets and the provided labels.
+    fn resolve_labels(&self) -> Result<HashMap<usize, String>, DisassemblerError> {
+        let mut labels = self.labels.clone();
+        if !self.prom.is_empty() {
+            labels
+                .entry(0)
+                .or_insert_with(|| self.unique_label("_start".to_string()));
+        }
+
+        for index in 0..self.prom.len() {
+            if let Some(target) = self.branch_target(index)? {
+                labels
+                    .entry(target)
+                    .or_insert_with(|| self.unique_label(format!("L{target}")));
+            }
+        }
+        Ok(labels)
+    }
+
+    /// Returns `name`, suffixed if needed so as not to collide with a provided
+    /// label.
+    fn unique_label(&self, mut name: String) -> String {
+        while self.labels.values().any(|label| *label == name) {
+            name.push('_');
+        }
+        name
+    }
+
+    /// Returns the PROM index targeted by the instruction at `index`, if it is
+    /// a branch to an immediate target.
+    fn branch_target(&self, index: usize) -> Result<Option<usize>, DisassemblerError> {
+        let entry = &self.prom[index];
+        if !matches!(
+            entry.opcode(),
+            Opcode::Jumpi
+                | Opcode::Calli
+                | Opcode::Taili
+                | Opcode::Bnz
+                | Opcode::Beq
+                | Opcode::Bne
+                | Opcode::Blt
+                | Opcode::Bge
+        ) {
+            return Ok(None);
+        }
+
+        let [low, high, _] = entry.args();
+        let target = join_halves(low, high);
+        // The advice holds the PROM index of the target, when available.
+        if let Some((prom_index, _)) = entry.advice {
+            return Ok(Some(prom_index as usize));
+        }
+        self.prom
+            .locate(FieldPc::from(target))
+            .map(|(prom_index, _)| Some(prom_index as usize))
+            .ok_or(DisassemblerError::UnknownTarget { index, target })
+    }
+
+    /// Returns the text of the instruction at `index`, together with the number
+    /// of PROM entries it spans.
+    fn instruction_text(
+        &self,
+        index: usize,
+        labels: &HashMap<usize, String>,
+    ) -> Result<(String, usize), DisassemblerError> {
+        let entry = &self.prom[index];
+        let opcode = entry.opcode();
+        let [arg0, arg1, arg2] = entry.args().map(|arg| arg.val());
+        let bang = if entry.prover_only { "!" } else { "" };
+        let target = || -> Result<String, DisassemblerError> {
+            let target = self.branch_target(index)?.expect("Instruction is a branch");
+            Ok(labels[&target].clone())
+        };
+
+        let text = match opcode {
+            Opcode::Invalid if entry.instruction[0].val() >= CUSTOM_OPCODE_BASE => format!(
+                "CUSTOM{bang} #{:#x}, #{arg0}, #{arg1}, #{arg2}",
+                entry.instruction[0].val()
+            ),
+            Opcode::Invalid | Opcode::Bz => {
+                return Err(DisassemblerError::InvalidOpcode {
+                    index,
+                    opcode: entry.instruction[0].val(),
+                });
+            }
+            Opcode::Groestl256Compress
+            | Opcode::Groestl256Output
+            | Opcode::Sha256Compress
+            | Opcode::Blake3Compress
+            | Opcode::BigMulMod
+            | Opcode::Xor
+            | Opcode::Add
+            | Opcode::Sub
+            | Opcode::And
+            | Opcode::Or
+            | Opcode::Sll
+            | Opcode::Srl
+            | Opcode::Sra
+            | Opcode::Rol
+            | Opcode::Ror
+            | Opcode::Div
+            | Opcode::Divu
+            | Opcode::Rem
+            | Opcode::Remu
+            | Opcode::Mul
+            | Opcode::Mulu
+            | Opcode::Mulsu
+            | Opcode::B32Mul
+            | Opcode::B64Mul
+            | Opcode::B128Add
+            | Opcode::B128Mul
+            | Opcode::Add64
+            | Opcode::Sub64
+            | Opcode::Sle
+            | Opcode::Sleu
+            | Opcode::Slt
+            | Opcode::Sltu
+            | Opcode::Slt64 => format!("{}{bang} @{arg0}, @{arg1}, @{arg2}", opcode.mnemonic()),
+            Opcode::Xori
+            | Opcode::Andi
+            | Opcode::Srli
+            | Opcode::Slli
+            | Opcode::Srai
+            | Opcode::Roli
+            | Opcode::Rori
+            | Opcode::Addi
+            | Opcode::Muli
+            | Opcode::Ori
+            | Opcode::Slei
+            | Opcode::Sleiu
+            | Opcode::Slti
+            | Opcode::Sltiu
+            | Opcode::Lw
+            | Opcode::Sw
+            | Opcode::Lb
+            | Opcode::Lbu
+            | Opcode::Lh
+            | Opcode::Lhu
+            | Opcode::Sb
+            | Opcode::Sh
+            | Opcode::Memcpy
+            | Opcode::Memset => format!("{}{bang} @{arg0}, @{arg1}, #{arg2}", opcode.mnemonic()),
+            Opcode::Clz
+            | Opcode::Ctz
+            | Opcode::Popcnt
+            | Opcode::Bswap
+            | Opcode::Sextb
+            | Opcode::Sexth
+            | Opcode::B32Inv
+            | Opcode::B128Inv => {
+                format!("{}{bang} @{arg0}, @{arg1}", opcode.mnemonic())
+            }
+            Opcode::B32Muli => {
+                // The upper half of the immediate is held by the next entry.
+                let high = self
+                    .prom
+                    .as_slice()
+                    .get(index + 1)
+                    .filter(|next| next.opcode() == Opcode::B32Muli)
+                    .ok_or(DisassemblerError::MissingImmediate(index))?
+                    .instruction[1];
+                let imm = join_halves(B16::new(arg2), high).val();
+                return Ok((format!("B32_MULI{bang} @{arg0}, @{arg1}, #{imm}"), 2));
+            }
+            Opcode::Mvvw | Opcode::Mvvl | Opcode::Mvvh => {
+                format!("{}{bang} @{arg0}[{arg1}], @{arg2}", opcode.mnemonic())
+            }
+            Opcode::Mvih => format!("MVI.H{bang} @{arg0}[{arg1}], #{arg2}"),
+            Opcode::Mvib => format!("MVI.B{bang} @{arg0}[{arg1}], #{arg2}"),
+            Opcode::Cmov => {
+                let (src1, src2) = unpack_slot_pair(arg2);
+                format!("CMOV{bang} @{arg0}, @{arg1}, @{src1}, @{src2}")
+            }
+            Opcode::Ldi => {
+                let imm = join_halves(B16::new(arg1), B16::new(arg2)).val();
+                format!("LDI.W{bang} @{arg0}, #{imm}")
+            }
+            Opcode::Jumpi => format!("J {}", target()?),
+            Opcode::Jumpv => format!("J @{arg0}"),
+            Opcode::Taili | Opcode::Calli | Opcode::Bnz => {
+                format!("{} {}, @{arg2}", opcode.mnemonic(), target()?)
+            }
+            Opcode::Beq | Opcode::Bne | Opcode::Blt | Opcode::Bge => {
+                let (src1, src2) = unpack_slot_pair(arg2);
+                format!("{} {}, @{src1}, @{src2}", opcode.mnemonic(), target()?)
+            }
+            Opcode::Tailv | Opcode::Callv => format!("{} @{arg0}, @{arg1}", opcode.mnemonic()),
+            Opcode::Ret => "RET".to_string(),
+            Opcode::Halt => format!("HALT #{arg0}"),
+            Opcode::Alloci => format!("ALLOCI! @{arg0}, #{arg1}"),
+            Opcode::Allocv => format!("ALLOCV! @{arg0}, @{arg1}"),
+            Opcode::Alloch => format!("ALLOCH! @{arg0}, @{arg1}"),
+            Opcode::Free => format!("FREE! @{arg0}"),
+            Opcode::Commit => format!("COMMIT @{arg0}"),
+            Opcode::MerkleVerify => format!("MERKLE_VERIFY @{arg0}, @{arg1}, @{arg2}"),
+            Opcode::Fp => format!("FP{bang} @{arg0}, #{arg1}"),
+            Opcode::Nop => "NOP".to_string(),
+            Opcode::Abort => format!("ABORT @{arg0}, @{arg1}"),
+            Opcode::AssertEq => format!("ASSERT_EQ{bang} @{arg0}, @{arg1}"),
+            Opcode::AssertZero => format!("ASSERT_ZERO{bang} @{arg0}"),
+            Opcode::RangeCheck => format!("RANGE_CHECK{bang} @{arg0}, #{arg1}"),
+            Opcode::Ecall => format!("ECALL! #{arg0}, @{arg1}"),
+            Opcode::ReadAdvice => format!("READ_ADVICE! @{arg0}"),
+            Opcode::Debug => format!("DEBUG @{arg0}"),
+        };
+        Ok((text, 1))
+    }
+}
+
+/// Rebuilds a 32-bit value from its lower and upper 16-bit halves.
+fn join_halves(low: B16, high: B16) -> B32 {
+    B32::new(low.val() as u32 + ((high.val() as u32) << 16))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assembler;
+
+    #[test]
+    fn test_round_trip() {
+        let examples = [
+            include_str!("../../examples/opcodes.asm"),
+            include_str!("../../examples/fib.asm"),
+            include_str!("../../examples/branch_cmp.asm"),
+            include_str!("../../examples/collatz.asm"),
+            include_str!("../../examples/static_int_list_sum_ram.asm"),
+        ];
+
+        for code in examples {
+            let program = Assembler::from_code(code).unwrap();
+
+            // With the original labels and frame sizes.
+            let text = Disassembler::from_program(&program).disassemble().unwrap();
+            let reassembled = Assembler::from_code(&text).unwrap();
+            assert_eq!(reassembled.prom, program.prom, "{text}");
+            assert_eq!(reassembled.frame_sizes, program.frame_sizes);
+
+            // From the bare PROM, with synthetic labels and inferred frame sizes.
+            let text = Disassembler::new(&program.prom).disassemble().unwrap();
+            let reassembled = Assembler::from_code(&text).unwrap();
+            assert_eq!(reassembled.prom, program.prom, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_synthetic_labels() {
+        let code = "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #-1
+            loop:
+                B32_MULI @3, @2, #65537
+                BNZ loop, @2
+                RET";
+        let program = Assembler::from_code(code).unwrap();
+        let text = Disassembler::new(&program.prom).disassemble().unwrap();
+        assert_eq!(
+            text,
+            "_start:
+    LDI.W @2, #4294967295
+
+L1:
+    B32_MULI @3, @2, #65537
+    BNZ L1, @2
+    RET
+"
+        );
+    }
+
+    #[test]
+    fn test_invalid_opcode() {
+        let prom = ProgramRom::new(vec![crate::InterpreterInstruction::default()]);
+        assert!(matches!(
+            Disassembler::new(&prom).disassemble(),
+            Err(DisassemblerError::InvalidOpcode { index: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+use binius_m3::builder::B16;
+
+use super::context::EventContext;
+use crate::{
+    event::Event,
+    execution::{InterpreterChannels, InterpreterError, InterpreterException},
+};
+
+/// Event for ABORT.
+///
+/// Aborts the execution, reporting a guest-provided message to the host.
+///
+/// The message is a UTF-8 string of `FP[len]` bytes, stored in little-endian
+/// order in the consecutive VROM words starting at address `FP[ptr]`. Only its
+/// first [`MAX_ABORT_MESSAGE_LEN`] bytes are reported. An aborted execution
+/// cannot be proven, hence this event is never recorded in the trace.
+///
+/// Logic:
+///   1. Read the message of `FP[len]` bytes starting at address `FP[ptr]`.
+///   2. Stop the execution with an [`InterpreterException::Abort`].
+#[derive(Debug, Clone)]
+pub struct AbortEvent {}
+
+/// Maximum number of bytes of an `ABORT` message reported to the host.
+pub const MAX_ABORT_MESSAGE_LEN: u32 = 1 << 12;
+
+impl Event for AbortEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        ptr: B16,
+        len: B16,
+        _unused: B16,
+    ) -> Result<(), InterpreterError> {
+        let (pc, field_pc, _fp, _timestamp) = ctx.program_state();
+        let ptr_val = ctx.vrom().peek::<u32>(ctx.addr(ptr.val()))?;
+        let len_val = ctx.vrom().peek::<u32>(ctx.addr(len.val()))?;
+
+        let len_val = len_val.min(MAX_ABORT_MESSAGE_LEN);
+        let end = ptr_val.checked_add(len_val.div_ceil(4)).ok_or(
+            InterpreterError::AbortMessageOutOfBounds {
+                ptr: ptr_val,
+                len: len_val,
+            },
+        )?;
+        let mut bytes = Vec::with_capacity(len_val as usize);
+        for addr in ptr_val..end {
+            let word = ctx.vrom().peek::<u32>(addr)?;
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.truncate(len_val as usize);
+
+        Err(InterpreterError::Exception(InterpreterException::Abort {
+            pc,
+            field_pc,
+            message: String::from_utf8_lossy(&bytes).into_owned(),
+        }))
+    }
+
+    fn fire(&self, _channels: &mut InterpreterChannels) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        execution::{InterpreterError, InterpreterException},
+        isa::GenericISA,
+        Assembler, Memory, PetraTrace, ValueRom,
+    };
+
+    fn run(code: &str) -> InterpreterError {
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes)
+            .err()
+            .expect("The execution should abort")
+    }
+
+    #[test]
+    fn test_abort() {
+        // "oops!" is stored as two little-endian words.
+        let code = "#[framesize(0x8)]
+        _start:
+            FP @2, #4
+            LDI.W @3, #5
+            LDI.W @4, #1936748399
+            LDI.W @5, #33
+            ABORT @2, @3
+            RET
+        ";
+        match run(code).root() {
+            InterpreterError::Exception(InterpreterException::Abort { pc, message, .. }) => {
+                assert_eq!(*pc, 5);
+                assert_eq!(message, "oops!");
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_abort_hostile_message() {
+        // A message running past the end of the address space is rejected.
+        let code = "#[framesize(0x8)]
+        _start:
+            LDI.W @2, #0xffffffff
+            LDI.W @3, #8
+            ABORT @2, @3
+            RET
+        ";
+        match run(code).root() {
+            InterpreterError::AbortMessageOutOfBounds { ptr, len } => {
+                assert_eq!((*ptr, *len), (u32::MAX, 8));
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+
+        // Huge lengths are capped before reading the message, which then
+        // misses words.
+        let code = "#[framesize(0x8)]
+        _start:
+            FP @2, #4
+            LDI.W @3, #0xffffffff
+            LDI.W @4, #33
+            ABORT @2, @3
+            RET
+        ";
+        let err = run(code);
+        assert!(
+            matches!(err.root(), InterpreterError::MemoryError(_)),
+            "{err:?}"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use binius_m3::builder::B16;
+
+use super::context::EventContext;
+use crate::{
+    event::Event,
+    execution::{InterpreterChannels, InterpreterError},
+};
+
+/// Event for READ_ADVICE.
+///
+/// Prover-only instruction reading the next word of the advice tape, the
+/// private input stream provided to the emulator, into `FP[dst]`.
+///
+/// Like any other prover-only write, the word is a nondeterministic value for
+/// the verifier, which only sees it through the instructions later reading
+/// `FP[dst]`. This event is thus never recorded in the trace.
+///
+/// Logic:
+///   1. Read the next word of the advice tape.
+///   2. FP[dst] = word
+#[derive(Debug, Clone)]
+pub struct ReadAdviceEvent {}
+
+impl Event for ReadAdviceEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        _unused0: B16,
+        _unused1: B16,
+    ) -> Result<(), InterpreterError> {
+        let word = ctx.next_advice()?;
+        ctx.vrom_write(ctx.addr(dst.val()), word)?;
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, _channels: &mut InterpreterChannels) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{isa::GenericISA, Assembler, GenerationOptions, Memory, PetraTrace, ValueRom};
+
+    #[test]
+    fn test_read_advice() {
+        let code = "#[framesize(0x8)]
+        _start:
+            READ_ADVICE! @2
+            READ_ADVICE! @3
+            ADD @4, @2, @3
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let (trace, _) = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            program.frame_sizes,
+            GenerationOptions::default().advice_tape(vec![40, 2]),
+        )
+        .unwrap();
+        assert_eq!(trace.vrom().peek::<u32>(4).unwrap(), 42);
+        assert_eq!(trace.add.len(), 1);
+        assert_eq!(trace.instruction_counter.iter().sum::<u32>(), 2);
+    }
+}
@@ -17,7 +17,7 @@ impl Event for AllociEvent {
         _unused: B16,
     ) -> Result<(), InterpreterError> {
         let dst_addr = ctx.addr(dst.val());
-        let ptr = ctx.vrom_mut().allocate_new_frame(imm.val() as u32);
+        let ptr = ctx.vrom_mut().allocate_region(imm.val() as u32)?;
         ctx.vrom_write(dst_addr, ptr)?;
         ctx.incr_counters();
         Ok(())
@@ -38,7 +38,7 @@ impl Event for AllocvEvent {
     ) -> Result<(), InterpreterError> {
         let dst_addr = ctx.addr(dst.val());
         let src_val = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
-        let ptr = ctx.vrom_mut().allocate_new_frame(src_val);
+        let ptr = ctx.vrom_mut().allocate_region(src_val)?;
         ctx.vrom_write(dst_addr, ptr)?;
         ctx.incr_counters();
         Ok(())
@@ -46,3 +46,157 @@ impl Event for AllocvEvent {
 
     fn fire(&self, _channels: &mut InterpreterChannels) {}
 }
+
+/// Event for ALLOCH.
+///
+/// Prover-only hint allocating a block of `FP[size]` words in the heap region
+/// of the VROM, see [`Memory`](crate::Memory). Heap blocks are bump-allocated
+/// and never freed.
+///
+/// Logic:
+///   1. FP[dst] = address of a new heap block of FP[size] words
+#[derive(Debug, Clone)]
+pub struct AllochEvent {}
+
+impl Event for AllochEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        size: B16,
+        _unused: B16,
+    ) -> Result<(), InterpreterError> {
+        let dst_addr = ctx.addr(dst.val());
+        let size_val = ctx.vrom_read::<u32>(ctx.addr(size.val()))?;
+        let ptr = ctx.vrom_mut().allocate_heap_block(size_val)?;
+        ctx.vrom_write(dst_addr, ptr)?;
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, _channels: &mut InterpreterChannels) {}
+}
+
+/// Event for FREE.
+///
+/// Prover-only hint releasing the region allocated by `ALLOCI!` / `ALLOCV!` at
+/// the address held in `FP[ptr]`. It only has an effect when the heap regions
+/// are tracked, see
+/// [`Memory::with_heap_regions`](crate::Memory::with_heap_regions):
+/// the words of the region that were never written may then be handed out
+/// again by later allocations.
+///
+/// Logic:
+///   1. Release the region at FP[ptr].
+#[derive(Debug, Clone)]
+pub struct FreeEvent {}
+
+impl Event for FreeEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        ptr: B16,
+        _unused0: B16,
+        _unused1: B16,
+    ) -> Result<(), InterpreterError> {
+        let ptr_val = ctx.vrom_read::<u32>(ctx.addr(ptr.val()))?;
+        ctx.vrom_mut().free_region(ptr_val)?;
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, _channels: &mut InterpreterChannels) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        execution::InterpreterError,
+        isa::GenericISA,
+        memory::{MemoryError, HEAP_START},
+        Assembler, HeapStats, Memory, PetraTrace, ValueRom,
+    };
+
+    #[test]
+    fn test_alloch() {
+        let code = "#[framesize(0x8)]
+        _start:
+            LDI.W @2, #3
+            ALLOCH! @3, @2
+            ALLOCH! @4, @2
+            ALLOCI! @5, #4
+            MVV.W @3[2], @2
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, _) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+
+        // Heap blocks are bump-allocated in the heap region, away from the
+        // frames.
+        let vrom = trace.vrom();
+        assert_eq!(vrom.read::<u32>(3).unwrap(), HEAP_START);
+        assert_eq!(vrom.read::<u32>(4).unwrap(), HEAP_START + 4);
+        assert!(vrom.read::<u32>(5).unwrap() < HEAP_START);
+        assert_eq!(vrom.read::<u32>(HEAP_START + 2).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_free() {
+        let code = "#[framesize(0x8)]
+        _start:
+            ALLOCI! @2, #8
+            LDI.W @3, #7
+            MVV.W @2[0], @3
+            FREE! @2
+            ALLOCI! @4, #4
+            MVV.W @4[0], @3
+            RET
+        ";
+        let run = |code: &str, limit| {
+            let program = Assembler::from_code(code).unwrap();
+            let mut memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+            if let Some(limit) = limit {
+                memory = memory.with_heap_regions(limit);
+            }
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes)
+                .map(|(trace, _)| trace)
+        };
+
+        // Without tracking, `FREE!` has no effect and the second block comes
+        // after the first one.
+        let trace = run(code, None).unwrap();
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), 16);
+        assert_eq!(trace.vrom().heap_stats(), None);
+
+        // The unwritten words of the first block, from 9 to 15, are reused.
+        let trace = run(code, Some(None)).unwrap();
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), 12);
+        assert_eq!(trace.vrom_size(), 16);
+        assert_eq!(
+            trace.vrom().heap_stats(),
+            Some(HeapStats {
+                allocations: 2,
+                frees: 1,
+                live_words: 4,
+                peak_live_words: 8,
+                reclaimed_words: 6,
+            })
+        );
+
+        // Released blocks no longer count towards the limit.
+        run(code, Some(Some(8))).unwrap();
+        let err = run(&code.replace("FREE! @2", "NOP"), Some(Some(8))).unwrap_err();
+        match err.root() {
+            InterpreterError::MemoryError(MemoryError::HeapLimitExceeded(8)) => {}
+            err => panic!("Unexpected error: {err:?}"),
+        }
+
+        // Blocks can only be released once.
+        let double_free = code.replace("FREE! @2", "FREE! @2\n FREE! @2");
+        let err = run(&double_free, Some(None)).unwrap_err();
+        match err.root() {
+            InterpreterError::MemoryError(MemoryError::InvalidFree(8)) => {}
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+}
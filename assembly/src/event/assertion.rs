@@ -0,0 +1,213 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError, InterpreterException},
+    macros::fire_non_jump_event,
+    Opcode,
+};
+
+/// Returns the exception raised by the assertion `opcode` failing on `left`
+/// and `right`.
+fn assertion_failure(
+    ctx: &EventContext,
+    opcode: Opcode,
+    left: u32,
+    right: u32,
+) -> InterpreterError {
+    let (pc, field_pc, _fp, _timestamp) = ctx.program_state();
+    InterpreterError::Exception(InterpreterException::AssertionFailed {
+        pc,
+        field_pc,
+        opcode,
+        left,
+        right,
+    })
+}
+
+/// Event for ASSERT_EQ.
+///
+/// Asserts that two slots hold the same value, stopping the execution with an
+/// [`InterpreterException::AssertionFailed`] otherwise. A failed assertion
+/// cannot be proven.
+///
+/// Logic:
+///   1. Assert FP[src1] == FP[src2]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertEqEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub src1: u16,
+    pub src2: u16,
+    pub val: u32,
+}
+
+impl Event for AssertEqEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        src1: B16,
+        src2: B16,
+        _unused: B16,
+    ) -> Result<(), InterpreterError> {
+        let src1_val = ctx.vrom_read::<u32>(ctx.addr(src1.val()))?;
+        let src2_val = ctx.vrom_read::<u32>(ctx.addr(src2.val()))?;
+        if src1_val != src2_val {
+            return Err(assertion_failure(ctx, Opcode::AssertEq, src1_val, src2_val));
+        }
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                src1: src1.val(),
+                src2: src2.val(),
+                val: src1_val,
+            };
+
+            ctx.record(|trace| &mut trace.assert_eq, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+/// Event for ASSERT_ZERO.
+///
+/// Asserts that a slot holds zero, stopping the execution with an
+/// [`InterpreterException::AssertionFailed`] otherwise. A failed assertion
+/// cannot be proven.
+///
+/// Logic:
+///   1. Assert FP[src] == 0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertZeroEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub src: u16,
+}
+
+impl Event for AssertZeroEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        src: B16,
+        _unused0: B16,
+        _unused1: B16,
+    ) -> Result<(), InterpreterError> {
+        let src_val = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
+        if src_val != 0 {
+            return Err(assertion_failure(ctx, Opcode::AssertZero, src_val, 0));
+        }
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                src: src.val(),
+            };
+
+            ctx.record(|trace| &mut trace.assert_zero, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        execution::{InterpreterError, InterpreterException},
+        isa::GenericISA,
+        Assembler, Memory, Opcode, PetraTrace, ValueRom,
+    };
+
+    fn run(code: &str) -> Result<PetraTrace, InterpreterError> {
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).map(
+            |(trace, boundary_values)| {
+                trace.validate(boundary_values);
+                trace
+            },
+        )
+    }
+
+    #[test]
+    fn test_assertions() {
+        let trace = run("#[framesize(0x8)]
+        _start:
+            LDI.W @2, #42
+            LDI.W @3, #42
+            XOR @4, @2, @3
+            ASSERT_EQ @2, @3
+            ASSERT_ZERO @4
+            ASSERT_EQ! @2, @3
+            RET
+        ")
+        .unwrap();
+
+        // The prover-only assertion is checked, but not recorded.
+        assert_eq!(trace.assert_eq.len(), 1);
+        assert_eq!(trace.assert_zero.len(), 1);
+    }
+
+    #[test]
+    fn test_failed_assertions() {
+        for (code, expected_opcode, expected_left, expected_right) in [
+            (
+                "#[framesize(0x4)]
+                _start:
+                    LDI.W @2, #1
+                    LDI.W @3, #2
+                    ASSERT_EQ @2, @3
+                    RET
+                ",
+                Opcode::AssertEq,
+                1,
+                2,
+            ),
+            (
+                "#[framesize(0x4)]
+                _start:
+                    LDI.W @2, #5
+                    ASSERT_ZERO! @2
+                    RET
+                ",
+                Opcode::AssertZero,
+                5,
+                0,
+            ),
+        ] {
+            let err = run(code).unwrap_err();
+            match err.root() {
+                InterpreterError::Exception(InterpreterException::AssertionFailed {
+                    opcode,
+                    left,
+                    right,
+                    ..
+                }) => {
+                    assert_eq!(*opcode, expected_opcode);
+                    assert_eq!(*left, expected_left);
+                    assert_eq!(*right, expected_right);
+                }
+                err => panic!("Unexpected error {err:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, sha256::read_words, Event};
+use crate::{
+    bigint::{self, LIMBS},
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::fire_non_jump_event,
+};
+
+/// Event for BIGMULMOD.
+///
+/// Performs the modular multiplication `a * b mod m` of 256-bit integers, and
+/// writes the result to `dst`. The first input holds `a` followed by `b`, and
+/// the second input holds `m`. Each integer spans 8 words, least significant
+/// word first.
+///
+/// Both factors must be reduced modulo `m`, which in particular rejects a zero
+/// modulus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BigMulModEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    /// dst is the offset where the result is stored.
+    /// Since we are writing 8 words to memory, it needs to be 8-word aligned.
+    pub dst: u16,
+    pub dst_val: [u32; 8],
+    /// src1 is the offset where the two factors are stored.
+    /// Since we are reading 16 words from memory, it needs to be 16-word
+    /// aligned.
+    pub src1: u16,
+    pub src1_val: [u32; 16],
+    /// src2 is the offset where the modulus is stored.
+    /// Since we are reading 8 words from memory, it needs to be 8-word aligned.
+    pub src2: u16,
+    pub src2_val: [u32; 8],
+}
+
+impl BigMulModEvent {
+    /// Returns the factors `a` and `b`.
+    pub fn factors(&self) -> ([u32; 8], [u32; 8]) {
+        split_factors(&self.src1_val)
+    }
+}
+
+fn split_factors(src1_val: &[u32; 16]) -> ([u32; 8], [u32; 8]) {
+    let (a, b) = src1_val.split_at(LIMBS);
+    (
+        a.try_into().expect("a spans 8 words"),
+        b.try_into().expect("b spans 8 words"),
+    )
+}
+
+impl Event for BigMulModEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        src1: B16,
+        src2: B16,
+    ) -> Result<(), InterpreterError> {
+        let src1_val = read_words::<16>(ctx, src1)?;
+        let src2_val = read_words::<8>(ctx, src2)?;
+
+        let (a, b) = split_factors(&src1_val);
+        if !bigint::lt(&a, &src2_val) || !bigint::lt(&b, &src2_val) {
+            return Err(InterpreterError::InvalidInput);
+        }
+        let dst_val = bigint::mul_mod(&a, &b, &src2_val).ok_or(InterpreterError::InvalidInput)?;
+
+        ctx.vrom_write_slots(dst.val(), &dst_val)?;
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_val,
+                src1: src1.val(),
+                src1_val,
+                src2: src2.val(),
+                src2_val,
+            };
+
+            ctx.record(|trace| &mut trace.big_mul_mod, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, Memory, Opcode, PetraTrace,
+        ValueRom,
+    };
+
+    fn run_big_mul_mod(
+        a: [u32; 8],
+        b: [u32; 8],
+        m: [u32; 8],
+    ) -> Result<PetraTrace, InterpreterError> {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slots 2-7: Padding
+        // Slots 8-15: src2_val
+        // Slots 16-31: src1_val
+        // Slots 32-39: dst_val
+        let dst_offset = 32;
+        let src1_offset = 16;
+        let src2_offset = 8;
+        let mut init_values = vec![0; 32];
+        init_values[src1_offset..(8 + src1_offset)].copy_from_slice(&a);
+        init_values[(8 + src1_offset)..(16 + src1_offset)].copy_from_slice(&b);
+        init_values[src2_offset..(8 + src2_offset)].copy_from_slice(&m);
+
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        // Construct a simple program with the BigMulMod instruction
+        // 1. BIGMULMOD @output, @src1, @src2
+        // 2. RET
+        let zero = B16::ZERO;
+        let dst = B16::from(dst_offset as u16);
+        let src1 = B16::from(src1_offset as u16);
+        let src2 = B16::from(src2_offset as u16);
+        let instructions = vec![
+            [Opcode::BigMulMod.get_field_elt(), dst, src1, src2],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        // Set up frame sizes
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 40);
+
+        // Create the PROM
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        // Create an interpreter and run the program
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)?;
+
+        // Validate the trace (this consumes boundary_values)
+        trace.validate(boundary_values);
+        Ok(trace)
+    }
+
+    #[test]
+    fn test_big_mul_mod() {
+        // (m - 1)^2 = 1 mod m.
+        let m = [
+            0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+            0xffffffff,
+        ];
+        let mut m_minus_one = m;
+        m_minus_one[0] -= 1;
+
+        let trace = run_big_mul_mod(m_minus_one, m_minus_one, m)
+            .expect("Trace generation should not fail.");
+        let actual = (0..8)
+            .map(|i| trace.vrom().read::<u32>(32 + i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, bigint::ONE);
+        assert_eq!(trace.big_mul_mod.len(), 1);
+
+        // The factors must be reduced.
+        let err = run_big_mul_mod(m, bigint::ONE, m).unwrap_err();
+        assert!(matches!(err.root(), InterpreterError::InvalidInput));
+        let err = run_big_mul_mod(bigint::ZERO, bigint::ZERO, bigint::ZERO).unwrap_err();
+        assert!(matches!(err.root(), InterpreterError::InvalidInput));
+    }
+}
@@ -1,7 +1,8 @@
+use binius_field::Field;
 use binius_m3::builder::{B128, B16, B32};
 
 use super::BinaryOperation;
-use crate::macros::define_bin128_op_event;
+use crate::macros::{define_bin128_op_event, define_unary_op_event};
 use crate::{
     event::{context::EventContext, Event},
     execution::{FramePointer, InterpreterChannels, InterpreterError},
@@ -31,6 +32,20 @@ define_bin128_op_event!(
     *
 );
 
+define_unary_op_event!(
+    /// Event for B128_INV.
+    ///
+    /// Computes the 128-bit binary field inverse of a target address, mapping
+    /// zero to zero.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = __b128_inv(FP[src])
+    B128InvEvent,
+    b128_inv,
+    u128,
+    |val: u128| B128::new(val).invert_or_zero().val()
+);
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -158,9 +173,8 @@ mod tests {
         frames.insert(B32::ONE, 24);
 
         // Create an interpreter and run the program
-        let (trace, boundary_values) =
-            PetraTrace::generate(Box::new(GenericISA), memory, frames, HashMap::new())
-                .expect("Trace generation should not fail.");
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
 
         // Capture the final PC before boundary_values is moved
         let final_pc = boundary_values.final_pc;
@@ -198,4 +212,73 @@ mod tests {
         // The trace should have completed successfully
         assert_eq!(final_pc, B32::ZERO, "Program did not end correctly");
     }
+
+    #[test]
+    fn test_binary_field_inversions() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slot 2: b32 src_val
+        // Slot 3: b32 src_val (zero)
+        // Slots 4-7: b128 src_val
+        // Slots 8-11: b128 src_val (zero)
+        // Slots 12-13: b32 dst_val
+        // Slots 16-23: b128 dst_val
+        let b32_val = 0x1234_5678u32;
+        let b128_val = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210u128;
+        let mut init_values = vec![0, 0, b32_val, 0];
+        init_values.extend((0..4).map(|i| (b128_val >> (32 * i)) as u32));
+        init_values.extend([0; 4]);
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        let zero = B16::ZERO;
+        let instructions = vec![
+            [
+                Opcode::B32Inv.get_field_elt(),
+                B16::new(12),
+                B16::new(2),
+                zero,
+            ],
+            [
+                Opcode::B32Inv.get_field_elt(),
+                B16::new(13),
+                B16::new(3),
+                zero,
+            ],
+            [
+                Opcode::B128Inv.get_field_elt(),
+                B16::new(16),
+                B16::new(4),
+                zero,
+            ],
+            [
+                Opcode::B128Inv.get_field_elt(),
+                B16::new(20),
+                B16::new(8),
+                zero,
+            ],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 24);
+
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+        trace.validate(boundary_values);
+
+        let b32_inv = trace.vrom().read::<u32>(12).unwrap();
+        let b128_inv = trace.vrom().read::<u128>(16).unwrap();
+        assert_eq!(B32::new(b32_val) * B32::new(b32_inv), B32::ONE);
+        assert_eq!(B128::new(b128_val) * B128::new(b128_inv), B128::ONE);
+
+        // Zero is mapped to zero.
+        assert_eq!(trace.vrom().read::<u32>(13).unwrap(), 0);
+        assert_eq!(trace.vrom().read::<u128>(20).unwrap(), 0);
+
+        assert_eq!(trace.b32_inv.len(), 2);
+        assert_eq!(trace.b128_inv.len(), 2);
+    }
 }
@@ -1,13 +1,15 @@
 use binius_field::Field;
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::BinaryOperation;
 use crate::macros::{
-    define_bin32_imm_op_event, define_bin32_op_event, impl_32b_immediate_binary_operation,
+    define_bin32_imm_op_event, define_bin32_op_event, define_unary_op_event,
+    impl_32b_immediate_binary_operation,
 };
 use crate::{
     event::{binary_ops::*, context::EventContext, Event},
-    execution::{InterpreterError, G},
+    execution::{FieldPc, InterpreterChannels, InterpreterError},
     Opcode,
 };
 
@@ -95,15 +97,29 @@ define_bin32_op_event!(
     |a, b| a * b
 );
 
+define_unary_op_event!(
+    /// Event for B32_INV.
+    ///
+    /// Computes the 32-bit binary field inverse of a target address, mapping
+    /// zero to zero.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = __b32_inv(FP[src])
+    B32InvEvent,
+    b32_inv,
+    |val: u32| B32::new(val).invert_or_zero().val()
+);
+
 /// Event for B32_MULI.
 ///
 /// Performs a 32-bit MUL between a target address and an immediate.
 ///
 /// Logic:
 ///   1. FP[dst] = __b32_mul(FP[src], imm)
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct B32MuliEvent {
     pub timestamp: u32,
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub dst: u16,
@@ -157,7 +173,7 @@ impl Event for B32MuliEvent {
                 imm.val(),
             );
 
-            ctx.trace.b32_muli.push(event);
+            ctx.record(|trace| &mut trace.b32_muli, event);
         }
         // The instruction is over two rows in the PROM.
         ctx.incr_counters();
@@ -165,7 +181,7 @@ impl Event for B32MuliEvent {
         Ok(())
     }
 
-    fn fire(&self, channels: &mut crate::execution::InterpreterChannels) {
+    fn fire(&self, channels: &mut InterpreterChannels) {
         assert_eq!(
             self.dst_val,
             Self::operation(B32::new(self.src_val), self.imm.into()).val()
@@ -174,9 +190,12 @@ impl Event for B32MuliEvent {
         channels
             .state_channel
             .pull((self.pc, *self.fp, self.timestamp));
+        // B32_MULI spans two PROM entries.
+        let mut next_pc = FieldPc::from(self.pc);
+        next_pc.advance(2);
         channels
             .state_channel
-            .push((self.pc * G * G, *self.fp, self.timestamp));
+            .push((next_pc.field(), *self.fp, self.timestamp));
     }
 }
 
@@ -0,0 +1,89 @@
+use binius_m3::builder::{B16, B32, B64};
+
+use crate::{
+    event::{context::EventContext, Event},
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::define_bin64_op_event,
+};
+
+define_bin64_op_event!(
+    /// Event for B64_MUL.
+    ///
+    /// Performs a 64-bit binary field multiplication between two target
+    /// addresses.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = __b64_mul(FP[src1], FP[src2])
+    B64MulEvent,
+    b64_mul,
+    u64,
+    |a: u64, b: u64| (B64::new(a) * B64::new(b)).val()
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, Memory, Opcode, PetraTrace,
+        ValueRom,
+    };
+
+    #[test]
+    fn test_b64_mul() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slots 2-3: src1_val
+        // Slots 4-5: src2_val
+        // Slots 6-9: dst_val
+        let src1_val = 0x0123_4567_89ab_cdefu64;
+        let src2_val = 0xfedc_ba98_7654_3210u64;
+        let init_values = vec![
+            0,
+            0,
+            src1_val as u32,
+            (src1_val >> 32) as u32,
+            src2_val as u32,
+            (src2_val >> 32) as u32,
+        ];
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        let zero = B16::ZERO;
+        let instructions = vec![
+            [
+                Opcode::B64Mul.get_field_elt(),
+                B16::new(6),
+                B16::new(2),
+                B16::new(4),
+            ],
+            [
+                Opcode::B64Mul.get_field_elt(),
+                B16::new(8),
+                B16::new(6),
+                B16::new(4),
+            ],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 10);
+
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+        trace.validate(boundary_values);
+
+        let product = B64::new(src1_val) * B64::new(src2_val);
+        assert_eq!(trace.vrom().read::<u64>(6).unwrap(), product.val());
+        assert_eq!(
+            trace.vrom().read::<u64>(8).unwrap(),
+            (product * B64::new(src2_val)).val()
+        );
+        assert_eq!(trace.b64_mul.len(), 2);
+    }
+}
@@ -7,6 +7,7 @@ use crate::execution::{FramePointer, InterpreterError};
 
 pub(crate) mod b128;
 pub(crate) mod b32;
+pub(crate) mod b64;
 
 pub(crate) trait BinaryOperation: Sized + LeftOp + RightOp + OutputOp {
     fn operation(left: Self::Left, right: Self::Right) -> Self::Output;
@@ -0,0 +1,99 @@
+use binius_m3::builder::{B16, B32};
+
+use super::{context::EventContext, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::define_unary_op_event,
+};
+
+define_unary_op_event!(
+    /// Event for CLZ.
+    ///
+    /// Counts the leading zero bits of a value, i.e. FP[dst] = clz(FP[src]).
+    /// The count is 32 when the value is zero.
+    ClzEvent,
+    clz,
+    u32::leading_zeros
+);
+
+define_unary_op_event!(
+    /// Event for CTZ.
+    ///
+    /// Counts the trailing zero bits of a value, i.e. FP[dst] = ctz(FP[src]).
+    /// The count is 32 when the value is zero.
+    CtzEvent,
+    ctz,
+    u32::trailing_zeros
+);
+
+define_unary_op_event!(
+    /// Event for POPCNT.
+    ///
+    /// Counts the set bits of a value, i.e. FP[dst] = popcount(FP[src]).
+    PopcntEvent,
+    popcnt,
+    u32::count_ones
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, Memory, Opcode, PetraTrace,
+        ValueRom,
+    };
+
+    #[test]
+    fn test_bit_count() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slot 2: src_val
+        // Slot 3: src_val (zero)
+        // Slots 4-9: dst_val
+        let init_values = vec![0, 0, 0x00f0_0100, 0];
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        let zero = B16::ZERO;
+        let instructions = vec![
+            [Opcode::Clz.get_field_elt(), B16::new(4), B16::new(2), zero],
+            [Opcode::Ctz.get_field_elt(), B16::new(5), B16::new(2), zero],
+            [
+                Opcode::Popcnt.get_field_elt(),
+                B16::new(6),
+                B16::new(2),
+                zero,
+            ],
+            [Opcode::Clz.get_field_elt(), B16::new(7), B16::new(3), zero],
+            [Opcode::Ctz.get_field_elt(), B16::new(8), B16::new(3), zero],
+            [
+                Opcode::Popcnt.get_field_elt(),
+                B16::new(9),
+                B16::new(3),
+                zero,
+            ],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 10);
+
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+        trace.validate(boundary_values);
+
+        let results = (4..10)
+            .map(|slot| trace.vrom().read::<u32>(slot).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(results, [8, 8, 5, 32, 32, 0]);
+        assert_eq!(trace.clz.len(), 2);
+        assert_eq!(trace.ctz.len(), 2);
+        assert_eq!(trace.popcnt.len(), 2);
+    }
+}
@@ -0,0 +1,159 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, sha256::read_words, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::fire_non_jump_event,
+    util::blake3_compress,
+};
+
+/// Event for BLAKE3_COMPRESS.
+///
+/// Applies the BLAKE3 compression function and writes its 16 output words to
+/// `dst`. The first input holds the chaining value, the 64-bit block counter
+/// (low word first), the block length and the flags, and the second input
+/// holds the message block. Each word holds four little-endian bytes, as in
+/// the BLAKE3 specs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Blake3CompressEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    /// dst is the offset where the output is stored.
+    /// Since we are writing 16 words to memory, it needs to be 16-word
+    /// aligned.
+    pub dst: u16,
+    pub dst_val: [u32; 16],
+    /// src1 is the offset where the chaining value, counter, block length
+    /// and flags are stored.
+    /// Since we are reading 12 words from memory, it needs to be 16-word
+    /// aligned.
+    pub src1: u16,
+    pub src1_val: [u32; 12],
+    /// src2 is the offset where the message block is stored.
+    /// Since we are reading 16 words from memory, it needs to be 16-word
+    /// aligned.
+    pub src2: u16,
+    pub src2_val: [u32; 16],
+}
+
+impl Event for Blake3CompressEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        src1: B16,
+        src2: B16,
+    ) -> Result<(), InterpreterError> {
+        let src1_val = read_words::<12>(ctx, src1)?;
+        let src2_val = read_words::<16>(ctx, src2)?;
+
+        let dst_val = blake3_compress(&src1_val, &src2_val);
+
+        ctx.vrom_write_slots(dst.val(), &dst_val)?;
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_val,
+                src1: src1.val(),
+                src1_val,
+                src2: src2.val(),
+                src2_val,
+            };
+
+            ctx.record(|trace| &mut trace.blake3_compress, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, util::BLAKE3_IV, Memory, Opcode,
+        PetraTrace, ValueRom,
+    };
+
+    #[test]
+    fn test_blake3_compress() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slots 2-15: Padding
+        // Slots 16-27: src1_val
+        // Slots 28-31: Padding
+        // Slots 32-47: src2_val
+        // Slots 48-63: dst_val
+
+        // Single block of the message "abc", which is both the start and the end
+        // of the root chunk.
+        let mut input = [0u32; 12];
+        input[..8].copy_from_slice(&BLAKE3_IV);
+        input[10] = 3;
+        input[11] = 0b1011;
+        let mut block = [0u32; 16];
+        block[0] = u32::from_le_bytes(*b"abc\0");
+
+        let dst_offset = 48;
+        let src1_offset = 16;
+        let src2_offset = 32;
+        let mut init_values = vec![0; 48];
+        init_values[src1_offset..(12 + src1_offset)].copy_from_slice(&input);
+        init_values[src2_offset..(16 + src2_offset)].copy_from_slice(&block);
+
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        // Construct a simple program with the Blake3Compress instruction
+        // 1. BLAKE3_COMPRESS @output, @src1, @src2
+        // 2. RET
+        let zero = B16::ZERO;
+        let dst = B16::from(dst_offset as u16);
+        let src1 = B16::from(src1_offset as u16);
+        let src2 = B16::from(src2_offset as u16);
+        let instructions = vec![
+            [Opcode::Blake3Compress.get_field_elt(), dst, src1, src2],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        // Set up frame sizes
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 64);
+
+        // Create the PROM
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        // Create an interpreter and run the program
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+
+        // Validate the trace (this consumes boundary_values)
+        trace.validate(boundary_values);
+
+        // BLAKE3("abc")
+        let expected = [
+            0xacb33764, 0x33514638, 0x753bb6ff, 0xb58d3a27, 0x4658c548, 0x03db795d, 0x6c9c35fd,
+            0x859dbdd5,
+        ];
+        let actual = (0..8)
+            .map(|i| trace.vrom().read::<u32>(dst_offset + i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+        assert_eq!(trace.blake3_compress.len(), 1);
+    }
+}
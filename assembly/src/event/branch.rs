@@ -1,8 +1,9 @@
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
-use super::{context::EventContext, Event};
+use super::{context::EventContext, unpack_slot_pair, Event};
 use crate::{
-    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    execution::{FieldPc, FramePointer, InterpreterChannels, InterpreterError},
     macros::fire_non_jump_event,
     Opcode,
 };
@@ -14,13 +15,15 @@ use crate::{
 /// Logic:
 ///   1. if FP[cond] <> 0, then PC = target
 ///   2. if FP[cond] == 0, then increment PC
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BnzEvent {
     pub timestamp: u32,
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub cond: u16,
     pub cond_val: u32,
+    #[serde(with = "crate::util::serde_b32")]
     pub target: B32,
 }
 
@@ -50,11 +53,11 @@ impl Event for BnzEvent {
                 cond_val,
                 target,
             };
-            ctx.trace.bnz.push(event);
+            ctx.record(|trace| &mut trace.bnz, event);
             let advice = ctx
                 .advice
                 .ok_or(InterpreterError::MissingAdvice(Opcode::Bnz))?;
-            ctx.jump_to_u32(target, advice);
+            ctx.jump_to_u32(target, advice)?;
         } else {
             // We are not branching.
             let event = BzEvent {
@@ -65,7 +68,7 @@ impl Event for BnzEvent {
                 cond_val,
                 target,
             };
-            ctx.trace.bz.push(event);
+            ctx.record(|trace| &mut trace.bz, event);
             ctx.incr_counters();
         }
 
@@ -84,13 +87,15 @@ impl Event for BnzEvent {
 }
 
 // TODO: Maybe this could be just a NoopEvent?
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BzEvent {
     pub timestamp: u32,
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub cond: u16,
     pub cond_val: u32,
+    #[serde(with = "crate::util::serde_b32")]
     pub target: B32,
 }
 
@@ -101,7 +106,8 @@ impl Event for BzEvent {
         _target_high: B16,
         _cond: B16,
     ) -> Result<(), InterpreterError> {
-        unimplemented!("BzEvent generation is defined in BnzEvent::generate method");
+        // BzEvent generation is defined in BnzEvent::generate method.
+        Err(InterpreterError::InvalidOpcode)
     }
 
     fn fire(&self, channels: &mut InterpreterChannels) {
@@ -109,3 +115,143 @@ impl Event for BzEvent {
         fire_non_jump_event!(self, channels);
     }
 }
+
+/// Defines a branch-on-compare event and implements the [`Event`] trait for
+/// it.
+///
+/// It takes as argument the instruction, with optional Rust documentation, its
+/// opcode, its corresponding field name in the
+/// [`PetraTrace`](crate::execution::trace::PetraTrace) and the predicate on the
+/// two operands under which the branch is taken.
+///
+/// Unlike [`BnzEvent`], a single event type covers both outcomes of the
+/// branch.
+macro_rules! define_branch_cmp_event {
+    ($(#[$meta:meta])* $name:ident, $opcode:path, $trace_field:ident, $taken:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct $name {
+            pub timestamp: u32,
+            #[serde(with = "crate::util::serde_b32")]
+            pub pc: B32,
+            pub fp: FramePointer,
+            pub src1: u16,
+            pub src1_val: u32,
+            pub src2: u16,
+            pub src2_val: u32,
+            #[serde(with = "crate::util::serde_b32")]
+            pub target: B32,
+            /// Whether the branch is taken.
+            pub taken: bool,
+        }
+
+        impl $name {
+            /// Returns the program counter following this instruction.
+            pub fn next_pc(&self) -> B32 {
+                if self.taken {
+                    self.target
+                } else {
+                    FieldPc::from(self.pc).next().field()
+                }
+            }
+        }
+
+        impl Event for $name {
+            fn generate(
+                ctx: &mut EventContext,
+                target_low: B16,
+                target_high: B16,
+                srcs: B16,
+            ) -> Result<(), InterpreterError> {
+                let target =
+                    B32::new(target_low.val() as u32 + ((target_high.val() as u32) << 16));
+                let (src1, src2) = unpack_slot_pair(srcs.val());
+
+                let (pc, field_pc, fp, timestamp) = ctx.program_state();
+                if pc == 0 {
+                    return Err(InterpreterError::BadPc);
+                }
+
+                let src1_val = ctx.vrom_read::<u32>(ctx.addr(src1))?;
+                let src2_val = ctx.vrom_read::<u32>(ctx.addr(src2))?;
+                let taken = ($taken)(src1_val, src2_val);
+
+                let event = Self {
+                    timestamp,
+                    pc: field_pc,
+                    fp,
+                    src1,
+                    src1_val,
+                    src2,
+                    src2_val,
+                    target,
+                    taken,
+                };
+                ctx.record(|trace| &mut trace.$trace_field, event);
+
+                if taken {
+                    let advice = ctx
+                        .advice
+                        .ok_or(InterpreterError::MissingAdvice($opcode))?;
+                    ctx.jump_to_u32(target, advice)?;
+                } else {
+                    ctx.incr_counters();
+                }
+
+                Ok(())
+            }
+
+            fn fire(&self, channels: &mut InterpreterChannels) {
+                channels
+                    .state_channel
+                    .pull((self.pc, *self.fp, self.timestamp));
+                channels
+                    .state_channel
+                    .push((self.next_pc(), *self.fp, self.timestamp));
+            }
+        }
+    };
+}
+
+define_branch_cmp_event!(
+    /// Event for BEQ.
+    ///
+    /// Performs a branching to the target address if both arguments are equal.
+    BeqEvent,
+    Opcode::Beq,
+    beq,
+    |a: u32, b: u32| a == b
+);
+
+define_branch_cmp_event!(
+    /// Event for BNE.
+    ///
+    /// Performs a branching to the target address if the arguments differ.
+    BneEvent,
+    Opcode::Bne,
+    bne,
+    |a: u32, b: u32| a != b
+);
+
+define_branch_cmp_event!(
+    /// Event for BLT.
+    ///
+    /// Performs a branching to the target address if the first argument is
+    /// less than the second one, both being interpreted as signed integers.
+    BltEvent,
+    Opcode::Blt,
+    blt,
+    |a: u32, b: u32| (a as i32) < (b as i32)
+);
+
+define_branch_cmp_event!(
+    /// Event for BGE.
+    ///
+    /// Performs a branching to the target address if the first argument is
+    /// greater than or equal to the second one, both being interpreted as
+    /// signed integers.
+    BgeEvent,
+    Opcode::Bge,
+    bge,
+    |a: u32, b: u32| (a as i32) >= (b as i32)
+);
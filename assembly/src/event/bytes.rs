@@ -0,0 +1,129 @@
+use binius_m3::builder::{B16, B32};
+
+use super::{context::EventContext, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::define_unary_op_event,
+};
+
+define_unary_op_event!(
+    /// Event for BSWAP.
+    ///
+    /// Reverses the byte order of a value, i.e. FP[dst] = bswap(FP[src]).
+    BswapEvent,
+    bswap,
+    u32::swap_bytes
+);
+
+define_unary_op_event!(
+    /// Event for SEXT.B.
+    ///
+    /// Sign-extends the lowest byte of a value to 32 bits, i.e.
+    /// FP[dst] = FP[src] as i8 as i32.
+    SextbEvent,
+    sextb,
+    |val: u32| val as i8 as i32 as u32
+);
+
+define_unary_op_event!(
+    /// Event for SEXT.H.
+    ///
+    /// Sign-extends the lowest half-word of a value to 32 bits, i.e.
+    /// FP[dst] = FP[src] as i16 as i32.
+    SexthEvent,
+    sexth,
+    |val: u32| val as i16 as i32 as u32
+);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, Memory, Opcode, PetraTrace,
+        ValueRom,
+    };
+
+    #[test]
+    fn test_bswap_and_sign_extension() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slot 2: src_val (negative low byte and half-word)
+        // Slot 3: src_val (positive low byte and half-word)
+        // Slots 4-9: dst_val
+        let init_values = vec![0, 0, 0x1234_8081, 0xfedc_7f01];
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        let zero = B16::ZERO;
+        let instructions = vec![
+            [
+                Opcode::Bswap.get_field_elt(),
+                B16::new(4),
+                B16::new(2),
+                zero,
+            ],
+            [
+                Opcode::Sextb.get_field_elt(),
+                B16::new(5),
+                B16::new(2),
+                zero,
+            ],
+            [
+                Opcode::Sexth.get_field_elt(),
+                B16::new(6),
+                B16::new(2),
+                zero,
+            ],
+            [
+                Opcode::Bswap.get_field_elt(),
+                B16::new(7),
+                B16::new(3),
+                zero,
+            ],
+            [
+                Opcode::Sextb.get_field_elt(),
+                B16::new(8),
+                B16::new(3),
+                zero,
+            ],
+            [
+                Opcode::Sexth.get_field_elt(),
+                B16::new(9),
+                B16::new(3),
+                zero,
+            ],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 10);
+
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+        trace.validate(boundary_values);
+
+        let results = (4..10)
+            .map(|slot| trace.vrom().read::<u32>(slot).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            [
+                0x8180_3412,
+                0xffff_ff81,
+                0xffff_8081,
+                0x017f_dcfe,
+                0x0000_0001,
+                0x0000_7f01
+            ]
+        );
+        assert_eq!(trace.bswap.len(), 2);
+        assert_eq!(trace.sextb.len(), 2);
+        assert_eq!(trace.sexth.len(), 2);
+    }
+}
@@ -1,9 +1,10 @@
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::context::EventContext;
 use crate::{
     event::Event,
-    execution::{FramePointer, InterpreterChannels, InterpreterError, G},
+    execution::{FieldPc, FramePointer, InterpreterChannels, InterpreterError},
     Opcode,
 };
 
@@ -16,8 +17,14 @@ use crate::{
 ///   2. [FP[next_fp] + 1] = FP[1] (old frame pointer)
 ///   3. FP = FP[next_fp]
 ///   4. PC = target
-#[derive(Debug, Clone)]
+///
+/// The next frame is allocated beforehand, typically with `ALLOCI!`. It can't
+/// reuse the current frame, even when large enough: the VROM being write-once,
+/// the slots of the current frame keep their values, and its address may still
+/// be held by other frames, e.g. to receive a return value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TailiEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -52,7 +59,7 @@ impl Event for TailiEvent {
         let next_fp_val = ctx.setup_call_frame(next_fp)?;
 
         // Jump to the target, received as advice.
-        ctx.jump_to_u32(target, advice);
+        ctx.jump_to_u32(target, advice)?;
 
         // Perform a single packed write to store both u32 values at once.
         ctx.vrom_write::<u64>(*ctx.fp, pack)?;
@@ -68,7 +75,7 @@ impl Event for TailiEvent {
             old_fp_val: old_fp_val as u16,
         };
 
-        ctx.trace.taili.push(event);
+        ctx.record(|trace| &mut trace.taili, event);
         Ok(())
     }
 
@@ -91,8 +98,11 @@ impl Event for TailiEvent {
 ///   2. [FP[next_fp] + 1] = FP[1] (old frame pointer)
 ///   3. FP = FP[next_fp]
 ///   4. PC = FP[offset]
-#[derive(Debug, Clone)]
+///
+/// The next frame is allocated beforehand, like for [`TailiEvent`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TailvEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -125,7 +135,7 @@ impl Event for TailvEvent {
         let next_fp_val = ctx.setup_call_frame(next_fp)?;
 
         // Jump to the target,
-        ctx.jump_to(B32::new(target));
+        ctx.jump_to(B32::new(target))?;
 
         // Perform a single packed write to store both u32 values at once.
         ctx.vrom_write::<u64>(*ctx.fp, pack)?;
@@ -142,7 +152,7 @@ impl Event for TailvEvent {
             target,
         };
 
-        ctx.trace.tailv.push(event);
+        ctx.record(|trace| &mut trace.tailv, event);
         Ok(())
     }
 
@@ -166,8 +176,9 @@ impl Event for TailvEvent {
 ///   3. FP = FP[next_fp]
 ///   4. PC = target
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalliEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -195,9 +206,9 @@ impl Event for CalliEvent {
         let next_fp_val = ctx.setup_call_frame(next_fp)?;
 
         // Jump to the target, received as advice.
-        ctx.jump_to_u32(target, advice);
+        ctx.jump_to_u32(target, advice)?;
 
-        let return_pc = (field_pc * G).val();
+        let return_pc = FieldPc::from(field_pc).next().val();
 
         // Perform a single packed write to store both u32 values at once.
         ctx.vrom_write::<u64>(*ctx.fp, return_pc as u64 + ((*fp as u64) << 32))?;
@@ -211,7 +222,7 @@ impl Event for CalliEvent {
             next_fp_val,
         };
 
-        ctx.trace.calli.push(event);
+        ctx.record(|trace| &mut trace.calli, event);
         Ok(())
     }
 
@@ -234,8 +245,9 @@ impl Event for CalliEvent {
 ///   2. [FP[next_fp] + 1] = FP (old frame pointer)
 ///   3. FP = FP[next_fp]
 ///   4. PC = FP[offset]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CallvEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -262,9 +274,9 @@ impl Event for CallvEvent {
         let next_fp_val = ctx.setup_call_frame(next_fp)?;
 
         // Jump to the target,
-        ctx.jump_to(B32::new(target));
+        ctx.jump_to(B32::new(target))?;
 
-        let return_pc = (field_pc * G).val();
+        let return_pc = FieldPc::from(field_pc).next().val();
 
         // Perform a single packed write to store both u32 values at once.
         ctx.vrom_write::<u64>(*ctx.fp, return_pc as u64 + ((*fp as u64) << 32))?;
@@ -279,7 +291,7 @@ impl Event for CallvEvent {
             target,
         };
 
-        ctx.trace.callv.push(event);
+        ctx.record(|trace| &mut trace.callv, event);
         Ok(())
     }
 
@@ -316,7 +328,6 @@ mod tests {
         // Slot 3: Next_fp
         // Slot 4: unused_dst_addr (should never be written)
 
-        let ret_prom_index = 3;
         let ret_pc = 3;
         let target = G.pow(ret_pc - 1);
         let target_addr = 2.into();
@@ -370,12 +381,9 @@ mod tests {
         vrom.write(target_addr.val() as u32, target.val(), false)
             .unwrap();
 
-        let mut pc_field_to_index_pc = HashMap::new();
-        pc_field_to_index_pc.insert(target, (ret_prom_index, ret_pc as u32));
         let memory = Memory::new(prom, vrom);
-        let (trace, _) =
-            PetraTrace::generate(Box::new(GenericISA), memory, frames, pc_field_to_index_pc)
-                .expect("Trace generation should not fail.");
+        let (trace, _) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
 
         // Check that the next frame pointer was set correctly.
         assert_eq!(trace.vrom().read::<u32>(3).unwrap(), 6u32);
@@ -397,12 +405,8 @@ mod tests {
         // Slot 3: Next_fp
         // Slot 4: dst
 
-        let ret_prom_index = 3;
         let ret_pc = 3;
         let target = G.pow(ret_pc - 1);
-        let ldi_prom_index = 2;
-        let ldi_pc = 2;
-        let ldi = G.pow(ldi_pc - 1);
         let target_addr = 2.into();
         let next_fp_addr = 3.into();
 
@@ -441,13 +445,9 @@ mod tests {
         vrom.write(target_addr.val() as u32, target.val(), false)
             .unwrap();
 
-        let mut pc_field_to_index_pc = HashMap::new();
-        pc_field_to_index_pc.insert(target, (ret_prom_index, ret_pc as u32));
-        pc_field_to_index_pc.insert(ldi, (ldi_prom_index, ldi_pc as u32));
         let memory = Memory::new(prom, vrom);
-        let (trace, _) =
-            PetraTrace::generate(Box::new(GenericISA), memory, frames, pc_field_to_index_pc)
-                .expect("Trace generation should not fail.");
+        let (trace, _) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
 
         assert_eq!(trace.vrom().read::<u32>(3).unwrap(), 6u32);
         // Check that the load instruction was executed.
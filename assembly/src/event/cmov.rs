@@ -0,0 +1,134 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, unpack_slot_pair, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::fire_non_jump_event,
+};
+
+/// Event for CMOV.
+///
+/// Performs a branchless selection between two values.
+///
+/// Logic:
+///   1. if FP[cond] <> 0, then FP[dst] = FP[src1]
+///   2. if FP[cond] == 0, then FP[dst] = FP[src2]
+///
+/// Both source slots are packed in the third argument of the instruction,
+/// `src1` in its lower byte and `src2` in its upper one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmovEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub dst: u16,
+    pub dst_val: u32,
+    pub cond: u16,
+    pub cond_val: u32,
+    pub src1: u16,
+    pub src1_val: u32,
+    pub src2: u16,
+    pub src2_val: u32,
+}
+
+impl Event for CmovEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        cond: B16,
+        srcs: B16,
+    ) -> Result<(), InterpreterError> {
+        let (src1, src2) = unpack_slot_pair(srcs.val());
+
+        let cond_val = ctx.vrom_read::<u32>(ctx.addr(cond.val()))?;
+        let src1_val = ctx.vrom_read::<u32>(ctx.addr(src1))?;
+        let src2_val = ctx.vrom_read::<u32>(ctx.addr(src2))?;
+        let dst_val = if cond_val != 0 { src1_val } else { src2_val };
+        ctx.vrom_write(ctx.addr(dst.val()), dst_val)?;
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_val,
+                cond: cond.val(),
+                cond_val,
+                src1,
+                src1_val,
+                src2,
+                src2_val,
+            };
+
+            ctx.record(|trace| &mut trace.cmov, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, Memory, Opcode, PetraTrace,
+        ValueRom,
+    };
+
+    #[test]
+    fn test_cmov() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slot 2: cond (non-zero)
+        // Slot 3: cond (zero)
+        // Slot 4: src1_val
+        // Slot 5: src2_val
+        // Slot 6: dst_val (non-zero cond)
+        // Slot 7: dst_val (zero cond)
+        let init_values = vec![0, 0, 3, 0, 42, 7];
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        // Construct a simple program with two CMOV instructions
+        // 1. CMOV @6, @2, @4, @5
+        // 2. CMOV @7, @3, @4, @5
+        // 3. RET
+        let zero = B16::ZERO;
+        let srcs = B16::new(4 | (5 << 8));
+        let instructions = vec![
+            [Opcode::Cmov.get_field_elt(), B16::new(6), B16::new(2), srcs],
+            [Opcode::Cmov.get_field_elt(), B16::new(7), B16::new(3), srcs],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        // Set up frame sizes
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 8);
+
+        // Create the PROM
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        // Create an interpreter and run the program
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+
+        // Validate the trace (this consumes boundary_values)
+        trace.validate(boundary_values);
+
+        assert_eq!(trace.vrom().read::<u32>(6).unwrap(), 42);
+        assert_eq!(trace.vrom().read::<u32>(7).unwrap(), 7);
+        assert_eq!(trace.cmov.len(), 2);
+    }
+}
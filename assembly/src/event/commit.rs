@@ -0,0 +1,155 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, Event};
+use crate::{
+    execution::{FieldPc, FramePointer, InterpreterChannels, InterpreterError},
+    util::groestl256,
+};
+
+/// Event for COMMIT.
+///
+/// Appends the value of a slot to the public outputs of the execution, in the
+/// order of the COMMIT instructions. Like the RAM accesses, it increments the
+/// system timestamp, which orders the outputs.
+///
+/// Logic:
+///   1. outputs.push(FP[src])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub src: u16,
+    pub val: u32,
+    /// Position of the value among the public outputs.
+    pub index: u32,
+    /// System timestamp of the previous COMMIT, if any.
+    pub prev_timestamp: Option<u32>,
+}
+
+impl Event for CommitEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        src: B16,
+        _unused0: B16,
+        _unused1: B16,
+    ) -> Result<(), InterpreterError> {
+        let val = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
+
+        let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+        let event = Self {
+            pc: field_pc,
+            fp,
+            timestamp,
+            src: src.val(),
+            val,
+            index: ctx.trace.outputs.len() as u32,
+            prev_timestamp: ctx.trace.last_commit_timestamp.replace(timestamp),
+        };
+
+        ctx.trace.outputs.push(val);
+        ctx.record(|trace| &mut trace.commit, event);
+        ctx.incr_timestamp();
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        channels
+            .state_channel
+            .pull((self.pc, *self.fp, self.timestamp));
+        channels.state_channel.push((
+            FieldPc::from(self.pc).next().field(),
+            *self.fp,
+            self.timestamp + 1,
+        ));
+    }
+}
+
+/// Returns the commitment to the public outputs `outputs`, as a running
+/// Groestl-256 hash: starting from 32 zero bytes, each output is absorbed by
+/// hashing the current digest followed by the output in little-endian.
+///
+/// Hosts can compute it from the outputs reported by a proof, to compare them
+/// with the digest of an execution.
+pub fn commit_outputs(outputs: &[u32]) -> [u8; 32] {
+    outputs.iter().fold([0; 32], |digest, output| {
+        groestl256(&[digest.as_slice(), &output.to_le_bytes()].concat())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, Memory, PetraTrace, ValueRom};
+
+    #[test]
+    fn test_commit() {
+        let code = "#[framesize(0x10)]
+        _start:
+            LDI.W @3, #7
+            COMMIT @2
+            COMMIT @3
+            ALLOCI! @4, #4
+            MVV.W @4[2], @3
+            CALLI callee, @4
+            RET
+
+        #[framesize(0x4)]
+        callee:
+            ADDI @3, @2, #1
+            COMMIT @3
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0, 42]));
+
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+
+        // The outputs are committed in execution order, across frames.
+        assert_eq!(trace.outputs(), [42, 7, 8]);
+        assert_eq!(boundary_values.outputs, [42, 7, 8]);
+        assert_eq!(
+            trace
+                .commit
+                .iter()
+                .map(|event| event.index)
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        // Each COMMIT increments the timestamp, and refers to the previous one.
+        assert_eq!(
+            trace
+                .commit
+                .iter()
+                .map(|event| (event.timestamp, event.prev_timestamp))
+                .collect::<Vec<_>>(),
+            [(0, None), (1, Some(0)), (2, Some(1))]
+        );
+        assert_eq!(boundary_values.timestamp, 3);
+        assert_eq!(
+            boundary_values.output_commitment,
+            commit_outputs(&[42, 7, 8])
+        );
+        trace.validate(boundary_values);
+    }
+
+    #[test]
+    fn test_commit_outputs() {
+        assert_eq!(commit_outputs(&[]), [0; 32]);
+
+        // The commitment depends on the order of the outputs, and on each of
+        // them.
+        let outputs = commit_outputs(&[1, 2]);
+        assert_ne!(outputs, commit_outputs(&[2, 1]));
+        assert_ne!(outputs, commit_outputs(&[1, 2, 0]));
+        assert_eq!(
+            outputs,
+            groestl256(&[commit_outputs(&[1]).as_slice(), &2u32.to_le_bytes()].concat())
+        );
+    }
+}
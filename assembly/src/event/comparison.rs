@@ -1,10 +1,10 @@
 use binius_m3::builder::{B16, B32};
 
 use super::context::EventContext;
-use crate::macros::{define_bin32_imm_op_event, define_bin32_op_event};
+use crate::macros::{define_bin32_imm_op_event, define_bin32_op_event, define_bin64_op_event};
 use crate::{
-    event::binary_ops::*,
-    execution::{FramePointer, InterpreterError},
+    event::{binary_ops::*, Event},
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
 };
 
 // Note: The addition is checked thanks to the ADD32 table.
@@ -115,6 +115,20 @@ define_bin32_imm_op_event!(
     |a: B32, imm: B16| B32::new((a.val() <= imm.val() as u32) as u32)
 );
 
+define_bin64_op_event!(
+    /// Event for SLT.64.
+    ///
+    /// Performs an SLT between two signed 64-bit values, each stored in a
+    /// pair of slots.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = FP[src1..src1+2] < FP[src2..src2+2]
+    Slt64Event,
+    slt64,
+    u32,
+    |a: u64, b: u64| ((a as i64) < (b as i64)) as u32
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -489,4 +503,33 @@ mod tests {
             );
         }
     }
+
+    /// Tests for 64-bit signed comparisons
+    #[test]
+    fn test_slt64_operations() {
+        let test_cases: [(u64, u64, u32, &str); 5] = [
+            // (src1_val, src2_val, expected, description)
+            (5, 10, 1, "simple less than"),
+            (10, 5, 0, "simple greater than"),
+            (0x1_0000_0000, 0xFFFF_FFFF, 0, "high word decides"),
+            (u64::MAX, 0, 1, "signed -1 < 0"),
+            (i64::MAX as u64, i64::MIN as u64, 0, "signed MAX > MIN"),
+        ];
+
+        for (src1_val, src2_val, expected, desc) in test_cases {
+            let mut interpreter = Interpreter::default();
+            let mut trace = PetraTrace::default();
+            let mut ctx = EventContext::new(&mut interpreter, &mut trace);
+
+            // Set both words of each source, least significant first
+            ctx.set_vrom(2, src1_val as u32);
+            ctx.set_vrom(3, (src1_val >> 32) as u32);
+            ctx.set_vrom(4, src2_val as u32);
+            ctx.set_vrom(5, (src2_val >> 32) as u32);
+
+            Slt64Event::generate(&mut ctx, B16::new(6), B16::new(2), B16::new(4)).unwrap();
+            let event = get_last_event!(ctx, slt64);
+            assert_eq!(event.dst_val, expected, "SLT.64 failed for {desc}");
+        }
+    }
 }
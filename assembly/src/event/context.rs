@@ -1,9 +1,12 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
 
 use binius_m3::builder::{B16, B32};
 
 use crate::{
-    execution::{FramePointer, Interpreter, InterpreterError},
+    execution::{CustomEvent, FramePointer, Interpreter, InterpreterError},
     memory::{MemoryError, Ram, RamValueT, VromValueT},
     PetraTrace, ValueRom,
 };
@@ -25,13 +28,29 @@ pub struct EventContext<'a> {
     pub field_pc: B32,
     pub advice: Option<(u32, u32)>,
     pub prover_only: bool,
+    /// Frame size of the current function, if the slots accessed by the
+    /// instruction are checked to lie within its frame.
+    pub(crate) frame_size: Option<u16>,
+    /// First slot accessed beyond `frame_size`, if any.
+    pub(crate) out_of_frame: Cell<Option<u32>>,
 }
 
 impl EventContext<'_> {
     /// Computes a VROM address from a provided offset, by scaling the frame
     /// pointer accordingly.
+    ///
+    /// With frame checks enabled, an offset beyond the frame size of the
+    /// current function is recorded, to fail the instruction once executed.
     pub fn addr(&self, offset: impl Into<u32>) -> u32 {
-        *self.fp ^ offset.into()
+        let offset = offset.into();
+        if self
+            .frame_size
+            .is_some_and(|frame_size| offset >= frame_size as u32)
+            && self.out_of_frame.get().is_none()
+        {
+            self.out_of_frame.set(Some(offset));
+        }
+        *self.fp ^ offset
     }
 
     /// Outputs the current program state tuple, containing:
@@ -59,7 +78,7 @@ impl EventContext<'_> {
     where
         T: VromValueT,
     {
-        if self.prover_only {
+        if self.prover_only || !self.record_events {
             self.vrom().peek::<T>(addr)
         } else {
             self.vrom().read::<T>(addr)
@@ -79,7 +98,69 @@ impl EventContext<'_> {
     {
         // In prover-only mode, we don't need to check for deferred moves,
         // nor to record the access.
-        self.trace.vrom_write(addr, value, !self.prover_only)
+        let record = !self.prover_only && self.record_events;
+        self.trace.vrom_write(addr, value, record)
+    }
+
+    /// Reads the words at the consecutive VROM addresses from `addr` into
+    /// `words`, like as many [`EventContext::vrom_read`] of `u32`, but looking
+    /// each page of the VROM up once.
+    pub fn vrom_read_words(&self, addr: u32, words: &mut [u32]) -> Result<(), MemoryError> {
+        let record = !self.prover_only && self.record_events;
+        self.vrom().read_words(addr, words, record)
+    }
+
+    /// Writes `words` at the consecutive VROM addresses from `addr`, like as
+    /// many [`EventContext::vrom_write`], but looking each page of the VROM up
+    /// once.
+    pub fn vrom_write_words(&mut self, addr: u32, words: &[u32]) -> Result<(), MemoryError> {
+        let record = !self.prover_only && self.record_events;
+        self.vrom_mut().write_words(addr, words, record)
+    }
+
+    /// Reads the `N` consecutive frame slots from `slot`, in a batch when they
+    /// are contiguous in the VROM.
+    pub fn vrom_read_slots<const N: usize>(&self, slot: u16) -> Result<[u32; N], MemoryError> {
+        let mut words = [0; N];
+        match self.slots_addr(slot, N) {
+            Some(addr) => self.vrom_read_words(addr, &mut words)?,
+            None => {
+                for (i, word) in words.iter_mut().enumerate() {
+                    *word = self.vrom_read(self.addr(slot as u32 + i as u32))?;
+                }
+            }
+        }
+        Ok(words)
+    }
+
+    /// Writes `words` to the consecutive frame slots from `slot`, in a batch
+    /// when they are contiguous in the VROM.
+    pub fn vrom_write_slots(&mut self, slot: u16, words: &[u32]) -> Result<(), MemoryError> {
+        match self.slots_addr(slot, words.len()) {
+            Some(addr) => self.vrom_write_words(addr, words),
+            None => {
+                for (i, &word) in words.iter().enumerate() {
+                    self.vrom_write(self.addr(slot as u32 + i as u32), word)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the VROM address of the `len` consecutive frame slots from
+    /// `slot`, if they are contiguous in the VROM, i.e. if the frame pointer
+    /// has no bit set below the last of them.
+    ///
+    /// With frame checks enabled, the slots are checked like with
+    /// [`EventContext::addr`].
+    fn slots_addr(&self, slot: u16, len: usize) -> Option<u32> {
+        let end = slot as u32 + len as u32;
+        if self.frame_size.is_some() {
+            for offset in slot as u32..end {
+                self.addr(offset);
+            }
+        }
+        (*self.fp & (end.next_power_of_two() - 1) == 0).then(|| *self.fp | slot as u32)
     }
 
     pub const fn ram(&self) -> &Ram {
@@ -94,6 +175,9 @@ impl EventContext<'_> {
     where
         T: RamValueT,
     {
+        if !self.record_events {
+            return self.ram().peek(addr);
+        }
         self.ram_mut().read(addr, timestamp, pc)
     }
 
@@ -107,6 +191,9 @@ impl EventContext<'_> {
     where
         T: RamValueT,
     {
+        if !self.record_events {
+            return self.ram_mut().store(addr, value);
+        }
         self.ram_mut().write(addr, value, timestamp, pc)
     }
 
@@ -119,11 +206,43 @@ impl EventContext<'_> {
         }
     }
 
+    /// Records `event` in the events of the trace selected by `events`, unless
+    /// the interpreter runs without recording events, see
+    /// [`Interpreter::run_only`].
+    pub fn record<E>(&mut self, events: impl FnOnce(&mut PetraTrace) -> &mut Vec<E>, event: E) {
+        if self.record_events {
+            events(self.trace).push(event);
+        }
+    }
+
+    /// Records `event` in the trace under the custom `opcode`, unless in
+    /// prover-only mode or without recording events.
+    pub fn record_custom_event<E: CustomEvent>(&mut self, opcode: u16, event: E) {
+        if !self.prover_only && self.record_events {
+            self.trace
+                .custom
+                .entry(opcode)
+                .or_default()
+                .push(Box::new(event));
+        }
+    }
+
+    /// Increments the system timestamp. This must be called after every RAM
+    /// access.
+    pub fn incr_timestamp(&mut self) {
+        self.interpreter.incr_timestamp();
+    }
+
     /// Increments the underlying [`Interpreter`]'s PROM index.
     pub fn incr_prom_index(&mut self) {
         self.interpreter.incr_prom_index();
     }
 
+    /// Jumps to `target` in the PROM, given as a field element.
+    pub fn jump_to(&mut self, target: B32) -> Result<(), InterpreterError> {
+        self.interpreter.jump_to(self.trace.prom(), target)
+    }
+
     /// Helper method to update the [`FramePointer`]. It assumes that the next
     /// frame has already been allocated.
     ///
@@ -167,6 +286,8 @@ impl<'a> EventContext<'a> {
             field_pc: B32::ONE,
             advice: None,
             prover_only: false,
+            frame_size: None,
+            out_of_frame: Cell::new(None),
         }
     }
 
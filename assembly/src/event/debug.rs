@@ -0,0 +1,97 @@
+use binius_m3::builder::B16;
+use tracing::info;
+
+use super::context::EventContext;
+use crate::{
+    event::Event,
+    execution::{InterpreterChannels, InterpreterError},
+};
+
+/// Event for DEBUG.
+///
+/// Prover-only instruction logging the value of `FP[src]` through `tracing`,
+/// for print-style debugging of guest programs.
+///
+/// As a prover-only instruction, it takes no part in the proven program: it
+/// neither advances the field PC nor records an access to `FP[src]`. Adding
+/// or removing DEBUG instructions thus never changes what is proven. This
+/// event is never recorded in the trace.
+///
+/// Logic:
+///   1. Log FP[src], or that it is unset.
+#[derive(Debug, Clone)]
+pub struct DebugEvent {}
+
+impl Event for DebugEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        src: B16,
+        _unused0: B16,
+        _unused1: B16,
+    ) -> Result<(), InterpreterError> {
+        let (pc, field_pc, fp, _timestamp) = ctx.program_state();
+        let src = src.val();
+        let field_pc = field_pc.val();
+        match ctx.vrom_read::<u32>(ctx.addr(src)) {
+            Ok(value) => info!(
+                pc,
+                field_pc,
+                fp = *fp,
+                "DEBUG @{src} = {value} ({value:#x})"
+            ),
+            Err(_) => info!(pc, field_pc, fp = *fp, "DEBUG @{src} is unset"),
+        }
+
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, _channels: &mut InterpreterChannels) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{isa::GenericISA, Assembler, Memory, PetraTrace, ValueRom};
+
+    #[test]
+    fn test_debug() {
+        let with_debug = Assembler::from_code(
+            "#[framesize(0x8)]
+            _start:
+                LDI.W @2, #42
+                DEBUG @2
+                DEBUG @3 ;; Unset slot
+                XORI @3, @2, #1
+                RET
+            ",
+        )
+        .unwrap();
+        let without_debug = Assembler::from_code(
+            "#[framesize(0x8)]
+            _start:
+                LDI.W @2, #42
+                XORI @3, @2, #1
+                RET
+            ",
+        )
+        .unwrap();
+
+        // DEBUG instructions are stripped from the proven program.
+        let proven_instructions = |prom: &crate::ProgramRom| {
+            prom.iter()
+                .filter(|instr| !instr.prover_only)
+                .map(|instr| (instr.field_pc, instr.instruction))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            proven_instructions(&with_debug.prom),
+            proven_instructions(&without_debug.prom)
+        );
+
+        let memory = Memory::new(with_debug.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, with_debug.frame_sizes).unwrap();
+        assert_eq!(trace.vrom().read::<u32>(3).unwrap(), 43);
+        trace.validate(boundary_values);
+    }
+}
@@ -0,0 +1,83 @@
+use binius_m3::builder::B16;
+
+use super::context::EventContext;
+use crate::{
+    event::Event,
+    execution::{InterpreterChannels, InterpreterError},
+};
+
+/// Event for ECALL.
+///
+/// Prover-only instruction calling the host function registered under the
+/// identifier `id`, with the slot `base` of the current frame as argument.
+///
+/// The host function writes its results to VROM without recording any access,
+/// so that they are treated like any other nondeterministic value. This event
+/// is thus never recorded in the trace.
+///
+/// Logic:
+///   1. Look up the host function registered under `id`.
+///   2. Call it with the current context and `base`.
+#[derive(Debug, Clone)]
+pub struct EcallEvent {}
+
+impl Event for EcallEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        id: B16,
+        base: B16,
+        _unused: B16,
+    ) -> Result<(), InterpreterError> {
+        let host_fn = ctx
+            .host_fns
+            .get(id.val())
+            .ok_or(InterpreterError::UnknownHostFunction(id.val()))?;
+        host_fn(ctx, base.val())?;
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, _channels: &mut InterpreterChannels) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        execution::HostFunctions, isa::GenericISA, Assembler, GenerationOptions, Memory,
+        PetraTrace, ValueRom,
+    };
+
+    #[test]
+    fn test_ecall() {
+        // The host halves FP[3] into FP[4], which the guest checks.
+        let code = "#[framesize(0x8)]
+        _start:
+            LDI.W @3, #42
+            ECALL! #7, @3
+            ADD @5, @4, @4
+            XOR @6, @5, @3
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let mut host_fns = HostFunctions::new();
+        host_fns.register(7, |ctx, base| {
+            let value = ctx.vrom_read::<u32>(ctx.addr(base))?;
+            ctx.vrom_write(ctx.addr(base + 1), value / 2)?;
+            Ok(())
+        });
+        let (trace, _) = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            program.frame_sizes,
+            GenerationOptions::default().host_fns(host_fns),
+        )
+        .unwrap();
+
+        assert_eq!(trace.vrom().peek::<u32>(4).unwrap(), 21);
+        assert_eq!(trace.vrom().peek::<u32>(6).unwrap(), 0);
+        // The ECALL itself leaves no event behind.
+        assert_eq!(trace.instruction_counter.iter().sum::<u32>(), 4);
+    }
+}
@@ -1,4 +1,5 @@
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::context::EventContext;
 use crate::{
@@ -13,8 +14,9 @@ use crate::{
 ///
 /// Logic:
 ///   1. FP[dst] = FP + imm
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FpEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -44,7 +46,7 @@ impl Event for FpEvent {
                 imm: imm_val,
             };
 
-            ctx.trace.fp.push(event);
+            ctx.record(|trace| &mut trace.fp, event);
         }
 
         ctx.incr_counters();
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ValueRom;
+
+/// An event representing a single word moved by a MEMCPY or MEMSET
+/// instruction.
+///
+/// Unlike opcode events, this is not fired directly but is collected to
+/// generate proof gadgets. The words of a block move are chained through their
+/// remaining count: the word event of the `i`-th moved word copies
+/// `VROM[src_addr]` into `VROM[dst_addr]`, with `len - i - 1` words remaining
+/// after it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemWordGadgetEvent {
+    /// The destination address of the word.
+    pub dst_addr: u32,
+    /// The source address of the word.
+    pub src_addr: u32,
+    /// The source address increment between two consecutive words: 1 for
+    /// MEMCPY, 0 for MEMSET.
+    pub src_stride: u32,
+    /// The number of words remaining to be moved after this one.
+    pub remaining: u32,
+    /// The moved value.
+    pub val: u32,
+}
+
+impl MemWordGadgetEvent {
+    /// Returns the events of the `len` words moved into the VROM region
+    /// starting at `dst_ptr`, reading the `i`-th word at
+    /// `src_ptr + i * src_stride`.
+    ///
+    /// The moved values are read from `vrom`, which must hold the destination
+    /// words.
+    pub(crate) fn block(
+        vrom: &ValueRom,
+        dst_ptr: u32,
+        src_ptr: u32,
+        src_stride: u32,
+        len: u32,
+    ) -> impl Iterator<Item = Self> + '_ {
+        (0..len).map(move |i| {
+            let dst_addr = dst_ptr + i;
+            Self {
+                dst_addr,
+                src_addr: src_ptr + i * src_stride,
+                src_stride,
+                remaining: len - i - 1,
+                val: vrom
+                    .peek::<u32>(dst_addr)
+                    .expect("Moved words are set in the VROM"),
+            }
+        })
+    }
+}
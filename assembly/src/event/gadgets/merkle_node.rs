@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// An event representing a level of a Merkle path verified by a MERKLE_VERIFY
+/// instruction.
+///
+/// Unlike opcode events, this is not fired directly but is collected to
+/// generate proof gadgets. The levels of a path are chained through the
+/// address of their sibling and the index of their node: the level event of a
+/// node hashes it with the sibling stored 8 words after `addr`, and the parent
+/// node is hashed at the next level with the sibling stored 8 words further,
+/// using the index shifted right by one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleNodeGadgetEvent {
+    /// The address 8 words before the sibling: the address of the path for
+    /// the first level, and of the previous sibling otherwise.
+    pub addr: u32,
+    /// The index of the node at this level, whose lowest bit tells whether
+    /// the node is the right child of its parent.
+    pub index: u32,
+    /// The hashed node.
+    pub node: [u32; 8],
+    /// The sibling of the node.
+    pub sibling: [u32; 8],
+    /// The parent of the node and its sibling.
+    pub parent: [u32; 8],
+}
+
+impl MerkleNodeGadgetEvent {
+    /// Returns the address of the sibling.
+    pub const fn sibling_addr(&self) -> u32 {
+        self.addr + 8
+    }
+
+    /// Returns the left and right children of the parent.
+    pub const fn children(&self) -> ([u32; 8], [u32; 8]) {
+        if self.index & 1 == 0 {
+            (self.node, self.sibling)
+        } else {
+            (self.sibling, self.node)
+        }
+    }
+}
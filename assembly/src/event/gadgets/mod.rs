@@ -1 +1,3 @@
+pub(crate) mod mem_word;
+pub(crate) mod merkle_node;
 pub(crate) mod right_logic_shift;
@@ -1,11 +1,13 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::execution::PetraTrace;
 
 /// An event representing a right logical shift operation for gadget purposes.
 /// Unlike opcode events, this is not fired directly but is collected to
 /// generate proof gadgets.
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct RightLogicShiftGadgetEvent {
     /// The input value to be shifted
     pub input: u32,
@@ -24,6 +26,17 @@ impl RightLogicShiftGadgetEvent {
             output,
         }
     }
+
+    /// Creates the gadget event of an arithmetic right shift of `input`.
+    ///
+    /// A negative input is complemented, so that its arithmetic shift is the
+    /// complement of the logical shift of its complement.
+    pub(crate) fn arithmetic(input: u32, shift_amount: u32) -> Self {
+        let sign = (input >> 31) & 1 == 1;
+        let input = if sign { !input } else { input };
+        let output = input >> (shift_amount & 0x1F);
+        Self::new(input, shift_amount, output)
+    }
 }
 
 impl fmt::Debug for RightLogicShiftGadgetEvent {
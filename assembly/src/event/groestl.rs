@@ -1,12 +1,13 @@
 use binius_field::AESTowerField8b;
 use binius_hash::groestl::{GroestlShortImpl, GroestlShortInternal};
 use binius_m3::builder::{B16, B32, B8};
+use serde::{Deserialize, Serialize};
 
 use super::{context::EventContext, Event};
 use crate::{
     execution::{FramePointer, InterpreterChannels, InterpreterError},
     macros::fire_non_jump_event,
-    util::{bytes_to_u32, bytes_to_u64},
+    util::{bytes_to_u32, bytes_to_u64, GROESTL256_IV},
 };
 
 /// Event for GROESTL256_COMPRESS.
@@ -23,8 +24,9 @@ use crate::{
 /// output. So we need to change bases for the first input and the output:
 /// - go from binary to AES for the first input
 /// - go from AES to binary for the output.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Groestl256CompressEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -37,11 +39,13 @@ pub struct Groestl256CompressEvent {
     /// Since we are reading 16 words from memory, it needs to be 16-word
     /// aligned.
     pub src1: u16,
+    #[serde(with = "serde_big_array::BigArray")]
     pub src1_val: [u8; 64],
     /// src2 is the offset where the output is stored.
     /// Since we are reading 16 words from memory, it needs to be 16-word
     /// aligned.
     pub src2: u16,
+    #[serde(with = "serde_big_array::BigArray")]
     pub src2_val: [u8; 64],
 }
 
@@ -102,7 +106,7 @@ impl Event for Groestl256CompressEvent {
                 src2_val: src2_val.try_into().expect("src2_val should be 64 bytes"),
             };
 
-            ctx.trace.groestl_compress.push(event);
+            ctx.record(|trace| &mut trace.groestl_compress, event);
         }
         ctx.incr_counters();
         Ok(())
@@ -122,8 +126,9 @@ impl Event for Groestl256CompressEvent {
 /// already been changed from the AES basis to the bonary basis. Thus, we need
 /// to start by transposing the input and applying a change of basis from binary
 /// to AES).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Groestl256OutputEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -187,9 +192,7 @@ impl Event for Groestl256OutputEvent {
             .try_into()
             .expect("out_state_bytes is 64 bytes");
         let dst_val = bytes_to_u32(&dst_val);
-        for i in 0..8 {
-            ctx.vrom_write(ctx.addr(dst.val() + i), dst_val[i as usize])?;
-        }
+        ctx.vrom_write_slots(dst.val(), &dst_val)?;
 
         if !ctx.prover_only {
             let (_pc, field_pc, fp, timestamp) = ctx.program_state();
@@ -206,7 +209,7 @@ impl Event for Groestl256OutputEvent {
                 src2_val: src2_val.try_into().expect("src2_val is exactly 32 bytes"),
             };
 
-            ctx.trace.groestl_output.push(event);
+            ctx.record(|trace| &mut trace.groestl_output, event);
         }
         ctx.incr_counters();
         Ok(())
@@ -221,15 +224,16 @@ fn read_bytes<const N: usize>(
     ctx: &mut EventContext,
     src: B16,
 ) -> Result<Vec<u8>, InterpreterError> {
-    let mut src_val = Vec::with_capacity(N * 4);
-    for i in 0..N {
-        src_val.extend(
-            ctx.vrom_read::<u32>(ctx.addr(src.val() + i as u16))?
-                .to_le_bytes(),
-        );
-    }
+    let words = ctx.vrom_read_slots::<N>(src.val())?;
+    Ok(words.iter().flat_map(|word| word.to_le_bytes()).collect())
+}
 
-    Ok(src_val)
+/// Returns the initial chaining value of Groestl-256, transposed and in the
+/// binary basis, as expected by the first input of GROESTL256_COMPRESS.
+pub fn groestl256_initial_chaining_value() -> [u8; 64] {
+    transpose_in_bin(&GROESTL256_IV)
+        .try_into()
+        .expect("The chaining value is exactly 64 bytes")
 }
 
 pub fn transpose_in_aes(src_val: &[u8]) -> Vec<u8> {
@@ -310,9 +314,8 @@ mod tests {
         let memory = Memory::new(prom, vrom);
 
         // Create an interpreter and run the program
-        let (trace, boundary_values) =
-            PetraTrace::generate(Box::new(RecursionISA), memory, frames, HashMap::new())
-                .expect("Trace generation should not fail.");
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(RecursionISA), memory, frames)
+            .expect("Trace generation should not fail.");
 
         // Validate the trace (this consumes boundary_values)
         trace.validate(boundary_values);
@@ -385,9 +388,8 @@ mod tests {
         let memory = Memory::new(prom, vrom);
 
         // Create an interpreter and run the program
-        let (trace, boundary_values) =
-            PetraTrace::generate(Box::new(RecursionISA), memory, frames, HashMap::new())
-                .expect("Trace generation should not fail.");
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(RecursionISA), memory, frames)
+            .expect("Trace generation should not fail.");
 
         // Validate the trace (this consumes boundary_values)
         trace.validate(boundary_values);
@@ -496,9 +498,8 @@ mod tests {
         let memory = Memory::new(prom, vrom);
 
         // Create an interpreter and run the program
-        let (trace, boundary_values) =
-            PetraTrace::generate(Box::new(RecursionISA), memory, frames, HashMap::new())
-                .expect("Trace generation should not fail.");
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(RecursionISA), memory, frames)
+            .expect("Trace generation should not fail.");
 
         // Validate the trace (this consumes boundary_values)
         trace.validate(boundary_values);
@@ -0,0 +1,94 @@
+use binius_field::Field;
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, Event};
+use crate::execution::{FramePointer, InterpreterChannels, InterpreterError};
+
+/// Event for HALT.
+///
+/// Ends the execution with an exit code, whatever the depth of the current
+/// frame.
+///
+/// The exit code is carried by the FP of the final state, so that executions
+/// returning from their initial frame end with the exit code 0.
+///
+/// Logic:
+///   1. PC = 0
+///   2. FP = exit_code
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HaltEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub exit_code: u16,
+}
+
+impl Event for HaltEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        exit_code: B16,
+        _unused0: B16,
+        _unused1: B16,
+    ) -> Result<(), InterpreterError> {
+        let (_, field_pc, fp, timestamp) = ctx.program_state();
+
+        let halt_event = Self {
+            pc: field_pc,
+            fp,
+            timestamp,
+            exit_code: exit_code.val(),
+        };
+
+        ctx.jump_to(B32::ZERO)?;
+        ctx.set_fp(halt_event.exit_code as u32);
+
+        ctx.record(|trace| &mut trace.halt, halt_event);
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        channels
+            .state_channel
+            .pull((self.pc, *self.fp, self.timestamp));
+        channels
+            .state_channel
+            .push((B32::ZERO, self.exit_code as u32, self.timestamp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{isa::GenericISA, Assembler, Memory, PetraTrace, ValueRom};
+
+    #[test]
+    fn test_halt() {
+        let code = "#[framesize(0x4)]
+        _start:
+            ALLOCI! @3, #4
+            CALLI callee, @3
+            RET
+
+        #[framesize(0x4)]
+        callee:
+            HALT #42
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+
+        // The execution stops in the callee, before returning.
+        assert_eq!(boundary_values.exit_code, 42);
+        assert_eq!(
+            boundary_values.init_commitment,
+            ValueRom::commit_init_values(&[0, 0])
+        );
+        assert_eq!(trace.halt.len(), 1);
+        assert!(trace.ret.is_empty());
+        trace.validate(boundary_values);
+    }
+}
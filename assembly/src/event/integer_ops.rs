@@ -2,9 +2,12 @@ use core::fmt::Debug;
 use std::marker::PhantomData;
 
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::context::EventContext;
-use crate::macros::{define_bin32_imm_op_event, define_bin32_op_event, fire_non_jump_event};
+use crate::macros::{
+    define_bin32_imm_op_event, define_bin32_op_event, define_bin64_op_event, fire_non_jump_event,
+};
 use crate::{
     event::{binary_ops::*, Event},
     execution::{FramePointer, InterpreterChannels, InterpreterError},
@@ -38,8 +41,9 @@ define_bin32_op_event!(
 /// Event for MULI.
 ///
 /// Performs a MUL between a signed 32-bit integer and a 16-bit immediate.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MuliEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -77,7 +81,7 @@ impl Event for MuliEvent {
                 imm: imm_val,
             };
 
-            ctx.trace.muli.push(event);
+            ctx.record(|trace| &mut trace.muli, event);
         }
         ctx.incr_counters();
         Ok(())
@@ -96,8 +100,9 @@ impl Event for MuliEvent {
 ///
 /// Performs a MULU between two unsigned 32-bit integers. Returns a 64-bit
 /// result.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MuluEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -137,7 +142,7 @@ impl Event for MuluEvent {
                 src2_val,
             };
 
-            ctx.trace.mulu.push(mulu_event);
+            ctx.record(|trace| &mut trace.mulu, mulu_event);
         }
         ctx.incr_counters();
         Ok(())
@@ -219,7 +224,7 @@ macro_rules! impl_signed_mul_event {
                         _phantom: PhantomData,
                     };
 
-                    ctx.trace.$variant.push(event);
+                    ctx.record(|trace| &mut trace.$variant, event);
                 }
                 ctx.incr_counters();
                 Ok(())
@@ -239,8 +244,10 @@ impl_signed_mul_event!(mulsu, MulsuEvent, MulsuOp);
 /// Event for MUL or MULSU.
 ///
 /// Performs a MUL between two signed 32-bit integers.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct SignedMulEvent<SignedMulOperation> {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -270,6 +277,109 @@ define_bin32_op_event!(
     |a: B32, b: B32| B32::new(((a.val() as i32).wrapping_sub(b.val() as i32)) as u32)
 );
 
+// Division by zero and signed overflow follow the RISC-V M extension: they
+// never trap and instead return a well-defined value.
+define_bin32_op_event!(
+    /// Event for DIV.
+    ///
+    /// Performs a signed division between two target addresses, rounding
+    /// towards zero.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = FP[src1] / FP[src2]
+    ///
+    /// A division by zero returns -1, and `i32::MIN / -1` returns `i32::MIN`.
+    DivEvent,
+    div,
+    |a: B32, b: B32| {
+        let (a, b) = (a.val() as i32, b.val() as i32);
+        if b == 0 {
+            B32::new(u32::MAX)
+        } else {
+            B32::new(a.wrapping_div(b) as u32)
+        }
+    }
+);
+
+define_bin32_op_event!(
+    /// Event for DIVU.
+    ///
+    /// Performs an unsigned division between two target addresses.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = FP[src1] / FP[src2]
+    ///
+    /// A division by zero returns `u32::MAX`.
+    DivuEvent,
+    divu,
+    |a: B32, b: B32| B32::new(a.val().checked_div(b.val()).unwrap_or(u32::MAX))
+);
+
+define_bin32_op_event!(
+    /// Event for REM.
+    ///
+    /// Computes the signed remainder of the division between two target
+    /// addresses. The result has the sign of the dividend.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = FP[src1] % FP[src2]
+    ///
+    /// A division by zero returns the dividend, and `i32::MIN % -1` returns 0.
+    RemEvent,
+    rem,
+    |a: B32, b: B32| {
+        let (a, b) = (a.val() as i32, b.val() as i32);
+        if b == 0 {
+            B32::new(a as u32)
+        } else {
+            B32::new(a.wrapping_rem(b) as u32)
+        }
+    }
+);
+
+define_bin32_op_event!(
+    /// Event for REMU.
+    ///
+    /// Computes the unsigned remainder of the division between two target
+    /// addresses.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = FP[src1] % FP[src2]
+    ///
+    /// A division by zero returns the dividend.
+    RemuEvent,
+    remu,
+    |a: B32, b: B32| B32::new(a.val().checked_rem(b.val()).unwrap_or(a.val()))
+);
+
+define_bin64_op_event!(
+    /// Event for ADD.64.
+    ///
+    /// Performs an ADD between two 64-bit values, each stored in a pair of
+    /// slots.
+    ///
+    /// Logic:
+    ///   1. FP[dst..dst+2] = FP[src1..src1+2] + FP[src2..src2+2]
+    Add64Event,
+    add64,
+    u64,
+    u64::wrapping_add
+);
+
+define_bin64_op_event!(
+    /// Event for SUB.64.
+    ///
+    /// Performs a SUB between two 64-bit values, each stored in a pair of
+    /// slots.
+    ///
+    /// Logic:
+    ///   1. FP[dst..dst+2] = FP[src1..src1+2] - FP[src2..src2+2]
+    Sub64Event,
+    sub64,
+    u64,
+    u64::wrapping_sub
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,6 +499,123 @@ mod tests {
         }
     }
 
+    /// Tests for 64-bit Add and Sub operations
+    #[test]
+    fn test_64bit_operations() {
+        let test_cases: [(u64, u64, u64, u64, &str); 4] = [
+            // (src1_val, src2_val, add_expected, sub_expected, description)
+            (10, 20, 30, u64::MAX - 9, "simple values"),
+            (
+                0xFFFF_FFFF,
+                1,
+                0x1_0000_0000,
+                0xFFFF_FFFE,
+                "carry across words",
+            ),
+            (
+                0x1_0000_0000,
+                1,
+                0x1_0000_0001,
+                0xFFFF_FFFF,
+                "borrow across words",
+            ),
+            (u64::MAX, 1, 0, u64::MAX - 1, "overflow"),
+        ];
+
+        for (src1_val, src2_val, add_expected, sub_expected, desc) in test_cases {
+            let mut interpreter = Interpreter::default();
+            let mut trace = PetraTrace::default();
+            let mut ctx = EventContext::new(&mut interpreter, &mut trace);
+            let src1_offset = B16::new(2);
+            let src2_offset = B16::new(4);
+            let add_dst_offset = B16::new(6);
+            let sub_dst_offset = B16::new(8);
+
+            // Set both words of each source, least significant first
+            ctx.set_vrom(2, src1_val as u32);
+            ctx.set_vrom(3, (src1_val >> 32) as u32);
+            ctx.set_vrom(4, src2_val as u32);
+            ctx.set_vrom(5, (src2_val >> 32) as u32);
+
+            Add64Event::generate(&mut ctx, add_dst_offset, src1_offset, src2_offset).unwrap();
+            let event = get_last_event!(ctx, add64);
+            assert_eq!(event.dst_val, add_expected, "ADD.64 failed for {desc}");
+
+            Sub64Event::generate(&mut ctx, sub_dst_offset, src1_offset, src2_offset).unwrap();
+            let event = get_last_event!(ctx, sub64);
+            assert_eq!(event.dst_val, sub_expected, "SUB.64 failed for {desc}");
+        }
+    }
+
+    /// Tests for DIV, DIVU, REM and REMU operations
+    #[test]
+    fn test_div_rem_operations() {
+        let test_cases = [
+            // (src1_val, src2_val, (div, divu, rem, remu), description)
+            (20, 6, (3, 3, 2, 2), "simple division"),
+            (0, 7, (0, 0, 0, 0), "zero dividend"),
+            (
+                (-20i32) as u32,
+                6,
+                ((-3i32) as u32, 0x2AAAAAA7, (-2i32) as u32, 2),
+                "negative dividend",
+            ),
+            (
+                20,
+                (-6i32) as u32,
+                ((-3i32) as u32, 0, 2, 20),
+                "negative divisor",
+            ),
+            (42, 0, (u32::MAX, u32::MAX, 42, 42), "division by zero"),
+            (
+                0x80000000,
+                u32::MAX,
+                (0x80000000, 0, 0, 0x80000000),
+                "MIN_INT / -1 (overflow)",
+            ),
+        ];
+
+        for (src1_val, src2_val, (div, divu, rem, remu), desc) in test_cases {
+            let mut interpreter = Interpreter::default();
+            let mut trace = PetraTrace::default();
+            let mut ctx = EventContext::new(&mut interpreter, &mut trace);
+            let src1_offset = B16::new(2);
+            let src2_offset = B16::new(3);
+
+            // Set values in VROM at the computed addresses (FP ^ offset)
+            ctx.set_vrom(src1_offset.val(), src1_val);
+            ctx.set_vrom(src2_offset.val(), src2_val);
+
+            DivEvent::generate(&mut ctx, B16::new(4), src1_offset, src2_offset).unwrap();
+            assert_eq!(
+                get_last_event!(ctx, div).dst_val,
+                div,
+                "DIV failed for {desc}"
+            );
+
+            DivuEvent::generate(&mut ctx, B16::new(5), src1_offset, src2_offset).unwrap();
+            assert_eq!(
+                get_last_event!(ctx, divu).dst_val,
+                divu,
+                "DIVU failed for {desc}"
+            );
+
+            RemEvent::generate(&mut ctx, B16::new(6), src1_offset, src2_offset).unwrap();
+            assert_eq!(
+                get_last_event!(ctx, rem).dst_val,
+                rem,
+                "REM failed for {desc}"
+            );
+
+            RemuEvent::generate(&mut ctx, B16::new(7), src1_offset, src2_offset).unwrap();
+            assert_eq!(
+                get_last_event!(ctx, remu).dst_val,
+                remu,
+                "REMU failed for {desc}"
+            );
+        }
+    }
+
     /// Tests for Addi operations
     #[test]
     fn test_addi_operations() {
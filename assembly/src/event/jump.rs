@@ -1,4 +1,5 @@
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::{context::EventContext, Event};
 use crate::{
@@ -12,8 +13,9 @@ use crate::{
 ///
 /// Logic:
 /// 1. PC = FP[offset]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JumpvEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -32,7 +34,7 @@ impl Event for JumpvEvent {
 
         let (_pc, field_pc, fp, timestamp) = ctx.program_state();
 
-        ctx.jump_to(target.into());
+        ctx.jump_to(target.into())?;
 
         let event = Self {
             pc: field_pc,
@@ -42,7 +44,7 @@ impl Event for JumpvEvent {
             target,
         };
 
-        ctx.trace.jumpv.push(event);
+        ctx.record(|trace| &mut trace.jumpv, event);
         Ok(())
     }
 
@@ -62,11 +64,13 @@ impl Event for JumpvEvent {
 ///
 /// Logic:
 /// 1. PC = target
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JumpiEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
+    #[serde(with = "crate::util::serde_b32")]
     pub target: B32,
 }
 
@@ -84,7 +88,7 @@ impl Event for JumpiEvent {
             .advice
             .ok_or(InterpreterError::MissingAdvice(Opcode::Jumpi))?;
 
-        ctx.jump_to_u32(target, advice);
+        ctx.jump_to_u32(target, advice)?;
 
         let event = Self {
             pc: field_pc,
@@ -93,7 +97,7 @@ impl Event for JumpiEvent {
             target,
         };
 
-        ctx.trace.jumpi.push(event);
+        ctx.record(|trace| &mut trace.jumpi, event);
         Ok(())
     }
 
@@ -138,7 +138,7 @@ macro_rules! impl_event_for_binary_operation {
                 arg2: B16,
             ) -> Result<(), InterpreterError> {
                 Self::generate_event(ctx, arg0, arg1, arg2)?.map(|event| {
-                    ctx.trace.$trace_field.push(event);
+                    ctx.record(|trace| &mut trace.$trace_field, event);
                 });
                 Ok(())
             }
@@ -167,7 +167,7 @@ macro_rules! fire_non_jump_event {
             .state_channel
             .pull(($event.pc, *$event.fp, $event.timestamp));
         $channels.state_channel.push((
-            $event.pc * $crate::execution::G,
+            $crate::execution::FieldPc::from($event.pc).next().field(),
             *$event.fp,
             $event.timestamp,
         ));
@@ -282,9 +282,10 @@ macro_rules! impl_32b_immediate_binary_operation {
 macro_rules! define_bin32_op_event {
     ($(#[$meta:meta])* $name:ident, $trace_field:ident, $op_fn:expr) => {
         $(#[$meta])*
-        #[derive(Debug, Default, Clone)]
+        #[derive(Debug, Default, Clone, ::serde::Serialize, ::serde::Deserialize)]
         pub struct $name {
             pub timestamp: u32,
+            #[serde(with = "crate::util::serde_b32")]
             pub pc: B32,
             pub fp: FramePointer,
             pub dst: u16,
@@ -336,9 +337,10 @@ macro_rules! define_bin32_op_event {
 macro_rules! define_bin32_imm_op_event {
     ($(#[$meta:meta])* $name:ident, $trace_field:ident, $op_fn:expr) => {
         $(#[$meta])*
-        #[derive(Debug, Default, Clone)]
+        #[derive(Debug, Default, Clone, ::serde::Serialize, ::serde::Deserialize)]
         pub struct $name {
             pub timestamp: u32,
+            #[serde(with = "crate::util::serde_b32")]
             pub pc: B32,
             pub fp: FramePointer,
             pub dst: u16,
@@ -388,9 +390,10 @@ macro_rules! define_bin32_imm_op_event {
 macro_rules! define_bin128_op_event {
     ($(#[$meta:meta])* $name:ident, $trace_field:ident, $op:tt) => {
         $(#[$meta])*
-        #[derive(Debug, Default, Clone)]
+        #[derive(Debug, Default, Clone, ::serde::Serialize, ::serde::Deserialize)]
         pub struct $name {
             pub timestamp: u32,
+            #[serde(with = "crate::util::serde_b32")]
             pub pc: B32,
             pub fp: FramePointer,
             pub dst: u16,
@@ -444,7 +447,7 @@ macro_rules! define_bin128_op_event {
                         src2_val,
                     };
 
-                    ctx.trace.$trace_field.push(event);
+                    ctx.record(|trace| &mut trace.$trace_field, event);
 
                 }
                 ctx.incr_counters();
@@ -464,9 +467,166 @@ macro_rules! define_bin128_op_event {
     };
 }
 
+/// Defines an event and implements the [`Event`](crate::event::Event) trait for
+/// an instruction computing a value from a single source.
+///
+/// It takes as argument the instruction, with optional Rust documentation, its
+/// corresponding field name in the
+/// [`PetraTrace`](crate::execution::trace::PetraTrace) where such events are
+/// being logged, optionally the type of the source and destination values
+/// (defaulting to `u32`), and the operation to be applied on the instruction's
+/// input.
+///
+/// # Example
+///
+/// ```ignore
+/// define_unary_op_event!(
+///    /// Event for POPCNT.
+///    ///
+///    /// Counts the set bits of a value, i.e. FP[dst] = popcount(FP[src]).
+///    PopcntEvent,
+///    popcnt,
+///    u32::count_ones
+/// );
+/// ```
+macro_rules! define_unary_op_event {
+    ($(#[$meta:meta])* $name:ident, $trace_field:ident, $op_fn:expr) => {
+        $crate::macros::define_unary_op_event!($(#[$meta])* $name, $trace_field, u32, $op_fn);
+    };
+    ($(#[$meta:meta])* $name:ident, $trace_field:ident, $val_ty:ty, $op_fn:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct $name {
+            #[serde(with = "crate::util::serde_b32")]
+            pub pc: B32,
+            pub fp: FramePointer,
+            pub timestamp: u32,
+            pub dst: u16,
+            pub dst_val: $val_ty,
+            pub src: u16,
+            pub src_val: $val_ty,
+        }
+
+        impl Event for $name {
+            fn generate(
+                ctx: &mut EventContext,
+                dst: B16,
+                src: B16,
+                _unused: B16,
+            ) -> Result<(), InterpreterError> {
+                let src_val = ctx.vrom_read::<$val_ty>(ctx.addr(src.val()))?;
+                let dst_val: $val_ty = ($op_fn)(src_val);
+                ctx.vrom_write(ctx.addr(dst.val()), dst_val)?;
+
+                if !ctx.prover_only {
+                    let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+                    let event = Self {
+                        pc: field_pc,
+                        fp,
+                        timestamp,
+                        dst: dst.val(),
+                        dst_val,
+                        src: src.val(),
+                        src_val,
+                    };
+
+                    ctx.record(|trace| &mut trace.$trace_field, event);
+                }
+                ctx.incr_counters();
+                Ok(())
+            }
+
+            fn fire(&self, channels: &mut InterpreterChannels) {
+                $crate::macros::fire_non_jump_event!(self, channels);
+            }
+        }
+    };
+}
+
+/// Defines an event and implements the [`Event`](crate::event::Event) trait for
+/// a binary operation over 64-bit integers.
+///
+/// The sources are read from (and a 64-bit destination is written to) pairs of
+/// consecutive slots, least significant word first. It takes as argument the
+/// instruction, with optional Rust documentation, its corresponding field name
+/// in the [`PetraTrace`](crate::execution::trace::PetraTrace) where such events
+/// are being logged, the type of the destination value, and the operation to
+/// be applied on the instruction's inputs.
+///
+/// # Example
+///
+/// ```ignore
+/// define_bin64_op_event!(
+///    /// Event for ADD.64.
+///    ///
+///    /// Performs an ADD between two 64-bit target addresses.
+///    Add64Event,
+///    add64,
+///    u64,
+///    u64::wrapping_add
+/// );
+/// ```
+macro_rules! define_bin64_op_event {
+    ($(#[$meta:meta])* $name:ident, $trace_field:ident, $dst_ty:ty, $op_fn:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct $name {
+            #[serde(with = "crate::util::serde_b32")]
+            pub pc: B32,
+            pub fp: FramePointer,
+            pub timestamp: u32,
+            pub dst: u16,
+            pub dst_val: $dst_ty,
+            pub src1: u16,
+            pub src1_val: u64,
+            pub src2: u16,
+            pub src2_val: u64,
+        }
+
+        impl Event for $name {
+            fn generate(
+                ctx: &mut EventContext,
+                dst: B16,
+                src1: B16,
+                src2: B16,
+            ) -> Result<(), InterpreterError> {
+                let src1_val = ctx.vrom_read::<u64>(ctx.addr(src1.val()))?;
+                let src2_val = ctx.vrom_read::<u64>(ctx.addr(src2.val()))?;
+                let dst_val: $dst_ty = ($op_fn)(src1_val, src2_val);
+                ctx.vrom_write(ctx.addr(dst.val()), dst_val)?;
+
+                if !ctx.prover_only {
+                    let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+                    let event = Self {
+                        pc: field_pc,
+                        fp,
+                        timestamp,
+                        dst: dst.val(),
+                        dst_val,
+                        src1: src1.val(),
+                        src1_val,
+                        src2: src2.val(),
+                        src2_val,
+                    };
+
+                    ctx.record(|trace| &mut trace.$trace_field, event);
+                }
+                ctx.incr_counters();
+                Ok(())
+            }
+
+            fn fire(&self, channels: &mut InterpreterChannels) {
+                assert_eq!(self.dst_val, ($op_fn)(self.src1_val, self.src2_val));
+                $crate::macros::fire_non_jump_event!(self, channels);
+            }
+        }
+    };
+}
+
 // Re-export macros for use in other modules
 pub(crate) use {
-    define_bin128_op_event, define_bin32_imm_op_event, define_bin32_op_event, fire_non_jump_event,
+    define_bin128_op_event, define_bin32_imm_op_event, define_bin32_op_event,
+    define_bin64_op_event, define_unary_op_event, fire_non_jump_event,
     impl_32b_immediate_binary_operation, impl_binary_operation, impl_event_for_binary_operation,
     impl_immediate_binary_operation, impl_left_right_output_for_bin_op,
     impl_left_right_output_for_imm_bin_op,
@@ -0,0 +1,249 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, gadgets::mem_word::MemWordGadgetEvent};
+use crate::{
+    event::Event,
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::fire_non_jump_event,
+};
+
+/// Moves `len` words into the VROM region starting at `dst_ptr`, reading the
+/// `i`-th word at `src_ptr + i * src_stride`.
+///
+/// Every moved word is recorded as a [`MemWordGadgetEvent`], unless the
+/// instruction is prover-only or the gadget events are deferred.
+fn move_words(
+    ctx: &mut EventContext,
+    dst_ptr: u32,
+    src_ptr: u32,
+    src_stride: u32,
+    len: u16,
+) -> Result<(), InterpreterError> {
+    // Both regions must fit in the address space.
+    let len = len as u32;
+    dst_ptr
+        .checked_add(len)
+        .ok_or(InterpreterError::InvalidInput)?;
+    src_ptr
+        .checked_add(len * src_stride)
+        .ok_or(InterpreterError::InvalidInput)?;
+
+    // Copies between disjoint regions are moved in a batch, the others word by
+    // word, as a word written may be read again.
+    let batch = src_stride == 1 && (dst_ptr + len <= src_ptr || src_ptr + len <= dst_ptr);
+    let mut vals = Vec::new();
+    if batch {
+        vals.resize(len as usize, 0);
+        ctx.vrom_read_words(src_ptr, &mut vals)?;
+        ctx.vrom_write_words(dst_ptr, &vals)?;
+    }
+
+    for i in 0..len {
+        let dst_addr = dst_ptr + i;
+        let src_addr = src_ptr + i * src_stride;
+        let val = if batch {
+            vals[i as usize]
+        } else {
+            let val = ctx.vrom_read::<u32>(src_addr)?;
+            ctx.vrom_write(dst_addr, val)?;
+            val
+        };
+
+        if !ctx.prover_only && ctx.record_events && !ctx.defer_gadget_events {
+            ctx.trace.mem_word_gadget.push(MemWordGadgetEvent {
+                dst_addr,
+                src_addr,
+                src_stride,
+                remaining: len - i - 1,
+                val,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Event for MEMCPY.
+///
+/// Copies a block of words between two VROM regions, whose start addresses
+/// are held in frame slots.
+///
+/// Logic:
+///   1. for i in 0..len: VROM[FP[dst] + i] = VROM[FP[src] + i]
+///
+/// The source words must already be set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemcpyEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub dst: u16,
+    pub dst_ptr: u32,
+    pub src: u16,
+    pub src_ptr: u32,
+    pub len: u16,
+}
+
+impl Event for MemcpyEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        src: B16,
+        len: B16,
+    ) -> Result<(), InterpreterError> {
+        let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+
+        let dst_ptr = ctx.vrom_read::<u32>(ctx.addr(dst.val()))?;
+        let src_ptr = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
+        move_words(ctx, dst_ptr, src_ptr, 1, len.val())?;
+
+        if !ctx.prover_only {
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_ptr,
+                src: src.val(),
+                src_ptr,
+                len: len.val(),
+            };
+            ctx.record(|trace| &mut trace.memcpy, event);
+        }
+
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+/// Event for MEMSET.
+///
+/// Fills a block of words of a VROM region, whose start address is held in a
+/// frame slot, with the value of a frame slot.
+///
+/// Logic:
+///   1. for i in 0..len: VROM[FP[dst] + i] = FP[src]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemsetEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub dst: u16,
+    pub dst_ptr: u32,
+    pub src: u16,
+    pub len: u16,
+}
+
+impl Event for MemsetEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        src: B16,
+        len: B16,
+    ) -> Result<(), InterpreterError> {
+        let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+
+        let dst_ptr = ctx.vrom_read::<u32>(ctx.addr(dst.val()))?;
+        move_words(ctx, dst_ptr, ctx.addr(src.val()), 0, len.val())?;
+
+        if !ctx.prover_only {
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_ptr,
+                src: src.val(),
+                len: len.val(),
+            };
+            ctx.record(|trace| &mut trace.memset, event);
+        }
+
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, Memory, Opcode, PetraTrace,
+        ValueRom,
+    };
+
+    #[test]
+    fn test_memcpy_and_memset() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slot 2: Source pointer (slot 9)
+        // Slot 3: MEMCPY destination pointer (slot 12)
+        // Slot 4: MEMSET destination pointer (slot 16)
+        // Slot 5: MEMSET value
+        // Slots 8-10: Source words
+        // Slots 12-13: Copied words
+        // Slots 16-19: Set words
+        let init_values = vec![0, 0, 9, 12, 16, 0xabcd, 0, 0, 1, 2, 3];
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        let zero = B16::ZERO;
+        let instructions = vec![
+            [
+                Opcode::Memcpy.get_field_elt(),
+                B16::new(3),
+                B16::new(2),
+                B16::new(2),
+            ],
+            [
+                Opcode::Memset.get_field_elt(),
+                B16::new(4),
+                B16::new(5),
+                B16::new(4),
+            ],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 20);
+
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+        trace.validate(boundary_values);
+
+        let copied = (12..14)
+            .map(|slot| trace.vrom().read::<u32>(slot).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(copied, [2, 3]);
+        let set = (16..20)
+            .map(|slot| trace.vrom().read::<u32>(slot).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(set, [0xabcd; 4]);
+
+        assert_eq!(trace.memcpy.len(), 1);
+        assert_eq!(trace.memset.len(), 1);
+        let remaining = trace
+            .mem_word_gadget
+            .iter()
+            .map(|word| word.remaining)
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, [1, 0, 3, 2, 1, 0]);
+    }
+}
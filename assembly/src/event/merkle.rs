@@ -0,0 +1,241 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, gadgets::merkle_node::MerkleNodeGadgetEvent, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError, InterpreterException},
+    macros::fire_non_jump_event,
+    util::{bytes_to_u32, groestl256_compress_pair, u32_to_bytes},
+};
+
+/// Reads the 8-word digest starting at the VROM address `addr`.
+fn read_digest(ctx: &EventContext, addr: u32) -> Result<[u32; 8], InterpreterError> {
+    let mut digest = [0; 8];
+    ctx.vrom_read_words(addr, &mut digest)?;
+    Ok(digest)
+}
+
+/// Returns the parent of the Merkle tree nodes `left` and `right`.
+fn hash_children(left: &[u32; 8], right: &[u32; 8]) -> [u32; 8] {
+    let parent = groestl256_compress_pair(
+        &u32_to_bytes(left).try_into().expect("A digest is 32 bytes"),
+        &u32_to_bytes(right)
+            .try_into()
+            .expect("A digest is 32 bytes"),
+    );
+    bytes_to_u32(&parent)
+        .try_into()
+        .expect("A digest is 8 words")
+}
+
+/// Event for MERKLE_VERIFY.
+///
+/// Verifies that a leaf belongs to a Merkle tree of Groestl-256 digests, whose
+/// nodes hash their two children with [`groestl256_compress_pair`], stopping
+/// the execution with an [`InterpreterException::MerkleVerifyFailed`]
+/// otherwise. A failed verification cannot be proven.
+///
+/// The path is stored in the frame as the index of the leaf, followed by the
+/// depth of the tree, and by the siblings of the nodes from the leaf to the
+/// root, the `i`-th one starting at `FP[path + 8 * (i + 1)]`. The digests,
+/// each spanning 8 slots, and the path must be 8-slot aligned.
+///
+/// Logic:
+///   1. node = FP[leaf..leaf + 8], index = FP[path], depth = FP[path + 1]
+///   2. for i in 0..depth: sibling = FP[path + 8 * (i + 1)..path + 8 * (i + 2)]
+///      node = index & 1 == 0 ? H(node, sibling) : H(sibling, node) index =
+///      index >> 1
+///   3. Assert node == FP[root..root + 8] and index == 0
+///
+/// The levels of the path are recorded as [`MerkleNodeGadgetEvent`]s, even
+/// when the gadget events are deferred.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleVerifyEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub root: u16,
+    pub root_val: [u32; 8],
+    pub leaf: u16,
+    pub leaf_val: [u32; 8],
+    pub path: u16,
+    /// Index of the leaf among the leaves of the tree.
+    pub index: u32,
+    /// Number of siblings of the path.
+    pub depth: u32,
+}
+
+impl Event for MerkleVerifyEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        root: B16,
+        leaf: B16,
+        path: B16,
+    ) -> Result<(), InterpreterError> {
+        if [root, leaf, path].iter().any(|slot| slot.val() % 8 != 0) {
+            return Err(InterpreterError::InvalidInput);
+        }
+
+        let root_val = read_digest(ctx, ctx.addr(root.val()))?;
+        let leaf_val = read_digest(ctx, ctx.addr(leaf.val()))?;
+        let path_addr = ctx.addr(path.val());
+        let index = ctx.vrom_read::<u32>(path_addr)?;
+        let depth = ctx.vrom_read::<u32>(path_addr + 1)?;
+
+        let mut node = leaf_val;
+        let mut node_index = index;
+        let mut addr = path_addr;
+        let mut levels = Vec::new();
+        for _ in 0..depth {
+            let mut event = MerkleNodeGadgetEvent {
+                addr,
+                index: node_index,
+                node,
+                sibling: [0; 8],
+                parent: [0; 8],
+            };
+            event.sibling = read_digest(ctx, event.sibling_addr())?;
+            let (left, right) = event.children();
+            event.parent = hash_children(&left, &right);
+
+            node = event.parent;
+            node_index >>= 1;
+            addr = event.sibling_addr();
+            levels.push(event);
+        }
+
+        if node != root_val || node_index != 0 {
+            let (pc, field_pc, _fp, _timestamp) = ctx.program_state();
+            return Err(InterpreterError::Exception(
+                InterpreterException::MerkleVerifyFailed {
+                    pc,
+                    field_pc,
+                    index,
+                    depth,
+                },
+            ));
+        }
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                root: root.val(),
+                root_val,
+                leaf: leaf.val(),
+                leaf_val,
+                path: path.val(),
+                index,
+                depth,
+            };
+            ctx.record(|trace| &mut trace.merkle_verify, event);
+            for level in levels {
+                ctx.record(|trace| &mut trace.merkle_node_gadget, level);
+            }
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+/// Returns the root of the Merkle tree of Groestl-256 digests with the leaf
+/// `leaf` at `index` and the siblings `path`, from the leaf to the root, as
+/// verified by MERKLE_VERIFY.
+///
+/// Hosts can use it to compute the root expected by a guest.
+pub fn merkle_root(leaf: [u32; 8], index: u32, path: &[[u32; 8]]) -> [u32; 8] {
+    path.iter()
+        .enumerate()
+        .fold(leaf, |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                hash_children(&node, sibling)
+            } else {
+                hash_children(sibling, &node)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, util::groestl256, Assembler, Memory, PetraTrace, ValueRom};
+
+    const CODE: &str = "#[framesize(0x40)]
+        _start:
+            MERKLE_VERIFY @8, @16, @24
+            RET
+        ";
+
+    /// Returns the initial values of a frame holding the root `root`, the
+    /// leaf `leaf` and the path of index `index` with the siblings `path`.
+    fn init_values(root: [u32; 8], leaf: [u32; 8], index: u32, path: &[[u32; 8]]) -> Vec<u32> {
+        let mut values = vec![0; 8];
+        values.extend(root);
+        values.extend(leaf);
+        values.extend([index, path.len() as u32, 0, 0, 0, 0, 0, 0]);
+        values.extend(path.iter().flatten());
+        values
+    }
+
+    fn digest(data: &[u8]) -> [u32; 8] {
+        bytes_to_u32(&groestl256(data)).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_merkle_verify() {
+        let program = Assembler::from_code(CODE).unwrap();
+        let leaf = digest(b"leaf");
+        let path = [digest(b"a"), digest(b"b"), digest(b"c")];
+        let index = 0b101;
+        let root = merkle_root(leaf, index, &path);
+
+        let vrom = ValueRom::new_with_init_vals(&init_values(root, leaf, index, &path));
+        let memory = Memory::new(program.prom, vrom);
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+
+        // Each level hashes the node on the side given by its index bit.
+        assert_eq!(trace.merkle_verify.len(), 1);
+        let levels = &trace.merkle_node_gadget;
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0].parent, hash_children(&path[0], &leaf));
+        assert_eq!(levels[1].parent, hash_children(&levels[0].parent, &path[1]));
+        assert_eq!(levels[2].parent, root);
+        assert_eq!(
+            levels.iter().map(|level| level.index).collect::<Vec<_>>(),
+            [0b101, 0b10, 0b1]
+        );
+        trace.validate(boundary_values);
+    }
+
+    #[test]
+    fn test_merkle_verify_failure() {
+        let leaf = digest(b"leaf");
+        let path = [digest(b"a"), digest(b"b")];
+        let root = merkle_root(leaf, 2, &path);
+        let run = |root, index| {
+            let program = Assembler::from_code(CODE).unwrap();
+            let vrom = ValueRom::new_with_init_vals(&init_values(root, leaf, index, &path));
+            let memory = Memory::new(program.prom, vrom);
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).map(|_| ())
+        };
+        run(root, 2).unwrap();
+
+        // Another index, a wrong root, or an index beyond the leaves of the
+        // tree fail the verification.
+        for (root, index) in [(root, 1), (leaf, 2), (root, 2 + (1 << 2))] {
+            let err = run(root, index).unwrap_err();
+            assert!(matches!(
+                err.root(),
+                InterpreterError::Exception(InterpreterException::MerkleVerifyFailed { .. })
+            ));
+        }
+    }
+}
@@ -11,50 +11,97 @@
 
 use binius_m3::builder::B16;
 use context::EventContext;
-use tracing::instrument;
 
-use crate::{
-    execution::{InterpreterChannels, InterpreterError},
-    Opcode,
-};
+use crate::execution::{InterpreterChannels, InterpreterError};
 
+pub(crate) mod abort;
+pub(crate) mod advice;
 pub(crate) mod alloc;
+pub(crate) mod assertion;
+pub(crate) mod bigint;
 pub(crate) mod binary_ops;
+pub(crate) mod bit_count;
+pub(crate) mod blake3;
 pub(crate) mod branch;
+pub(crate) mod bytes;
 pub(crate) mod call;
+pub(crate) mod cmov;
+pub(crate) mod commit;
 pub(crate) mod comparison;
 pub(crate) mod context;
+pub(crate) mod debug;
+pub(crate) mod ecall;
 pub(crate) mod fp;
 pub(crate) mod gadgets;
 pub(crate) mod groestl;
+pub(crate) mod halt;
 pub(crate) mod integer_ops;
 pub(crate) mod jump;
 #[macro_use]
 pub(crate) mod macros;
+pub(crate) mod memcpy;
+pub(crate) mod merkle;
 pub(crate) mod mv;
+pub(crate) mod nop;
+pub(crate) mod ram;
+pub(crate) mod range_check;
 pub(crate) mod ret;
+pub(crate) mod sha256;
 pub(crate) mod shift;
 
-pub(crate) use binary_ops::{b128, b32};
+pub(crate) use binary_ops::{b128, b32, b64};
 
 // Re-exports
 pub use self::{
-    alloc::{AllociEvent, AllocvEvent},
-    b128::{B128AddEvent, B128MulEvent},
-    b32::{AndEvent, AndiEvent, B32MulEvent, B32MuliEvent, OrEvent, OriEvent, XorEvent, XoriEvent},
-    branch::{BnzEvent, BzEvent},
+    abort::{AbortEvent, MAX_ABORT_MESSAGE_LEN},
+    advice::ReadAdviceEvent,
+    alloc::{AllochEvent, AllociEvent, AllocvEvent, FreeEvent},
+    assertion::{AssertEqEvent, AssertZeroEvent},
+    b128::{B128AddEvent, B128InvEvent, B128MulEvent},
+    b32::{
+        AndEvent, AndiEvent, B32InvEvent, B32MulEvent, B32MuliEvent, OrEvent, OriEvent, XorEvent,
+        XoriEvent,
+    },
+    b64::B64MulEvent,
+    bigint::BigMulModEvent,
+    bit_count::{ClzEvent, CtzEvent, PopcntEvent},
+    blake3::Blake3CompressEvent,
+    branch::{BeqEvent, BgeEvent, BltEvent, BneEvent, BnzEvent, BzEvent},
+    bytes::{BswapEvent, SextbEvent, SexthEvent},
     call::{CalliEvent, CallvEvent, TailiEvent, TailvEvent},
+    cmov::CmovEvent,
+    commit::{commit_outputs, CommitEvent},
     comparison::{
-        SleEvent, SleiEvent, SleiuEvent, SleuEvent, SltEvent, SltiEvent, SltiuEvent, SltuEvent,
+        SleEvent, SleiEvent, SleiuEvent, SleuEvent, Slt64Event, SltEvent, SltiEvent, SltiuEvent,
+        SltuEvent,
     },
+    context::EventContext,
+    debug::DebugEvent,
+    ecall::EcallEvent,
     fp::FpEvent,
-    gadgets::right_logic_shift::RightLogicShiftGadgetEvent,
+    gadgets::{
+        mem_word::MemWordGadgetEvent, merkle_node::MerkleNodeGadgetEvent,
+        right_logic_shift::RightLogicShiftGadgetEvent,
+    },
     groestl::{Groestl256CompressEvent, Groestl256OutputEvent},
-    integer_ops::{AddEvent, AddiEvent, MulEvent, MuliEvent, MulsuEvent, MuluEvent, SubEvent},
+    halt::HaltEvent,
+    integer_ops::{
+        Add64Event, AddEvent, AddiEvent, DivEvent, DivuEvent, MulEvent, MuliEvent, MulsuEvent,
+        MuluEvent, RemEvent, RemuEvent, Sub64Event, SubEvent,
+    },
     jump::{JumpiEvent, JumpvEvent},
-    mv::{LdiEvent, MvihEvent, MvvlEvent, MvvwEvent},
+    memcpy::{MemcpyEvent, MemsetEvent},
+    merkle::{merkle_root, MerkleVerifyEvent},
+    mv::{LdiEvent, MvibEvent, MvihEvent, MvvhEvent, MvvlEvent, MvvwEvent},
+    nop::NopEvent,
+    ram::{LbEvent, LbuEvent, LhEvent, LhuEvent, LwEvent, SbEvent, ShEvent, SwEvent},
+    range_check::{RangeCheckEvent, RANGE_CHECK_WIDTHS},
     ret::RetEvent,
-    shift::{SllEvent, SlliEvent, SraEvent, SraiEvent, SrlEvent, SrliEvent},
+    sha256::Sha256CompressEvent,
+    shift::{
+        RolEvent, RoliEvent, RorEvent, RoriEvent, SllEvent, SlliEvent, SraEvent, SraiEvent,
+        SrlEvent, SrliEvent,
+    },
 };
 
 /// An `Event` represents an instruction that can be executed by the VM.
@@ -78,81 +125,30 @@ pub trait Event {
     fn fire(&self, channels: &mut InterpreterChannels);
 }
 
-impl Opcode {
-    /// Generates the appropriate event for this opcode.
-    #[instrument(
-        level = "trace",
-        skip(ctx),
-        fields(
-            arg0 = %format!("0x{:x}", arg0.val()),
-            arg1 = %format!("0x{:x}", arg1.val()),
-            arg2 = %format!("0x{:x}", arg2.val()),
-        )
-    )]
-    pub(crate) fn generate_event(
-        self,
-        ctx: &mut EventContext,
-        arg0: B16,
-        arg1: B16,
-        arg2: B16,
-    ) -> Result<(), InterpreterError> {
-        match self {
-            Opcode::Fp => fp::FpEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Groestl256Compress => {
-                groestl::Groestl256CompressEvent::generate(ctx, arg0, arg1, arg2)
-            }
-            Opcode::Groestl256Output => {
-                groestl::Groestl256OutputEvent::generate(ctx, arg0, arg1, arg2)
-            }
-            Opcode::Bnz => BnzEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Bz => {
-                unreachable!("BzEvent can only be triggered through the Bnz instruction.")
-            }
-            Opcode::Jumpi => jump::JumpiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Jumpv => jump::JumpvEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Xori => b32::XoriEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Xor => b32::XorEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Slli => shift::SlliEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Srli => shift::SrliEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Srai => shift::SraiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sll => shift::SllEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Srl => shift::SrlEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sra => shift::SraEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Addi => integer_ops::AddiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Add => integer_ops::AddEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sle => comparison::SleEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Slei => comparison::SleiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sleu => comparison::SleuEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sleiu => comparison::SleiuEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Slt => comparison::SltEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Slti => comparison::SltiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sltu => comparison::SltuEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sltiu => comparison::SltiuEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Muli => integer_ops::MuliEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Mulu => integer_ops::MuluEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Mulsu => integer_ops::MulsuEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Mul => integer_ops::MulEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Sub => integer_ops::SubEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Ret => ret::RetEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Taili => call::TailiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Tailv => call::TailvEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Calli => call::CalliEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Callv => call::CallvEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::And => b32::AndEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Andi => b32::AndiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Or => b32::OrEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Ori => b32::OriEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Mvih => mv::MvihEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Mvvw => mv::MvvwEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Mvvl => mv::MvvlEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Ldi => mv::LdiEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::B32Mul => b32::B32MulEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::B32Muli => b32::B32MuliEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::B128Add => b128::B128AddEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::B128Mul => b128::B128MulEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Alloci => alloc::AllociEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Allocv => alloc::AllocvEvent::generate(ctx, arg0, arg1, arg2),
-            Opcode::Invalid => Err(InterpreterError::InvalidOpcode),
-        }
-    }
+/// An event recorded in a field of the [`PetraTrace`](crate::PetraTrace).
+///
+/// This trait is implemented by the events of every opcode of the VM, as well
+/// as by the gadget events, so that their records can be retrieved generically
+/// with [`PetraTrace::events_of`](crate::PetraTrace::events_of).
+pub trait TraceEvent: Sized {
+    /// Returns the events of this type recorded in `trace`.
+    fn events(trace: &crate::PetraTrace) -> &[Self];
+}
+
+/// Packs two operand slots into a single instruction argument, `low` in the
+/// lower byte and `high` in the upper one, for instructions with more operands
+/// than arguments.
+///
+/// Returns `None` if one of the slots does not fit in 8 bits.
+pub(crate) fn pack_slot_pair(low: u32, high: u32) -> Option<u16> {
+    let low = u8::try_from(low).ok()?;
+    let high = u8::try_from(high).ok()?;
+    Some(u16::from_le_bytes([low, high]))
+}
+
+/// Splits an instruction argument into the two operand slots packed by
+/// [`pack_slot_pair`].
+pub(crate) const fn unpack_slot_pair(arg: u16) -> (u16, u16) {
+    let [low, high] = arg.to_le_bytes();
+    (low as u16, high as u16)
 }
@@ -1,4 +1,5 @@
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::context::EventContext;
 use crate::{
@@ -29,7 +30,7 @@ macro_rules! impl_mv_event {
             ) -> Result<(), InterpreterError> {
                 let opt_event = Self::generate_event(ctx, arg0, arg1, arg2)?;
                 if let Some(event) = opt_event {
-                    ctx.trace.$trace_field.push(event);
+                    ctx.record(|trace| &mut trace.$trace_field, event);
                 }
 
                 Ok(())
@@ -48,8 +49,9 @@ macro_rules! impl_mv_event {
 ///
 /// Logic:
 ///   1. VROM[FP[dst] + offset] = FP[src]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MvvwEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -142,8 +144,9 @@ impl_mv_event!(MvvwEvent, mvvw);
 ///
 /// Logic:
 ///   1. VROM128[FP[dst] + offset] = FP128[src]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MvvlEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -234,6 +237,67 @@ impl MvvlEvent {
 
 impl_mv_event!(MvvlEvent, mvvl);
 
+/// Event for MVV.H.
+///
+/// Performs a MOVE of the lowest 2 bytes of a value between VROM addresses,
+/// zero-extending to 32-bits.
+///
+/// Unlike MVV.W, the source value must be set, as it cannot be recovered from
+/// the destination.
+///
+/// Logic:
+///   1. VROM[FP[dst] + offset] = ZeroExtend(FP[src] & 0xFFFF)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MvvhEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub dst: u16,
+    pub dst_addr: u32,
+    pub src: u16,
+    pub src_val: u32,
+    pub offset: u16,
+}
+
+impl MvvhEvent {
+    /// Returns the half-word moved to the destination.
+    pub const fn dst_val(&self) -> u32 {
+        self.src_val & 0xFFFF
+    }
+
+    pub(crate) fn generate_event(
+        ctx: &mut EventContext,
+        dst: B16,
+        offset: B16,
+        src: B16,
+    ) -> Result<Option<Self>, InterpreterError> {
+        let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+
+        let src_val = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
+        let dst_addr = ctx.vrom_read::<u32>(ctx.addr(dst.val()))?;
+
+        execute_mv(ctx, dst_addr ^ offset.val() as u32, src_val & 0xFFFF)?;
+
+        if ctx.prover_only {
+            Ok(None)
+        } else {
+            Ok(Some(Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_addr,
+                src: src.val(),
+                src_val,
+                offset: offset.val(),
+            }))
+        }
+    }
+}
+
+impl_mv_event!(MvvhEvent, mvvh);
+
 /// Event for MVI.H.
 ///
 /// Performs a MOVE of 2-byte value from a 16-bit immediate into a VROM address,
@@ -241,8 +305,9 @@ impl_mv_event!(MvvlEvent, mvvl);
 ///
 /// Logic:
 ///   1. VROM[FP[dst] + offset] = ZeroExtend(imm)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MvihEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -283,14 +348,66 @@ impl MvihEvent {
 
 impl_mv_event!(MvihEvent, mvih);
 
+/// Event for MVI.B.
+///
+/// Performs a MOVE of 1-byte value from an 8-bit immediate into a VROM address,
+/// zero-extending to 32-bits.
+///
+/// Logic:
+///   1. VROM[FP[dst] + offset] = ZeroExtend(imm)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MvibEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub dst: u16,
+    pub dst_addr: u32,
+    pub imm: u8,
+    pub offset: u16,
+}
+
+impl MvibEvent {
+    pub(crate) fn generate_event(
+        ctx: &mut EventContext,
+        dst: B16,
+        offset: B16,
+        imm: B16,
+    ) -> Result<Option<Self>, InterpreterError> {
+        let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+
+        let imm = u8::try_from(imm.val()).map_err(|_| InterpreterError::InvalidInput)?;
+        let dst_addr = ctx.vrom_read::<u32>(ctx.addr(dst.val()))?;
+
+        execute_mv(ctx, dst_addr ^ offset.val() as u32, imm as u32)?;
+
+        if ctx.prover_only {
+            Ok(None)
+        } else {
+            Ok(Some(Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_addr,
+                imm,
+                offset: offset.val(),
+            }))
+        }
+    }
+}
+
+impl_mv_event!(MvibEvent, mvib);
+
 /// Event for LDI (Load Immediate).
 ///
 /// Performs a load of an immediate value into a VROM address.
 ///
 /// Logic:
 ///   1. FP[dst] = imm
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LdiEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -346,7 +463,7 @@ mod tests {
     use binius_m3::builder::{B16, B32};
 
     use crate::{
-        execution::{Interpreter, G},
+        execution::{Interpreter, InterpreterError, G},
         isa::GenericISA,
         memory::Memory,
         opcodes::Opcode,
@@ -400,7 +517,7 @@ mod tests {
 
         let memory = Memory::new(prom, vrom);
 
-        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames, HashMap::new());
+        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames);
 
         let trace = interpreter
             .run(memory)
@@ -490,9 +607,7 @@ mod tests {
 
         let memory = Memory::new(prom, vrom);
 
-        let mut pc_field_to_index_pc = HashMap::new();
-        pc_field_to_index_pc.insert(target, (4, 5));
-        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames, pc_field_to_index_pc);
+        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames);
 
         let traces = interpreter
             .run(memory)
@@ -597,8 +712,7 @@ mod tests {
         // We do not set `src_addr_mvvl` and `src_val_mvvw`.
         let memory = Memory::new(prom, vrom);
 
-        let pc_field_to_int = HashMap::new();
-        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames, pc_field_to_int);
+        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames);
 
         let traces = interpreter
             .run(memory)
@@ -708,8 +822,7 @@ mod tests {
         // We do not set `src_addr_mvvl` and `src_val_mvvw`.
         let memory = Memory::new(prom, vrom);
 
-        let pc_field_to_int = HashMap::new();
-        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames, pc_field_to_int);
+        let mut interpreter = Interpreter::new(Box::new(GenericISA), frames);
 
         let traces = interpreter
             .run(memory)
@@ -737,4 +850,50 @@ mod tests {
             imm.val() as u32
         );
     }
+
+    #[test]
+    fn test_sub_word_mv() {
+        // Frame
+        // Slot 0: Return PC
+        // Slot 1: Return FP
+        // Slot 2: src_val
+        // Slot 3: Storage MVVH
+        // Slot 4: Storage MVIB
+
+        let cur_fp = B16::one();
+        let zero = B16::zero();
+        let src_val = 0x1234_5678u32;
+
+        let run = |imm: u16| {
+            let instructions = vec![
+                [Opcode::Mvvh.get_field_elt(), cur_fp, 3.into(), 2.into()],
+                [Opcode::Mvib.get_field_elt(), cur_fp, 4.into(), imm.into()],
+                [Opcode::Ret.get_field_elt(), zero, zero, zero],
+            ];
+
+            let mut frames = HashMap::new();
+            frames.insert(B32::one(), 5);
+
+            let prom = code_to_prom_no_prover_only(&instructions);
+            let mut vrom = ValueRom::default();
+            // Set FP and PC
+            vrom.write(0, 0u32, false).unwrap();
+            vrom.write(1, 0u32, false).unwrap();
+            vrom.write(2, src_val, false).unwrap();
+            let memory = Memory::new(prom, vrom);
+
+            let mut interpreter = Interpreter::new(Box::new(GenericISA), frames);
+            interpreter.run(memory)
+        };
+
+        let traces = run(0xab).expect("The interpreter should run smoothly.");
+        assert_eq!(traces.vrom().read::<u32>(3).unwrap(), 0x5678);
+        assert_eq!(traces.vrom().read::<u32>(4).unwrap(), 0xab);
+        assert_eq!(traces.mvvh.len(), 1);
+        assert_eq!(traces.mvib.len(), 1);
+
+        // The immediate of MVI.B must fit in a byte.
+        let err = run(0x100).unwrap_err();
+        assert!(matches!(err.root(), InterpreterError::InvalidInput));
+    }
 }
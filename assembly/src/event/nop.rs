@@ -0,0 +1,51 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::context::EventContext;
+use crate::{
+    event::Event,
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::fire_non_jump_event,
+};
+
+/// Event for NOP.
+///
+/// Advances the PC without any other effect on the state. The assembler uses
+/// it to pad the PROM, e.g. for the `.align` directive.
+///
+/// Logic:
+///   1. PC = PC * G
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NopEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+}
+
+impl Event for NopEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        _unused0: B16,
+        _unused1: B16,
+        _unused2: B16,
+    ) -> Result<(), InterpreterError> {
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+            };
+
+            ctx.record(|trace| &mut trace.nop, event);
+        }
+
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
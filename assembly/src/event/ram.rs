@@ -0,0 +1,359 @@
+//! RAM access events.
+//!
+//! Unlike the VROM, the RAM is a byte-addressable, read-write memory. Every
+//! access is tagged with the current system timestamp, which is incremented
+//! after each RAM operation (and COMMIT), so that the sequence of accesses can
+//! later be checked for consistency.
+
+use binius_m3::builder::{B16, B32};
+
+use super::context::EventContext;
+use crate::{
+    event::Event,
+    execution::{FieldPc, FramePointer, InterpreterChannels, InterpreterError},
+};
+
+/// Computes the RAM address targeted by a load or a store, by adding the
+/// sign-extended 16-bit `offset` to the base address.
+const fn ram_address(base_val: u32, offset: u16) -> u32 {
+    base_val.wrapping_add(offset as i16 as i32 as u32)
+}
+
+/// Defines a RAM load event and implements the [`Event`] trait for it.
+///
+/// It takes as argument the instruction, with optional Rust documentation, its
+/// corresponding field name in the
+/// [`PetraTrace`](crate::execution::trace::PetraTrace), the type of the value
+/// read from RAM and the function extending it to 32 bits.
+macro_rules! define_ram_load_event {
+    ($(#[$meta:meta])* $name:ident, $trace_field:ident, $ty:ty, $extend:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct $name {
+            pub timestamp: u32,
+            #[serde(with = "crate::util::serde_b32")]
+            pub pc: B32,
+            pub fp: FramePointer,
+            pub dst: u16,
+            pub dst_val: u32,
+            pub base: u16,
+            pub base_val: u32,
+            pub offset: u16,
+            pub addr: u32,
+            /// Value of the accessed word before this access.
+            pub prev_word: u32,
+            /// Timestamp of the previous access to the accessed word, if any.
+            pub prev_timestamp: Option<u32>,
+        }
+
+        impl Event for $name {
+            fn generate(
+                ctx: &mut EventContext,
+                dst: B16,
+                base: B16,
+                offset: B16,
+            ) -> Result<(), InterpreterError> {
+                let base_val = ctx.vrom_read::<u32>(ctx.addr(base.val()))?;
+                let addr = ram_address(base_val, offset.val());
+
+                let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+                let value = ctx.ram_read::<$ty>(addr, timestamp, field_pc)?;
+                let dst_val: u32 = $extend(value);
+                let (prev_word, prev_timestamp) = last_word_access(ctx);
+                ctx.vrom_write(ctx.addr(dst.val()), dst_val)?;
+
+                let event = Self {
+                    timestamp,
+                    pc: field_pc,
+                    fp,
+                    dst: dst.val(),
+                    dst_val,
+                    base: base.val(),
+                    base_val,
+                    offset: offset.val(),
+                    addr,
+                    prev_word,
+                    prev_timestamp,
+                };
+                ctx.record(|trace| &mut trace.$trace_field, event);
+                ctx.incr_timestamp();
+                ctx.incr_counters();
+                Ok(())
+            }
+
+            fn fire(&self, channels: &mut InterpreterChannels) {
+                fire_ram_event(self.pc, self.fp, self.timestamp, channels);
+            }
+        }
+    };
+}
+
+/// Defines a RAM store event and implements the [`Event`] trait for it.
+///
+/// It takes as argument the instruction, with optional Rust documentation, its
+/// corresponding field name in the
+/// [`PetraTrace`](crate::execution::trace::PetraTrace) and the type of the
+/// value written to RAM. The source value is truncated to that type.
+macro_rules! define_ram_store_event {
+    ($(#[$meta:meta])* $name:ident, $trace_field:ident, $ty:ty) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct $name {
+            pub timestamp: u32,
+            #[serde(with = "crate::util::serde_b32")]
+            pub pc: B32,
+            pub fp: FramePointer,
+            pub src: u16,
+            pub src_val: u32,
+            pub base: u16,
+            pub base_val: u32,
+            pub offset: u16,
+            pub addr: u32,
+            /// Value of the accessed word before this access.
+            pub prev_word: u32,
+            /// Timestamp of the previous access to the accessed word, if any.
+            pub prev_timestamp: Option<u32>,
+        }
+
+        impl Event for $name {
+            fn generate(
+                ctx: &mut EventContext,
+                src: B16,
+                base: B16,
+                offset: B16,
+            ) -> Result<(), InterpreterError> {
+                let src_val = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
+                let base_val = ctx.vrom_read::<u32>(ctx.addr(base.val()))?;
+                let addr = ram_address(base_val, offset.val());
+
+                let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+                ctx.ram_write::<$ty>(addr, src_val as $ty, timestamp, field_pc)?;
+                let (prev_word, prev_timestamp) = last_word_access(ctx);
+
+                let event = Self {
+                    timestamp,
+                    pc: field_pc,
+                    fp,
+                    src: src.val(),
+                    src_val,
+                    base: base.val(),
+                    base_val,
+                    offset: offset.val(),
+                    addr,
+                    prev_word,
+                    prev_timestamp,
+                };
+                ctx.record(|trace| &mut trace.$trace_field, event);
+                ctx.incr_timestamp();
+                ctx.incr_counters();
+                Ok(())
+            }
+
+            fn fire(&self, channels: &mut InterpreterChannels) {
+                fire_ram_event(self.pc, self.fp, self.timestamp, channels);
+            }
+        }
+    };
+}
+
+/// Returns the previous value and access timestamp of the word touched by the
+/// last RAM access.
+fn last_word_access(ctx: &EventContext) -> (u32, Option<u32>) {
+    let access = ctx
+        .ram()
+        .access_history()
+        .last()
+        .expect("A RAM access was just recorded");
+    (access.prev_word, access.prev_timestamp)
+}
+
+/// Flushing rules shared by all RAM events: the PC is incremented and, since
+/// the RAM is accessed, so is the timestamp.
+fn fire_ram_event(pc: B32, fp: FramePointer, timestamp: u32, channels: &mut InterpreterChannels) {
+    channels.state_channel.pull((pc, *fp, timestamp));
+    channels
+        .state_channel
+        .push((FieldPc::from(pc).next().field(), *fp, timestamp + 1));
+}
+
+define_ram_load_event!(
+    /// Event for LW.
+    ///
+    /// Loads a 32-bit word from RAM.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = RAM[FP[base] + offset]
+    LwEvent,
+    lw,
+    u32,
+    |value: u32| value
+);
+
+define_ram_load_event!(
+    /// Event for LH.
+    ///
+    /// Loads a 16-bit half-word from RAM, and sign-extends it.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = SignExtend(RAM[FP[base] + offset])
+    LhEvent,
+    lh,
+    u16,
+    |value: u16| value as i16 as i32 as u32
+);
+
+define_ram_load_event!(
+    /// Event for LHU.
+    ///
+    /// Loads a 16-bit half-word from RAM, and zero-extends it.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = ZeroExtend(RAM[FP[base] + offset])
+    LhuEvent,
+    lhu,
+    u16,
+    |value: u16| value as u32
+);
+
+define_ram_load_event!(
+    /// Event for LB.
+    ///
+    /// Loads a byte from RAM, and sign-extends it.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = SignExtend(RAM[FP[base] + offset])
+    LbEvent,
+    lb,
+    u8,
+    |value: u8| value as i8 as i32 as u32
+);
+
+define_ram_load_event!(
+    /// Event for LBU.
+    ///
+    /// Loads a byte from RAM, and zero-extends it.
+    ///
+    /// Logic:
+    ///   1. FP[dst] = ZeroExtend(RAM[FP[base] + offset])
+    LbuEvent,
+    lbu,
+    u8,
+    |value: u8| value as u32
+);
+
+define_ram_store_event!(
+    /// Event for SW.
+    ///
+    /// Stores a 32-bit word into RAM.
+    ///
+    /// Logic:
+    ///   1. RAM[FP[base] + offset] = FP[src]
+    SwEvent,
+    sw,
+    u32
+);
+
+define_ram_store_event!(
+    /// Event for SH.
+    ///
+    /// Stores the low 16 bits of a word into RAM.
+    ///
+    /// Logic:
+    ///   1. RAM[FP[base] + offset] = FP[src] & 0xFFFF
+    ShEvent,
+    sh,
+    u16
+);
+
+define_ram_store_event!(
+    /// Event for SB.
+    ///
+    /// Stores the low 8 bits of a word into RAM.
+    ///
+    /// Logic:
+    ///   1. RAM[FP[base] + offset] = FP[src] & 0xFF
+    SbEvent,
+    sb,
+    u8
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execution::Interpreter, isa::GenericISA, Assembler, Memory, PetraTrace, ValueRom};
+
+    #[test]
+    fn test_ram_store_then_load() {
+        let mut interpreter = Interpreter::default();
+        let mut trace = PetraTrace::default();
+        let mut ctx = EventContext::new(&mut interpreter, &mut trace);
+
+        // Base address, and the value to store.
+        ctx.set_vrom(2, 16);
+        ctx.set_vrom(3, 0x8001_F0FE);
+
+        // RAM[16 + 4] = 0x8001F0FE
+        SwEvent::generate(&mut ctx, B16::new(3), B16::new(2), B16::new(4)).unwrap();
+        // RAM[16 + 8] = 0xF0FE
+        ShEvent::generate(&mut ctx, B16::new(3), B16::new(2), B16::new(8)).unwrap();
+        // RAM[16 - 1] = 0xFE
+        SbEvent::generate(&mut ctx, B16::new(3), B16::new(2), B16::new(-1i16 as u16)).unwrap();
+
+        LwEvent::generate(&mut ctx, B16::new(4), B16::new(2), B16::new(4)).unwrap();
+        LhEvent::generate(&mut ctx, B16::new(5), B16::new(2), B16::new(8)).unwrap();
+        LhuEvent::generate(&mut ctx, B16::new(6), B16::new(2), B16::new(8)).unwrap();
+        LbEvent::generate(&mut ctx, B16::new(7), B16::new(2), B16::new(-1i16 as u16)).unwrap();
+        LbuEvent::generate(&mut ctx, B16::new(8), B16::new(2), B16::new(-1i16 as u16)).unwrap();
+
+        assert_eq!(ctx.trace.lw[0].dst_val, 0x8001_F0FE);
+        assert_eq!(ctx.trace.lh[0].dst_val, 0xFFFF_F0FE);
+        assert_eq!(ctx.trace.lhu[0].dst_val, 0xF0FE);
+        assert_eq!(ctx.trace.lb[0].dst_val, 0xFFFF_FFFE);
+        assert_eq!(ctx.trace.lbu[0].dst_val, 0xFE);
+        assert_eq!(ctx.trace.sb[0].addr, 15);
+        assert_eq!(ctx.trace.lw[0].prev_word, 0x8001_F0FE);
+        assert_eq!(ctx.trace.lw[0].prev_timestamp, Some(0));
+        assert_eq!(ctx.trace.lb[0].prev_word, 0xFE00_0000);
+
+        // Each RAM access increments the timestamp.
+        assert_eq!(ctx.trace.lbu[0].timestamp, 7);
+        assert_eq!(ctx.timestamp, 8);
+        assert_eq!(ctx.ram().access_history().len(), 8);
+    }
+
+    #[test]
+    fn test_ram_misaligned_access() {
+        let mut interpreter = Interpreter::default();
+        let mut trace = PetraTrace::default();
+        let mut ctx = EventContext::new(&mut interpreter, &mut trace);
+
+        ctx.set_vrom(2, 2);
+        let result = LwEvent::generate(&mut ctx, B16::new(3), B16::new(2), B16::new(0));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_ram_program() {
+        let code = "#[framesize(0x8)]
+        _start:
+            LDI.W @2, #64
+            LDI.W @3, #-2
+            SW @3, @2, #0
+            SB @3, @2, #4
+            LW @4, @2, #0
+            LBU @5, @2, #4
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes)
+                .expect("Trace generation should not fail.");
+
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), -2i32 as u32);
+        assert_eq!(trace.vrom().read::<u32>(5).unwrap(), 0xFE);
+        assert_eq!(boundary_values.timestamp, 4);
+        trace.validate(boundary_values);
+    }
+}
@@ -0,0 +1,139 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError, InterpreterException},
+    macros::fire_non_jump_event,
+};
+
+/// Bit widths supported by RANGE_CHECK, each backed by a lookup table in the
+/// prover.
+pub const RANGE_CHECK_WIDTHS: [u16; 2] = [8, 16];
+
+/// Event for RANGE_CHECK.
+///
+/// Asserts that a slot holds a value fitting in `bits` bits, stopping the
+/// execution with an [`InterpreterException::RangeCheckFailed`] otherwise. The
+/// width must be one of [`RANGE_CHECK_WIDTHS`] for the check to be proven.
+///
+/// Logic:
+///   1. Assert FP[src] < 2^bits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeCheckEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    pub src: u16,
+    pub bits: u16,
+    pub val: u32,
+}
+
+impl Event for RangeCheckEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        src: B16,
+        bits: B16,
+        _unused: B16,
+    ) -> Result<(), InterpreterError> {
+        let src_val = ctx.vrom_read::<u32>(ctx.addr(src.val()))?;
+        let bits = bits.val();
+        if src_val.checked_shr(bits as u32).unwrap_or(0) != 0 {
+            let (pc, field_pc, _fp, _timestamp) = ctx.program_state();
+            return Err(InterpreterError::Exception(
+                InterpreterException::RangeCheckFailed {
+                    pc,
+                    field_pc,
+                    value: src_val,
+                    bits,
+                },
+            ));
+        }
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                src: src.val(),
+                bits,
+                val: src_val,
+            };
+
+            ctx.record(|trace| &mut trace.range_check, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        execution::{InterpreterError, InterpreterException},
+        isa::GenericISA,
+        Assembler, Memory, PetraTrace, ValueRom,
+    };
+
+    fn run(code: &str) -> Result<PetraTrace, InterpreterError> {
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).map(
+            |(trace, boundary_values)| {
+                trace.validate(boundary_values);
+                trace
+            },
+        )
+    }
+
+    #[test]
+    fn test_range_check() {
+        let trace = run("#[framesize(0x8)]
+        _start:
+            LDI.W @2, #255
+            LDI.W @3, #65535
+            RANGE_CHECK @2, #8
+            RANGE_CHECK @3, #16
+            RANGE_CHECK! @2, #16
+            RET
+        ")
+        .unwrap();
+
+        // The prover-only check is performed, but not recorded.
+        assert_eq!(trace.range_check.len(), 2);
+        assert_eq!(trace.range_check[0].bits, 8);
+        assert_eq!(trace.range_check[1].val, 65535);
+    }
+
+    #[test]
+    fn test_failed_range_check() {
+        for (value, bits) in [(256, 8), (65536, 16)] {
+            let code = format!(
+                "#[framesize(0x4)]
+                _start:
+                    LDI.W @2, #{value}
+                    RANGE_CHECK @2, #{bits}
+                    RET
+                "
+            );
+            let err = run(&code).unwrap_err();
+            match err.root() {
+                InterpreterError::Exception(InterpreterException::RangeCheckFailed {
+                    value: failed_value,
+                    bits: failed_bits,
+                    ..
+                }) => {
+                    assert_eq!(*failed_value, value);
+                    assert_eq!(*failed_bits, bits);
+                }
+                err => panic!("Unexpected error {err:?}"),
+            }
+        }
+    }
+}
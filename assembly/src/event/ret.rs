@@ -1,4 +1,5 @@
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::{context::EventContext, Event};
 use crate::execution::{FramePointer, InterpreterChannels, InterpreterError};
@@ -10,8 +11,9 @@ use crate::execution::{FramePointer, InterpreterChannels, InterpreterError};
 /// Logic:
 ///   1. PC = FP[0]
 ///   2. FP = FP[1]
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct RetEvent {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -49,10 +51,10 @@ impl Event for RetEvent {
         let ret_event = RetEvent::new(ctx)?;
 
         let target = ret_event.pc_next;
-        ctx.jump_to(B32::new(target));
+        ctx.jump_to(B32::new(target))?;
         ctx.set_fp(ret_event.fp_next);
 
-        ctx.trace.ret.push(ret_event);
+        ctx.record(|trace| &mut trace.ret, ret_event);
         Ok(())
     }
 
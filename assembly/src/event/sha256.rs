@@ -0,0 +1,158 @@
+use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
+
+use super::{context::EventContext, Event};
+use crate::{
+    execution::{FramePointer, InterpreterChannels, InterpreterError},
+    macros::fire_non_jump_event,
+    util::sha256_compress,
+};
+
+/// Event for SHA256_COMPRESS.
+///
+/// Applies the SHA-256 compression function to an 8-word state and a 16-word
+/// message block, and writes the new state to `dst`. Each word holds four
+/// big-endian bytes of the state or block, as in the SHA-256 specs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sha256CompressEvent {
+    #[serde(with = "crate::util::serde_b32")]
+    pub pc: B32,
+    pub fp: FramePointer,
+    pub timestamp: u32,
+    /// dst is the offset where the new state is stored.
+    /// Since we are writing 8 words to memory, it needs to be 8-word aligned.
+    pub dst: u16,
+    pub dst_val: [u32; 8],
+    /// src1 is the offset where the input state is stored.
+    /// Since we are reading 8 words from memory, it needs to be 8-word aligned.
+    pub src1: u16,
+    pub src1_val: [u32; 8],
+    /// src2 is the offset where the message block is stored.
+    /// Since we are reading 16 words from memory, it needs to be 16-word
+    /// aligned.
+    pub src2: u16,
+    pub src2_val: [u32; 16],
+}
+
+impl Event for Sha256CompressEvent {
+    fn generate(
+        ctx: &mut EventContext,
+        dst: B16,
+        src1: B16,
+        src2: B16,
+    ) -> Result<(), InterpreterError> {
+        let src1_val = read_words::<8>(ctx, src1)?;
+        let src2_val = read_words::<16>(ctx, src2)?;
+
+        let mut dst_val = src1_val;
+        sha256_compress(&mut dst_val, &src2_val);
+
+        ctx.vrom_write_slots(dst.val(), &dst_val)?;
+
+        if !ctx.prover_only {
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            let event = Self {
+                pc: field_pc,
+                fp,
+                timestamp,
+                dst: dst.val(),
+                dst_val,
+                src1: src1.val(),
+                src1_val,
+                src2: src2.val(),
+                src2_val,
+            };
+
+            ctx.record(|trace| &mut trace.sha256_compress, event);
+        }
+        ctx.incr_counters();
+        Ok(())
+    }
+
+    fn fire(&self, channels: &mut InterpreterChannels) {
+        fire_non_jump_event!(self, channels);
+    }
+}
+
+/// Reads `N` consecutive words starting at offset `src`.
+pub(crate) fn read_words<const N: usize>(
+    ctx: &mut EventContext,
+    src: B16,
+) -> Result<[u32; N], InterpreterError> {
+    Ok(ctx.vrom_read_slots(src.val())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use binius_field::Field;
+
+    use super::*;
+    use crate::{
+        isa::GenericISA, test_util::code_to_prom_no_prover_only, util::SHA256_IV, Memory, Opcode,
+        PetraTrace, ValueRom,
+    };
+
+    #[test]
+    fn test_sha256_compress() {
+        // Frame:
+        // Slot 0: PC
+        // Slot 1: FP
+        // Slots 2-7: Padding
+        // Slots 8-15: src1_val
+        // Slots 16-31: src2_val
+        // Slots 32-39: dst_val
+
+        // Single padded block for the message "abc".
+        let mut block = [0u32; 16];
+        block[0] = 0x61626380;
+        block[15] = 24;
+
+        let dst_offset = 32;
+        let src1_offset = 8;
+        let src2_offset = 16;
+        let mut init_values = vec![0; 32];
+        init_values[src1_offset..(8 + src1_offset)].copy_from_slice(&SHA256_IV);
+        init_values[src2_offset..(16 + src2_offset)].copy_from_slice(&block);
+
+        let vrom = ValueRom::new_with_init_vals(&init_values);
+
+        // Construct a simple program with the Sha256Compress instruction
+        // 1. SHA256_COMPRESS @output, @src1, @src2
+        // 2. RET
+        let zero = B16::ZERO;
+        let dst = B16::from(dst_offset as u16);
+        let src1 = B16::from(src1_offset as u16);
+        let src2 = B16::from(src2_offset as u16);
+        let instructions = vec![
+            [Opcode::Sha256Compress.get_field_elt(), dst, src1, src2],
+            [Opcode::Ret.get_field_elt(), zero, zero, zero],
+        ];
+        // Set up frame sizes
+        let mut frames = HashMap::new();
+        frames.insert(B32::ONE, 64);
+
+        // Create the PROM
+        let prom = code_to_prom_no_prover_only(&instructions);
+        let memory = Memory::new(prom, vrom);
+
+        // Create an interpreter and run the program
+        let (trace, boundary_values) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
+            .expect("Trace generation should not fail.");
+
+        // Validate the trace (this consumes boundary_values)
+        trace.validate(boundary_values);
+
+        // SHA-256("abc")
+        let expected = [
+            0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+            0xf20015ad,
+        ];
+        let actual = (0..8)
+            .map(|i| trace.vrom().read::<u32>(dst_offset + i).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+        assert_eq!(trace.sha256_compress.len(), 1);
+    }
+}
@@ -2,9 +2,10 @@ use core::fmt::Debug;
 use std::marker::PhantomData;
 
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 
 use super::context::EventContext;
-use super::gadgets::right_logic_shift::RightLogicShiftExtension;
+use super::gadgets::right_logic_shift::{RightLogicShiftExtension, RightLogicShiftGadgetEvent};
 use crate::{
     event::Event,
     execution::{FramePointer, InterpreterChannels, InterpreterError},
@@ -58,6 +59,34 @@ impl ShiftOperation<VromOffsetShift> for ArithmeticRight {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotateLeft;
+impl ShiftOperation<ImmediateShift> for RotateLeft {
+    fn shift_op(val: u32, shift: u32) -> u32 {
+        val.rotate_left(shift)
+    }
+}
+
+impl ShiftOperation<VromOffsetShift> for RotateLeft {
+    fn shift_op(val: u32, shift: u32) -> u32 {
+        val.rotate_left(shift)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotateRight;
+impl ShiftOperation<ImmediateShift> for RotateRight {
+    fn shift_op(val: u32, shift: u32) -> u32 {
+        val.rotate_right(shift)
+    }
+}
+
+impl ShiftOperation<VromOffsetShift> for RotateRight {
+    fn shift_op(val: u32, shift: u32) -> u32 {
+        val.rotate_right(shift)
+    }
+}
+
 /// Indicates the source of the shift amount.
 pub trait ShiftSource: Debug + Clone + PartialEq {
     fn is_immediate() -> bool;
@@ -81,12 +110,14 @@ impl ShiftSource for VromOffsetShift {
 
 /// Combined event for both logical and arithmetic shift operations.
 /// The type of shift is determined by the `shift_op` field.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ShiftEvent<S, O>
 where
     S: ShiftSource,
     O: ShiftOperation<S>,
 {
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     pub fp: FramePointer,
     pub timestamp: u32,
@@ -140,6 +171,8 @@ where
     /// - LogicalLeft: `src_val << effective_shift`
     /// - LogicalRight: `src_val >> effective_shift`
     /// - ArithmeticRight: arithmetic right shift preserving the sign bit.
+    /// - RotateLeft: `src_val` rotated left by `effective_shift`
+    /// - RotateRight: `src_val` rotated right by `effective_shift`
     pub fn calculate_result(src_val: u32, shift_amount: u32) -> u32 {
         let effective_shift = shift_amount & 0x1f;
         if effective_shift == 0 {
@@ -151,9 +184,9 @@ where
 
     /// Generate a ShiftEvent for immediate shift operations.
     ///
-    /// For immediate shifts (like SLLI, SRLI, SRAI), the shift amount comes
-    /// directly from the instruction (as a 16-bit immediate) and masked to 5
-    /// bits.
+    /// For immediate shifts (like SLLI, SRLI, SRAI, ROLI, RORI), the shift
+    /// amount comes directly from the instruction (as a 16-bit immediate)
+    /// and masked to 5 bits.
     pub(crate) fn generate_immediate_event(
         ctx: &mut EventContext,
         dst: B16,
@@ -188,8 +221,8 @@ where
 
     /// Generate a ShiftEvent for VROM-based shift operations.
     ///
-    /// For VROM-based shifts (like SLL, SRL, SRA), the shift amount is read
-    /// from another VROM location and masked to 5 bits.
+    /// For VROM-based shifts (like SLL, SRL, SRA, ROL, ROR), the shift amount
+    /// is read from another VROM location and masked to 5 bits.
     pub(crate) fn generate_vrom_event(
         ctx: &mut EventContext,
         dst: B16,
@@ -252,27 +285,31 @@ macro_rules! impl_shift_event {
                     let event = event.ok_or(InterpreterError::InvalidInput)?;
                     // For right shift operations, create a RightLogicShiftGadgetEvent
                     // This needs to handle both logical and arithmetic right shifts
-                    match stringify!($variant) {
-                        "srli" | "srl" => {
-                            // For logical right shifts, just use the values directly
-                            ctx.trace.add_right_shift_event(
-                                event.src_val,
-                                event.shift_amount,
-                                event.dst_val,
-                            );
-                        }
-                        "srai" | "sra" => {
-                            // For arithmetic right shifts, handle sign bit appropriately
-                            let sign = (event.src_val >> 31) & 1 == 1;
-                            let input = if sign { !event.src_val } else { event.src_val };
-                            let output = input >> (event.shift_amount & 0x1F);
-                            ctx.trace
-                                .add_right_shift_event(input, event.shift_amount, output);
+                    // Deferred gadget events are derived after the execution instead
+                    if ctx.record_events && !ctx.defer_gadget_events {
+                        match stringify!($variant) {
+                            "srli" | "srl" => {
+                                // For logical right shifts, just use the values directly
+                                ctx.trace.add_right_shift_event(
+                                    event.src_val,
+                                    event.shift_amount,
+                                    event.dst_val,
+                                );
+                            }
+                            "srai" | "sra" => {
+                                // For arithmetic right shifts, handle sign bit appropriately
+                                ctx.trace.right_logic_shift_gadget.push(
+                                    RightLogicShiftGadgetEvent::arithmetic(
+                                        event.src_val,
+                                        event.shift_amount,
+                                    ),
+                                );
+                            }
+                            _ => {}
                         }
-                        _ => {}
                     }
 
-                    ctx.trace.$variant.push(event);
+                    ctx.record(|trace| &mut trace.$variant, event);
                 }
                 Ok(())
             }
@@ -290,6 +327,10 @@ pub type SraiEvent = ShiftEvent<ImmediateShift, ArithmeticRight>;
 pub type SllEvent = ShiftEvent<VromOffsetShift, LogicalLeft>;
 pub type SrlEvent = ShiftEvent<VromOffsetShift, LogicalRight>;
 pub type SraEvent = ShiftEvent<VromOffsetShift, ArithmeticRight>;
+pub type RoliEvent = ShiftEvent<ImmediateShift, RotateLeft>;
+pub type RoriEvent = ShiftEvent<ImmediateShift, RotateRight>;
+pub type RolEvent = ShiftEvent<VromOffsetShift, RotateLeft>;
+pub type RorEvent = ShiftEvent<VromOffsetShift, RotateRight>;
 
 impl_shift_event!(slli, SlliEvent, ImmediateShift);
 impl_shift_event!(srli, SrliEvent, ImmediateShift);
@@ -297,6 +338,10 @@ impl_shift_event!(srai, SraiEvent, ImmediateShift);
 impl_shift_event!(sll, SllEvent, VromOffsetShift);
 impl_shift_event!(srl, SrlEvent, VromOffsetShift);
 impl_shift_event!(sra, SraEvent, VromOffsetShift);
+impl_shift_event!(roli, RoliEvent, ImmediateShift);
+impl_shift_event!(rori, RoriEvent, ImmediateShift);
+impl_shift_event!(rol, RolEvent, VromOffsetShift);
+impl_shift_event!(ror, RorEvent, VromOffsetShift);
 
 #[cfg(test)]
 mod test {
@@ -409,6 +454,54 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_rotate_event_calculate() {
+        // Each tuple is:
+        // (src_val, shift_amount, expected_left, expected_right, description)
+        let test_cases = [
+            (
+                0x12345678,
+                0,
+                0x12345678,
+                0x12345678,
+                "identity rotation (0)",
+            ),
+            (0x80000001, 1, 0x00000003, 0xc0000000, "rotate by 1"),
+            (0x12345678, 8, 0x34567812, 0x78123456, "rotate by 8"),
+            (0x80000001, 31, 0xc0000000, 0x00000003, "rotate by 31"),
+            (
+                0x12345678,
+                32,
+                0x12345678,
+                0x12345678,
+                "rotate by 32 (mod 32 => 0)",
+            ),
+            (
+                0x12345678,
+                36,
+                0x23456781,
+                0x81234567,
+                "rotate by 36 (effective 4)",
+            ),
+        ];
+
+        for (src_val, shift_amount, expected_left, expected_right, desc) in test_cases {
+            let result_left =
+                ShiftEvent::<ImmediateShift, RotateLeft>::calculate_result(src_val, shift_amount);
+            let result_right =
+                ShiftEvent::<VromOffsetShift, RotateRight>::calculate_result(src_val, shift_amount);
+
+            assert_eq!(
+                result_left, expected_left,
+                "RotateLeft failed for {desc}: expected 0x{expected_left:08x}, got 0x{result_left:08x}"
+            );
+            assert_eq!(
+                result_right, expected_right,
+                "RotateRight failed for {desc}: expected 0x{expected_right:08x}, got 0x{result_right:08x}"
+            );
+        }
+    }
+
     #[test]
     fn test_shift_event_integration() {
         let zero = B16::zero();
@@ -517,7 +610,7 @@ mod test {
         let prom = code_to_prom_no_prover_only(&instructions);
         let memory = Memory::new(prom, vrom);
 
-        let (trace, _) = PetraTrace::generate(Box::new(GenericISA), memory, frames, HashMap::new())
+        let (trace, _) = PetraTrace::generate(Box::new(GenericISA), memory, frames)
             .expect("Trace generation should not fail.");
 
         // Check results for immediate shift operations
@@ -0,0 +1,101 @@
+//! Symbolic backtraces of guest executions.
+//!
+//! The [`Interpreter`](super::Interpreter) keeps track of the chain of frames
+//! created by the `CALL*` and `TAIL*` instructions, and attaches a
+//! [`Backtrace`] of it to the errors raised during the execution, so that
+//! failures deep inside library functions can be traced back to their callers.
+//! A backtrace only knows about field PCs, and can be given names with
+//! [`Backtrace::symbolize`] from the labels of the assembled program.
+
+use std::{collections::HashMap, fmt};
+
+use binius_m3::builder::B32;
+
+use super::FramePointer;
+
+/// Entry of the call stack tracked by the interpreter.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CallFrame {
+    /// Field PC of the function entry.
+    pub(crate) function: B32,
+    /// Frame pointer of the frame.
+    pub(crate) fp: FramePointer,
+    /// Field PC of the call instruction in the caller, zero for the entry
+    /// point.
+    pub(crate) call_site: B32,
+}
+
+/// A function frame that was live when a backtrace was captured.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Field PC of the function entry.
+    pub function: B32,
+    /// Field PC of the instruction being executed in this frame: the faulting
+    /// instruction for the innermost frame, the call instruction for the
+    /// others.
+    pub pc: B32,
+    /// Frame pointer of the frame.
+    pub fp: FramePointer,
+    /// Name of the function, once symbolized.
+    pub label: Option<String>,
+}
+
+/// Chain of live frames at the time of an error, innermost first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Backtrace {
+    pub frames: Vec<StackFrame>,
+}
+
+impl Backtrace {
+    /// Captures the backtrace of `call_stack`, the innermost frame currently
+    /// executing the instruction at `pc`.
+    pub(crate) fn capture(call_stack: &[CallFrame], pc: B32) -> Self {
+        let mut pc = pc;
+        let frames = call_stack
+            .iter()
+            .rev()
+            .map(|frame| {
+                let stack_frame = StackFrame {
+                    function: frame.function,
+                    pc,
+                    fp: frame.fp,
+                    label: None,
+                };
+                pc = frame.call_site;
+                stack_frame
+            })
+            .collect();
+        Self { frames }
+    }
+
+    /// Names the frames after the function labels found in `labels`, as
+    /// returned by the assembler.
+    pub fn symbolize(&mut self, labels: &HashMap<String, (B32, u32, u32)>) {
+        let names: HashMap<B32, &str> = labels
+            .iter()
+            .map(|(name, (field_pc, _, _))| (*field_pc, name.as_str()))
+            .collect();
+        for frame in &mut self.frames {
+            if let Some(name) = names.get(&frame.function) {
+                frame.label = Some(name.to_string());
+            }
+        }
+    }
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "#{i} ")?;
+            match &frame.label {
+                Some(label) => write!(f, "{label}")?,
+                None => write!(f, "{:#010x}", frame.function.val())?,
+            }
+            write!(f, " at PC {:#010x} (FP {:#x})", frame.pc.val(), *frame.fp)?;
+        }
+        Ok(())
+    }
+}
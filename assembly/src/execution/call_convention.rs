@@ -0,0 +1,212 @@
+//! Post-execution check of the calling convention.
+//!
+//! A call sets up the frame of the callee through three VROM slots: the slot
+//! of the caller holding the new frame pointer, and the first two slots of the
+//! new frame, holding the return PC and the frame pointer to return to. The
+//! emulator writes the last two eagerly, but a trace assembled or patched
+//! outside of it, e.g. by custom instructions writing to the VROM, can leave
+//! them unset or inconsistent, which only surfaces as an opaque missing value
+//! or channel imbalance once proving.
+//!
+//! [`PetraTrace::check_call_convention`] checks every CALL and TAIL event of a
+//! trace against its VROM, and reports the slots breaking the convention along
+//! with the instruction that set them up.
+
+use binius_m3::builder::B32;
+
+use super::{FieldPc, FramePointer, PetraTrace};
+use crate::Opcode;
+
+/// Violation of the calling convention by a CALL or TAIL instruction.
+#[derive(Debug, thiserror::Error)]
+pub enum CallConventionError {
+    #[error("The {slot} slot {addr:#x} of the {opcode} at PC {field_pc} is unset")]
+    UnsetSlot {
+        /// Opcode of the call.
+        opcode: Opcode,
+        /// Field PC of the call.
+        field_pc: B32,
+        /// Name of the slot.
+        slot: &'static str,
+        /// VROM address of the slot.
+        addr: u32,
+    },
+    #[error(
+        "The {slot} slot {addr:#x} of the {opcode} at PC {field_pc} holds {found:#x} instead of {expected:#x}"
+    )]
+    WrongSlot {
+        /// Opcode of the call.
+        opcode: Opcode,
+        /// Field PC of the call.
+        field_pc: B32,
+        /// Name of the slot.
+        slot: &'static str,
+        /// VROM address of the slot.
+        addr: u32,
+        /// Value expected by the convention.
+        expected: u32,
+        /// Value held by the slot.
+        found: u32,
+    },
+}
+
+/// A frame set up by a CALL or TAIL event.
+struct CallSite {
+    opcode: Opcode,
+    field_pc: B32,
+    fp: FramePointer,
+    next_fp: u16,
+    next_fp_val: u32,
+    /// Return PC and FP of the callee, or `None` for tail calls, which pass on
+    /// those of the caller.
+    returns_to: Option<(u32, u32)>,
+}
+
+impl PetraTrace {
+    /// Checks that the CALL and TAIL events of the trace follow the calling
+    /// convention, see the [module-level documentation](self):
+    /// - the next-FP slot of the caller holds the frame pointer of the callee,
+    /// - the return-PC and return-FP slots of the callee hold the PC following
+    ///   the call and the frame pointer of the caller, or the slots of the
+    ///   caller for tail calls.
+    ///
+    /// The violations are reported in the order of the event fields, CALLs
+    /// first.
+    pub fn check_call_convention(&self) -> Result<(), CallConventionError> {
+        let call = |opcode, field_pc: B32, fp: FramePointer, next_fp, next_fp_val| CallSite {
+            opcode,
+            field_pc,
+            fp,
+            next_fp,
+            next_fp_val,
+            returns_to: Some((FieldPc::from(field_pc).next().val(), *fp)),
+        };
+        let tail = |opcode, field_pc, fp, next_fp, next_fp_val| CallSite {
+            opcode,
+            field_pc,
+            fp,
+            next_fp,
+            next_fp_val,
+            returns_to: None,
+        };
+        let sites = self
+            .calli
+            .iter()
+            .map(|ev| call(Opcode::Calli, ev.pc, ev.fp, ev.next_fp, ev.next_fp_val))
+            .chain(
+                self.callv
+                    .iter()
+                    .map(|ev| call(Opcode::Callv, ev.pc, ev.fp, ev.next_fp, ev.next_fp_val)),
+            )
+            .chain(
+                self.taili
+                    .iter()
+                    .map(|ev| tail(Opcode::Taili, ev.pc, ev.fp, ev.next_fp, ev.next_fp_val)),
+            )
+            .chain(
+                self.tailv
+                    .iter()
+                    .map(|ev| tail(Opcode::Tailv, ev.pc, ev.fp, ev.next_fp, ev.next_fp_val)),
+            );
+
+        for site in sites {
+            let read = |slot: &'static str, addr: u32| {
+                self.vrom()
+                    .peek::<u32>(addr)
+                    .map_err(|_| CallConventionError::UnsetSlot {
+                        opcode: site.opcode,
+                        field_pc: site.field_pc,
+                        slot,
+                        addr,
+                    })
+            };
+            let check = |slot: &'static str, addr: u32, expected: u32| {
+                let found = read(slot, addr)?;
+                if found != expected {
+                    return Err(CallConventionError::WrongSlot {
+                        opcode: site.opcode,
+                        field_pc: site.field_pc,
+                        slot,
+                        addr,
+                        expected,
+                        found,
+                    });
+                }
+                Ok(())
+            };
+
+            check("next FP", site.fp.addr(site.next_fp), site.next_fp_val)?;
+            let (return_pc, return_fp) = match site.returns_to {
+                Some(returns_to) => returns_to,
+                None => (
+                    read("return PC", *site.fp)?,
+                    read("return FP", *site.fp + 1)?,
+                ),
+            };
+            check("return PC", site.next_fp_val, return_pc)?;
+            check("return FP", site.next_fp_val + 1, return_fp)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, Memory, ValueRom};
+
+    fn generate(code: &str) -> PetraTrace {
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_call_convention() {
+        let trace = generate(
+            "#[framesize(0x10)]
+            _start:
+                CALLI f, @4
+                RET
+            #[framesize(0x10)]
+            f:
+                TAILI g, @4
+            #[framesize(0x4)]
+            g:
+                RET
+            ",
+        );
+        assert_eq!(trace.calli.len(), 1);
+        assert_eq!(trace.taili.len(), 1);
+        trace.check_call_convention().unwrap();
+    }
+
+    #[test]
+    fn test_call_convention_violation() {
+        let mut trace = generate(
+            "#[framesize(0x10)]
+            _start:
+                CALLI f, @4
+                RET
+            #[framesize(0x4)]
+            f:
+                RET
+            ",
+        );
+
+        // A call whose frame was not set up is reported with its instruction.
+        let mut event = trace.calli[0].clone();
+        event.next_fp_val = trace.vrom().high_water_mark() + 0x100;
+        trace.calli.push(event);
+        match trace.check_call_convention() {
+            Err(CallConventionError::WrongSlot {
+                opcode: Opcode::Calli,
+                slot: "next FP",
+                ..
+            }) => {}
+            res => panic!("Unexpected result {res:?}"),
+        }
+    }
+}
@@ -1,6 +1,14 @@
 //! Debugging module to detect unbalanced channels during program execution.
+//!
+//! Beyond checking that a channel is balanced, the flushes of a trace can be
+//! recorded along with the events performing them, with a [`FlushRecorder`],
+//! to report the values left unbalanced and where they come from.
 
-use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    hash::Hash,
+};
 
 use binius_m3::builder::B32;
 use tracing::trace;
@@ -56,6 +64,13 @@ impl<T: Hash + Eq + Debug> Channel<T> {
     }
 }
 
+impl<T: Hash + Eq + Debug> Channel<T> {
+    /// Takes out the values with a non-zero net multiplicity.
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = (T, isize)> + '_ {
+        self.net_multiplicities.drain()
+    }
+}
+
 impl StateChannel {
     pub(crate) fn is_balanced(&self) -> bool {
         #[cfg(debug_assertions)]
@@ -76,3 +91,149 @@ impl StateChannel {
         self.net_multiplicities.is_empty()
     }
 }
+
+/// Direction of a flush, from the point of view of the flushing event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushDirection {
+    Push,
+    Pull,
+}
+
+/// Origin of a flush: the events performing it, named after the trace field
+/// holding them, and the index of the event there.
+///
+/// Flushes of the execution boundaries have no index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlushOrigin {
+    pub events: String,
+    pub index: Option<usize>,
+}
+
+impl FlushOrigin {
+    pub fn event(events: impl Into<String>, index: usize) -> Self {
+        Self {
+            events: events.into(),
+            index: Some(index),
+        }
+    }
+
+    pub fn boundary() -> Self {
+        Self {
+            events: "boundary".to_string(),
+            index: None,
+        }
+    }
+}
+
+impl fmt::Display for FlushOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{}[{index}]", self.events),
+            None => write!(f, "{}", self.events),
+        }
+    }
+}
+
+/// A flush of a value to a channel, with its origin.
+#[derive(Clone, Debug)]
+pub struct Flush<T> {
+    pub value: T,
+    pub direction: FlushDirection,
+    pub origin: FlushOrigin,
+}
+
+/// A value whose pushes and pulls do not cancel out, with the origins of all
+/// its flushes.
+#[derive(Clone, Debug)]
+pub struct UnbalancedValue<T> {
+    pub value: T,
+    /// Number of pushes minus number of pulls.
+    pub multiplicity: isize,
+    pub pushes: Vec<FlushOrigin>,
+    pub pulls: Vec<FlushOrigin>,
+}
+
+/// Report of an unbalanced channel, listing its unbalanced values.
+#[derive(Clone, Debug, thiserror::Error)]
+pub struct ChannelImbalance<T: Debug> {
+    pub channel: &'static str,
+    pub unbalanced: Vec<UnbalancedValue<T>>,
+}
+
+impl<T: Debug> fmt::Display for ChannelImbalance<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unbalanced {} channel, with {} unbalanced values:",
+            self.channel,
+            self.unbalanced.len()
+        )?;
+        for value in &self.unbalanced {
+            let origins = |origins: &[FlushOrigin]| {
+                origins
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            write!(
+                f,
+                "\n  {:?} with multiplicity {}, pushed by [{}], pulled by [{}]",
+                value.value,
+                value.multiplicity,
+                origins(&value.pushes),
+                origins(&value.pulls)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the flushes of a channel with their origins, to diagnose an
+/// imbalance.
+#[derive(Debug)]
+pub struct FlushRecorder<T> {
+    channel: &'static str,
+    flushes: HashMap<T, (Vec<FlushOrigin>, Vec<FlushOrigin>)>,
+}
+
+impl<T: Hash + Eq + Debug> FlushRecorder<T> {
+    pub fn new(channel: &'static str) -> Self {
+        Self {
+            channel,
+            flushes: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, flush: Flush<T>) {
+        let (pushes, pulls) = self.flushes.entry(flush.value).or_default();
+        match flush.direction {
+            FlushDirection::Push => pushes.push(flush.origin),
+            FlushDirection::Pull => pulls.push(flush.origin),
+        }
+    }
+
+    /// Checks that the recorded flushes are balanced, reporting the unbalanced
+    /// values otherwise, sorted with `sort_key`.
+    pub fn check<K: Ord>(self, sort_key: impl Fn(&T) -> K) -> Result<(), ChannelImbalance<T>> {
+        let mut unbalanced = self
+            .flushes
+            .into_iter()
+            .filter(|(_, (pushes, pulls))| pushes.len() != pulls.len())
+            .map(|(value, (pushes, pulls))| UnbalancedValue {
+                value,
+                multiplicity: pushes.len() as isize - pulls.len() as isize,
+                pushes,
+                pulls,
+            })
+            .collect::<Vec<_>>();
+        if unbalanced.is_empty() {
+            return Ok(());
+        }
+        unbalanced.sort_by_key(|value| sort_key(&value.value));
+        Err(ChannelImbalance {
+            channel: self.channel,
+            unbalanced,
+        })
+    }
+}
@@ -0,0 +1,214 @@
+//! Custom instructions, extending the VM with instructions defined outside of
+//! this crate, e.g. domain-specific accelerators.
+//!
+//! A custom instruction is identified by an opcode of at least
+//! [`CUSTOM_OPCODE_BASE`], so as not to collide with any built-in [`Opcode`],
+//! and is written `CUSTOM #opcode, arg0, arg1, arg2` in assembly, each
+//! argument being a slot or an immediate. Its semantics are given by a
+//! [`CustomInstruction`] registered in [`CustomInstructions`], which records
+//! [`CustomEvent`]s in the trace. Proving them requires a matching table on
+//! the prover side.
+//!
+//! [`Opcode`]: crate::Opcode
+
+use std::{any::Any, collections::BTreeMap, fmt, sync::Arc};
+
+use binius_m3::builder::{B16, B32};
+
+use super::{FieldPc, InterpreterChannels, InterpreterError};
+use crate::event::EventContext;
+
+/// Smallest opcode of a custom instruction.
+pub const CUSTOM_OPCODE_BASE: u16 = 0x8000;
+
+/// An event recorded by a [`CustomInstruction`].
+///
+/// The events of a custom instruction are stored type-erased in the trace, and
+/// can be recovered by downcasting them, e.g. with
+/// [`PetraTrace::custom_events`](crate::PetraTrace::custom_events).
+pub trait CustomEvent: Any + fmt::Debug + Send + Sync {
+    /// Returns the field PC, the frame pointer and the timestamp at which the
+    /// instruction was executed.
+    ///
+    /// As custom instructions cannot alter the control flow, these are enough
+    /// to apply the flushing rules of the event.
+    fn state(&self) -> (B32, u32, u32);
+}
+
+/// Applies the flushing rules of a custom event to the interpreter channels,
+/// like [`Event::fire`](crate::event::Event::fire) for a non-jump instruction.
+pub(crate) fn fire_custom_event(event: &dyn CustomEvent, channels: &mut InterpreterChannels) {
+    let (pc, fp, timestamp) = event.state();
+    channels.state_channel.pull((pc, fp, timestamp));
+    channels
+        .state_channel
+        .push((FieldPc::from(pc).next().field(), fp, timestamp));
+}
+
+/// An instruction defined outside of this crate.
+pub trait CustomInstruction: Send + Sync + 'static {
+    /// Returns the opcode of the instruction, of at least
+    /// [`CUSTOM_OPCODE_BASE`].
+    fn opcode(&self) -> u16;
+
+    /// Executes the instruction, like
+    /// [`Event::generate`](crate::event::Event::generate).
+    ///
+    /// The instruction must record its event with
+    /// [`EventContext::record_custom_event`], and advance the PC with
+    /// [`EventContext::incr_counters`]. It cannot alter the control flow.
+    fn generate(
+        &self,
+        ctx: &mut EventContext,
+        arg0: B16,
+        arg1: B16,
+        arg2: B16,
+    ) -> Result<(), InterpreterError>;
+}
+
+/// Registry of the custom instructions available to a guest, by opcode.
+#[derive(Clone, Default)]
+pub struct CustomInstructions {
+    instructions: BTreeMap<u16, Arc<dyn CustomInstruction>>,
+}
+
+impl CustomInstructions {
+    pub const fn new() -> Self {
+        Self {
+            instructions: BTreeMap::new(),
+        }
+    }
+
+    /// Registers `instruction` under its opcode, replacing any instruction
+    /// previously registered under it.
+    ///
+    /// # Panics
+    /// If the opcode of `instruction` is below [`CUSTOM_OPCODE_BASE`].
+    pub fn register<I: CustomInstruction>(&mut self, instruction: I) {
+        let opcode = instruction.opcode();
+        assert!(
+            opcode >= CUSTOM_OPCODE_BASE,
+            "Custom opcode 0x{opcode:x} is below 0x{CUSTOM_OPCODE_BASE:x}"
+        );
+        self.instructions.insert(opcode, Arc::new(instruction));
+    }
+
+    /// Returns the instruction registered under `opcode`, if any.
+    pub fn get(&self, opcode: u16) -> Option<Arc<dyn CustomInstruction>> {
+        self.instructions.get(&opcode).cloned()
+    }
+}
+
+impl fmt::Debug for CustomInstructions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.instructions.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execution::FramePointer, isa::GenericISA, Assembler, GenerationOptions, Memory, PetraTrace,
+        ValueRom,
+    };
+
+    /// Sets FP[dst] to the bitwise negation of FP[src1] ^ FP[src2].
+    struct Xnor;
+
+    #[derive(Debug)]
+    struct XnorEvent {
+        pc: B32,
+        fp: FramePointer,
+        timestamp: u32,
+        dst_val: u32,
+    }
+
+    impl CustomEvent for XnorEvent {
+        fn state(&self) -> (B32, u32, u32) {
+            (self.pc, *self.fp, self.timestamp)
+        }
+    }
+
+    impl CustomInstruction for Xnor {
+        fn opcode(&self) -> u16 {
+            CUSTOM_OPCODE_BASE
+        }
+
+        fn generate(
+            &self,
+            ctx: &mut EventContext,
+            dst: B16,
+            src1: B16,
+            src2: B16,
+        ) -> Result<(), InterpreterError> {
+            let src1_val = ctx.vrom_read::<u32>(ctx.addr(src1.val()))?;
+            let src2_val = ctx.vrom_read::<u32>(ctx.addr(src2.val()))?;
+            let dst_val = !(src1_val ^ src2_val);
+            ctx.vrom_write(ctx.addr(dst.val()), dst_val)?;
+
+            let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+            ctx.record_custom_event(
+                self.opcode(),
+                XnorEvent {
+                    pc: field_pc,
+                    fp,
+                    timestamp,
+                    dst_val,
+                },
+            );
+            ctx.incr_counters();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_custom_instruction() {
+        let code = "#[framesize(0x8)]
+        _start:
+            LDI.W @2, #0xff00
+            LDI.W @3, #0x0ff0
+            CUSTOM #0x8000, @4, @2, @3
+            CUSTOM! #0x8000, @5, @4, @4
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let mut custom_instructions = CustomInstructions::new();
+        custom_instructions.register(Xnor);
+        let (trace, boundary_values) = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            program.frame_sizes,
+            GenerationOptions::default().custom_instructions(custom_instructions),
+        )
+        .unwrap();
+
+        assert_eq!(trace.vrom().read::<u32>(4).unwrap(), !0xf0f0);
+        assert_eq!(trace.vrom().read::<u32>(5).unwrap(), u32::MAX);
+        // The prover-only instruction is executed, but not recorded.
+        let events = trace.custom_events::<XnorEvent>(CUSTOM_OPCODE_BASE);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].dst_val, !0xf0f0);
+        trace.validate(boundary_values);
+    }
+
+    #[test]
+    fn test_unregistered_custom_instruction() {
+        let code = "#[framesize(0x8)]
+        _start:
+            CUSTOM #0x8001, #0, #0, #0
+            RET
+        ";
+        let program = Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        let mut interpreter =
+            crate::execution::Interpreter::new(Box::new(GenericISA), program.frame_sizes);
+        let mut trace = PetraTrace::new(memory);
+        assert!(matches!(
+            interpreter.step(&mut trace),
+            Err(InterpreterError::InvalidOpcode)
+        ));
+    }
+}
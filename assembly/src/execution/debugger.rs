@@ -0,0 +1,347 @@
+//! Interactive debugging of program executions.
+//!
+//! A [`Debugger`] drives an [`Interpreter`] one instruction at a time, stops at
+//! breakpoints set by label or field PC, and gives access to the machine state
+//! in between: PC, FP, timestamp and VROM contents, as well as the source
+//! location of the next instruction when the program has debug info.
+
+use std::collections::{HashMap, HashSet};
+
+use binius_field::Field;
+use binius_m3::builder::B32;
+
+use super::{Backtrace, FramePointer, Interpreter, InterpreterError, PetraTrace};
+use crate::{
+    isa::ISA,
+    memory::{vrom::VromValueT, MemoryError},
+    AssembledProgram, DebugInfo, Memory, SourceLocation, ValueRom,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DebuggerError {
+    #[error("Unknown label: {0}")]
+    UnknownLabel(String),
+
+    #[error(transparent)]
+    Interpreter(#[from] InterpreterError),
+}
+
+/// Why the execution stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// A single instruction was executed.
+    Step,
+    /// The next instruction, at the given field PC, has a breakpoint. It has
+    /// not been executed yet.
+    Breakpoint(B32),
+    /// The next instruction is at the requested field PC.
+    Reached(B32),
+    /// The program halted.
+    Halted,
+}
+
+/// Step-by-step executor of a program. See the [module-level
+/// documentation](self) for details.
+pub struct Debugger {
+    interpreter: Interpreter,
+    trace: PetraTrace,
+    /// Field PCs of the program labels.
+    labels: HashMap<String, B32>,
+    /// Source locations of the program instructions, if known.
+    debug_info: Option<DebugInfo>,
+    breakpoints: HashSet<B32>,
+}
+
+impl Debugger {
+    /// Prepares the execution of `program`, stopped before its first
+    /// instruction.
+    ///
+    /// `init_values` are the initial values of the VROM, the first two of them
+    /// being the return PC and FP of the entry point.
+    pub fn new(
+        isa: Box<dyn ISA>,
+        program: &AssembledProgram,
+        init_values: &[u32],
+    ) -> Result<Self, DebuggerError> {
+        let memory = Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(init_values),
+        )
+        .with_ram_data(&program.data);
+        let interpreter = Interpreter::new(isa, program.frame_sizes.clone());
+        let mut trace = PetraTrace::new(memory);
+
+        // Allocate a frame for the entry point, as `Interpreter::run` does.
+        if let Some(entry) = trace.prom().first() {
+            let field_pc = entry.field_pc;
+            interpreter.allocate_new_frame(&mut trace, field_pc)?;
+        }
+
+        Ok(Self {
+            interpreter,
+            trace,
+            labels: program
+                .labels
+                .iter()
+                .map(|(name, (field_pc, _, _))| (name.clone(), *field_pc))
+                .collect(),
+            debug_info: program.debug_info.clone(),
+            breakpoints: HashSet::new(),
+        })
+    }
+
+    /// Returns whether the program has halted.
+    pub const fn is_halted(&self) -> bool {
+        self.interpreter.is_halted()
+    }
+
+    /// Returns the field PC of the next instruction, or zero once halted.
+    pub fn pc(&self) -> B32 {
+        if self.is_halted() {
+            return B32::zero();
+        }
+        self.trace
+            .prom()
+            .as_slice()
+            .get(self.interpreter.prom_index as usize)
+            .map_or(B32::zero(), |instruction| instruction.field_pc)
+    }
+
+    /// Returns the PROM index of the next instruction.
+    pub const fn prom_index(&self) -> u32 {
+        self.interpreter.prom_index
+    }
+
+    /// Returns the source location of the next instruction, if the program
+    /// has debug info.
+    pub fn location(&self) -> Option<&SourceLocation> {
+        if self.is_halted() {
+            return None;
+        }
+        self.debug_info.as_ref()?.location(self.prom_index())
+    }
+
+    /// Returns the current frame pointer.
+    pub const fn fp(&self) -> FramePointer {
+        self.interpreter.fp
+    }
+
+    /// Returns the current system timestamp.
+    pub const fn timestamp(&self) -> u32 {
+        self.interpreter.timestamp
+    }
+
+    /// Reads the value at `slot` in the current frame, without recording a
+    /// VROM access.
+    pub fn read_slot<T: VromValueT>(&self, slot: u32) -> Result<T, MemoryError> {
+        self.read_vrom(self.interpreter.fp.addr(slot))
+    }
+
+    /// Reads the value at the VROM address `addr`, without recording a VROM
+    /// access.
+    pub fn read_vrom<T: VromValueT>(&self, addr: u32) -> Result<T, MemoryError> {
+        self.trace.vrom().peek(addr)
+    }
+
+    /// Formats the VROM words set so far, grouped by the frames allocated
+    /// since the start of the execution.
+    pub fn dump_frames(&self) -> String {
+        self.trace
+            .vrom()
+            .dump_frames(&self.trace.frame_map(), self.interpreter.frame_sizes())
+    }
+
+    /// Returns the chain of live function frames, innermost first.
+    pub fn backtrace(&self) -> Backtrace {
+        let mut backtrace = self.interpreter.backtrace(self.pc());
+        for frame in &mut backtrace.frames {
+            frame.label = self
+                .labels
+                .iter()
+                .find(|(_, field_pc)| **field_pc == frame.function)
+                .map(|(name, _)| name.clone());
+        }
+        backtrace
+    }
+
+    /// Returns the trace built so far.
+    pub const fn trace(&self) -> &PetraTrace {
+        &self.trace
+    }
+
+    /// Consumes the debugger, returning the trace built so far.
+    pub fn into_trace(self) -> PetraTrace {
+        self.trace
+    }
+
+    /// Sets a breakpoint on the instruction at `field_pc`.
+    pub fn set_breakpoint(&mut self, field_pc: B32) {
+        self.breakpoints.insert(field_pc);
+    }
+
+    /// Clears the breakpoint on the instruction at `field_pc`, returning
+    /// whether there was one.
+    pub fn clear_breakpoint(&mut self, field_pc: B32) -> bool {
+        self.breakpoints.remove(&field_pc)
+    }
+
+    /// Sets a breakpoint on the instruction following `label`.
+    pub fn set_label_breakpoint(&mut self, label: &str) -> Result<(), DebuggerError> {
+        let field_pc = self.label_pc(label)?;
+        self.set_breakpoint(field_pc);
+        Ok(())
+    }
+
+    /// Clears the breakpoint on the instruction following `label`, returning
+    /// whether there was one.
+    pub fn clear_label_breakpoint(&mut self, label: &str) -> Result<bool, DebuggerError> {
+        let field_pc = self.label_pc(label)?;
+        Ok(self.clear_breakpoint(field_pc))
+    }
+
+    /// Returns the field PC of `label`.
+    pub fn label_pc(&self, label: &str) -> Result<B32, DebuggerError> {
+        self.labels
+            .get(label)
+            .copied()
+            .ok_or_else(|| DebuggerError::UnknownLabel(label.to_string()))
+    }
+
+    /// Executes the next instruction.
+    pub fn step(&mut self) -> Result<StopReason, DebuggerError> {
+        if !self.is_halted() {
+            self.interpreter.step(&mut self.trace)?;
+        }
+        Ok(if self.is_halted() {
+            StopReason::Halted
+        } else {
+            StopReason::Step
+        })
+    }
+
+    /// Runs until the next breakpoint, or until the program halts.
+    pub fn run(&mut self) -> Result<StopReason, DebuggerError> {
+        self.run_while(|_| true)
+    }
+
+    /// Runs until the next instruction is at `field_pc`, a breakpoint is
+    /// reached or the program halts.
+    pub fn run_until(&mut self, field_pc: B32) -> Result<StopReason, DebuggerError> {
+        self.run_while(|pc| pc != field_pc)
+            .map(|reason| match reason {
+                StopReason::Step => StopReason::Reached(field_pc),
+                reason => reason,
+            })
+    }
+
+    /// Executes at least one instruction, then keeps going while `keep_going`
+    /// holds for the next field PC. Returns [`StopReason::Step`] when stopped
+    /// by `keep_going`.
+    fn run_while<F>(&mut self, keep_going: F) -> Result<StopReason, DebuggerError>
+    where
+        F: Fn(B32) -> bool,
+    {
+        loop {
+            if self.step()? == StopReason::Halted {
+                return Ok(StopReason::Halted);
+            }
+            let pc = self.pc();
+            if !keep_going(pc) {
+                return Ok(StopReason::Step);
+            }
+            if self.breakpoints.contains(&pc) {
+                return Ok(StopReason::Breakpoint(pc));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, AssemblerOptions};
+
+    #[test]
+    fn test_debugger() {
+        let program = Assembler::from_code(include_str!("../../../examples/func_call.asm"))
+            .expect("The program should assemble");
+        let mut debugger = Debugger::new(Box::new(GenericISA), &program, &[0, 0]).unwrap();
+        assert_eq!(debugger.pc(), B32::ONE);
+        assert_eq!(debugger.step().unwrap(), StopReason::Step);
+        assert_eq!(debugger.read_slot::<u32>(0).unwrap(), 0);
+
+        // Stop when entering the called function.
+        debugger.set_label_breakpoint("add_two_numbers").unwrap();
+        let target = debugger.label_pc("add_two_numbers").unwrap();
+        assert_eq!(debugger.run().unwrap(), StopReason::Breakpoint(target));
+        assert_eq!(debugger.pc(), target);
+        assert_ne!(*debugger.fp(), 0);
+        // The caller's frame is linked in the callee's one.
+        assert_eq!(debugger.read_slot::<u32>(1).unwrap(), 0);
+        let backtrace = debugger.backtrace();
+        assert_eq!(backtrace.frames.len(), 2);
+        assert_eq!(
+            backtrace.frames[0].label.as_deref(),
+            Some("add_two_numbers")
+        );
+        assert_eq!(backtrace.frames[1].label.as_deref(), Some("func_call"));
+
+        assert!(debugger.clear_label_breakpoint("add_two_numbers").unwrap());
+        assert!(matches!(
+            debugger.set_label_breakpoint("nowhere"),
+            Err(DebuggerError::UnknownLabel(_))
+        ));
+        assert_eq!(debugger.run().unwrap(), StopReason::Halted);
+        assert!(debugger.is_halted());
+        assert_eq!(debugger.pc(), B32::zero());
+        assert_eq!(debugger.step().unwrap(), StopReason::Halted);
+    }
+
+    #[test]
+    fn test_run_until() {
+        let code = "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #3
+                ADDI @3, @2, #1
+            middle:
+                ADDI @4, @3, #1
+                RET";
+        let program = Assembler::from_code(code).unwrap();
+        let mut debugger = Debugger::new(Box::new(GenericISA), &program, &[0, 0]).unwrap();
+
+        let middle = debugger.label_pc("middle").unwrap();
+        assert_eq!(
+            debugger.run_until(middle).unwrap(),
+            StopReason::Reached(middle)
+        );
+        assert_eq!(debugger.read_slot::<u32>(3).unwrap(), 4);
+        assert!(debugger.read_slot::<u32>(4).is_err());
+        // Without debug info, the location is unknown.
+        assert_eq!(debugger.location(), None);
+
+        // Running until an instruction that is never reached halts.
+        assert_eq!(debugger.run_until(B32::ONE).unwrap(), StopReason::Halted);
+        assert_eq!(debugger.read_slot::<u32>(4).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_debugger_location() {
+        let code = "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #3
+                ADDI @3, @2, #1
+                RET";
+        let options = AssemblerOptions {
+            debug_info: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(code, &options).unwrap();
+        let mut debugger = Debugger::new(Box::new(GenericISA), &program, &[0, 0]).unwrap();
+
+        assert_eq!(debugger.location().map(|loc| loc.line), Some(3));
+        debugger.step().unwrap();
+        assert_eq!(debugger.location().map(|loc| loc.line), Some(4));
+        assert_eq!(debugger.run().unwrap(), StopReason::Halted);
+        assert_eq!(debugger.location(), None);
+    }
+}
@@ -3,6 +3,7 @@
 //! accordingly.
 
 use std::{
+    cell::Cell,
     collections::HashMap,
     fmt::Debug,
     ops::{Deref, DerefMut},
@@ -10,14 +11,18 @@ use std::{
 
 use binius_field::{BinaryField, PackedField};
 use binius_m3::builder::{B16, B32};
+use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use crate::{
     assembler::LabelsFrameSizes,
     context::EventContext,
-    execution::{PetraTrace, StateChannel},
+    execution::{
+        backtrace::{Backtrace, CallFrame},
+        CustomInstruction, CustomInstructions, HostFunctions, PetraTrace, StateChannel,
+    },
     isa::{GenericISA, ISA},
-    memory::{Memory, MemoryError},
+    memory::{Memory, MemoryError, ProgramRom},
     opcodes::Opcode,
 };
 
@@ -31,7 +36,8 @@ pub struct InterpreterChannels {
 
 /// A wrapper around a `u32` representing the frame pointer (FP) in VROM for
 /// type-safety and easy memory-address access.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct FramePointer(u32);
 
 impl FramePointer {
@@ -62,6 +68,76 @@ impl DerefMut for FramePointer {
     }
 }
 
+/// A program counter (PC) in the multiplicative group of `B32`.
+///
+/// The first instruction of a program is at PC 1, and each following one at
+/// the PC of the previous one times the multiplicative generator. Prover-only
+/// instructions share the PC of the next instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldPc(B32);
+
+impl FieldPc {
+    /// PC of the first instruction.
+    pub const START: Self = Self(<B32 as binius_field::Field>::ONE);
+
+    /// Returns the PC of the instruction at position `index` in the program,
+    /// not counting prover-only instructions.
+    pub fn from_index(index: usize) -> Self {
+        Self(G.pow(index as u64))
+    }
+
+    /// Moves to the PC of the next instruction.
+    #[inline(always)]
+    pub fn incr(&mut self) {
+        self.0 *= G;
+    }
+
+    /// Moves `n` instructions forward.
+    pub fn advance(&mut self, n: usize) {
+        self.0 *= G.pow(n as u64);
+    }
+
+    /// Returns the PC of the next instruction.
+    #[inline(always)]
+    pub fn next(self) -> Self {
+        Self(self.0 * G)
+    }
+
+    /// Returns the PC as a field element.
+    pub const fn field(self) -> B32 {
+        self.0
+    }
+
+    /// Returns the PC as a `u32`.
+    pub fn val(self) -> u32 {
+        self.0.val()
+    }
+}
+
+impl Default for FieldPc {
+    fn default() -> Self {
+        Self::START
+    }
+}
+
+impl From<B32> for FieldPc {
+    fn from(pc: B32) -> Self {
+        Self(pc)
+    }
+}
+
+impl From<FieldPc> for B32 {
+    fn from(pc: FieldPc) -> Self {
+        pc.0
+    }
+}
+
+impl std::fmt::Display for FieldPc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Main program executor, used to build a [`PetraTrace`] from a program's PROM.
 ///
 /// The interpreter manages control flow, memory accesses, instruction execution
@@ -79,12 +155,32 @@ pub struct Interpreter {
     pub(crate) pc: u32,
     pub(crate) prom_index: u32,
     pub(crate) fp: FramePointer,
-    /// The system timestamp. Only RAM operations increase it.
+    /// The system timestamp. Only RAM operations and COMMIT increase it.
     pub timestamp: u32,
+    /// Number of instructions executed so far.
+    pub(crate) cycles: u64,
+    /// Maximum number of instructions to execute before aborting, if any.
+    max_cycles: Option<u64>,
+    /// Chain of live function frames, the innermost last.
+    pub(crate) call_stack: Vec<CallFrame>,
+    /// Host functions callable through `ECALL!`.
+    pub(crate) host_fns: HostFunctions,
+    /// Custom instructions, by opcode.
+    custom_instructions: CustomInstructions,
+    /// Whether the gadget events are derived from the opcode events after the
+    /// execution, instead of being recorded along with them.
+    pub(crate) defer_gadget_events: bool,
+    /// Whether the events and memory accesses are recorded in the trace, which
+    /// pure executions skip, see [`Interpreter::run_only`].
+    pub(crate) record_events: bool,
+    /// Nondeterministic words read in order by `READ_ADVICE!`.
+    advice_tape: Vec<u32>,
+    /// Position of the next word to read on the advice tape.
+    advice_position: usize,
+    /// Whether every slot accessed by an instruction is checked to lie within
+    /// the frame of its function.
+    frame_checks: bool,
     frames: LabelsFrameSizes,
-    // Temporary HashMap storing the mapping between binary field elements that appear in the PROM
-    // and their associated PROM index and integer PC.
-    pc_field_to_index_pc: HashMap<B32, (u32, u32)>,
 }
 
 impl Default for Interpreter {
@@ -95,8 +191,17 @@ impl Default for Interpreter {
             prom_index: 0,
             fp: FramePointer(0),
             timestamp: 0,
+            cycles: 0,
+            max_cycles: None,
+            call_stack: Vec::new(),
+            host_fns: HostFunctions::new(),
+            custom_instructions: CustomInstructions::new(),
+            defer_gadget_events: false,
+            record_events: true,
+            advice_tape: Vec::new(),
+            advice_position: 0,
+            frame_checks: false,
             frames: HashMap::new(),
-            pc_field_to_index_pc: HashMap::new(),
         }
     }
 }
@@ -105,9 +210,11 @@ impl Default for Interpreter {
 /// arguments to be used by this operation.
 pub type Instruction = [B16; 4];
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct InterpreterInstruction {
+    #[serde(with = "crate::util::serde_b16_array")]
     pub instruction: Instruction,
+    #[serde(with = "crate::util::serde_b32")]
     pub field_pc: B32,
     /// Optional advice. Used for providing the PROM index and the discrete
     /// logarithm in base `B32::MULTIPLICATIVE_GENERATOR` of some group
@@ -155,12 +262,87 @@ pub enum InterpreterError {
     BadPc,
     #[error("The arguments to this opcode are invalid.")]
     InvalidInput,
+    #[error("The opcode {0} cannot be prover-only.")]
+    CannotBeProverOnly(Opcode),
+    #[error("The opcode {0} must be prover-only.")]
+    MustBeProverOnly(Opcode),
     #[error("A memory access failed with error {0}")]
     MemoryError(MemoryError),
     #[error("The instruction requires an advice, but none was provided.")]
     MissingAdvice(Opcode),
-    #[error("An exception occurred.")]
+    #[error("The advice does not locate the jump target {0}.")]
+    InvalidAdvice(B32),
+    #[error("The jump target {0} is not in the PROM.")]
+    InvalidJumpTarget(B32),
+    #[error(
+        "The instruction at PC {field_pc} accesses slot @{slot}, beyond the frame size {frame_size:#x} of its function at PC {function}."
+    )]
+    SlotOutOfFrame {
+        /// Field PC of the offending instruction.
+        field_pc: B32,
+        /// Offset of the accessed slot.
+        slot: u32,
+        /// Field PC of the entry of the current function.
+        function: B32,
+        /// Declared frame size of the current function.
+        frame_size: u16,
+    },
+    #[error("No host function is registered under the identifier {0}.")]
+    UnknownHostFunction(u16),
+    #[error("The advice tape is exhausted after {0} words.")]
+    AdviceTapeExhausted(usize),
+    #[error("The ABORT message of {len} bytes at address {ptr:#x} exceeds the VROM.")]
+    AbortMessageOutOfBounds {
+        /// Address of the message.
+        ptr: u32,
+        /// Length of the message, in bytes.
+        len: u32,
+    },
+    #[error("An exception occurred: {0}")]
     Exception(InterpreterException),
+    #[error("The execution exceeded {max_cycles} cycles, at PC {field_pc} with FP {fp}.")]
+    CycleLimitExceeded {
+        /// The maximum number of cycles that was configured.
+        max_cycles: u64,
+        /// Field PC of the next instruction to execute.
+        field_pc: B32,
+        /// Current frame pointer.
+        fp: u32,
+    },
+    #[error("{error}\nBacktrace:\n{backtrace}")]
+    WithBacktrace {
+        /// The error that stopped the execution.
+        error: Box<InterpreterError>,
+        /// The live function frames when it occurred.
+        backtrace: Backtrace,
+    },
+}
+
+impl InterpreterError {
+    /// Returns the underlying error, without its backtrace.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::WithBacktrace { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// Returns the backtrace attached to this error, if any.
+    pub const fn backtrace(&self) -> Option<&Backtrace> {
+        match self {
+            Self::WithBacktrace { backtrace, .. } => Some(backtrace),
+            _ => None,
+        }
+    }
+
+    /// Names the frames of the attached backtrace, if any, after the labels of
+    /// the assembled program.
+    pub fn symbolize(mut self, labels: &HashMap<String, (B32, u32, u32)>) -> Self {
+        if let Self::WithBacktrace { backtrace, .. } = &mut self {
+            backtrace.symbolize(labels);
+        }
+        self
+    }
 }
 
 impl From<MemoryError> for InterpreterError {
@@ -169,26 +351,152 @@ impl From<MemoryError> for InterpreterError {
     }
 }
 
-#[derive(Debug)]
-pub enum InterpreterException {}
+/// Exceptions raised by the guest program, which stop the execution and are
+/// reported to the host.
+#[derive(Debug, thiserror::Error)]
+pub enum InterpreterException {
+    /// The guest aborted through the `ABORT` instruction.
+    #[error("The guest aborted at PC {pc}: {message}")]
+    Abort {
+        /// Integer PC of the `ABORT` instruction.
+        pc: u32,
+        /// Field PC of the `ABORT` instruction.
+        field_pc: B32,
+        /// Message provided by the guest.
+        message: String,
+    },
+    /// A guest assertion, `ASSERT_EQ` or `ASSERT_ZERO`, failed.
+    #[error("The guest assertion {opcode} failed at PC {pc}: {left:#x} != {right:#x}")]
+    AssertionFailed {
+        /// Integer PC of the assertion.
+        pc: u32,
+        /// Field PC of the assertion.
+        field_pc: B32,
+        /// Opcode of the assertion.
+        opcode: Opcode,
+        /// Value of the first operand.
+        left: u32,
+        /// Value expected by the assertion.
+        right: u32,
+    },
+    /// A guest range check, `RANGE_CHECK`, failed.
+    #[error("The guest range check failed at PC {pc}: {value:#x} does not fit in {bits} bits")]
+    RangeCheckFailed {
+        /// Integer PC of the range check.
+        pc: u32,
+        /// Field PC of the range check.
+        field_pc: B32,
+        /// Value of the checked operand.
+        value: u32,
+        /// Number of bits the value should fit in.
+        bits: u16,
+    },
+    /// A guest Merkle path verification, `MERKLE_VERIFY`, failed.
+    #[error(
+        "The guest Merkle path of index {index} and depth {depth} failed to verify at PC {pc}"
+    )]
+    MerkleVerifyFailed {
+        /// Integer PC of the verification.
+        pc: u32,
+        /// Field PC of the verification.
+        field_pc: B32,
+        /// Index of the verified leaf.
+        index: u32,
+        /// Depth of the verified path.
+        depth: u32,
+    },
+}
 
 impl Interpreter {
-    pub(crate) const fn new(
-        isa: Box<dyn ISA>,
-        frames: LabelsFrameSizes,
-        pc_field_to_index_pc: HashMap<B32, (u32, u32)>,
-    ) -> Self {
+    pub(crate) const fn new(isa: Box<dyn ISA>, frames: LabelsFrameSizes) -> Self {
         Self {
             isa,
             pc: 1,
             prom_index: 0,
             fp: FramePointer(0),
             timestamp: 0,
+            cycles: 0,
+            max_cycles: None,
+            call_stack: Vec::new(),
+            host_fns: HostFunctions::new(),
+            custom_instructions: CustomInstructions::new(),
+            defer_gadget_events: false,
+            record_events: true,
+            advice_tape: Vec::new(),
+            advice_position: 0,
+            frame_checks: false,
             frames,
-            pc_field_to_index_pc,
         }
     }
 
+    /// Bounds the number of instructions to execute, the execution failing
+    /// with [`InterpreterError::CycleLimitExceeded`] past it.
+    pub(crate) fn with_max_cycles(mut self, max_cycles: Option<u64>) -> Self {
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    /// Sets the host functions callable through `ECALL!`.
+    pub(crate) fn with_host_fns(mut self, host_fns: HostFunctions) -> Self {
+        self.host_fns = host_fns;
+        self
+    }
+
+    /// Sets the custom instructions executable by the guest.
+    pub(crate) fn with_custom_instructions(
+        mut self,
+        custom_instructions: CustomInstructions,
+    ) -> Self {
+        self.custom_instructions = custom_instructions;
+        self
+    }
+
+    /// Defers the generation of the gadget events to
+    /// [`PetraTrace::derive_gadget_events`], after the execution.
+    pub(crate) const fn with_deferred_gadget_events(mut self) -> Self {
+        self.defer_gadget_events = true;
+        self
+    }
+
+    /// Sets the advice tape, read word by word by `READ_ADVICE!`.
+    pub(crate) fn with_advice_tape(mut self, advice_tape: Vec<u32>) -> Self {
+        self.advice_tape = advice_tape;
+        self.advice_position = 0;
+        self
+    }
+
+    /// Checks that every slot accessed by an instruction lies within the
+    /// declared frame size of its function, the execution failing with
+    /// [`InterpreterError::SlotOutOfFrame`] otherwise.
+    pub(crate) const fn with_frame_checks(mut self) -> Self {
+        self.frame_checks = true;
+        self
+    }
+
+    /// Returns the next word of the advice tape, moving past it.
+    pub(crate) fn next_advice(&mut self) -> Result<u32, InterpreterError> {
+        let word = self.advice_tape.get(self.advice_position).copied().ok_or(
+            InterpreterError::AdviceTapeExhausted(self.advice_tape.len()),
+        )?;
+        self.advice_position += 1;
+        Ok(word)
+    }
+
+    /// Registers `function` under `id`, to be called by the guest through
+    /// `ECALL! #id, @base`. See [`HostFunctions::register`].
+    pub fn register_host_fn<F>(&mut self, id: u16, function: F)
+    where
+        F: Fn(&mut EventContext, u16) -> Result<(), InterpreterError> + Send + Sync + 'static,
+    {
+        self.host_fns.register(id, function);
+    }
+
+    /// Registers `instruction` under its opcode, to be executed by the guest
+    /// through `CUSTOM #opcode, ...`. See [`CustomInstructions::register`].
+    pub fn register_custom_instruction<I: CustomInstruction>(&mut self, instruction: I) {
+        self.custom_instructions.register(instruction);
+    }
+
     #[inline(always)]
     pub(crate) const fn incr_pc(&mut self) {
         if self.pc == u32::MAX {
@@ -199,38 +507,56 @@ impl Interpreter {
         }
     }
 
+    #[inline(always)]
+    pub(crate) const fn incr_timestamp(&mut self) {
+        self.timestamp += 1;
+    }
+
     #[inline(always)]
     pub(crate) const fn incr_prom_index(&mut self) {
         self.prom_index += 1;
     }
 
     #[inline(always)]
-    /// Jump to a specific target in the PROM, given as a field element
-    pub(crate) fn jump_to(&mut self, target: B32) {
+    /// Jump to a specific target in `prom`, given as a field element
+    pub(crate) fn jump_to(
+        &mut self,
+        prom: &ProgramRom,
+        target: B32,
+    ) -> Result<(), InterpreterError> {
         if target == B32::zero() {
             self.pc = 0;
         } else {
-            let (prom_index, pc) = *self
-                .pc_field_to_index_pc
-                .get(&target)
-                .expect("This target should have been parsed.");
-            debug_assert!(G.pow(pc as u64 - 1) == target);
+            let (prom_index, pc) = prom
+                .locate(FieldPc::from(target))
+                .ok_or(InterpreterError::InvalidJumpTarget(target))?;
+            debug_assert!(FieldPc::from_index(pc as usize - 1).field() == target);
             self.prom_index = prom_index;
             self.pc = pc;
         }
+        Ok(())
     }
 
     #[inline(always)]
     /// Jump to a specific target in the PROM given as the discrete
     /// logarithm of the field pc.
-    pub(crate) fn jump_to_u32(&mut self, target: B32, advice: (u32, u32)) {
+    pub(crate) fn jump_to_u32(
+        &mut self,
+        target: B32,
+        advice: (u32, u32),
+    ) -> Result<(), InterpreterError> {
         let (prom_index, pc) = advice;
-        debug_assert!(
-            target == B32::MULTIPLICATIVE_GENERATOR.pow((pc - 1) as u64),
-            "The advice must be the discrete logarithm of the target address in base `B32::MULTIPLICATIVE_GENERATOR`"
-        );
+        // The advice must be the discrete logarithm of the target address in
+        // base `B32::MULTIPLICATIVE_GENERATOR`. Checking it is costly, so it is
+        // left to the prover in release builds.
+        if pc == 0
+            || cfg!(debug_assertions) && target != FieldPc::from_index(pc as usize - 1).field()
+        {
+            return Err(InterpreterError::InvalidAdvice(target));
+        }
         self.prom_index = prom_index;
         self.pc = pc;
+        Ok(())
     }
 
     #[inline(always)]
@@ -240,23 +566,95 @@ impl Interpreter {
 
     #[instrument(level = "info", skip_all)]
     pub fn run(&mut self, memory: Memory) -> Result<PetraTrace, InterpreterError> {
+        self.run_with_hook(memory, |_, _, _| {})
+    }
+
+    /// Runs the program like [`Interpreter::run`], but without recording any
+    /// event, nor the accesses to the memory and the instruction counters
+    /// needed to prove the execution.
+    ///
+    /// The instructions share their semantics with the trace-generating
+    /// execution, so the returned trace holds the same memory and outputs.
+    /// This suits pure executions, e.g. to compute the outputs of a program,
+    /// which are spared the allocation of the events.
+    #[instrument(level = "info", skip_all)]
+    pub fn run_only(&mut self, memory: Memory) -> Result<PetraTrace, InterpreterError> {
+        self.record_events = false;
+        let result = self.run(memory);
+        self.record_events = true;
+        result
+    }
+
+    /// Runs the program like [`Interpreter::run`], calling `observer` after
+    /// each executed instruction with the interpreter state, the trace built so
+    /// far and the instruction that was just executed.
+    pub(crate) fn run_with_observer<F>(
+        &mut self,
+        memory: Memory,
+        mut observer: F,
+    ) -> Result<PetraTrace, InterpreterError>
+    where
+        F: FnMut(&Self, &PetraTrace, &InterpreterInstruction),
+    {
+        self.run_with_hook(memory, |interpreter, trace, instruction| {
+            observer(interpreter, trace, instruction)
+        })
+    }
+
+    /// Runs the program like [`Interpreter::run_with_observer`], but `hook`
+    /// may also modify the trace built so far, e.g. to take its events out.
+    ///
+    /// The hook is inlined in the execution loop, so that runs without one,
+    /// such as [`Interpreter::run`], don't pay for the statistics and
+    /// profiles recorded by others.
+    pub(crate) fn run_with_hook<F>(
+        &mut self,
+        memory: Memory,
+        mut hook: F,
+    ) -> Result<PetraTrace, InterpreterError>
+    where
+        F: FnMut(&Self, &mut PetraTrace, &InterpreterInstruction),
+    {
         let mut trace = PetraTrace::new(memory);
 
-        let field_pc = trace.prom()[self.pc as usize - 1].field_pc;
+        let field_pc = trace
+            .prom()
+            .as_slice()
+            .get(self.pc as usize - 1)
+            .ok_or(InterpreterError::BadPc)?
+            .field_pc;
         // Start by allocating a frame for the initial label.
         self.allocate_new_frame(&mut trace, field_pc)?;
         loop {
-            match self.step(&mut trace) {
-                Ok(_) => {}
-                Err(error) => {
-                    match error {
-                        InterpreterError::Exception(_exc) => {} //TODO: handle exception
-                        critical_error => {
-                            panic!("{critical_error:?}");
-                        } //TODO: properly format error
-                    }
+            let instruction = trace
+                .prom()
+                .as_slice()
+                .get(self.prom_index as usize)
+                .cloned();
+            let field_pc = instruction
+                .as_ref()
+                .map_or(B32::zero(), |instruction| instruction.field_pc);
+            if let Some(max_cycles) = self.max_cycles {
+                if self.cycles >= max_cycles {
+                    return Err(self.with_backtrace(
+                        InterpreterError::CycleLimitExceeded {
+                            max_cycles,
+                            field_pc,
+                            fp: *self.fp,
+                        },
+                        field_pc,
+                    ));
                 }
             }
+            let result = self.step(&mut trace);
+            if let Some(instruction) = instruction {
+                hook(self, &mut trace, &instruction);
+            }
+            // Exceptions raised by the guest and invalid programs are both
+            // reported to the host, rather than panicking on untrusted PROMs.
+            if let Err(error) = result {
+                return Err(self.with_backtrace(error, field_pc));
+            }
             if self.is_halted() {
                 return Ok(trace);
             }
@@ -276,7 +674,8 @@ impl Interpreter {
             prover_only,
         } = trace.prom()[self.prom_index as usize];
         let [opcode, arg0, arg1, arg2] = instruction;
-        if !prover_only {
+        self.cycles += 1;
+        if !prover_only && self.record_events {
             trace.record_instruction(self.pc);
             // Special handling for B32Muli
             if opcode == Opcode::B32Muli.get_field_elt() {
@@ -284,31 +683,173 @@ impl Interpreter {
             }
         }
 
-        debug_assert_eq!(field_pc, G.pow(self.pc as u64 - 1));
+        #[cfg(debug_assertions)]
+        if field_pc != FieldPc::from_index(self.pc as usize - 1).field() {
+            return Err(InterpreterError::BadPc);
+        }
 
-        let opcode = Opcode::try_from(opcode.val()).map_err(|_| InterpreterError::InvalidOpcode)?;
+        let Some((opcode, generate)) = self.isa.dispatch_table().get(opcode.val()) else {
+            if let Ok(opcode) = Opcode::try_from(opcode.val()) {
+                // Known opcodes missing from the table are not supported by the
+                // ISA.
+                return Err(match opcode {
+                    Opcode::Invalid | Opcode::Bz => InterpreterError::InvalidOpcode,
+                    _ => InterpreterError::UnsupportedOpcode(opcode),
+                });
+            }
+            let custom_instruction = self
+                .custom_instructions
+                .get(opcode.val())
+                .ok_or(InterpreterError::InvalidOpcode)?;
+            self.enter_entry_point(field_pc);
+            let frame_size = self.checked_frame_size();
+            let mut ctx = EventContext {
+                interpreter: self,
+                trace,
+                field_pc,
+                advice,
+                prover_only,
+                frame_size,
+                out_of_frame: Cell::new(None),
+            };
+            let result = custom_instruction.generate(&mut ctx, arg0, arg1, arg2);
+            let out_of_frame = ctx.out_of_frame.get();
+            return self.check_frame_access(field_pc, out_of_frame).and(result);
+        };
         #[cfg(debug_assertions)]
         {
-            if !self.isa.is_supported(opcode) {
-                return Err(InterpreterError::UnsupportedOpcode(opcode));
-            }
             if opcode.is_verifier_only() && prover_only {
-                panic!("{opcode:?} cannot be prover-only.");
+                return Err(InterpreterError::CannotBeProverOnly(opcode));
             }
-            if (opcode == Opcode::Alloci || opcode == Opcode::Allocv) && !prover_only {
-                panic!("{opcode:?} must be prover-only.");
+            if opcode.is_prover_only() && !prover_only {
+                return Err(InterpreterError::MustBeProverOnly(opcode));
             }
         }
 
+        self.enter_entry_point(field_pc);
+
+        let frame_size = self.checked_frame_size();
         let mut ctx = EventContext {
             interpreter: self,
             trace,
             field_pc,
             advice,
             prover_only,
+            frame_size,
+            out_of_frame: Cell::new(None),
+        };
+
+        tracing::trace!(
+            %opcode,
+            arg0 = arg0.val(),
+            arg1 = arg1.val(),
+            arg2 = arg2.val(),
+            "Executing instruction"
+        );
+        let result = generate(&mut ctx, arg0, arg1, arg2);
+        let out_of_frame = ctx.out_of_frame.get();
+        // An out-of-frame access is reported first, as it is the likely cause
+        // of any error raised by the instruction.
+        self.check_frame_access(field_pc, out_of_frame)?;
+        result?;
+        self.update_call_stack(trace, opcode, field_pc);
+        Ok(())
+    }
+
+    /// Returns the frame size of each function, by field PC of its entry.
+    pub(crate) const fn frame_sizes(&self) -> &LabelsFrameSizes {
+        &self.frames
+    }
+
+    /// Returns the frame size of the current function, if frame checks are
+    /// enabled and it is known.
+    fn checked_frame_size(&self) -> Option<u16> {
+        if !self.frame_checks {
+            return None;
+        }
+        let function = self.call_stack.last()?.function;
+        self.frames.get(&function).copied()
+    }
+
+    /// Fails the instruction at `field_pc` if it accessed the slot
+    /// `out_of_frame`, beyond the frame of the current function.
+    fn check_frame_access(
+        &self,
+        field_pc: B32,
+        out_of_frame: Option<u32>,
+    ) -> Result<(), InterpreterError> {
+        let (Some(slot), Some(frame)) = (out_of_frame, self.call_stack.last()) else {
+            return Ok(());
+        };
+        Err(InterpreterError::SlotOutOfFrame {
+            field_pc,
+            slot,
+            function: frame.function,
+            frame_size: self
+                .frames
+                .get(&frame.function)
+                .copied()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Records the entry point in the call stack, when executing its first
+    /// instruction at `field_pc`.
+    fn enter_entry_point(&mut self, field_pc: B32) {
+        if self.call_stack.is_empty() {
+            self.call_stack.push(CallFrame {
+                function: field_pc,
+                fp: self.fp,
+                call_site: B32::zero(),
+            });
+        }
+    }
+
+    /// Updates the call stack after the execution of `opcode` at `field_pc`.
+    fn update_call_stack(&mut self, trace: &PetraTrace, opcode: Opcode, field_pc: B32) {
+        let callee = |interpreter: &Self| CallFrame {
+            function: trace
+                .prom()
+                .as_slice()
+                .get(interpreter.prom_index as usize)
+                .map_or(B32::zero(), |instruction| instruction.field_pc),
+            fp: interpreter.fp,
+            call_site: field_pc,
         };
+        match opcode {
+            Opcode::Calli | Opcode::Callv => self.call_stack.push(callee(self)),
+            Opcode::Taili | Opcode::Tailv => {
+                // The callee replaces the current frame and returns to its caller.
+                let call_site = self
+                    .call_stack
+                    .pop()
+                    .map_or(B32::zero(), |frame| frame.call_site);
+                self.call_stack.push(CallFrame {
+                    call_site,
+                    ..callee(self)
+                });
+            }
+            Opcode::Ret => {
+                self.call_stack.pop();
+            }
+            Opcode::Halt => self.call_stack.clear(),
+            _ => {}
+        }
+    }
 
-        opcode.generate_event(&mut ctx, arg0, arg1, arg2)
+    /// Returns the backtrace of the live function frames, the innermost one
+    /// executing the instruction at `field_pc`.
+    pub(crate) fn backtrace(&self, field_pc: B32) -> Backtrace {
+        Backtrace::capture(&self.call_stack, field_pc)
+    }
+
+    /// Attaches the current backtrace to `error`, raised by the instruction at
+    /// `field_pc`.
+    fn with_backtrace(&self, error: InterpreterError, field_pc: B32) -> InterpreterError {
+        InterpreterError::WithBacktrace {
+            error: Box::new(error),
+            backtrace: self.backtrace(field_pc),
+        }
     }
 
     pub(crate) fn allocate_new_frame(
@@ -331,7 +872,7 @@ mod tests {
     use super::*;
     use crate::test_util::{code_to_prom, collatz_orbits, get_binary_slot};
     use crate::util::init_logger;
-    use crate::ValueRom;
+    use crate::{GenerationOptions, ValueRom};
 
     #[test]
     fn test_petra() {
@@ -344,8 +885,7 @@ mod tests {
         frames.insert(B32::ONE, 12);
 
         let (trace, boundary_values) =
-            PetraTrace::generate(Box::new(GenericISA), memory, frames, HashMap::new())
-                .expect("Ouch!");
+            PetraTrace::generate(Box::new(GenericISA), memory, frames).expect("Ouch!");
         trace.validate(boundary_values);
     }
 
@@ -515,7 +1055,7 @@ mod tests {
         let initial_val = 5;
         let (expected_evens, expected_odds) = collatz_orbits(initial_val);
 
-        let mut prom = code_to_prom(&instructions_prover_only);
+        let mut prom = Vec::from(code_to_prom(&instructions_prover_only));
         // Set the expected advice for the first TAILI
         prom[4].advice = Some((collatz_prom_index, collatz_advice));
         // Set the expected advice for BNZ
@@ -530,19 +1070,15 @@ mod tests {
         // return PC = 0, return FP = 0, n = 5
         let vrom = ValueRom::new_with_init_vals(&[0, 0, initial_val]);
 
-        let memory = Memory::new(prom, vrom);
+        let memory = Memory::new(prom.into(), vrom);
 
         // TODO: We could build this with compiler hints.
         let mut frames_args_size = HashMap::new();
         frames_args_size.insert(B32::ONE, 10);
 
-        let (traces, boundary_values) = PetraTrace::generate(
-            Box::new(GenericISA),
-            memory,
-            frames_args_size,
-            HashMap::new(), // We only need the advice for the instructions
-        )
-        .expect("Trace generation should not fail.");
+        let (traces, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory, frames_args_size)
+                .expect("Trace generation should not fail.");
 
         traces.validate(boundary_values);
 
@@ -594,4 +1130,246 @@ mod tests {
         // Check return value abs address.
         assert_eq!(traces.vrom().read::<u32>(3).unwrap(), 4);
     }
+
+    #[test]
+    fn test_max_cycles() {
+        let code = "#[framesize(0x4)]
+        _start:
+            LDI.W @2, #1
+        loop:
+            J loop
+        ";
+        let program = crate::Assembler::from_code(code).unwrap();
+        let loop_pc = program.labels["loop"].0;
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let err = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            program.frame_sizes,
+            GenerationOptions::default().max_cycles(Some(100)),
+        )
+        .err()
+        .expect("The execution should not terminate");
+
+        match err.root() {
+            InterpreterError::CycleLimitExceeded {
+                max_cycles,
+                field_pc,
+                fp,
+            } => {
+                assert_eq!(*max_cycles, 100);
+                assert_eq!(*field_pc, loop_pc);
+                assert_eq!(*fp, 0);
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_programs() {
+        let zero = B16::zero();
+        let run = |code: &[(Instruction, bool)], init_values: &[u32]| {
+            let memory = Memory::new(
+                code_to_prom(code),
+                ValueRom::new_with_init_vals(init_values),
+            );
+            let frames = HashMap::from([(B32::ONE, 12)]);
+            PetraTrace::generate(Box::new(GenericISA), memory, frames)
+                .err()
+                .expect("The program is invalid")
+        };
+
+        // Returning to a PC outside of the PROM.
+        let ret = [([Opcode::Ret.get_field_elt(), zero, zero, zero], false)];
+        let err = run(&ret, &[12345, 0]);
+        assert!(matches!(
+            err.root(),
+            InterpreterError::InvalidJumpTarget(target) if *target == B32::new(12345)
+        ));
+
+        // `BZ` is only an event of `BNZ`, not an instruction.
+        let bz = [([Opcode::Bz.get_field_elt(), zero, zero, zero], false)];
+        assert!(matches!(
+            run(&bz, &[0, 0]).root(),
+            InterpreterError::InvalidOpcode
+        ));
+
+        // `ALLOCI` must be prover-only, `RET` must not.
+        let alloci = [([Opcode::Alloci.get_field_elt(), zero, zero, zero], false)];
+        assert!(matches!(
+            run(&alloci, &[0, 0]).root(),
+            InterpreterError::MustBeProverOnly(Opcode::Alloci)
+        ));
+        let ret = [
+            ([Opcode::Ret.get_field_elt(), zero, zero, zero], true),
+            ([Opcode::Ret.get_field_elt(), zero, zero, zero], false),
+        ];
+        assert!(matches!(
+            run(&ret, &[0, 0]).root(),
+            InterpreterError::CannotBeProverOnly(Opcode::Ret)
+        ));
+    }
+
+    #[test]
+    fn test_frame_checks() {
+        // The callee writes one slot past its frame, into the return PC of the
+        // entry frame.
+        let code = "#[framesize(0x8)]
+        _start:
+            ALLOCI! @3, #8
+            CALLI callee, @3
+            RET
+
+        #[framesize(0x8)]
+        callee:
+            LDI.W @2, #1
+            LDI.W @8, #2
+            RET
+        ";
+        let program = crate::Assembler::from_code(code).unwrap();
+        let callee_pc = program.labels["callee"].0;
+        let memory = || Memory::new(program.prom.clone(), ValueRom::new_with_init_vals(&[0, 0]));
+
+        let err = PetraTrace::generate(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+            .err()
+            .expect("The write aliases the entry frame");
+        assert!(matches!(
+            err.root(),
+            InterpreterError::MemoryError(MemoryError::VromRewrite(0, 0, 2))
+        ));
+
+        let err = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory(),
+            program.frame_sizes.clone(),
+            GenerationOptions::default().frame_checks(),
+        )
+        .err()
+        .expect("The write is out of the frame");
+        match err.root() {
+            InterpreterError::SlotOutOfFrame {
+                field_pc,
+                slot,
+                function,
+                frame_size,
+            } => {
+                assert_eq!(*field_pc, callee_pc * G);
+                assert_eq!(*slot, 8);
+                assert_eq!(*function, callee_pc);
+                assert_eq!(*frame_size, 8);
+            }
+            err => panic!("Unexpected error: {err:?}"),
+        }
+
+        // Programs within their frames run the same with the checks.
+        let fixed = crate::Assembler::from_code(&code.replace("@8, #2", "@7, #2")).unwrap();
+        let memory = Memory::new(fixed.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            fixed.frame_sizes,
+            GenerationOptions::default().frame_checks(),
+        )
+        .expect("The program stays within its frames");
+    }
+
+    #[test]
+    fn test_backtrace() {
+        // The guest aborts two calls deep, the second one being a tail call.
+        let code = "#[framesize(0x4)]
+        _start:
+            ALLOCI! @3, #4
+            CALLI outer, @3
+            RET
+
+        #[framesize(0x4)]
+        outer:
+            ALLOCI! @3, #8
+            TAILI inner, @3
+
+        #[framesize(0x8)]
+        inner:
+            FP @2, #4
+            LDI.W @3, #2
+            LDI.W @4, #17007
+            ABORT @2, @3
+            RET
+        ";
+        let program = crate::Assembler::from_code(code).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+
+        let err = PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes)
+            .err()
+            .expect("The execution should abort")
+            .symbolize(&program.labels);
+
+        assert!(matches!(err.root(), InterpreterError::Exception(_)));
+        let frames = &err.backtrace().expect("A backtrace is attached").frames;
+        let names = frames
+            .iter()
+            .map(|frame| frame.label.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        // `outer` tail-called `inner`, so its frame is gone.
+        assert_eq!(names, ["inner", "_start"]);
+        // The innermost frame is at the `ABORT`, its caller at the `CALLI`.
+        // Prover-only instructions share the PC of the next instruction.
+        assert_eq!(frames[0].pc, G.pow(6));
+        assert_eq!(frames[1].pc, B32::ONE);
+        assert_eq!(frames[1].fp, FramePointer(0));
+        assert!(err.to_string().contains("#0 inner at PC"));
+    }
+
+    #[test]
+    fn test_run_only() {
+        let code = "#[framesize(0x8)]
+        _start:
+            LDI.W @2, #64
+            LDI.W @3, #-2
+            SW @3, @2, #0
+            LW @4, @2, #0
+            SRAI @5, @4, #3
+            COMMIT @5
+            RET
+        ";
+        let collatz = include_str!("../../../examples/collatz.asm");
+        for (code, init_values) in [(code, vec![0, 0]), (collatz, vec![0, 0, 27])] {
+            let program = crate::Assembler::from_code(code).unwrap();
+            let memory = || {
+                Memory::new(
+                    program.prom.clone(),
+                    ValueRom::new_with_init_vals(&init_values),
+                )
+            };
+
+            let (trace, boundary_values) =
+                PetraTrace::generate(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+                    .unwrap();
+            let (executed, executed_boundary_values) =
+                PetraTrace::execute(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+                    .unwrap();
+
+            // The execution reaches the same state, without recording anything
+            // on the way.
+            assert!(trace.event_counts().iter().any(|(_, count)| *count > 0));
+            assert!(executed.event_counts().iter().all(|(_, count)| *count == 0));
+            assert!(executed.instruction_counter.iter().all(|count| *count == 0));
+            assert!(executed.ram().access_history().is_empty());
+            assert_eq!(executed.vrom_size(), trace.vrom_size());
+            for addr in 0..trace.vrom_size() as u32 {
+                assert_eq!(
+                    executed.vrom().peek::<u32>(addr).ok(),
+                    trace.vrom().peek::<u32>(addr).ok()
+                );
+            }
+            assert_eq!(executed.ram().data(), trace.ram().data());
+            assert_eq!(executed_boundary_values.final_pc, boundary_values.final_pc);
+            assert_eq!(executed_boundary_values.final_fp, boundary_values.final_fp);
+            assert_eq!(
+                executed_boundary_values.timestamp,
+                boundary_values.timestamp
+            );
+            assert_eq!(executed_boundary_values.outputs, boundary_values.outputs);
+        }
+    }
 }
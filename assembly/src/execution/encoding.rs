@@ -0,0 +1,114 @@
+//! Stable binary encoding of instructions.
+//!
+//! An [`Instruction`] is encoded as four `u16` words, in the layout of version
+//! [`INSTRUCTION_ENCODING_VERSION`]:
+//! - word 0 holds the opcode, either a built-in [`Opcode`] or a custom opcode
+//!   of at least [`CUSTOM_OPCODE_BASE`],
+//! - words 1 to 3 hold the arguments, in the order of [`Opcode::operands`], the
+//!   unused ones being zero.
+//!
+//! Immediates wider than 16 bits span two arguments, low half first, or an
+//! additional instruction carrying the upper half in its first argument, e.g.
+//! for `B32_MULI`.
+//!
+//! The layout only changes along with the version, so that encoded programs,
+//! e.g. in the binary format of an
+//! [`AssembledProgram`](crate::AssembledProgram), remain readable.
+
+use binius_m3::builder::B16;
+
+use super::{Instruction, CUSTOM_OPCODE_BASE};
+use crate::Opcode;
+
+/// Version of the instruction layout described in the
+/// [module-level documentation](self).
+pub const INSTRUCTION_ENCODING_VERSION: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    #[error("Invalid opcode 0x{0:x}")]
+    InvalidOpcode(u16),
+}
+
+/// Encoding and decoding of an [`Instruction`] in its stable binary layout.
+pub trait InstructionEncoding: Sized {
+    /// Builds the instruction of the opcode `opcode` with the arguments
+    /// `args`.
+    fn from_parts(opcode: Opcode, args: [u16; 3]) -> Self;
+
+    /// Encodes the instruction.
+    fn encode(&self) -> [u16; 4];
+
+    /// Decodes an instruction, checking that its opcode is a valid built-in or
+    /// custom one.
+    fn decode(words: [u16; 4]) -> Result<Self, DecodeError>;
+}
+
+impl InstructionEncoding for Instruction {
+    fn from_parts(opcode: Opcode, [arg0, arg1, arg2]: [u16; 3]) -> Self {
+        [opcode as u16, arg0, arg1, arg2].map(B16::new)
+    }
+
+    fn encode(&self) -> [u16; 4] {
+        self.map(|word| word.val())
+    }
+
+    fn decode(words: [u16; 4]) -> Result<Self, DecodeError> {
+        let opcode = words[0];
+        match Opcode::try_from(opcode) {
+            Ok(Opcode::Invalid | Opcode::Bz) => return Err(DecodeError::InvalidOpcode(opcode)),
+            Ok(_) => {}
+            Err(_) if opcode >= CUSTOM_OPCODE_BASE => {}
+            Err(_) => return Err(DecodeError::InvalidOpcode(opcode)),
+        }
+        Ok(words.map(B16::new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::VariantArray;
+
+    use super::*;
+    use crate::Assembler;
+
+    #[test]
+    fn test_round_trip() {
+        for &opcode in Opcode::VARIANTS {
+            if matches!(opcode, Opcode::Invalid | Opcode::Bz) {
+                continue;
+            }
+            let instruction = Instruction::from_parts(opcode, [1, 0x1234, 0xffff]);
+            let words = instruction.encode();
+            assert_eq!(words, [opcode as u16, 1, 0x1234, 0xffff]);
+            assert_eq!(Instruction::decode(words), Ok(instruction));
+        }
+
+        // Assembled programs decode to themselves.
+        let program = Assembler::from_code(
+            "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #0x12345678
+                B32_MULI @3, @2, #65537
+                CUSTOM #0x8001, @2, @3, #4
+                RET
+            ",
+        )
+        .unwrap();
+        for entry in &program.prom {
+            let words = entry.instruction.encode();
+            assert_eq!(Instruction::decode(words), Ok(entry.instruction));
+        }
+    }
+
+    #[test]
+    fn test_invalid_opcode() {
+        for opcode in [Opcode::Invalid as u16, Opcode::Bz as u16, 0x7fff] {
+            assert_eq!(
+                Instruction::decode([opcode, 0, 0, 0]),
+                Err(DecodeError::InvalidOpcode(opcode))
+            );
+        }
+        assert!(Instruction::decode([CUSTOM_OPCODE_BASE, 0, 0, 0]).is_ok());
+    }
+}
@@ -0,0 +1,55 @@
+//! Host functions, callable by the guest through the `ECALL!` instruction.
+//!
+//! Some computations are much cheaper to check than to perform, e.g. finding a
+//! hash preimage or the inverse of a divisor. A guest can delegate them to the
+//! host by calling a function registered in [`HostFunctions`], which writes its
+//! results to VROM. Since `ECALL!` is prover-only, these results are ordinary
+//! nondeterministic witness values: the guest must constrain them with regular
+//! instructions before relying on them.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use super::InterpreterError;
+use crate::event::EventContext;
+
+/// A function executed on the host when the guest issues an `ECALL!`.
+///
+/// It is given the context of the current instruction and the `base` slot
+/// operand of the `ECALL!`, from which it reads its arguments and writes its
+/// results in the current frame, e.g. with [`EventContext::addr`].
+pub type HostFn =
+    dyn Fn(&mut EventContext, u16) -> Result<(), InterpreterError> + Send + Sync + 'static;
+
+/// Registry of the host functions available to a guest, by identifier.
+#[derive(Clone, Default)]
+pub struct HostFunctions {
+    functions: BTreeMap<u16, Arc<HostFn>>,
+}
+
+impl HostFunctions {
+    pub const fn new() -> Self {
+        Self {
+            functions: BTreeMap::new(),
+        }
+    }
+
+    /// Registers `function` under `id`, replacing any function previously
+    /// registered under it.
+    pub fn register<F>(&mut self, id: u16, function: F)
+    where
+        F: Fn(&mut EventContext, u16) -> Result<(), InterpreterError> + Send + Sync + 'static,
+    {
+        self.functions.insert(id, Arc::new(function));
+    }
+
+    /// Returns the function registered under `id`, if any.
+    pub fn get(&self, id: u16) -> Option<Arc<HostFn>> {
+        self.functions.get(&id).cloned()
+    }
+}
+
+impl fmt::Debug for HostFunctions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.functions.keys()).finish()
+    }
+}
@@ -3,10 +3,32 @@
 //! The emulator is responsible for interpreting and running
 //! parsed programs and managing the virtual machine state.
 
+pub mod backtrace;
+pub mod call_convention;
 pub mod channels;
+pub mod custom;
+pub mod debugger;
 pub mod emulator;
+pub mod encoding;
+pub mod host;
+pub mod repl;
+pub mod segment;
+pub mod sink;
+pub mod stats;
+pub mod timeline;
 pub mod trace;
 
+pub use backtrace::{Backtrace, StackFrame};
+pub use call_convention::CallConventionError;
 pub use channels::*;
+pub use custom::{CustomEvent, CustomInstruction, CustomInstructions, CUSTOM_OPCODE_BASE};
+pub use debugger::{Debugger, DebuggerError, StopReason};
 pub use emulator::*;
-pub use trace::PetraTrace;
+pub use encoding::{DecodeError, InstructionEncoding, INSTRUCTION_ENCODING_VERSION};
+pub use host::{HostFn, HostFunctions};
+pub use repl::{Evaluation, Repl, ReplError};
+pub use segment::{check_segments, Segment, SegmentBoundary, SegmentError};
+pub use sink::{EventCounter, EventSink};
+pub use stats::{AllCycleStats, ProfileReport};
+pub use timeline::Timeline;
+pub use trace::{GenerationOptions, PetraTrace};
@@ -0,0 +1,459 @@
+//! Interactive, line-by-line execution of programs.
+//!
+//! A [`Repl`] evaluates the lines typed by a user. Lines starting with `:` are
+//! commands stepping through the execution and inspecting the machine state,
+//! listed in [`HELP`].
+//!
+//! Without a loaded program, the REPL is in scratch mode: any other line is an
+//! instruction or a label, appended to a scratch program which is executed up
+//! to its end. The scratch program is re-executed from its start after each
+//! line, so that instructions can branch to the labels of previous lines.
+
+use std::collections::{BTreeMap, HashSet};
+
+use binius_m3::builder::B32;
+
+use super::{Debugger, DebuggerError, StopReason};
+use crate::{
+    isa::ISA, memory::MemoryError, AssembledProgram, Assembler, AssemblerError, Disassembler,
+    DisassemblerError,
+};
+
+/// Frame size of the entry point of the scratch program.
+const SCRATCH_FRAME_SIZE: u16 = 0x100;
+
+/// Maximum number of instructions executed when re-executing the scratch
+/// program, to get out of infinite loops.
+const MAX_SCRATCH_STEPS: u64 = 1 << 20;
+
+/// Help message of the REPL commands.
+pub const HELP: &str = "\
+Commands:
+  :step [n]         Execute the next n instructions (default 1)
+  :continue         Run until a breakpoint or the end of the program
+  :break <target>   Set a breakpoint on a label or a field PC
+  :delete <target>  Clear the breakpoint on a label or a field PC
+  :slot <slot>...   Print slots of the current frame
+  :vrom <addr>...   Print VROM words
+  :state            Print the PC, FP and timestamp
+  :backtrace        Print the live function frames
+  :frames           Print the VROM words set so far, by frame
+  :program          Print the program
+  :reset            Restart the execution, clearing the scratch program
+  :help             Print this help
+  :quit             Exit
+In scratch mode, other lines are instructions or labels, executed right away.";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplError {
+    #[error("Unknown command :{0}, type :help for the list of commands")]
+    UnknownCommand(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Instructions can only be typed in scratch mode, without a loaded program")]
+    ProgramLoaded,
+
+    #[error("No instruction was executed yet")]
+    NotStarted,
+
+    #[error("The scratch program didn't reach its end after {0} steps")]
+    StepLimit(u64),
+
+    #[error(transparent)]
+    Assembler(#[from] AssemblerError),
+
+    #[error(transparent)]
+    Disassembler(#[from] DisassemblerError),
+
+    #[error(transparent)]
+    Debugger(#[from] DebuggerError),
+}
+
+/// Result of the evaluation of a line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Evaluation {
+    /// Text to print back to the user, possibly empty.
+    Output(String),
+    /// The user asked to exit.
+    Quit,
+}
+
+/// Program under execution.
+struct Session {
+    debugger: Debugger,
+    /// Disassembled instructions, by PROM index.
+    instructions: BTreeMap<usize, String>,
+}
+
+impl Session {
+    /// Returns whether the execution went past the last instruction, which
+    /// only happens to scratch programs.
+    fn at_end(&self) -> bool {
+        self.debugger.prom_index() as usize >= self.debugger.trace().prom().len()
+    }
+}
+
+/// Read-eval-print loop over a [`Debugger`]. See the [module-level
+/// documentation](self) for details.
+pub struct Repl {
+    isa: Box<dyn Fn() -> Box<dyn ISA>>,
+    init_values: Vec<u32>,
+    /// Loaded program, or `None` in scratch mode.
+    program: Option<AssembledProgram>,
+    /// Lines of the scratch program.
+    scratch: Vec<String>,
+    /// Field PCs of the breakpoints, kept across executions.
+    breakpoints: HashSet<B32>,
+    session: Option<Session>,
+}
+
+impl Repl {
+    /// Creates a REPL in scratch mode.
+    ///
+    /// `isa` builds the instruction set of each execution, and `init_values`
+    /// are the initial values of the VROM, as for [`Debugger::new`].
+    pub fn new(isa: impl Fn() -> Box<dyn ISA> + 'static, init_values: &[u32]) -> Self {
+        Self {
+            isa: Box::new(isa),
+            init_values: init_values.to_vec(),
+            program: None,
+            scratch: Vec::new(),
+            breakpoints: HashSet::new(),
+            session: None,
+        }
+    }
+
+    /// Creates a REPL single-stepping `program`, stopped before its first
+    /// instruction.
+    pub fn with_program(
+        isa: impl Fn() -> Box<dyn ISA> + 'static,
+        program: AssembledProgram,
+        init_values: &[u32],
+    ) -> Result<Self, ReplError> {
+        let mut repl = Self::new(isa, init_values);
+        repl.session = Some(repl.start(&program)?);
+        repl.program = Some(program);
+        Ok(repl)
+    }
+
+    /// Evaluates a line typed by the user.
+    pub fn eval(&mut self, line: &str) -> Result<Evaluation, ReplError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(Evaluation::Output(String::new()));
+        }
+        let Some(command) = line.strip_prefix(':') else {
+            return self.eval_scratch(line).map(Evaluation::Output);
+        };
+
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let args = words.collect::<Vec<_>>();
+        let output = match name {
+            "s" | "step" => {
+                let count = match args.first() {
+                    Some(count) => parse_u32(count)?,
+                    None => 1,
+                };
+                let session = self.session_mut()?;
+                let mut reason = StopReason::Step;
+                for _ in 0..count {
+                    if session.at_end() {
+                        break;
+                    }
+                    reason = session.debugger.step()?;
+                    if reason == StopReason::Halted {
+                        break;
+                    }
+                }
+                self.describe(reason)?
+            }
+            "c" | "continue" => {
+                let session = self.session_mut()?;
+                let reason = if session.at_end() {
+                    StopReason::Step
+                } else {
+                    session.debugger.run()?
+                };
+                self.describe(reason)?
+            }
+            "b" | "break" => {
+                let field_pc = self.parse_target(&args)?;
+                self.breakpoints.insert(field_pc);
+                if let Some(session) = &mut self.session {
+                    session.debugger.set_breakpoint(field_pc);
+                }
+                format!("Breakpoint set at PC {:#010x}", field_pc.val())
+            }
+            "d" | "delete" => {
+                let field_pc = self.parse_target(&args)?;
+                if let Some(session) = &mut self.session {
+                    session.debugger.clear_breakpoint(field_pc);
+                }
+                if self.breakpoints.remove(&field_pc) {
+                    format!("Breakpoint cleared at PC {:#010x}", field_pc.val())
+                } else {
+                    format!("No breakpoint at PC {:#010x}", field_pc.val())
+                }
+            }
+            "slot" | "p" => {
+                let debugger = &self.session()?.debugger;
+                self.print_words(&args, "@", |slot| debugger.read_slot::<u32>(slot))?
+            }
+            "vrom" => {
+                let debugger = &self.session()?.debugger;
+                self.print_words(&args, "VROM ", |addr| debugger.read_vrom::<u32>(addr))?
+            }
+            "state" => self.describe(StopReason::Step)?,
+            "bt" | "backtrace" => self.session()?.debugger.backtrace().to_string(),
+            "frames" => self.session()?.debugger.dump_frames(),
+            "program" => match &self.program {
+                Some(program) => Disassembler::from_program(program).disassemble()?,
+                None => self.scratch_source(),
+            },
+            "reset" => {
+                self.scratch.clear();
+                self.session = match self.program.take() {
+                    Some(program) => {
+                        let session = self.start(&program)?;
+                        self.program = Some(program);
+                        Some(session)
+                    }
+                    None => None,
+                };
+                "Execution restarted".to_string()
+            }
+            "h" | "help" => HELP.to_string(),
+            "q" | "quit" => return Ok(Evaluation::Quit),
+            _ => return Err(ReplError::UnknownCommand(name.to_string())),
+        };
+        Ok(Evaluation::Output(output))
+    }
+
+    /// Returns the debugger of the current execution, if any.
+    pub fn debugger(&self) -> Option<&Debugger> {
+        self.session.as_ref().map(|session| &session.debugger)
+    }
+
+    /// Appends `line` to the scratch program and executes it.
+    fn eval_scratch(&mut self, line: &str) -> Result<String, ReplError> {
+        if self.program.is_some() {
+            return Err(ReplError::ProgramLoaded);
+        }
+        self.scratch.push(line.to_string());
+        // Labels and annotations can't be assembled until an instruction
+        // follows them.
+        if line.ends_with(':') || line.starts_with("#[") {
+            return Ok(String::new());
+        }
+
+        match self.run_scratch() {
+            Ok(session) => {
+                self.session = Some(session);
+                self.describe(StopReason::Step)
+            }
+            Err(err) => {
+                self.scratch.pop();
+                Err(err)
+            }
+        }
+    }
+
+    /// Executes the scratch program from its start up to its end.
+    fn run_scratch(&self) -> Result<Session, ReplError> {
+        let program = Assembler::from_code(&self.scratch_source())?;
+        let mut session = self.start(&program)?;
+        let mut steps = 0;
+        while !session.debugger.is_halted() && !session.at_end() {
+            if steps == MAX_SCRATCH_STEPS {
+                return Err(ReplError::StepLimit(steps));
+            }
+            session.debugger.step()?;
+            steps += 1;
+        }
+        Ok(session)
+    }
+
+    /// Returns the source of the scratch program.
+    fn scratch_source(&self) -> String {
+        let mut source = format!("#[framesize({SCRATCH_FRAME_SIZE:#x})]\n_start:\n");
+        for line in &self.scratch {
+            source.push_str(line);
+            source.push('\n');
+        }
+        source
+    }
+
+    /// Starts a new execution of `program`.
+    fn start(&self, program: &AssembledProgram) -> Result<Session, ReplError> {
+        let mut debugger = Debugger::new((self.isa)(), program, &self.init_values)?;
+        for &field_pc in &self.breakpoints {
+            debugger.set_breakpoint(field_pc);
+        }
+        let instructions = Disassembler::from_program(program)
+            .instructions()?
+            .into_iter()
+            .collect();
+        Ok(Session {
+            debugger,
+            instructions,
+        })
+    }
+
+    fn session(&self) -> Result<&Session, ReplError> {
+        self.session.as_ref().ok_or(ReplError::NotStarted)
+    }
+
+    fn session_mut(&mut self) -> Result<&mut Session, ReplError> {
+        self.session.as_mut().ok_or(ReplError::NotStarted)
+    }
+
+    /// Parses the breakpoint target of a command, either a label or a field
+    /// PC.
+    fn parse_target(&self, args: &[&str]) -> Result<B32, ReplError> {
+        let [target] = args else {
+            return Err(ReplError::InvalidArgument(
+                "Expected a label or a field PC".to_string(),
+            ));
+        };
+        match parse_u32(target) {
+            Ok(field_pc) => Ok(B32::new(field_pc)),
+            Err(_) => Ok(self.session()?.debugger.label_pc(target)?),
+        }
+    }
+
+    /// Prints the VROM words read by `read` at each of the addresses or
+    /// slots in `args`.
+    fn print_words<F>(&self, args: &[&str], prefix: &str, read: F) -> Result<String, ReplError>
+    where
+        F: Fn(u32) -> Result<u32, MemoryError>,
+    {
+        if args.is_empty() {
+            return Err(ReplError::InvalidArgument(
+                "Expected at least one address".to_string(),
+            ));
+        }
+        let mut lines = Vec::with_capacity(args.len());
+        for arg in args {
+            let offset = parse_u32(arg.trim_start_matches('@'))?;
+            lines.push(match read(offset) {
+                Ok(value) => format!("{prefix}{offset} = {value} ({value:#x})"),
+                Err(err) => format!("{prefix}{offset}: {err}"),
+            });
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Describes the state of the execution after it stopped for `reason`.
+    fn describe(&self, reason: StopReason) -> Result<String, ReplError> {
+        let session = self.session()?;
+        let Session {
+            debugger,
+            instructions,
+        } = session;
+        if reason == StopReason::Halted || debugger.is_halted() {
+            return Ok("Program halted".to_string());
+        }
+        if session.at_end() {
+            return Ok("End of the program".to_string());
+        }
+
+        let mut description = match reason {
+            StopReason::Breakpoint(_) => "Breakpoint reached\n".to_string(),
+            _ => String::new(),
+        };
+        description.push_str(&format!(
+            "PC {:#010x} (index {}), FP {:#x}, timestamp {}",
+            debugger.pc().val(),
+            debugger.prom_index(),
+            *debugger.fp(),
+            debugger.timestamp()
+        ));
+        if let Some(location) = debugger.location() {
+            description.push_str(&format!(", at {location}"));
+        }
+        if let Some(instruction) = instructions.get(&(debugger.prom_index() as usize)) {
+            description.push_str(&format!("\nNext: {instruction}"));
+        }
+        Ok(description)
+    }
+}
+
+/// Parses a decimal or `0x`-prefixed hexadecimal number.
+fn parse_u32(value: &str) -> Result<u32, ReplError> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|_| ReplError::InvalidArgument(format!("Expected a number, got {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::isa::GenericISA;
+
+    fn output(repl: &mut Repl, line: &str) -> String {
+        match repl.eval(line).unwrap() {
+            Evaluation::Output(output) => output,
+            Evaluation::Quit => panic!("Unexpected quit"),
+        }
+    }
+
+    #[test]
+    fn test_scratch_mode() {
+        let mut repl = Repl::new(|| Box::new(GenericISA), &[0, 0]);
+        assert!(matches!(repl.eval(":slot 2"), Err(ReplError::NotStarted)));
+
+        output(&mut repl, "LDI.W @2, #3");
+        assert_eq!(output(&mut repl, ":slot @2"), "@2 = 3 (0x3)");
+
+        // Instructions can branch to the labels of previous lines.
+        assert_eq!(output(&mut repl, "loop:"), "");
+        output(&mut repl, "ADDI @2, @2, #-1");
+        output(&mut repl, "BNZ loop, @2");
+        assert_eq!(output(&mut repl, ":p 2"), "@2 = 0 (0x0)");
+        assert_eq!(output(&mut repl, ":step"), "End of the program");
+
+        // Invalid lines are dropped from the scratch program.
+        assert!(matches!(repl.eval("NOPE @2"), Err(ReplError::Assembler(_))));
+        assert!(!repl.scratch_source().contains("NOPE"));
+
+        assert_eq!(output(&mut repl, "RET"), "Program halted");
+        assert_eq!(repl.eval(":quit").unwrap(), Evaluation::Quit);
+    }
+
+    #[test]
+    fn test_program_mode() {
+        let program = Assembler::from_code(include_str!("../../../examples/func_call.asm"))
+            .expect("The program should assemble");
+        let mut repl = Repl::with_program(|| Box::new(GenericISA), program, &[0, 0]).unwrap();
+        assert!(matches!(repl.eval("RET"), Err(ReplError::ProgramLoaded)));
+
+        let state = output(&mut repl, ":state");
+        assert!(state.starts_with("PC 0x00000001 (index 0)"), "{state}");
+
+        output(&mut repl, ":break add_two_numbers");
+        let stop = output(&mut repl, ":continue");
+        assert!(stop.starts_with("Breakpoint reached"), "{stop}");
+        let backtrace = output(&mut repl, ":bt");
+        assert!(backtrace.contains("add_two_numbers"), "{backtrace}");
+        let frames = output(&mut repl, ":frames");
+        assert_eq!(frames.matches("Frame ").count(), 2, "{frames}");
+
+        // Breakpoints are kept when restarting the execution.
+        output(&mut repl, ":reset");
+        assert_eq!(repl.debugger().unwrap().prom_index(), 0);
+        let stop = output(&mut repl, ":c");
+        assert!(stop.starts_with("Breakpoint reached"), "{stop}");
+
+        output(&mut repl, ":delete add_two_numbers");
+        assert_eq!(output(&mut repl, ":continue"), "Program halted");
+        assert!(matches!(
+            repl.eval(":frobnicate"),
+            Err(ReplError::UnknownCommand(_))
+        ));
+    }
+}
@@ -0,0 +1,307 @@
+//! Chunked execution in the emulator.
+//!
+//! [`PetraTrace::generate_segments`] splits an execution into [`Segment`]s of
+//! a fixed number of cycles, each one holding the events of its own
+//! instructions together with the machine state at its start and end. This
+//! state includes commitments to the memory writes so far, so that a segment
+//! can be checked against its neighbours without their traces.
+//!
+//! [`check_segments`] checks that the boundaries chain into a complete
+//! execution: consecutive segments must agree on their shared boundary, the
+//! first one must start at the program entry and the last one must end with
+//! the program halted.
+//!
+//! NOTE: Segments are an emulator facility only, and are not proven. The
+//! circuit proves a whole execution from the program entry: there is no
+//! per-segment statement binding a proof to the boundary states of a segment,
+//! and no aggregation of segment proofs. The memory commitments would have to
+//! be recomputed in-circuit to bind them, which the prover does not support.
+//! Segments therefore do not make longer executions provable.
+
+use std::collections::BTreeSet;
+
+use binius_field::{Field, PackedField};
+use binius_m3::builder::B32;
+
+use super::{FieldPc, FramePointer, Interpreter, InterpreterError, PetraTrace};
+use crate::{assembler::LabelsFrameSizes, isa::ISA, memory::Ram, util::groestl256, Memory};
+
+/// Machine state at the boundary between two segments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SegmentBoundary {
+    /// Field PC of the next instruction, zero once halted.
+    pub pc: B32,
+    /// Frame pointer.
+    pub fp: FramePointer,
+    /// System timestamp.
+    pub timestamp: u32,
+    /// Commitment to the VROM words written so far, see
+    /// [`chain_commitment`].
+    pub vrom_commitment: [u8; 32],
+    /// Commitment to the RAM words written so far, see [`chain_commitment`].
+    pub ram_commitment: [u8; 32],
+}
+
+impl SegmentBoundary {
+    /// Returns the state at the start of a program execution over `memory`,
+    /// committing to its initial VROM values and RAM image.
+    fn initial(memory: &Memory) -> Self {
+        let vrom_words = memory.vrom().iter_set().collect::<Vec<_>>();
+        let ram_words = (0..)
+            .zip(memory.ram().initial_data().chunks(4))
+            .map(|(word_addr, bytes)| {
+                let mut word = [0; 4];
+                word[..bytes.len()].copy_from_slice(bytes);
+                (word_addr, u32::from_le_bytes(word))
+            })
+            .collect::<Vec<_>>();
+        Self {
+            pc: B32::ONE,
+            fp: FramePointer::default(),
+            timestamp: 0,
+            vrom_commitment: chain_commitment(&[0; 32], &vrom_words),
+            ram_commitment: chain_commitment(&[0; 32], &ram_words),
+        }
+    }
+
+    /// Returns the current state of `interpreter`, following this one after the
+    /// VROM words `vrom_writes` and the RAM words `ram_writes` were written.
+    fn next(
+        &self,
+        interpreter: &Interpreter,
+        vrom_writes: &[(u32, u32)],
+        ram_writes: &[(u32, u32)],
+    ) -> Self {
+        Self {
+            pc: if interpreter.is_halted() {
+                B32::zero()
+            } else {
+                FieldPc::from_index(interpreter.pc as usize - 1).field()
+            },
+            fp: interpreter.fp,
+            timestamp: interpreter.timestamp,
+            vrom_commitment: chain_commitment(&self.vrom_commitment, vrom_writes),
+            ram_commitment: chain_commitment(&self.ram_commitment, ram_writes),
+        }
+    }
+}
+
+/// Extends the memory commitment `prev` with the `(address, value)` couples of
+/// the words written since, as the Groestl-256 digest of `prev` followed by
+/// the little-endian addresses and values.
+///
+/// Only the writes of a segment are hashed at its end, so that committing to
+/// the memory takes time linear in the length of the execution. The commitment
+/// thus depends on the segmentation, and can only be compared between
+/// segmentations sharing the same boundaries.
+pub fn chain_commitment(prev: &[u8; 32], writes: &[(u32, u32)]) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(32 + 8 * writes.len());
+    bytes.extend_from_slice(prev);
+    for (addr, value) in writes {
+        bytes.extend_from_slice(&addr.to_le_bytes());
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    groestl256(&bytes)
+}
+
+/// Returns the `(word_addr, value)` couples of the RAM words written by the
+/// accesses of `ram` from the `from`-th one, by increasing address.
+fn ram_written_words(ram: &Ram, from: usize) -> Vec<(u32, u32)> {
+    let written = ram.access_history()[from..]
+        .iter()
+        .filter(|access| access.is_write)
+        .map(|access| access.address >> 2)
+        .collect::<BTreeSet<_>>();
+    written
+        .into_iter()
+        .map(|word_addr| {
+            let value = ram
+                .peek::<u32>(word_addr << 2)
+                .expect("Written words are within the RAM");
+            (word_addr, value)
+        })
+        .collect()
+}
+
+/// A fixed-size chunk of a program execution.
+#[derive(Debug)]
+pub struct Segment {
+    /// Machine state before the first instruction of the segment.
+    pub start: SegmentBoundary,
+    /// Machine state after the last instruction of the segment.
+    pub end: SegmentBoundary,
+    /// Number of instructions executed in the segment.
+    pub cycles: u64,
+    /// Events and instruction counters of the segment.
+    pub events: PetraTrace,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SegmentError {
+    #[error("No segment was provided")]
+    Empty,
+    #[error("The first segment does not start at the program entry")]
+    BadStart,
+    #[error("Segment {index} does not start where the previous one ends")]
+    Discontinuity { index: usize },
+    #[error("The last segment does not end with the program halted")]
+    NotHalted,
+}
+
+impl PetraTrace {
+    /// Executes the program like [`PetraTrace::generate`], splitting the
+    /// execution into segments of `segment_cycles` instructions, the last one
+    /// possibly being shorter.
+    pub fn generate_segments(
+        isa: Box<dyn ISA>,
+        memory: Memory,
+        frames: LabelsFrameSizes,
+        segment_cycles: u64,
+    ) -> Result<Vec<Segment>, InterpreterError> {
+        assert!(segment_cycles > 0, "Segments must span at least one cycle");
+        let mut interpreter = Interpreter::new(isa, frames);
+        let memory = memory.with_vrom_write_log();
+
+        let mut segments = Vec::new();
+        let mut start = SegmentBoundary::initial(&memory);
+        let mut start_cycle = 0;
+        let mut ram_accesses = 0;
+        let mut close_segment = |interpreter: &Interpreter, trace: &mut PetraTrace| {
+            let vrom_writes = trace.vrom_mut().take_written_words();
+            let ram_writes = ram_written_words(trace.ram(), ram_accesses);
+            ram_accesses = trace.ram().access_history().len();
+            let end = start.next(interpreter, &vrom_writes, &ram_writes);
+            let mut events = trace.take_events();
+            // Each segment only accounts for its own instructions.
+            let prom_size = trace.instruction_counter.len();
+            events.instruction_counter =
+                std::mem::replace(&mut trace.instruction_counter, vec![0; prom_size]);
+            segments.push(Segment {
+                start: std::mem::replace(&mut start, end.clone()),
+                end,
+                cycles: interpreter.cycles - start_cycle,
+                events,
+            });
+            start_cycle = interpreter.cycles;
+        };
+
+        let mut trace = interpreter.run_with_hook(memory, |interpreter, trace, _| {
+            if interpreter.cycles % segment_cycles == 0 {
+                close_segment(interpreter, trace);
+            }
+        })?;
+        if interpreter.cycles % segment_cycles != 0 {
+            close_segment(&interpreter, &mut trace);
+        }
+
+        Ok(segments)
+    }
+}
+
+/// Checks that `segments` chain into a complete program execution.
+pub fn check_segments(segments: &[Segment]) -> Result<(), SegmentError> {
+    let first = segments.first().ok_or(SegmentError::Empty)?;
+    if first.start.pc != B32::ONE || *first.start.fp != 0 || first.start.timestamp != 0 {
+        return Err(SegmentError::BadStart);
+    }
+    for (index, pair) in segments.windows(2).enumerate() {
+        if pair[0].end != pair[1].start {
+            return Err(SegmentError::Discontinuity { index: index + 1 });
+        }
+    }
+    let last = segments.last().ok_or(SegmentError::Empty)?;
+    if last.end.pc != B32::zero() {
+        return Err(SegmentError::NotHalted);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, ValueRom};
+
+    #[test]
+    fn test_segments() {
+        let program = Assembler::from_code(include_str!("../../../examples/collatz.asm"))
+            .expect("The program should assemble");
+        let memory = || {
+            Memory::new(
+                program.prom.clone(),
+                ValueRom::new_with_init_vals(&[0, 0, 27]),
+            )
+        };
+
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+                .unwrap();
+        let mut segments = PetraTrace::generate_segments(
+            Box::new(GenericISA),
+            memory(),
+            program.frame_sizes.clone(),
+            100,
+        )
+        .unwrap();
+
+        assert!(segments.len() > 1);
+        assert!(segments[..segments.len() - 1]
+            .iter()
+            .all(|segment| segment.cycles == 100));
+        check_segments(&segments).unwrap();
+
+        // The segments partition the events and instruction counters.
+        let last = segments.last().unwrap();
+        assert_eq!(last.end.timestamp, boundary_values.timestamp);
+        assert_eq!(last.end.fp, boundary_values.final_fp);
+        for (i, count) in trace.instruction_counter.iter().enumerate() {
+            let total = segments
+                .iter()
+                .map(|segment| segment.events.instruction_counter[i])
+                .sum::<u32>();
+            assert_eq!(total, *count);
+        }
+        let ret_events = segments
+            .iter()
+            .map(|segment| segment.events.ret.len())
+            .sum::<usize>();
+        assert_eq!(ret_events, trace.ret.len());
+
+        // The memory commitments cover the inputs and the writes.
+        let other = PetraTrace::generate_segments(
+            Box::new(GenericISA),
+            Memory::new(
+                program.prom.clone(),
+                ValueRom::new_with_init_vals(&[0, 0, 28]),
+            ),
+            program.frame_sizes.clone(),
+            100,
+        )
+        .unwrap();
+        assert_ne!(
+            other[0].start.vrom_commitment,
+            segments[0].start.vrom_commitment
+        );
+        assert_ne!(
+            segments[0].end.vrom_commitment,
+            segments[0].start.vrom_commitment
+        );
+        assert_eq!(
+            segments[0].end.ram_commitment,
+            segments[0].start.ram_commitment
+        );
+
+        // Tampering with a boundary breaks the chain.
+        segments[1].start.vrom_commitment[0] ^= 1;
+        assert!(matches!(
+            check_segments(&segments),
+            Err(SegmentError::Discontinuity { index: 1 })
+        ));
+        segments[1].start.vrom_commitment[0] ^= 1;
+        segments.pop();
+        assert!(matches!(
+            check_segments(&segments),
+            Err(SegmentError::NotHalted)
+        ));
+        assert!(matches!(check_segments(&[]), Err(SegmentError::Empty)));
+    }
+}
@@ -0,0 +1,106 @@
+//! Streaming of trace events.
+//!
+//! By default, [`PetraTrace::generate`] retains every event in memory, which
+//! does not scale to long executions. With a sink set in its
+//! [`GenerationOptions`](super::GenerationOptions),
+//! [`PetraTrace::generate_with_options`] instead hands the events over to an
+//! [`EventSink`] in batches, which can write them to disk, count them or
+//! aggregate them as it sees fit.
+
+use std::collections::HashMap;
+
+use super::PetraTrace;
+
+/// Destination of the events generated by an execution.
+pub trait EventSink {
+    /// Receives a batch of events, as a trace holding only them. Batches come
+    /// in execution order.
+    fn consume(&mut self, events: PetraTrace);
+}
+
+/// Sink discarding events, only keeping the number of events of each kind.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventCounter {
+    counts: HashMap<&'static str, usize>,
+}
+
+impl EventCounter {
+    /// Returns the number of events of each kind, named after the
+    /// [`PetraTrace`] fields holding them.
+    pub const fn counts(&self) -> &HashMap<&'static str, usize> {
+        &self.counts
+    }
+
+    /// Returns the total number of events.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+impl EventSink for EventCounter {
+    fn consume(&mut self, events: PetraTrace) {
+        for (kind, count) in events.event_counts() {
+            *self.counts.entry(kind).or_default() += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, GenerationOptions, Memory, ValueRom};
+
+    #[test]
+    fn test_generate_with_sink() {
+        let program = Assembler::from_code(include_str!("../../../examples/collatz.asm"))
+            .expect("The program should assemble");
+        let memory = || {
+            Memory::new(
+                program.prom.clone(),
+                ValueRom::new_with_init_vals(&[0, 0, 27]),
+            )
+        };
+
+        let (trace, boundary_values) =
+            PetraTrace::generate(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+                .unwrap();
+
+        let mut counter = EventCounter::default();
+        let (streamed, streamed_boundary_values) = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory(),
+            program.frame_sizes.clone(),
+            GenerationOptions::default().sink(&mut counter, 16),
+        )
+        .unwrap();
+
+        for (kind, count) in trace.event_counts() {
+            assert_eq!(counter.counts().get(kind).copied().unwrap_or(0), count);
+        }
+        assert!(counter.total() > 0);
+        // No event is left in the returned trace, but the rest of it is.
+        assert!(streamed.event_counts().iter().all(|(_, count)| *count == 0));
+        assert_eq!(streamed.instruction_counter, trace.instruction_counter);
+        assert_eq!(streamed.vrom_size(), trace.vrom_size());
+        assert_eq!(streamed_boundary_values.final_pc, boundary_values.final_pc);
+        assert_eq!(
+            streamed_boundary_values.timestamp,
+            boundary_values.timestamp
+        );
+
+        // The options combine: gadget events are derived for each batch.
+        let mut combined = EventCounter::default();
+        PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory(),
+            program.frame_sizes.clone(),
+            GenerationOptions::default()
+                .max_cycles(Some(10_000))
+                .frame_checks()
+                .deferred_gadget_events()
+                .sink(&mut combined, 16),
+        )
+        .unwrap();
+        assert_eq!(combined, counter);
+    }
+}
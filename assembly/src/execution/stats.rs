@@ -0,0 +1,306 @@
+//! Cycle statistics and profiling of program executions.
+//!
+//! [`AllCycleStats`] counts, for a given program execution, the cycles spent
+//! per opcode and per function, a cycle being an executed (non-prover-only)
+//! instruction. Each cycle is attributed to the function whose frame executes
+//! it, functions being tracked through the `CALL*`, `TAIL*` and `RET`
+//! instructions. A [`ProfileReport`] then names and sorts these buckets, to
+//! find out which guest routines dominate the execution, and locates them in
+//! the source when the program has debug info.
+
+use std::{collections::HashMap, fmt};
+
+use binius_m3::builder::B32;
+
+use super::{FieldPc, Interpreter, InterpreterError, InterpreterInstruction, PetraTrace};
+use crate::{isa::ISA, opcodes::Opcode, AssembledProgram, Memory, SourceLocation, ValueRom};
+
+/// Cycles spent in a single function.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FunctionStats {
+    /// Number of times the function was entered, through a call or tail call.
+    pub calls: u64,
+    /// Number of cycles executed in the function's own frames.
+    pub cycles: u64,
+    /// Number of cycles per opcode executed in the function's own frames.
+    pub opcodes: HashMap<Opcode, u64>,
+}
+
+/// Cycle statistics of a program execution. See the [module-level
+/// documentation](self) for details.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AllCycleStats {
+    /// Total number of cycles.
+    pub total_cycles: u64,
+    /// Number of cycles per opcode.
+    pub opcodes: HashMap<Opcode, u64>,
+    /// Statistics per function, keyed by the field PC of its entry.
+    pub functions: HashMap<B32, FunctionStats>,
+}
+
+impl AllCycleStats {
+    /// Executes the provided program and records its cycle statistics.
+    ///
+    /// `init_values` are the initial values of the VROM, the first two of them
+    /// being the return PC and FP of the entry point.
+    pub fn record(
+        isa: Box<dyn ISA>,
+        program: &AssembledProgram,
+        init_values: &[u32],
+    ) -> Result<Self, InterpreterError> {
+        let memory = Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(init_values),
+        )
+        .with_ram_data(&program.data);
+        let mut interpreter = Interpreter::new(isa, program.frame_sizes.clone());
+
+        let mut stats = Self::default();
+        // Function executing the next instruction.
+        let mut current = program.prom.first().map_or(B32::zero(), |entry| {
+            stats.functions.entry(entry.field_pc).or_default().calls += 1;
+            entry.field_pc
+        });
+
+        interpreter.run_with_observer(
+            memory,
+            |interpreter: &Interpreter, _: &PetraTrace, instruction: &InterpreterInstruction| {
+                let function = current;
+                if let Some(frame) = interpreter.call_stack.last() {
+                    current = frame.function;
+                }
+                if instruction.prover_only {
+                    return;
+                }
+
+                let opcode = instruction.opcode();
+                stats.record_cycle(function, opcode);
+                if matches!(
+                    opcode,
+                    Opcode::Calli | Opcode::Callv | Opcode::Taili | Opcode::Tailv
+                ) {
+                    stats.functions.entry(current).or_default().calls += 1;
+                }
+            },
+        )?;
+
+        Ok(stats)
+    }
+
+    fn record_cycle(&mut self, function: B32, opcode: Opcode) {
+        self.total_cycles += 1;
+        *self.opcodes.entry(opcode).or_default() += 1;
+        let function = self.functions.entry(function).or_default();
+        function.cycles += 1;
+        *function.opcodes.entry(opcode).or_default() += 1;
+    }
+
+    /// Builds a [`ProfileReport`], naming functions after the labels of the
+    /// assembled program.
+    pub fn report(&self, labels: &HashMap<String, (B32, u32, u32)>) -> ProfileReport {
+        // Several labels can share a field PC: pick the first one in
+        // lexicographic order, so that the report is deterministic.
+        let mut names: HashMap<B32, &str> = HashMap::new();
+        for (name, (field_pc, _, _)) in labels {
+            names
+                .entry(*field_pc)
+                .and_modify(|current| *current = (*current).min(name.as_str()))
+                .or_insert(name.as_str());
+        }
+
+        let mut functions = self
+            .functions
+            .iter()
+            .map(|(field_pc, stats)| FunctionProfile {
+                name: names.get(field_pc).map_or_else(
+                    || format!("{:#010x}", field_pc.val()),
+                    |name| name.to_string(),
+                ),
+                entry: *field_pc,
+                location: None,
+                calls: stats.calls,
+                cycles: stats.cycles,
+                opcodes: sorted_histogram(&stats.opcodes),
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.cycles.cmp(&a.cycles).then_with(|| a.name.cmp(&b.name)));
+
+        ProfileReport {
+            total_cycles: self.total_cycles,
+            functions,
+            opcodes: sorted_histogram(&self.opcodes),
+        }
+    }
+}
+
+/// Returns the entries of `histogram`, by decreasing count.
+fn sorted_histogram(histogram: &HashMap<Opcode, u64>) -> Vec<(Opcode, u64)> {
+    let mut entries = histogram
+        .iter()
+        .map(|(opcode, count)| (*opcode, *count))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(a, a_count), (b, b_count)| {
+        b_count
+            .cmp(a_count)
+            .then_with(|| u16::from(*a).cmp(&u16::from(*b)))
+    });
+    entries
+}
+
+/// Profile of a single function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionProfile {
+    /// Name of the function, or the hexadecimal field PC of its entry if it
+    /// has no label.
+    pub name: String,
+    /// Field PC of the entry of the function.
+    pub entry: B32,
+    /// Source location of the entry of the function, filled in by
+    /// [`ProfileReport::with_locations`].
+    pub location: Option<SourceLocation>,
+    /// Number of times the function was entered.
+    pub calls: u64,
+    /// Number of cycles executed in the function's own frames.
+    pub cycles: u64,
+    /// Number of cycles per opcode, by decreasing count.
+    pub opcodes: Vec<(Opcode, u64)>,
+}
+
+/// Execution profile, with the hottest functions and opcodes first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileReport {
+    /// Total number of cycles.
+    pub total_cycles: u64,
+    /// Function profiles, by decreasing number of cycles.
+    pub functions: Vec<FunctionProfile>,
+    /// Number of cycles per opcode, by decreasing count.
+    pub opcodes: Vec<(Opcode, u64)>,
+}
+
+impl ProfileReport {
+    /// Locates the functions in the source of `program`, if it was assembled
+    /// with debug info.
+    pub fn with_locations(mut self, program: &AssembledProgram) -> Self {
+        let Some(debug_info) = &program.debug_info else {
+            return self;
+        };
+        for function in &mut self.functions {
+            function.location = program
+                .prom
+                .locate(FieldPc::from(function.entry))
+                .and_then(|(prom_index, _)| debug_info.location(prom_index))
+                .cloned();
+        }
+        self
+    }
+
+    fn share(&self, cycles: u64) -> f64 {
+        if self.total_cycles == 0 {
+            0.0
+        } else {
+            100.0 * cycles as f64 / self.total_cycles as f64
+        }
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Total cycles: {}", self.total_cycles)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<32} {:>10} {:>12} {:>7}",
+            "Function", "Calls", "Cycles", "%"
+        )?;
+        for function in &self.functions {
+            write!(
+                f,
+                "{:<32} {:>10} {:>12} {:>6.2}%",
+                function.name,
+                function.calls,
+                function.cycles,
+                self.share(function.cycles)
+            )?;
+            match &function.location {
+                Some(location) => writeln!(f, "  {location}")?,
+                None => writeln!(f)?,
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<32} {:>10} {:>12} {:>7}", "Opcode", "", "Cycles", "%")?;
+        for (opcode, count) in &self.opcodes {
+            writeln!(
+                f,
+                "{:<32} {:>10} {:>12} {:>6.2}%",
+                opcode.to_string(),
+                "",
+                count,
+                self.share(*count)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, AssemblerOptions};
+
+    #[test]
+    fn test_func_call_profile() {
+        let program = Assembler::from_code(include_str!("../../../examples/func_call.asm"))
+            .expect("The program should assemble");
+        let stats = AllCycleStats::record(Box::new(GenericISA), &program, &[0, 0])
+            .expect("The program should run");
+
+        let entry = program.labels["func_call"].0;
+        let callee = program.labels["add_two_numbers"].0;
+        assert_eq!(stats.functions.len(), 2);
+        assert_eq!(stats.functions[&entry].calls, 1);
+        assert_eq!(stats.functions[&callee].calls, 1);
+        assert_eq!(
+            stats.functions.values().map(|f| f.cycles).sum::<u64>(),
+            stats.total_cycles
+        );
+        assert_eq!(stats.opcodes.values().sum::<u64>(), stats.total_cycles);
+        // The call is attributed to the caller, the return to the callee.
+        assert_eq!(stats.functions[&entry].opcodes[&Opcode::Calli], 1);
+        assert_eq!(stats.functions[&callee].opcodes[&Opcode::Ret], 1);
+        assert_eq!(stats.opcodes[&Opcode::Ret], 2);
+
+        let report = stats.report(&program.labels);
+        assert_eq!(report.total_cycles, stats.total_cycles);
+        assert_eq!(report.functions[0].name, "func_call");
+        assert!(report.functions[0].cycles >= report.functions[1].cycles);
+        assert!(report.opcodes.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert!(report.to_string().contains("add_two_numbers"));
+        // Without debug info, the functions are not located.
+        let report = report.with_locations(&program);
+        assert!(report.functions.iter().all(|f| f.location.is_none()));
+    }
+
+    #[test]
+    fn test_profile_locations() {
+        let options = AssemblerOptions {
+            debug_info: true,
+            ..Default::default()
+        };
+        let (program, _) = Assembler::from_code_with_options(
+            include_str!("../../../examples/func_call.asm"),
+            &options,
+        )
+        .unwrap();
+        let stats = AllCycleStats::record(Box::new(GenericISA), &program, &[0, 0])
+            .expect("The program should run");
+
+        let report = stats.report(&program.labels).with_locations(&program);
+        for function in &report.functions {
+            let (_, prom_index, _) = program.labels[&function.name];
+            let location = program.debug_info.as_ref().unwrap().location(prom_index);
+            assert!(location.is_some());
+            assert_eq!(function.location.as_ref(), location);
+        }
+        assert!(report.to_string().contains("line "));
+    }
+}
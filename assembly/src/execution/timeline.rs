@@ -0,0 +1,268 @@
+//! Execution timeline export in the Chrome tracing format.
+//!
+//! A [`Timeline`] records, for a given program execution, one span per function
+//! call (derived from the `CALL*`, `TAIL*` and `RET` instructions) together
+//! with counters tracking the number of elapsed cycles and VROM allocations.
+//! It can then be serialized into a JSON file loadable in `chrome://tracing`
+//! or [Perfetto](https://ui.perfetto.dev), where each function gets its own
+//! lane.
+//!
+//! Timestamps are expressed in cycles, i.e. in number of executed
+//! (non-prover-only) instructions.
+
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+use binius_m3::builder::B32;
+
+use super::{Interpreter, InterpreterError, InterpreterInstruction, PetraTrace};
+use crate::{isa::ISA, opcodes::Opcode, AssembledProgram, Memory, ValueRom};
+
+/// A single function call, spanning from the call (or tail call) reaching the
+/// function to the instruction leaving it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallSpan {
+    /// Name of the called function.
+    pub name: String,
+    /// Cycle at which the function was entered.
+    pub start: u64,
+    /// Cycle at which the function was left.
+    pub end: u64,
+    /// Call depth of this span, the entry point being at depth 0.
+    pub depth: usize,
+}
+
+/// Snapshot of the execution counters, taken at each span boundary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CounterSample {
+    /// Cycle at which the sample was taken.
+    pub cycle: u64,
+    /// Number of `ALLOCI!` / `ALLOCV!` instructions executed so far.
+    pub allocations: u64,
+    /// Size of the VROM at that point.
+    pub vrom_size: usize,
+}
+
+/// Execution timeline of a program. See the [module-level
+/// documentation](self) for details.
+#[derive(Clone, Debug, Default)]
+pub struct Timeline {
+    spans: Vec<CallSpan>,
+    counters: Vec<CounterSample>,
+    total_cycles: u64,
+}
+
+impl Timeline {
+    /// Executes the provided program and records its timeline.
+    ///
+    /// `init_values` are the initial values of the VROM, the first two of them
+    /// being the return PC and FP of the entry point.
+    pub fn record(
+        isa: Box<dyn ISA>,
+        program: &AssembledProgram,
+        init_values: &[u32],
+    ) -> Result<Self, InterpreterError> {
+        let names: HashMap<B32, &str> = program
+            .labels
+            .iter()
+            .map(|(name, (field_pc, _, _))| (*field_pc, name.as_str()))
+            .collect();
+        let name_of = |field_pc: B32| {
+            names.get(&field_pc).map_or_else(
+                || format!("{:#010x}", field_pc.val()),
+                |name| name.to_string(),
+            )
+        };
+
+        let memory = Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(init_values),
+        );
+        let mut interpreter = Interpreter::new(isa, program.frame_sizes.clone());
+
+        let mut timeline = Self::default();
+        let mut cycle = 0;
+        let mut allocations = 0;
+        // Spans that have been entered but not left yet, as (name, start).
+        let mut stack = match program.prom.first() {
+            Some(entry) => vec![(name_of(entry.field_pc), 0)],
+            None => vec![],
+        };
+
+        interpreter.run_with_observer(
+            memory,
+            |interpreter: &Interpreter,
+             trace: &PetraTrace,
+             instruction: &InterpreterInstruction| {
+                if instruction.prover_only {
+                    if matches!(instruction.opcode(), Opcode::Alloci | Opcode::Allocv) {
+                        allocations += 1;
+                    }
+                    return;
+                }
+                cycle += 1;
+
+                let target = || {
+                    trace
+                        .prom()
+                        .as_slice()
+                        .get(interpreter.prom_index as usize)
+                        .map(|inst| name_of(inst.field_pc))
+                        .unwrap_or_default()
+                };
+                let mut boundary = true;
+                match instruction.opcode() {
+                    Opcode::Calli | Opcode::Callv => stack.push((target(), cycle)),
+                    Opcode::Taili | Opcode::Tailv => {
+                        timeline.close_span(&mut stack, cycle);
+                        stack.push((target(), cycle));
+                    }
+                    Opcode::Ret => timeline.close_span(&mut stack, cycle),
+                    _ => boundary = false,
+                }
+                if boundary {
+                    timeline.counters.push(CounterSample {
+                        cycle,
+                        allocations,
+                        vrom_size: trace.vrom_size(),
+                    });
+                }
+            },
+        )?;
+
+        // Close the spans that are still open, if any.
+        while !stack.is_empty() {
+            timeline.close_span(&mut stack, cycle);
+        }
+        timeline.total_cycles = cycle;
+
+        Ok(timeline)
+    }
+
+    fn close_span(&mut self, stack: &mut Vec<(String, u64)>, end: u64) {
+        if let Some((name, start)) = stack.pop() {
+            self.spans.push(CallSpan {
+                name,
+                start,
+                end,
+                depth: stack.len(),
+            });
+        }
+    }
+
+    /// Returns the recorded spans, ordered by exit cycle.
+    pub fn spans(&self) -> &[CallSpan] {
+        &self.spans
+    }
+
+    /// Returns the recorded counter samples, ordered by cycle.
+    pub fn counters(&self) -> &[CounterSample] {
+        &self.counters
+    }
+
+    /// Returns the total number of cycles of the execution.
+    pub const fn total_cycles(&self) -> u64 {
+        self.total_cycles
+    }
+
+    /// Serializes the timeline into the Chrome tracing JSON format.
+    pub fn to_chrome_trace_json(&self) -> String {
+        // Assign one lane (i.e. thread id) per function, in order of first exit.
+        let mut lanes: HashMap<&str, usize> = HashMap::new();
+        for span in &self.spans {
+            let next_lane = lanes.len() + 1;
+            lanes.entry(span.name.as_str()).or_insert(next_lane);
+        }
+
+        let mut entries = Vec::with_capacity(lanes.len() + self.spans.len() + self.counters.len());
+        let mut lane_names = lanes.iter().collect::<Vec<_>>();
+        lane_names.sort_by_key(|(_, lane)| **lane);
+        for (name, lane) in lane_names {
+            entries.push(format!(
+                r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{lane},"args":{{"name":"{}"}}}}"#,
+                escape_json(name)
+            ));
+        }
+        for span in &self.spans {
+            entries.push(format!(
+                r#"{{"name":"{}","cat":"call","ph":"X","pid":1,"tid":{},"ts":{},"dur":{},"args":{{"depth":{}}}}}"#,
+                escape_json(&span.name),
+                lanes[span.name.as_str()],
+                span.start,
+                span.end - span.start,
+                span.depth,
+            ));
+        }
+        for sample in &self.counters {
+            entries.push(format!(
+                r#"{{"name":"cycles","ph":"C","pid":1,"ts":{0},"args":{{"cycles":{0}}}}}"#,
+                sample.cycle
+            ));
+            entries.push(format!(
+                r#"{{"name":"allocations","ph":"C","pid":1,"ts":{},"args":{{"allocations":{},"vrom_size":{}}}}}"#,
+                sample.cycle, sample.allocations, sample.vrom_size,
+            ));
+        }
+
+        format!(
+            r#"{{"displayTimeUnit":"ns","traceEvents":[{}]}}"#,
+            entries.join(",")
+        )
+    }
+
+    /// Writes the timeline to `path`, in the Chrome tracing JSON format.
+    pub fn write_chrome_trace<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_chrome_trace_json())
+    }
+}
+
+/// Escapes a string so that it can be embedded in a JSON string literal.
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler};
+
+    #[test]
+    fn test_func_call_timeline() {
+        let program = Assembler::from_code(include_str!("../../../examples/func_call.asm"))
+            .expect("The program should assemble");
+        let timeline = Timeline::record(Box::new(GenericISA), &program, &[0, 0])
+            .expect("The program should run");
+
+        let spans = timeline.spans();
+        assert_eq!(spans.len(), 2);
+
+        // `add_two_numbers` returns first.
+        assert_eq!(spans[0].name, "add_two_numbers");
+        assert_eq!(spans[0].depth, 1);
+        assert_eq!(spans[1].name, "func_call");
+        assert_eq!(spans[1].depth, 0);
+        assert_eq!(spans[1].start, 0);
+        assert_eq!(spans[1].end, timeline.total_cycles());
+        assert!(spans[1].start <= spans[0].start && spans[0].end <= spans[1].end);
+
+        // Counters are sampled at the call and at both returns.
+        let counters = timeline.counters();
+        assert_eq!(counters.len(), 3);
+        assert!(counters.iter().all(|sample| sample.allocations == 1));
+
+        let json = timeline.to_chrome_trace_json();
+        assert!(json.starts_with(r#"{"displayTimeUnit":"ns","traceEvents":["#));
+        assert!(json.contains(r#""name":"add_two_numbers","cat":"call","ph":"X""#));
+        assert!(json.contains(r#""args":{"name":"func_call"}"#));
+    }
+}
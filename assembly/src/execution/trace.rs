@@ -1,47 +1,88 @@
 //! This module stores all `Event`s generated during a program execution and
 //! generates the associated execution trace.
 
-use std::collections::HashMap;
+use std::{any::Any, collections::BTreeMap};
 
 use binius_field::{Field, PackedField};
 use binius_m3::builder::B32;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
 
-use super::FramePointer;
+use super::{
+    custom::fire_custom_event, ChannelImbalance, CustomEvent, CustomInstructions, EventSink, Flush,
+    FlushDirection, FlushOrigin, FlushRecorder, FramePointer, HostFunctions,
+};
 use crate::{
     assembler::LabelsFrameSizes,
     event::{
-        b128::{B128AddEvent, B128MulEvent},
+        assertion::{AssertEqEvent, AssertZeroEvent},
+        b128::{B128AddEvent, B128InvEvent, B128MulEvent},
         b32::{
-            AndEvent, AndiEvent, B32MulEvent, B32MuliEvent, OrEvent, OriEvent, XorEvent, XoriEvent,
+            AndEvent, AndiEvent, B32InvEvent, B32MulEvent, B32MuliEvent, OrEvent, OriEvent,
+            XorEvent, XoriEvent,
         },
-        branch::{BnzEvent, BzEvent},
+        b64::B64MulEvent,
+        bigint::BigMulModEvent,
+        bit_count::{ClzEvent, CtzEvent, PopcntEvent},
+        blake3::Blake3CompressEvent,
+        branch::{BeqEvent, BgeEvent, BltEvent, BneEvent, BnzEvent, BzEvent},
+        bytes::{BswapEvent, SextbEvent, SexthEvent},
         call::{CalliEvent, CallvEvent, TailiEvent, TailvEvent},
+        cmov::CmovEvent,
+        commit::{commit_outputs, CommitEvent},
         comparison::{
-            SleEvent, SleiEvent, SleiuEvent, SleuEvent, SltEvent, SltiEvent, SltiuEvent, SltuEvent,
+            SleEvent, SleiEvent, SleiuEvent, SleuEvent, Slt64Event, SltEvent, SltiEvent,
+            SltiuEvent, SltuEvent,
         },
         fp::FpEvent,
-        gadgets::right_logic_shift::RightLogicShiftGadgetEvent,
+        gadgets::{
+            mem_word::MemWordGadgetEvent, merkle_node::MerkleNodeGadgetEvent,
+            right_logic_shift::RightLogicShiftGadgetEvent,
+        },
         groestl::{Groestl256CompressEvent, Groestl256OutputEvent},
-        integer_ops::{AddEvent, AddiEvent, MulEvent, MuliEvent, MulsuEvent, MuluEvent, SubEvent},
+        halt::HaltEvent,
+        integer_ops::{
+            Add64Event, AddEvent, AddiEvent, DivEvent, DivuEvent, MulEvent, MuliEvent, MulsuEvent,
+            MuluEvent, RemEvent, RemuEvent, Sub64Event, SubEvent,
+        },
         jump::{JumpiEvent, JumpvEvent},
-        mv::{LdiEvent, MvihEvent, MvvlEvent, MvvwEvent},
+        memcpy::{MemcpyEvent, MemsetEvent},
+        merkle::MerkleVerifyEvent,
+        mv::{LdiEvent, MvibEvent, MvihEvent, MvvhEvent, MvvlEvent, MvvwEvent},
+        nop::NopEvent,
+        ram::{LbEvent, LbuEvent, LhEvent, LhuEvent, LwEvent, SbEvent, ShEvent, SwEvent},
+        range_check::RangeCheckEvent,
         ret::RetEvent,
-        shift::{SllEvent, SlliEvent, SraEvent, SraiEvent, SrlEvent, SrliEvent},
-        Event,
+        sha256::Sha256CompressEvent,
+        shift::{
+            RolEvent, RoliEvent, RorEvent, RoriEvent, SllEvent, SlliEvent, SraEvent, SraiEvent,
+            SrlEvent, SrliEvent,
+        },
+        Event, TraceEvent,
     },
-    execution::{Interpreter, InterpreterChannels, InterpreterError, G},
+    execution::{FieldPc, Interpreter, InterpreterChannels, InterpreterError},
     isa::ISA,
-    memory::{Memory, MemoryError, ProgramRom, Ram, ValueRom, VromValueT},
+    memory::{FrameMap, Memory, MemoryError, ProgramRom, Ram, ValueRom, VromValueT},
 };
 
-#[derive(Debug, Default)]
+/// Events recorded by the execution of a program, along with its memory.
+///
+/// The trace can be serialized, e.g. to prove an execution on another
+/// machine, except for the events of custom instructions, which are skipped.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct PetraTrace {
     pub fp: Vec<FpEvent>,
+    pub nop: Vec<NopEvent>,
     pub bnz: Vec<BnzEvent>,
     pub jumpi: Vec<JumpiEvent>,
     pub jumpv: Vec<JumpvEvent>,
     pub xor: Vec<XorEvent>,
     pub bz: Vec<BzEvent>,
+    pub beq: Vec<BeqEvent>,
+    pub bne: Vec<BneEvent>,
+    pub blt: Vec<BltEvent>,
+    pub bge: Vec<BgeEvent>,
     pub or: Vec<OrEvent>,
     pub ori: Vec<OriEvent>,
     pub xori: Vec<XoriEvent>,
@@ -56,45 +97,255 @@ pub struct PetraTrace {
     pub sleiu: Vec<SleiuEvent>,
     pub sltu: Vec<SltuEvent>,
     pub sltiu: Vec<SltiuEvent>,
+    pub slt64: Vec<Slt64Event>,
     pub srli: Vec<SrliEvent>,
     pub slli: Vec<SlliEvent>,
     pub srai: Vec<SraiEvent>,
     pub sll: Vec<SllEvent>,
     pub srl: Vec<SrlEvent>,
     pub sra: Vec<SraEvent>,
+    pub roli: Vec<RoliEvent>,
+    pub rori: Vec<RoriEvent>,
+    pub rol: Vec<RolEvent>,
+    pub ror: Vec<RorEvent>,
+    pub clz: Vec<ClzEvent>,
+    pub ctz: Vec<CtzEvent>,
+    pub popcnt: Vec<PopcntEvent>,
+    pub bswap: Vec<BswapEvent>,
+    pub sextb: Vec<SextbEvent>,
+    pub sexth: Vec<SexthEvent>,
     pub add: Vec<AddEvent>,
     pub addi: Vec<AddiEvent>,
     pub muli: Vec<MuliEvent>,
     pub mul: Vec<MulEvent>,
     pub mulsu: Vec<MulsuEvent>,
     pub mulu: Vec<MuluEvent>,
+    pub div: Vec<DivEvent>,
+    pub divu: Vec<DivuEvent>,
+    pub rem: Vec<RemEvent>,
+    pub remu: Vec<RemuEvent>,
+    pub add64: Vec<Add64Event>,
+    pub sub64: Vec<Sub64Event>,
     pub taili: Vec<TailiEvent>,
     pub tailv: Vec<TailvEvent>,
     pub calli: Vec<CalliEvent>,
     pub callv: Vec<CallvEvent>,
     pub ret: Vec<RetEvent>,
+    pub halt: Vec<HaltEvent>,
     pub mvih: Vec<MvihEvent>,
     pub mvvw: Vec<MvvwEvent>,
     pub mvvl: Vec<MvvlEvent>,
+    pub mvvh: Vec<MvvhEvent>,
+    pub mvib: Vec<MvibEvent>,
+    pub cmov: Vec<CmovEvent>,
+    pub memcpy: Vec<MemcpyEvent>,
+    pub memset: Vec<MemsetEvent>,
     pub ldi: Vec<LdiEvent>,
     pub b32_mul: Vec<B32MulEvent>,
     pub b32_muli: Vec<B32MuliEvent>,
+    pub b32_inv: Vec<B32InvEvent>,
+    pub b64_mul: Vec<B64MulEvent>,
     pub b128_add: Vec<B128AddEvent>,
     pub b128_mul: Vec<B128MulEvent>,
+    pub b128_inv: Vec<B128InvEvent>,
     pub groestl_compress: Vec<Groestl256CompressEvent>,
     pub groestl_output: Vec<Groestl256OutputEvent>,
+    pub sha256_compress: Vec<Sha256CompressEvent>,
+    pub blake3_compress: Vec<Blake3CompressEvent>,
+    pub big_mul_mod: Vec<BigMulModEvent>,
+    pub lw: Vec<LwEvent>,
+    pub lb: Vec<LbEvent>,
+    pub lbu: Vec<LbuEvent>,
+    pub lh: Vec<LhEvent>,
+    pub lhu: Vec<LhuEvent>,
+    pub sw: Vec<SwEvent>,
+    pub sb: Vec<SbEvent>,
+    pub sh: Vec<ShEvent>,
+    pub assert_eq: Vec<AssertEqEvent>,
+    pub assert_zero: Vec<AssertZeroEvent>,
+    pub range_check: Vec<RangeCheckEvent>,
+    pub commit: Vec<CommitEvent>,
+    pub merkle_verify: Vec<MerkleVerifyEvent>,
+    /// Events of the custom instructions, by opcode.
+    #[serde(skip)]
+    pub custom: BTreeMap<u16, Vec<Box<dyn CustomEvent>>>,
 
     memory: Memory,
     /// A vector recording the number of times an instruction has been executed.
     pub instruction_counter: Vec<u32>,
+    /// Values committed by `COMMIT`, in order. Unlike the events, they are
+    /// kept when streaming the events to a sink.
+    pub(crate) outputs: Vec<u32>,
+    /// System timestamp of the last `COMMIT`, if any, kept along with the
+    /// outputs.
+    pub(crate) last_commit_timestamp: Option<u32>,
 
     pub right_logic_shift_gadget: Vec<RightLogicShiftGadgetEvent>,
+    pub mem_word_gadget: Vec<MemWordGadgetEvent>,
+    pub merkle_node_gadget: Vec<MerkleNodeGadgetEvent>,
+}
+
+/// Implements [`TraceEvent`] for each gadget event type, retrieving its
+/// records from the given field of the [`PetraTrace`]. Opcode events implement
+/// it through the [`Opcode`](crate::Opcode) specification.
+macro_rules! impl_trace_events {
+    ($(($field:ident, $event:ty)),* $(,)?) => {
+        $(
+            impl TraceEvent for $event {
+                fn events(trace: &PetraTrace) -> &[Self] {
+                    &trace.$field
+                }
+            }
+        )*
+    };
+}
+
+impl_trace_events!(
+    (right_logic_shift_gadget, RightLogicShiftGadgetEvent),
+    (mem_word_gadget, MemWordGadgetEvent),
+    (merkle_node_gadget, MerkleNodeGadgetEvent),
+);
+
+/// The events of an opcode recorded in a [`PetraTrace`], regardless of their
+/// type.
+pub(crate) trait EventList {
+    /// Returns the number of recorded events.
+    fn count(&self) -> usize;
+
+    /// Fires the `index`-th recorded event.
+    fn fire(&self, index: usize, channels: &mut InterpreterChannels);
+}
+
+impl<E: Event> EventList for Vec<E> {
+    fn count(&self) -> usize {
+        self.len()
+    }
+
+    fn fire(&self, index: usize, channels: &mut InterpreterChannels) {
+        self[index].fire(channels);
+    }
 }
 
 pub struct BoundaryValues {
     pub final_pc: B32,
     pub final_fp: FramePointer,
     pub timestamp: u32,
+    /// Exit code of the execution, as set by `HALT`. Executions returning
+    /// from their initial frame exit with code 0.
+    pub exit_code: u32,
+    /// Commitment to the initial VROM values provided by the host, as
+    /// computed by [`ValueRom::commit_init_values`].
+    pub init_commitment: [u8; 32],
+    /// Public outputs of the execution, in the order of the `COMMIT`
+    /// instructions.
+    pub outputs: Vec<u32>,
+    /// Commitment to the public outputs, as computed by [`commit_outputs`].
+    pub output_commitment: [u8; 32],
+}
+
+impl BoundaryValues {
+    /// Returns the boundary values of the execution stopped at the current
+    /// state of `interpreter`, with the memory of `trace`.
+    fn of(interpreter: &Interpreter, trace: &PetraTrace) -> Self {
+        let final_pc = if interpreter.pc == 0 {
+            B32::zero()
+        } else {
+            FieldPc::from_index(interpreter.pc as usize).field()
+        };
+
+        // A halted execution holds its exit code in its final FP.
+        let exit_code = if interpreter.is_halted() {
+            *interpreter.fp
+        } else {
+            0
+        };
+
+        Self {
+            final_pc,
+            final_fp: interpreter.fp,
+            timestamp: interpreter.timestamp,
+            exit_code,
+            init_commitment: trace.vrom().init_commitment(),
+            outputs: trace.outputs.clone(),
+            output_commitment: commit_outputs(&trace.outputs),
+        }
+    }
+}
+
+/// Execution settings of [`PetraTrace::generate_with_options`].
+///
+/// All the settings are off by default, and can be combined freely.
+#[derive(Default)]
+pub struct GenerationOptions<'a> {
+    max_cycles: Option<u64>,
+    frame_checks: bool,
+    host_fns: HostFunctions,
+    custom_instructions: CustomInstructions,
+    deferred_gadget_events: bool,
+    advice_tape: Vec<u32>,
+    sink: Option<(&'a mut dyn EventSink, u64)>,
+}
+
+impl<'a> GenerationOptions<'a> {
+    /// Aborts the execution with [`InterpreterError::CycleLimitExceeded`] once
+    /// `max_cycles` instructions have been executed, if provided.
+    pub fn max_cycles(mut self, max_cycles: Option<u64>) -> Self {
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    /// Checks that every slot accessed by an instruction lies within the
+    /// declared frame size of its function.
+    ///
+    /// Out-of-frame slots otherwise silently alias the next frames, and the
+    /// execution only fails later, if ever, on a rewrite of their values. With
+    /// the checks, it fails with [`InterpreterError::SlotOutOfFrame`] at the
+    /// offending instruction.
+    pub const fn frame_checks(mut self) -> Self {
+        self.frame_checks = true;
+        self
+    }
+
+    /// Makes `host_fns` callable by the guest through `ECALL!`.
+    pub fn host_fns(mut self, host_fns: HostFunctions) -> Self {
+        self.host_fns = host_fns;
+        self
+    }
+
+    /// Makes `custom_instructions` executable by the guest through `CUSTOM`.
+    pub fn custom_instructions(mut self, custom_instructions: CustomInstructions) -> Self {
+        self.custom_instructions = custom_instructions;
+        self
+    }
+
+    /// Derives the gadget events from the opcode events in parallel after the
+    /// execution, with [`PetraTrace::derive_gadget_events`], rather than
+    /// inline during the interpretation.
+    ///
+    /// The gadget events are grouped by kind of opcode event rather than in
+    /// execution order, which the prover does not depend on.
+    pub const fn deferred_gadget_events(mut self) -> Self {
+        self.deferred_gadget_events = true;
+        self
+    }
+
+    /// Sets `advice_tape` as the private input stream read by `READ_ADVICE!`.
+    pub fn advice_tape(mut self, advice_tape: Vec<u32>) -> Self {
+        self.advice_tape = advice_tape;
+        self
+    }
+
+    /// Streams the events to `sink` instead of retaining them, bounding the
+    /// memory used by long executions.
+    ///
+    /// The events are handed over in batches, every `batch_cycles` executed
+    /// instructions and once more at the end of the execution. The returned
+    /// trace holds the memory and instruction counters, but no events.
+    pub fn sink(mut self, sink: &'a mut dyn EventSink, batch_cycles: u64) -> Self {
+        assert!(batch_cycles > 0, "Batches must span at least one cycle");
+        self.sink = Some((sink, batch_cycles));
+        self
+    }
 }
 
 /// Convenience macro to execute all the flushing rules of a given kind of
@@ -134,24 +385,171 @@ impl PetraTrace {
         isa: Box<dyn ISA>,
         memory: Memory,
         frames: LabelsFrameSizes,
-        pc_field_to_index_pc: HashMap<B32, (u32, u32)>,
     ) -> Result<(Self, BoundaryValues), InterpreterError> {
-        let mut interpreter = Interpreter::new(isa, frames, pc_field_to_index_pc);
+        Self::generate_with_options(isa, memory, frames, GenerationOptions::default())
+    }
 
-        let trace = interpreter.run(memory)?;
+    /// Generates the trace like [`PetraTrace::generate`], with the execution
+    /// settings of `options`, which can be combined freely.
+    pub fn generate_with_options(
+        isa: Box<dyn ISA>,
+        memory: Memory,
+        frames: LabelsFrameSizes,
+        options: GenerationOptions<'_>,
+    ) -> Result<(Self, BoundaryValues), InterpreterError> {
+        let GenerationOptions {
+            max_cycles,
+            frame_checks,
+            host_fns,
+            custom_instructions,
+            deferred_gadget_events,
+            advice_tape,
+            sink,
+        } = options;
+        let mut interpreter = Interpreter::new(isa, frames)
+            .with_max_cycles(max_cycles)
+            .with_host_fns(host_fns)
+            .with_custom_instructions(custom_instructions)
+            .with_advice_tape(advice_tape);
+        if frame_checks {
+            interpreter = interpreter.with_frame_checks();
+        }
+        if deferred_gadget_events {
+            interpreter = interpreter.with_deferred_gadget_events();
+        }
 
-        let final_pc = if interpreter.pc == 0 {
-            B32::zero()
-        } else {
-            G.pow(interpreter.pc as u64)
+        let trace = match sink {
+            Some((sink, batch_cycles)) => {
+                let mut flush = |trace: &mut PetraTrace| {
+                    if deferred_gadget_events {
+                        trace.derive_gadget_events();
+                    }
+                    sink.consume(trace.take_events());
+                };
+                let mut trace = interpreter.run_with_hook(memory, |interpreter, trace, _| {
+                    if interpreter.cycles % batch_cycles == 0 {
+                        flush(trace);
+                    }
+                })?;
+                flush(&mut trace);
+                trace
+            }
+            None => {
+                let mut trace = interpreter.run(memory)?;
+                if deferred_gadget_events {
+                    trace.derive_gadget_events();
+                }
+                trace
+            }
         };
 
-        let boundary_values = BoundaryValues {
-            final_pc,
-            final_fp: interpreter.fp,
-            timestamp: interpreter.timestamp,
-        };
-        Ok((trace, boundary_values))
+        Ok((trace, BoundaryValues::of(&interpreter, &trace)))
+    }
+
+    /// Derives the gadget events of the right shifts and block moves of this
+    /// trace from their opcode events, in parallel, replacing any gadget event
+    /// recorded during the execution.
+    #[instrument(level = "info", skip_all)]
+    pub fn derive_gadget_events(&mut self) {
+        let (right_logic_shift_gadget, mem_word_gadget) = rayon::join(
+            || {
+                let logical = self
+                    .srli
+                    .par_iter()
+                    .map(|event| (event.src_val, event.shift_amount, event.dst_val))
+                    .chain(
+                        self.srl
+                            .par_iter()
+                            .map(|event| (event.src_val, event.shift_amount, event.dst_val)),
+                    )
+                    .map(|(input, shift_amount, output)| {
+                        RightLogicShiftGadgetEvent::new(input, shift_amount, output)
+                    });
+                let arithmetic = self
+                    .srai
+                    .par_iter()
+                    .map(|event| (event.src_val, event.shift_amount))
+                    .chain(
+                        self.sra
+                            .par_iter()
+                            .map(|event| (event.src_val, event.shift_amount)),
+                    )
+                    .map(|(input, shift_amount)| {
+                        RightLogicShiftGadgetEvent::arithmetic(input, shift_amount)
+                    });
+                logical.chain(arithmetic).collect::<Vec<_>>()
+            },
+            || {
+                let vrom = self.vrom();
+                let copied = self.memcpy.par_iter().flat_map_iter(|event| {
+                    MemWordGadgetEvent::block(
+                        vrom,
+                        event.dst_ptr,
+                        event.src_ptr,
+                        1,
+                        event.len as u32,
+                    )
+                });
+                let set = self.memset.par_iter().flat_map_iter(|event| {
+                    MemWordGadgetEvent::block(
+                        vrom,
+                        event.dst_ptr,
+                        event.fp.addr(event.src),
+                        0,
+                        event.len as u32,
+                    )
+                });
+                copied.chain(set).collect::<Vec<_>>()
+            },
+        );
+        self.right_logic_shift_gadget = right_logic_shift_gadget;
+        self.mem_word_gadget = mem_word_gadget;
+    }
+
+    /// Returns the events recorded by the custom instruction of `opcode`,
+    /// downcast to `E`.
+    ///
+    /// Events of other types are skipped, so the result is empty if `E` is not
+    /// the event type of this instruction.
+    pub fn custom_events<E: CustomEvent>(&self, opcode: u16) -> Vec<&E> {
+        self.custom.get(&opcode).map_or_else(Vec::new, |events| {
+            events
+                .iter()
+                .filter_map(|event| (event.as_ref() as &dyn Any).downcast_ref::<E>())
+                .collect()
+        })
+    }
+
+    /// Executes the program like [`PetraTrace::generate`], but without
+    /// recording the events, see [`Interpreter::run_only`]. The returned trace
+    /// holds the memory and outputs of the execution, but no events.
+    pub fn execute(
+        isa: Box<dyn ISA>,
+        memory: Memory,
+        frames: LabelsFrameSizes,
+    ) -> Result<(Self, BoundaryValues), InterpreterError> {
+        let mut interpreter = Interpreter::new(isa, frames);
+
+        let trace = interpreter.run_only(memory)?;
+
+        Ok((trace, BoundaryValues::of(&interpreter, &trace)))
+    }
+
+    /// Moves the events recorded so far out of this trace, into a new trace
+    /// holding only them.
+    pub fn take_events(&mut self) -> Self {
+        let mut events = std::mem::take(self);
+        std::mem::swap(&mut self.memory, &mut events.memory);
+        std::mem::swap(
+            &mut self.instruction_counter,
+            &mut events.instruction_counter,
+        );
+        std::mem::swap(&mut self.outputs, &mut events.outputs);
+        std::mem::swap(
+            &mut self.last_commit_timestamp,
+            &mut events.last_commit_timestamp,
+        );
+        events
     }
 
     pub fn validate(&self, boundary_values: BoundaryValues) {
@@ -166,61 +564,147 @@ impl PetraTrace {
             boundary_values.timestamp,
         ));
 
-        fire_events!(self.bnz, &mut channels);
-        fire_events!(self.fp, &mut channels);
-        fire_events!(self.jumpi, &mut channels);
-        fire_events!(self.jumpv, &mut channels);
-        fire_events!(self.xor, &mut channels);
-        fire_events!(self.bz, &mut channels);
-        fire_events!(self.or, &mut channels);
-        fire_events!(self.ori, &mut channels);
-        fire_events!(self.xori, &mut channels);
-        fire_events!(self.and, &mut channels);
-        fire_events!(self.andi, &mut channels);
-        fire_events!(self.sub, &mut channels);
-        fire_events!(self.sle, &mut channels);
-        fire_events!(self.slei, &mut channels);
-        fire_events!(self.sleu, &mut channels);
-        fire_events!(self.sleiu, &mut channels);
-        fire_events!(self.slt, &mut channels);
-        fire_events!(self.slti, &mut channels);
-        fire_events!(self.sltu, &mut channels);
-        fire_events!(self.sltiu, &mut channels);
-        fire_events!(self.slli, &mut channels);
-        fire_events!(self.srli, &mut channels);
-        fire_events!(self.srai, &mut channels);
-        fire_events!(self.sll, &mut channels);
-        fire_events!(self.srl, &mut channels);
-        fire_events!(self.sra, &mut channels);
-        fire_events!(self.add, &mut channels);
-        fire_events!(self.addi, &mut channels);
-        fire_events!(self.muli, &mut channels);
-        fire_events!(self.mul, &mut channels);
-        fire_events!(self.mulsu, &mut channels);
-        fire_events!(self.mulu, &mut channels);
-        fire_events!(self.taili, &mut channels);
-        fire_events!(self.tailv, &mut channels);
-        fire_events!(self.calli, &mut channels);
-        fire_events!(self.callv, &mut channels);
-        fire_events!(self.ret, &mut channels);
-        fire_events!(self.mvih, &mut channels);
-        fire_events!(self.mvvw, &mut channels);
-        fire_events!(self.mvvl, &mut channels);
-        fire_events!(self.ldi, &mut channels);
-        fire_events!(self.b32_mul, &mut channels);
-        fire_events!(self.b32_muli, &mut channels);
-        fire_events!(self.b128_add, &mut channels);
-        fire_events!(self.b128_mul, &mut channels);
-        fire_events!(self.groestl_compress, &mut channels);
-        fire_events!(self.groestl_output, &mut channels);
+        for (_, events) in self.opcode_events() {
+            for index in 0..events.count() {
+                events.fire(index, &mut channels);
+            }
+        }
+        for event in self.custom.values().flatten() {
+            fire_custom_event(event.as_ref(), &mut channels);
+        }
 
         assert!(channels.state_channel.is_balanced());
     }
 
+    /// Returns the flushes of all the events to the state channel, along with
+    /// the events performing them, named after the trace fields holding them.
+    ///
+    /// Custom events are named after their opcode, e.g. `custom_0x8000`.
+    pub fn state_flushes(&self) -> Vec<Flush<(B32, u32, u32)>> {
+        let mut flushes = Vec::new();
+        let mut record = |events: String, index: usize, channels: &mut InterpreterChannels| {
+            for (value, multiplicity) in channels.state_channel.drain() {
+                let direction = if multiplicity > 0 {
+                    FlushDirection::Push
+                } else {
+                    FlushDirection::Pull
+                };
+                for _ in 0..multiplicity.unsigned_abs() {
+                    flushes.push(Flush {
+                        value,
+                        direction,
+                        origin: FlushOrigin::event(events.clone(), index),
+                    });
+                }
+            }
+        };
+
+        let mut channels = InterpreterChannels::default();
+        for (name, events) in self.opcode_events() {
+            for index in 0..events.count() {
+                events.fire(index, &mut channels);
+                record(name.to_string(), index, &mut channels);
+            }
+        }
+        for (opcode, events) in &self.custom {
+            for (index, event) in events.iter().enumerate() {
+                fire_custom_event(event.as_ref(), &mut channels);
+                record(format!("custom_0x{opcode:x}"), index, &mut channels);
+            }
+        }
+
+        flushes
+    }
+
+    /// Checks that the state channel is balanced, like
+    /// [`PetraTrace::validate`], but reports the unbalanced values along with
+    /// the events flushing them instead of panicking.
+    pub fn diagnose(
+        &self,
+        boundary_values: &BoundaryValues,
+    ) -> Result<(), ChannelImbalance<(B32, u32, u32)>> {
+        let mut recorder = FlushRecorder::new("state");
+        recorder.record(Flush {
+            value: (B32::ONE, 0, 0),
+            direction: FlushDirection::Push,
+            origin: FlushOrigin::boundary(),
+        });
+        recorder.record(Flush {
+            value: (
+                boundary_values.final_pc,
+                *boundary_values.final_fp,
+                boundary_values.timestamp,
+            ),
+            direction: FlushDirection::Pull,
+            origin: FlushOrigin::boundary(),
+        });
+        for flush in self.state_flushes() {
+            recorder.record(flush);
+        }
+        recorder.check(|&(_, fp, timestamp)| (timestamp, fp))
+    }
+
+    /// Returns the number of events of each kind, named after the trace fields
+    /// holding them.
+    pub fn event_counts(&self) -> Vec<(&'static str, usize)> {
+        let gadget_counts = [
+            (
+                "right_logic_shift_gadget",
+                self.right_logic_shift_gadget.len(),
+            ),
+            ("mem_word_gadget", self.mem_word_gadget.len()),
+            ("merkle_node_gadget", self.merkle_node_gadget.len()),
+        ];
+        self.opcode_events()
+            .into_iter()
+            .map(|(name, events)| (name, events.count()))
+            .chain(gadget_counts)
+            .collect()
+    }
+
+    /// Returns the values committed by `COMMIT`, in order.
+    pub fn outputs(&self) -> &[u32] {
+        &self.outputs
+    }
+
     pub const fn vrom_size(&self) -> usize {
         self.memory.vrom().size()
     }
 
+    /// Returns the function owning each frame allocated by the recorded calls,
+    /// along with the entry frame at address 0.
+    ///
+    /// Frames allocated by calls whose events were taken out of the trace, as
+    /// when streaming them to a sink, are missing.
+    pub fn frame_map(&self) -> FrameMap {
+        let calls = self
+            .calli
+            .iter()
+            .map(|event| (event.next_fp_val, event.target));
+        let calls = calls
+            .chain(
+                self.callv
+                    .iter()
+                    .map(|event| (event.next_fp_val, event.target)),
+            )
+            .chain(
+                self.taili
+                    .iter()
+                    .map(|event| (event.next_fp_val, event.target)),
+            )
+            .chain(
+                self.tailv
+                    .iter()
+                    .map(|event| (event.next_fp_val, event.target)),
+            );
+        self.prom()
+            .first()
+            .map(|entry| (0, entry.field_pc))
+            .into_iter()
+            .chain(calls.map(|(fp, target)| (fp, B32::new(target))))
+            .collect()
+    }
+
     /// Sets a value of one of the supported types at the provided index in
     /// VROM.
     pub(crate) fn vrom_write<T>(
@@ -235,6 +719,12 @@ impl PetraTrace {
         self.vrom_mut().write(index, value, record)
     }
 
+    /// Returns the recorded events of type `E`, e.g.
+    /// `trace.events_of::<AddEvent>()`.
+    pub fn events_of<E: TraceEvent>(&self) -> &[E] {
+        E::events(self)
+    }
+
     /// Returns a reference to the VROM.
     pub const fn vrom(&self) -> &ValueRom {
         self.memory.vrom()
@@ -0,0 +1,246 @@
+//! Structured exports of programs and executions, for external tooling such
+//! as visualizers or cost estimators.
+//!
+//! [`AssembledProgram::export`] describes the instructions of a program, with
+//! their decoded operands, and its labels, while [`PetraTrace::export`]
+//! summarizes an execution. Both exports can be encoded as JSON or CBOR with
+//! the [`Export`] trait.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AssembledProgram, Disassembler, DisassemblerError, PetraTrace};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("Failed to disassemble the program: {0}")]
+    Disassembler(#[from] DisassemblerError),
+
+    #[error("Failed to encode as JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Failed to encode as CBOR: {0}")]
+    Cbor(#[from] ciborium::ser::Error<std::io::Error>),
+}
+
+/// Encodings of the exports.
+pub trait Export: Serialize {
+    /// Encodes the export as pretty-printed JSON.
+    fn to_json(&self) -> Result<String, ExportError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Encodes the export as CBOR.
+    fn to_cbor(&self) -> Result<Vec<u8>, ExportError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// An instruction of an exported program.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedInstruction {
+    /// Index of the instruction in the PROM.
+    pub index: usize,
+    /// Field PC of the instruction.
+    pub pc: u32,
+    /// Name of the opcode, e.g. `Addi`.
+    pub opcode: String,
+    /// Raw value of the opcode.
+    pub opcode_value: u16,
+    /// Raw arguments of the instruction.
+    pub args: [u16; 3],
+    /// Whether the instruction is only executed by the prover.
+    pub prover_only: bool,
+    /// Assembly text of the instruction, with its decoded operands.
+    ///
+    /// Instructions spanning several PROM entries only have a text at their
+    /// first entry.
+    pub text: Option<String>,
+}
+
+/// A label of an exported program.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedLabel {
+    pub name: String,
+    /// Field PC of the labeled instruction.
+    pub pc: u32,
+    /// PROM index of the labeled instruction.
+    pub index: u32,
+    /// Frame size of the function starting at the label, if any.
+    pub frame_size: Option<u16>,
+}
+
+/// Structured description of an [`AssembledProgram`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramExport {
+    pub instructions: Vec<ExportedInstruction>,
+    /// Labels, sorted by PROM index.
+    pub labels: Vec<ExportedLabel>,
+    /// RAM addresses of the labels of the `.data` sections.
+    pub data_labels: BTreeMap<String, u32>,
+    /// Size of the static data, in bytes.
+    pub data_size: usize,
+    /// Slots of the entry frame exposed as public values.
+    pub public_slots: Vec<u32>,
+}
+
+impl Export for ProgramExport {}
+
+/// Structured summary of a [`PetraTrace`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceExport {
+    /// Number of executed instructions of each opcode, by opcode name.
+    pub opcode_counts: BTreeMap<String, u64>,
+    /// Number of events of each kind, named after the trace fields holding
+    /// them.
+    pub event_counts: BTreeMap<String, usize>,
+    /// Number of events of each custom instruction, by opcode.
+    pub custom_event_counts: BTreeMap<u16, usize>,
+    /// Number of executions of each instruction, by PROM index.
+    pub instruction_counts: Vec<u32>,
+    /// VROM high-water mark, i.e. the number of VROM words allocated by the
+    /// execution.
+    pub vrom_size: usize,
+}
+
+impl Export for TraceExport {}
+
+impl AssembledProgram {
+    /// Exports the instructions and labels of the program.
+    pub fn export(&self) -> Result<ProgramExport, ExportError> {
+        let texts = Disassembler::from_program(self)
+            .instructions()?
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+
+        let instructions = self
+            .prom
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| ExportedInstruction {
+                index,
+                pc: entry.field_pc.val(),
+                opcode: entry.opcode().to_string(),
+                opcode_value: entry.instruction[0].val(),
+                args: entry.args().map(|arg| arg.val()),
+                prover_only: entry.prover_only,
+                text: texts.get(&index).cloned(),
+            })
+            .collect();
+
+        let mut labels = self
+            .labels
+            .iter()
+            .map(|(name, &(pc, index, _))| ExportedLabel {
+                name: name.clone(),
+                pc: pc.val(),
+                index,
+                frame_size: self.frame_sizes.get(&pc).copied(),
+            })
+            .collect::<Vec<_>>();
+        labels.sort_by(|a, b| (a.index, &a.name).cmp(&(b.index, &b.name)));
+
+        Ok(ProgramExport {
+            instructions,
+            labels,
+            data_labels: self.data_labels.clone().into_iter().collect(),
+            data_size: self.data.len(),
+            public_slots: self.public_slots.clone(),
+        })
+    }
+}
+
+impl PetraTrace {
+    /// Exports statistics of the execution.
+    pub fn export(&self) -> TraceExport {
+        let mut opcode_counts = BTreeMap::new();
+        for (entry, &count) in self.prom().iter().zip(&self.instruction_counter) {
+            if count > 0 {
+                *opcode_counts.entry(entry.opcode().to_string()).or_default() += count as u64;
+            }
+        }
+
+        TraceExport {
+            opcode_counts,
+            event_counts: self
+                .event_counts()
+                .into_iter()
+                .map(|(name, count)| (name.to_string(), count))
+                .collect(),
+            custom_event_counts: self
+                .custom
+                .iter()
+                .map(|(&opcode, events)| (opcode, events.len()))
+                .collect(),
+            instruction_counts: self.instruction_counter.clone(),
+            vrom_size: self.vrom_size(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, Assembler, Memory, ValueRom};
+
+    const CODE: &str = "#[framesize(0x10)]
+        _start:
+            LDI.W @2, #3
+        loop:
+            ADDI @2, @2, #-1
+            BNZ loop, @2
+            RET
+        ";
+
+    #[test]
+    fn test_program_export() {
+        let program = Assembler::from_code(CODE).unwrap();
+        let export = program.export().unwrap();
+
+        assert_eq!(export.instructions.len(), program.prom.len());
+        let addi = &export.instructions[1];
+        assert_eq!(addi.opcode, "Addi");
+        assert_eq!(addi.args[0], 2);
+        assert_eq!(addi.text.as_deref(), Some("ADDI @2, @2, #65535"));
+
+        let names = export
+            .labels
+            .iter()
+            .map(|label| (label.name.as_str(), label.index, label.frame_size))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [("_start", 0, Some(0x10)), ("loop", 1, None)]);
+
+        let json = export.to_json().unwrap();
+        assert_eq!(
+            serde_json::from_str::<ProgramExport>(&json).unwrap(),
+            export
+        );
+        let cbor = export.to_cbor().unwrap();
+        let decoded: ProgramExport = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(decoded, export);
+    }
+
+    #[test]
+    fn test_trace_export() {
+        let program = Assembler::from_code(CODE).unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, _) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+        let export = trace.export();
+
+        assert_eq!(export.opcode_counts["Ldi"], 1);
+        assert_eq!(export.opcode_counts["Addi"], 3);
+        assert_eq!(export.opcode_counts["Bnz"], 3);
+        assert_eq!(export.opcode_counts["Ret"], 1);
+        assert_eq!(export.event_counts["addi"], 3);
+        assert_eq!(export.event_counts["bnz"], 2);
+        assert_eq!(export.event_counts["bz"], 1);
+        assert_eq!(export.vrom_size, trace.vrom_size());
+
+        let json = export.to_json().unwrap();
+        assert_eq!(serde_json::from_str::<TraceExport>(&json).unwrap(), export);
+    }
+}
@@ -0,0 +1,124 @@
+//! Entry points for fuzzing the assembler and the interpreter.
+//!
+//! These functions discard any error: they only exist to drive untrusted input
+//! through the parser and the interpreter, so that fuzzers report the inputs
+//! causing a panic. The `fuzz` directory at the root of the repository wraps
+//! them into `cargo fuzz` targets.
+
+use std::collections::HashMap;
+
+use binius_m3::builder::{B16, B32};
+
+use crate::{
+    isa::GenericISA, Assembler, Disassembler, FieldPc, GenerationOptions, InterpreterInstruction,
+    Memory, Opcode, PetraTrace, ProgramRom, ValueRom,
+};
+
+/// Maximum number of instructions executed by [`fuzz_execute`].
+pub const FUZZ_MAX_CYCLES: u64 = 1 << 12;
+
+/// Frame size of every function of the programs run by [`fuzz_execute`].
+pub const FUZZ_FRAME_SIZE: u16 = 16;
+
+/// Assembles `bytes` as source code, and disassembles the resulting program.
+pub fn fuzz_parse(bytes: &[u8]) {
+    let Ok(code) = std::str::from_utf8(bytes) else {
+        return;
+    };
+    if let Ok(program) = Assembler::from_code(code) {
+        let _ = Disassembler::from_program(&program).disassemble();
+    }
+}
+
+/// Runs the PROM encoded in `program` with the VROM initialized with
+/// `inputs`, for at most [`FUZZ_MAX_CYCLES`] instructions.
+///
+/// Every 8 bytes of `program` encode an instruction, as its opcode and
+/// arguments in little-endian, and trailing bytes are ignored. Opcodes that
+/// must be prover-only are decoded as such. Every
+/// instruction may be called, with a frame of [`FUZZ_FRAME_SIZE`] slots.
+pub fn fuzz_execute(program: &[u8], inputs: &[u32]) {
+    let prom = program_rom(program);
+    let frames = prom
+        .iter()
+        .map(|instruction| (instruction.field_pc, FUZZ_FRAME_SIZE))
+        .collect::<HashMap<_, _>>();
+    let memory = Memory::new(prom, ValueRom::new_with_init_vals(inputs));
+    let _ = PetraTrace::generate_with_options(
+        Box::new(GenericISA),
+        memory,
+        frames,
+        GenerationOptions::default().max_cycles(Some(FUZZ_MAX_CYCLES)),
+    );
+}
+
+/// Decodes the instructions of `program`, at successive PCs.
+///
+/// Jumps get the advice the assembler would provide, i.e. the location of
+/// their target, if it is in the PROM.
+fn program_rom(program: &[u8]) -> ProgramRom {
+    let mut pc = FieldPc::START;
+    let prom = program
+        .chunks_exact(8)
+        .map(|chunk| {
+            let half = |i: usize| B16::new(u16::from_le_bytes([chunk[2 * i], chunk[2 * i + 1]]));
+            let instruction = [half(0), half(1), half(2), half(3)];
+            // Prover-only instructions share the PC of the next instruction, as
+            // in assembled programs.
+            let prover_only =
+                Opcode::try_from(instruction[0].val()).is_ok_and(|opcode| opcode.is_prover_only());
+            let field_pc = pc.field();
+            if !prover_only {
+                pc.incr();
+            }
+            InterpreterInstruction::new(instruction, field_pc, None, prover_only)
+        })
+        .collect::<ProgramRom>();
+
+    let targets = prom
+        .iter()
+        .map(|instruction| {
+            let [_, target_low, target_high, _] = instruction.instruction;
+            let target = target_low.val() as u32 + ((target_high.val() as u32) << 16);
+            prom.locate(FieldPc::from(B32::new(target)))
+        })
+        .collect::<Vec<_>>();
+    prom.into_iter()
+        .zip(targets)
+        .map(|(instruction, advice)| InterpreterInstruction {
+            advice,
+            ..instruction
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzz_inputs() {
+        fuzz_parse(b"");
+        fuzz_parse(&[0xff, 0xfe]);
+        fuzz_parse(b"#[framesize(0x4)]\n_start:\n    LDI.W @2, #1\n    RET\n");
+        fuzz_parse(b"_start:\n    LDI.W @2,");
+
+        // Empty programs, invalid opcodes and infinite loops are all rejected.
+        fuzz_execute(&[], &[]);
+        fuzz_execute(&[0xff; 8], &[0, 0]);
+        let jump_to_self = [Opcode::Jumpi as u16, 1, 0, 0]
+            .iter()
+            .flat_map(|half| half.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(program_rom(&jump_to_self)[0].advice, Some((0, 1)));
+        fuzz_execute(&jump_to_self, &[0, 0]);
+
+        // A lone prover-only instruction has no instruction to share its PC with.
+        let alloc = [Opcode::Alloci as u16, 3, 4, 0]
+            .iter()
+            .flat_map(|half| half.to_le_bytes())
+            .collect::<Vec<_>>();
+        assert!(program_rom(&alloc)[0].prover_only);
+        fuzz_execute(&alloc, &[0, 0]);
+    }
+}
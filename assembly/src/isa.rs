@@ -6,12 +6,17 @@
 //!
 //! On the prover side, the ISA is fed when initializing a new
 //! `Circuit`, which invokes the static table registry to instantiate and wire
-//! up all instruction tables needed by this ISA.
+//! up all instruction tables needed by this ISA, along with the auxiliary
+//! tables (such as the RAM table) that these instructions rely on.
 
 use core::fmt::Debug;
 use std::collections::HashSet;
 
+#[doc(hidden)]
+pub use once_cell;
+
 use crate::event::*;
+use crate::opcodes::DispatchTable;
 use crate::Opcode;
 
 /// Defines an Instruction Set Architecture for the Petra Virtual Machine.
@@ -19,7 +24,7 @@ use crate::Opcode;
 /// Each implementation of this trait should provide the different instructions
 /// supported. This can be done easily through the
 /// [`define_isa!`](crate::define_isa) macro.
-pub trait ISA: Debug {
+pub trait ISA: Debug + Send + Sync {
     /// Returns the set of supported opcodes.
     fn supported_opcodes(&self) -> &HashSet<Opcode>;
 
@@ -28,11 +33,18 @@ pub trait ISA: Debug {
         self.supported_opcodes().contains(&opcode)
     }
 
+    /// Returns the table dispatching the supported opcodes to their event
+    /// generators, built once for the ISA.
+    fn dispatch_table(&self) -> &DispatchTable;
+
     // TODO: add other feature markers
 }
 
 /// Creates a new ISA and registers all its supported instructions.
 ///
+/// The macro can be invoked from any crate to define a reduced ISA, for which
+/// the prover only instantiates the tables needed by its instructions.
+///
 /// # Example
 ///
 /// ```ignore
@@ -48,11 +60,11 @@ macro_rules! define_isa {
         $(#[$doc])*
         pub struct $isa_ty;
 
-        impl ISA for $isa_ty {
-            fn supported_opcodes(&self) -> &HashSet<Opcode> {
-                use once_cell::sync::Lazy;
-                static OPCODES: Lazy<HashSet<Opcode>> = Lazy::new(|| {
-                    let mut set = HashSet::new();
+        impl $crate::isa::ISA for $isa_ty {
+            fn supported_opcodes(&self) -> &::std::collections::HashSet<$crate::Opcode> {
+                use $crate::isa::once_cell::sync::Lazy;
+                static OPCODES: Lazy<::std::collections::HashSet<$crate::Opcode>> = Lazy::new(|| {
+                    let mut set = ::std::collections::HashSet::new();
                     $(
                         set.insert(<$event_ty as $crate::opcodes::InstructionInfo>::opcode());
                     )*
@@ -61,6 +73,15 @@ macro_rules! define_isa {
 
                 &OPCODES
             }
+
+            fn dispatch_table(&self) -> &$crate::opcodes::DispatchTable {
+                use $crate::isa::once_cell::sync::Lazy;
+                static DISPATCH: Lazy<$crate::opcodes::DispatchTable> = Lazy::new(|| {
+                    $crate::opcodes::DispatchTable::new($isa_ty.supported_opcodes())
+                });
+
+                &DISPATCH
+            }
         }
     };
 }
@@ -74,8 +95,12 @@ define_isa!(
     RecursionISA => [
         B32MulEvent,
         B32MuliEvent,
+        B32InvEvent,
+        B64MulEvent,
         B128AddEvent,
         B128MulEvent,
+        B128InvEvent,
+        BigMulModEvent,
         Groestl256CompressEvent,
         Groestl256OutputEvent,
         RetEvent,
@@ -87,31 +112,71 @@ define_isa!(
     /// supporting all existing instructions.
     GenericISA => [
         AddEvent,
+        Add64Event,
         AddiEvent,
         AndEvent,
         AndiEvent,
         BnzEvent,
         BzEvent,
+        BeqEvent,
+        BneEvent,
+        BltEvent,
+        BgeEvent,
         FpEvent,
+        NopEvent,
         B32MulEvent,
         B32MuliEvent,
+        B32InvEvent,
+        B64MulEvent,
         B128AddEvent,
         B128MulEvent,
+        B128InvEvent,
+        Blake3CompressEvent,
+        BswapEvent,
         CalliEvent,
         CallvEvent,
+        ClzEvent,
+        CmovEvent,
+        CtzEvent,
+        DivEvent,
+        DivuEvent,
+        Groestl256CompressEvent,
+        Groestl256OutputEvent,
+        HaltEvent,
         JumpiEvent,
         JumpvEvent,
+        LbEvent,
+        LbuEvent,
         LdiEvent,
+        LhEvent,
+        LhuEvent,
+        LwEvent,
+        MemcpyEvent,
+        MemsetEvent,
         MulEvent,
         MuliEvent,
         MuluEvent,
         MulsuEvent,
+        MvibEvent,
         MvihEvent,
+        MvvhEvent,
         MvvlEvent,
         MvvwEvent,
         OrEvent,
         OriEvent,
+        PopcntEvent,
+        RemEvent,
+        RemuEvent,
         RetEvent,
+        RolEvent,
+        RoliEvent,
+        RorEvent,
+        RoriEvent,
+        SbEvent,
+        SextbEvent,
+        SexthEvent,
+        Sha256CompressEvent,
+        ShEvent,
         SleEvent,
         SleiEvent,
         SleiuEvent,
@@ -122,16 +187,30 @@ define_isa!(
         SltiEvent,
         SltuEvent,
         SltiuEvent,
+        Slt64Event,
         SraEvent,
         SraiEvent,
         SrlEvent,
         SrliEvent,
         SubEvent,
+        Sub64Event,
+        SwEvent,
         TailiEvent,
         TailvEvent,
         XorEvent,
         XoriEvent,
         AllociEvent,
         AllocvEvent,
+        AllochEvent,
+        FreeEvent,
+        CommitEvent,
+        MerkleVerifyEvent,
+        AbortEvent,
+        AssertEqEvent,
+        AssertZeroEvent,
+        RangeCheckEvent,
+        EcallEvent,
+        ReadAdviceEvent,
+        DebugEvent,
     ]
 );
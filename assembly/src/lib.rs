@@ -6,24 +6,39 @@
 
 // TODO: Add doc
 
+pub mod abi;
+pub mod analysis;
 pub mod assembler;
+pub mod bigint;
+pub mod disassembler;
 pub mod event;
 pub mod execution;
+pub mod export;
+pub mod fuzz;
 pub mod isa;
 pub mod memory;
 pub mod opcodes;
 mod parser;
+pub mod riscv;
+pub mod runtime;
 pub mod util;
+pub mod wasm;
 
 #[cfg(test)]
 mod test_util;
 
-pub use assembler::{AssembledProgram, Assembler, AssemblerError};
+pub use abi::Abi;
+pub use assembler::{
+    AssembledProgram, Assembler, AssemblerError, AssemblerOptions, AssemblerWarning, DebugInfo,
+    SourceLocation, SourceSpan,
+};
+pub use disassembler::{Disassembler, DisassemblerError};
 pub use event::*;
-pub use execution::emulator::{Instruction, InterpreterInstruction};
+pub use execution::emulator::{FieldPc, Instruction, InterpreterInstruction};
+pub use execution::encoding::{DecodeError, InstructionEncoding, INSTRUCTION_ENCODING_VERSION};
 pub use execution::trace::BoundaryValues;
-pub use execution::trace::PetraTrace;
-pub use groestl::{transpose_in_aes, transpose_in_bin};
-pub use memory::{Memory, ProgramRom, ValueRom};
+pub use execution::trace::{GenerationOptions, PetraTrace};
+pub use groestl::{groestl256_initial_chaining_value, transpose_in_aes, transpose_in_bin};
+pub use memory::{FrameMap, HeapStats, Memory, ProgramRom, ValueRom};
 pub use opcodes::{InstructionInfo, Opcode};
 pub use util::init_logger;
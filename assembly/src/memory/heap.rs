@@ -0,0 +1,181 @@
+//! Heap region of the VROM, and tracking of the VROM regions allocated by
+//! `ALLOCI!` / `ALLOCV!` and released by `FREE!`.
+//!
+//! The blocks allocated by `ALLOCH!` live in a region of the VROM reserved to
+//! them, from [`HEAP_START`] to [`HEAP_END`], away from the frames. They are
+//! handed out by a bump allocator and never freed: the VROM being write-once,
+//! the words of a block cannot be reused once written.
+//!
+//! For the same reason, a region allocated by `ALLOCI!` / `ALLOCV!` and
+//! released by `FREE!` cannot be handed out again as a whole. Only the runs of
+//! its words that were never written are given back to the
+//! [`VromAllocator`](super::vrom_allocator::VromAllocator), typically the
+//! unused tail of a buffer allocated for its worst-case size.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::MemoryError;
+
+/// First address of the heap region of the VROM.
+pub const HEAP_START: u32 = 1 << 26;
+/// Address following the heap region of the VROM.
+pub const HEAP_END: u32 = 1 << 27;
+
+/// Bump allocator of the blocks of the heap region.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct HeapAllocator {
+    /// Number of words of the heap region handed out so far, including
+    /// alignment gaps.
+    used: u32,
+}
+
+impl HeapAllocator {
+    /// Allocates a block of `size` words, aligned to its size padded to a
+    /// power of two so that its words can be addressed as `ptr ^ i`.
+    pub(crate) fn alloc(&mut self, size: u32) -> Result<u32, MemoryError> {
+        let exhausted = || MemoryError::HeapExhausted(size);
+        let alignment = size.checked_next_power_of_two().ok_or_else(exhausted)?;
+        let ptr = (HEAP_START + self.used)
+            .checked_next_multiple_of(alignment)
+            .ok_or_else(exhausted)?;
+        let end = ptr
+            .checked_add(size)
+            .filter(|&end| end <= HEAP_END)
+            .ok_or_else(exhausted)?;
+        self.used = end - HEAP_START;
+        Ok(ptr)
+    }
+}
+
+/// Report of the allocations tracked by [`HeapRegions`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapStats {
+    /// Number of allocated regions.
+    pub allocations: u32,
+    /// Number of released regions.
+    pub frees: u32,
+    /// Number of words of the live regions.
+    pub live_words: u64,
+    /// Highest number of words of the live regions over the execution.
+    pub peak_live_words: u64,
+    /// Number of words of released regions given back to the allocator.
+    pub reclaimed_words: u64,
+}
+
+/// Live regions allocated by `ALLOCI!` / `ALLOCV!`, optionally bounded in
+/// total size.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct HeapRegions {
+    /// Requested size of each live region, by address.
+    live: BTreeMap<u32, u32>,
+    /// Maximum number of words of the live regions.
+    limit: Option<u64>,
+    stats: HeapStats,
+}
+
+impl HeapRegions {
+    pub(crate) fn new(limit: Option<u64>) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) const fn stats(&self) -> HeapStats {
+        self.stats
+    }
+
+    /// Checks that a region of `size` words may be allocated without exceeding
+    /// the limit.
+    pub(crate) fn check_allocation(&self, size: u32) -> Result<(), MemoryError> {
+        match self.limit {
+            Some(limit) if self.stats.live_words + size as u64 > limit => {
+                Err(MemoryError::HeapLimitExceeded(limit))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the allocation of `size` words at `ptr`.
+    pub(crate) fn record_allocation(&mut self, ptr: u32, size: u32) {
+        self.live.insert(ptr, size);
+        self.stats.allocations += 1;
+        self.stats.live_words += size as u64;
+        self.stats.peak_live_words = self.stats.peak_live_words.max(self.stats.live_words);
+    }
+
+    /// Releases the live region at `ptr`, returning its size.
+    pub(crate) fn release(&mut self, ptr: u32) -> Result<u32, MemoryError> {
+        let size = self
+            .live
+            .remove(&ptr)
+            .ok_or(MemoryError::InvalidFree(ptr))?;
+        self.stats.frees += 1;
+        self.stats.live_words -= size as u64;
+        Ok(size)
+    }
+
+    /// Records that `words` words of a released region were given back to the
+    /// allocator.
+    pub(crate) fn record_reclaimed(&mut self, words: u32) {
+        self.stats.reclaimed_words += words as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heap_allocator() {
+        let mut heap = HeapAllocator::default();
+        assert_eq!(heap.alloc(3).unwrap(), HEAP_START);
+        // Blocks are aligned to their padded size.
+        assert_eq!(heap.alloc(2).unwrap(), HEAP_START + 4);
+        assert_eq!(heap.alloc(8).unwrap(), HEAP_START + 8);
+        assert_eq!(heap.alloc(0).unwrap(), HEAP_START + 16);
+
+        // The heap region is bounded, and sizes can't overflow.
+        assert!(matches!(
+            heap.alloc(HEAP_END - HEAP_START),
+            Err(MemoryError::HeapExhausted(_))
+        ));
+        assert!(matches!(
+            heap.alloc(u32::MAX),
+            Err(MemoryError::HeapExhausted(u32::MAX))
+        ));
+        let mut heap = HeapAllocator::default();
+        assert_eq!(heap.alloc(HEAP_END - HEAP_START).unwrap(), HEAP_START);
+        assert!(heap.alloc(1).is_err());
+    }
+
+    #[test]
+    fn test_heap_regions() {
+        let mut heap = HeapRegions::new(Some(16));
+        heap.check_allocation(12).unwrap();
+        heap.record_allocation(8, 12);
+        heap.check_allocation(4).unwrap();
+        assert!(matches!(
+            heap.check_allocation(5),
+            Err(MemoryError::HeapLimitExceeded(16))
+        ));
+
+        // Released regions make room for new ones, and are only released once.
+        assert_eq!(heap.release(8).unwrap(), 12);
+        assert!(matches!(heap.release(8), Err(MemoryError::InvalidFree(8))));
+        heap.check_allocation(16).unwrap();
+        heap.record_allocation(32, 16);
+        assert_eq!(
+            heap.stats(),
+            HeapStats {
+                allocations: 2,
+                frees: 1,
+                live_words: 16,
+                peak_live_words: 16,
+                reclaimed_words: 0,
+            }
+        );
+    }
+}
@@ -1,13 +1,18 @@
+mod heap;
+mod prom;
 mod ram;
 pub mod vrom;
 pub mod vrom_allocator;
+mod vrom_pages;
 
-pub(crate) use ram::{Ram, RamValueT};
+pub use heap::{HeapStats, HEAP_END, HEAP_START};
+pub use prom::ProgramRom;
+pub(crate) use ram::{Ram, RamValueT, MAX_RAM_SIZE};
+use serde::{Deserialize, Serialize};
 use strum_macros::Display;
-pub use vrom::ValueRom;
 pub(crate) use vrom::VromValueT;
-
-use crate::execution::InterpreterInstruction;
+pub use vrom::{FrameMap, ValueRom};
+pub use vrom_allocator::{AllocationStrategy, AllocatorStats};
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Display)]
@@ -18,6 +23,10 @@ pub enum MemoryError {
     VromAddressOutOfBounds(u32, usize),
     RamAddressOutOfBounds(u32, usize),
     RamMisalignedAccess(u32, usize),
+    HeapLimitExceeded(u64),
+    InvalidFree(u32),
+    HeapExhausted(u32),
+    FramesOverlapHeap(u32),
 }
 
 /// Trait that defines access granularity in memory, like word size (e.g., u32,
@@ -77,23 +86,66 @@ impl AccessSize for u128 {
     }
 }
 
-/// The Program ROM, or Instruction Memory, is an immutable memory where code is
-/// loaded. It maps every PC to a specific instruction to execute.
-pub type ProgramRom = Vec<InterpreterInstruction>;
-
 /// The `Memory` for an execution contains an *immutable* Program ROM,
-/// and a *mutable* Value ROM.
-#[derive(Debug, Default)]
+/// a *mutable* Value ROM and a byte-addressable RAM.
+///
+/// The VROM is laid out as follows:
+/// - the initial frame starts at address 0, its first two slots (return PC and
+///   return FP) being set to 0,
+/// - all subsequent regions allocated by `ALLOCI!` / `ALLOCV!`, mostly call
+///   frames, are handed out below [`HEAP_START`] by the
+///   [`VromAllocator`](vrom_allocator::VromAllocator). Every region is aligned
+///   to its padded power-of-two size. Where regions are placed depends on the
+///   [`AllocationStrategy`], chosen with [`Memory::with_allocation_strategy`].
+/// - the heap region, from [`HEAP_START`] to [`HEAP_END`], is reserved to the
+///   blocks allocated by `ALLOCH!`, e.g. by the [heap
+///   runtime](crate::runtime::heap). They are bump-allocated and never freed.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Memory {
     prom: ProgramRom,
     vrom: ValueRom,
-    // TODO: We won't need to implement RAM ops at all for the first version.
+    ram: Ram,
 }
 
 impl Memory {
     /// Initializes a new `Memory` instance.
-    pub const fn new(prom: ProgramRom, vrom: ValueRom) -> Self {
-        Self { prom, vrom }
+    pub fn new(prom: ProgramRom, vrom: ValueRom) -> Self {
+        Self {
+            prom,
+            vrom,
+            ram: Ram::default(),
+        }
+    }
+
+    /// Loads `data` in RAM from address 0, e.g. the
+    /// [`data`](crate::AssembledProgram::data) of an assembled program.
+    pub fn with_ram_data(mut self, data: &[u8]) -> Self {
+        self.ram = Ram::new_with_data(data);
+        self
+    }
+
+    /// Places the VROM regions according to `strategy`, instead of the default
+    /// [`AllocationStrategy::SizeClass`].
+    pub fn with_allocation_strategy(mut self, strategy: AllocationStrategy) -> Self {
+        self.vrom = self.vrom.with_allocation_strategy(strategy);
+        self
+    }
+
+    /// Tracks the regions allocated by `ALLOCI!` / `ALLOCV!` and released by
+    /// `FREE!`, failing allocations that would bring the live regions beyond
+    /// `limit` words.
+    ///
+    /// Without tracking, `FREE!` has no effect.
+    pub fn with_heap_regions(mut self, limit: Option<u64>) -> Self {
+        self.vrom = self.vrom.with_heap_regions(limit);
+        self
+    }
+
+    /// Logs the VROM words set from now on, see
+    /// [`ValueRom::take_written_words`].
+    pub fn with_vrom_write_log(mut self) -> Self {
+        self.vrom = self.vrom.with_write_log();
+        self
     }
 
     /// Returns a reference to the PROM.
@@ -113,11 +165,11 @@ impl Memory {
 
     /// Returns a reference to the RAM.
     pub const fn ram(&self) -> &Ram {
-        todo!()
+        &self.ram
     }
 
     /// Returns a mutable reference to the RAM.
     pub fn ram_mut(&mut self) -> &mut Ram {
-        todo!()
+        &mut self.ram
     }
 }
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+
+use crate::execution::{FieldPc, InterpreterInstruction};
+use crate::util::groestl256;
+
+/// The Program ROM, or Instruction Memory, is an immutable memory where code is
+/// loaded. It maps every PC to a specific instruction to execute.
+///
+/// Instructions are stored in program order, and dereference to a slice
+/// indexed by PROM index. Prover-only instructions share the PC of the next
+/// instruction, so a PC may be held by several PROM entries: lookups by PC
+/// return the first of them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(
+    from = "Vec<InterpreterInstruction>",
+    into = "Vec<InterpreterInstruction>"
+)]
+pub struct ProgramRom {
+    instructions: Vec<InterpreterInstruction>,
+    /// PROM index and integer PC of the first instruction at each field PC.
+    targets: HashMap<FieldPc, (u32, u32)>,
+    /// Number of instructions that are not prover-only.
+    num_pcs: u32,
+}
+
+impl ProgramRom {
+    /// Creates a PROM holding `instructions`, in program order.
+    pub fn new(instructions: Vec<InterpreterInstruction>) -> Self {
+        instructions.into_iter().collect()
+    }
+
+    /// Appends `instruction` at the end of the PROM.
+    pub fn push(&mut self, instruction: InterpreterInstruction) {
+        let prom_index = self.instructions.len() as u32;
+        self.targets
+            .entry(FieldPc::from(instruction.field_pc))
+            .or_insert((prom_index, self.num_pcs + 1));
+        if !instruction.prover_only {
+            self.num_pcs += 1;
+        }
+        self.instructions.push(instruction);
+    }
+
+    /// Returns the first instruction at `pc`, if any.
+    pub fn get(&self, pc: FieldPc) -> Option<&InterpreterInstruction> {
+        self.locate(pc)
+            .map(|(prom_index, _)| &self.instructions[prom_index as usize])
+    }
+
+    /// Returns the PROM index and integer PC of the first instruction at `pc`,
+    /// if any.
+    pub fn locate(&self, pc: FieldPc) -> Option<(u32, u32)> {
+        self.targets.get(&pc).copied()
+    }
+
+    /// Returns the instructions, in program order.
+    pub fn as_slice(&self) -> &[InterpreterInstruction] {
+        &self.instructions
+    }
+
+    /// Returns the Groestl-256 digest of the instructions executed by the
+    /// prover, i.e. all but the prover-only ones.
+    ///
+    /// Each instruction is encoded as its field PC followed by its four 16-bit
+    /// halves, all little-endian.
+    pub fn digest(&self) -> [u8; 32] {
+        let bytes = self
+            .instructions
+            .iter()
+            .filter(|entry| !entry.prover_only)
+            .flat_map(|entry| {
+                let halves = entry.instruction.map(|half| half.val().to_le_bytes());
+                entry
+                    .field_pc
+                    .val()
+                    .to_le_bytes()
+                    .into_iter()
+                    .chain(halves.into_iter().flatten())
+            })
+            .collect::<Vec<_>>();
+        groestl256(&bytes)
+    }
+}
+
+impl Deref for ProgramRom {
+    type Target = [InterpreterInstruction];
+
+    fn deref(&self) -> &Self::Target {
+        &self.instructions
+    }
+}
+
+impl From<Vec<InterpreterInstruction>> for ProgramRom {
+    fn from(instructions: Vec<InterpreterInstruction>) -> Self {
+        Self::new(instructions)
+    }
+}
+
+impl From<ProgramRom> for Vec<InterpreterInstruction> {
+    fn from(prom: ProgramRom) -> Self {
+        prom.instructions
+    }
+}
+
+impl FromIterator<InterpreterInstruction> for ProgramRom {
+    fn from_iter<I: IntoIterator<Item = InterpreterInstruction>>(iter: I) -> Self {
+        let mut prom = Self::default();
+        for instruction in iter {
+            prom.push(instruction);
+        }
+        prom
+    }
+}
+
+impl IntoIterator for ProgramRom {
+    type Item = InterpreterInstruction;
+    type IntoIter = std::vec::IntoIter<InterpreterInstruction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.instructions.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ProgramRom {
+    type Item = &'a InterpreterInstruction;
+    type IntoIter = std::slice::Iter<'a, InterpreterInstruction>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.instructions.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assembler;
+
+    #[test]
+    fn test_lookup_by_pc() {
+        let code = "#[framesize(0x10)]
+        _start:
+            ALLOCI! @3, #4
+            B32_MULI @4, @2, #7
+            J done
+        done:
+            RET
+        ";
+        let prom = Assembler::from_code(code).unwrap().prom;
+
+        // The prover-only `ALLOCI!` shares the PC of the next instruction, and
+        // `B32_MULI` spans two PROM entries.
+        assert_eq!(prom.locate(FieldPc::START), Some((0, 1)));
+        assert!(prom.get(FieldPc::START).unwrap().prover_only);
+        assert_eq!(prom.locate(FieldPc::START.next()), Some((2, 2)));
+        let mut pc = FieldPc::START;
+        pc.advance(3);
+        assert_eq!(pc, FieldPc::from_index(3));
+        assert_eq!(prom.locate(pc), Some((4, 4)));
+        assert_eq!(prom.get(pc), prom.last());
+        assert_eq!(prom.locate(pc.next()), None);
+    }
+
+    #[test]
+    fn test_digest() {
+        let code = "#[framesize(0x10)]
+        _start:
+            LDI.W @2, #1
+            RET
+        ";
+        let prom = Assembler::from_code(code).unwrap().prom;
+        let with_hint = Assembler::from_code(&code.replace("RET", "ALLOCI! @3, #4\n RET"))
+            .unwrap()
+            .prom;
+        let other = Assembler::from_code(&code.replace("#1", "#2"))
+            .unwrap()
+            .prom;
+
+        // Prover-only instructions are not part of the digest.
+        assert_eq!(prom.digest(), with_hint.digest());
+        assert_ne!(prom.digest(), other.digest());
+
+        let round_trip = ProgramRom::from(Vec::from(prom.clone()));
+        assert_eq!(round_trip, prom);
+    }
+}
@@ -1,22 +1,34 @@
+use std::collections::HashMap;
+
 use binius_m3::builder::B32;
+use serde::{Deserialize, Serialize};
 
 use super::AccessSize;
 use crate::memory::MemoryError;
 
 /// Represents the RAM for the PetraVM
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Ram {
     /// The actual RAM data
     data: Vec<u8>,
+    /// Initial RAM contents, starting at address 0. The rest of the RAM is
+    /// initially zeroed.
+    initial_data: Vec<u8>,
     /// History of RAM accesses for trace generation
     access_history: Vec<RamAccessEvent>,
+    /// Timestamp of the last access to each word, indexed by word address.
+    word_timestamps: HashMap<u32, u32>,
 }
 
 /// Minimum RAM size in bytes (1KB)
 pub const MIN_RAM_SIZE: usize = 1024;
 
+/// Maximum RAM size in bytes (64MB). Accesses beyond it are out of bounds,
+/// rather than growing the RAM, and its table, to the whole address space.
+pub const MAX_RAM_SIZE: usize = 1 << 26;
+
 /// The concrete type of accessed RAM values.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RamValue {
     /// 8-bit value
     Byte(u8),
@@ -27,7 +39,7 @@ pub enum RamValue {
 }
 
 /// Represents a RAM access event for tracing/proving
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RamAccessEvent {
     /// Address at which the RAM access happened.
     pub address: u32,
@@ -39,9 +51,15 @@ pub struct RamAccessEvent {
     /// Timestamp at which the RAM access happened.
     pub timestamp: u32,
     /// Program Counter at which the RAM access happened.
+    #[serde(with = "crate::util::serde_b32")]
     pub pc: B32,
     /// Type of the RAM access, i.e. whether READ or WRITE.
     pub is_write: bool,
+    /// Value of the 32-bit word containing `address`, before the access.
+    pub prev_word: u32,
+    /// Timestamp of the previous access to the word containing `address`, or
+    /// `None` if this is the first one.
+    pub prev_timestamp: Option<u32>,
 }
 
 /// Trait for types that can be read from or written to the RAM.
@@ -121,10 +139,34 @@ impl Ram {
 
         Self {
             data: vec![0; capacity],
+            initial_data: Vec::new(),
             access_history: Vec::new(),
+            word_timestamps: HashMap::new(),
         }
     }
 
+    /// Creates a new RAM whose contents start with `initial_data`, e.g. the
+    /// static data of an assembled program.
+    pub fn new_with_data(initial_data: &[u8]) -> Self {
+        Self::from_image(initial_data, 0)
+    }
+
+    /// Creates a new RAM holding `image` from `base_addr`, e.g. a data
+    /// structure preloaded for the guest. The rest of the RAM is zeroed.
+    pub fn from_image(image: &[u8], base_addr: u32) -> Self {
+        let end = base_addr as usize + image.len();
+        let mut ram = Self::new(end);
+        ram.data[base_addr as usize..end].copy_from_slice(image);
+        ram.initial_data = ram.data[..end].to_vec();
+        ram
+    }
+
+    /// Returns the initial RAM contents, starting at address 0. The rest of
+    /// the RAM is initially zeroed.
+    pub fn initial_data(&self) -> &[u8] {
+        &self.initial_data
+    }
+
     pub fn capacity(&self) -> usize {
         self.data.len()
     }
@@ -133,12 +175,67 @@ impl Ram {
         &self.access_history
     }
 
+    /// Returns the value of the 32-bit word stored at `word_addr`.
+    fn word(&self, word_addr: u32) -> u32 {
+        let start = (word_addr as usize) << 2;
+        u32::from_le_bytes([
+            self.data[start],
+            self.data[start + 1],
+            self.data[start + 2],
+            self.data[start + 3],
+        ])
+    }
+
+    /// Records an access at `timestamp` to the word containing `addr`.
+    ///
+    /// Returns the current value of that word, along with the timestamp of the
+    /// previous access to it, if any.
+    fn touch_word(&mut self, addr: u32, timestamp: u32) -> (u32, Option<u32>) {
+        let word_addr = addr >> 2;
+        let prev_timestamp = self.word_timestamps.insert(word_addr, timestamp);
+        (self.word(word_addr), prev_timestamp)
+    }
+
+    /// Returns the initial value of the 32-bit word stored at `word_addr`.
+    pub fn initial_word(&self, word_addr: u32) -> u32 {
+        let start = (word_addr as usize) << 2;
+        let mut bytes = [0; 4];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = self
+                .initial_data
+                .get(start + i)
+                .copied()
+                .unwrap_or_default();
+        }
+        u32::from_le_bytes(bytes)
+    }
+
+    /// Returns the final state of every word of the RAM, as
+    /// `(word_addr, value, last_access_timestamp)` tuples ordered by address.
+    ///
+    /// The timestamp is `None` for words that were never accessed.
+    pub fn final_words(&self) -> impl Iterator<Item = (u32, u32, Option<u32>)> + '_ {
+        (0..(self.data.len() >> 2) as u32).map(|word_addr| {
+            (
+                word_addr,
+                self.word(word_addr),
+                self.word_timestamps.get(&word_addr).copied(),
+            )
+        })
+    }
+
     /// Ensures RAM has enough capacity for an access, resizing if necessary.
-    fn ensure_capacity<T: AccessSize>(&mut self, addr: u32) {
+    ///
+    /// Fails if the access goes past [`MAX_RAM_SIZE`].
+    fn ensure_capacity<T: AccessSize>(&mut self, addr: u32) -> Result<(), MemoryError> {
         let required_size = addr as usize + T::byte_size();
+        if required_size > MAX_RAM_SIZE {
+            return Err(MemoryError::RamAddressOutOfBounds(addr, T::byte_size()));
+        }
         if required_size > self.data.len() {
             self.data.resize(required_size.next_power_of_two(), 0);
         }
+        Ok(())
     }
 
     /// Checks if an access is properly aligned
@@ -170,12 +267,8 @@ impl Ram {
         timestamp: u32,
         pc: B32,
     ) -> Result<T, MemoryError> {
-        self.check_alignment::<T>(addr)?;
-        self.check_bounds::<T>(addr)?;
-
-        let addr_usize = addr as usize;
-        let end_addr = addr_usize + T::byte_size();
-        let value = T::from_le_bytes(&self.data[addr_usize..end_addr]);
+        let value = self.peek::<T>(addr)?;
+        let (prev_word, prev_timestamp) = self.touch_word(addr, timestamp);
 
         self.access_history.push(RamAccessEvent {
             address: addr,
@@ -184,6 +277,8 @@ impl Ram {
             timestamp,
             pc,
             is_write: false,
+            prev_word,
+            prev_timestamp,
         });
 
         Ok(value)
@@ -198,12 +293,13 @@ impl Ram {
         pc: B32,
     ) -> Result<(), MemoryError> {
         self.check_alignment::<T>(addr)?;
-        self.ensure_capacity::<T>(addr);
+        self.ensure_capacity::<T>(addr)?;
 
         let addr_usize = addr as usize;
         let end_addr = addr_usize + T::byte_size();
 
         let previous_value = T::from_le_bytes(&self.data[addr_usize..end_addr]);
+        let (prev_word, prev_timestamp) = self.touch_word(addr, timestamp);
 
         let bytes = value.to_le_bytes();
         self.data[addr_usize..addr_usize + bytes.len()].copy_from_slice(&bytes);
@@ -215,11 +311,34 @@ impl Ram {
             timestamp,
             pc,
             is_write: true,
+            prev_word,
+            prev_timestamp,
         });
 
         Ok(())
     }
 
+    /// Reads a value like [`Ram::read`], without recording the access.
+    pub fn peek<T: RamValueT>(&self, addr: u32) -> Result<T, MemoryError> {
+        self.check_alignment::<T>(addr)?;
+        self.check_bounds::<T>(addr)?;
+
+        let addr_usize = addr as usize;
+        let end_addr = addr_usize + T::byte_size();
+        Ok(T::from_le_bytes(&self.data[addr_usize..end_addr]))
+    }
+
+    /// Writes a value like [`Ram::write`], without recording the access.
+    pub fn store<T: RamValueT>(&mut self, addr: u32, value: T) -> Result<(), MemoryError> {
+        self.check_alignment::<T>(addr)?;
+        self.ensure_capacity::<T>(addr)?;
+
+        let addr_usize = addr as usize;
+        let bytes = value.to_le_bytes();
+        self.data[addr_usize..addr_usize + bytes.len()].copy_from_slice(&bytes);
+        Ok(())
+    }
+
     pub fn data(&self) -> &[u8] {
         &self.data
     }
@@ -249,6 +368,8 @@ mod tests {
             timestamp: 1,
             pc: B32::ONE,
             is_write: true,
+            prev_word: 0,
+            prev_timestamp: None,
         };
         let read_history = RamAccessEvent {
             address: 0,
@@ -257,6 +378,8 @@ mod tests {
             timestamp: 2,
             pc: B32::ONE,
             is_write: false,
+            prev_word: 0x12345678,
+            prev_timestamp: Some(1),
         };
         assert_eq!(ram.access_history[0], write_history);
         assert_eq!(ram.access_history[1], read_history);
@@ -298,6 +421,20 @@ mod tests {
         assert_eq!(ram.capacity(), MIN_RAM_SIZE * 2);
     }
 
+    #[test]
+    fn test_from_image() {
+        let mut ram = Ram::from_image(&[1, 2, 3, 4, 5], 2000);
+        assert_eq!(ram.capacity(), 2048);
+        assert_eq!(ram.initial_word(499), 0);
+        assert_eq!(ram.initial_word(500), 0x04030201);
+        assert_eq!(ram.initial_word(501), 5);
+        assert_eq!(ram.read::<u16>(2002, 1, B32::ONE).unwrap(), 0x0403);
+        assert_eq!(ram.read::<u8>(2004, 2, B32::ONE).unwrap(), 5);
+        assert_eq!(ram.read::<u32>(0, 3, B32::ONE).unwrap(), 0);
+
+        assert_eq!(Ram::from_image(&[], 0).capacity(), MIN_RAM_SIZE);
+    }
+
     #[test]
     fn test_read_out_of_bounds() {
         let mut ram = Ram::new(MIN_RAM_SIZE);
@@ -314,6 +451,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_write_past_max_size() {
+        let mut ram = Ram::new(MIN_RAM_SIZE);
+
+        let last_word = (MAX_RAM_SIZE - 4) as u32;
+        ram.write::<u32>(last_word, 0x12345678, 1, B32::ONE)
+            .unwrap();
+        assert_eq!(ram.capacity(), MAX_RAM_SIZE);
+
+        for addr in [MAX_RAM_SIZE as u32, 0xFFFFFFFC] {
+            match ram.write::<u32>(addr, 0x12345678, 2, B32::ONE) {
+                Err(MemoryError::RamAddressOutOfBounds(a, 4)) => assert_eq!(a, addr),
+                res => panic!("Expected RamAddressOutOfBounds error, got {res:?}"),
+            }
+            assert!(matches!(
+                ram.store::<u8>(addr, 1),
+                Err(MemoryError::RamAddressOutOfBounds(_, 1))
+            ));
+        }
+        assert_eq!(ram.capacity(), MAX_RAM_SIZE);
+        assert_eq!(ram.access_history().len(), 1);
+    }
+
     #[test]
     fn test_alignment_check() {
         let mut ram = Ram::new(MIN_RAM_SIZE);
@@ -351,4 +511,42 @@ mod tests {
         let word: u32 = ram.read(0, 9, B32::ONE).unwrap();
         assert_eq!(word, 0x44332211);
     }
+
+    #[test]
+    fn test_new_with_data() {
+        let mut ram = Ram::new_with_data(&[1, 2, 3, 4, 5]);
+        assert_eq!(ram.capacity(), MIN_RAM_SIZE);
+        assert_eq!(ram.initial_word(0), 0x04030201);
+        assert_eq!(ram.initial_word(1), 5);
+
+        ram.write::<u8>(4, 6, 0, B32::ONE).unwrap();
+        assert_eq!(ram.read::<u32>(0, 1, B32::ONE).unwrap(), 0x04030201);
+        assert_eq!(ram.read::<u32>(4, 2, B32::ONE).unwrap(), 6);
+        assert_eq!(ram.initial_word(1), 5);
+    }
+
+    #[test]
+    fn test_word_tracking() {
+        let mut ram = Ram::new(MIN_RAM_SIZE);
+
+        ram.write::<u32>(8, 0xAABBCCDD, 3, B32::ONE).unwrap();
+        ram.write::<u8>(9, 0x11, 5, B32::ONE).unwrap();
+        let _: u16 = ram.read(10, 7, B32::ONE).unwrap();
+
+        let history = ram.access_history();
+        assert_eq!((history[0].prev_word, history[0].prev_timestamp), (0, None));
+        assert_eq!(
+            (history[1].prev_word, history[1].prev_timestamp),
+            (0xAABBCCDD, Some(3))
+        );
+        assert_eq!(
+            (history[2].prev_word, history[2].prev_timestamp),
+            (0xAABB11DD, Some(5))
+        );
+
+        let final_words = ram.final_words().collect::<Vec<_>>();
+        assert_eq!(final_words.len(), MIN_RAM_SIZE / 4);
+        assert_eq!(final_words[0], (0, 0, None));
+        assert_eq!(final_words[2], (2, 0xAABB11DD, Some(7)));
+    }
 }
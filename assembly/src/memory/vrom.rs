@@ -1,31 +1,59 @@
-use std::{cell::Cell, ops::Shl};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    ops::Shl,
+};
 
 #[cfg(test)]
 use binius_m3::builder::B16;
+use binius_m3::builder::B32;
 use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 
 use super::{AccessSize, MemoryError};
-use crate::memory::vrom_allocator::VromAllocator;
+use crate::assembler::LabelsFrameSizes;
+use crate::memory::{
+    heap::{HeapAllocator, HeapRegions, HeapStats, HEAP_START},
+    vrom_allocator::{AllocationStrategy, AllocatorStats, VromAllocator},
+    vrom_pages::VromPages,
+};
+use crate::util::groestl256;
+
+/// Field PC of the entry of the function owning each frame, by frame pointer.
+pub type FrameMap = BTreeMap<u32, B32>;
 
 /// `ValueRom` represents a memory structure for storing different sized values.
-#[derive(Clone, Debug, Default)]
+///
+/// Values are stored in pages allocated on demand, so that the parts of the
+/// address space that are never written take no memory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ValueRom {
-    /// Storage for values, each slot is an `Option<u32>`.
-    data: Vec<Option<u32>>,
-    /// Number of reads/writes per address (interior mutability).
-    access_counts: Vec<Cell<u32>>,
+    /// Storage for values, each slot is an `Option<u32>` along with its number
+    /// of reads/writes.
+    data: VromPages,
     /// Allocator for new frames
     vrom_allocator: VromAllocator,
+    /// Initial values provided by the host, at the first addresses.
+    init_values: Vec<u32>,
+    /// Regions allocated by `ALLOCI!` / `ALLOCV!`, if tracked.
+    heap: Option<HeapRegions>,
+    /// Allocator of the blocks of the heap region.
+    heap_allocator: HeapAllocator,
+    /// Addresses of the words set since the log was last drained, if logged.
+    #[serde(skip)]
+    write_log: Option<Vec<u32>>,
 }
 
 impl ValueRom {
     /// Creates an new ValueRom.
     pub fn new(data: Vec<Option<u32>>) -> Self {
-        let len = data.len();
         Self {
-            data,
-            access_counts: vec![Cell::new(0); len],
+            data: data.into(),
             vrom_allocator: Default::default(),
+            init_values: Vec::new(),
+            heap: None,
+            heap_allocator: HeapAllocator::default(),
+            write_log: None,
         }
     }
 
@@ -33,17 +61,96 @@ impl ValueRom {
         self.vrom_allocator.size()
     }
 
+    /// Places the frames allocated from now on according to `strategy`.
+    pub fn with_allocation_strategy(mut self, strategy: AllocationStrategy) -> Self {
+        self.vrom_allocator.set_strategy(strategy);
+        self
+    }
+
+    /// Tracks the regions allocated from now on, bounding their total size to
+    /// `limit` words. See
+    /// [`Memory::with_heap_regions`](super::Memory::with_heap_regions).
+    pub fn with_heap_regions(mut self, limit: Option<u64>) -> Self {
+        self.heap = Some(HeapRegions::new(limit));
+        self
+    }
+
+    /// Logs the addresses of the words set from now on, to be drained with
+    /// [`ValueRom::take_written_words`].
+    pub fn with_write_log(mut self) -> Self {
+        self.write_log = Some(Vec::new());
+        self
+    }
+
+    /// Returns the addresses and values of the words set since the last call,
+    /// in order of writing, or nothing if the writes are not logged, see
+    /// [`ValueRom::with_write_log`].
+    pub fn take_written_words(&mut self) -> Vec<(u32, u32)> {
+        let Some(log) = &mut self.write_log else {
+            return Vec::new();
+        };
+        std::mem::take(log)
+            .into_iter()
+            .map(|addr| {
+                let word = self.data.get(addr as usize);
+                (addr, word.expect("Logged words are set"))
+            })
+            .collect()
+    }
+
+    /// Returns the report of the tracked regions, if any.
+    pub fn heap_stats(&self) -> Option<HeapStats> {
+        self.heap.as_ref().map(HeapRegions::stats)
+    }
+
+    /// Returns the statistics of the frames allocated so far.
+    pub fn allocator_stats(&self) -> AllocatorStats {
+        self.vrom_allocator.stats()
+    }
+
+    /// Returns the number of words held in memory, i.e. the words of the pages
+    /// with at least one value set.
+    pub fn resident_words(&self) -> usize {
+        self.data.resident_words()
+    }
+
     /// Creates a default VROM and initializes it with the provided u32 values.
     pub fn new_with_init_vals(init_values: &[u32]) -> Self {
         let data = init_values.iter().copied().map(Some).collect::<Vec<_>>();
-        let len = data.len();
         Self {
-            data,
-            access_counts: vec![Cell::new(0); len],
+            data: data.into(),
             vrom_allocator: Default::default(),
+            init_values: init_values.to_vec(),
+            heap: None,
+            heap_allocator: HeapAllocator::default(),
+            write_log: None,
         }
     }
 
+    /// Returns the initial values of the VROM, held at its first addresses.
+    pub fn init_values(&self) -> &[u32] {
+        &self.init_values
+    }
+
+    /// Returns the commitment to the initial values of the VROM, see
+    /// [`ValueRom::commit_init_values`].
+    pub fn init_commitment(&self) -> [u8; 32] {
+        Self::commit_init_values(&self.init_values)
+    }
+
+    /// Returns the commitment to `init_values`, as the Groestl-256 digest of
+    /// the values encoded in little-endian.
+    ///
+    /// Hosts can compute it from the inputs they provide, to check that an
+    /// execution started from them.
+    pub fn commit_init_values(init_values: &[u32]) -> [u8; 32] {
+        let bytes = init_values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<_>>();
+        groestl256(&bytes)
+    }
+
     /// Generic read method for supported types. This will read a value stored
     /// at the provided index.
     ///
@@ -67,11 +174,11 @@ impl ValueRom {
     fn read_internal<T: VromValueT>(&self, index: u32) -> Result<T, MemoryError> {
         let mut value = T::zero();
 
-        // Read the entire chunk at once.
-        let read_data = &self.data[index as usize..index as usize + T::word_size()];
-
-        for (i, opt_word) in read_data.iter().enumerate() {
-            let word = opt_word.ok_or(MemoryError::VromMissingValue(index))?;
+        for i in 0..T::word_size() {
+            let word = self
+                .data
+                .get(index as usize + i)
+                .ok_or(MemoryError::VromMissingValue(index))?;
 
             // Shift the word to its appropriate position and add to the value
             value = value + (T::from(word) << (i * 32));
@@ -80,6 +187,64 @@ impl ValueRom {
         Ok(value)
     }
 
+    /// Returns the addresses and values of all the words set so far, by
+    /// increasing address.
+    pub fn iter_set(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.data.iter_set().map(|(addr, word, _)| (addr, word))
+    }
+
+    /// Returns the address following the highest word set so far, or 0 if no
+    /// word is set.
+    ///
+    /// Unlike [`ValueRom::size`], which covers all the allocated frames, it
+    /// only accounts for the words actually written.
+    pub fn high_water_mark(&self) -> u32 {
+        self.data
+            .iter_set()
+            .next_back()
+            .map_or(0, |(addr, _, _)| addr + 1)
+    }
+
+    /// Formats the words set so far, grouped by frame.
+    ///
+    /// Each frame of `frame_map` spans the frame size of its function in
+    /// `frame_sizes`, and lists its set slots. The words outside of these
+    /// frames are listed last, by address.
+    pub fn dump_frames(&self, frame_map: &FrameMap, frame_sizes: &LabelsFrameSizes) -> String {
+        let mut dump = String::new();
+        let mut in_frames = HashSet::new();
+        for (&fp, &function) in frame_map {
+            let frame_size = frame_sizes.get(&function).copied().unwrap_or_default();
+            writeln!(
+                dump,
+                "Frame {fp:#010x} of function {:#010x}, size {frame_size:#x}",
+                function.val()
+            )
+            .expect("Writing to a string cannot fail");
+            for slot in 0..frame_size as u32 {
+                let addr = fp ^ slot;
+                let Some(word) = self.data.get(addr as usize) else {
+                    continue;
+                };
+                in_frames.insert(addr);
+                writeln!(dump, "  @{slot}: {word:#010x}").expect("Writing to a string cannot fail");
+            }
+        }
+
+        let mut outside = self
+            .iter_set()
+            .filter(|(addr, _)| !in_frames.contains(addr))
+            .peekable();
+        if outside.peek().is_some() {
+            dump.push_str("Outside of frames\n");
+        }
+        for (addr, word) in outside {
+            writeln!(dump, "  {addr:#010x}: {word:#010x}")
+                .expect("Writing to a string cannot fail");
+        }
+        dump
+    }
+
     /// Checks if the value at the given index is set.
     pub fn check_value_set<T: VromValueT>(&self, index: u32) -> Result<bool, MemoryError> {
         self.check_alignment::<T>(index)?;
@@ -87,13 +252,7 @@ impl ValueRom {
             // VROM hasn't been expanded to the target index, there is nothing to read yet.
             return Ok(false);
         };
-        let read_data = &self.data[index as usize..index as usize + T::word_size()];
-        for &opt_word in read_data {
-            if opt_word.is_none() {
-                return Ok(false);
-            }
-        }
-        Ok(true)
+        Ok((0..T::word_size()).all(|i| self.data.get(index as usize + i).is_some()))
     }
 
     /// Generic write method for supported types. Counts each write access per
@@ -110,12 +269,9 @@ impl ValueRom {
     ) -> Result<(), MemoryError> {
         self.check_alignment::<T>(index)?;
         self.ensure_capacity::<T>(index);
-        if record {
-            self.record_access::<T>(index);
-        }
         for i in 0..T::word_size() {
             let cur_word = (value.to_u128() >> (32 * i)) as u32;
-            let prev_value = &mut self.data[index as usize + i];
+            let prev_value = self.data.get_mut(index as usize + i);
             if let Some(prev_val) = prev_value {
                 // The VROM is write-once. If a value already exists at `index`,
                 // check that it matches the value we wanted to write.
@@ -125,9 +281,17 @@ impl ValueRom {
             } else {
                 // The VROM hasn't been updated yet at the provided `index`.
                 *prev_value = Some(cur_word);
+                if let Some(log) = &mut self.write_log {
+                    log.push(index + i as u32);
+                }
             }
         }
 
+        // Record the access once the pages of the words are allocated.
+        if record {
+            self.record_access::<T>(index);
+        }
+
         Ok(())
     }
 
@@ -138,14 +302,66 @@ impl ValueRom {
         res
     }
 
+    /// Allocates a region of `size` words for `ALLOCI!` / `ALLOCV!`, tracking
+    /// it if heap regions are tracked.
+    ///
+    /// Fails if the region would reach the heap region.
+    pub(crate) fn allocate_region(&mut self, size: u32) -> Result<u32, MemoryError> {
+        if let Some(heap) = &self.heap {
+            heap.check_allocation(size)?;
+        }
+        let ptr = self.allocate_new_frame(size);
+        if self.vrom_allocator.size() > HEAP_START as usize {
+            return Err(MemoryError::FramesOverlapHeap(ptr));
+        }
+        if let Some(heap) = &mut self.heap {
+            heap.record_allocation(ptr, size);
+        }
+        Ok(ptr)
+    }
+
+    /// Allocates a block of `size` words in the heap region for `ALLOCH!`.
+    pub(crate) fn allocate_heap_block(&mut self, size: u32) -> Result<u32, MemoryError> {
+        let ptr = self.heap_allocator.alloc(size)?;
+        self.ensure_capacity::<u32>(ptr + size);
+        Ok(ptr)
+    }
+
+    /// Releases the region at `ptr` for `FREE!`, if heap regions are tracked.
+    ///
+    /// The runs of words of the region that were never written are given back
+    /// to the allocator. The other words cannot be reused, the VROM being
+    /// write-once.
+    pub(crate) fn free_region(&mut self, ptr: u32) -> Result<(), MemoryError> {
+        let Some(heap) = &mut self.heap else {
+            return Ok(());
+        };
+        let size = heap.release(ptr)?;
+
+        let mut reclaimed = 0;
+        let mut unset_run = None;
+        // The end of the region closes the last run.
+        for addr in ptr..=ptr + size {
+            let unset = addr < ptr + size && self.data.get(addr as usize).is_none();
+            match (unset, unset_run) {
+                (true, None) => unset_run = Some(addr),
+                (false, Some(start)) => {
+                    reclaimed += self.vrom_allocator.reclaim(start, addr - start);
+                    unset_run = None;
+                }
+                _ => {}
+            }
+        }
+        heap.record_reclaimed(reclaimed);
+        Ok(())
+    }
+
     /// Ensures the VROM has enough capacity for an access, resizing if
     /// necessary.
     fn ensure_capacity<T: VromValueT>(&mut self, addr: u32) {
         let required_size = addr as usize + T::word_size();
         if required_size > self.data.len() {
-            let new_len = required_size.next_power_of_two();
-            self.data.resize(new_len, None);
-            self.access_counts.resize(new_len, Cell::new(0));
+            self.data.grow(required_size.next_power_of_two());
         }
     }
 
@@ -169,6 +385,49 @@ impl ValueRom {
         Ok(())
     }
 
+    /// Reads the words at the consecutive addresses from `index` into `words`,
+    /// like as many [`ValueRom::read`] of `u32`, or [`ValueRom::peek`] unless
+    /// `record`, but looking each page of the VROM up once.
+    pub fn read_words(
+        &self,
+        index: u32,
+        words: &mut [u32],
+        record: bool,
+    ) -> Result<(), MemoryError> {
+        if index as usize + words.len() > self.data.len() {
+            return Err(MemoryError::VromAddressOutOfBounds(index, words.len()));
+        }
+        self.data
+            .read_run(index as usize, words, record)
+            .map_err(|addr| MemoryError::VromMissingValue(addr as u32))
+    }
+
+    /// Writes `words` at the consecutive addresses from `index`, like as many
+    /// [`ValueRom::write`] of `u32`, but looking each page of the VROM up once.
+    pub fn write_words(
+        &mut self,
+        index: u32,
+        words: &[u32],
+        record: bool,
+    ) -> Result<(), MemoryError> {
+        let required_size = index as usize + words.len();
+        if required_size > self.data.len() {
+            self.data.grow(required_size.next_power_of_two());
+        }
+        let unset = self.write_log.as_ref().map(|_| {
+            (index..index + words.len() as u32)
+                .filter(|&addr| self.data.get(addr as usize).is_none())
+                .collect::<Vec<_>>()
+        });
+        self.data
+            .write_run(index as usize, words, record)
+            .map_err(|(addr, prev, word)| MemoryError::VromRewrite(addr as u32, prev, word))?;
+        if let (Some(log), Some(unset)) = (&mut self.write_log, unset) {
+            log.extend(unset);
+        }
+        Ok(())
+    }
+
     /// Helper method to set a value at the given VROM offset and returns a
     /// [`B16`] for that offset.
     #[cfg(test)]
@@ -180,9 +439,7 @@ impl ValueRom {
     /// Record accesses for addresses [addr, addr+size).
     pub(crate) fn record_access<T: VromValueT>(&self, addr: u32) {
         for i in 0..T::word_size() {
-            let idx = addr as usize + i;
-            let count = self.access_counts[idx].get();
-            self.access_counts[idx].set(count + 1);
+            self.data.record_access(addr as usize + i);
         }
     }
 
@@ -190,17 +447,9 @@ impl ValueRom {
     /// descending.
     pub fn sorted_access_counts(&self) -> Vec<(u32, u32, u32)> {
         let mut entries: Vec<(u32, u32, u32)> = self
-            .access_counts
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, cell)| {
-                let count = cell.get();
-                if count > 0 {
-                    self.data[idx].map(|val| (idx as u32, val, count))
-                } else {
-                    None
-                }
-            })
+            .data
+            .iter_set()
+            .filter(|&(_, _, count)| count > 0)
             .collect();
         entries.sort_by(|a, b| b.2.cmp(&a.2));
         entries
@@ -233,6 +482,7 @@ impl VromValueT for u128 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::vrom_pages::PAGE_SIZE;
 
     #[test]
     fn test_set_and_get_value() {
@@ -272,6 +522,154 @@ mod tests {
         assert_eq!(vrom_access_counts[3], (1, 0x99AABBCC, 3));
     }
 
+    #[test]
+    fn test_words() {
+        let mut vrom = ValueRom::default();
+        // The words span two pages.
+        let start = PAGE_SIZE as u32 - 2;
+        let words = [1, 2, 3, 4];
+        vrom.write_words(start, &words, true).unwrap();
+
+        let mut read = [0; 4];
+        vrom.read_words(start, &mut read, true).unwrap();
+        assert_eq!(read, words);
+        vrom.read_words(start, &mut read, false).unwrap();
+        for (i, &word) in words.iter().enumerate() {
+            assert_eq!(vrom.read::<u32>(start + i as u32).unwrap(), word);
+        }
+        // Each word was written once and read twice with recording.
+        assert!(vrom
+            .sorted_access_counts()
+            .iter()
+            .all(|&(_, _, count)| count == 3));
+
+        // Rewriting the same words is allowed, but not other values.
+        vrom.write_words(start, &words, false).unwrap();
+        assert!(matches!(
+            vrom.write_words(start, &[1, 2, 5, 4], false),
+            Err(MemoryError::VromRewrite(addr, 3, 5)) if addr == start + 2
+        ));
+        assert!(matches!(
+            vrom.read_words(start + 2, &mut read, false),
+            Err(MemoryError::VromMissingValue(addr)) if addr == start + 4
+        ));
+        assert!(matches!(
+            vrom.read_words(vrom.data.len() as u32 - 2, &mut read, false),
+            Err(MemoryError::VromAddressOutOfBounds(_, 4))
+        ));
+    }
+
+    #[test]
+    fn test_sparse_frames() {
+        let mut vrom = ValueRom::default();
+        let frames = (0..4)
+            .map(|_| vrom.allocate_new_frame(1 << 16))
+            .collect::<Vec<_>>();
+        for &fp in &frames {
+            vrom.write(fp ^ 2, fp, true).unwrap();
+        }
+
+        // Only the pages holding the written slots take memory.
+        assert_eq!(vrom.size(), 1 << 18);
+        assert_eq!(vrom.resident_words(), 4 * PAGE_SIZE);
+        for &fp in &frames {
+            assert_eq!(vrom.read::<u32>(fp ^ 2).unwrap(), fp);
+            assert!(matches!(
+                vrom.read::<u32>(fp ^ 3),
+                Err(MemoryError::VromMissingValue(_))
+            ));
+        }
+        assert_eq!(vrom.sorted_access_counts().len(), 4);
+    }
+
+    #[test]
+    fn test_allocation_strategies() {
+        let program = crate::Assembler::from_code(include_str!("../../../examples/func_call.asm"))
+            .expect("The program should assemble");
+        for strategy in [
+            AllocationStrategy::Bump,
+            AllocationStrategy::SizeClass,
+            AllocationStrategy::FirstFit,
+        ] {
+            let memory =
+                crate::Memory::new(program.prom.clone(), ValueRom::new_with_init_vals(&[0, 0]))
+                    .with_allocation_strategy(strategy);
+            let (trace, _) = crate::PetraTrace::generate(
+                Box::new(crate::isa::GenericISA),
+                memory,
+                program.frame_sizes.clone(),
+            )
+            .unwrap();
+            assert_eq!(trace.vrom().read::<u32>(2).unwrap(), 22);
+
+            // The callee frame of 5 slots is padded to 8, leaving a slack block
+            // of 2 slots.
+            let stats = trace.vrom().allocator_stats();
+            assert_eq!(stats.regions, 2);
+            assert_eq!(stats.requested_words, 13);
+            assert_eq!(stats.allocated_words, 16);
+            let slack_words = if strategy == AllocationStrategy::Bump {
+                0
+            } else {
+                2
+            };
+            assert_eq!(stats.slack_words, slack_words);
+        }
+    }
+
+    #[test]
+    fn test_introspection() {
+        let mut vrom = ValueRom::new_with_init_vals(&[0, 0, 7]);
+        assert_eq!(vrom.allocate_new_frame(4), 0);
+        assert_eq!(vrom.allocate_new_frame(4), 4);
+        vrom.write(5, 0u32, false).unwrap();
+        vrom.write(6, 42u32, false).unwrap();
+        vrom.write(9, 1u32, false).unwrap();
+
+        assert_eq!(
+            vrom.iter_set().collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (2, 7), (5, 0), (6, 42), (9, 1)]
+        );
+        assert_eq!(vrom.high_water_mark(), 10);
+        assert_eq!(vrom.size(), 8);
+        assert_eq!(ValueRom::default().high_water_mark(), 0);
+
+        let callee = B32::new(0x42);
+        let frame_map = FrameMap::from([(0, B32::new(1)), (4, callee)]);
+        let frame_sizes = LabelsFrameSizes::from([(B32::new(1), 4), (callee, 4)]);
+        assert_eq!(
+            vrom.dump_frames(&frame_map, &frame_sizes),
+            "Frame 0x00000000 of function 0x00000001, size 0x4
+  @0: 0x00000000
+  @1: 0x00000000
+  @2: 0x00000007
+Frame 0x00000004 of function 0x00000042, size 0x4
+  @1: 0x00000000
+  @2: 0x0000002a
+Outside of frames
+  0x00000009: 0x00000001
+"
+        );
+    }
+
+    #[test]
+    fn test_init_commitment() {
+        let vrom = ValueRom::new_with_init_vals(&[0, 0, 27]);
+        assert_eq!(vrom.init_values(), [0, 0, 27]);
+        assert_eq!(
+            vrom.init_commitment(),
+            ValueRom::commit_init_values(&[0, 0, 27])
+        );
+        assert_ne!(
+            vrom.init_commitment(),
+            ValueRom::commit_init_values(&[0, 0, 28])
+        );
+        assert_ne!(
+            ValueRom::default().init_commitment(),
+            ValueRom::commit_init_values(&[0])
+        );
+    }
+
     #[test]
     fn test_value_rewrite_error() {
         let mut vrom = ValueRom::default();
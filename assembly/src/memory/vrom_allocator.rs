@@ -1,7 +1,11 @@
 use std::collections::BTreeMap;
 
+use serde::{Deserialize, Serialize};
+
+use crate::abi::Abi;
+
 // We need at least two slots for return pc and return fp.
-const MIN_FRAME_SIZE: u32 = 2;
+const MIN_FRAME_SIZE: u32 = Abi::STANDARD.min_frame_size as u32;
 
 /// An iterator used to allocate power-of-two blocks with proper alignment.
 pub struct PowerOfTwoBlocksIterator {
@@ -64,22 +68,105 @@ fn split_into_power_of_two_blocks(addr: u32, size: u32) -> PowerOfTwoBlocksItera
     }
 }
 
+/// How the [`VromAllocator`] places new regions.
+///
+/// The VROM being write-once, the frames of returned functions or of functions
+/// that performed a tail call are never reused: only the slack left by padding
+/// and alignment, which is never written, may be handed out again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AllocationStrategy {
+    /// Always allocates at the end of the allocated space, after aligning it.
+    /// Padding and alignment gaps are lost.
+    Bump,
+    /// Keeps padding and alignment gaps as slack blocks, by size class, and
+    /// reuses a block of the smallest size class fitting each new region.
+    #[default]
+    SizeClass,
+    /// Keeps padding and alignment gaps as slack blocks, and reuses the fitting
+    /// block with the lowest address, packing the regions at the start of the
+    /// VROM.
+    FirstFit,
+}
+
+/// Statistics of a [`VromAllocator`], to tune the frame sizes of a program.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocatorStats {
+    /// Number of allocated regions.
+    pub regions: u32,
+    /// Total number of words requested by the allocations.
+    pub requested_words: u64,
+    /// Size of the allocated address space, in words.
+    pub allocated_words: u64,
+    /// Number of words in slack blocks that may still be reused.
+    pub slack_words: u64,
+}
+
+impl AllocatorStats {
+    /// Returns the fraction of the allocated address space that was not
+    /// requested, lost to padding and alignment.
+    pub fn fragmentation(&self) -> f64 {
+        if self.allocated_words == 0 {
+            return 0.0;
+        }
+        1.0 - self.requested_words as f64 / self.allocated_words as f64
+    }
+}
+
 /// VromAllocator allocates VROM addresses for objects, ensuring that:
 /// - The object's size is padded to the next power-of-two (with a minimum of
 ///   MIN_FRAME_SIZE),
-/// - Available slack regions are reused when possible,
+/// - Available slack regions are reused when possible, according to the
+///   [`AllocationStrategy`],
 /// - The allocation pointer is aligned (least significant log₂(padded size)
 ///   bits are cleared).
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct VromAllocator {
     /// The next free allocation pointer.
     pos: u32,
     /// Slack blocks available for reuse, organized by the exponent
     /// (i.e. block size = 2^exponent).
     slack: BTreeMap<u32, Vec<u32>>,
+    strategy: AllocationStrategy,
+    /// Number of allocated regions.
+    regions: u32,
+    /// Total number of words requested by the allocations.
+    requested_words: u64,
 }
 
 impl VromAllocator {
+    /// Creates an allocator placing regions according to `strategy`.
+    pub fn new(strategy: AllocationStrategy) -> Self {
+        Self {
+            strategy,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the strategy used to place new regions.
+    pub const fn strategy(&self) -> AllocationStrategy {
+        self.strategy
+    }
+
+    /// Sets the strategy used to place new regions. The slack blocks recorded
+    /// so far are kept.
+    pub fn set_strategy(&mut self, strategy: AllocationStrategy) {
+        self.strategy = strategy;
+    }
+
+    /// Returns the statistics of the allocations so far.
+    pub fn stats(&self) -> AllocatorStats {
+        AllocatorStats {
+            regions: self.regions,
+            requested_words: self.requested_words,
+            allocated_words: self.pos as u64,
+            slack_words: self
+                .slack
+                .iter()
+                .map(|(exp, blocks)| (blocks.len() as u64) << exp)
+                .sum(),
+        }
+    }
+
     /// Get the size of the VROM.
     pub const fn size(&self) -> usize {
         self.pos as usize
@@ -94,7 +181,8 @@ impl VromAllocator {
     ///
     /// The allocation process:
     /// 1. Compute `p`, the padded size (power-of-two ≥ MIN_FRAME_SIZE).
-    /// 2. Attempt to reuse a slack block of size ≥ `p`.
+    /// 2. Attempt to reuse a slack block of size ≥ `p`, picked according to the
+    ///    [`AllocationStrategy`].
     /// 3. If found, split off any leftover external slack.
     /// 4. Otherwise, align the allocation pointer (by clearing the least
     ///    significant log₂(padded size) bits) and allocate a fresh block.
@@ -105,22 +193,18 @@ impl VromAllocator {
         let p = requested_size.next_power_of_two().max(MIN_FRAME_SIZE);
         // k: exponent such that p == 2^k.
         let k = p.trailing_zeros();
+        self.regions += 1;
+        self.requested_words += requested_size as u64;
 
         // Attempt to find a slack block with size ≥ p.
-        if let Some((&exp, blocks)) = self.slack.range_mut(k..).next() {
-            if let Some(addr) = blocks.pop() {
-                let block_size = 1 << exp;
-                // Remove empty vectors to keep the map clean
-                if blocks.is_empty() {
-                    self.slack.remove(&exp);
-                }
-                let allocated_addr = addr;
-                let external_leftover = block_size - p;
-                // Record leftover external slack.
-                self.add_slack(allocated_addr + p, external_leftover);
-                self.record_internal_slack(allocated_addr, requested_size, p);
-                return allocated_addr;
-            }
+        if let Some((addr, exp)) = self.take_slack(k) {
+            let block_size = 1 << exp;
+            let allocated_addr = addr;
+            let external_leftover = block_size - p;
+            // Record leftover external slack.
+            self.add_slack(allocated_addr + p, external_leftover);
+            self.record_internal_slack(allocated_addr, requested_size, p);
+            return allocated_addr;
         }
 
         // No suitable slack block found: perform a fresh allocation.
@@ -135,6 +219,48 @@ impl VromAllocator {
         allocated_addr
     }
 
+    /// Removes a slack block of size at least 2^`k` picked according to the
+    /// strategy, returning its address and exponent.
+    fn take_slack(&mut self, k: u32) -> Option<(u32, u32)> {
+        let (exp, index) = match self.strategy {
+            AllocationStrategy::Bump => return None,
+            AllocationStrategy::SizeClass => {
+                let (&exp, blocks) = self.slack.range(k..).next()?;
+                (exp, blocks.len() - 1)
+            }
+            AllocationStrategy::FirstFit => self
+                .slack
+                .range(k..)
+                .flat_map(|(&exp, blocks)| {
+                    blocks
+                        .iter()
+                        .enumerate()
+                        .map(move |(index, &addr)| (addr, exp, index))
+                })
+                .min()
+                .map(|(_, exp, index)| (exp, index))?,
+        };
+        let blocks = self.slack.get_mut(&exp)?;
+        let addr = blocks.swap_remove(index);
+        // Remove empty vectors to keep the map clean
+        if blocks.is_empty() {
+            self.slack.remove(&exp);
+        }
+        Some((addr, exp))
+    }
+
+    /// Makes the never-written region [addr, addr + size) available again,
+    /// returning the number of words that may be reused.
+    pub(crate) fn reclaim(&mut self, addr: u32, size: u32) -> u32 {
+        if size < MIN_FRAME_SIZE || self.strategy == AllocationStrategy::Bump {
+            return 0;
+        }
+        self.add_slack(addr, size);
+        split_into_power_of_two_blocks(addr, size)
+            .map(|(_, block_size)| block_size)
+            .sum()
+    }
+
     /// Helper to record internal slack (unused portion within the padded
     /// block).
     fn record_internal_slack(
@@ -152,9 +278,10 @@ impl VromAllocator {
     /// Records a free (slack) region starting at `addr` with length `size`
     /// by splitting it into power-of-two blocks.
     ///
-    /// Only blocks with size ≥ MIN_FRAME_SIZE are retained.
+    /// Only blocks with size ≥ MIN_FRAME_SIZE are retained, and none with the
+    /// bump strategy.
     fn add_slack(&mut self, addr: u32, size: u32) {
-        if size < MIN_FRAME_SIZE {
+        if size < MIN_FRAME_SIZE || self.strategy == AllocationStrategy::Bump {
             return;
         }
         for (block_addr, block_size) in split_into_power_of_two_blocks(addr, size) {
@@ -293,6 +420,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_strategies() {
+        let allocate = |strategy| {
+            let mut allocator = VromAllocator::new(strategy);
+            let addrs = [17, 33, 16].map(|size| allocator.alloc(size));
+            (addrs, allocator.stats())
+        };
+
+        // Gaps are lost with the bump strategy.
+        let (addrs, stats) = allocate(AllocationStrategy::Bump);
+        assert_eq!(addrs, [0, 64, 128]);
+        assert_eq!(stats.allocated_words, 144);
+        assert_eq!(stats.slack_words, 0);
+
+        // After the first two allocations, slack blocks of 8 words are at 24 and
+        // 104, of 16 words at 112 and of 32 words at 32.
+        let (addrs, stats) = allocate(AllocationStrategy::SizeClass);
+        assert_eq!(addrs, [0, 64, 112]);
+        assert_eq!(
+            stats,
+            AllocatorStats {
+                regions: 3,
+                requested_words: 66,
+                allocated_words: 128,
+                slack_words: 48,
+            }
+        );
+        assert_eq!(stats.fragmentation(), 1.0 - 66.0 / 128.0);
+
+        let (addrs, stats) = allocate(AllocationStrategy::FirstFit);
+        assert_eq!(addrs, [0, 64, 32]);
+        assert_eq!(stats.allocated_words, 128);
+        assert_eq!(stats.slack_words, 48);
+
+        assert_eq!(AllocatorStats::default().fragmentation(), 0.0);
+    }
+
     #[test]
     fn test_random_allocations_space_efficiency() {
         let mut allocator = VromAllocator::default();
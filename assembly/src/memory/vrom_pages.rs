@@ -0,0 +1,278 @@
+//! Paged storage of the VROM words.
+//!
+//! Frames are allocated at increasing addresses, and programs with large frame
+//! sizes or deep call chains leave most of the address space untouched. The
+//! words are thus stored in fixed-size pages, allocated on the first write to
+//! one of their addresses: the untouched parts of the address space only cost
+//! a pointer per page.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Number of words per page.
+pub(crate) const PAGE_SIZE: usize = 1 << 10;
+
+/// Words of a page, along with their access counts.
+#[derive(Debug)]
+struct Page {
+    words: [Option<u32>; PAGE_SIZE],
+    /// Number of reads/writes per word (atomic so that the VROM can be shared
+    /// between threads).
+    access_counts: [AtomicU32; PAGE_SIZE],
+}
+
+impl Page {
+    fn new() -> Box<Self> {
+        Box::new(Self {
+            words: [None; PAGE_SIZE],
+            access_counts: std::array::from_fn(|_| AtomicU32::new(0)),
+        })
+    }
+}
+
+impl Clone for Page {
+    fn clone(&self) -> Self {
+        Self {
+            words: self.words,
+            access_counts: std::array::from_fn(|i| {
+                AtomicU32::new(self.access_counts[i].load(Ordering::Relaxed))
+            }),
+        }
+    }
+}
+
+/// VROM words, addressable up to [`VromPages::len`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct VromPages {
+    /// Pages covering the addressable words, `None` until one of their words
+    /// is written.
+    pages: Vec<Option<Box<Page>>>,
+    /// Number of addressable words.
+    len: usize,
+}
+
+const fn split(addr: usize) -> (usize, usize) {
+    (addr / PAGE_SIZE, addr % PAGE_SIZE)
+}
+
+impl VromPages {
+    /// Returns the number of addressable words.
+    pub(crate) const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Makes the first `len` words addressable, if they are not already.
+    pub(crate) fn grow(&mut self, len: usize) {
+        if len > self.len {
+            self.len = len;
+            self.pages.resize_with(len.div_ceil(PAGE_SIZE), || None);
+        }
+    }
+
+    /// Returns the word at `addr`, if it is set.
+    pub(crate) fn get(&self, addr: usize) -> Option<u32> {
+        let (page, offset) = split(addr);
+        self.pages.get(page)?.as_ref()?.words[offset]
+    }
+
+    /// Returns a mutable reference to the word at `addr`, allocating its page
+    /// if needed.
+    ///
+    /// `addr` must be addressable.
+    pub(crate) fn get_mut(&mut self, addr: usize) -> &mut Option<u32> {
+        let (page, offset) = split(addr);
+        &mut self.pages[page].get_or_insert_with(Page::new).words[offset]
+    }
+
+    /// Copies the consecutive words from `addr` into `words`, looking each page
+    /// up once, and increments their access counts if `record`.
+    ///
+    /// Fails with the address of the first unset word, if any. `addr` and the
+    /// following words must be addressable.
+    pub(crate) fn read_run(
+        &self,
+        addr: usize,
+        words: &mut [u32],
+        record: bool,
+    ) -> Result<(), usize> {
+        let mut done = 0;
+        while done < words.len() {
+            let (page, offset) = split(addr + done);
+            let count = (PAGE_SIZE - offset).min(words.len() - done);
+            let page = self
+                .pages
+                .get(page)
+                .and_then(Option::as_ref)
+                .ok_or(addr + done)?;
+            for (i, word) in words[done..done + count].iter_mut().enumerate() {
+                *word = page.words[offset + i].ok_or(addr + done + i)?;
+                if record {
+                    page.access_counts[offset + i].fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            done += count;
+        }
+        Ok(())
+    }
+
+    /// Writes `words` at the consecutive addresses from `addr`, looking each
+    /// page up once, and increments their access counts if `record`.
+    ///
+    /// Words already set must hold the same value: otherwise, the writing stops
+    /// and fails with the address of the word, its value and the rejected one.
+    /// `addr` and the following words must be addressable.
+    pub(crate) fn write_run(
+        &mut self,
+        addr: usize,
+        words: &[u32],
+        record: bool,
+    ) -> Result<(), (usize, u32, u32)> {
+        let mut done = 0;
+        while done < words.len() {
+            let (page, offset) = split(addr + done);
+            let count = (PAGE_SIZE - offset).min(words.len() - done);
+            let page = self.pages[page].get_or_insert_with(Page::new);
+            for (i, &word) in words[done..done + count].iter().enumerate() {
+                match page.words[offset + i] {
+                    Some(prev) if prev != word => return Err((addr + done + i, prev, word)),
+                    Some(_) => {}
+                    None => page.words[offset + i] = Some(word),
+                }
+                if record {
+                    page.access_counts[offset + i].fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            done += count;
+        }
+        Ok(())
+    }
+
+    /// Increments the access count of the word at `addr`.
+    ///
+    /// Accesses to the words of pages that were never written are not
+    /// recorded: they fail, as the words are unset.
+    pub(crate) fn record_access(&self, addr: usize) {
+        let (page, offset) = split(addr);
+        if let Some(page) = self.pages.get(page).and_then(Option::as_ref) {
+            page.access_counts[offset].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the addresses, values and access counts of the set words, by
+    /// increasing address.
+    pub(crate) fn iter_set(&self) -> impl DoubleEndedIterator<Item = (u32, u32, u32)> + '_ {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(index, page)| page.as_ref().map(|page| (index * PAGE_SIZE, page)))
+            .flat_map(|(start, page)| {
+                page.words
+                    .iter()
+                    .zip(&page.access_counts)
+                    .enumerate()
+                    .filter_map(move |(offset, (word, count))| {
+                        let count = count.load(Ordering::Relaxed);
+                        word.map(|word| ((start + offset) as u32, word, count))
+                    })
+            })
+    }
+
+    /// Returns the number of words held in allocated pages.
+    pub(crate) fn resident_words(&self) -> usize {
+        self.pages.iter().flatten().count() * PAGE_SIZE
+    }
+}
+
+impl From<Vec<Option<u32>>> for VromPages {
+    fn from(words: Vec<Option<u32>>) -> Self {
+        let mut pages = Self::default();
+        pages.grow(words.len());
+        for (addr, word) in words.into_iter().enumerate() {
+            if word.is_some() {
+                *pages.get_mut(addr) = word;
+            }
+        }
+        pages
+    }
+}
+
+/// Serialized form of [`VromPages`], only holding the set words.
+#[derive(Serialize, Deserialize)]
+struct SparseWords {
+    len: usize,
+    /// Address, value and access count of each set word.
+    words: Vec<(u32, u32, u32)>,
+}
+
+impl Serialize for VromPages {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SparseWords {
+            len: self.len,
+            words: self.iter_set().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VromPages {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sparse = SparseWords::deserialize(deserializer)?;
+        let mut pages = Self::default();
+        pages.grow(sparse.len);
+        for (addr, word, count) in sparse.words {
+            if addr as usize >= sparse.len {
+                return Err(serde::de::Error::custom(format!(
+                    "VROM word at {addr} beyond the VROM length {}",
+                    sparse.len
+                )));
+            }
+            *pages.get_mut(addr as usize) = Some(word);
+            let (page, offset) = split(addr as usize);
+            if let Some(page) = &pages.pages[page] {
+                page.access_counts[offset].store(count, Ordering::Relaxed);
+            }
+        }
+        Ok(pages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_on_demand() {
+        let mut pages = VromPages::default();
+        pages.grow(1 << 20);
+        assert_eq!(pages.len(), 1 << 20);
+        assert_eq!(pages.resident_words(), 0);
+
+        // Only the pages of the written words are allocated.
+        *pages.get_mut(3) = Some(1);
+        *pages.get_mut((1 << 20) - 1) = Some(2);
+        pages.record_access(3);
+        pages.record_access(PAGE_SIZE);
+        assert_eq!(pages.resident_words(), 2 * PAGE_SIZE);
+        assert_eq!(pages.get(3), Some(1));
+        assert_eq!(pages.get(4), None);
+        assert_eq!(pages.get(PAGE_SIZE), None);
+        assert_eq!(pages.get(1 << 21), None);
+        assert_eq!(
+            pages.iter_set().collect::<Vec<_>>(),
+            [(3, 1, 1), ((1 << 20) - 1, 2, 0)]
+        );
+
+        // Growing never shrinks the addressable words.
+        pages.grow(4);
+        assert_eq!(pages.len(), 1 << 20);
+
+        let bytes = serde_json::to_vec(&pages).unwrap();
+        let round_trip: VromPages = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(round_trip.len(), pages.len());
+        assert_eq!(
+            round_trip.iter_set().collect::<Vec<_>>(),
+            pages.iter_set().collect::<Vec<_>>()
+        );
+    }
+}
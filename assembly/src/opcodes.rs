@@ -1,115 +1,307 @@
+use std::collections::HashSet;
+
 use binius_m3::builder::B16;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use strum::EnumCount;
+use strum::{EnumCount, VariantArray};
 use strum_macros::{Display, EnumCount, IntoStaticStr, VariantArray};
 
-use crate::event::*;
-
-/// Represents the set of instructions supported by the PetraVM.
-#[derive(
-    Debug,
-    Display,
-    Clone,
-    Copy,
-    Hash,
-    Default,
-    EnumCount,
-    TryFromPrimitive,
-    IntoPrimitive,
-    PartialEq,
-    Eq,
-    VariantArray,
-    IntoStaticStr,
-)]
-#[repr(u16)]
-#[allow(clippy::upper_case_acronyms)]
-// TODO: Adjust opcode discriminants once settled on their values.
-// Consider Deref to account for aliases?
-pub enum Opcode {
-    #[default]
-    Invalid = 0x00,
+use crate::{
+    event::*,
+    execution::{trace::EventList, InterpreterError},
+    PetraTrace,
+};
+
+/// Function generating the event of an instruction, given its arguments.
+pub type EventGenerator = fn(&mut EventContext, B16, B16, B16) -> Result<(), InterpreterError>;
+
+/// Defines the [`Opcode`]s from a single specification listing, for each
+/// instruction in opcode order:
+/// - its variant, along with its doc comments and an optional discriminant,
+/// - its [`Event`] type,
+/// - its assembly mnemonic,
+/// - the names of its operands, in argument order,
+/// - the field of the [`PetraTrace`] recording its events, if any.
+///
+/// From it are generated the [`Opcode`] enum, the arity and the mnemonic of
+/// the opcodes, the event generator of each opcode, dispatched by the
+/// [`DispatchTable`], their [`InstructionInfo`] and the [`TraceEvent`] of the
+/// recorded events, the listing of the recorded events used to validate a
+/// trace and to report statistics on it, and the exported
+/// `for_each_recorded_instruction!` macro, from which the prover registers the
+/// tables of the instructions.
+///
+/// Adding an instruction therefore only requires, besides its event and the
+/// trace field recording it, a line in this specification, its syntax in the
+/// assembler and its `<Variant>Table` in the prover, registered from the
+/// specification. The syntax is left out of the
+/// specification: the grammar accepts aliases and several operand forms for
+/// some instructions, e.g. `B32_ADD` for `XOR` or the `@dst[offset]` operands
+/// of the moves, and the mnemonics are checked against it by the round trip of
+/// the [`Disassembler`](crate::Disassembler).
+///
+/// # Example
+///
+/// ```ignore
+/// define_instructions! {
+///     Add(AddEvent, "ADD", [dst, src1, src2]) => add,
+///     Alloci(AllociEvent, "ALLOCI", [dst, imm]),
+///     Bz(BzEvent, "BZ", []) = 0xffff => bz,
+/// }
+/// ```
+macro_rules! define_instructions {
+    (
+        $(
+            $(#[$meta:meta])*
+            $variant:ident($event:ident, $mnemonic:literal, [$($arg:ident),*])
+                $(= $disc:literal)? $(=> $field:ident)?
+        ),* $(,)?
+    ) => {
+        /// Represents the set of instructions supported by the PetraVM.
+        #[derive(
+            Debug,
+            Display,
+            Clone,
+            Copy,
+            Hash,
+            Default,
+            EnumCount,
+            TryFromPrimitive,
+            IntoPrimitive,
+            PartialEq,
+            Eq,
+            VariantArray,
+            IntoStaticStr,
+        )]
+        #[repr(u16)]
+        #[allow(clippy::upper_case_acronyms)]
+        // TODO: Adjust opcode discriminants once settled on their values.
+        // Consider Deref to account for aliases?
+        pub enum Opcode {
+            #[default]
+            Invalid = 0x00,
+            $(
+                $(#[$meta])*
+                $variant $(= $disc)?,
+            )*
+        }
+
+        impl Opcode {
+            /// Returns the names of the operands expected by the given opcode,
+            /// in argument order.
+            pub const fn operands(&self) -> &'static [&'static str] {
+                match self {
+                    Opcode::Invalid => &[],
+                    $(Opcode::$variant => &[$(stringify!($arg)),*],)*
+                }
+            }
+
+            /// Returns the assembly mnemonic of the given opcode.
+            pub const fn mnemonic(&self) -> &'static str {
+                match self {
+                    Opcode::Invalid => "INVALID",
+                    $(Opcode::$variant => $mnemonic,)*
+                }
+            }
+
+            /// Returns the function generating the events of this opcode.
+            pub(crate) fn event_generator(self) -> EventGenerator {
+                match self {
+                    Opcode::Invalid => |_, _, _, _| Err(InterpreterError::InvalidOpcode),
+                    $(Opcode::$variant => $event::generate,)*
+                }
+            }
+        }
+
+        $(
+            impl InstructionInfo for $event {
+                fn opcode() -> Opcode {
+                    Opcode::$variant
+                }
+            }
+
+            $(
+                impl TraceEvent for $event {
+                    fn events(trace: &PetraTrace) -> &[Self] {
+                        &trace.$field
+                    }
+                }
+            )?
+        )*
+
+        impl PetraTrace {
+            /// Returns the events of each opcode, named after the trace fields
+            /// holding them, in opcode order.
+            pub(crate) fn opcode_events(&self) -> Vec<(&'static str, &dyn EventList)> {
+                vec![$($((stringify!($field), &self.$field as &dyn EventList),)?)*]
+            }
+        }
+
+        define_instructions!(@recorded ($) $($($field, $variant;)?)*);
+    };
+    (@recorded ($d:tt) $($field:ident, $variant:ident;)*) => {
+        /// Invokes the macro `$callback` with the recorded instructions, as
+        /// `(field, Variant)` couples of the [`PetraTrace`] field recording
+        /// the events of the instruction and of its [`Opcode`] variant, in
+        /// opcode order.
+        #[macro_export]
+        macro_rules! for_each_recorded_instruction {
+            ($d callback:ident) => {
+                $d callback! { $(($field, $variant),)* }
+            };
+        }
+    };
+}
 
+define_instructions! {
     // GROESTL instructions
-    Groestl256Compress,
-    Groestl256Output,
+    Groestl256Compress(Groestl256CompressEvent, "GROESTL256_COMPRESS", [dst, src1, src2])
+        => groestl_compress,
+    Groestl256Output(Groestl256OutputEvent, "GROESTL256_OUTPUT", [dst, src1, src2])
+        => groestl_output,
+
+    // SHA-256 instructions
+    Sha256Compress(Sha256CompressEvent, "SHA256_COMPRESS", [dst, src1, src2]) => sha256_compress,
+
+    // BLAKE3 instructions
+    Blake3Compress(Blake3CompressEvent, "BLAKE3_COMPRESS", [dst, src1, src2]) => blake3_compress,
+
+    // Big integer instructions
+    // 0x05 is left free, so that the opcodes of the version 1 encoding keep
+    // their values.
+    BigMulMod(BigMulModEvent, "BIGMULMOD", [dst, src1, src2]) = 0x06 => big_mul_mod,
 
     // Integer instructions
-    Xori,
-    Xor,
-    Andi,
-    Srli,
-    Slli,
-    Srai,
-    Addi,
-    Add,
-    Muli,
-    Mulu,
-    Mulsu,
-    Mul,
-    B32Mul,
-    B32Muli,
-    B128Add,
-    B128Mul,
-    And,
-    Or,
-    Ori,
-    Sub,
-    Sll,
-    Srl,
-    Sra,
+    Xori(XoriEvent, "XORI", [dst, src, imm]) => xori,
+    Xor(XorEvent, "XOR", [dst, src1, src2]) => xor,
+    Andi(AndiEvent, "ANDI", [dst, src, imm]) => andi,
+    Srli(SrliEvent, "SRLI", [dst, src, imm]) => srli,
+    Slli(SlliEvent, "SLLI", [dst, src, imm]) => slli,
+    Srai(SraiEvent, "SRAI", [dst, src, imm]) => srai,
+    Roli(RoliEvent, "ROLI", [dst, src, imm]) => roli,
+    Rori(RoriEvent, "RORI", [dst, src, imm]) => rori,
+    Addi(AddiEvent, "ADDI", [dst, src, imm]) => addi,
+    Add(AddEvent, "ADD", [dst, src1, src2]) => add,
+    Muli(MuliEvent, "MULI", [dst, src, imm]) => muli,
+    Mulu(MuluEvent, "MULU", [dst, src1, src2]) => mulu,
+    Mulsu(MulsuEvent, "MULSU", [dst, src1, src2]) => mulsu,
+    Mul(MulEvent, "MUL", [dst, src1, src2]) => mul,
+    B32Mul(B32MulEvent, "B32_MUL", [dst, src1, src2]) => b32_mul,
+    B32Muli(B32MuliEvent, "B32_MULI", [dst, src, imm]) => b32_muli,
+    B32Inv(B32InvEvent, "B32_INV", [dst, src]) => b32_inv,
+    B64Mul(B64MulEvent, "B64_MUL", [dst, src1, src2]) => b64_mul,
+    B128Add(B128AddEvent, "B128_ADD", [dst, src1, src2]) => b128_add,
+    B128Mul(B128MulEvent, "B128_MUL", [dst, src1, src2]) => b128_mul,
+    B128Inv(B128InvEvent, "B128_INV", [dst, src]) => b128_inv,
+    And(AndEvent, "AND", [dst, src1, src2]) => and,
+    Or(OrEvent, "OR", [dst, src1, src2]) => or,
+    Ori(OriEvent, "ORI", [dst, src, imm]) => ori,
+    Sub(SubEvent, "SUB", [dst, src1, src2]) => sub,
+    Sll(SllEvent, "SLL", [dst, src1, src2]) => sll,
+    Srl(SrlEvent, "SRL", [dst, src1, src2]) => srl,
+    Sra(SraEvent, "SRA", [dst, src1, src2]) => sra,
+    Rol(RolEvent, "ROL", [dst, src1, src2]) => rol,
+    Ror(RorEvent, "ROR", [dst, src1, src2]) => ror,
+    Clz(ClzEvent, "CLZ", [dst, src]) => clz,
+    Ctz(CtzEvent, "CTZ", [dst, src]) => ctz,
+    Popcnt(PopcntEvent, "POPCNT", [dst, src]) => popcnt,
+    Bswap(BswapEvent, "BSWAP", [dst, src]) => bswap,
+    Sextb(SextbEvent, "SEXT.B", [dst, src]) => sextb,
+    Sexth(SexthEvent, "SEXT.H", [dst, src]) => sexth,
+    Div(DivEvent, "DIV", [dst, src1, src2]) => div,
+    Divu(DivuEvent, "DIVU", [dst, src1, src2]) => divu,
+    Rem(RemEvent, "REM", [dst, src1, src2]) => rem,
+    Remu(RemuEvent, "REMU", [dst, src1, src2]) => remu,
+    Add64(Add64Event, "ADD.64", [dst, src1, src2]) => add64,
+    Sub64(Sub64Event, "SUB.64", [dst, src1, src2]) => sub64,
 
     // Move instructions
-    Mvvw,
-    Mvih,
-    Ldi,
-    Mvvl,
+    Mvvw(MvvwEvent, "MVV.W", [dst, offset, src]) => mvvw,
+    Mvih(MvihEvent, "MVI.H", [dst, offset, imm]) => mvih,
+    Ldi(LdiEvent, "LDI.W", [dst, imm_low, imm_high]) => ldi,
+    Mvvl(MvvlEvent, "MVV.L", [dst, offset, src]) => mvvl,
+    Mvvh(MvvhEvent, "MVV.H", [dst, offset, src]) => mvvh,
+    Mvib(MvibEvent, "MVI.B", [dst, offset, imm]) => mvib,
+    Cmov(CmovEvent, "CMOV", [dst, cond, srcs]) => cmov,
+    Memcpy(MemcpyEvent, "MEMCPY", [dst, src, len]) => memcpy,
+    Memset(MemsetEvent, "MEMSET", [dst, src, len]) => memset,
 
     // Jump instructions
-    Jumpi,
-    Jumpv,
-    Taili,
-    Tailv,
-    Calli,
-    Callv,
-    Ret,
+    Jumpi(JumpiEvent, "J", [target_low, target_high]) => jumpi,
+    Jumpv(JumpvEvent, "J", [offset]) => jumpv,
+    Taili(TailiEvent, "TAILI", [target_low, target_high, next_fp]) => taili,
+    Tailv(TailvEvent, "TAILV", [offset, next_fp]) => tailv,
+    Calli(CalliEvent, "CALLI", [target_low, target_high, next_fp]) => calli,
+    Callv(CallvEvent, "CALLV", [offset, next_fp]) => callv,
+    Ret(RetEvent, "RET", []) => ret,
+    Halt(HaltEvent, "HALT", [exit_code]) => halt,
 
     // Comparison instructions
-    Sle,
-    Slei,
-    Sleu,
-    Sleiu,
-    Slt,
-    Slti,
-    Sltu,
-    Sltiu,
+    Sle(SleEvent, "SLE", [dst, src1, src2]) => sle,
+    Slei(SleiEvent, "SLEI", [dst, src, imm]) => slei,
+    Sleu(SleuEvent, "SLEU", [dst, src1, src2]) => sleu,
+    Sleiu(SleiuEvent, "SLEIU", [dst, src, imm]) => sleiu,
+    Slt(SltEvent, "SLT", [dst, src1, src2]) => slt,
+    Slti(SltiEvent, "SLTI", [dst, src, imm]) => slti,
+    Sltu(SltuEvent, "SLTU", [dst, src1, src2]) => sltu,
+    Sltiu(SltiuEvent, "SLTIU", [dst, src, imm]) => sltiu,
+    Slt64(Slt64Event, "SLT.64", [dst, src1, src2]) => slt64,
 
     // Allocation instructions (prover-only)
-    Alloci,
-    Allocv,
+    Alloci(AllociEvent, "ALLOCI", [dst, imm]),
+    Allocv(AllocvEvent, "ALLOCV", [dst, src]),
 
     // Register instructions
-    Fp,
+    Fp(FpEvent, "FP", [dst, imm]) => fp,
+
+    // No-op instruction
+    Nop(NopEvent, "NOP", []) => nop,
 
     // Memory Access (RAM) instructions
-    // TODO: optional ISA extension for future implementation
-    // Not needed for recursion program or first version of PetraVM
-    // Design note: Considering 32-bit word-sized memory instead of byte-addressed memory
-    // LW,
-    // SW,
-    // LB,
-    // LBU,
-    // LH,
-    // LHU,
-    // SB,
-    // SH,
+    // Optional ISA extension, not needed for the recursion program.
+    Lw(LwEvent, "LW", [dst, base, offset]) => lw,
+    Sw(SwEvent, "SW", [src, base, offset]) => sw,
+    Lb(LbEvent, "LB", [dst, base, offset]) => lb,
+    Lbu(LbuEvent, "LBU", [dst, base, offset]) => lbu,
+    Lh(LhEvent, "LH", [dst, base, offset]) => lh,
+    Lhu(LhuEvent, "LHU", [dst, base, offset]) => lhu,
+    Sb(SbEvent, "SB", [src, base, offset]) => sb,
+    Sh(ShEvent, "SH", [src, base, offset]) => sh,
 
     // Branch instructions
-    Bnz,
+    Bnz(BnzEvent, "BNZ", [target_low, target_high, cond]) => bnz,
+    Beq(BeqEvent, "BEQ", [target_low, target_high, srcs]) => beq,
+    Bne(BneEvent, "BNE", [target_low, target_high, srcs]) => bne,
+    Blt(BltEvent, "BLT", [target_low, target_high, srcs]) => blt,
+    Bge(BgeEvent, "BGE", [target_low, target_high, srcs]) => bge,
+
+    // Exception instructions
+    Abort(AbortEvent, "ABORT", [msg_ptr, msg_len]),
+    AssertEq(AssertEqEvent, "ASSERT_EQ", [src1, src2]) => assert_eq,
+    AssertZero(AssertZeroEvent, "ASSERT_ZERO", [src]) => assert_zero,
+    RangeCheck(RangeCheckEvent, "RANGE_CHECK", [src, bits]) => range_check,
+
+    // Host instructions (prover-only)
+    Ecall(EcallEvent, "ECALL", [id, base]),
+    ReadAdvice(ReadAdviceEvent, "READ_ADVICE", [dst]),
+    Debug(DebugEvent, "DEBUG", [src]),
+
+    // Heap instructions (prover-only)
+    Free(FreeEvent, "FREE", [ptr]),
+
+    // Output instructions
+    Commit(CommitEvent, "COMMIT", [src]) => commit,
+
+    // Merkle instructions
+    MerkleVerify(MerkleVerifyEvent, "MERKLE_VERIFY", [root, leaf, path]) => merkle_verify,
+
+    // Heap instructions (prover-only)
+    Alloch(AllochEvent, "ALLOCH", [dst, size]),
+
     /// Bz is only declared to allow for proper mapping with the associated
     /// table. This is an *invalid* instruction and should never be reached.
     /// [`BzEvent`] should only be generated through the execution of
     /// [`Opcode::Bnz`] when no branching occurs.
-    Bz = 0xffff,
+    Bz(BzEvent, "BZ", []) = 0xffff => bz,
 }
 
 impl Opcode {
@@ -120,58 +312,7 @@ impl Opcode {
 
     /// Returns the number of arguments expected by the given opcode.
     pub const fn num_args(&self) -> usize {
-        match self {
-            Opcode::Fp => 2,                 // dst, imm
-            Opcode::Groestl256Compress => 3, // dst, src1, src2
-            Opcode::Groestl256Output => 3,   // dst, src1, src2
-            Opcode::Bnz => 3,                // target_low, target_high, cond
-            Opcode::Bz => 0,                 // non-existing instruction
-            Opcode::Jumpi => 2,              // target_low, target_high
-            Opcode::Jumpv => 1,              // offset
-            Opcode::Xori => 3,               // dst, src, imm
-            Opcode::Xor => 3,                // dst, src1, src2
-            Opcode::Ret => 0,                //
-            Opcode::Slli => 3,               // dst, src, imm
-            Opcode::Srli => 3,               // dst, src, imm
-            Opcode::Srai => 3,               // dst, src, imm
-            Opcode::Sll => 3,                // dst, src1, src2
-            Opcode::Srl => 3,                // dst, src1, src2
-            Opcode::Sra => 3,                // dst, src1, src2
-            Opcode::Tailv => 2,              // offset, next_fp
-            Opcode::Taili => 3,              // target_low, target_high, next_fp
-            Opcode::Calli => 3,              // target_low, target_high, next_fp
-            Opcode::Callv => 2,              // offset, next_fp
-            Opcode::And => 3,                // dst, src1, src2
-            Opcode::Andi => 3,               // dst, src, imm
-            Opcode::Sub => 3,                // dst, src1, src2
-            Opcode::Sle => 3,                // dst, src1, src2
-            Opcode::Slei => 3,               // dst, src, imm
-            Opcode::Sleu => 3,               // dst, src1, src2
-            Opcode::Sleiu => 3,              // dst, src, imm
-            Opcode::Slt => 3,                // dst, src1, src2
-            Opcode::Slti => 3,               // dst, src, imm
-            Opcode::Sltu => 3,               // dst, src1, src2
-            Opcode::Sltiu => 3,              // dst, src, imm
-            Opcode::Or => 3,                 // dst, src1, src2
-            Opcode::Ori => 3,                // dst, src, imm
-            Opcode::Muli => 3,               // dst, src, imm
-            Opcode::Mulu => 3,               // dst, src1, src2
-            Opcode::Mul => 3,                // dst, src1, src2
-            Opcode::Mulsu => 3,              // dst, src1, src2
-            Opcode::B32Mul => 3,             // dst, src1, src2
-            Opcode::B32Muli => 3,            // dst, src, imm
-            Opcode::B128Add => 3,            // dst, src1, src2
-            Opcode::B128Mul => 3,            // dst, src1, src2
-            Opcode::Add => 3,                // dst, src1, src2
-            Opcode::Addi => 3,               // dst, src, imm
-            Opcode::Mvvw => 3,               // dst, offset, src
-            Opcode::Mvvl => 3,               // dst, offset, src
-            Opcode::Mvih => 3,               // dst, offset, imm
-            Opcode::Ldi => 3,                // dst, imm_low, imm_high
-            Opcode::Alloci => 2,             // dst, imm
-            Opcode::Allocv => 2,             // dst, src
-            Opcode::Invalid => 0,            // invalid
-        }
+        self.operands().len()
     }
 
     /// Returns true if the opcode cannot be prover-only.
@@ -180,6 +321,10 @@ impl Opcode {
             self,
             Opcode::Bnz
                 | Opcode::Bz
+                | Opcode::Beq
+                | Opcode::Bne
+                | Opcode::Blt
+                | Opcode::Bge
                 | Opcode::Jumpi
                 | Opcode::Jumpv
                 | Opcode::Taili
@@ -187,77 +332,164 @@ impl Opcode {
                 | Opcode::Calli
                 | Opcode::Callv
                 | Opcode::Ret
+                | Opcode::Halt
+                | Opcode::Lw
+                | Opcode::Lb
+                | Opcode::Lbu
+                | Opcode::Lh
+                | Opcode::Lhu
+                | Opcode::Sw
+                | Opcode::Sb
+                | Opcode::Sh
+        )
+    }
+
+    /// Returns true if the opcode must be prover-only.
+    pub const fn is_prover_only(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Alloci
+                | Opcode::Allocv
+                | Opcode::Alloch
+                | Opcode::Free
+                | Opcode::Ecall
+                | Opcode::ReadAdvice
         )
     }
 }
 
+/// Event generators of the opcodes supported by an [`ISA`](crate::isa::ISA),
+/// indexed by opcode.
+///
+/// The interpreter looks each executed instruction up in this table, rather
+/// than matching on its opcode, and rejects the opcodes it lacks. It is built
+/// once per ISA, by [`ISA::dispatch_table`](crate::isa::ISA::dispatch_table).
+#[derive(Debug)]
+pub struct DispatchTable {
+    generators: Vec<Option<(Opcode, EventGenerator)>>,
+}
+
+impl DispatchTable {
+    /// Builds the dispatch table of the given `opcodes`.
+    ///
+    /// The pseudo-opcode [`Opcode::Bz`], which never appears in a PROM, is
+    /// left out, so that the table only spans the opcodes up to the last
+    /// one, gaps included.
+    pub fn new(opcodes: &HashSet<Opcode>) -> Self {
+        let len = Opcode::VARIANTS
+            .iter()
+            .filter(|&&opcode| opcode != Opcode::Bz)
+            .map(|&opcode| opcode as usize + 1)
+            .max()
+            .unwrap_or_default();
+        let mut generators = vec![None; len];
+        for &opcode in opcodes {
+            if let Some(generator) = generators.get_mut(opcode as usize) {
+                *generator = Some((opcode, opcode.event_generator()));
+            }
+        }
+        Self { generators }
+    }
+
+    /// Returns the opcode of value `opcode` and its event generator, if it is
+    /// supported.
+    #[inline(always)]
+    pub(crate) fn get(&self, opcode: u16) -> Option<(Opcode, EventGenerator)> {
+        self.generators.get(opcode as usize).copied().flatten()
+    }
+}
+
 /// Trait implemented by each [`Event`] type.
 pub trait InstructionInfo {
     /// The unique opcode associated with this instruction.
     fn opcode() -> Opcode;
 }
 
-macro_rules! impl_instruction_info {
-    ( $( ($event_ty:ty, $opcode:path) ),* $(,)? ) => {
-        $(
-            impl InstructionInfo for $event_ty {
-                fn opcode() -> Opcode {
-                    $opcode
-                }
-            }
-        )*
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        isa::{GenericISA, RecursionISA, ISA},
+        Assembler, Memory, ValueRom,
     };
-}
 
-impl_instruction_info!(
-    (AddEvent, Opcode::Add),
-    (AddiEvent, Opcode::Addi),
-    (AndEvent, Opcode::And),
-    (AndiEvent, Opcode::Andi),
-    (BnzEvent, Opcode::Bnz),
-    // `BzEvent` is actually triggered through the `Bnz` instruction
-    (BzEvent, Opcode::Bz),
-    (B32MulEvent, Opcode::B32Mul),
-    (B32MuliEvent, Opcode::B32Muli),
-    (B128AddEvent, Opcode::B128Add),
-    (B128MulEvent, Opcode::B128Mul),
-    (CalliEvent, Opcode::Calli),
-    (CallvEvent, Opcode::Callv),
-    (FpEvent, Opcode::Fp),
-    (Groestl256CompressEvent, Opcode::Groestl256Compress),
-    (Groestl256OutputEvent, Opcode::Groestl256Output),
-    (JumpiEvent, Opcode::Jumpi),
-    (JumpvEvent, Opcode::Jumpv),
-    (LdiEvent, Opcode::Ldi),
-    (MulEvent, Opcode::Mul),
-    (MuliEvent, Opcode::Muli),
-    (MuluEvent, Opcode::Mulu),
-    (MulsuEvent, Opcode::Mulsu),
-    (MvihEvent, Opcode::Mvih),
-    (MvvlEvent, Opcode::Mvvl),
-    (MvvwEvent, Opcode::Mvvw),
-    (OrEvent, Opcode::Or),
-    (OriEvent, Opcode::Ori),
-    (RetEvent, Opcode::Ret),
-    (SleEvent, Opcode::Sle),
-    (SleiEvent, Opcode::Slei),
-    (SleuEvent, Opcode::Sleu),
-    (SleiuEvent, Opcode::Sleiu),
-    (SllEvent, Opcode::Sll),
-    (SlliEvent, Opcode::Slli),
-    (SltEvent, Opcode::Slt),
-    (SltiEvent, Opcode::Slti),
-    (SltuEvent, Opcode::Sltu),
-    (SltiuEvent, Opcode::Sltiu),
-    (SraEvent, Opcode::Sra),
-    (SraiEvent, Opcode::Srai),
-    (SrlEvent, Opcode::Srl),
-    (SrliEvent, Opcode::Srli),
-    (SubEvent, Opcode::Sub),
-    (TailiEvent, Opcode::Taili),
-    (TailvEvent, Opcode::Tailv),
-    (XorEvent, Opcode::Xor),
-    (XoriEvent, Opcode::Xori),
-    (AllociEvent, Opcode::Alloci),
-    (AllocvEvent, Opcode::Allocv),
-);
+    #[test]
+    fn test_instruction_definitions() {
+        assert_eq!(Opcode::Add.operands(), ["dst", "src1", "src2"]);
+        assert_eq!(Opcode::Ret.num_args(), 0);
+        assert_eq!(Opcode::Sextb.mnemonic(), "SEXT.B");
+        assert_eq!(Opcode::Jumpv.mnemonic(), "J");
+        assert_eq!(Opcode::Bz as u16, 0xffff);
+        assert_eq!(AddEvent::opcode(), Opcode::Add);
+        assert!(Opcode::VARIANTS.iter().all(|opcode| opcode.num_args() <= 3));
+
+        // The recorded events are listed in opcode order.
+        let program = Assembler::from_code(
+            "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #3
+                ADDI @3, @2, #4
+                RET
+            ",
+        )
+        .unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, _) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+        let counts = trace.event_counts();
+        let count = |name| counts.iter().find(|(field, _)| *field == name).unwrap().1;
+        assert_eq!((count("ldi"), count("addi"), count("ret")), (1, 1, 1));
+        assert_eq!(trace.events_of::<AddiEvent>().len(), 1);
+        let names = trace
+            .opcode_events()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            &names[..3],
+            ["groestl_compress", "groestl_output", "sha256_compress"]
+        );
+        assert_eq!(names.last(), Some(&"bz"));
+    }
+
+    #[test]
+    fn test_dispatch_table() {
+        let table = GenericISA.dispatch_table();
+        assert!(matches!(
+            table.get(Opcode::Add as u16),
+            Some((Opcode::Add, _))
+        ));
+        assert!(table.get(Opcode::Invalid as u16).is_none());
+        assert!(table.get(Opcode::Bz as u16).is_none());
+        // The table spans the gap left by removed opcodes.
+        assert!(table.get(0x05).is_none());
+        assert_eq!(Opcode::BigMulMod as u16, 0x06);
+        assert!(matches!(
+            table.get(Opcode::Alloch as u16),
+            Some((Opcode::Alloch, _))
+        ));
+        // Opcodes outside of the ISA are left out.
+        assert!(RecursionISA
+            .dispatch_table()
+            .get(Opcode::Add as u16)
+            .is_none());
+
+        let program = Assembler::from_code(
+            "#[framesize(0x10)]
+            _start:
+                LDI.W @2, #3
+                ADDI @3, @2, #4
+                RET
+            ",
+        )
+        .unwrap();
+        let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]));
+        let err = PetraTrace::generate(Box::new(RecursionISA), memory, program.frame_sizes)
+            .err()
+            .expect("The ISA lacks LDI");
+        assert!(matches!(
+            err.root(),
+            InterpreterError::UnsupportedOpcode(Opcode::Ldi)
+        ));
+    }
+}
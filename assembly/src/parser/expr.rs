@@ -0,0 +1,205 @@
+//! Compile-time integer expressions.
+//!
+//! Expressions appear in immediates (`#{FOO + 4}`, `#(1 << 12)`) and in
+//! `.equ NAME, expr` constant definitions. They support decimal and
+//! hexadecimal literals, previously defined constants, parentheses or braces,
+//! and the following operators, by increasing precedence:
+//! `|`, `^`, `&`, `<<` and `>>`, `+` and `-`, `*`, `/` and `%`, and the unary
+//! `-` and `~`. Arithmetic is carried out on wrapping 64-bit integers.
+
+use std::collections::HashMap;
+
+use super::instruction_args::BadArgumentError;
+
+/// Constants defined with `.equ`, by name.
+pub(crate) type Constants = HashMap<String, i64>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Number(i64),
+    Name(&'a str),
+    Op(&'a str),
+    Open,
+    Close,
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token<'_>>, BadArgumentError> {
+    let err = || BadArgumentError::Expression(expr.to_string());
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            let literal = &rest[..len];
+            let value = match literal.strip_prefix("0x") {
+                Some(hex) => i64::from_str_radix(hex, 16),
+                None => literal.parse(),
+            };
+            tokens.push(Token::Number(value.map_err(|_| err())?));
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(&rest[..len]));
+            len
+        } else if c == '(' || c == '{' {
+            tokens.push(Token::Open);
+            1
+        } else if c == ')' || c == '}' {
+            tokens.push(Token::Close);
+            1
+        } else if rest.starts_with("<<") || rest.starts_with(">>") {
+            tokens.push(Token::Op(&rest[..2]));
+            2
+        } else if "+-*/%&|^~".contains(c) {
+            tokens.push(Token::Op(&rest[..1]));
+            1
+        } else {
+            return Err(err());
+        };
+        rest = rest[len..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// Binary operators, from lowest to highest precedence.
+const BINARY_OPS: [&[&str]; 6] = [
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct ExprParser<'a, 'b> {
+    expr: &'a str,
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    constants: &'b Constants,
+}
+
+impl ExprParser<'_, '_> {
+    fn err(&self) -> BadArgumentError {
+        BadArgumentError::Expression(self.expr.to_string())
+    }
+
+    fn peek(&self) -> Option<Token<'_>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn binary(&mut self, level: usize) -> Result<i64, BadArgumentError> {
+        if level == BINARY_OPS.len() {
+            return self.unary();
+        }
+
+        let mut lhs = self.binary(level + 1)?;
+        while let Some(Token::Op(op)) = self.peek() {
+            if !BINARY_OPS[level].contains(&op) {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(level + 1)?;
+            lhs = match op {
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "<<" => lhs.wrapping_shl(rhs as u32),
+                ">>" => lhs.wrapping_shr(rhs as u32),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "*" => lhs.wrapping_mul(rhs),
+                "/" => lhs.checked_div(rhs).ok_or_else(|| self.err())?,
+                "%" => lhs.checked_rem(rhs).ok_or_else(|| self.err())?,
+                _ => return Err(self.err()),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<i64, BadArgumentError> {
+        let token = self.peek().ok_or_else(|| self.err())?;
+        self.pos += 1;
+        match token {
+            Token::Op("-") => Ok(self.unary()?.wrapping_neg()),
+            Token::Op("~") => Ok(!self.unary()?),
+            Token::Number(value) => Ok(value),
+            Token::Name(name) => self
+                .constants
+                .get(name)
+                .copied()
+                .ok_or_else(|| BadArgumentError::UnknownConstant(name.to_string())),
+            Token::Open => {
+                let value = self.binary(0)?;
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(value)
+                    }
+                    _ => Err(self.err()),
+                }
+            }
+            Token::Op(_) | Token::Close => Err(self.err()),
+        }
+    }
+}
+
+/// Evaluates `expr`, resolving names with `constants`.
+pub(crate) fn eval(expr: &str, constants: &Constants) -> Result<i64, BadArgumentError> {
+    let mut parser = ExprParser {
+        expr,
+        tokens: tokenize(expr)?,
+        pos: 0,
+        constants,
+    };
+    let value = parser.binary(0)?;
+    if parser.pos != parser.tokens.len() {
+        return Err(parser.err());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let constants = Constants::from([("FOO".to_string(), 12), ("BAR_2".to_string(), -3)]);
+        let cases = [
+            ("42", 42),
+            ("0x10", 16),
+            ("-7", -7),
+            ("FOO+4", 16),
+            ("1 << 12", 4096),
+            ("(1<<12) | 3", 4099),
+            ("2 + 3 * 4", 14),
+            ("{2 + 3} * 4", 20),
+            ("FOO / BAR_2 % 3", -1),
+            ("~0 & 0xff", 255),
+            ("-(FOO - 2) ^ 1", -9),
+            ("1 + 2 << 3", 24),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(eval(expr, &constants).unwrap(), expected, "{expr}");
+        }
+
+        for expr in [
+            "",
+            "1 +",
+            "(1",
+            "1)",
+            "FOO BAR_2",
+            "1 / 0",
+            "UNKNOWN",
+            "1 $ 2",
+        ] {
+            assert!(eval(expr, &constants).is_err(), "{expr}");
+        }
+    }
+}
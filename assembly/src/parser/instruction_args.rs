@@ -1,16 +1,27 @@
+use std::str::FromStr;
+
 use binius_field::{BinaryField, Field, PackedField};
 use binius_m3::builder::{B16, B32};
 use thiserror::Error;
 
+use super::expr::{self, Constants};
+
 #[derive(Debug, Clone, Copy)]
 pub struct Slot(u32);
 
 #[derive(Debug, Clone, Copy)]
-pub struct SlotWithOffset(u32, u16);
+pub struct SlotWithOffset(Slot, u16);
 
 #[derive(Debug, Clone, Copy)]
 pub struct Immediate(u32);
 
+/// Argument of a custom instruction, either a slot or an immediate.
+#[derive(Debug, Clone, Copy)]
+pub enum Operand {
+    Slot(Slot),
+    Immediate(Immediate),
+}
+
 impl std::fmt::Display for Slot {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "@{}", self.0)
@@ -27,6 +38,14 @@ impl std::str::FromStr for Slot {
 }
 
 impl Slot {
+    pub(crate) const fn new(index: u32) -> Self {
+        Self(index)
+    }
+
+    pub(crate) const fn index(self) -> u32 {
+        self.0
+    }
+
     pub(crate) const fn get_16bfield_val(self) -> B16 {
         B16::new(self.0 as u16)
     }
@@ -34,7 +53,7 @@ impl Slot {
 
 impl std::fmt::Display for SlotWithOffset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "@{}[{}]", self.0, self.1)
+        write!(f, "{}[{}]", self.0, self.1)
     }
 }
 
@@ -47,13 +66,27 @@ impl std::str::FromStr for SlotWithOffset {
         let slot = Slot::from_str(slot)?;
         let offset = u16::from_str(offset.trim_end_matches(']'))
             .map_err(|_| BadArgumentError::SlotOffset(s.to_string()))?;
-        Ok(Self(slot.0, offset))
+        Ok(Self(slot, offset))
     }
 }
 
 impl SlotWithOffset {
+    /// Returns the slot holding the address of the target frame.
+    pub(crate) const fn slot(self) -> Slot {
+        self.0
+    }
+
+    pub(crate) fn slot_mut(&mut self) -> &mut Slot {
+        &mut self.0
+    }
+
+    /// Returns the offset of the target slot in the target frame.
+    pub(crate) const fn offset(self) -> u16 {
+        self.1
+    }
+
     pub(crate) const fn get_slot_16bfield_val(self) -> B16 {
-        B16::new(self.0 as u16)
+        self.0.get_16bfield_val()
     }
 
     pub(crate) const fn get_offset_field_val(self) -> B16 {
@@ -70,11 +103,30 @@ impl std::fmt::Display for Immediate {
 impl std::str::FromStr for Immediate {
     type Err = BadArgumentError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let is_field = s.ends_with('G');
-        let s = s.trim_start_matches('#').trim_end_matches("G");
+        Self::parse(s, &Constants::new())
+    }
+}
+
+impl Immediate {
+    pub(crate) const fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// Parses an immediate, whose value may be an expression referring to
+    /// `constants`.
+    pub(crate) fn parse(s: &str, constants: &Constants) -> Result<Self, BadArgumentError> {
+        let s = s.trim_start_matches('#');
+        // A trailing "G" denotes a generator power, unless it ends a constant name.
+        let (s, is_field) = match s.strip_suffix('G') {
+            Some(value)
+                if value.ends_with(|c: char| c.is_ascii_digit() || c == ')' || c == '}') =>
+            {
+                (value, true)
+            }
+            _ => (s, false),
+        };
 
-        let int_val =
-            i64::from_str(s).map_err(|_| BadArgumentError::Immediate(s.to_string()))? as i32;
+        let int_val = expr::eval(s, constants)? as i32;
         if is_field {
             let v = B32::MULTIPLICATIVE_GENERATOR.pow(int_val.unsigned_abs() as u64);
             if int_val < 0 {
@@ -88,9 +140,11 @@ impl std::str::FromStr for Immediate {
             Ok(Immediate(int_val as u32))
         }
     }
-}
 
-impl Immediate {
+    pub(crate) const fn value(self) -> u32 {
+        self.0
+    }
+
     pub(crate) const fn get_field_val(self) -> B16 {
         B16::new(self.0 as u16)
     }
@@ -98,6 +152,255 @@ impl Immediate {
     pub(crate) const fn get_high_field_val(self) -> B16 {
         B16::new((self.0 >> 16) as u16)
     }
+
+    /// Returns the immediate as a field element, if it fits in a byte.
+    pub(crate) fn get_byte_field_val(self) -> Option<B16> {
+        (self.0 <= 0xff).then_some(B16::new(self.0 as u16))
+    }
+}
+
+/// Splits a label reference, `label`, `label+n` or `label-n`, into the label
+/// name and its offset in instructions.
+///
+/// Offsets too large for an `i64` saturate, and are out of range of any
+/// program.
+pub(crate) fn split_label_ref(label: &str) -> (&str, i64) {
+    match label.find(['+', '-']) {
+        Some(index) => {
+            let (name, offset) = label.split_at(index);
+            let magnitude = offset[1..].parse::<i64>().unwrap_or(i64::MAX);
+            let offset = if offset.starts_with('-') {
+                -magnitude
+            } else {
+                magnitude
+            };
+            (name, offset)
+        }
+        None => (label, 0),
+    }
+}
+
+/// Part of the field PC of a label held by a [`LabelImmediate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelPart {
+    /// `#pc(label)`: the whole field PC.
+    Full,
+    /// `#lo(label)`: its lower 16 bits.
+    Low,
+    /// `#hi(label)`: its upper 16 bits.
+    High,
+}
+
+/// Immediate holding (part of) the field PC of a label reference, e.g.
+/// `#lo(table+1)`, resolved once the program is laid out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelImmediate {
+    part: LabelPart,
+    /// The label reference, possibly with an offset.
+    label: String,
+}
+
+impl std::fmt::Display for LabelImmediate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let part = match self.part {
+            LabelPart::Full => "pc",
+            LabelPart::Low => "lo",
+            LabelPart::High => "hi",
+        };
+        write!(f, "#{part}({})", self.label)
+    }
+}
+
+impl std::str::FromStr for LabelImmediate {
+    type Err = BadArgumentError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || BadArgumentError::Immediate(s.to_string());
+        let (part, label) = s
+            .strip_prefix('#')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(err)?;
+        let part = match part {
+            "pc" => LabelPart::Full,
+            "lo" => LabelPart::Low,
+            "hi" => LabelPart::High,
+            _ => return Err(err()),
+        };
+        Ok(Self {
+            part,
+            label: label.to_string(),
+        })
+    }
+}
+
+impl LabelImmediate {
+    /// Returns the label reference.
+    pub(crate) fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the value of the immediate, given the field PC of its label
+    /// reference.
+    pub(crate) fn value(&self, field_pc: B32) -> u32 {
+        match self.part {
+            LabelPart::Full => field_pc.val(),
+            LabelPart::Low => field_pc.val() & 0xffff,
+            LabelPart::High => field_pc.val() >> 16,
+        }
+    }
+}
+
+impl std::fmt::Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Slot(slot) => write!(f, "{slot}"),
+            Self::Immediate(imm) => write!(f, "{imm}"),
+        }
+    }
+}
+
+impl Operand {
+    /// Returns the slot, if the operand is one.
+    pub(crate) const fn slot(self) -> Option<Slot> {
+        match self {
+            Self::Slot(slot) => Some(slot),
+            Self::Immediate(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the slot, if the operand is one.
+    pub(crate) fn slot_mut(&mut self) -> Option<&mut Slot> {
+        match self {
+            Self::Slot(slot) => Some(slot),
+            Self::Immediate(_) => None,
+        }
+    }
+
+    /// Returns the operand as encoded in an instruction, if it fits in 16
+    /// bits.
+    pub(crate) const fn get_field_val(self) -> Option<B16> {
+        match self {
+            Self::Slot(slot) if slot.0 <= 0xffff => Some(slot.get_16bfield_val()),
+            Self::Immediate(imm) if imm.0 <= 0xffff => Some(imm.get_field_val()),
+            _ => None,
+        }
+    }
+}
+
+/// Static data declared in a `.data` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataDirective {
+    /// `.word`: 32-bit little-endian words, aligned on 4 bytes.
+    Words(Vec<u32>),
+    /// `.bytes`: raw bytes.
+    Bytes(Vec<u8>),
+    /// `.ascii`: the bytes of a string, without any terminating null byte.
+    Ascii(String),
+    /// `.word` of label references: the field PCs of the labels, as 32-bit
+    /// little-endian words aligned on 4 bytes, filled in once the program is
+    /// laid out.
+    Labels(Vec<String>),
+}
+
+impl DataDirective {
+    /// Parses a `.word` value, either in hexadecimal or as a (possibly
+    /// negative) decimal integer.
+    pub(crate) fn parse_word(s: &str) -> Result<u32, BadArgumentError> {
+        let value = match s.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => i64::from_str(s)
+                .ok()
+                .filter(|v| (i32::MIN as i64..=u32::MAX as i64).contains(v))
+                .map(|v| v as u32),
+        };
+        value.ok_or_else(|| BadArgumentError::Data(s.to_string()))
+    }
+
+    /// Parses a `.bytes` value, either in hexadecimal or as a (possibly
+    /// negative) decimal integer.
+    pub(crate) fn parse_byte(s: &str) -> Result<u8, BadArgumentError> {
+        let value = Self::parse_word(s)?;
+        if value <= u8::MAX as u32 || value >= i8::MIN as i32 as u32 {
+            Ok(value as u8)
+        } else {
+            Err(BadArgumentError::Data(s.to_string()))
+        }
+    }
+
+    /// Parses a quoted string literal, with `\n`, `\t`, `\0`, `\\` and `\"`
+    /// escape sequences.
+    pub(crate) fn parse_string(s: &str) -> Result<String, BadArgumentError> {
+        let inner = &s[1..s.len() - 1];
+        let mut string = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                string.push(c);
+                continue;
+            }
+            string.push(match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                _ => return Err(BadArgumentError::Data(s.to_string())),
+            });
+        }
+        Ok(string)
+    }
+
+    /// Returns the alignment of the directive, in bytes.
+    pub(crate) const fn alignment(&self) -> usize {
+        match self {
+            Self::Words(_) | Self::Labels(_) => 4,
+            Self::Bytes(_) | Self::Ascii(_) => 1,
+        }
+    }
+
+    /// Returns the bytes of the directive, as laid out in memory.
+    ///
+    /// Label references are left zero.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Words(words) => words.iter().flat_map(|word| word.to_le_bytes()).collect(),
+            Self::Labels(labels) => vec![0; 4 * labels.len()],
+            Self::Bytes(bytes) => bytes.clone(),
+            Self::Ascii(string) => string.as_bytes().to_vec(),
+        }
+    }
+}
+
+impl std::fmt::Display for DataDirective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |values: Vec<String>| values.join(", ");
+        match self {
+            Self::Words(words) => write!(
+                f,
+                ".word {}",
+                join(words.iter().map(|w| format!("0x{w:x}")).collect())
+            ),
+            Self::Labels(labels) => write!(f, ".word {}", labels.join(", ")),
+            Self::Bytes(bytes) => write!(
+                f,
+                ".bytes {}",
+                join(bytes.iter().map(|b| b.to_string()).collect())
+            ),
+            Self::Ascii(string) => {
+                let escaped = string
+                    .chars()
+                    .map(|c| match c {
+                        '\n' => "\\n".to_string(),
+                        '\t' => "\\t".to_string(),
+                        '\0' => "\\0".to_string(),
+                        '\\' | '"' => format!("\\{c}"),
+                        c => c.to_string(),
+                    })
+                    .collect::<String>();
+                write!(f, ".ascii \"{escaped}\"")
+            }
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -111,6 +414,30 @@ pub enum BadArgumentError {
     #[error("Bad immediate argument: {0}")]
     Immediate(String),
 
+    #[error("Bad constant expression: {0}")]
+    Expression(String),
+
+    #[error("Unknown constant: {0}")]
+    UnknownConstant(String),
+
     #[error("Bad frame size argument: {0}")]
     FrameSize(String),
+
+    #[error("Bad data value: {0}")]
+    Data(String),
+}
+
+impl BadArgumentError {
+    /// Returns the offending argument.
+    pub(crate) fn token(&self) -> &str {
+        match self {
+            Self::Slot(token)
+            | Self::SlotOffset(token)
+            | Self::Immediate(token)
+            | Self::Expression(token)
+            | Self::UnknownConstant(token)
+            | Self::FrameSize(token)
+            | Self::Data(token) => token,
+        }
+    }
 }
@@ -1,6 +1,10 @@
+use pest::error::LineColLocation;
 use thiserror::Error;
 
-use super::instruction_args::{Immediate, Slot, SlotWithOffset};
+use super::instruction_args::{
+    DataDirective, Immediate, LabelImmediate, Operand, Slot, SlotWithOffset,
+};
+use crate::assembler::{SourceLocation, SourceSpan};
 
 /// This is an incomplete list of instructions
 /// So far, only the ones added for parsing the fibonacci example has been added
@@ -8,9 +12,30 @@ use super::instruction_args::{Immediate, Slot, SlotWithOffset};
 /// Ideally we want another pass that removes labels, and replaces label
 /// references with the absolute program counter/instruction index we would jump
 /// to.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum InstructionsWithLabels {
     Label(String, Option<u16>),
+    /// Label of the `.data` section, pointing to the RAM address of the data
+    /// that follows it.
+    DataLabel(String),
+    /// Static data of the `.data` section.
+    Data(DataDirective),
+    /// Slot of the entry frame exposed as a public value.
+    Public(Slot),
+    /// Alignment of the next instruction in the PROM, padded with NOPs by the
+    /// assembler.
+    Align(u32),
+    /// Source location of the following instructions, recorded when parsing
+    /// with locations for the debug info of the program.
+    Location(SourceLocation),
+    /// Jump to the case of index `FP[index]`, falling through when out of
+    /// bounds, lowered by the assembler using the three slots from `scratch`.
+    JumpTable {
+        name: String,
+        index: Slot,
+        scratch: Slot,
+        cases: Vec<String>,
+    },
     Fp {
         dst: Slot,
         imm: Immediate,
@@ -28,6 +53,17 @@ pub enum InstructionsWithLabels {
         imm: Immediate,
         prover_only: bool,
     },
+    B32Inv {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    B64Mul {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
     B128Add {
         dst: Slot,
         src1: Slot,
@@ -40,6 +76,11 @@ pub enum InstructionsWithLabels {
         src2: Slot,
         prover_only: bool,
     },
+    B128Inv {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
     Groestl256Compress {
         dst: Slot,
         src1: Slot,
@@ -52,6 +93,24 @@ pub enum InstructionsWithLabels {
         src2: Slot,
         prover_only: bool,
     },
+    Sha256Compress {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Blake3Compress {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    BigMulMod {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
     Mvih {
         dst: SlotWithOffset,
         imm: Immediate,
@@ -67,6 +126,35 @@ pub enum InstructionsWithLabels {
         src: Slot,
         prover_only: bool,
     },
+    Mvvh {
+        dst: SlotWithOffset,
+        src: Slot,
+        prover_only: bool,
+    },
+    Mvib {
+        dst: SlotWithOffset,
+        imm: Immediate,
+        prover_only: bool,
+    },
+    Cmov {
+        dst: Slot,
+        cond: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Memcpy {
+        dst: Slot,
+        src: Slot,
+        len: Immediate,
+        prover_only: bool,
+    },
+    Memset {
+        dst: Slot,
+        src: Slot,
+        len: Immediate,
+        prover_only: bool,
+    },
     Taili {
         label: String,
         next_fp: Slot,
@@ -94,6 +182,12 @@ pub enum InstructionsWithLabels {
         imm: Immediate,
         prover_only: bool,
     },
+    /// LDI.W of a value derived from the field PC of a label.
+    LdiLabel {
+        dst: Slot,
+        imm: LabelImmediate,
+        prover_only: bool,
+    },
     Xor {
         dst: Slot,
         src1: Slot,
@@ -110,6 +204,26 @@ pub enum InstructionsWithLabels {
         label: String,
         src: Slot,
     },
+    Beq {
+        label: String,
+        src1: Slot,
+        src2: Slot,
+    },
+    Bne {
+        label: String,
+        src1: Slot,
+        src2: Slot,
+    },
+    Blt {
+        label: String,
+        src1: Slot,
+        src2: Slot,
+    },
+    Bge {
+        label: String,
+        src1: Slot,
+        src2: Slot,
+    },
     Add {
         dst: Slot,
         src1: Slot,
@@ -206,6 +320,60 @@ pub enum InstructionsWithLabels {
         src2: Slot,
         prover_only: bool,
     },
+    Rol {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Ror {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Div {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Divu {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Rem {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Remu {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Add64 {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Sub64 {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    Slt64 {
+        dst: Slot,
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
     Andi {
         dst: Slot,
         src1: Slot,
@@ -260,6 +428,48 @@ pub enum InstructionsWithLabels {
         imm: Immediate,
         prover_only: bool,
     },
+    Clz {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    Ctz {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    Popcnt {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    Bswap {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    Sextb {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    Sexth {
+        dst: Slot,
+        src: Slot,
+        prover_only: bool,
+    },
+    Roli {
+        dst: Slot,
+        src1: Slot,
+        imm: Immediate,
+        prover_only: bool,
+    },
+    Rori {
+        dst: Slot,
+        src1: Slot,
+        imm: Immediate,
+        prover_only: bool,
+    },
     Alloci {
         dst: Slot,
         imm: Immediate,
@@ -268,7 +478,107 @@ pub enum InstructionsWithLabels {
         dst: Slot,
         src: Slot,
     },
+    Alloch {
+        dst: Slot,
+        size: Slot,
+    },
+    Free {
+        ptr: Slot,
+    },
+    Commit {
+        src: Slot,
+    },
+    MerkleVerify {
+        root: Slot,
+        leaf: Slot,
+        path: Slot,
+    },
+    Abort {
+        ptr: Slot,
+        len: Slot,
+    },
+    AssertEq {
+        src1: Slot,
+        src2: Slot,
+        prover_only: bool,
+    },
+    AssertZero {
+        src: Slot,
+        prover_only: bool,
+    },
+    RangeCheck {
+        src: Slot,
+        bits: Immediate,
+        prover_only: bool,
+    },
+    Ecall {
+        id: Immediate,
+        base: Slot,
+    },
+    ReadAdvice {
+        dst: Slot,
+    },
+    Debug {
+        src: Slot,
+    },
+    Custom {
+        opcode: Immediate,
+        args: [Operand; 3],
+        prover_only: bool,
+    },
+    Lw {
+        dst: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Lb {
+        dst: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Lbu {
+        dst: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Lh {
+        dst: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Lhu {
+        dst: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Sw {
+        src: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Sb {
+        src: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
+    Sh {
+        src: Slot,
+        base: Slot,
+        offset: Immediate,
+        prover_only: bool,
+    },
     Ret,
+    Halt {
+        exit_code: Immediate,
+    },
+    Nop,
 }
 
 impl InstructionsWithLabels {
@@ -278,12 +588,21 @@ impl InstructionsWithLabels {
             Fp { prover_only, .. } => *prover_only,
             B32Mul { prover_only, .. } => *prover_only,
             B32Muli { prover_only, .. } => *prover_only,
+            B32Inv { prover_only, .. } => *prover_only,
+            B64Mul { prover_only, .. } => *prover_only,
             B128Add { prover_only, .. } => *prover_only,
             B128Mul { prover_only, .. } => *prover_only,
+            B128Inv { prover_only, .. } => *prover_only,
             Mvih { prover_only, .. } => *prover_only,
             Mvvw { prover_only, .. } => *prover_only,
             Mvvl { prover_only, .. } => *prover_only,
+            Mvvh { prover_only, .. } => *prover_only,
+            Mvib { prover_only, .. } => *prover_only,
+            Cmov { prover_only, .. } => *prover_only,
+            Memcpy { prover_only, .. } => *prover_only,
+            Memset { prover_only, .. } => *prover_only,
             Ldi { prover_only, .. } => *prover_only,
+            LdiLabel { prover_only, .. } => *prover_only,
             Xor { prover_only, .. } => *prover_only,
             Xori { prover_only, .. } => *prover_only,
             Add { prover_only, .. } => *prover_only,
@@ -302,6 +621,23 @@ impl InstructionsWithLabels {
             Sll { prover_only, .. } => *prover_only,
             Srl { prover_only, .. } => *prover_only,
             Sra { prover_only, .. } => *prover_only,
+            Rol { prover_only, .. } => *prover_only,
+            Ror { prover_only, .. } => *prover_only,
+            Lw { prover_only, .. } => *prover_only,
+            Lb { prover_only, .. } => *prover_only,
+            Lbu { prover_only, .. } => *prover_only,
+            Lh { prover_only, .. } => *prover_only,
+            Lhu { prover_only, .. } => *prover_only,
+            Sw { prover_only, .. } => *prover_only,
+            Sb { prover_only, .. } => *prover_only,
+            Sh { prover_only, .. } => *prover_only,
+            Div { prover_only, .. } => *prover_only,
+            Divu { prover_only, .. } => *prover_only,
+            Rem { prover_only, .. } => *prover_only,
+            Remu { prover_only, .. } => *prover_only,
+            Add64 { prover_only, .. } => *prover_only,
+            Sub64 { prover_only, .. } => *prover_only,
+            Slt64 { prover_only, .. } => *prover_only,
             Andi { prover_only, .. } => *prover_only,
             And { prover_only, .. } => *prover_only,
             Muli { prover_only, .. } => *prover_only,
@@ -311,11 +647,369 @@ impl InstructionsWithLabels {
             Srli { prover_only, .. } => *prover_only,
             Slli { prover_only, .. } => *prover_only,
             Srai { prover_only, .. } => *prover_only,
+            Roli { prover_only, .. } => *prover_only,
+            Clz { prover_only, .. } => *prover_only,
+            Ctz { prover_only, .. } => *prover_only,
+            Popcnt { prover_only, .. } => *prover_only,
+            Bswap { prover_only, .. } => *prover_only,
+            Sextb { prover_only, .. } => *prover_only,
+            Sexth { prover_only, .. } => *prover_only,
+            Rori { prover_only, .. } => *prover_only,
+            AssertEq { prover_only, .. } => *prover_only,
+            AssertZero { prover_only, .. } => *prover_only,
+            RangeCheck { prover_only, .. } => *prover_only,
+            Custom { prover_only, .. } => *prover_only,
             Alloci { .. } => true,
             Allocv { .. } => true,
+            Alloch { .. } => true,
+            Free { .. } => true,
+            Ecall { .. } => true,
+            ReadAdvice { .. } => true,
+            Debug { .. } => true,
             _ => false,
         }
     }
+
+    /// Returns the label the instruction branches to or calls, if any.
+    pub(crate) fn target_label(&self) -> Option<&str> {
+        use InstructionsWithLabels::*;
+        match self {
+            Taili { label, .. }
+            | Calli { label, .. }
+            | Jumpi { label }
+            | Bnz { label, .. }
+            | Beq { label, .. }
+            | Bne { label, .. }
+            | Blt { label, .. }
+            | Bge { label, .. } => Some(label),
+            _ => None,
+        }
+    }
+
+    /// Returns the labels the instruction or data directive refers to, with
+    /// their offsets if any, e.g. `loop+2`.
+    pub(crate) fn label_refs(&self) -> Vec<&str> {
+        match self {
+            InstructionsWithLabels::LdiLabel { imm, .. } => vec![imm.label()],
+            InstructionsWithLabels::Data(DataDirective::Labels(labels))
+            | InstructionsWithLabels::JumpTable { cases: labels, .. } => {
+                labels.iter().map(String::as_str).collect()
+            }
+            _ => self.target_label().into_iter().collect(),
+        }
+    }
+
+    /// Returns whether the execution never falls through to the next
+    /// instruction.
+    pub(crate) const fn is_terminator(&self) -> bool {
+        use InstructionsWithLabels::*;
+        matches!(
+            self,
+            Taili { .. }
+                | Tailv { .. }
+                | Jumpi { .. }
+                | Jumpv { .. }
+                | Ret
+                | Halt { .. }
+                | Abort { .. }
+        )
+    }
+
+    /// Returns the first slot of the current frame the instruction writes to,
+    /// with the number of 32-bit words it writes, if any.
+    ///
+    /// Writes to other frames or to RAM are not included.
+    pub(crate) const fn written_slots(&self) -> Option<(Slot, u32)> {
+        use InstructionsWithLabels::*;
+        let written = match self {
+            B128Add { dst, .. } | B128Mul { dst, .. } | B128Inv { dst, .. } => (*dst, 4),
+            Groestl256Compress { dst, .. } | Blake3Compress { dst, .. } => (*dst, 16),
+            Groestl256Output { dst, .. } | Sha256Compress { dst, .. } | BigMulMod { dst, .. } => {
+                (*dst, 8)
+            }
+            B64Mul { dst, .. }
+            | Add64 { dst, .. }
+            | Sub64 { dst, .. }
+            | Muli { dst, .. }
+            | Mul { dst, .. }
+            | Mulu { dst, .. }
+            | Mulsu { dst, .. } => (*dst, 2),
+            Fp { dst, .. }
+            | B32Mul { dst, .. }
+            | B32Muli { dst, .. }
+            | B32Inv { dst, .. }
+            | Cmov { dst, .. }
+            | Ldi { dst, .. }
+            | LdiLabel { dst, .. }
+            | Xor { dst, .. }
+            | Xori { dst, .. }
+            | Add { dst, .. }
+            | Addi { dst, .. }
+            | Or { dst, .. }
+            | Ori { dst, .. }
+            | Sub { dst, .. }
+            | Sle { dst, .. }
+            | Slei { dst, .. }
+            | Sleu { dst, .. }
+            | Sleiu { dst, .. }
+            | Slt { dst, .. }
+            | Slti { dst, .. }
+            | Sltu { dst, .. }
+            | Sltiu { dst, .. }
+            | Sll { dst, .. }
+            | Srl { dst, .. }
+            | Sra { dst, .. }
+            | Rol { dst, .. }
+            | Ror { dst, .. }
+            | Div { dst, .. }
+            | Divu { dst, .. }
+            | Rem { dst, .. }
+            | Remu { dst, .. }
+            | Slt64 { dst, .. }
+            | Andi { dst, .. }
+            | And { dst, .. }
+            | Srli { dst, .. }
+            | Slli { dst, .. }
+            | Srai { dst, .. }
+            | Clz { dst, .. }
+            | Ctz { dst, .. }
+            | Popcnt { dst, .. }
+            | Bswap { dst, .. }
+            | Sextb { dst, .. }
+            | Sexth { dst, .. }
+            | Roli { dst, .. }
+            | Rori { dst, .. }
+            | Alloci { dst, .. }
+            | Allocv { dst, .. }
+            | Alloch { dst, .. }
+            | ReadAdvice { dst }
+            | Lw { dst, .. }
+            | Lb { dst, .. }
+            | Lbu { dst, .. }
+            | Lh { dst, .. }
+            | Lhu { dst, .. } => (*dst, 1),
+            JumpTable { scratch, .. } => (*scratch, 3),
+            _ => return None,
+        };
+        Some(written)
+    }
+
+    /// Returns the frame size needed by the instruction, i.e. one past the
+    /// highest slot it accesses in the current frame.
+    ///
+    /// For moves to another frame, only the slot holding the pointer to that
+    /// frame counts.
+    pub(crate) fn frame_extent(&self) -> u32 {
+        self.accessed_slots()
+            .into_iter()
+            .map(|(slot, width)| slot.index() + width)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the slots of the current frame the instruction accesses, with
+    /// the number of 32-bit words they span, as counted by
+    /// [`InstructionsWithLabels::frame_extent`].
+    pub(crate) fn accessed_slots(&self) -> Vec<(Slot, u32)> {
+        self.clone()
+            .accessed_slots_mut()
+            .into_iter()
+            .map(|(slot, width)| (slot, width))
+            .collect()
+    }
+
+    /// Returns mutable references to the slots of the current frame the
+    /// instruction accesses, with the number of 32-bit words they span.
+    pub(crate) fn accessed_slots_mut(&mut self) -> Vec<(&mut Slot, u32)> {
+        use InstructionsWithLabels::*;
+        match self {
+            Label(..)
+            | DataLabel(_)
+            | Data(_)
+            | Public(_)
+            | Align(_)
+            | Location(_)
+            | Jumpi { .. }
+            | Ret
+            | Halt { .. }
+            | Nop => vec![],
+            JumpTable { index, scratch, .. } => vec![(index, 1), (scratch, 3)],
+            Fp { dst, .. }
+            | Ldi { dst, .. }
+            | LdiLabel { dst, .. }
+            | Alloci { dst, .. }
+            | ReadAdvice { dst } => {
+                vec![(dst, 1)]
+            }
+            Mvih { dst, .. } | Mvib { dst, .. } => vec![(dst.slot_mut(), 1)],
+            Mvvw { dst, src, .. } | Mvvh { dst, src, .. } => vec![(dst.slot_mut(), 1), (src, 1)],
+            Mvvl { dst, src, .. } => vec![(dst.slot_mut(), 1), (src, 4)],
+            Memcpy { dst, src, .. } | Memset { dst, src, .. } => vec![(dst, 1), (src, 1)],
+            Cmov {
+                dst,
+                cond,
+                src1,
+                src2,
+                ..
+            } => vec![(dst, 1), (cond, 1), (src1, 1), (src2, 1)],
+            Taili { next_fp, .. } | Calli { next_fp, .. } => vec![(next_fp, 1)],
+            Tailv { offset, next_fp } | Callv { offset, next_fp } => {
+                vec![(offset, 1), (next_fp, 1)]
+            }
+            Jumpv { offset } => vec![(offset, 1)],
+            Bnz { src, .. } => vec![(src, 1)],
+            Beq { src1, src2, .. }
+            | Bne { src1, src2, .. }
+            | Blt { src1, src2, .. }
+            | Bge { src1, src2, .. } => vec![(src1, 1), (src2, 1)],
+            Allocv { dst, src } | Alloch { dst, size: src } => vec![(dst, 1), (src, 1)],
+            Abort { ptr, len } => vec![(ptr, 1), (len, 1)],
+            AssertEq { src1, src2, .. } => vec![(src1, 1), (src2, 1)],
+            AssertZero { src, .. } | RangeCheck { src, .. } => vec![(src, 1)],
+            Ecall { base, .. } => vec![(base, 1)],
+            Free { ptr } => vec![(ptr, 1)],
+            Commit { src } => vec![(src, 1)],
+            // The siblings following the index and depth of the path are only
+            // known at runtime.
+            MerkleVerify { root, leaf, path } => vec![(root, 8), (leaf, 8), (path, 2)],
+            Debug { src } => vec![(src, 1)],
+            Custom { args, .. } => args
+                .iter_mut()
+                .filter_map(Operand::slot_mut)
+                .map(|slot| (slot, 1))
+                .collect(),
+            B128Add {
+                dst, src1, src2, ..
+            }
+            | B128Mul {
+                dst, src1, src2, ..
+            } => vec![(dst, 4), (src1, 4), (src2, 4)],
+            B128Inv { dst, src, .. } => vec![(dst, 4), (src, 4)],
+            Groestl256Compress {
+                dst, src1, src2, ..
+            } => vec![(dst, 16), (src1, 16), (src2, 16)],
+            Groestl256Output {
+                dst, src1, src2, ..
+            } => vec![(dst, 8), (src1, 8), (src2, 8)],
+            Sha256Compress {
+                dst, src1, src2, ..
+            } => vec![(dst, 8), (src1, 8), (src2, 16)],
+            Blake3Compress {
+                dst, src1, src2, ..
+            } => vec![(dst, 16), (src1, 12), (src2, 16)],
+            BigMulMod {
+                dst, src1, src2, ..
+            } => vec![(dst, 8), (src1, 16), (src2, 8)],
+            Mul {
+                dst, src1, src2, ..
+            }
+            | Mulu {
+                dst, src1, src2, ..
+            }
+            | Mulsu {
+                dst, src1, src2, ..
+            } => vec![(dst, 2), (src1, 1), (src2, 1)],
+            Muli { dst, src1, .. } => vec![(dst, 2), (src1, 1)],
+            Add64 {
+                dst, src1, src2, ..
+            }
+            | Sub64 {
+                dst, src1, src2, ..
+            }
+            | B64Mul {
+                dst, src1, src2, ..
+            } => vec![(dst, 2), (src1, 2), (src2, 2)],
+            Slt64 {
+                dst, src1, src2, ..
+            } => vec![(dst, 1), (src1, 2), (src2, 2)],
+            B32Mul {
+                dst, src1, src2, ..
+            }
+            | Xor {
+                dst, src1, src2, ..
+            }
+            | Add {
+                dst, src1, src2, ..
+            }
+            | Or {
+                dst, src1, src2, ..
+            }
+            | Sub {
+                dst, src1, src2, ..
+            }
+            | Sle {
+                dst, src1, src2, ..
+            }
+            | Sleu {
+                dst, src1, src2, ..
+            }
+            | Slt {
+                dst, src1, src2, ..
+            }
+            | Sltu {
+                dst, src1, src2, ..
+            }
+            | Sll {
+                dst, src1, src2, ..
+            }
+            | Srl {
+                dst, src1, src2, ..
+            }
+            | Sra {
+                dst, src1, src2, ..
+            }
+            | Rol {
+                dst, src1, src2, ..
+            }
+            | Ror {
+                dst, src1, src2, ..
+            }
+            | Div {
+                dst, src1, src2, ..
+            }
+            | Divu {
+                dst, src1, src2, ..
+            }
+            | Rem {
+                dst, src1, src2, ..
+            }
+            | Remu {
+                dst, src1, src2, ..
+            }
+            | And {
+                dst, src1, src2, ..
+            } => vec![(dst, 1), (src1, 1), (src2, 1)],
+            B32Muli { dst, src1, .. }
+            | Addi { dst, src1, .. }
+            | Ori { dst, src1, .. }
+            | Andi { dst, src1, .. }
+            | Srli { dst, src1, .. }
+            | Slli { dst, src1, .. }
+            | Srai { dst, src1, .. }
+            | Roli { dst, src1, .. }
+            | Rori { dst, src1, .. } => vec![(dst, 1), (src1, 1)],
+            Xori { dst, src, .. }
+            | Clz { dst, src, .. }
+            | Ctz { dst, src, .. }
+            | Popcnt { dst, src, .. }
+            | Bswap { dst, src, .. }
+            | Sextb { dst, src, .. }
+            | Sexth { dst, src, .. }
+            | B32Inv { dst, src, .. }
+            | Slei { dst, src, .. }
+            | Sleiu { dst, src, .. }
+            | Slti { dst, src, .. }
+            | Sltiu { dst, src, .. } => vec![(dst, 1), (src, 1)],
+            Lw { dst, base, .. }
+            | Lb { dst, base, .. }
+            | Lbu { dst, base, .. }
+            | Lh { dst, base, .. }
+            | Lhu { dst, base, .. } => vec![(dst, 1), (base, 1)],
+            Sw { src, base, .. } | Sb { src, base, .. } | Sh { src, base, .. } => {
+                vec![(src, 1), (base, 1)]
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for InstructionsWithLabels {
@@ -330,6 +1024,21 @@ impl std::fmt::Display for InstructionsWithLabels {
                     write!(f, "{label}:")
                 }
             }
+            DataLabel(label) => write!(f, "{label}:"),
+            Data(directive) => write!(f, "{directive}"),
+            Public(slot) => write!(f, ".public {slot}"),
+            Align(alignment) => write!(f, ".align {alignment}"),
+            Location(location) => write!(f, ";; {location}"),
+            JumpTable {
+                name,
+                index,
+                scratch,
+                cases,
+            } => write!(
+                f,
+                ".jumptable {name}, {index}, {scratch}, [{}]",
+                cases.join(", ")
+            ),
             Fp { dst, imm, .. } => {
                 write!(f, "FP{bang} {dst} {imm}")
             }
@@ -341,6 +1050,12 @@ impl std::fmt::Display for InstructionsWithLabels {
             B32Muli { dst, src1, imm, .. } => {
                 write!(f, "B32_MULI{bang} {dst} {src1} {imm}")
             }
+            B32Inv { dst, src, .. } => write!(f, "B32_INV{bang} {dst} {src}"),
+            B64Mul {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "B64_MUL{bang} {dst} {src1} {src2}")
+            }
             B128Add {
                 dst, src1, src2, ..
             } => {
@@ -351,6 +1066,7 @@ impl std::fmt::Display for InstructionsWithLabels {
             } => {
                 write!(f, "B128_MUL{bang} {dst} {src1} {src2}")
             }
+            B128Inv { dst, src, .. } => write!(f, "B128_INV{bang} {dst} {src}"),
             InstructionsWithLabels::Groestl256Compress {
                 dst, src1, src2, ..
             } => {
@@ -361,6 +1077,21 @@ impl std::fmt::Display for InstructionsWithLabels {
             } => {
                 write!(f, "GROESTL256_OUTPUT{bang} {dst} {src1} {src2}")
             }
+            InstructionsWithLabels::Sha256Compress {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "SHA256_COMPRESS{bang} {dst} {src1} {src2}")
+            }
+            InstructionsWithLabels::Blake3Compress {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "BLAKE3_COMPRESS{bang} {dst} {src1} {src2}")
+            }
+            InstructionsWithLabels::BigMulMod {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "BIGMULMOD{bang} {dst} {src1} {src2}")
+            }
             Mvih { dst, imm, .. } => {
                 write!(f, "MVI.H{bang} {dst} {imm}")
             }
@@ -370,6 +1101,25 @@ impl std::fmt::Display for InstructionsWithLabels {
             Mvvl { dst, src, .. } => {
                 write!(f, "MVV.L{bang} {dst} {src}")
             }
+            Mvvh { dst, src, .. } => {
+                write!(f, "MVV.H{bang} {dst} {src}")
+            }
+            Mvib { dst, imm, .. } => {
+                write!(f, "MVI.B{bang} {dst} {imm}")
+            }
+            Cmov {
+                dst,
+                cond,
+                src1,
+                src2,
+                ..
+            } => write!(f, "CMOV{bang} {dst} {cond} {src1} {src2}"),
+            Memcpy { dst, src, len, .. } => {
+                write!(f, "MEMCPY{bang} {dst} {src} {len}")
+            }
+            Memset { dst, src, len, .. } => {
+                write!(f, "MEMSET{bang} {dst} {src} {len}")
+            }
             Taili { label, next_fp } => {
                 write!(f, "TAILI {label} {next_fp}")
             }
@@ -385,6 +1135,7 @@ impl std::fmt::Display for InstructionsWithLabels {
             Jumpi { label } => write!(f, "J {label}"),
             Jumpv { offset } => write!(f, "J {offset}"),
             Ldi { dst, imm, .. } => write!(f, "LDI{bang} {dst} {imm}"),
+            LdiLabel { dst, imm, .. } => write!(f, "LDI{bang} {dst} {imm}"),
             Xor {
                 dst, src1, src2, ..
             } => write!(f, "XOR{bang} {dst} {src1} {src2}"),
@@ -392,6 +1143,10 @@ impl std::fmt::Display for InstructionsWithLabels {
                 write!(f, "XORI{bang} {dst} {src} {imm}")
             }
             Bnz { label, src } => write!(f, "BNZ {label} {src}"),
+            Beq { label, src1, src2 } => write!(f, "BEQ {label} {src1} {src2}"),
+            Bne { label, src1, src2 } => write!(f, "BNE {label} {src1} {src2}"),
+            Blt { label, src1, src2 } => write!(f, "BLT {label} {src1} {src2}"),
+            Bge { label, src1, src2 } => write!(f, "BGE {label} {src1} {src2}"),
             Add {
                 dst, src1, src2, ..
             } => write!(f, "ADD{bang} {dst} {src1} {src2}"),
@@ -454,6 +1209,51 @@ impl std::fmt::Display for InstructionsWithLabels {
             } => {
                 write!(f, "SRA{bang} {dst} {src1} {src2}")
             }
+            Rol {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "ROL{bang} {dst} {src1} {src2}")
+            }
+            Ror {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "ROR{bang} {dst} {src1} {src2}")
+            }
+            Div {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "DIV{bang} {dst} {src1} {src2}")
+            }
+            Divu {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "DIVU{bang} {dst} {src1} {src2}")
+            }
+            Rem {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "REM{bang} {dst} {src1} {src2}")
+            }
+            Remu {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "REMU{bang} {dst} {src1} {src2}")
+            }
+            Add64 {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "ADD.64{bang} {dst} {src1} {src2}")
+            }
+            Sub64 {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "SUB.64{bang} {dst} {src1} {src2}")
+            }
+            Slt64 {
+                dst, src1, src2, ..
+            } => {
+                write!(f, "SLT.64{bang} {dst} {src1} {src2}")
+            }
             Andi { dst, src1, imm, .. } => {
                 write!(f, "ANDI{bang} {dst} {src1} {imm}")
             }
@@ -487,13 +1287,96 @@ impl std::fmt::Display for InstructionsWithLabels {
             Srai { dst, src1, imm, .. } => {
                 write!(f, "SRAI{bang} {dst} {src1} {imm}")
             }
+            Clz { dst, src, .. } => write!(f, "CLZ{bang} {dst} {src}"),
+            Ctz { dst, src, .. } => write!(f, "CTZ{bang} {dst} {src}"),
+            Popcnt { dst, src, .. } => write!(f, "POPCNT{bang} {dst} {src}"),
+            Bswap { dst, src, .. } => write!(f, "BSWAP{bang} {dst} {src}"),
+            Sextb { dst, src, .. } => write!(f, "SEXT.B{bang} {dst} {src}"),
+            Sexth { dst, src, .. } => write!(f, "SEXT.H{bang} {dst} {src}"),
+            Roli { dst, src1, imm, .. } => {
+                write!(f, "ROLI{bang} {dst} {src1} {imm}")
+            }
+            Rori { dst, src1, imm, .. } => {
+                write!(f, "RORI{bang} {dst} {src1} {imm}")
+            }
             Ret => write!(f, "RET"),
+            Halt { exit_code } => write!(f, "HALT {exit_code}"),
+            Nop => write!(f, "NOP"),
             Alloci { dst, imm } => {
                 write!(f, "ALLOCI! {dst} {imm}")
             }
             Allocv { dst, src } => {
                 write!(f, "ALLOCV! {dst} {src}")
             }
+            Alloch { dst, size } => {
+                write!(f, "ALLOCH! {dst} {size}")
+            }
+            Free { ptr } => write!(f, "FREE! {ptr}"),
+            Commit { src } => write!(f, "COMMIT {src}"),
+            MerkleVerify { root, leaf, path } => write!(f, "MERKLE_VERIFY {root} {leaf} {path}"),
+            Abort { ptr, len } => {
+                write!(f, "ABORT {ptr} {len}")
+            }
+            AssertEq { src1, src2, .. } => {
+                write!(f, "ASSERT_EQ{bang} {src1} {src2}")
+            }
+            AssertZero { src, .. } => {
+                write!(f, "ASSERT_ZERO{bang} {src}")
+            }
+            RangeCheck { src, bits, .. } => {
+                write!(f, "RANGE_CHECK{bang} {src} {bits}")
+            }
+            Ecall { id, base } => {
+                write!(f, "ECALL! {id} {base}")
+            }
+            ReadAdvice { dst } => {
+                write!(f, "READ_ADVICE! {dst}")
+            }
+            Debug { src } => write!(f, "DEBUG {src}"),
+            Custom { opcode, args, .. } => {
+                let [arg0, arg1, arg2] = args;
+                write!(f, "CUSTOM{bang} {opcode} {arg0} {arg1} {arg2}")
+            }
+            Lw {
+                dst, base, offset, ..
+            } => {
+                write!(f, "LW{bang} {dst} {base} {offset}")
+            }
+            Lb {
+                dst, base, offset, ..
+            } => {
+                write!(f, "LB{bang} {dst} {base} {offset}")
+            }
+            Lbu {
+                dst, base, offset, ..
+            } => {
+                write!(f, "LBU{bang} {dst} {base} {offset}")
+            }
+            Lh {
+                dst, base, offset, ..
+            } => {
+                write!(f, "LH{bang} {dst} {base} {offset}")
+            }
+            Lhu {
+                dst, base, offset, ..
+            } => {
+                write!(f, "LHU{bang} {dst} {base} {offset}")
+            }
+            Sw {
+                src, base, offset, ..
+            } => {
+                write!(f, "SW{bang} {src} {base} {offset}")
+            }
+            Sb {
+                src, base, offset, ..
+            } => {
+                write!(f, "SB{bang} {src} {base} {offset}")
+            }
+            Sh {
+                src, base, offset, ..
+            } => {
+                write!(f, "SH{bang} {src} {base} {offset}")
+            }
         }
     }
 }
@@ -503,6 +1386,9 @@ pub enum Error {
     #[error("Unknown instruction: {0}")]
     UnknownInstruction(String),
 
+    #[error("Malformed syntax tree: {0}")]
+    MissingToken(&'static str),
+
     #[error(
         "Wrong number of arguments on line {line_number} for instruction: {instruction} {args:?}"
     )]
@@ -518,6 +1404,90 @@ pub enum Error {
     #[error("You must have at least one label and one instruction")]
     NoStartLabelOrInstructionFound,
 
+    #[error("Unknown macro: {0}")]
+    UnknownMacro(String),
+
+    #[error("Macro {0} is defined more than once")]
+    DuplicateMacro(String),
+
+    #[error("Macro {name} has a duplicate parameter: {param}")]
+    DuplicateMacroParameter { name: String, param: String },
+
+    #[error("Macro {name} has no parameter named {param}")]
+    UnknownMacroParameter { name: String, param: String },
+
+    #[error("Macro {name} expects {expected} arguments, but {found} were given")]
+    WrongNumberOfMacroArguments {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Expansion of macro {0} is nested too deeply, is it recursive?")]
+    MacroExpansionTooDeep(String),
+
+    #[error("Constant {0} is defined more than once")]
+    DuplicateConstant(String),
+
+    #[error("Invalid alignment: {0}, expected a positive number of instructions")]
+    InvalidAlignment(String),
+
     #[error(transparent)]
     PestParse(#[from] Box<pest::error::Error<super::Rule>>),
+
+    #[error("{span}: {error}")]
+    WithSpan {
+        /// The error raised on the source.
+        error: Box<Error>,
+        /// Location of the offending token.
+        span: SourceSpan,
+    },
+}
+
+impl Error {
+    /// Locates the error in `span`, narrowed down to the offending token when
+    /// it appears in it. Errors that are already located are left untouched.
+    pub(crate) fn at(self, span: pest::Span<'_>) -> Self {
+        if matches!(self, Self::WithSpan { .. }) {
+            return self;
+        }
+        let span = self
+            .token()
+            .and_then(|token| {
+                let offset = span.as_str().find(token)?;
+                span.get(offset..offset + token.len())
+            })
+            .unwrap_or(span);
+        Self::WithSpan {
+            error: Box::new(self),
+            span: SourceSpan::from_pest(span),
+        }
+    }
+
+    /// Locates a pest error in `input`.
+    pub(crate) fn from_pest(input: &str, error: pest::error::Error<super::Rule>) -> Self {
+        let (line, column) = match error.line_col {
+            LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+        };
+        Self::WithSpan {
+            error: Box::new(Self::PestParse(Box::new(error))),
+            span: SourceSpan::at(input, line, column),
+        }
+    }
+
+    /// Returns the offending token, if the error is about one.
+    fn token(&self) -> Option<&str> {
+        match self {
+            Self::UnknownInstruction(token)
+            | Self::UnknownMacro(token)
+            | Self::DuplicateMacro(token)
+            | Self::MacroExpansionTooDeep(token)
+            | Self::DuplicateConstant(token)
+            | Self::InvalidAlignment(token)
+            | Self::DuplicateMacroParameter { param: token, .. }
+            | Self::UnknownMacroParameter { param: token, .. } => Some(token),
+            Self::BadArgument(err) => Some(err.token()),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,143 @@
+//! Assembler macros.
+//!
+//! A macro is declared with `#[macro] name(param1, param2) { ... }`, and
+//! invoked as `name(arg1, arg2)` in place of an instruction. Invocations are
+//! expanded textually before the resulting lines are parsed, so that macros
+//! may expand to any sequence of instructions, labels or other macro calls.
+//!
+//! Within a macro body, `$param` is replaced by the corresponding argument and
+//! `$@` by an identifier unique to each expansion, which allows a macro to
+//! define local labels.
+
+use std::collections::HashMap;
+
+use pest::iterators::Pair;
+
+use super::{next_token, Error, Rule};
+
+/// Maximum nesting depth of macro expansions, to catch recursive macros.
+const MAX_EXPANSION_DEPTH: usize = 64;
+
+#[derive(Debug, Clone)]
+struct Macro {
+    params: Vec<String>,
+    body: String,
+}
+
+/// Macros defined so far in a program.
+#[derive(Debug, Default)]
+pub(crate) struct MacroTable {
+    macros: HashMap<String, Macro>,
+    /// Number of expansions so far, used to generate unique identifiers.
+    num_expansions: usize,
+    /// Current nesting depth of expansions.
+    depth: usize,
+}
+
+impl MacroTable {
+    /// Registers the macro defined by a `macro_def` pair.
+    pub(crate) fn define(&mut self, pair: Pair<'_, Rule>) -> Result<(), Error> {
+        let mut inner = pair.into_inner();
+        let name = next_token(&mut inner, "macro_def has a name")?.as_str();
+        let params = next_token(&mut inner, "macro_def has params")?
+            .into_inner()
+            .map(|param| param.as_str().to_string())
+            .collect::<Vec<_>>();
+        let body = next_token(&mut inner, "macro_def has a body")?.as_str();
+
+        for (i, param) in params.iter().enumerate() {
+            if params[..i].contains(param) {
+                return Err(Error::DuplicateMacroParameter {
+                    name: name.to_string(),
+                    param: param.clone(),
+                });
+            }
+        }
+
+        let new_macro = Macro {
+            params,
+            body: body.to_string(),
+        };
+        if self.macros.insert(name.to_string(), new_macro).is_some() {
+            return Err(Error::DuplicateMacro(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Expands the invocation given by a `macro_call` pair into the assembly
+    /// code it stands for.
+    ///
+    /// Every successful call must be matched by a call to
+    /// [`end_expansion`](Self::end_expansion) once the expanded code has been
+    /// parsed.
+    pub(crate) fn expand(&mut self, pair: Pair<'_, Rule>) -> Result<String, Error> {
+        let mut inner = pair.into_inner();
+        let name = next_token(&mut inner, "macro_call has a name")?.as_str();
+        let args = inner.map(|arg| arg.as_str()).collect::<Vec<_>>();
+
+        let called = self
+            .macros
+            .get(name)
+            .ok_or_else(|| Error::UnknownMacro(name.to_string()))?;
+        if called.params.len() != args.len() {
+            return Err(Error::WrongNumberOfMacroArguments {
+                name: name.to_string(),
+                expected: called.params.len(),
+                found: args.len(),
+            });
+        }
+        if self.depth == MAX_EXPANSION_DEPTH {
+            return Err(Error::MacroExpansionTooDeep(name.to_string()));
+        }
+
+        let expansion = substitute(name, called, &args, self.num_expansions)?;
+        self.num_expansions += 1;
+        self.depth += 1;
+        Ok(expansion)
+    }
+
+    /// Marks the end of the innermost expansion.
+    pub(crate) fn end_expansion(&mut self) {
+        self.depth -= 1;
+    }
+}
+
+/// Substitutes the parameters of `called` in its body.
+fn substitute(
+    name: &str,
+    called: &Macro,
+    args: &[&str],
+    expansion_id: usize,
+) -> Result<String, Error> {
+    let mut expanded = String::with_capacity(called.body.len());
+    let mut rest = called.body.as_str();
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(stripped) = rest.strip_prefix('@') {
+            expanded.push_str(&expansion_id.to_string());
+            rest = stripped;
+            continue;
+        }
+
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let param = &rest[..len];
+        let index = called
+            .params
+            .iter()
+            .position(|p| p == param)
+            .ok_or_else(|| Error::UnknownMacroParameter {
+                name: name.to_string(),
+                param: param.to_string(),
+            })?;
+        expanded.push_str(args[index]);
+        rest = &rest[len..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
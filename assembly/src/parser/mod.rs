@@ -2,34 +2,92 @@ use std::str::FromStr;
 
 use pest::{iterators::Pair, iterators::Pairs, Parser};
 
+mod expr;
 mod instruction_args;
 mod instructions_with_labels;
+mod macros;
 mod tests;
 
-use instruction_args::{Immediate, Slot, SlotWithOffset};
+use expr::Constants;
+pub(crate) use instruction_args::{split_label_ref, DataDirective, Immediate, Slot};
+use instruction_args::{LabelImmediate, Operand, SlotWithOffset};
 pub(crate) use instructions_with_labels::{Error, InstructionsWithLabels};
+use macros::MacroTable;
 use tracing::instrument;
 
+use crate::assembler::SourceLocation;
+
 #[derive(pest_derive::Parser)]
 #[grammar = "parser/asm.pest"]
 struct AsmParser;
 
+/// Returns the next token of `pairs`, which the grammar guarantees to exist as
+/// stated by `msg`.
+#[inline]
+pub(super) fn next_token<'a>(
+    pairs: &mut Pairs<'a, Rule>,
+    msg: &'static str,
+) -> Result<Pair<'a, Rule>, Error> {
+    pairs.next().ok_or(Error::MissingToken(msg))
+}
+
 #[inline]
-fn get_first_inner<'a>(pair: Pair<'a, Rule>, msg: &str) -> Pair<'a, Rule> {
-    pair.into_inner().next().expect(msg)
+fn get_first_inner<'a>(pair: Pair<'a, Rule>, msg: &'static str) -> Result<Pair<'a, Rule>, Error> {
+    next_token(&mut pair.into_inner(), msg)
 }
 
 #[inline]
-fn parse_opcode<'a>(pair: Pair<'a, Rule>) -> (Rule, bool) {
+fn parse_opcode(pair: Pair<'_, Rule>) -> Result<(Rule, bool), Error> {
     let mut pairs = pair.into_inner();
-    let opcode_rule = pairs.next().expect("opcode is always present").as_rule();
+    let opcode_rule = next_token(&mut pairs, "opcode is always present")?.as_rule();
     let prover_only = pairs.next().is_some();
-    (opcode_rule, prover_only)
+    Ok((opcode_rule, prover_only))
+}
+
+/// Definitions accumulated while parsing a program.
+#[derive(Debug, Default)]
+struct ParserState {
+    macros: MacroTable,
+    constants: Constants,
+    /// Whether to record the source location of the instructions.
+    locations: bool,
+    /// Origin given by the last `.loc` directive.
+    origin: Option<String>,
+}
+
+impl ParserState {
+    /// Registers the constant defined by an `equ` pair.
+    fn define_constant(&mut self, pair: Pair<'_, Rule>) -> Result<(), Error> {
+        let mut inner = pair.into_inner();
+        let name = next_token(&mut inner, "equ has a name")?.as_str();
+        let value = expr::eval(
+            next_token(&mut inner, "equ has a value")?.as_str(),
+            &self.constants,
+        )?;
+        if self.constants.insert(name.to_string(), value).is_some() {
+            return Err(Error::DuplicateConstant(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Records the source location of the instructions parsed from `pair`,
+    /// if enabled.
+    fn locate(&self, instrs: &mut Vec<InstructionsWithLabels>, pair: &Pair<'_, Rule>) {
+        if self.locations {
+            instrs.push(InstructionsWithLabels::Location(SourceLocation {
+                file: None,
+                line: pair.as_span().start_pos().line_col().0,
+                origin: self.origin.clone(),
+            }));
+        }
+    }
 }
 
-// A line may have a frame size annotation, a label and an instruction
+// A line may have a frame size annotation, a label and an instruction or a
+// macro call. It may also define a macro or a constant.
 fn parse_line(
     instrs: &mut Vec<InstructionsWithLabels>,
+    state: &mut ParserState,
     pairs: Pairs<'_, Rule>,
 ) -> Result<(), Error> {
     let mut current_frame_size: Option<u16> = None;
@@ -38,7 +96,7 @@ fn parse_line(
         match instr_or_label.as_rule() {
             Rule::frame_size_annotation => {
                 let frame_size_hex =
-                    get_first_inner(instr_or_label, "frame_size_annotation must have frame_size");
+                    get_first_inner(instr_or_label, "frame_size_annotation must have frame_size")?;
                 let hex_str = frame_size_hex.as_str().trim_start_matches("0x");
                 let frame_size = u16::from_str_radix(hex_str, 16).map_err(|_| {
                     Error::BadArgument(instruction_args::BadArgumentError::FrameSize(
@@ -48,7 +106,7 @@ fn parse_line(
                 current_frame_size = Some(frame_size);
             }
             Rule::label => {
-                let label_name = get_first_inner(instr_or_label, "label must have label_name");
+                let label_name = get_first_inner(instr_or_label, "label must have label_name")?;
                 instrs.push(InstructionsWithLabels::Label(
                     label_name.as_span().as_str().to_string(),
                     current_frame_size, // Include the frame size with the label
@@ -56,17 +114,17 @@ fn parse_line(
                 current_frame_size = None; // Reset after using it
             }
             Rule::instruction => {
-                let instruction = get_first_inner(instr_or_label, "Instruction has inner tokens");
+                state.locate(instrs, &instr_or_label);
+                let instruction = get_first_inner(instr_or_label, "Instruction has inner tokens")?;
                 match instruction.as_rule() {
                     Rule::mov_imm => {
                         let mut mov_imm = instruction.into_inner();
-                        // Since we know this has to be MVI_H instruction
                         let (opcode_rule, prover_only) =
-                            parse_opcode(mov_imm.next().expect("This is MVI_H"));
-                        let dest = mov_imm.next().expect("MVI_H has dest");
-                        let imm = mov_imm.next().expect("MVI_H has imm");
+                            parse_opcode(next_token(&mut mov_imm, "mov_imm has instruction")?)?;
+                        let dest = next_token(&mut mov_imm, "mov_imm has dest")?;
+                        let imm = next_token(&mut mov_imm, "mov_imm has imm")?;
                         let dst = SlotWithOffset::from_str(dest.as_str())?;
-                        let imm = Immediate::from_str(imm.as_str())?;
+                        let imm = Immediate::parse(imm.as_str(), &state.constants)?;
                         match opcode_rule {
                             Rule::MVI_H_instr => {
                                 instrs.push(InstructionsWithLabels::Mvih {
@@ -75,19 +133,29 @@ fn parse_line(
                                     prover_only,
                                 });
                             }
+                            Rule::MVI_B_instr => {
+                                instrs.push(InstructionsWithLabels::Mvib {
+                                    dst,
+                                    imm,
+                                    prover_only,
+                                });
+                            }
                             _ => {
-                                unreachable!("We have implemented all mov_imm instructions");
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         }
                     }
                     Rule::binary_imm => {
                         let mut binary_imm = instruction.into_inner();
-                        let (opcode_rule, prover_only) =
-                            parse_opcode(binary_imm.next().expect("binary_imm has instruction"));
-                        let dst = binary_imm.next().expect("binary_imm has dest");
-                        let src1 = binary_imm.next().expect("binary_imm has src1");
-                        let imm = Immediate::from_str(
-                            binary_imm.next().expect("binary_imm has imm").as_str(),
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut binary_imm,
+                            "binary_imm has instruction",
+                        )?)?;
+                        let dst = next_token(&mut binary_imm, "binary_imm has dest")?;
+                        let src1 = next_token(&mut binary_imm, "binary_imm has src1")?;
+                        let imm = Immediate::parse(
+                            next_token(&mut binary_imm, "binary_imm has imm")?.as_str(),
+                            &state.constants,
                         )?;
                         match opcode_rule {
                             // B32_ADDI is an alias for XORI.
@@ -195,17 +263,35 @@ fn parse_line(
                                     prover_only,
                                 });
                             }
+                            Rule::ROLI_instr => {
+                                instrs.push(InstructionsWithLabels::Roli {
+                                    dst: Slot::from_str(dst.as_str())?,
+                                    src1: Slot::from_str(src1.as_str())?,
+                                    imm,
+                                    prover_only,
+                                });
+                            }
+                            Rule::RORI_instr => {
+                                instrs.push(InstructionsWithLabels::Rori {
+                                    dst: Slot::from_str(dst.as_str())?,
+                                    src1: Slot::from_str(src1.as_str())?,
+                                    imm,
+                                    prover_only,
+                                });
+                            }
                             _ => {
-                                unimplemented!("binary_imm: {:?} not implemented", opcode_rule);
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         };
                     }
                     Rule::mov_non_imm => {
                         let mut mov_non_imm = instruction.into_inner();
-                        let (opcode_rule, prover_only) =
-                            parse_opcode(mov_non_imm.next().expect("mov_non_imm has instruction"));
-                        let dst = mov_non_imm.next().expect("mov_non_imm has dst");
-                        let src = mov_non_imm.next().expect("mov_non_imm has src");
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut mov_non_imm,
+                            "mov_non_imm has instruction",
+                        )?)?;
+                        let dst = next_token(&mut mov_non_imm, "mov_non_imm has dst")?;
+                        let src = next_token(&mut mov_non_imm, "mov_non_imm has src")?;
                         match opcode_rule {
                             Rule::MVV_W_instr => {
                                 instrs.push(InstructionsWithLabels::Mvvw {
@@ -221,27 +307,35 @@ fn parse_line(
                                     prover_only,
                                 });
                             }
+                            Rule::MVV_H_instr => {
+                                instrs.push(InstructionsWithLabels::Mvvh {
+                                    dst: SlotWithOffset::from_str(dst.as_str())?,
+                                    src: Slot::from_str(src.as_str())?,
+                                    prover_only,
+                                });
+                            }
                             _ => {
-                                unimplemented!("mov_non_imm: {:?} not implemented", opcode_rule);
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         };
                     }
                     Rule::jump_with_op_imm => {
                         let mut jump_with_op_instrs_imm = instruction.into_inner();
-                        let (opcode_rule, prover_only) = parse_opcode(
-                            jump_with_op_instrs_imm
-                                .next()
-                                .expect("jump_with_op_instrs_imm has instruction"),
-                        );
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut jump_with_op_instrs_imm,
+                            "jump_with_op_instrs_imm has instruction",
+                        )?)?;
                         if prover_only {
                             return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                         }
-                        let dst = jump_with_op_instrs_imm
-                            .next()
-                            .expect("jump_with_op_instrs_imm has dst");
-                        let imm = jump_with_op_instrs_imm
-                            .next()
-                            .expect("jump_with_op_instrs_imm has imm");
+                        let dst = next_token(
+                            &mut jump_with_op_instrs_imm,
+                            "jump_with_op_instrs_imm has dst",
+                        )?;
+                        let imm = next_token(
+                            &mut jump_with_op_instrs_imm,
+                            "jump_with_op_instrs_imm has imm",
+                        )?;
                         match opcode_rule {
                             Rule::TAILI_instr => {
                                 instrs.push(InstructionsWithLabels::Taili {
@@ -262,29 +356,27 @@ fn parse_line(
                                 });
                             }
                             _ => {
-                                unimplemented!(
-                                    "jump_with_op_imm: {:?} not implemented",
-                                    opcode_rule
-                                );
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         };
                     }
                     Rule::jump_with_op_non_imm => {
                         let mut jump_non_imm = instruction.into_inner();
-                        let (opcode_rule, prover_only) = parse_opcode(
-                            jump_non_imm
-                                .next()
-                                .expect("jump_with_op_non_imm has instruction"),
-                        );
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut jump_non_imm,
+                            "jump_with_op_non_imm has instruction",
+                        )?)?;
                         if prover_only {
                             return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                         }
-                        let op1 = jump_non_imm
-                            .next()
-                            .expect("jump_with_op_non_imm has first operand");
-                        let op2 = jump_non_imm
-                            .next()
-                            .expect("jump_with_op_non_imm has second operand");
+                        let op1 = next_token(
+                            &mut jump_non_imm,
+                            "jump_with_op_non_imm has first operand",
+                        )?;
+                        let op2 = next_token(
+                            &mut jump_non_imm,
+                            "jump_with_op_non_imm has second operand",
+                        )?;
                         match opcode_rule {
                             Rule::TAILV_instr => {
                                 instrs.push(InstructionsWithLabels::Tailv {
@@ -299,45 +391,89 @@ fn parse_line(
                                 });
                             }
                             _ => {
-                                unimplemented!(
-                                    "jump_with_op_non_imm: {:?} not implemented",
-                                    opcode_rule
-                                );
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::branch_cmp => {
+                        let mut branch_cmp = instruction.into_inner();
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut branch_cmp,
+                            "branch_cmp has instruction",
+                        )?)?;
+                        if prover_only {
+                            return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                        }
+                        let label = next_token(&mut branch_cmp, "branch_cmp has label")?
+                            .as_str()
+                            .to_string();
+                        let src1 = Slot::from_str(
+                            next_token(&mut branch_cmp, "branch_cmp has src1")?.as_str(),
+                        )?;
+                        let src2 = Slot::from_str(
+                            next_token(&mut branch_cmp, "branch_cmp has src2")?.as_str(),
+                        )?;
+                        match opcode_rule {
+                            Rule::BEQ_instr => {
+                                instrs.push(InstructionsWithLabels::Beq { label, src1, src2 });
+                            }
+                            Rule::BNE_instr => {
+                                instrs.push(InstructionsWithLabels::Bne { label, src1, src2 });
+                            }
+                            Rule::BLT_instr => {
+                                instrs.push(InstructionsWithLabels::Blt { label, src1, src2 });
+                            }
+                            Rule::BGE_instr => {
+                                instrs.push(InstructionsWithLabels::Bge { label, src1, src2 });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         }
                     }
                     Rule::load_imm => {
                         let mut load_imm = instruction.into_inner();
-                        let (opcode_rule, prover_only) =
-                            parse_opcode(load_imm.next().expect("load_imm has LDI.W instruction"));
-                        let dst =
-                            Slot::from_str(load_imm.next().expect("load_imm has dst").as_str())?;
-                        let imm = Immediate::from_str(
-                            load_imm.next().expect("load_imm has imm").as_str(),
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut load_imm,
+                            "load_imm has LDI.W instruction",
+                        )?)?;
+                        let dst = Slot::from_str(
+                            next_token(&mut load_imm, "load_imm has dst")?.as_str(),
                         )?;
+                        let imm = next_token(&mut load_imm, "load_imm has imm")?;
                         match opcode_rule {
+                            Rule::LDI_W_instr if imm.as_rule() == Rule::label_immediate => {
+                                instrs.push(InstructionsWithLabels::LdiLabel {
+                                    dst,
+                                    imm: LabelImmediate::from_str(imm.as_str())?,
+                                    prover_only,
+                                });
+                            }
                             Rule::LDI_W_instr => {
                                 instrs.push(InstructionsWithLabels::Ldi {
                                     dst,
-                                    imm,
+                                    imm: Immediate::parse(imm.as_str(), &state.constants)?,
                                     prover_only,
                                 });
                             }
                             _ => {
-                                unreachable!("We have implemented all load_imm instructions");
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         }
                     }
                     Rule::binary_non_imm => {
                         let mut binary_op = instruction.into_inner();
                         let (opcode_rule, prover_only) =
-                            parse_opcode(binary_op.next().expect("binary_op has instruction"));
-                        let dst =
-                            Slot::from_str(binary_op.next().expect("binary_op has dst").as_str())?;
-                        let src1 =
-                            Slot::from_str(binary_op.next().expect("binary_op has src1").as_str())?;
-                        let src2 =
-                            Slot::from_str(binary_op.next().expect("binary_op has src2").as_str())?;
+                            parse_opcode(next_token(&mut binary_op, "binary_op has instruction")?)?;
+                        let dst = Slot::from_str(
+                            next_token(&mut binary_op, "binary_op has dst")?.as_str(),
+                        )?;
+                        let src1 = Slot::from_str(
+                            next_token(&mut binary_op, "binary_op has src1")?.as_str(),
+                        )?;
+                        let src2 = Slot::from_str(
+                            next_token(&mut binary_op, "binary_op has src2")?.as_str(),
+                        )?;
                         match opcode_rule {
                             // B32_ADD is an alias for XOR.
                             Rule::XOR_instr | Rule::B32_ADD_instr => {
@@ -396,6 +532,78 @@ fn parse_line(
                                     prover_only,
                                 });
                             }
+                            Rule::ROL_instr => {
+                                instrs.push(InstructionsWithLabels::Rol {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::ROR_instr => {
+                                instrs.push(InstructionsWithLabels::Ror {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::DIV_instr => {
+                                instrs.push(InstructionsWithLabels::Div {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::DIVU_instr => {
+                                instrs.push(InstructionsWithLabels::Divu {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::REM_instr => {
+                                instrs.push(InstructionsWithLabels::Rem {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::REMU_instr => {
+                                instrs.push(InstructionsWithLabels::Remu {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::ADD_64_instr => {
+                                instrs.push(InstructionsWithLabels::Add64 {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SUB_64_instr => {
+                                instrs.push(InstructionsWithLabels::Sub64 {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SLT_64_instr => {
+                                instrs.push(InstructionsWithLabels::Slt64 {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
                             Rule::SLE_instr => {
                                 instrs.push(InstructionsWithLabels::Sle {
                                     dst,
@@ -468,6 +676,14 @@ fn parse_line(
                                     prover_only,
                                 });
                             }
+                            Rule::B64_MUL_instr => {
+                                instrs.push(InstructionsWithLabels::B64Mul {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
                             Rule::MULU_instr => {
                                 instrs.push(InstructionsWithLabels::Mulu {
                                     dst,
@@ -500,35 +716,63 @@ fn parse_line(
                                     prover_only,
                                 });
                             }
+                            Rule::SHA256_COMPRESS_instr => {
+                                instrs.push(InstructionsWithLabels::Sha256Compress {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::BLAKE3_COMPRESS_instr => {
+                                instrs.push(InstructionsWithLabels::Blake3Compress {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
+                            Rule::BIGMULMOD_instr => {
+                                instrs.push(InstructionsWithLabels::BigMulMod {
+                                    dst,
+                                    src1,
+                                    src2,
+                                    prover_only,
+                                });
+                            }
                             _ => {
-                                unimplemented!("binary_op: {opcode_rule:?} not implemented");
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         };
                     }
                     Rule::nullary => {
                         let mut nullary = instruction.into_inner();
-                        let rule =
-                            get_first_inner(nullary.next().unwrap(), "nullary has instruction")
-                                .as_rule();
+                        let rule = get_first_inner(
+                            next_token(&mut nullary, "nullary has instruction")?,
+                            "nullary has instruction",
+                        )?
+                        .as_rule();
                         match rule {
                             Rule::RET_instr => {
                                 instrs.push(InstructionsWithLabels::Ret);
                             }
-                            _ => unreachable!("All nullary instructions are implemented"),
+                            Rule::NOP_instr => {
+                                instrs.push(InstructionsWithLabels::Nop);
+                            }
+                            _ => return Err(Error::UnknownInstruction(format!("{rule:?}"))),
                         }
                     }
                     Rule::simple_jump => {
                         let mut simple_jump = instruction.into_inner();
                         let (opcode_rule, prover_only) =
-                            parse_opcode(simple_jump.next().expect("jump has instruction"));
+                            parse_opcode(next_token(&mut simple_jump, "jump has instruction")?)?;
                         if prover_only {
                             return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                         }
-                        let dst = simple_jump
-                            .next()
-                            .expect("simple_jump expects a destination operand");
+                        let dst =
+                            next_token(&mut simple_jump, "simple_jump has a destination operand")?;
                         match dst.as_rule() {
-                            Rule::label_name => {
+                            Rule::label_ref => {
                                 // This is a jump to a label
                                 instrs.push(InstructionsWithLabels::Jumpi {
                                     label: dst.as_str().to_string(),
@@ -540,40 +784,41 @@ fn parse_line(
                                     offset: Slot::from_str(dst.as_str())?,
                                 });
                             }
-                            _ => unreachable!("Unexpected token in simple_jump"),
+                            _ => return Err(Error::UnknownInstruction(dst.as_str().to_string())),
                         }
                     }
                     Rule::alloc_imm => {
                         let mut alloc_imm = instruction.into_inner();
                         let (opcode_rule, prover_only) =
-                            parse_opcode(alloc_imm.next().expect("alloc_imm has instruction"));
+                            parse_opcode(next_token(&mut alloc_imm, "alloc_imm has instruction")?)?;
                         if !prover_only {
                             return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                         }
-                        let dst = alloc_imm.next().expect("alloc_imm has dst");
-                        let imm = alloc_imm.next().expect("alloc_imm has src");
+                        let dst = next_token(&mut alloc_imm, "alloc_imm has dst")?;
+                        let imm = next_token(&mut alloc_imm, "alloc_imm has src")?;
                         match opcode_rule {
                             Rule::ALLOCI_instr => {
                                 instrs.push(InstructionsWithLabels::Alloci {
                                     dst: Slot::from_str(dst.as_str())?,
-                                    imm: Immediate::from_str(imm.as_str())?,
+                                    imm: Immediate::parse(imm.as_str(), &state.constants)?,
                                 });
                             }
                             _ => {
-                                unreachable!("We have implemented all alloc_imm instructions");
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         };
                     }
                     Rule::alloc_non_imm => {
                         let mut alloc_non_imm = instruction.into_inner();
-                        let (opcode_rule, prover_only) = parse_opcode(
-                            alloc_non_imm.next().expect("alloc_non_imm has instruction"),
-                        );
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut alloc_non_imm,
+                            "alloc_non_imm has instruction",
+                        )?)?;
                         if !prover_only {
                             return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                         }
-                        let dst = alloc_non_imm.next().expect("alloc_non_imm has dst");
-                        let src = alloc_non_imm.next().expect("alloc_non_imm has src");
+                        let dst = next_token(&mut alloc_non_imm, "alloc_non_imm has dst")?;
+                        let src = next_token(&mut alloc_non_imm, "alloc_non_imm has src")?;
                         match opcode_rule {
                             Rule::ALLOCV_instr => {
                                 instrs.push(InstructionsWithLabels::Allocv {
@@ -581,27 +826,488 @@ fn parse_line(
                                     src: Slot::from_str(src.as_str())?,
                                 });
                             }
+                            Rule::ALLOCH_instr => {
+                                instrs.push(InstructionsWithLabels::Alloch {
+                                    dst: Slot::from_str(dst.as_str())?,
+                                    size: Slot::from_str(src.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        };
+                    }
+                    Rule::free => {
+                        let mut free = instruction.into_inner();
+                        let (opcode_rule, prover_only) =
+                            parse_opcode(next_token(&mut free, "free has instruction")?)?;
+                        if !prover_only {
+                            return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                        }
+                        let ptr = next_token(&mut free, "free has ptr")?;
+                        match opcode_rule {
+                            Rule::FREE_instr => {
+                                instrs.push(InstructionsWithLabels::Free {
+                                    ptr: Slot::from_str(ptr.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::unary => {
+                        let mut unary = instruction.into_inner();
+                        let (opcode_rule, prover_only) =
+                            parse_opcode(next_token(&mut unary, "unary has instruction")?)?;
+                        let dst =
+                            Slot::from_str(next_token(&mut unary, "unary has dst")?.as_str())?;
+                        let src =
+                            Slot::from_str(next_token(&mut unary, "unary has src")?.as_str())?;
+                        match opcode_rule {
+                            Rule::CLZ_instr => {
+                                instrs.push(InstructionsWithLabels::Clz {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::CTZ_instr => {
+                                instrs.push(InstructionsWithLabels::Ctz {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::POPCNT_instr => {
+                                instrs.push(InstructionsWithLabels::Popcnt {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::BSWAP_instr => {
+                                instrs.push(InstructionsWithLabels::Bswap {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SEXT_B_instr => {
+                                instrs.push(InstructionsWithLabels::Sextb {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SEXT_H_instr => {
+                                instrs.push(InstructionsWithLabels::Sexth {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::B32_INV_instr => {
+                                instrs.push(InstructionsWithLabels::B32Inv {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
+                            Rule::B128_INV_instr => {
+                                instrs.push(InstructionsWithLabels::B128Inv {
+                                    dst,
+                                    src,
+                                    prover_only,
+                                });
+                            }
                             _ => {
-                                unreachable!("We have implemented all alloc_non_imm instructions");
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         };
                     }
+                    Rule::cmov => {
+                        let mut cmov = instruction.into_inner();
+                        let (_, prover_only) =
+                            parse_opcode(next_token(&mut cmov, "cmov has instruction")?)?;
+                        let dst = Slot::from_str(next_token(&mut cmov, "cmov has dst")?.as_str())?;
+                        let cond =
+                            Slot::from_str(next_token(&mut cmov, "cmov has cond")?.as_str())?;
+                        let src1 =
+                            Slot::from_str(next_token(&mut cmov, "cmov has src1")?.as_str())?;
+                        let src2 =
+                            Slot::from_str(next_token(&mut cmov, "cmov has src2")?.as_str())?;
+                        instrs.push(InstructionsWithLabels::Cmov {
+                            dst,
+                            cond,
+                            src1,
+                            src2,
+                            prover_only,
+                        });
+                    }
+                    Rule::mem_block => {
+                        let mut mem_block = instruction.into_inner();
+                        let (opcode_rule, prover_only) =
+                            parse_opcode(next_token(&mut mem_block, "mem_block has instruction")?)?;
+                        let dst = Slot::from_str(
+                            next_token(&mut mem_block, "mem_block has dst")?.as_str(),
+                        )?;
+                        let src = Slot::from_str(
+                            next_token(&mut mem_block, "mem_block has src")?.as_str(),
+                        )?;
+                        let len = Immediate::parse(
+                            next_token(&mut mem_block, "mem_block has len")?.as_str(),
+                            &state.constants,
+                        )?;
+                        match opcode_rule {
+                            Rule::MEMCPY_instr => {
+                                instrs.push(InstructionsWithLabels::Memcpy {
+                                    dst,
+                                    src,
+                                    len,
+                                    prover_only,
+                                });
+                            }
+                            Rule::MEMSET_instr => {
+                                instrs.push(InstructionsWithLabels::Memset {
+                                    dst,
+                                    src,
+                                    len,
+                                    prover_only,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
                     Rule::fp => {
                         let mut fp = instruction.into_inner();
                         let (opcode_rule, prover_only) =
-                            parse_opcode(fp.next().expect("fp has instruction"));
-                        let dst = fp.next().expect("fp has dst");
-                        let imm = fp.next().expect("fp has imm");
+                            parse_opcode(next_token(&mut fp, "fp has instruction")?)?;
+                        let dst = next_token(&mut fp, "fp has dst")?;
+                        let imm = next_token(&mut fp, "fp has imm")?;
                         match opcode_rule {
                             Rule::FP_instr => {
                                 instrs.push(InstructionsWithLabels::Fp {
                                     dst: Slot::from_str(dst.as_str())?,
-                                    imm: Immediate::from_str(imm.as_str())?,
+                                    imm: Immediate::parse(imm.as_str(), &state.constants)?,
+                                    prover_only,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::load_store => {
+                        let mut load_store = instruction.into_inner();
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut load_store,
+                            "load_store has instruction",
+                        )?)?;
+                        let value = Slot::from_str(
+                            next_token(&mut load_store, "load_store has value")?.as_str(),
+                        )?;
+                        let base = Slot::from_str(
+                            next_token(&mut load_store, "load_store has base")?.as_str(),
+                        )?;
+                        let offset = Immediate::parse(
+                            next_token(&mut load_store, "load_store has offset")?.as_str(),
+                            &state.constants,
+                        )?;
+                        match opcode_rule {
+                            Rule::LW_instr => {
+                                instrs.push(InstructionsWithLabels::Lw {
+                                    dst: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::LB_instr => {
+                                instrs.push(InstructionsWithLabels::Lb {
+                                    dst: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::LBU_instr => {
+                                instrs.push(InstructionsWithLabels::Lbu {
+                                    dst: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::LH_instr => {
+                                instrs.push(InstructionsWithLabels::Lh {
+                                    dst: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::LHU_instr => {
+                                instrs.push(InstructionsWithLabels::Lhu {
+                                    dst: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SW_instr => {
+                                instrs.push(InstructionsWithLabels::Sw {
+                                    src: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SB_instr => {
+                                instrs.push(InstructionsWithLabels::Sb {
+                                    src: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            Rule::SH_instr => {
+                                instrs.push(InstructionsWithLabels::Sh {
+                                    src: value,
+                                    base,
+                                    offset,
+                                    prover_only,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::abort => {
+                        let mut abort = instruction.into_inner();
+                        let (opcode_rule, _) =
+                            parse_opcode(next_token(&mut abort, "abort has instruction")?)?;
+                        let ptr = next_token(&mut abort, "abort has ptr")?;
+                        let len = next_token(&mut abort, "abort has len")?;
+                        match opcode_rule {
+                            Rule::ABORT_instr => {
+                                instrs.push(InstructionsWithLabels::Abort {
+                                    ptr: Slot::from_str(ptr.as_str())?,
+                                    len: Slot::from_str(len.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::commit => {
+                        let mut commit = instruction.into_inner();
+                        let (opcode_rule, _) =
+                            parse_opcode(next_token(&mut commit, "commit has instruction")?)?;
+                        let src = next_token(&mut commit, "commit has src")?;
+                        match opcode_rule {
+                            Rule::COMMIT_instr => {
+                                instrs.push(InstructionsWithLabels::Commit {
+                                    src: Slot::from_str(src.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::merkle_verify => {
+                        let mut merkle_verify = instruction.into_inner();
+                        let (opcode_rule, _) = parse_opcode(next_token(
+                            &mut merkle_verify,
+                            "merkle_verify has instruction",
+                        )?)?;
+                        let root = next_token(&mut merkle_verify, "merkle_verify has root")?;
+                        let leaf = next_token(&mut merkle_verify, "merkle_verify has leaf")?;
+                        let path = next_token(&mut merkle_verify, "merkle_verify has path")?;
+                        match opcode_rule {
+                            Rule::MERKLE_VERIFY_instr => {
+                                instrs.push(InstructionsWithLabels::MerkleVerify {
+                                    root: Slot::from_str(root.as_str())?,
+                                    leaf: Slot::from_str(leaf.as_str())?,
+                                    path: Slot::from_str(path.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::assert_eq => {
+                        let mut assert_eq = instruction.into_inner();
+                        let (opcode_rule, prover_only) =
+                            parse_opcode(next_token(&mut assert_eq, "assert_eq has instruction")?)?;
+                        let src1 = next_token(&mut assert_eq, "assert_eq has src1")?;
+                        let src2 = next_token(&mut assert_eq, "assert_eq has src2")?;
+                        match opcode_rule {
+                            Rule::ASSERT_EQ_instr => {
+                                instrs.push(InstructionsWithLabels::AssertEq {
+                                    src1: Slot::from_str(src1.as_str())?,
+                                    src2: Slot::from_str(src2.as_str())?,
+                                    prover_only,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::assert_zero => {
+                        let mut assert_zero = instruction.into_inner();
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut assert_zero,
+                            "assert_zero has instruction",
+                        )?)?;
+                        let src = next_token(&mut assert_zero, "assert_zero has src")?;
+                        match opcode_rule {
+                            Rule::ASSERT_ZERO_instr => {
+                                instrs.push(InstructionsWithLabels::AssertZero {
+                                    src: Slot::from_str(src.as_str())?,
+                                    prover_only,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::range_check => {
+                        let mut range_check = instruction.into_inner();
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut range_check,
+                            "range_check has instruction",
+                        )?)?;
+                        let src = next_token(&mut range_check, "range_check has src")?;
+                        let bits = next_token(&mut range_check, "range_check has bits")?;
+                        match opcode_rule {
+                            Rule::RANGE_CHECK_instr => {
+                                instrs.push(InstructionsWithLabels::RangeCheck {
+                                    src: Slot::from_str(src.as_str())?,
+                                    bits: Immediate::parse(bits.as_str(), &state.constants)?,
+                                    prover_only,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::ecall => {
+                        let mut ecall = instruction.into_inner();
+                        let (opcode_rule, prover_only) =
+                            parse_opcode(next_token(&mut ecall, "ecall has instruction")?)?;
+                        if !prover_only {
+                            return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                        }
+                        let id = next_token(&mut ecall, "ecall has id")?;
+                        let base = next_token(&mut ecall, "ecall has base")?;
+                        match opcode_rule {
+                            Rule::ECALL_instr => {
+                                instrs.push(InstructionsWithLabels::Ecall {
+                                    id: Immediate::parse(id.as_str(), &state.constants)?,
+                                    base: Slot::from_str(base.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::read_advice => {
+                        let mut read_advice = instruction.into_inner();
+                        let (opcode_rule, prover_only) = parse_opcode(next_token(
+                            &mut read_advice,
+                            "read_advice has instruction",
+                        )?)?;
+                        if !prover_only {
+                            return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                        }
+                        let dst = next_token(&mut read_advice, "read_advice has dst")?;
+                        match opcode_rule {
+                            Rule::READ_ADVICE_instr => {
+                                instrs.push(InstructionsWithLabels::ReadAdvice {
+                                    dst: Slot::from_str(dst.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::debug => {
+                        let mut debug = instruction.into_inner();
+                        // DEBUG is always prover-only, with or without the flag.
+                        let (opcode_rule, _) =
+                            parse_opcode(next_token(&mut debug, "debug has instruction")?)?;
+                        let src = next_token(&mut debug, "debug has src")?;
+                        match opcode_rule {
+                            Rule::DEBUG_instr => {
+                                instrs.push(InstructionsWithLabels::Debug {
+                                    src: Slot::from_str(src.as_str())?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::custom => {
+                        let mut custom = instruction.into_inner();
+                        let (opcode_rule, prover_only) =
+                            parse_opcode(next_token(&mut custom, "custom has instruction")?)?;
+                        let opcode = next_token(&mut custom, "custom has opcode")?;
+                        let mut parse_operand = || -> Result<Operand, Error> {
+                            let operand = get_first_inner(
+                                next_token(&mut custom, "custom has three operands")?,
+                                "operand has slot or immediate",
+                            )?;
+                            Ok(match operand.as_rule() {
+                                Rule::slot => Operand::Slot(Slot::from_str(operand.as_str())?),
+                                _ => Operand::Immediate(Immediate::parse(
+                                    operand.as_str(),
+                                    &state.constants,
+                                )?),
+                            })
+                        };
+                        let args = [parse_operand()?, parse_operand()?, parse_operand()?];
+                        match opcode_rule {
+                            Rule::CUSTOM_instr => {
+                                instrs.push(InstructionsWithLabels::Custom {
+                                    opcode: Immediate::parse(opcode.as_str(), &state.constants)?,
+                                    args,
                                     prover_only,
                                 });
                             }
                             _ => {
-                                unreachable!("We have implemented all alloc_non_imm instructions");
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
+                            }
+                        }
+                    }
+                    Rule::halt => {
+                        let mut halt = instruction.into_inner();
+                        let (opcode_rule, _) =
+                            parse_opcode(next_token(&mut halt, "halt has instruction")?)?;
+                        let exit_code = next_token(&mut halt, "halt has exit_code")?;
+                        match opcode_rule {
+                            Rule::HALT_instr => {
+                                instrs.push(InstructionsWithLabels::Halt {
+                                    exit_code: Immediate::parse(
+                                        exit_code.as_str(),
+                                        &state.constants,
+                                    )?,
+                                });
+                            }
+                            _ => {
+                                return Err(Error::UnknownInstruction(format!("{opcode_rule:?}")));
                             }
                         }
                     }
@@ -613,8 +1319,35 @@ fn parse_line(
                     }
                 };
             }
+            Rule::macro_def => state.macros.define(instr_or_label)?,
+            Rule::equ => state.define_constant(instr_or_label)?,
+            Rule::public => parse_public(instrs, instr_or_label)?,
+            Rule::align => parse_align(instrs, instr_or_label)?,
+            Rule::loc => {
+                let origin = get_first_inner(instr_or_label, "loc must have a string")?;
+                let origin = DataDirective::parse_string(origin.as_str())?;
+                state.origin = (!origin.is_empty()).then_some(origin);
+            }
+            Rule::jumptable => {
+                state.locate(instrs, &instr_or_label);
+                parse_jump_table(instrs, instr_or_label)?;
+            }
+            Rule::macro_call => {
+                // The expanded instructions are located at the call.
+                state.locate(instrs, &instr_or_label);
+                let locations = std::mem::replace(&mut state.locations, false);
+                let expansion = state.macros.expand(instr_or_label)?;
+                let lines = AsmParser::parse(Rule::macro_expansion, &expansion)
+                    .map_err(|err| Error::PestParse(Box::new(err)))?
+                    .next()
+                    .ok_or(Error::MissingToken("macro_expansion is always present"))?
+                    .into_inner();
+                parse_line(instrs, state, lines)?;
+                state.macros.end_expansion();
+                state.locations = locations;
+            }
             Rule::EOI => (),
-            Rule::line => parse_line(instrs, instr_or_label.into_inner())?,
+            Rule::line => parse_line(instrs, state, instr_or_label.into_inner())?,
             _ => {
                 return Err(Error::UnknownInstruction(
                     instr_or_label.as_span().as_str().to_string(),
@@ -625,19 +1358,151 @@ fn parse_line(
     Ok(())
 }
 
+// A public directive exposes a slot of the entry frame
+fn parse_public(
+    instrs: &mut Vec<InstructionsWithLabels>,
+    pair: Pair<'_, Rule>,
+) -> Result<(), Error> {
+    let slot = get_first_inner(pair, "public must have a slot")?;
+    instrs.push(InstructionsWithLabels::Public(Slot::from_str(
+        slot.as_str(),
+    )?));
+    Ok(())
+}
+
+// An align directive pads the PROM up to a multiple of its alignment
+fn parse_align(
+    instrs: &mut Vec<InstructionsWithLabels>,
+    pair: Pair<'_, Rule>,
+) -> Result<(), Error> {
+    let alignment = get_first_inner(pair, "align must have an alignment")?.as_str();
+    match alignment.parse::<u32>() {
+        Ok(alignment) if alignment > 0 => {
+            instrs.push(InstructionsWithLabels::Align(alignment));
+            Ok(())
+        }
+        _ => Err(Error::InvalidAlignment(alignment.to_string())),
+    }
+}
+
+fn parse_jump_table(
+    instrs: &mut Vec<InstructionsWithLabels>,
+    pair: Pair<'_, Rule>,
+) -> Result<(), Error> {
+    let mut args = pair.into_inner();
+    let name = next_token(&mut args, "jumptable must have a name")?;
+    let index = next_token(&mut args, "jumptable must have an index")?;
+    let scratch = next_token(&mut args, "jumptable must have a scratch slot")?;
+    instrs.push(InstructionsWithLabels::JumpTable {
+        name: name.as_str().to_string(),
+        index: Slot::from_str(index.as_str())?,
+        scratch: Slot::from_str(scratch.as_str())?,
+        cases: args.map(|case| case.as_str().to_string()).collect(),
+    });
+    Ok(())
+}
+
+// A data section holds data labels and data directives
+fn parse_data_section(
+    instrs: &mut Vec<InstructionsWithLabels>,
+    pairs: Pairs<'_, Rule>,
+) -> Result<(), Error> {
+    for item in pairs {
+        let span = item.as_span();
+        parse_data_item(instrs, item).map_err(|err| err.at(span))?;
+    }
+    Ok(())
+}
+
+// A data label or a data directive
+fn parse_data_item(
+    instrs: &mut Vec<InstructionsWithLabels>,
+    item: Pair<'_, Rule>,
+) -> Result<(), Error> {
+    let directive = match item.as_rule() {
+        Rule::label => {
+            let label_name = get_first_inner(item, "label must have label_name")?;
+            instrs.push(InstructionsWithLabels::DataLabel(
+                label_name.as_str().to_string(),
+            ));
+            return Ok(());
+        }
+        Rule::data_word => {
+            // Consecutive values and label references make separate directives,
+            // which are laid out contiguously.
+            let mut directive: Option<DataDirective> = None;
+            for value in item.into_inner() {
+                match (value.as_rule(), &mut directive) {
+                    (Rule::label_ref, Some(DataDirective::Labels(labels))) => {
+                        labels.push(value.as_str().to_string());
+                    }
+                    (Rule::label_ref, _) => {
+                        instrs.extend(directive.take().map(InstructionsWithLabels::Data));
+                        directive = Some(DataDirective::Labels(vec![value.as_str().to_string()]));
+                    }
+                    (_, Some(DataDirective::Words(words))) => {
+                        words.push(DataDirective::parse_word(value.as_str())?);
+                    }
+                    _ => {
+                        instrs.extend(directive.take().map(InstructionsWithLabels::Data));
+                        directive = Some(DataDirective::Words(vec![DataDirective::parse_word(
+                            value.as_str(),
+                        )?]));
+                    }
+                }
+            }
+            directive.ok_or(Error::MissingToken("data_word has a value"))?
+        }
+        Rule::data_bytes => DataDirective::Bytes(
+            item.into_inner()
+                .map(|value| DataDirective::parse_byte(value.as_str()))
+                .collect::<Result<_, _>>()?,
+        ),
+        Rule::data_ascii => DataDirective::Ascii(DataDirective::parse_string(
+            get_first_inner(item, "data_ascii must have a string")?.as_str(),
+        )?),
+        _ => {
+            return Err(Error::UnknownInstruction(item.as_str().to_string()));
+        }
+    };
+    instrs.push(InstructionsWithLabels::Data(directive));
+    Ok(())
+}
+
 #[instrument(level = "debug", skip_all)]
 pub fn parse_program(input: &str) -> Result<Vec<InstructionsWithLabels>, Error> {
+    parse_program_with_locations(input, false)
+}
+
+/// Parses `input`, preceding the instructions with their source location if
+/// `locations` is set.
+pub(crate) fn parse_program_with_locations(
+    input: &str,
+    locations: bool,
+) -> Result<Vec<InstructionsWithLabels>, Error> {
     let parser = AsmParser::parse(Rule::program, input);
     let mut instrs = Vec::<InstructionsWithLabels>::new();
+    let mut state = ParserState {
+        locations,
+        ..Default::default()
+    };
 
     let program = parser
-        .map_err(|err| Error::PestParse(Box::new(err)))?
+        .map_err(|err| Error::from_pest(input, err))?
         .next()
         .ok_or(Error::NoStartLabelOrInstructionFound)?
         .into_inner();
 
     for line in program {
-        parse_line(&mut instrs, line.into_inner())?;
+        let span = line.as_span();
+        let result = match line.as_rule() {
+            Rule::macro_def => state.macros.define(line),
+            Rule::equ => state.define_constant(line),
+            Rule::public => parse_public(&mut instrs, line),
+            Rule::data_section => parse_data_section(&mut instrs, line.into_inner()),
+            _ => parse_line(&mut instrs, &mut state, line.into_inner()),
+        };
+        result.map_err(|err| err.at(span))?;
     }
 
     Ok(instrs)
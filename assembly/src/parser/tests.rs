@@ -104,6 +104,151 @@ mod test_parser {
         );
     }
 
+    #[test]
+    fn test_label_refs() {
+        let code = "_start: J loop+2\nBNZ loop-1, @4\nLDI.W @3, #lo(table+1)\n";
+        let instrs = parse_program(code).unwrap();
+        assert!(matches!(&instrs[1], InstructionsWithLabels::Jumpi { label } if label == "loop+2"));
+        assert!(
+            matches!(&instrs[2], InstructionsWithLabels::Bnz { label, .. } if label == "loop-1")
+        );
+        assert!(
+            matches!(&instrs[3], InstructionsWithLabels::LdiLabel { imm, .. } if imm.to_string() == "#lo(table+1)")
+        );
+
+        ensure_parser_succeeds(Rule::label_immediate, "#hi(table)");
+        ensure_parser_fails(Rule::label_immediate, "#mid(table)");
+    }
+
+    #[test]
+    fn test_macros() {
+        let code = "#[macro] call_with(func, frame, arg) {
+                MVV.W $frame[2], $arg
+                CALLI $func, $frame
+            }
+
+            #[framesize(0x10)]
+            _start:
+                call_with(f, @4, @2)
+                call_with( f, @5, @3 ) ;; Some comment
+                #[macro] spin() {
+                spin_$@:
+                    J spin_$@
+                }
+                spin()
+                spin()
+                RET
+            ";
+        let expanded = "#[framesize(0x10)]
+            _start:
+                MVV.W @4[2], @2
+                CALLI f, @4
+                MVV.W @5[2], @3
+                CALLI f, @5
+            spin_2:
+                J spin_2
+            spin_3:
+                J spin_3
+                RET
+            ";
+
+        let to_string = |instrs: Vec<InstructionsWithLabels>| {
+            instrs
+                .iter()
+                .map(|instr| instr.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            to_string(parse_program(code).unwrap()),
+            to_string(parse_program(expanded).unwrap())
+        );
+
+        let err_programs = [
+            // Unknown macro
+            "_start: foo(@2)",
+            // Wrong number of arguments
+            "#[macro] foo(a) { RET }
+_start: foo(@2, @3)",
+            // Unknown parameter
+            "#[macro] foo(a) { J $b }
+_start: foo(@2)",
+            // Duplicate macro
+            "#[macro] foo() { RET }
+#[macro] foo() { RET }
+_start: foo()",
+            // Recursive macro
+            "#[macro] foo() { foo() }
+_start: foo()",
+        ];
+        for asm in err_programs {
+            assert!(parse_program(asm).is_err(), "{asm} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_constants() {
+        let code = ".equ ARG, 2
+            .equ PAGE, 1 << 12 ;; Some comment
+            #[macro] store(offset) {
+                SW @2, @3, #{$offset + 4}
+            }
+
+            #[framesize(0x10)]
+            _start:
+                LDI.W @3, #PAGE
+                .equ FRAME, 0x4
+                LDI.W @4, #{PAGE + FRAME * 2}
+                ADDI @5, @2, #(PAGE - 1)
+                B32_MULI @6, @2, #{-ARG}G
+                MVI.H @7[2], #(1 << (FRAME - 1))
+                store(FRAME)
+                store((FRAME - ARG))
+                RET
+            ";
+        let expanded = "#[framesize(0x10)]
+            _start:
+                LDI.W @3, #4096
+                LDI.W @4, #4104
+                ADDI @5, @2, #4095
+                B32_MULI @6, @2, #-2G
+                MVI.H @7[2], #8
+                SW @2, @3, #8
+                SW @2, @3, #6
+                RET
+            ";
+
+        let to_string = |instrs: Vec<InstructionsWithLabels>| {
+            instrs
+                .iter()
+                .map(|instr| instr.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            to_string(parse_program(code).unwrap()),
+            to_string(parse_program(expanded).unwrap())
+        );
+
+        let err_programs = [
+            // Unknown constant
+            "_start: LDI.W @2, #{FOO + 1}",
+            // Constant used before its definition
+            "_start: LDI.W @2, #FOO
+.equ FOO, 1",
+            // Duplicate constant
+            ".equ FOO, 1
+.equ FOO, 2
+_start: RET",
+            // Malformed expression
+            "_start: LDI.W @2, #(1 +)",
+            // Division by zero
+            ".equ FOO, 1 / 0
+_start: RET",
+        ];
+        for asm in err_programs {
+            assert!(parse_program(asm).is_err(), "{asm} should not parse");
+        }
+    }
+
     #[test]
     fn test_prover_flag() {
         parse_program(include_str!("../../../examples/bezout.asm")).unwrap();
@@ -118,8 +263,11 @@ mod test_parser {
             "B32_ADD @4, @3, @2",
             "B32_MUL @4, @3, @2",
             "B32_MULI @3, @2, #1",
+            "B32_INV @3, @2",
+            "B64_MUL @4, @2, @6",
             "B128_ADD @4, @3, @2",
             "B128_MUL @4, @3, @2",
+            "B128_INV @4, @8",
             "ADD @3, @2, @1",
             "SUB @3, @2, @1",
             "SLE @3, @2, @1",
@@ -133,9 +281,18 @@ mod test_parser {
             "SLL @3, @2, @1",
             "SRL @3, @2, @1",
             "SRA @3, @2, @1",
+            "ROL @3, @2, @1",
+            "ROR @3, @2, @1",
             "MUL @3, @2, @1",
             "MULU @3, @2, @1",
             "MULSU @3, @2, @1",
+            "DIV @3, @2, @1",
+            "DIVU @3, @2, @1",
+            "REM @3, @2, @1",
+            "REMU @3, @2, @1",
+            "ADD.64 @4, @2, @6",
+            "SUB.64 @4, @2, @6",
+            "SLT.64 @3, @2, @6",
             "XORI @3, @2, #1",
             "B32_ADDI @3, @2, #1",
             "ADDI @3, @2, #1",
@@ -146,6 +303,14 @@ mod test_parser {
             "SLLI @3, @2, #1",
             "SRLI @3, @2, #1",
             "SRAI @3, @2, #1",
+            "ROLI @3, @2, #1",
+            "RORI @3, @2, #1",
+            "CLZ @3, @2",
+            "CTZ @3, @2",
+            "POPCNT @3, @2",
+            "BSWAP @3, @2",
+            "SEXT.B @3, @2",
+            "SEXT.H @3, @2",
             "MULI @3, @2, #1",
             "LW @3, @2, #1",
             "SW @3, @2, #1",
@@ -157,14 +322,34 @@ mod test_parser {
             "SH @3, @2, #1",
             "MVV.W @3[4], @2",
             "MVV.L @3[4], @2",
+            "MVV.H @3[4], @2",
             "MVI.H @3[4], #2",
+            "MVI.B @3[4], #2",
+            "MEMCPY @3, @4, #8",
+            "MEMSET @3, @4, #8",
+            "CMOV @3, @4, @5, @6",
             "LDI.W @3, #2",
             "RET",
+            "NOP",
+            "DEBUG @3",
+            "DEBUG! @3",
+            "FREE! @3",
+            "COMMIT @3",
+            "MERKLE_VERIFY @8, @16, @24",
+            "HALT #3",
+            "ASSERT_EQ @3, @4",
+            "ASSERT_ZERO @3",
+            "RANGE_CHECK @3, #8",
+            "CUSTOM #0x8000, @3, #4, @5",
             "J label",
             "J @4",
             "CALLI label, @4",
             "TAILI label, @4",
             "BNZ label, @4",
+            "BEQ label, @4, @5",
+            "BNE label, @4, @5",
+            "BLT label, @4, @5",
+            "BGE label, @4, @5",
             "CALLV @5, @3",
             "TAILV @5, @3",
         ];
@@ -337,7 +522,7 @@ mod test_parser {
             })
             .collect::<Vec<_>>();
 
-        let mut expected_prom = code_to_prom(&expected_prom_prover_only);
+        let mut expected_prom = Vec::from(code_to_prom(&expected_prom_prover_only));
 
         // Set the expected advice for the first TAILI
         expected_prom[4].advice = Some((collatz_prom_index, collatz_advice));
@@ -0,0 +1,316 @@
+//! Decoder for the RV32IM base instruction set.
+
+/// Condition of a conditional branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BranchCond {
+    Eq,
+    Ne,
+    Lt,
+    Ge,
+    Ltu,
+    Geu,
+}
+
+/// Width and extension of a load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LoadWidth {
+    Byte,
+    Half,
+    Word,
+    ByteUnsigned,
+    HalfUnsigned,
+}
+
+/// Width of a store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StoreWidth {
+    Byte,
+    Half,
+    Word,
+}
+
+/// Register-immediate operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ImmOp {
+    Add,
+    Slt,
+    Sltu,
+    Xor,
+    Or,
+    And,
+    Sll,
+    Srl,
+    Sra,
+}
+
+/// Register-register operation, including the M extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RegOp {
+    Add,
+    Sub,
+    Sll,
+    Slt,
+    Sltu,
+    Xor,
+    Srl,
+    Sra,
+    Or,
+    And,
+    Mul,
+    Mulh,
+    Mulhsu,
+    Mulhu,
+    Div,
+    Divu,
+    Rem,
+    Remu,
+}
+
+/// A decoded RV32IM instruction. Registers are numbered from 0 to 31 and
+/// immediates are sign-extended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum RvInstruction {
+    Lui {
+        rd: u8,
+        imm: u32,
+    },
+    Auipc {
+        rd: u8,
+        imm: u32,
+    },
+    Jal {
+        rd: u8,
+        offset: i32,
+    },
+    Jalr {
+        rd: u8,
+        rs1: u8,
+        offset: i32,
+    },
+    Branch {
+        cond: BranchCond,
+        rs1: u8,
+        rs2: u8,
+        offset: i32,
+    },
+    Load {
+        width: LoadWidth,
+        rd: u8,
+        rs1: u8,
+        offset: i32,
+    },
+    Store {
+        width: StoreWidth,
+        rs1: u8,
+        rs2: u8,
+        offset: i32,
+    },
+    OpImm {
+        op: ImmOp,
+        rd: u8,
+        rs1: u8,
+        imm: i32,
+    },
+    Op {
+        op: RegOp,
+        rd: u8,
+        rs1: u8,
+        rs2: u8,
+    },
+    Fence,
+    Ecall,
+    Ebreak,
+}
+
+const fn bits(word: u32, lo: u32, len: u32) -> u32 {
+    (word >> lo) & ((1 << len) - 1)
+}
+
+/// Sign-extends the `len` lowest bits of `value`.
+const fn sign_extend(value: u32, len: u32) -> i32 {
+    ((value << (32 - len)) as i32) >> (32 - len)
+}
+
+/// Decodes a 32-bit instruction word, returning `None` if it is not a valid
+/// RV32IM instruction.
+pub(crate) fn decode(word: u32) -> Option<RvInstruction> {
+    let opcode = bits(word, 0, 7);
+    let rd = bits(word, 7, 5) as u8;
+    let funct3 = bits(word, 12, 3);
+    let rs1 = bits(word, 15, 5) as u8;
+    let rs2 = bits(word, 20, 5) as u8;
+    let funct7 = bits(word, 25, 7);
+
+    let imm_i = sign_extend(bits(word, 20, 12), 12);
+    let imm_s = sign_extend(bits(word, 25, 7) << 5 | bits(word, 7, 5), 12);
+    let imm_b = sign_extend(
+        bits(word, 31, 1) << 12
+            | bits(word, 7, 1) << 11
+            | bits(word, 25, 6) << 5
+            | bits(word, 8, 4) << 1,
+        13,
+    );
+    let imm_u = word & 0xffff_f000;
+    let imm_j = sign_extend(
+        bits(word, 31, 1) << 20
+            | bits(word, 12, 8) << 12
+            | bits(word, 20, 1) << 11
+            | bits(word, 21, 10) << 1,
+        21,
+    );
+
+    let instr = match opcode {
+        0x37 => RvInstruction::Lui { rd, imm: imm_u },
+        0x17 => RvInstruction::Auipc { rd, imm: imm_u },
+        0x6f => RvInstruction::Jal { rd, offset: imm_j },
+        0x67 if funct3 == 0 => RvInstruction::Jalr {
+            rd,
+            rs1,
+            offset: imm_i,
+        },
+        0x63 => {
+            let cond = match funct3 {
+                0 => BranchCond::Eq,
+                1 => BranchCond::Ne,
+                4 => BranchCond::Lt,
+                5 => BranchCond::Ge,
+                6 => BranchCond::Ltu,
+                7 => BranchCond::Geu,
+                _ => return None,
+            };
+            RvInstruction::Branch {
+                cond,
+                rs1,
+                rs2,
+                offset: imm_b,
+            }
+        }
+        0x03 => {
+            let width = match funct3 {
+                0 => LoadWidth::Byte,
+                1 => LoadWidth::Half,
+                2 => LoadWidth::Word,
+                4 => LoadWidth::ByteUnsigned,
+                5 => LoadWidth::HalfUnsigned,
+                _ => return None,
+            };
+            RvInstruction::Load {
+                width,
+                rd,
+                rs1,
+                offset: imm_i,
+            }
+        }
+        0x23 => {
+            let width = match funct3 {
+                0 => StoreWidth::Byte,
+                1 => StoreWidth::Half,
+                2 => StoreWidth::Word,
+                _ => return None,
+            };
+            RvInstruction::Store {
+                width,
+                rs1,
+                rs2,
+                offset: imm_s,
+            }
+        }
+        0x13 => {
+            let (op, imm) = match (funct3, funct7) {
+                (0, _) => (ImmOp::Add, imm_i),
+                (2, _) => (ImmOp::Slt, imm_i),
+                (3, _) => (ImmOp::Sltu, imm_i),
+                (4, _) => (ImmOp::Xor, imm_i),
+                (6, _) => (ImmOp::Or, imm_i),
+                (7, _) => (ImmOp::And, imm_i),
+                (1, 0x00) => (ImmOp::Sll, rs2 as i32),
+                (5, 0x00) => (ImmOp::Srl, rs2 as i32),
+                (5, 0x20) => (ImmOp::Sra, rs2 as i32),
+                _ => return None,
+            };
+            RvInstruction::OpImm { op, rd, rs1, imm }
+        }
+        0x33 => {
+            let op = match (funct7, funct3) {
+                (0x00, 0) => RegOp::Add,
+                (0x20, 0) => RegOp::Sub,
+                (0x00, 1) => RegOp::Sll,
+                (0x00, 2) => RegOp::Slt,
+                (0x00, 3) => RegOp::Sltu,
+                (0x00, 4) => RegOp::Xor,
+                (0x00, 5) => RegOp::Srl,
+                (0x20, 5) => RegOp::Sra,
+                (0x00, 6) => RegOp::Or,
+                (0x00, 7) => RegOp::And,
+                (0x01, 0) => RegOp::Mul,
+                (0x01, 1) => RegOp::Mulh,
+                (0x01, 2) => RegOp::Mulhsu,
+                (0x01, 3) => RegOp::Mulhu,
+                (0x01, 4) => RegOp::Div,
+                (0x01, 5) => RegOp::Divu,
+                (0x01, 6) => RegOp::Rem,
+                (0x01, 7) => RegOp::Remu,
+                _ => return None,
+            };
+            RvInstruction::Op { op, rd, rs1, rs2 }
+        }
+        0x0f => RvInstruction::Fence,
+        0x73 => match word {
+            0x0000_0073 => RvInstruction::Ecall,
+            0x0010_0073 => RvInstruction::Ebreak,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(instr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode(0xfff58593),
+            Some(RvInstruction::OpImm {
+                op: ImmOp::Add,
+                rd: 11,
+                rs1: 11,
+                imm: -1
+            })
+        );
+        assert_eq!(
+            decode(0xfe059ce3),
+            Some(RvInstruction::Branch {
+                cond: BranchCond::Ne,
+                rs1: 11,
+                rs2: 0,
+                offset: -8
+            })
+        );
+        assert_eq!(
+            decode(0x01c000ef),
+            Some(RvInstruction::Jal { rd: 1, offset: 28 })
+        );
+        assert_eq!(
+            decode(0xfea12e23),
+            Some(RvInstruction::Store {
+                width: StoreWidth::Word,
+                rs1: 2,
+                rs2: 10,
+                offset: -4
+            })
+        );
+        assert_eq!(
+            decode(0x02c606b3),
+            Some(RvInstruction::Op {
+                op: RegOp::Mul,
+                rd: 13,
+                rs1: 12,
+                rs2: 12
+            })
+        );
+        assert_eq!(decode(0), None);
+    }
+}
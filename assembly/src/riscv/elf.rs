@@ -0,0 +1,143 @@
+//! Minimal loader for 32-bit little-endian RISC-V ELF executables.
+
+use super::RiscvError;
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const ELFCLASS32: u8 = 1;
+const ELFDATA2LSB: u8 = 1;
+const ET_EXEC: u16 = 2;
+const EM_RISCV: u16 = 0xf3;
+/// `e_flags` bit set when the code may contain compressed instructions.
+const EF_RISCV_RVC: u32 = 0x1;
+
+const PT_LOAD: u32 = 1;
+const PF_X: u32 = 0x1;
+
+const SHT_SYMTAB: u32 = 2;
+const STT_FUNC: u8 = 2;
+
+const PHDR_SIZE: usize = 32;
+const SHDR_SIZE: usize = 40;
+const SYM_SIZE: usize = 16;
+
+/// A loadable segment, with its zero-filled tail.
+#[derive(Debug)]
+pub(crate) struct LoadSegment {
+    pub(crate) vaddr: u32,
+    pub(crate) data: Vec<u8>,
+    pub(crate) executable: bool,
+}
+
+/// The parts of an executable needed to lower it.
+#[derive(Debug)]
+pub(crate) struct Elf {
+    pub(crate) entry: u32,
+    pub(crate) segments: Vec<LoadSegment>,
+    /// Addresses of the function symbols, if the executable has a symbol
+    /// table.
+    pub(crate) functions: Vec<u32>,
+}
+
+fn invalid(reason: &str) -> RiscvError {
+    RiscvError::InvalidElf(reason.to_string())
+}
+
+fn read_u8(bytes: &[u8], offset: usize) -> Result<u8, RiscvError> {
+    bytes
+        .get(offset)
+        .copied()
+        .ok_or_else(|| invalid("truncated file"))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, RiscvError> {
+    let slice = bytes
+        .get(offset..offset + 2)
+        .ok_or_else(|| invalid("truncated file"))?;
+    Ok(u16::from_le_bytes([slice[0], slice[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, RiscvError> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .ok_or_else(|| invalid("truncated file"))?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+/// Parses the ELF header, loadable segments and function symbols of `bytes`.
+pub(crate) fn parse(bytes: &[u8]) -> Result<Elf, RiscvError> {
+    if bytes.get(..4) != Some(&ELF_MAGIC[..]) {
+        return Err(invalid("bad magic number"));
+    }
+    if read_u8(bytes, 4)? != ELFCLASS32 || read_u8(bytes, 5)? != ELFDATA2LSB {
+        return Err(RiscvError::UnsupportedElf(
+            "only 32-bit little-endian files are supported".to_string(),
+        ));
+    }
+    if read_u16(bytes, 16)? != ET_EXEC {
+        return Err(RiscvError::UnsupportedElf(
+            "only executables are supported".to_string(),
+        ));
+    }
+    if read_u16(bytes, 18)? != EM_RISCV {
+        return Err(RiscvError::UnsupportedElf("not a RISC-V file".to_string()));
+    }
+    if read_u32(bytes, 36)? & EF_RISCV_RVC != 0 {
+        return Err(RiscvError::UnsupportedElf(
+            "compressed instructions are not supported".to_string(),
+        ));
+    }
+
+    let entry = read_u32(bytes, 24)?;
+    let phoff = read_u32(bytes, 28)? as usize;
+    let shoff = read_u32(bytes, 32)? as usize;
+    let phnum = read_u16(bytes, 44)? as usize;
+    let shnum = read_u16(bytes, 48)? as usize;
+
+    let mut segments = Vec::new();
+    for i in 0..phnum {
+        let header = phoff + i * PHDR_SIZE;
+        if read_u32(bytes, header)? != PT_LOAD {
+            continue;
+        }
+        let offset = read_u32(bytes, header + 4)? as usize;
+        let vaddr = read_u32(bytes, header + 8)?;
+        let filesz = read_u32(bytes, header + 16)? as usize;
+        let memsz = read_u32(bytes, header + 20)? as usize;
+        let flags = read_u32(bytes, header + 24)?;
+        if filesz > memsz {
+            return Err(invalid("segment larger in file than in memory"));
+        }
+
+        let mut data = bytes
+            .get(offset..offset + filesz)
+            .ok_or_else(|| invalid("segment out of file bounds"))?
+            .to_vec();
+        data.resize(memsz, 0);
+        segments.push(LoadSegment {
+            vaddr,
+            data,
+            executable: flags & PF_X != 0,
+        });
+    }
+
+    let mut functions = Vec::new();
+    for i in 0..shnum {
+        let header = shoff + i * SHDR_SIZE;
+        if read_u32(bytes, header + 4)? != SHT_SYMTAB {
+            continue;
+        }
+        let offset = read_u32(bytes, header + 16)? as usize;
+        let size = read_u32(bytes, header + 20)? as usize;
+        for sym in (offset..offset + size).step_by(SYM_SIZE) {
+            if read_u8(bytes, sym + 12)? & 0xf == STT_FUNC {
+                functions.push(read_u32(bytes, sym + 4)?);
+            }
+        }
+    }
+
+    Ok(Elf {
+        entry,
+        segments,
+        functions,
+    })
+}
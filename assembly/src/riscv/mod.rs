@@ -0,0 +1,875 @@
+//! RISC-V front-end, lowering RV32IM executables to PetraVM.
+//!
+//! [`transpile_elf`] loads a statically linked RV32IM ELF executable and
+//! lowers its code to PetraVM assembly, so that programs built with existing
+//! toolchains can run on the VM. The VROM being write-once, RISC-V registers
+//! cannot be mapped onto fixed slots of a single frame. Instead:
+//!
+//! - every basic block becomes a function `bb_<address>`, whose frame holds the
+//!   registers `x1` to `x31` in slots 2 to 32, slots 0 and 1 holding the return
+//!   PC and FP as usual;
+//! - within a block, every register write goes to a fresh slot;
+//! - control flow between blocks is a tail call to a new frame, filled with the
+//!   current registers. Conditional branches use `BNZ` to select the tail call
+//!   to perform;
+//! - indirect jumps (`JALR`) go through `rv_dispatch`, which compares the
+//!   target address with the address of every block.
+//!
+//! The loadable segments are copied to RAM at their virtual addresses, so that
+//! loads and stores map directly onto the RAM instructions. `MUL`, `DIV` and
+//! their variants map onto the corresponding PetraVM opcodes, which follow the
+//! same semantics.
+//!
+//! The stack pointer starts at [`STACK_TOP`] and `ECALL` ends the execution.
+//! Words that cannot be decoded, as well as `EBREAK`, abort the execution when
+//! reached. `JALR` does not clear the lowest bit of its target, which is
+//! always even for code produced by compilers.
+//!
+//! Each lowered instruction is preceded by a `.loc` directive holding the
+//! address of the RISC-V instruction, so that the
+//! [`debug_info`](crate::AssembledProgram::debug_info) of the program maps
+//! the PROM back to the executable, and through `addr2line` to its source.
+
+mod decode;
+mod elf;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use decode::{decode, BranchCond, ImmOp, LoadWidth, RegOp, RvInstruction, StoreWidth};
+use thiserror::Error;
+
+use crate::{
+    memory::MAX_RAM_SIZE, Abi, AssembledProgram, Assembler, AssemblerError, AssemblerOptions,
+    Memory, ValueRom,
+};
+
+/// Initial value of the stack pointer.
+pub const STACK_TOP: u32 = 0x0010_0000;
+
+/// Slot holding the first register, `x1`, in the frame of a block, which
+/// takes the registers as arguments.
+const FIRST_REG_SLOT: u32 = Abi::STANDARD.arg_slot(0) as u32;
+/// First slot available for temporaries in the frame of a block.
+const FIRST_TEMP_SLOT: u32 = FIRST_REG_SLOT + 31;
+
+#[derive(Debug, Error)]
+pub enum RiscvError {
+    #[error("Invalid ELF file: {0}")]
+    InvalidElf(String),
+
+    #[error("Unsupported ELF file: {0}")]
+    UnsupportedElf(String),
+
+    #[error("Entry point {0:#x} is not in an executable segment")]
+    InvalidEntryPoint(u32),
+
+    #[error("Loadable segments end at {0:#x}, past the maximum RAM size")]
+    RamImageTooLarge(u64),
+
+    #[error("Lowered frames need {0} slots, more than a frame can hold")]
+    FrameTooLarge(u32),
+
+    #[error("Failed to assemble the lowered program: {0}")]
+    Assembler(#[from] AssemblerError),
+}
+
+/// A RISC-V executable lowered to PetraVM.
+#[derive(Clone, Debug)]
+pub struct RiscvProgram {
+    /// Lowered assembly code.
+    pub asm: String,
+    /// Assembled program.
+    pub program: AssembledProgram,
+    /// Initial RAM contents, holding the loadable segments.
+    pub ram_image: Vec<u8>,
+}
+
+impl RiscvProgram {
+    /// Returns the initial memory to run the program with.
+    pub fn memory(&self) -> Memory {
+        Memory::new(
+            self.program.prom.clone(),
+            ValueRom::new_with_init_vals(&[0, 0]),
+        )
+        .with_ram_data(&self.ram_image)
+    }
+}
+
+/// Loads an RV32IM ELF executable and lowers it to PetraVM.
+pub fn transpile_elf(bytes: &[u8]) -> Result<RiscvProgram, RiscvError> {
+    let elf = elf::parse(bytes)?;
+    let ram_image = ram_image(&elf.segments)?;
+
+    // Decode every word of the executable segments.
+    let mut code = BTreeMap::new();
+    let mut leaders = BTreeSet::new();
+    for segment in elf.segments.iter().filter(|s| s.executable) {
+        leaders.insert(segment.vaddr);
+        for (i, word) in segment.data.chunks_exact(4).enumerate() {
+            let word = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+            code.insert(segment.vaddr + 4 * i as u32, decode(word));
+        }
+    }
+    if !code.contains_key(&elf.entry) {
+        return Err(RiscvError::InvalidEntryPoint(elf.entry));
+    }
+
+    // Basic blocks start at the entry point, at functions, at branch targets
+    // and after any control flow instruction.
+    leaders.insert(elf.entry);
+    leaders.extend(elf.functions.iter().copied());
+    for (&pc, instr) in &code {
+        match instr {
+            Some(RvInstruction::Jal { offset, .. })
+            | Some(RvInstruction::Branch { offset, .. }) => {
+                leaders.insert(pc.wrapping_add(*offset as u32));
+                leaders.insert(pc.wrapping_add(4));
+            }
+            Some(RvInstruction::Jalr { .. })
+            | Some(RvInstruction::Ecall)
+            | Some(RvInstruction::Ebreak)
+            | None => {
+                leaders.insert(pc.wrapping_add(4));
+            }
+            _ => {}
+        }
+    }
+    leaders.retain(|pc| code.contains_key(pc));
+
+    // Lower every block.
+    let mut blocks = Vec::new();
+    let mut frame_size = FIRST_TEMP_SLOT;
+    for &start in &leaders {
+        let mut block = Block::new(start);
+        let mut pc = start;
+        loop {
+            block.emit(format!(".loc \"{pc:#010x}\""));
+            if block.lower(pc, code[&pc]) {
+                break;
+            }
+            pc = pc.wrapping_add(4);
+            if leaders.contains(&pc) {
+                block.tail_call(&block_label(pc));
+                break;
+            }
+            if !code.contains_key(&pc) {
+                block.trap();
+                break;
+            }
+        }
+        frame_size = frame_size.max(block.next_slot);
+        blocks.push(block);
+    }
+    if frame_size > u16::MAX as u32 {
+        return Err(RiscvError::FrameTooLarge(frame_size));
+    }
+    let frame_size = frame_size.next_power_of_two();
+
+    let mut asm = format!(
+        ";; Lowered from a RISC-V executable, with entry point {:#x}.
+.equ RV_FRAME_SIZE, {frame_size}
+.equ RV_DISPATCH_FRAME_SIZE, {}
+",
+        elf.entry,
+        dispatch_frame_size(leaders.len()),
+    );
+    asm.push_str(&entry_asm(elf.entry));
+    for block in &blocks {
+        asm.push_str(&format!(
+            "#[framesize({frame_size:#x})]\n{}:\n",
+            block_label(block.start)
+        ));
+        for line in &block.lines {
+            asm.push_str(&format!("    {line}\n"));
+        }
+    }
+    asm.push_str(".loc \"\"\n");
+    asm.push_str(&dispatch_asm(&leaders));
+
+    let options = AssemblerOptions {
+        debug_info: true,
+        ..Default::default()
+    };
+    let (program, _) = Assembler::from_code_with_options(&asm, &options)?;
+    Ok(RiscvProgram {
+        asm,
+        program,
+        ram_image,
+    })
+}
+
+/// Builds the initial RAM, with every loadable segment at its virtual address.
+fn ram_image(segments: &[elf::LoadSegment]) -> Result<Vec<u8>, RiscvError> {
+    let end = segments
+        .iter()
+        .map(|s| s.vaddr as u64 + s.data.len() as u64)
+        .max()
+        .unwrap_or(0);
+    if end > MAX_RAM_SIZE as u64 {
+        return Err(RiscvError::RamImageTooLarge(end));
+    }
+
+    let mut image = vec![0; end as usize];
+    for segment in segments {
+        let start = segment.vaddr as usize;
+        image[start..start + segment.data.len()].copy_from_slice(&segment.data);
+    }
+    Ok(image)
+}
+
+fn block_label(pc: u32) -> String {
+    format!("bb_{pc:08x}")
+}
+
+/// Returns the entry point, which sets up the registers before jumping to the
+/// block at `entry`. All registers are zero, except for the stack pointer.
+fn entry_asm(entry: u32) -> String {
+    let mut asm = format!(
+        "#[framesize(0x8)]
+_start:
+    ;; Slot 2: Local: zero
+    ;; Slot 3: Local: stack pointer
+    ;; Slot 4: ND Local: frame of the entry block
+    LDI.W @2, #0
+    LDI.W @3, #{STACK_TOP}
+    ALLOCI! @4, #RV_FRAME_SIZE
+"
+    );
+    for reg in 1..32 {
+        let src = if reg == 2 { 3 } else { 2 };
+        asm.push_str(&format!(
+            "    MVV.W @4[{}], @{src}\n",
+            FIRST_REG_SLOT + reg - 1
+        ));
+    }
+    asm.push_str(&format!("    TAILI {}, @4\n", block_label(entry)));
+    asm
+}
+
+const fn dispatch_frame_size(num_targets: usize) -> u32 {
+    (4 + 2 * num_targets as u32 + 1).next_power_of_two()
+}
+
+/// Returns the dispatcher of indirect jumps, which tail calls the block whose
+/// address is in slot 2 with the frame in slot 3, and aborts if there is none.
+fn dispatch_asm(targets: &BTreeSet<u32>) -> String {
+    let mut asm = format!(
+        "#[framesize({:#x})]
+rv_dispatch:
+    ;; Slot 2: Arg: target address
+    ;; Slot 3: Arg: frame of the target block
+",
+        dispatch_frame_size(targets.len())
+    );
+    let mut slot = 4;
+    for (i, &target) in targets.iter().enumerate() {
+        if i > 0 {
+            asm.push_str(&format!("rv_dispatch_{i}:\n"));
+        }
+        asm.push_str(&format!(
+            "    LDI.W @{slot}, #{target}
+    XOR @{}, @2, @{slot}
+    BNZ rv_dispatch_{}, @{}
+    TAILI {}, @3
+",
+            slot + 1,
+            i + 1,
+            slot + 1,
+            block_label(target),
+        ));
+        slot += 2;
+    }
+    asm.push_str(&format!(
+        "rv_dispatch_{}:
+    LDI.W @{slot}, #0
+    ABORT @{slot}, @{slot}
+",
+        targets.len()
+    ));
+    asm
+}
+
+/// A basic block being lowered.
+struct Block {
+    start: u32,
+    lines: Vec<String>,
+    /// Slot currently holding each register. The entry of `x0` is unused.
+    regs: [u32; 32],
+    /// Slot holding zero, materialized when `x0` is first read.
+    zero: Option<u32>,
+    next_slot: u32,
+}
+
+impl Block {
+    fn new(start: u32) -> Self {
+        Self {
+            start,
+            lines: Vec::new(),
+            regs: std::array::from_fn(|reg| FIRST_REG_SLOT + reg as u32 - 1),
+            zero: None,
+            next_slot: FIRST_TEMP_SLOT,
+        }
+    }
+
+    fn emit(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    fn temp(&mut self) -> u32 {
+        let slot = self.next_slot;
+        self.next_slot += 1;
+        slot
+    }
+
+    /// Returns an even-aligned pair of slots, for 64-bit results.
+    fn temp_pair(&mut self) -> u32 {
+        let slot = self.next_slot.next_multiple_of(2);
+        self.next_slot = slot + 2;
+        slot
+    }
+
+    fn imm(&mut self, value: u32) -> u32 {
+        let slot = self.temp();
+        self.emit(format!("LDI.W @{slot}, #{value}"));
+        slot
+    }
+
+    /// Returns the slot holding `reg`.
+    fn reg(&mut self, reg: u8) -> u32 {
+        if reg != 0 {
+            return self.regs[reg as usize];
+        }
+        match self.zero {
+            Some(slot) => slot,
+            None => {
+                let slot = self.imm(0);
+                self.zero = Some(slot);
+                slot
+            }
+        }
+    }
+
+    /// Returns a fresh slot for the new value of `rd`, or `None` if `rd` is
+    /// `x0` and the write can be dropped.
+    fn def(&mut self, rd: u8) -> Option<u32> {
+        if rd == 0 {
+            return None;
+        }
+        let slot = self.temp();
+        self.regs[rd as usize] = slot;
+        Some(slot)
+    }
+
+    /// Allocates a block frame and copies the current registers in it.
+    fn fill_frame(&mut self) -> u32 {
+        let frame = self.temp();
+        self.emit(format!("ALLOCI! @{frame}, #RV_FRAME_SIZE"));
+        for reg in 1..32 {
+            let src = self.regs[reg];
+            self.emit(format!(
+                "MVV.W @{frame}[{}], @{src}",
+                FIRST_REG_SLOT + reg as u32 - 1
+            ));
+        }
+        frame
+    }
+
+    fn tail_call(&mut self, label: &str) {
+        let frame = self.fill_frame();
+        self.emit(format!("TAILI {label}, @{frame}"));
+    }
+
+    fn trap(&mut self) {
+        let slot = self.imm(0);
+        self.emit(format!("ABORT @{slot}, @{slot}"));
+    }
+
+    /// Lowers the instruction at `pc`, returning whether it ends the block.
+    fn lower(&mut self, pc: u32, instr: Option<RvInstruction>) -> bool {
+        let Some(instr) = instr else {
+            self.trap();
+            return true;
+        };
+
+        match instr {
+            RvInstruction::Lui { rd, imm } => {
+                if let Some(dst) = self.def(rd) {
+                    self.emit(format!("LDI.W @{dst}, #{imm}"));
+                }
+            }
+            RvInstruction::Auipc { rd, imm } => {
+                if let Some(dst) = self.def(rd) {
+                    self.emit(format!("LDI.W @{dst}, #{}", pc.wrapping_add(imm)));
+                }
+            }
+            RvInstruction::Jal { rd, offset } => {
+                if let Some(dst) = self.def(rd) {
+                    self.emit(format!("LDI.W @{dst}, #{}", pc.wrapping_add(4)));
+                }
+                self.tail_call(&block_label(pc.wrapping_add(offset as u32)));
+                return true;
+            }
+            RvInstruction::Jalr { rd, rs1, offset } => {
+                let base = self.reg(rs1);
+                let target = self.temp();
+                self.emit(format!("ADDI @{target}, @{base}, #{offset}"));
+                if let Some(dst) = self.def(rd) {
+                    self.emit(format!("LDI.W @{dst}, #{}", pc.wrapping_add(4)));
+                }
+                let frame = self.fill_frame();
+                let dispatch = self.temp();
+                self.emit(format!("ALLOCI! @{dispatch}, #RV_DISPATCH_FRAME_SIZE"));
+                self.emit(format!("MVV.W @{dispatch}[2], @{target}"));
+                self.emit(format!("MVV.W @{dispatch}[3], @{frame}"));
+                self.emit(format!("TAILI rv_dispatch, @{dispatch}"));
+                return true;
+            }
+            RvInstruction::Branch {
+                cond,
+                rs1,
+                rs2,
+                offset,
+            } => {
+                let (lhs, rhs) = (self.reg(rs1), self.reg(rs2));
+                let (mnemonic, taken_if_nonzero) = match cond {
+                    BranchCond::Eq => ("XOR", false),
+                    BranchCond::Ne => ("XOR", true),
+                    BranchCond::Lt => ("SLT", true),
+                    BranchCond::Ge => ("SLT", false),
+                    BranchCond::Ltu => ("SLTU", true),
+                    BranchCond::Geu => ("SLTU", false),
+                };
+                let cond = self.temp();
+                self.emit(format!("{mnemonic} @{cond}, @{lhs}, @{rhs}"));
+
+                let taken = block_label(pc.wrapping_add(offset as u32));
+                let not_taken = block_label(pc.wrapping_add(4));
+                let (if_nonzero, if_zero) = if taken_if_nonzero {
+                    (taken, not_taken)
+                } else {
+                    (not_taken, taken)
+                };
+                let nonzero_label = format!("{}_nz", block_label(self.start));
+                self.emit(format!("BNZ {nonzero_label}, @{cond}"));
+                self.tail_call(&if_zero);
+                self.emit(format!("{nonzero_label}:"));
+                self.tail_call(&if_nonzero);
+                return true;
+            }
+            RvInstruction::Load {
+                width,
+                rd,
+                rs1,
+                offset,
+            } => {
+                let base = self.reg(rs1);
+                if let Some(dst) = self.def(rd) {
+                    let mnemonic = match width {
+                        LoadWidth::Byte => "LB",
+                        LoadWidth::Half => "LH",
+                        LoadWidth::Word => "LW",
+                        LoadWidth::ByteUnsigned => "LBU",
+                        LoadWidth::HalfUnsigned => "LHU",
+                    };
+                    self.emit(format!("{mnemonic} @{dst}, @{base}, #{offset}"));
+                }
+            }
+            RvInstruction::Store {
+                width,
+                rs1,
+                rs2,
+                offset,
+            } => {
+                let (base, src) = (self.reg(rs1), self.reg(rs2));
+                let mnemonic = match width {
+                    StoreWidth::Byte => "SB",
+                    StoreWidth::Half => "SH",
+                    StoreWidth::Word => "SW",
+                };
+                self.emit(format!("{mnemonic} @{src}, @{base}, #{offset}"));
+            }
+            RvInstruction::OpImm { op, rd, rs1, imm } => {
+                let src = self.reg(rs1);
+                if rd == 0 {
+                    return false;
+                }
+                // SLTIU, XORI, ORI and ANDI zero-extend their immediate, so
+                // negative immediates go through a register operation.
+                let (mnemonic, operand) = match op {
+                    ImmOp::Add => ("ADDI", format!("#{imm}")),
+                    ImmOp::Slt => ("SLTI", format!("#{imm}")),
+                    ImmOp::Sll => ("SLLI", format!("#{imm}")),
+                    ImmOp::Srl => ("SRLI", format!("#{imm}")),
+                    ImmOp::Sra => ("SRAI", format!("#{imm}")),
+                    ImmOp::Sltu if imm >= 0 => ("SLTIU", format!("#{imm}")),
+                    ImmOp::Xor if imm >= 0 => ("XORI", format!("#{imm}")),
+                    ImmOp::Or if imm >= 0 => ("ORI", format!("#{imm}")),
+                    ImmOp::And if imm >= 0 => ("ANDI", format!("#{imm}")),
+                    ImmOp::Sltu | ImmOp::Xor | ImmOp::Or | ImmOp::And => {
+                        let mnemonic = match op {
+                            ImmOp::Sltu => "SLTU",
+                            ImmOp::Xor => "XOR",
+                            ImmOp::Or => "OR",
+                            _ => "AND",
+                        };
+                        (mnemonic, format!("@{}", self.imm(imm as u32)))
+                    }
+                };
+                let dst = self.def(rd).expect("rd is not x0");
+                self.emit(format!("{mnemonic} @{dst}, @{src}, {operand}"));
+            }
+            RvInstruction::Op { op, rd, rs1, rs2 } => {
+                let (lhs, rhs) = (self.reg(rs1), self.reg(rs2));
+                if rd == 0 {
+                    return false;
+                }
+                let mnemonic = match op {
+                    RegOp::Add => "ADD",
+                    RegOp::Sub => "SUB",
+                    RegOp::Sll => "SLL",
+                    RegOp::Slt => "SLT",
+                    RegOp::Sltu => "SLTU",
+                    RegOp::Xor => "XOR",
+                    RegOp::Srl => "SRL",
+                    RegOp::Sra => "SRA",
+                    RegOp::Or => "OR",
+                    RegOp::And => "AND",
+                    RegOp::Div => "DIV",
+                    RegOp::Divu => "DIVU",
+                    RegOp::Rem => "REM",
+                    RegOp::Remu => "REMU",
+                    RegOp::Mul | RegOp::Mulh | RegOp::Mulhsu | RegOp::Mulhu => {
+                        // The multiplications write a 64-bit result, whose low
+                        // or high half is the new value of `rd`.
+                        let (mnemonic, high) = match op {
+                            RegOp::Mul => ("MUL", false),
+                            RegOp::Mulh => ("MUL", true),
+                            RegOp::Mulhsu => ("MULSU", true),
+                            _ => ("MULU", true),
+                        };
+                        let pair = self.temp_pair();
+                        self.emit(format!("{mnemonic} @{pair}, @{lhs}, @{rhs}"));
+                        self.regs[rd as usize] = pair + high as u32;
+                        return false;
+                    }
+                };
+                let dst = self.def(rd).expect("rd is not x0");
+                self.emit(format!("{mnemonic} @{dst}, @{lhs}, @{rhs}"));
+            }
+            RvInstruction::Fence => {}
+            RvInstruction::Ecall => {
+                self.emit("RET".to_string());
+                return true;
+            }
+            RvInstruction::Ebreak => {
+                self.trap();
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isa::GenericISA, PetraTrace};
+
+    /// Wraps `code` in a minimal executable, with a single segment loaded and
+    /// starting at `base`.
+    fn build_elf(base: u32, code: &[u32]) -> Vec<u8> {
+        let mut elf = vec![0; 84];
+        elf[..4].copy_from_slice(&[0x7f, b'E', b'L', b'F']);
+        elf[4] = 1; // ELFCLASS32
+        elf[5] = 1; // ELFDATA2LSB
+        elf[6] = 1; // EV_CURRENT
+        elf[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+        elf[18..20].copy_from_slice(&0xf3u16.to_le_bytes()); // EM_RISCV
+        elf[24..28].copy_from_slice(&base.to_le_bytes()); // e_entry
+        elf[28..32].copy_from_slice(&52u32.to_le_bytes()); // e_phoff
+        elf[42..44].copy_from_slice(&32u16.to_le_bytes()); // e_phentsize
+        elf[44..46].copy_from_slice(&1u16.to_le_bytes()); // e_phnum
+
+        let size = 4 * code.len() as u32;
+        elf[52..56].copy_from_slice(&1u32.to_le_bytes()); // PT_LOAD
+        elf[56..60].copy_from_slice(&84u32.to_le_bytes()); // p_offset
+        elf[60..64].copy_from_slice(&base.to_le_bytes()); // p_vaddr
+        elf[68..72].copy_from_slice(&size.to_le_bytes()); // p_filesz
+        elf[72..76].copy_from_slice(&size.to_le_bytes()); // p_memsz
+        elf[76..80].copy_from_slice(&5u32.to_le_bytes()); // PF_R | PF_X
+
+        for word in code {
+            elf.extend_from_slice(&word.to_le_bytes());
+        }
+        elf
+    }
+
+    #[test]
+    fn test_transpile_elf() {
+        let code = [
+            0x00000513, // li a0, 0
+            0x00a00593, // li a1, 10
+            0x00b50533, // loop: add a0, a0, a1
+            0xfff58593, // addi a1, a1, -1
+            0xfe059ce3, // bnez a1, loop
+            0x01c000ef, // jal ra, double
+            0xfea12e23, // sw a0, -4(sp)
+            0xffc12603, // lw a2, -4(sp)
+            0x02c606b3, // mul a3, a2, a2
+            0x000012b7, // lui t0, 0x1
+            0x00d2a023, // sw a3, 0(t0)
+            0x00000073, // ecall
+            0x00a50533, // double: add a0, a0, a0
+            0x00008067, // ret
+        ];
+        let elf = build_elf(0x10000, &code);
+        let riscv = transpile_elf(&elf).unwrap();
+
+        // Lowered instructions are located at the RISC-V instruction.
+        let debug_info = riscv.program.debug_info.as_ref().unwrap();
+        let (_, loop_index, _) = riscv.program.labels[&block_label(0x10008)];
+        let origin = |index| debug_info.location(index).unwrap().origin.as_deref();
+        assert_eq!(origin(loop_index), Some("0x00010008"));
+        let (_, dispatch_index, _) = riscv.program.labels["rv_dispatch"];
+        assert_eq!(origin(dispatch_index), None);
+
+        let (trace, _) = PetraTrace::generate(
+            Box::new(GenericISA),
+            riscv.memory(),
+            riscv.program.frame_sizes,
+        )
+        .unwrap();
+
+        // (1 + ... + 10) * 2 = 110, squared.
+        let result = &trace.ram().data()[0x1000..0x1004];
+        assert_eq!(u32::from_le_bytes(result.try_into().unwrap()), 12100);
+        assert_eq!(trace.mul.len(), 1);
+    }
+
+    /// Address at which [`run`] stores the results.
+    const RESULTS_ADDR: u32 = 0x1000;
+    /// Register holding [`RESULTS_ADDR`], which the tests do not use.
+    const RESULTS_REG: u32 = 31;
+
+    const fn r_type(funct7: u32, funct3: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+        funct7 << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | 0x33
+    }
+
+    const fn i_type(opcode: u32, funct3: u32, rd: u32, rs1: u32, imm: i32) -> u32 {
+        (imm as u32 & 0xfff) << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+    }
+
+    const fn s_type(funct3: u32, rs1: u32, rs2: u32, imm: i32) -> u32 {
+        let imm = imm as u32;
+        (imm >> 5 & 0x7f) << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | (imm & 0x1f) << 7 | 0x23
+    }
+
+    const fn b_type(funct3: u32, rs1: u32, rs2: u32, offset: i32) -> u32 {
+        let imm = offset as u32;
+        (imm >> 12 & 1) << 31
+            | (imm >> 5 & 0x3f) << 25
+            | rs2 << 20
+            | rs1 << 15
+            | funct3 << 12
+            | (imm >> 1 & 0xf) << 8
+            | (imm >> 11 & 1) << 7
+            | 0x63
+    }
+
+    /// `li rd, value`, as a LUI followed by an ADDI.
+    const fn li(rd: u32, value: u32) -> [u32; 2] {
+        let upper = value.wrapping_add(0x800) & 0xffff_f000;
+        let lower = value.wrapping_sub(upper) as i32;
+        [upper | rd << 7 | 0x37, i_type(0x13, 0, rd, rd, lower)]
+    }
+
+    /// Runs `code`, then returns the final values of `regs`.
+    fn run(code: &[u32], regs: &[u32]) -> Vec<u32> {
+        let mut code = code.to_vec();
+        code.extend(li(RESULTS_REG, RESULTS_ADDR));
+        for (i, &reg) in regs.iter().enumerate() {
+            code.push(s_type(2, RESULTS_REG, reg, 4 * i as i32));
+        }
+        code.push(0x00000073); // ecall
+
+        let riscv = transpile_elf(&build_elf(0x10000, &code)).unwrap();
+        let (trace, _) = PetraTrace::generate(
+            Box::new(GenericISA),
+            riscv.memory(),
+            riscv.program.frame_sizes,
+        )
+        .unwrap();
+        let results = &trace.ram().data()[RESULTS_ADDR as usize..][..4 * regs.len()];
+        results
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lower_mul_div() {
+        // (a, b, [MUL, MULH, MULHSU, MULHU, DIV, DIVU, REM, REMU])
+        let test_cases = [
+            (20, 6, [120, 0, 0, 0, 3, 3, 2, 2]),
+            (
+                -3i32 as u32,
+                5,
+                [
+                    -15i32 as u32,
+                    u32::MAX,
+                    u32::MAX,
+                    4,
+                    0,
+                    0x33333332,
+                    -3i32 as u32,
+                    3,
+                ],
+            ),
+            (
+                5,
+                -3i32 as u32,
+                [-15i32 as u32, u32::MAX, 4, 4, -1i32 as u32, 0, 2, 5],
+            ),
+            (
+                -20i32 as u32,
+                6,
+                [
+                    -120i32 as u32,
+                    u32::MAX,
+                    u32::MAX,
+                    5,
+                    -3i32 as u32,
+                    0x2AAAAAA7,
+                    -2i32 as u32,
+                    2,
+                ],
+            ),
+            (42, 0, [0, 0, 0, 0, u32::MAX, u32::MAX, 42, 42]),
+            (
+                0x80000000,
+                u32::MAX,
+                [
+                    0x80000000, 0, 0x80000000, 0x7FFFFFFF, 0x80000000, 0, 0, 0x80000000,
+                ],
+            ),
+        ];
+
+        for (a, b, expected) in test_cases {
+            let mut code = [li(10, a), li(11, b)].concat();
+            for (rd, funct3) in (12..).zip(0..8) {
+                code.push(r_type(0x01, funct3, rd, 10, 11));
+            }
+            assert_eq!(
+                run(&code, &[12, 13, 14, 15, 16, 17, 18, 19]),
+                expected,
+                "{a:#x}, {b:#x}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_lower_loads_stores() {
+        let mut code = [li(10, 0x1f0), li(11, 0x80f1_7fff)].concat();
+        code.push(s_type(2, 10, 11, 0)); // sw a1, 0(a0)
+        code.push(s_type(0, 10, 11, 4)); // sb a1, 4(a0)
+        code.push(s_type(1, 10, 11, 6)); // sh a1, 6(a0)
+
+        // (funct3, offset, expected)
+        let loads = [
+            (0, 0, u32::MAX),   // lb
+            (4, 0, 0xff),       // lbu
+            (0, 1, 0x7f),       // lb
+            (0, 3, 0xffffff80), // lb
+            (4, 3, 0x80),       // lbu
+            (1, 0, 0x7fff),     // lh
+            (1, 2, 0xffff80f1), // lh
+            (5, 2, 0x80f1),     // lhu
+            (2, 0, 0x80f17fff), // lw
+            (2, 4, 0x7fff00ff), // lw
+            (0, -0x1f0, 0),     // lb
+        ];
+        for (rd, (funct3, offset, _)) in (12..).zip(loads) {
+            code.push(i_type(0x03, funct3, rd, 10, offset));
+        }
+        let regs = (12..12 + loads.len() as u32).collect::<Vec<_>>();
+        let expected = loads.map(|(_, _, expected)| expected);
+        assert_eq!(run(&code, &regs), expected);
+    }
+
+    #[test]
+    fn test_lower_comparisons_shifts_logic() {
+        let mut code = [li(10, 0x8000_0010), li(11, 1), li(12, 36)].concat();
+        // (instruction, expected)
+        let ops = [
+            (r_type(0x00, 2, 0, 10, 11), 1),                // slt
+            (r_type(0x00, 3, 0, 10, 11), 0),                // sltu
+            (r_type(0x00, 2, 0, 11, 10), 0),                // slt
+            (r_type(0x00, 3, 0, 11, 10), 1),                // sltu
+            (i_type(0x13, 2, 0, 10, 0), 1),                 // slti
+            (i_type(0x13, 2, 0, 11, -5), 0),                // slti
+            (i_type(0x13, 3, 0, 11, 2), 1),                 // sltiu
+            (i_type(0x13, 3, 0, 10, -1), 1),                // sltiu
+            (i_type(0x13, 3, 0, 11, 0), 0),                 // sltiu
+            (r_type(0x00, 1, 0, 10, 12), 0x100),            // sll
+            (r_type(0x00, 5, 0, 10, 12), 0x08000001),       // srl
+            (r_type(0x20, 5, 0, 10, 12), 0xf8000001),       // sra
+            (i_type(0x13, 1, 0, 10, 1), 0x20),              // slli
+            (i_type(0x13, 5, 0, 10, 31), 1),                // srli
+            (i_type(0x13, 5, 0, 10, 0x400 | 31), u32::MAX), // srai
+            (i_type(0x13, 7, 0, 10, -16), 0x80000010),      // andi
+            (i_type(0x13, 4, 0, 10, -1), 0x7fffffef),       // xori
+            (i_type(0x13, 6, 0, 11, -2048), 0xfffff801),    // ori
+        ];
+        for (rd, (instr, _)) in (13u32..).zip(ops) {
+            code.push(instr | rd << 7);
+        }
+        let regs = (13..13 + ops.len() as u32).collect::<Vec<_>>();
+        let expected = ops.map(|(_, expected)| expected);
+        assert_eq!(run(&code, &regs), expected);
+    }
+
+    #[test]
+    fn test_lower_branches() {
+        let mut code = [li(10, u32::MAX), li(11, 1)].concat();
+        // (funct3, rs1, rs2, taken)
+        let branches = [
+            (0, 10, 10, true),  // beq
+            (0, 10, 11, false), // beq
+            (1, 10, 11, true),  // bne
+            (1, 11, 11, false), // bne
+            (4, 10, 11, true),  // blt
+            (4, 11, 10, false), // blt
+            (6, 10, 11, false), // bltu
+            (6, 11, 10, true),  // bltu
+            (5, 10, 11, false), // bge
+            (5, 11, 11, true),  // bge
+            (7, 10, 11, true),  // bgeu
+            (7, 11, 10, false), // bgeu
+        ];
+        // Each register is set to 1, then reset to 0 unless the branch skips
+        // the reset.
+        for (rd, (funct3, rs1, rs2, _)) in (12..).zip(branches) {
+            code.push(i_type(0x13, 0, rd, 0, 1));
+            code.push(b_type(funct3, rs1, rs2, 8));
+            code.push(i_type(0x13, 0, rd, 0, 0));
+        }
+        let regs = (12..12 + branches.len() as u32).collect::<Vec<_>>();
+        let expected = branches.map(|(_, _, _, taken)| taken as u32);
+        assert_eq!(run(&code, &regs), expected);
+    }
+
+    #[test]
+    fn test_transpile_invalid_elf() {
+        assert!(matches!(
+            transpile_elf(b"not an elf"),
+            Err(RiscvError::InvalidElf(_))
+        ));
+
+        let mut elf = build_elf(0x10000, &[0x00000073]);
+        elf[36] = 1; // EF_RISCV_RVC
+        assert!(matches!(
+            transpile_elf(&elf),
+            Err(RiscvError::UnsupportedElf(_))
+        ));
+    }
+}
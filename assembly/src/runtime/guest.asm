@@ -0,0 +1,20 @@
+;; PetraVM guest runtime prelude.
+;;
+;; These macros are the shims between a guest and its host. They are defined
+;; before the entry point, so that the whole guest code can use them.
+
+;; Reads the next word of the private input stream into `dst`.
+;;
+;; The input stream is the advice tape of the emulator: the word is a
+;; nondeterministic value, which the guest must check before relying on it.
+#[macro] read_input(dst) {
+    READ_ADVICE! $dst
+}
+
+;; Commits `src` as the public output number `index`.
+;;
+;; `out` is the slot holding the pointer to the outputs, i.e. slot 2 of `main`
+;; unless the pointer was passed down to another function.
+#[macro] commit_output(out, index, src) {
+    MVV.W $out[$index], $src
+}
@@ -0,0 +1,125 @@
+//! Minimal runtime for guest programs.
+//!
+//! Every guest needs the same glue around its own code: an entry point, a way
+//! to read private inputs and a way to expose outputs to the verifier. This
+//! runtime provides it, following the [calling convention](crate::abi) (slot 0
+//! holds the return PC and slot 1 the return FP):
+//!
+//! - [`GuestEntry`] generates the `_start` entry point, which calls the guest's
+//!   `main` function with, in slot 2, a pointer to the public outputs. These
+//!   outputs live in the entry frame and are declared with `.public`, so that
+//!   they are part of the statement.
+//! - `read_input(@dst)` reads the next word of the private input stream, i.e.
+//!   the advice tape of the emulator, into `@dst`.
+//! - `commit_output(@out, index, @src)` writes `@src` to the output `index`,
+//!   `@out` being the slot holding the pointer to the outputs.
+//!
+//! The shims are assembler macros, defined in the prelude that
+//! [`link_guest_runtime`] puts before the guest code.
+
+use crate::{
+    abi::Abi, assembler::AssemblerError, memory::MemoryError, AssembledProgram, Assembler, ValueRom,
+};
+
+/// Assembly source of the macros of the guest runtime.
+pub const GUEST_PRELUDE_ASM: &str = include_str!("guest.asm");
+
+/// Slot of the `main` frame holding the pointer to the public outputs.
+pub const MAIN_OUTPUTS_SLOT: u16 = Abi::STANDARD.arg_slot(0);
+
+/// Entry point of a guest program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuestEntry {
+    /// Label of the function called by the entry point.
+    main: String,
+    /// Frame size of that function.
+    main_frame_size: u16,
+    /// Number of public output words.
+    num_outputs: u16,
+}
+
+impl GuestEntry {
+    /// Creates an entry point calling `main`, whose frame spans
+    /// `main_frame_size` slots, without any output.
+    pub fn new(main: &str, main_frame_size: u16) -> Self {
+        Self {
+            main: main.to_string(),
+            main_frame_size,
+            num_outputs: 0,
+        }
+    }
+
+    /// Sets the number of public output words.
+    pub const fn with_outputs(mut self, num_outputs: u16) -> Self {
+        self.num_outputs = num_outputs;
+        self
+    }
+
+    /// Returns the VROM addresses of the outputs. The entry frame being the
+    /// first one, they are also its slots.
+    pub fn output_addresses(&self) -> std::ops::Range<u32> {
+        2..2 + self.num_outputs as u32
+    }
+
+    /// Returns the assembly code of the entry point.
+    pub fn to_asm(&self) -> String {
+        let outputs = self.output_addresses();
+        let ptr_slot = outputs.end;
+        let frame_slot = ptr_slot + 1;
+
+        let mut asm = String::from(";; Guest entry point.\n");
+        for slot in outputs.clone() {
+            asm.push_str(&format!(".public @{slot}\n"));
+        }
+        asm.push_str(&format!(
+            "#[framesize({:#x})]
+_start:
+    ;; Frame:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slots {}..{}: Public outputs
+    ;; Slot {ptr_slot}: Local: pointer to the outputs
+    ;; Slot {frame_slot}: ND Local: main frame
+    FP @{ptr_slot}, #{}
+    ALLOCI! @{frame_slot}, #{}
+    MVV.W @{frame_slot}[{MAIN_OUTPUTS_SLOT}], @{ptr_slot}
+    CALLI {}, @{frame_slot}
+    RET
+",
+            frame_slot + 1,
+            outputs.start,
+            outputs.end,
+            outputs.start,
+            self.main_frame_size,
+            self.main,
+        ));
+        asm
+    }
+}
+
+/// Links the provided guest code with the guest runtime: the prelude and the
+/// entry point come first, so that execution starts at `_start`.
+pub fn link_guest_runtime(code: &str, entry: &GuestEntry) -> String {
+    let entry_asm = entry.to_asm();
+    let mut linked =
+        String::with_capacity(GUEST_PRELUDE_ASM.len() + entry_asm.len() + code.len() + 2);
+    linked.push_str(GUEST_PRELUDE_ASM);
+    linked.push('\n');
+    linked.push_str(&entry_asm);
+    linked.push('\n');
+    linked.push_str(code);
+    linked
+}
+
+/// Assembles the provided guest code, linked with the guest runtime.
+pub fn assemble_guest(code: &str, entry: &GuestEntry) -> Result<AssembledProgram, AssemblerError> {
+    Assembler::from_code(&link_guest_runtime(code, entry))
+}
+
+/// Reads the public outputs committed by a guest.
+pub fn read_outputs(vrom: &ValueRom, entry: &GuestEntry) -> Result<Vec<u32>, MemoryError> {
+    entry
+        .output_addresses()
+        .map(|addr| vrom.read::<u32>(addr))
+        .collect()
+}
@@ -0,0 +1,28 @@
+;; PetraVM heap runtime.
+;;
+;; Heap blocks are allocated with `ALLOCH!` in the heap region of the VROM,
+;; reserved to them, so a heap pointer is a regular VROM address. A block of
+;; `n` words is accessed like a frame, i.e. `MVV.W @ptr[i], @val` with `@ptr`
+;; holding the pointer returned by `malloc`.
+;;
+;; The allocator is a bump allocator that never frees: since the VROM is
+;; write-once, every word of a block can only be written once, and blocks are
+;; never reused. `free` is a no-op kept for source compatibility.
+
+#[framesize(0x4)]
+malloc:
+    ;; Frame:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slot 2: Arg: size (in 32-bit words)
+    ;; Slot 3: Return value: pointer to the allocated block
+    ALLOCH! @3, @2
+    RET
+
+#[framesize(0x3)]
+free:
+    ;; Frame:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slot 2: Arg: pointer to the block to release, ignored
+    RET
@@ -0,0 +1,61 @@
+//! Heap allocator runtime built on top of the `ALLOCH!` opcode.
+//!
+//! Heap blocks live in the heap region of the VROM, from
+//! [`HEAP_START`](crate::memory::HEAP_START) to
+//! [`HEAP_END`](crate::memory::HEAP_END), reserved to them and away from the
+//! call frames. See [`Memory`](crate::Memory) for the overall memory layout.
+//!
+//! The allocator is a bump allocator that never frees: the VROM being
+//! write-once, the words of a block cannot be reused once written.
+//!
+//! The runtime exposes two functions, following the
+//! [calling convention](crate::abi) (slot 0 holds the return PC and slot 1 the
+//! return FP):
+//!
+//! - `malloc(size) -> ptr`: allocates a block of `size` 32-bit words.
+//! - `free(ptr)`: a no-op, kept for source compatibility with guests ported
+//!   from other targets.
+
+use crate::{
+    abi::Abi, assembler::AssemblerError, memory::MemoryError, AssembledProgram, Assembler, ValueRom,
+};
+
+/// Assembly source of the heap runtime.
+pub const HEAP_RUNTIME_ASM: &str = include_str!("heap.asm");
+
+/// Frame size of the `malloc` function.
+pub const MALLOC_FRAME_SIZE: u16 = 4;
+/// Slot of the `malloc` frame holding the requested size, in words.
+pub const MALLOC_SIZE_SLOT: u16 = Abi::STANDARD.arg_slot(0);
+/// Slot of the `malloc` frame holding the returned pointer.
+pub const MALLOC_PTR_SLOT: u16 = Abi::STANDARD.arg_slot(1);
+
+/// Frame size of the `free` function.
+pub const FREE_FRAME_SIZE: u16 = 3;
+/// Slot of the `free` frame holding the pointer to release.
+pub const FREE_PTR_SLOT: u16 = Abi::STANDARD.arg_slot(0);
+
+/// Appends the heap runtime to the provided guest code.
+///
+/// The guest code comes first so that execution still starts at its first
+/// label.
+pub fn link_heap_runtime(code: &str) -> String {
+    let mut linked = String::with_capacity(code.len() + HEAP_RUNTIME_ASM.len() + 1);
+    linked.push_str(code);
+    linked.push('\n');
+    linked.push_str(HEAP_RUNTIME_ASM);
+    linked
+}
+
+/// Assembles the provided guest code, linked with the heap runtime.
+pub fn assemble_with_heap_runtime(code: &str) -> Result<AssembledProgram, AssemblerError> {
+    Assembler::from_code(&link_heap_runtime(code))
+}
+
+/// Reads the `len` words of the heap block starting at `ptr`.
+pub fn read_heap_block(vrom: &ValueRom, ptr: u32, len: u32) -> Result<Vec<u32>, MemoryError> {
+    let end = ptr
+        .checked_add(len)
+        .ok_or(MemoryError::VromAddressOutOfBounds(ptr, len as usize))?;
+    (ptr..end).map(|addr| vrom.read::<u32>(addr)).collect()
+}
@@ -0,0 +1,5 @@
+//! Runtime libraries written in PetraVM assembly that guest programs can link
+//! against.
+
+pub mod guest;
+pub mod heap;
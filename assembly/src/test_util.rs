@@ -26,21 +26,16 @@ pub(crate) fn collatz_orbits(initial_val: u32) -> (Vec<u32>, Vec<u32>) {
 /// `prover_only` indicates whether a given instruction should only e executed
 /// by the prover.
 pub(crate) fn code_to_prom(code: &[(crate::Instruction, bool)]) -> crate::ProgramRom {
-    use binius_field::Field;
-    use binius_m3::builder::B32;
+    use crate::execution::FieldPc;
 
-    use crate::execution::G;
-
-    let mut prom = crate::ProgramRom::new();
-    // TODO: type-gate field_pc and use some `incr()` method to abstract away `+1` /
-    // `*G`.
-    let mut pc = B32::ONE; // we start at PC = 0G.
+    let mut prom = crate::ProgramRom::default();
+    let mut pc = FieldPc::START;
     for &(instruction, prover_only) in code.iter() {
-        let interp_inst = InterpreterInstruction::new(instruction, pc, None, prover_only);
+        let interp_inst = InterpreterInstruction::new(instruction, pc.field(), None, prover_only);
         prom.push(interp_inst);
 
         if !prover_only {
-            pc *= G;
+            pc.incr();
         }
     }
 
@@ -50,20 +45,15 @@ pub(crate) fn code_to_prom(code: &[(crate::Instruction, bool)]) -> crate::Progra
 /// Helper method to convert Instructions to a program ROM. Assumes that no
 /// instruction is prover-only.
 pub(crate) fn code_to_prom_no_prover_only(code: &[crate::Instruction]) -> crate::ProgramRom {
-    use binius_field::Field;
-    use binius_m3::builder::B32;
-
-    use crate::execution::G;
+    use crate::execution::FieldPc;
 
-    let mut prom = crate::ProgramRom::new();
-    // TODO: type-gate field_pc and use some `incr()` method to abstract away `+1` /
-    // `*G`.
-    let mut pc = B32::ONE; // we start at PC = 0G.
+    let mut prom = crate::ProgramRom::default();
+    let mut pc = FieldPc::START;
     for &instruction in code.iter() {
-        let interp_inst = InterpreterInstruction::new(instruction, pc, None, false);
+        let interp_inst = InterpreterInstruction::new(instruction, pc.field(), None, false);
         prom.push(interp_inst);
 
-        pc *= G;
+        pc.incr();
     }
 
     prom
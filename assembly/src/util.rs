@@ -1,3 +1,4 @@
+use binius_hash::groestl::{GroestlShortImpl, GroestlShortInternal};
 use binius_utils::rayon::adjust_thread_pool;
 use tracing_forest::ForestLayer;
 use tracing_profile::init_tracing;
@@ -62,3 +63,262 @@ pub fn bytes_to_u64(input: &[u8]) -> Vec<u64> {
         output
     }
 }
+
+/// Initial chaining value of Groestl-256, encoding the output size in bits.
+pub const GROESTL256_IV: [u8; 64] = {
+    let mut iv = [0u8; 64];
+    iv[62] = 1;
+    iv
+};
+
+/// Pads `input` into the 64-byte blocks absorbed by Groestl-256: the input is
+/// followed by a single 1 bit, zeros and the number of blocks as a big-endian
+/// u64.
+pub fn groestl256_pad(input: &[u8]) -> Vec<[u8; 64]> {
+    let num_blocks = (input.len() + 9).div_ceil(64);
+    let mut padded = Vec::with_capacity(num_blocks * 64);
+    padded.extend_from_slice(input);
+    padded.push(0x80);
+    padded.resize(num_blocks * 64 - 8, 0);
+    padded.extend_from_slice(&(num_blocks as u64).to_be_bytes());
+    padded
+        .chunks_exact(64)
+        .map(|block| {
+            block
+                .try_into()
+                .expect("The block contains exactly 64 bytes")
+        })
+        .collect()
+}
+
+/// Computes the Groestl-256 digest of `input`.
+pub fn groestl256(input: &[u8]) -> [u8; 32] {
+    let mut state = GroestlShortImpl::state_from_bytes(&GROESTL256_IV);
+    for block in groestl256_pad(input) {
+        <GroestlShortImpl as GroestlShortInternal>::compress(&mut state, &block);
+    }
+    groestl256_output(state)
+}
+
+/// Hashes the Groestl-256 digests `left` and `right` into one, by applying the
+/// output transformation of Groestl-256 to their concatenation.
+///
+/// This is the 2-to-1 compression of the Merkle trees verified by
+/// MERKLE_VERIFY.
+pub fn groestl256_compress_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let input: [u8; 64] = [left.as_slice(), right.as_slice()]
+        .concat()
+        .try_into()
+        .expect("The digests contain 32 bytes each");
+    groestl256_output(GroestlShortImpl::state_from_bytes(&input))
+}
+
+/// Applies the output transformation of Groestl-256 to the chaining value
+/// `state`: truncate P(h) ^ h.
+fn groestl256_output(mut state: [u64; 8]) -> [u8; 32] {
+    let chaining = state;
+    GroestlShortImpl::p_perm(&mut state);
+    GroestlShortImpl::xor_state(&mut state, &chaining);
+    GroestlShortImpl::state_to_bytes(&state)[32..]
+        .try_into()
+        .expect("The state contains 64 bytes")
+}
+
+/// SHA-256 round constants.
+pub const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 initial hash value.
+pub const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Expands a 16-word SHA-256 block into the 64-word message schedule.
+pub fn sha256_schedule(block: &[u32; 16]) -> [u32; 64] {
+    let mut w = [0u32; 64];
+    w[..16].copy_from_slice(block);
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    w
+}
+
+/// Applies the SHA-256 compression function to `state` with a block of 16
+/// message words, each holding four big-endian bytes of the block.
+pub fn sha256_compress(state: &mut [u32; 8], block: &[u32; 16]) {
+    let w = sha256_schedule(block);
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (word, new) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(new);
+    }
+}
+
+/// BLAKE3 initial value, shared with SHA-256.
+pub const BLAKE3_IV: [u32; 8] = SHA256_IV;
+
+/// Permutation applied to the BLAKE3 message words between two rounds.
+pub const BLAKE3_MSG_PERMUTATION: [usize; 16] =
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// State words updated by the 8 G functions of a BLAKE3 round: first the
+/// columns, then the diagonals. The `i`-th G function mixes in the message
+/// words `2 * i` and `2 * i + 1`.
+pub const BLAKE3_G_INDICES: [[usize; 4]; 8] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// Rotation amounts of the BLAKE3 G function.
+pub const BLAKE3_G_ROTATIONS: [u32; 4] = [16, 12, 8, 7];
+
+/// The BLAKE3 G function, mixing the message words `x` and `y` into the state
+/// words `a`, `b`, `c` and `d`.
+pub const fn blake3_g([a, b, c, d]: [u32; 4], x: u32, y: u32) -> [u32; 4] {
+    let a = a.wrapping_add(b).wrapping_add(x);
+    let d = (d ^ a).rotate_right(BLAKE3_G_ROTATIONS[0]);
+    let c = c.wrapping_add(d);
+    let b = (b ^ c).rotate_right(BLAKE3_G_ROTATIONS[1]);
+    let a = a.wrapping_add(b).wrapping_add(y);
+    let d = (d ^ a).rotate_right(BLAKE3_G_ROTATIONS[2]);
+    let c = c.wrapping_add(d);
+    let b = (b ^ c).rotate_right(BLAKE3_G_ROTATIONS[3]);
+    [a, b, c, d]
+}
+
+/// Applies the BLAKE3 compression function.
+///
+/// `input` holds the 8-word chaining value, the 64-bit block counter (low word
+/// first), the block length and the flags. `block` holds the message words,
+/// each made of four little-endian bytes of the block. Returns the 16 output
+/// words, the first 8 of which are the new chaining value.
+pub fn blake3_compress(input: &[u32; 12], block: &[u32; 16]) -> [u32; 16] {
+    let mut state = [0; 16];
+    state[..8].copy_from_slice(&input[..8]);
+    state[8..12].copy_from_slice(&BLAKE3_IV[..4]);
+    state[12..].copy_from_slice(&input[8..]);
+
+    let mut m = *block;
+    for round in 0..7 {
+        for (i, indices) in BLAKE3_G_INDICES.iter().enumerate() {
+            let words = blake3_g(indices.map(|j| state[j]), m[2 * i], m[2 * i + 1]);
+            for (&j, word) in indices.iter().zip(words) {
+                state[j] = word;
+            }
+        }
+        if round < 6 {
+            m = BLAKE3_MSG_PERMUTATION.map(|j| m[j]);
+        }
+    }
+
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= input[i];
+    }
+    state
+}
+
+/// Serde helpers for [`B32`](binius_m3::builder::B32) fields, encoded as their
+/// underlying `u32`.
+pub mod serde_b32 {
+    use binius_m3::builder::B32;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &B32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(value.val())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<B32, D::Error> {
+        u32::deserialize(deserializer).map(B32::new)
+    }
+}
+
+/// Serde helpers for arrays of [`B16`](binius_m3::builder::B16), encoded as
+/// arrays of their underlying `u16`.
+pub mod serde_b16_array {
+    use binius_m3::builder::B16;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        value: &[B16; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        [u16; N]: Serialize,
+    {
+        value.map(|elt| elt.val()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[B16; N], D::Error>
+    where
+        [u16; N]: Deserialize<'de>,
+    {
+        <[u16; N]>::deserialize(deserializer).map(|values| values.map(B16::new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn test_groestl256_known_answers() {
+        // Reference digests of the Grøstl-256 specification.
+        assert_eq!(
+            hex(&groestl256(b"")),
+            "1a52d11d550039be16107f9c58db9ebcc417f16f736adb2502567119f0083467"
+        );
+        assert_eq!(
+            hex(&groestl256(b"The quick brown fox jumps over the lazy dog")),
+            "8c7ad62eb26a21297bc39c2d7293b4bd4d3399fa8afab29e970471739e28b301"
+        );
+        assert_eq!(
+            hex(&groestl256(b"The quick brown fox jumps over the lazy dog.")),
+            "f48290b1bcacee406a0429b993adb8fb3d065f4b09cbcdb464a631d4a0080aaf"
+        );
+    }
+}
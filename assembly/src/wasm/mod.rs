@@ -0,0 +1,1057 @@
+//! WebAssembly front-end, lowering wasm modules to PetraVM.
+//!
+//! [`transpile_wasm`] lowers a binary module to PetraVM assembly, so that
+//! programs built with existing wasm toolchains can run on the VM. Only the
+//! integer subset of the MVP is supported: `i32` values, locals and globals,
+//! structured control flow, direct calls and tail calls (`return_call`), and
+//! loads and stores to a single linear memory.
+//!
+//! The lowering follows the [calling convention](crate::abi). Every function
+//! `wasm_f<index>` takes, in its frame, the return PC and FP in slots 0 and 1,
+//! a pointer to where its results must be written in slot 2 and its
+//! parameters from slot 3 onwards. Callers point the callee to fresh slots of
+//! their own frame, and tail calls (`TAILI`) forward their own pointer.
+//!
+//! The VROM being write-once, locals cannot be overwritten in place. Instead:
+//!
+//! - stack values and locals are mapped onto slots, every write going to a
+//!   fresh slot;
+//! - a function is split into segments at loop headers and at the targets of
+//!   branches. Every segment is a function of its own, whose frame holds, after
+//!   the result pointer, the locals and then the values of the operand stack;
+//! - jumping to a segment is a tail call to a new frame, filled with the
+//!   current locals and stack values. Conditional branches use `BNZ` to select
+//!   the tail call to perform.
+//!
+//! The linear memory lives in RAM from address 0, followed by the globals. The
+//! RAM instructions requiring aligned accesses, loads and stores must be
+//! naturally aligned. Divisions by zero, and the overflowing `i32.div_s` of
+//! `i32::MIN` by -1, trap as wasm requires: they are checked before the
+//! division, whose RISC-V semantics in the VM would return a value instead.
+
+mod module;
+
+use std::ops::Range;
+
+use module::{BinOp, Instr, LoadKind, Module, StoreKind};
+use thiserror::Error;
+
+use crate::{
+    memory::MAX_RAM_SIZE, Abi, AssembledProgram, Assembler, AssemblerError, Memory, ValueRom,
+};
+
+/// Slot of a function frame holding the pointer to its results.
+const RESULTS_PTR_SLOT: u32 = Abi::STANDARD.arg_slot(0) as u32;
+/// Slot of a function frame holding the first local.
+const FIRST_LOCAL_SLOT: u32 = Abi::STANDARD.arg_slot(1) as u32;
+
+#[derive(Debug, Error)]
+pub enum WasmError {
+    #[error("Invalid wasm module: {0}")]
+    InvalidModule(String),
+
+    #[error("Unsupported wasm feature: {0}")]
+    Unsupported(String),
+
+    #[error("No exported function named {0}")]
+    MissingEntry(String),
+
+    #[error("The entry function must not take any parameter")]
+    InvalidEntry,
+
+    #[error("Linear memory and globals end at {0:#x}, past the maximum RAM size")]
+    RamImageTooLarge(u64),
+
+    #[error("Lowered frames of function {0} need {1} slots, more than a frame can hold")]
+    FrameTooLarge(u32, u32),
+
+    #[error("Failed to assemble the lowered program: {0}")]
+    Assembler(#[from] AssemblerError),
+}
+
+/// A wasm module lowered to PetraVM.
+#[derive(Clone, Debug)]
+pub struct WasmProgram {
+    /// Lowered assembly code.
+    pub asm: String,
+    /// Assembled program.
+    pub program: AssembledProgram,
+    /// Initial RAM contents, holding the linear memory and the globals.
+    pub ram_image: Vec<u8>,
+    /// Number of results of the entry function.
+    pub num_results: u16,
+}
+
+impl WasmProgram {
+    /// Returns the initial memory to run the program with.
+    pub fn memory(&self) -> Memory {
+        Memory::new(
+            self.program.prom.clone(),
+            ValueRom::new_with_init_vals(&[0, 0]),
+        )
+        .with_ram_data(&self.ram_image)
+    }
+
+    /// Returns the VROM addresses of the results of the entry function. The
+    /// frame of the entry point being the first one, they are also its slots.
+    pub fn result_addresses(&self) -> Range<u32> {
+        2..2 + self.num_results as u32
+    }
+}
+
+/// Lowers a binary wasm module to PetraVM, with the exported function `entry`
+/// as entry point. The start function of the module, if any, runs first.
+pub fn transpile_wasm(bytes: &[u8], entry: &str) -> Result<WasmProgram, WasmError> {
+    let module = module::parse(bytes)?;
+    let entry_idx = module
+        .exports
+        .iter()
+        .find(|(name, _)| name == entry)
+        .map(|&(_, index)| index)
+        .ok_or_else(|| WasmError::MissingEntry(entry.to_string()))?;
+    let entry_type = module.func_type(entry_idx)?;
+    if entry_type.params != 0 {
+        return Err(WasmError::InvalidEntry);
+    }
+    if let Some(start) = module.start {
+        let start_type = module.func_type(start)?;
+        if start_type.params != 0 || start_type.results != 0 {
+            return Err(WasmError::InvalidModule(
+                "bad start function type".to_string(),
+            ));
+        }
+    }
+    let ram_image = ram_image(&module)?;
+
+    let mut asm = String::from(";; Lowered from a wasm module.\n");
+    let mut bodies = String::new();
+    for index in 0..module.functions.len() as u32 {
+        let (frame_size, body) = FunctionLowering::new(&module, index)?.lower()?;
+        asm.push_str(&format!(".equ {}, {frame_size}\n", frame_size_name(index)));
+        bodies.push_str(&body);
+    }
+    asm.push_str(&entry_asm(module.start, entry_idx, entry_type.results));
+    asm.push_str(&bodies);
+
+    let program = Assembler::from_code(&asm)?;
+    Ok(WasmProgram {
+        asm,
+        program,
+        ram_image,
+        num_results: entry_type.results as u16,
+    })
+}
+
+/// Builds the initial RAM: the linear memory with its data segments, followed
+/// by the globals.
+fn ram_image(module: &Module) -> Result<Vec<u8>, WasmError> {
+    let end = module.memory_size as u64 + 4 * module.globals.len() as u64;
+    if end > MAX_RAM_SIZE as u64 {
+        return Err(WasmError::RamImageTooLarge(end));
+    }
+
+    let mut image = vec![0; end as usize];
+    for (offset, data) in &module.data {
+        let start = *offset as usize;
+        if start + data.len() > module.memory_size as usize {
+            return Err(WasmError::InvalidModule(
+                "data segment out of memory bounds".to_string(),
+            ));
+        }
+        image[start..start + data.len()].copy_from_slice(data);
+    }
+    for (i, value) in module.globals.iter().enumerate() {
+        let start = global_address(module, i as u32) as usize;
+        image[start..start + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    Ok(image)
+}
+
+const fn global_address(module: &Module, global: u32) -> u32 {
+    module.memory_size + 4 * global
+}
+
+fn function_label(index: u32) -> String {
+    format!("wasm_f{index}")
+}
+
+fn frame_size_name(index: u32) -> String {
+    format!("WASM_F{index}_FRAME_SIZE")
+}
+
+/// Returns the entry point, which runs the start function if any, and then
+/// calls the entry function with its results written to the entry frame.
+fn entry_asm(start: Option<u32>, entry: u32, num_results: usize) -> String {
+    let ptr = 2 + num_results as u32;
+    let start_frame = ptr + 1;
+    let entry_frame = ptr + 2;
+
+    let mut asm = format!(
+        "#[framesize({:#x})]
+_start:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slots 2..{ptr}: Results of the entry function
+    ;; Slot {ptr}: Local: pointer to the results
+    ;; Slot {start_frame}: ND Local: frame of the start function
+    ;; Slot {entry_frame}: ND Local: frame of the entry function
+    FP @{ptr}, #2
+",
+        entry_frame + 1
+    );
+    if let Some(start) = start {
+        asm.push_str(&format!(
+            "    ALLOCI! @{start_frame}, #{}
+    MVV.W @{start_frame}[{RESULTS_PTR_SLOT}], @{ptr}
+    CALLI {}, @{start_frame}
+",
+            frame_size_name(start),
+            function_label(start),
+        ));
+    }
+    asm.push_str(&format!(
+        "    ALLOCI! @{entry_frame}, #{}
+    MVV.W @{entry_frame}[{RESULTS_PTR_SLOT}], @{ptr}
+    CALLI {}, @{entry_frame}
+    RET
+",
+        frame_size_name(entry),
+        function_label(entry),
+    ));
+    asm
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ControlKind {
+    Block,
+    Loop,
+    If,
+}
+
+/// A structured control instruction being lowered.
+struct Control {
+    kind: ControlKind,
+    /// Height of the operand stack when entering the block.
+    height: usize,
+    /// Number of results of the block.
+    arity: usize,
+    /// Segment following the block.
+    end_label: String,
+    /// Segment starting the loop, for loops.
+    loop_label: Option<String>,
+    /// Segment of the `else` branch, for `if` blocks whose `else` has not been
+    /// reached yet.
+    else_label: Option<String>,
+}
+
+/// A segment being lowered, mapping locals and stack values onto slots.
+struct Segment {
+    label: String,
+    lines: Vec<String>,
+    locals: Vec<u32>,
+    stack: Vec<u32>,
+    /// Slot holding zero, materialized when first needed.
+    zero: Option<u32>,
+    next_slot: u32,
+}
+
+impl Segment {
+    fn new(label: String, num_locals: usize, height: usize) -> Self {
+        let first_stack_slot = FIRST_LOCAL_SLOT + num_locals as u32;
+        Self {
+            label,
+            lines: Vec::new(),
+            locals: (FIRST_LOCAL_SLOT..first_stack_slot).collect(),
+            stack: (first_stack_slot..first_stack_slot + height as u32).collect(),
+            zero: None,
+            next_slot: first_stack_slot + height as u32,
+        }
+    }
+}
+
+struct FunctionLowering<'a> {
+    module: &'a Module,
+    index: u32,
+    num_locals: usize,
+    num_results: usize,
+    segments: Vec<Segment>,
+    current: Segment,
+    controls: Vec<Control>,
+    next_label: usize,
+    reachable: bool,
+    /// Nesting depth of the blocks opened in unreachable code.
+    dead_depth: usize,
+}
+
+impl<'a> FunctionLowering<'a> {
+    fn new(module: &'a Module, index: u32) -> Result<Self, WasmError> {
+        let func_type = module.func_type(index)?;
+        let function = &module.functions[index as usize];
+        let num_locals = func_type.params + function.num_locals;
+
+        // Parameters are in the frame, and declared locals start at zero.
+        let current = Segment::new(function_label(index), func_type.params, 0);
+        let mut lowering = Self {
+            module,
+            index,
+            num_locals,
+            num_results: func_type.results,
+            segments: Vec::new(),
+            current,
+            controls: Vec::new(),
+            next_label: 0,
+            reachable: true,
+            dead_depth: 0,
+        };
+        if function.num_locals > 0 {
+            let zero = lowering.zero();
+            lowering.current.locals.resize(num_locals, zero);
+        }
+        Ok(lowering)
+    }
+
+    fn new_label(&mut self) -> String {
+        self.next_label += 1;
+        format!("{}_{}", function_label(self.index), self.next_label)
+    }
+
+    fn emit(&mut self, line: String) {
+        self.current.lines.push(line);
+    }
+
+    fn temp(&mut self) -> u32 {
+        let slot = self.current.next_slot;
+        self.current.next_slot += 1;
+        slot
+    }
+
+    /// Returns an even-aligned pair of slots, for 64-bit results.
+    fn temp_pair(&mut self) -> u32 {
+        let slot = self.current.next_slot.next_multiple_of(2);
+        self.current.next_slot = slot + 2;
+        slot
+    }
+
+    fn imm(&mut self, value: u32) -> u32 {
+        let slot = self.temp();
+        self.emit(format!("LDI.W @{slot}, #{value}"));
+        slot
+    }
+
+    fn zero(&mut self) -> u32 {
+        match self.current.zero {
+            Some(slot) => slot,
+            None => {
+                let slot = self.imm(0);
+                self.current.zero = Some(slot);
+                slot
+            }
+        }
+    }
+
+    fn push(&mut self, slot: u32) {
+        self.current.stack.push(slot);
+    }
+
+    fn pop(&mut self) -> Result<u32, WasmError> {
+        self.current
+            .stack
+            .pop()
+            .ok_or_else(|| WasmError::InvalidModule("operand stack underflow".to_string()))
+    }
+
+    fn local(&self, index: u32) -> Result<u32, WasmError> {
+        self.current
+            .locals
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| WasmError::InvalidModule("local index out of bounds".to_string()))
+    }
+
+    fn set_local(&mut self, index: u32, slot: u32) -> Result<(), WasmError> {
+        self.local(index)?;
+        self.current.locals[index as usize] = slot;
+        Ok(())
+    }
+
+    fn global_slot(&mut self, global: u32) -> Result<u32, WasmError> {
+        if global as usize >= self.module.globals.len() {
+            return Err(WasmError::InvalidModule(
+                "global index out of bounds".to_string(),
+            ));
+        }
+        Ok(self.imm(global_address(self.module, global)))
+    }
+
+    /// Returns the base slot and the immediate offset of a memory access.
+    fn address(&mut self, addr: u32, offset: u32) -> (u32, u32) {
+        // The offsets of the RAM instructions are signed 16-bit immediates.
+        if offset < 0x8000 {
+            return (addr, offset);
+        }
+        let offset = self.imm(offset);
+        let base = self.temp();
+        self.emit(format!("ADD @{base}, @{addr}, @{offset}"));
+        (base, 0)
+    }
+
+    /// Tail calls the segment `label` of the current function, passing the
+    /// locals, the `height` bottom values of the stack and its `arity` top
+    /// values.
+    fn jump(&mut self, label: &str, height: usize, arity: usize) {
+        let stack = &self.current.stack;
+        let values: Vec<u32> = stack[..height]
+            .iter()
+            .chain(&stack[stack.len() - arity..])
+            .copied()
+            .collect();
+        let locals = self.current.locals.clone();
+
+        let frame = self.temp();
+        self.emit(format!(
+            "ALLOCI! @{frame}, #{}",
+            frame_size_name(self.index)
+        ));
+        self.emit(format!(
+            "MVV.W @{frame}[{RESULTS_PTR_SLOT}], @{RESULTS_PTR_SLOT}"
+        ));
+        for (i, src) in locals.iter().chain(&values).enumerate() {
+            self.emit(format!(
+                "MVV.W @{frame}[{}], @{src}",
+                FIRST_LOCAL_SLOT + i as u32
+            ));
+        }
+        self.emit(format!("TAILI {label}, @{frame}"));
+    }
+
+    /// Ends the current segment and starts the segment `label`, receiving
+    /// `height` stack values.
+    fn start_segment(&mut self, label: String, height: usize) {
+        let segment = Segment::new(label, self.num_locals, height);
+        self.segments
+            .push(std::mem::replace(&mut self.current, segment));
+        self.reachable = true;
+    }
+
+    /// Writes the results of the function and returns.
+    fn ret(&mut self) -> Result<(), WasmError> {
+        let len = self.current.stack.len();
+        if len < self.num_results {
+            return Err(WasmError::InvalidModule(
+                "operand stack underflow".to_string(),
+            ));
+        }
+        for i in 0..self.num_results {
+            let src = self.current.stack[len - self.num_results + i];
+            self.emit(format!("MVV.W @{RESULTS_PTR_SLOT}[{i}], @{src}"));
+        }
+        self.emit("RET".to_string());
+        Ok(())
+    }
+
+    /// Branches to the block at relative depth `depth`.
+    fn branch(&mut self, depth: u32) -> Result<(), WasmError> {
+        let depth = depth as usize;
+        if depth == self.controls.len() {
+            return self.ret();
+        }
+        let control = self
+            .controls
+            .len()
+            .checked_sub(depth + 1)
+            .map(|i| &self.controls[i])
+            .ok_or_else(|| WasmError::InvalidModule("branch depth out of bounds".to_string()))?;
+        let (label, height, arity) = match &control.loop_label {
+            Some(label) => (label.clone(), control.height, 0),
+            None => (control.end_label.clone(), control.height, control.arity),
+        };
+        if self.current.stack.len() < height + arity {
+            return Err(WasmError::InvalidModule(
+                "operand stack underflow".to_string(),
+            ));
+        }
+        self.jump(&label, height, arity);
+        Ok(())
+    }
+
+    fn trap(&mut self) {
+        let slot = self.imm(0);
+        self.emit(format!("ABORT @{slot}, @{slot}"));
+    }
+
+    /// Traps unless the value in `cond` is non-zero.
+    fn trap_unless(&mut self, cond: u32) {
+        let ok_label = self.new_label();
+        self.emit(format!("BNZ {ok_label}, @{cond}"));
+        self.trap();
+        self.emit(format!("{ok_label}:"));
+    }
+
+    /// Lowers the function, returning its frame size and its code.
+    fn lower(mut self) -> Result<(u32, String), WasmError> {
+        let module = self.module;
+        let mut instrs = module.functions[self.index as usize].body.iter();
+        loop {
+            let instr = instrs
+                .next()
+                .ok_or_else(|| WasmError::InvalidModule("function body without end".to_string()))?;
+            if !self.reachable {
+                match instr {
+                    Instr::Block(_) | Instr::Loop(_) | Instr::If(_) => {
+                        self.dead_depth += 1;
+                        continue;
+                    }
+                    Instr::Else | Instr::End if self.dead_depth > 0 => {
+                        if *instr == Instr::End {
+                            self.dead_depth -= 1;
+                        }
+                        continue;
+                    }
+                    Instr::Else | Instr::End => {}
+                    _ => continue,
+                }
+            }
+            if *instr == Instr::End && self.controls.is_empty() {
+                if self.reachable {
+                    self.ret()?;
+                }
+                break;
+            }
+            self.lower_instr(instr)?;
+        }
+        self.segments.push(self.current);
+
+        let num_slots = self
+            .segments
+            .iter()
+            .map(|segment| segment.next_slot)
+            .max()
+            .unwrap_or(0);
+        if num_slots > u16::MAX as u32 {
+            return Err(WasmError::FrameTooLarge(self.index, num_slots));
+        }
+        // Frames are aligned on their size, and 64-bit results need aligned
+        // slots.
+        let frame_size = num_slots.next_power_of_two();
+
+        let mut code = String::new();
+        for segment in &self.segments {
+            code.push_str(&format!(
+                "#[framesize({frame_size:#x})]\n{}:\n",
+                segment.label
+            ));
+            for line in &segment.lines {
+                if line.ends_with(':') {
+                    code.push_str(&format!("{line}\n"));
+                } else {
+                    code.push_str(&format!("    {line}\n"));
+                }
+            }
+        }
+        Ok((frame_size, code))
+    }
+
+    fn lower_instr(&mut self, instr: &Instr) -> Result<(), WasmError> {
+        match instr {
+            Instr::Unreachable => {
+                self.trap();
+                self.reachable = false;
+            }
+            Instr::Nop => {}
+            Instr::Block(arity) => {
+                let end_label = self.new_label();
+                self.controls.push(Control {
+                    kind: ControlKind::Block,
+                    height: self.current.stack.len(),
+                    arity: *arity,
+                    end_label,
+                    loop_label: None,
+                    else_label: None,
+                });
+            }
+            Instr::Loop(arity) => {
+                let height = self.current.stack.len();
+                let loop_label = self.new_label();
+                let end_label = self.new_label();
+                self.jump(&loop_label, height, 0);
+                self.start_segment(loop_label.clone(), height);
+                self.controls.push(Control {
+                    kind: ControlKind::Loop,
+                    height,
+                    arity: *arity,
+                    end_label,
+                    loop_label: Some(loop_label),
+                    else_label: None,
+                });
+            }
+            Instr::If(arity) => {
+                let cond = self.pop()?;
+                let height = self.current.stack.len();
+                let then_label = self.new_label();
+                let else_label = self.new_label();
+                let end_label = self.new_label();
+                self.emit(format!("BNZ {then_label}, @{cond}"));
+                self.jump(&else_label, height, 0);
+                self.emit(format!("{then_label}:"));
+                self.controls.push(Control {
+                    kind: ControlKind::If,
+                    height,
+                    arity: *arity,
+                    end_label,
+                    loop_label: None,
+                    else_label: Some(else_label),
+                });
+            }
+            Instr::Else => {
+                let control = self.controls.last_mut().ok_or_else(|| {
+                    WasmError::InvalidModule("else outside of an if block".to_string())
+                })?;
+                let else_label = control.else_label.take().ok_or_else(|| {
+                    WasmError::InvalidModule("else outside of an if block".to_string())
+                })?;
+                let (end_label, height, arity) =
+                    (control.end_label.clone(), control.height, control.arity);
+                if self.reachable {
+                    self.jump(&end_label, height, arity);
+                }
+                self.start_segment(else_label, height);
+            }
+            Instr::End => {
+                let control = self.controls.pop().expect("checked by the caller");
+                if self.reachable {
+                    self.jump(&control.end_label, control.height, control.arity);
+                }
+                if let Some(else_label) = control.else_label {
+                    // An `if` without `else` leaves the stack unchanged.
+                    debug_assert_eq!(control.kind, ControlKind::If);
+                    self.start_segment(else_label, control.height);
+                    self.jump(&control.end_label, control.height, control.arity);
+                }
+                self.start_segment(control.end_label, control.height + control.arity);
+            }
+            Instr::Br(depth) => {
+                self.branch(*depth)?;
+                self.reachable = false;
+            }
+            Instr::BrIf(depth) => {
+                let cond = self.pop()?;
+                let height = self.current.stack.len();
+                let taken_label = self.new_label();
+                let next_label = self.new_label();
+                self.emit(format!("BNZ {taken_label}, @{cond}"));
+                self.jump(&next_label, height, 0);
+                self.emit(format!("{taken_label}:"));
+                self.branch(*depth)?;
+                self.start_segment(next_label, height);
+            }
+            Instr::BrTable(targets, default) => {
+                let index = self.pop()?;
+                for (i, target) in targets.iter().enumerate() {
+                    let value = self.imm(i as u32);
+                    let diff = self.temp();
+                    let next_label = self.new_label();
+                    self.emit(format!("XOR @{diff}, @{index}, @{value}"));
+                    self.emit(format!("BNZ {next_label}, @{diff}"));
+                    self.branch(*target)?;
+                    self.emit(format!("{next_label}:"));
+                }
+                self.branch(*default)?;
+                self.reachable = false;
+            }
+            Instr::Return => {
+                self.ret()?;
+                self.reachable = false;
+            }
+            Instr::Call(func) | Instr::ReturnCall(func) => {
+                let callee = self.module.func_type(*func)?;
+                let len = self.current.stack.len();
+                if len < callee.params {
+                    return Err(WasmError::InvalidModule(
+                        "operand stack underflow".to_string(),
+                    ));
+                }
+                let args = self.current.stack.split_off(len - callee.params);
+
+                let frame = self.temp();
+                let results_ptr = match instr {
+                    Instr::Call(_) => {
+                        let ptr = self.temp();
+                        let first_result = self.current.next_slot;
+                        self.current.next_slot += callee.results as u32;
+                        self.emit(format!("FP @{ptr}, #{first_result}"));
+                        self.current
+                            .stack
+                            .extend(first_result..first_result + callee.results as u32);
+                        ptr
+                    }
+                    _ => RESULTS_PTR_SLOT,
+                };
+                self.emit(format!("ALLOCI! @{frame}, #{}", frame_size_name(*func)));
+                self.emit(format!(
+                    "MVV.W @{frame}[{RESULTS_PTR_SLOT}], @{results_ptr}"
+                ));
+                for (i, arg) in args.iter().enumerate() {
+                    self.emit(format!(
+                        "MVV.W @{frame}[{}], @{arg}",
+                        FIRST_LOCAL_SLOT + i as u32
+                    ));
+                }
+                if let Instr::Call(_) = instr {
+                    self.emit(format!("CALLI {}, @{frame}", function_label(*func)));
+                } else {
+                    self.emit(format!("TAILI {}, @{frame}", function_label(*func)));
+                    self.reachable = false;
+                }
+            }
+            Instr::Drop => {
+                self.pop()?;
+            }
+            Instr::Select => {
+                // result = b ^ ((a ^ b) & -(cond != 0))
+                let cond = self.pop()?;
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let zero = self.zero();
+                let [nonzero, mask, diff, masked, result] = std::array::from_fn(|_| self.temp());
+                self.emit(format!("SLTU @{nonzero}, @{zero}, @{cond}"));
+                self.emit(format!("SUB @{mask}, @{zero}, @{nonzero}"));
+                self.emit(format!("XOR @{diff}, @{a}, @{b}"));
+                self.emit(format!("AND @{masked}, @{diff}, @{mask}"));
+                self.emit(format!("XOR @{result}, @{b}, @{masked}"));
+                self.push(result);
+            }
+            Instr::LocalGet(index) => {
+                let slot = self.local(*index)?;
+                self.push(slot);
+            }
+            Instr::LocalSet(index) => {
+                let slot = self.pop()?;
+                self.set_local(*index, slot)?;
+            }
+            Instr::LocalTee(index) => {
+                let slot = self.pop()?;
+                self.set_local(*index, slot)?;
+                self.push(slot);
+            }
+            Instr::GlobalGet(global) => {
+                let addr = self.global_slot(*global)?;
+                let dst = self.temp();
+                self.emit(format!("LW @{dst}, @{addr}, #0"));
+                self.push(dst);
+            }
+            Instr::GlobalSet(global) => {
+                let src = self.pop()?;
+                let addr = self.global_slot(*global)?;
+                self.emit(format!("SW @{src}, @{addr}, #0"));
+            }
+            Instr::Load(kind, offset) => {
+                let addr = self.pop()?;
+                let (base, offset) = self.address(addr, *offset);
+                let mnemonic = match kind {
+                    LoadKind::I32 => "LW",
+                    LoadKind::I8S => "LB",
+                    LoadKind::I8U => "LBU",
+                    LoadKind::I16S => "LH",
+                    LoadKind::I16U => "LHU",
+                };
+                let dst = self.temp();
+                self.emit(format!("{mnemonic} @{dst}, @{base}, #{offset}"));
+                self.push(dst);
+            }
+            Instr::Store(kind, offset) => {
+                let src = self.pop()?;
+                let addr = self.pop()?;
+                let (base, offset) = self.address(addr, *offset);
+                let mnemonic = match kind {
+                    StoreKind::I32 => "SW",
+                    StoreKind::I8 => "SB",
+                    StoreKind::I16 => "SH",
+                };
+                self.emit(format!("{mnemonic} @{src}, @{base}, #{offset}"));
+            }
+            Instr::I32Const(value) => {
+                let slot = self.imm(*value as u32);
+                self.push(slot);
+            }
+            Instr::I32Eqz => {
+                let src = self.pop()?;
+                let dst = self.temp();
+                self.emit(format!("SLTIU @{dst}, @{src}, #1"));
+                self.push(dst);
+            }
+            Instr::Binary(op) => {
+                let rhs = self.pop()?;
+                let lhs = self.pop()?;
+                let dst = self.binary(*op, lhs, rhs);
+                self.push(dst);
+            }
+        }
+        Ok(())
+    }
+
+    /// Lowers a binary operation, returning the slot of its result.
+    fn binary(&mut self, op: BinOp, lhs: u32, rhs: u32) -> u32 {
+        let (mnemonic, lhs, rhs) = match op {
+            BinOp::Eq | BinOp::Ne => {
+                let diff = self.temp();
+                let dst = self.temp();
+                self.emit(format!("XOR @{diff}, @{lhs}, @{rhs}"));
+                if op == BinOp::Eq {
+                    self.emit(format!("SLTIU @{dst}, @{diff}, #1"));
+                } else {
+                    let zero = self.zero();
+                    self.emit(format!("SLTU @{dst}, @{zero}, @{diff}"));
+                }
+                return dst;
+            }
+            BinOp::Mul => {
+                // MUL writes a 64-bit result, whose low half is the product.
+                let dst = self.temp_pair();
+                self.emit(format!("MUL @{dst}, @{lhs}, @{rhs}"));
+                return dst;
+            }
+            BinOp::LtS => ("SLT", lhs, rhs),
+            BinOp::LtU => ("SLTU", lhs, rhs),
+            BinOp::GtS => ("SLT", rhs, lhs),
+            BinOp::GtU => ("SLTU", rhs, lhs),
+            BinOp::LeS => ("SLE", lhs, rhs),
+            BinOp::LeU => ("SLEU", lhs, rhs),
+            BinOp::GeS => ("SLE", rhs, lhs),
+            BinOp::GeU => ("SLEU", rhs, lhs),
+            BinOp::Add => ("ADD", lhs, rhs),
+            BinOp::Sub => ("SUB", lhs, rhs),
+            BinOp::DivS | BinOp::DivU | BinOp::RemS | BinOp::RemU => {
+                self.trap_unless(rhs);
+                if op == BinOp::DivS {
+                    // `i32::MIN / -1` overflows unless `lhs ^ i32::MIN` or
+                    // `rhs + 1` is non-zero.
+                    let min = self.imm(i32::MIN as u32);
+                    let (not_min, not_minus_one) = (self.temp(), self.temp());
+                    let no_overflow = self.temp();
+                    self.emit(format!("XOR @{not_min}, @{lhs}, @{min}"));
+                    self.emit(format!("ADDI @{not_minus_one}, @{rhs}, #1"));
+                    self.emit(format!("OR @{no_overflow}, @{not_min}, @{not_minus_one}"));
+                    self.trap_unless(no_overflow);
+                }
+                let mnemonic = match op {
+                    BinOp::DivS => "DIV",
+                    BinOp::DivU => "DIVU",
+                    BinOp::RemS => "REM",
+                    _ => "REMU",
+                };
+                (mnemonic, lhs, rhs)
+            }
+            BinOp::And => ("AND", lhs, rhs),
+            BinOp::Or => ("OR", lhs, rhs),
+            BinOp::Xor => ("XOR", lhs, rhs),
+            BinOp::Shl => ("SLL", lhs, rhs),
+            BinOp::ShrS => ("SRA", lhs, rhs),
+            BinOp::ShrU => ("SRL", lhs, rhs),
+            BinOp::Rotl => ("ROL", lhs, rhs),
+            BinOp::Rotr => ("ROR", lhs, rhs),
+        };
+        let dst = self.temp();
+        self.emit(format!("{mnemonic} @{dst}, @{lhs}, @{rhs}"));
+        dst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execution::{InterpreterError, InterpreterException},
+        isa::GenericISA,
+        PetraTrace,
+    };
+
+    fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+        let mut section = vec![id, contents.len() as u8];
+        section.extend_from_slice(contents);
+        section
+    }
+
+    fn code(locals: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut code = vec![(locals.len() + body.len()) as u8];
+        code.extend_from_slice(locals);
+        code.extend_from_slice(body);
+        code
+    }
+
+    fn build_module(sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut module = b"\0asm\x01\0\0\0".to_vec();
+        for section in sections {
+            module.extend_from_slice(section);
+        }
+        module
+    }
+
+    #[test]
+    fn test_transpile_wasm() {
+        // (func $main (result i32)
+        //   (i32.store
+        //     (i32.const 16)
+        //     (i32.mul (call $tail (i32.const 10)) (global.get 0)))
+        //   (i32.add
+        //     (i32.load (i32.const 16))
+        //     (if (result i32) (i32.const 0)
+        //       (then (i32.const 1))
+        //       (else (i32.const 2)))))
+        let main = code(
+            &[0x00],
+            &[
+                0x41, 0x10, 0x41, 0x0a, 0x10, 0x02, 0x23, 0x00, 0x6c, 0x36, 0x02, 0x00, 0x41, 0x10,
+                0x28, 0x02, 0x00, 0x41, 0x00, 0x04, 0x7f, 0x41, 0x01, 0x05, 0x41, 0x02, 0x0b, 0x6a,
+                0x0b,
+            ],
+        );
+        // (func $sum (param $n i32) (result i32) (local $acc i32)
+        //   (block (loop
+        //     (br_if 1 (i32.eqz (local.get $n)))
+        //     (local.set $acc (i32.add (local.get $acc) (local.get $n)))
+        //     (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+        //     (br 0)))
+        //   (local.get $acc))
+        let sum = code(
+            &[0x01, 0x01, 0x7f],
+            &[
+                0x02, 0x40, 0x03, 0x40, 0x20, 0x00, 0x45, 0x0d, 0x01, 0x20, 0x01, 0x20, 0x00, 0x6a,
+                0x21, 0x01, 0x20, 0x00, 0x41, 0x01, 0x6b, 0x21, 0x00, 0x0c, 0x00, 0x0b, 0x0b, 0x20,
+                0x01, 0x0b,
+            ],
+        );
+        // (func $tail (param i32) (result i32) (return_call $sum (local.get 0)))
+        let tail = code(&[0x00], &[0x20, 0x00, 0x12, 0x01, 0x0b]);
+
+        let mut codes = vec![0x03];
+        codes.extend(main.iter().chain(&sum).chain(&tail));
+        let module = build_module(&[
+            section(
+                1,
+                &[0x02, 0x60, 0x00, 0x01, 0x7f, 0x60, 0x01, 0x7f, 0x01, 0x7f],
+            ),
+            section(3, &[0x03, 0x00, 0x01, 0x01]),
+            section(5, &[0x01, 0x00, 0x01]),
+            section(6, &[0x01, 0x7f, 0x01, 0x41, 0x07, 0x0b]),
+            section(7, &[0x01, 0x04, b'm', b'a', b'i', b'n', 0x00, 0x00]),
+            section(10, &codes),
+        ]);
+        let wasm = transpile_wasm(&module, "main").unwrap();
+
+        let (trace, _) = PetraTrace::generate(
+            Box::new(GenericISA),
+            wasm.memory(),
+            wasm.program.frame_sizes.clone(),
+        )
+        .unwrap();
+
+        // 55 * 7 + 2
+        let result = wasm.result_addresses().start;
+        assert_eq!(trace.vrom().read::<u32>(result).unwrap(), 387);
+    }
+
+    /// Builds a module whose `main` function returns `lhs <op> rhs`.
+    fn binary_module(op: u8, lhs: i32, rhs: i32) -> Vec<u8> {
+        let mut body = Vec::new();
+        for value in [lhs, rhs] {
+            body.push(0x41);
+            // Signed LEB128 encoding of the constant.
+            let mut value = value;
+            loop {
+                let byte = (value & 0x7f) as u8;
+                value >>= 7;
+                if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                    body.push(byte);
+                    break;
+                }
+                body.push(byte | 0x80);
+            }
+        }
+        body.extend([op, 0x0b]);
+
+        let mut codes = vec![0x01];
+        codes.extend(code(&[0x00], &body));
+        build_module(&[
+            section(1, &[0x01, 0x60, 0x00, 0x01, 0x7f]),
+            section(3, &[0x01, 0x00]),
+            section(7, &[0x01, 0x04, b'm', b'a', b'i', b'n', 0x00, 0x00]),
+            section(10, &codes),
+        ])
+    }
+
+    #[test]
+    fn test_division_traps() {
+        const DIV_S: u8 = 0x6d;
+        const DIV_U: u8 = 0x6e;
+        const REM_S: u8 = 0x6f;
+        const REM_U: u8 = 0x70;
+
+        // (op, lhs, rhs, result, or `None` if the division traps)
+        let test_cases = [
+            (DIV_S, -7, 2, Some(-3)),
+            (DIV_S, 7, 0, None),
+            (DIV_S, i32::MIN, -1, None),
+            (DIV_S, i32::MIN, 1, Some(i32::MIN)),
+            (DIV_S, 7, -1, Some(-7)),
+            (DIV_U, -7, 2, Some(0x7FFFFFFC)),
+            (DIV_U, 7, 0, None),
+            (DIV_U, i32::MIN, -1, Some(0)),
+            (REM_S, -7, 2, Some(-1)),
+            (REM_S, 7, 0, None),
+            (REM_S, i32::MIN, -1, Some(0)),
+            (REM_U, -7, 2, Some(1)),
+            (REM_U, 7, 0, None),
+        ];
+
+        for (op, lhs, rhs, expected) in test_cases {
+            let wasm = transpile_wasm(&binary_module(op, lhs, rhs), "main").unwrap();
+            let result = PetraTrace::generate(
+                Box::new(GenericISA),
+                wasm.memory(),
+                wasm.program.frame_sizes.clone(),
+            );
+            let desc = format!("{op:#x} with {lhs} and {rhs}");
+            match (result, expected) {
+                (Ok((trace, _)), Some(expected)) => {
+                    let result = wasm.result_addresses().start;
+                    assert_eq!(
+                        trace.vrom().read::<u32>(result).unwrap(),
+                        expected as u32,
+                        "{desc}"
+                    );
+                }
+                (Err(err), None) => assert!(
+                    matches!(
+                        err.root(),
+                        InterpreterError::Exception(InterpreterException::Abort { .. })
+                    ),
+                    "{desc}: {err:?}"
+                ),
+                (Ok(_), None) => panic!("{desc} should trap"),
+                (Err(err), Some(_)) => panic!("{desc} failed: {err:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_transpile_unsupported_wasm() {
+        // (func $main (result i32) (i32.wrap_i64 (i64.const 1)))
+        let main = code(&[0x00], &[0x42, 0x01, 0xa7, 0x0b]);
+        let mut codes = vec![0x01];
+        codes.extend(main);
+        let module = build_module(&[
+            section(1, &[0x01, 0x60, 0x00, 0x01, 0x7f]),
+            section(3, &[0x01, 0x00]),
+            section(7, &[0x01, 0x04, b'm', b'a', b'i', b'n', 0x00, 0x00]),
+            section(10, &codes),
+        ]);
+        assert!(matches!(
+            transpile_wasm(&module, "main"),
+            Err(WasmError::Unsupported(_))
+        ));
+        assert!(matches!(
+            transpile_wasm(b"\0asm", "main"),
+            Err(WasmError::InvalidModule(_))
+        ));
+    }
+}
@@ -0,0 +1,438 @@
+//! Parser for the subset of WebAssembly binary modules supported by the
+//! lowering: 32-bit integers only, without imports nor tables.
+
+use super::WasmError;
+
+const WASM_MAGIC: [u8; 4] = [0x00, b'a', b's', b'm'];
+const WASM_VERSION: [u8; 4] = [0x01, 0x00, 0x00, 0x00];
+
+const I32: u8 = 0x7f;
+const EMPTY_BLOCK_TYPE: u8 = 0x40;
+const FUNC_TYPE: u8 = 0x60;
+const FUNC_EXPORT: u8 = 0x00;
+const PAGE_SIZE: u32 = 1 << 16;
+
+fn invalid(reason: &str) -> WasmError {
+    WasmError::InvalidModule(reason.to_string())
+}
+
+fn unsupported(reason: &str) -> WasmError {
+    WasmError::Unsupported(reason.to_string())
+}
+
+/// Signature of a function, whose parameters and results are all `i32`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FuncType {
+    pub(crate) params: usize,
+    pub(crate) results: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct Function {
+    pub(crate) type_idx: u32,
+    /// Number of declared locals, excluding the parameters.
+    pub(crate) num_locals: usize,
+    /// Instructions of the body, including the final `end`.
+    pub(crate) body: Vec<Instr>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Module {
+    pub(crate) types: Vec<FuncType>,
+    pub(crate) functions: Vec<Function>,
+    /// Exported functions, with their index.
+    pub(crate) exports: Vec<(String, u32)>,
+    pub(crate) start: Option<u32>,
+    /// Initial size of the linear memory, in bytes.
+    pub(crate) memory_size: u32,
+    /// Initial values of the globals.
+    pub(crate) globals: Vec<u32>,
+    /// Active data segments, with their offset in the linear memory.
+    pub(crate) data: Vec<(u32, Vec<u8>)>,
+}
+
+impl Module {
+    pub(crate) fn func_type(&self, func: u32) -> Result<FuncType, WasmError> {
+        let function = self
+            .functions
+            .get(func as usize)
+            .ok_or_else(|| invalid("function index out of bounds"))?;
+        self.types
+            .get(function.type_idx as usize)
+            .copied()
+            .ok_or_else(|| invalid("type index out of bounds"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LoadKind {
+    I32,
+    I8S,
+    I8U,
+    I16S,
+    I16U,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StoreKind {
+    I32,
+    I8,
+    I16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BinOp {
+    Eq,
+    Ne,
+    LtS,
+    LtU,
+    GtS,
+    GtU,
+    LeS,
+    LeU,
+    GeS,
+    GeU,
+    Add,
+    Sub,
+    Mul,
+    DivS,
+    DivU,
+    RemS,
+    RemU,
+    And,
+    Or,
+    Xor,
+    Shl,
+    ShrS,
+    ShrU,
+    Rotl,
+    Rotr,
+}
+
+/// A supported instruction. Block types are given by their number of results.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Instr {
+    Unreachable,
+    Nop,
+    Block(usize),
+    Loop(usize),
+    If(usize),
+    Else,
+    End,
+    Br(u32),
+    BrIf(u32),
+    BrTable(Vec<u32>, u32),
+    Return,
+    Call(u32),
+    ReturnCall(u32),
+    Drop,
+    Select,
+    LocalGet(u32),
+    LocalSet(u32),
+    LocalTee(u32),
+    GlobalGet(u32),
+    GlobalSet(u32),
+    Load(LoadKind, u32),
+    Store(StoreKind, u32),
+    I32Const(i32),
+    I32Eqz,
+    Binary(BinOp),
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8, WasmError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| invalid("unexpected end of module"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], WasmError> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| invalid("unexpected end of module"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 integer.
+    fn u32(&mut self) -> Result<u32, WasmError> {
+        let mut value = 0u64;
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return u32::try_from(value).map_err(|_| invalid("integer too large"));
+            }
+        }
+        Err(invalid("integer too large"))
+    }
+
+    /// Reads a signed LEB128 integer.
+    fn i32(&mut self) -> Result<i32, WasmError> {
+        let mut value = 0i64;
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as i64) << shift;
+            if byte & 0x80 == 0 {
+                if byte & 0x40 != 0 {
+                    value |= -1i64 << (shift + 7);
+                }
+                return i32::try_from(value).map_err(|_| invalid("integer too large"));
+            }
+        }
+        Err(invalid("integer too large"))
+    }
+
+    fn name(&mut self) -> Result<String, WasmError> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec()).map_err(|_| invalid("invalid name"))
+    }
+
+    fn i32_type(&mut self) -> Result<(), WasmError> {
+        match self.byte()? {
+            I32 => Ok(()),
+            _ => Err(unsupported("value types other than i32")),
+        }
+    }
+
+    fn block_type(&mut self) -> Result<usize, WasmError> {
+        match self.byte()? {
+            EMPTY_BLOCK_TYPE => Ok(0),
+            I32 => Ok(1),
+            _ => Err(unsupported("block types other than [] and [i32]")),
+        }
+    }
+
+    /// Reads a constant expression, which must be a single `i32.const`.
+    fn const_expr(&mut self) -> Result<u32, WasmError> {
+        match (self.byte()?, self.i32()?, self.byte()?) {
+            (0x41, value, 0x0b) => Ok(value as u32),
+            _ => Err(unsupported("constant expressions other than i32.const")),
+        }
+    }
+
+    /// Reads the alignment and offset of a memory access, returning the
+    /// offset.
+    fn mem_arg(&mut self) -> Result<u32, WasmError> {
+        self.u32()?;
+        self.u32()
+    }
+
+    fn instr(&mut self) -> Result<Instr, WasmError> {
+        let opcode = self.byte()?;
+        let instr = match opcode {
+            0x00 => Instr::Unreachable,
+            0x01 => Instr::Nop,
+            0x02 => Instr::Block(self.block_type()?),
+            0x03 => Instr::Loop(self.block_type()?),
+            0x04 => Instr::If(self.block_type()?),
+            0x05 => Instr::Else,
+            0x0b => Instr::End,
+            0x0c => Instr::Br(self.u32()?),
+            0x0d => Instr::BrIf(self.u32()?),
+            0x0e => {
+                let len = self.u32()?;
+                let targets = (0..len).map(|_| self.u32()).collect::<Result<_, _>>()?;
+                Instr::BrTable(targets, self.u32()?)
+            }
+            0x0f => Instr::Return,
+            0x10 => Instr::Call(self.u32()?),
+            0x12 => Instr::ReturnCall(self.u32()?),
+            0x1a => Instr::Drop,
+            0x1b => Instr::Select,
+            0x20 => Instr::LocalGet(self.u32()?),
+            0x21 => Instr::LocalSet(self.u32()?),
+            0x22 => Instr::LocalTee(self.u32()?),
+            0x23 => Instr::GlobalGet(self.u32()?),
+            0x24 => Instr::GlobalSet(self.u32()?),
+            0x28 => Instr::Load(LoadKind::I32, self.mem_arg()?),
+            0x2c => Instr::Load(LoadKind::I8S, self.mem_arg()?),
+            0x2d => Instr::Load(LoadKind::I8U, self.mem_arg()?),
+            0x2e => Instr::Load(LoadKind::I16S, self.mem_arg()?),
+            0x2f => Instr::Load(LoadKind::I16U, self.mem_arg()?),
+            0x36 => Instr::Store(StoreKind::I32, self.mem_arg()?),
+            0x3a => Instr::Store(StoreKind::I8, self.mem_arg()?),
+            0x3b => Instr::Store(StoreKind::I16, self.mem_arg()?),
+            0x41 => Instr::I32Const(self.i32()?),
+            0x45 => Instr::I32Eqz,
+            0x46..=0x4f | 0x6a..=0x78 => {
+                let op = match opcode {
+                    0x46 => BinOp::Eq,
+                    0x47 => BinOp::Ne,
+                    0x48 => BinOp::LtS,
+                    0x49 => BinOp::LtU,
+                    0x4a => BinOp::GtS,
+                    0x4b => BinOp::GtU,
+                    0x4c => BinOp::LeS,
+                    0x4d => BinOp::LeU,
+                    0x4e => BinOp::GeS,
+                    0x4f => BinOp::GeU,
+                    0x6a => BinOp::Add,
+                    0x6b => BinOp::Sub,
+                    0x6c => BinOp::Mul,
+                    0x6d => BinOp::DivS,
+                    0x6e => BinOp::DivU,
+                    0x6f => BinOp::RemS,
+                    0x70 => BinOp::RemU,
+                    0x71 => BinOp::And,
+                    0x72 => BinOp::Or,
+                    0x73 => BinOp::Xor,
+                    0x74 => BinOp::Shl,
+                    0x75 => BinOp::ShrS,
+                    0x76 => BinOp::ShrU,
+                    0x77 => BinOp::Rotl,
+                    _ => BinOp::Rotr,
+                };
+                Instr::Binary(op)
+            }
+            _ => return Err(WasmError::Unsupported(format!("opcode {opcode:#04x}"))),
+        };
+        Ok(instr)
+    }
+}
+
+/// Parses a binary module.
+pub(crate) fn parse(bytes: &[u8]) -> Result<Module, WasmError> {
+    let mut reader = Reader::new(bytes);
+    if reader.bytes(4).ok() != Some(&WASM_MAGIC[..]) {
+        return Err(invalid("bad magic number"));
+    }
+    if reader.bytes(4)? != WASM_VERSION {
+        return Err(unsupported("versions other than 1"));
+    }
+
+    let mut module = Module::default();
+    let mut type_indices = Vec::new();
+    while !reader.is_empty() {
+        let id = reader.byte()?;
+        let len = reader.u32()? as usize;
+        let mut section = Reader::new(reader.bytes(len)?);
+        match id {
+            // Custom sections.
+            0 => {}
+            // Types.
+            1 => {
+                for _ in 0..section.u32()? {
+                    if section.byte()? != FUNC_TYPE {
+                        return Err(invalid("bad function type"));
+                    }
+                    let params = section.u32()? as usize;
+                    for _ in 0..params {
+                        section.i32_type()?;
+                    }
+                    let results = section.u32()? as usize;
+                    for _ in 0..results {
+                        section.i32_type()?;
+                    }
+                    module.types.push(FuncType { params, results });
+                }
+            }
+            2 => return Err(unsupported("imports")),
+            // Functions.
+            3 => {
+                for _ in 0..section.u32()? {
+                    type_indices.push(section.u32()?);
+                }
+            }
+            // Tables, only used by `call_indirect`, which is not supported.
+            4 => {}
+            // Memories.
+            5 => {
+                if section.u32()? != 1 {
+                    return Err(unsupported("multiple memories"));
+                }
+                let flags = section.byte()?;
+                let min_pages = section.u32()?;
+                if flags & 1 != 0 {
+                    section.u32()?;
+                }
+                module.memory_size = min_pages
+                    .checked_mul(PAGE_SIZE)
+                    .ok_or_else(|| invalid("memory too large"))?;
+            }
+            // Globals.
+            6 => {
+                for _ in 0..section.u32()? {
+                    section.i32_type()?;
+                    section.byte()?;
+                    module.globals.push(section.const_expr()?);
+                }
+            }
+            // Exports.
+            7 => {
+                for _ in 0..section.u32()? {
+                    let name = section.name()?;
+                    let kind = section.byte()?;
+                    let index = section.u32()?;
+                    if kind == FUNC_EXPORT {
+                        module.exports.push((name, index));
+                    }
+                }
+            }
+            // Start function.
+            8 => module.start = Some(section.u32()?),
+            // Element segments, only used by `call_indirect`.
+            9 => {}
+            // Code.
+            10 => {
+                let count = section.u32()? as usize;
+                if count != type_indices.len() {
+                    return Err(invalid("function and code sections do not match"));
+                }
+                for &type_idx in &type_indices {
+                    let size = section.u32()? as usize;
+                    let mut code = Reader::new(section.bytes(size)?);
+                    let mut num_locals = 0usize;
+                    for _ in 0..code.u32()? {
+                        num_locals += code.u32()? as usize;
+                        code.i32_type()?;
+                    }
+                    let mut body = Vec::new();
+                    while !code.is_empty() {
+                        body.push(code.instr()?);
+                    }
+                    module.functions.push(Function {
+                        type_idx,
+                        num_locals,
+                        body,
+                    });
+                }
+            }
+            // Data.
+            11 => {
+                for _ in 0..section.u32()? {
+                    if section.u32()? != 0 {
+                        return Err(unsupported("passive data segments"));
+                    }
+                    let offset = section.const_expr()?;
+                    let len = section.u32()? as usize;
+                    module.data.push((offset, section.bytes(len)?.to_vec()));
+                }
+            }
+            // Data count.
+            12 => {}
+            _ => return Err(invalid("unknown section")),
+        }
+    }
+    if module.functions.len() != type_indices.len() {
+        return Err(invalid("function and code sections do not match"));
+    }
+    Ok(module)
+}
@@ -9,13 +9,9 @@ fn test_naive_div() {
     let vrom = ValueRom::new_with_init_vals(&[0, 0, a, b]);
 
     let memory = Memory::new(compiled_program.prom, vrom);
-    let (trace, _) = PetraTrace::generate(
-        Box::new(GenericISA),
-        memory,
-        compiled_program.frame_sizes,
-        compiled_program.pc_field_to_index_pc,
-    )
-    .expect("Trace generation should not fail.");
+    let (trace, _) =
+        PetraTrace::generate(Box::new(GenericISA), memory, compiled_program.frame_sizes)
+            .expect("Trace generation should not fail.");
 
     assert_eq!(
         trace
@@ -48,13 +44,9 @@ fn test_bezout() {
     let vrom = ValueRom::new_with_init_vals(&[0, 0, a, b]);
 
     let memory = Memory::new(compiled_program.prom, vrom);
-    let (trace, _) = PetraTrace::generate(
-        Box::new(GenericISA),
-        memory,
-        compiled_program.frame_sizes,
-        compiled_program.pc_field_to_index_pc,
-    )
-    .expect("Trace generation should not fail.");
+    let (trace, _) =
+        PetraTrace::generate(Box::new(GenericISA), memory, compiled_program.frame_sizes)
+            .expect("Trace generation should not fail.");
 
     // gcd
     assert_eq!(
@@ -93,13 +85,9 @@ fn test_non_tail_long_div() {
     let vrom = ValueRom::new_with_init_vals(&[0, 0, a, b]);
 
     let memory = Memory::new(compiled_program.prom, vrom);
-    let (trace, _) = PetraTrace::generate(
-        Box::new(GenericISA),
-        memory,
-        compiled_program.frame_sizes,
-        compiled_program.pc_field_to_index_pc,
-    )
-    .expect("Trace generation should not fail.");
+    let (trace, _) =
+        PetraTrace::generate(Box::new(GenericISA), memory, compiled_program.frame_sizes)
+            .expect("Trace generation should not fail.");
 
     assert_eq!(
         trace
@@ -127,13 +115,9 @@ fn test_tail_long_div() {
     let vrom = ValueRom::new_with_init_vals(&[0, 0, a, b]);
 
     let memory = Memory::new(compiled_program.prom, vrom);
-    let (trace, _) = PetraTrace::generate(
-        Box::new(GenericISA),
-        memory,
-        compiled_program.frame_sizes,
-        compiled_program.pc_field_to_index_pc,
-    )
-    .expect("Trace generation should not fail.");
+    let (trace, _) =
+        PetraTrace::generate(Box::new(GenericISA), memory, compiled_program.frame_sizes)
+            .expect("Trace generation should not fail.");
 
     assert_eq!(
         trace
@@ -27,3 +27,28 @@ fn test_branch_integration_less_than() {
 fn test_branch_integration_greater_or_equal() {
     run_test(3, 2, "n >= 3");
 }
+
+fn run_compare_test(a: i32, b: i32, expected: u32) {
+    let mut info = execute_test_asm(
+        AsmToExecute::new(include_str!("../../examples/branch_cmp.asm"))
+            .init_vals(vec![a as u32, b as u32]),
+    );
+    let compare_frame = info.frames.add_frame("compare");
+
+    assert_eq!(
+        compare_frame.get_vrom_expected::<u32>(4),
+        expected,
+        "Comparison of {a} and {b}"
+    );
+}
+
+#[test]
+fn test_branch_cmp_integration() {
+    run_compare_test(7, 7, 0);
+    run_compare_test(-3, -3, 0);
+    run_compare_test(2, 3, 1);
+    run_compare_test(-1, 1, 1);
+    run_compare_test(3, 2, 2);
+    // BLT and BGE compare signed integers.
+    run_compare_test(1, -1, 2);
+}
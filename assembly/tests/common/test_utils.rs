@@ -238,14 +238,14 @@ pub fn execute_test_asm<T: Into<AsmToExecute>>(prog: T) -> ExecutedTestProgInfo
     processed_init_vals.extend(prog.init_vals);
 
     let vrom = ValueRom::new_with_init_vals(&processed_init_vals);
-    let memory = Memory::new(compiled_program.prom.clone(), vrom);
+    let memory =
+        Memory::new(compiled_program.prom.clone(), vrom).with_ram_data(&compiled_program.data);
 
     // Execute the program and generate the trace
     let (trace, boundary_values) = PetraTrace::generate(
         Box::new(GenericISA),
         memory,
         compiled_program.frame_sizes.clone(),
-        compiled_program.pc_field_to_index_pc.clone(),
     )
     .expect("Trace generation should not fail");
 
@@ -0,0 +1,44 @@
+use petravm_asm::{
+    isa::GenericISA,
+    runtime::guest::{assemble_guest, read_outputs, GuestEntry},
+    GenerationOptions, Memory, PetraTrace, ValueRom,
+};
+
+const GUEST: &str = "
+#[framesize(0x6)]
+main:
+    ;; Frame:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slot 2: Arg: pointer to the outputs
+    ;; Slot 3: Local: a
+    ;; Slot 4: Local: b
+    ;; Slot 5: Local: a + b
+    read_input(@3)
+    read_input(@4)
+    ADD @5, @3, @4
+    commit_output(@2, 0, @5)
+    commit_output(@2, 1, @3)
+    RET
+";
+
+#[test]
+fn test_guest_runtime() {
+    let entry = GuestEntry::new("main", 6).with_outputs(2);
+    let program = assemble_guest(GUEST, &entry).expect("The guest should assemble");
+    assert_eq!(program.public_slots, [2, 3]);
+
+    for (a, b) in [(0, 0), (40, 2), (u32::MAX, 2)] {
+        let memory = Memory::new(program.prom.clone(), ValueRom::new_with_init_vals(&[0, 0]));
+        let (trace, _) = PetraTrace::generate_with_options(
+            Box::new(GenericISA),
+            memory,
+            program.frame_sizes.clone(),
+            GenerationOptions::default().advice_tape(vec![a, b]),
+        )
+        .expect("The guest should run");
+
+        let outputs = read_outputs(trace.vrom(), &entry).expect("The outputs must be committed");
+        assert_eq!(outputs, vec![a.wrapping_add(b), a]);
+    }
+}
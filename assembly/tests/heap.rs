@@ -0,0 +1,36 @@
+pub mod common;
+use common::test_utils::{execute_test_asm, AsmToExecute};
+use petravm_asm::memory::{MemoryError, HEAP_START};
+use petravm_asm::runtime::heap::{read_heap_block, HEAP_RUNTIME_ASM};
+
+fn run_test(a: u32, b: u32) {
+    let mut info = execute_test_asm(
+        AsmToExecute::new(include_str!("../../examples/heap.asm"))
+            .add_binary(HEAP_RUNTIME_ASM)
+            .init_vals(vec![a, b]),
+    );
+    let make_pair_frame = info.frames.add_frame("make_pair");
+    let malloc_frame = info.frames.add_frame("malloc");
+    let pair_ptr = make_pair_frame.get_vrom_expected::<u32>(4);
+
+    assert_eq!(malloc_frame.get_vrom_expected::<u32>(2), 2, "size = 2");
+    assert_eq!(malloc_frame.get_vrom_expected::<u32>(3), pair_ptr);
+    assert_eq!(pair_ptr, HEAP_START, "The pair is in the heap region");
+
+    let pair = read_heap_block(info.frames.trace.vrom(), pair_ptr, 2)
+        .expect("The pair must have been written");
+    assert_eq!(pair, vec![a, a.wrapping_add(b)]);
+
+    assert!(matches!(
+        read_heap_block(info.frames.trace.vrom(), u32::MAX, 2),
+        Err(MemoryError::VromAddressOutOfBounds(u32::MAX, 2))
+    ));
+}
+
+#[test]
+fn test_heap_runtime() {
+    petravm_asm::init_logger();
+    for (a, b) in [(0, 0), (1, 2), (42, 7), (u32::MAX, 1)] {
+        run_test(a, b);
+    }
+}
@@ -3,7 +3,11 @@ pub mod common;
 use std::collections::HashSet;
 
 use common::test_utils::execute_test_asm;
-use petravm_asm::{init_logger, Opcode};
+use petravm_asm::execution::FlushOrigin;
+use petravm_asm::{
+    init_logger, isa::GenericISA, Assembler, GenerationOptions, Memory, Opcode, PetraTrace,
+    ValueRom,
+};
 use strum::VariantArray;
 
 #[test]
@@ -15,8 +19,18 @@ fn test_opcodes() {
     let mut unseen_types_remaining: HashSet<_> = HashSet::from_iter(Opcode::VARIANTS);
     unseen_types_remaining.remove(&Opcode::Bz); // Bz isn't an actual opcode
     unseen_types_remaining.remove(&Opcode::Invalid); // Invalid is not an opcode.
-    unseen_types_remaining.remove(&Opcode::Groestl256Compress); // Groestl256Compress is not an opcode.
-    unseen_types_remaining.remove(&Opcode::Groestl256Output); // Groestl256Output is not an opcode.
+    unseen_types_remaining.remove(&Opcode::Groestl256Compress); // GROESTL256_COMPRESS is tested separately.
+    unseen_types_remaining.remove(&Opcode::Groestl256Output); // GROESTL256_OUTPUT is tested separately.
+    unseen_types_remaining.remove(&Opcode::Sha256Compress); // SHA256_COMPRESS is tested separately.
+    unseen_types_remaining.remove(&Opcode::Blake3Compress); // BLAKE3_COMPRESS is tested separately.
+    unseen_types_remaining.remove(&Opcode::BigMulMod); // BIGMULMOD is tested separately.
+    unseen_types_remaining.remove(&Opcode::MerkleVerify); // MERKLE_VERIFY is tested separately.
+    unseen_types_remaining.remove(&Opcode::Abort); // ABORT ends the execution with an error.
+    unseen_types_remaining.remove(&Opcode::Halt); // HALT ends the execution, and is tested separately.
+    unseen_types_remaining.remove(&Opcode::Ecall); // ECALL needs a registered host function.
+    unseen_types_remaining.remove(&Opcode::ReadAdvice); // READ_ADVICE needs an advice tape.
+    unseen_types_remaining.remove(&Opcode::Debug); // DEBUG is not part of the proven program.
+    unseen_types_remaining.remove(&Opcode::Free); // FREE is tested separately.
 
     for instr in &info.compiled_program.prom {
         unseen_types_remaining.remove(&instr.opcode());
@@ -34,3 +48,83 @@ fn test_opcodes() {
         "Final result should be 0"
     );
 }
+
+#[test]
+fn test_deferred_gadget_events() {
+    let program = Assembler::from_code(include_str!("../../examples/opcodes.asm")).unwrap();
+    let memory = || {
+        Memory::new(program.prom.clone(), ValueRom::new_with_init_vals(&[0, 0]))
+            .with_ram_data(&program.data)
+    };
+
+    let (inline, _) =
+        PetraTrace::generate(Box::new(GenericISA), memory(), program.frame_sizes.clone()).unwrap();
+    let (deferred, boundary_values) = PetraTrace::generate_with_options(
+        Box::new(GenericISA),
+        memory(),
+        program.frame_sizes.clone(),
+        GenerationOptions::default().deferred_gadget_events(),
+    )
+    .unwrap();
+    deferred.validate(boundary_values);
+
+    // The deferred gadget events are the same, up to their order.
+    let right_shifts = |trace: &PetraTrace| {
+        let mut events = trace
+            .right_logic_shift_gadget
+            .iter()
+            .map(|event| (event.input, event.shift_amount, event.output))
+            .collect::<Vec<_>>();
+        events.sort_unstable();
+        events
+    };
+    assert!(!inline.right_logic_shift_gadget.is_empty());
+    assert_eq!(right_shifts(&inline), right_shifts(&deferred));
+
+    let mem_words = |trace: &PetraTrace| {
+        let mut events = trace
+            .mem_word_gadget
+            .iter()
+            .map(|event| {
+                (
+                    event.dst_addr,
+                    event.src_addr,
+                    event.src_stride,
+                    event.remaining,
+                    event.val,
+                )
+            })
+            .collect::<Vec<_>>();
+        events.sort_unstable();
+        events
+    };
+    assert!(!inline.mem_word_gadget.is_empty());
+    assert_eq!(mem_words(&inline), mem_words(&deferred));
+}
+
+#[test]
+fn test_diagnose_unbalanced_trace() {
+    let program = Assembler::from_code(include_str!("../../examples/opcodes.asm")).unwrap();
+    let memory = Memory::new(program.prom, ValueRom::new_with_init_vals(&[0, 0]))
+        .with_ram_data(&program.data);
+    let (mut trace, boundary_values) =
+        PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes).unwrap();
+    assert!(trace.diagnose(&boundary_values).is_ok());
+
+    // Tampering with an event unbalances the states it flushes, which are
+    // reported along with the tampered event.
+    trace.ldi[1].timestamp = u32::MAX;
+    let imbalance = trace.diagnose(&boundary_values).unwrap_err();
+    assert_eq!(imbalance.channel, "state");
+    assert_eq!(imbalance.unbalanced.len(), 4);
+    let tampered = FlushOrigin::event("ldi", 1);
+    assert!(imbalance
+        .unbalanced
+        .iter()
+        .any(|value| value.multiplicity == -1 && value.pulls.contains(&tampered)));
+    assert!(imbalance
+        .unbalanced
+        .iter()
+        .any(|value| value.multiplicity == 1 && value.pushes.contains(&tampered)));
+    assert!(imbalance.to_string().contains("ldi[1]"));
+}
@@ -0,0 +1,40 @@
+;; Rust equivalent:
+;; ------------
+;; fn compare(a: i32, b: i32) -> u32 {
+;;     if a == b {
+;;         return 0;
+;;     } else if a < b {
+;;         return 1;
+;;     } else {
+;;         return 2;
+;;     }
+;; }
+;; ------------
+
+#[framesize(0x5)]
+compare:
+    ;; Frame:
+    ;; Slot @0: Return PC
+    ;; Slot @1: Return FP
+    ;; Slot @2: Arg: a
+    ;; Slot @3: Arg: b
+    ;; Slot @4: Return value
+
+    BEQ equal, @2, @3     ;; if a == b
+    BNE not_equal, @2, @3 ;; always taken at this point
+
+equal:
+    LDI.W @4, #0 ;; return 0
+    RET
+
+not_equal:
+    BLT less, @2, @3    ;; if a < b
+    BGE greater, @2, @3 ;; always taken at this point
+
+less:
+    LDI.W @4, #1 ;; return 1
+    RET
+
+greater:
+    LDI.W @4, #2 ;; return 2
+    RET
@@ -0,0 +1,22 @@
+;; Groestl-256 digest of a message spanning two blocks once padded.
+;;
+;; The host provides the initial chaining value of Groestl-256, transposed and
+;; in the binary basis as expected by GROESTL256_COMPRESS, followed by the
+;; padded message blocks.
+#[framesize(0x80)]
+groestl:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slots 16-31: Arg: initial chaining value
+    ;; Slots 32-47: Arg: first message block
+    ;; Slots 48-63: Arg: second message block
+    ;; Slots 64-79: Local: chaining value after the first block
+    ;; Slots 80-95: Local: chaining value after the second block
+    ;; Slots 96-103: Return value: digest
+
+    GROESTL256_COMPRESS @64, @16, @32
+    GROESTL256_COMPRESS @80, @64, @48
+
+    ;; The output transformation takes both halves of the last chaining value.
+    GROESTL256_OUTPUT @96, @80, @88
+    RET
@@ -0,0 +1,31 @@
+;; Builds a pair `(a, a + b)` on the heap and returns a pointer to it.
+;; The heap runtime (`malloc` / `free`) must be linked with this program.
+
+#[framesize(0x9)]
+make_pair:
+    ;; Frame:
+    ;; Slot 0: Return PC
+    ;; Slot 1: Return FP
+    ;; Slot 2: Arg: a
+    ;; Slot 3: Arg: b
+    ;; Slot 4: Return value: pointer to the pair
+    ;; Slot 5: ND Local: malloc frame
+    ;; Slot 6: Local: a + b
+    ;; Slot 7: ND Local: free frame
+    ;; Slot 8: Local: pair size
+
+    LDI.W @8, #2
+    ALLOCI! @5, #4
+    MVV.W @5[2], @8 ;; size
+    CALLI malloc, @5
+    MVV.W @5[3], @4 ;; pointer to the newly allocated block
+
+    ADD @6, @2, @3
+    MVV.W @4[0], @2 ;; pair.0 = a
+    MVV.W @4[1], @6 ;; pair.1 = a + b
+
+    ;; Heap blocks are never freed, `free` is a no-op.
+    ALLOCI! @7, #3
+    MVV.W @7[2], @4
+    CALLI free, @7
+    RET
@@ -37,14 +37,14 @@
 _start: 
     ;; Call the binary field test
     ;; We also test ALLOCI with the test_binary_field frame
-    ALLOCI! @3, #41
+    ALLOCI! @3, #61
     CALLI test_binary_field, @3
     MVV.W @3[2], @4
     BNZ test_failed, @4
     
     ;; Call the integer operations test
     ;; We also test ALLOCV with the test_integer_ops frame
-    LDI.W! @17, #78
+    LDI.W! @17, #111
     ALLOCV! @5, @17
     CALLI test_integer_ops, @5
     MVV.W @5[2], @6
@@ -75,7 +75,7 @@ _start:
     BNZ test_failed, @14
 
     ;; Call the FP test
-    ALLOCI! @15, #5
+    ALLOCI! @15, #7
     CALLI test_fp, @15
     MVV.W @15[2], @16
     BNZ test_failed, @16
@@ -127,7 +127,7 @@ jumpv_done:
 ;; Binary field multiplication has special semantics for the field.
 ;; ============================================================================
 
-#[framesize(0x29)]
+#[framesize(0x3d)]
 test_binary_field:
     ;; Frame slots:
     ;; Slot 0: Return PC
@@ -232,6 +232,52 @@ test_binary_field:
     XORI @40, @38, #5
     BNZ bf_fail, @40
 
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: B32_INV / B128_INV
+    ;; 
+    ;; FORMAT: 
+    ;;   B32_INV dst, src      (32-bit variant)
+    ;;   B128_INV dst, src     (128-bit variant)
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Invert a binary field element. Zero is mapped to zero.
+    ;;
+    ;; EFFECT: fp[dst] = fp[src]^(-1) (in GF(2^32) or GF(2^128))
+    ;; ------------------------------------------------------------
+    B32_INV @41, @4
+    B32_MUL @42, @41, @4  ;; x^(-1) * x = 1
+    XORI @43, @42, #1
+    BNZ bf_fail, @43
+
+    B128_INV @52, @24
+    B128_MUL @56, @52, @24
+    XORI @60, @56, #1     ;; Only the lowest word of 1 is set
+    BNZ bf_fail, @60
+    BNZ bf_fail, @57
+    BNZ bf_fail, @58
+    BNZ bf_fail, @59
+
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: B64_MUL
+    ;; 
+    ;; FORMAT: B64_MUL dst, src1, src2
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Multiply two 64-bit binary field elements, each held in
+    ;;   two consecutive slots (least significant word first).
+    ;;
+    ;; EFFECT: fp[dst] = fp[src1] * fp[src2] (in GF(2^64))
+    ;; ------------------------------------------------------------
+    LDI.W @44, #1         ;; 64-bit multiplicative identity at @44
+    LDI.W @45, #0
+    LDI.W @46, #42        ;; 64-bit value at @46
+    LDI.W @47, #5
+
+    ;; Test multiplication (1 * x = x)
+    B64_MUL @48, @44, @46
+    XORI @50, @49, #5
+    BNZ bf_fail, @50
+
     LDI.W @2, #0         ;; Set success flag (0 = success)
     RET
 bf_fail:
@@ -245,7 +291,7 @@ bf_fail:
 ;; Includes arithmetic, logical, comparison, and shift operations.
 ;; ============================================================================
 
-#[framesize(0x4e)]
+#[framesize(0x6f)]
 test_integer_ops:
     ;; Frame slots:
     ;; Slot 0: Return PC
@@ -408,6 +454,99 @@ test_integer_ops:
     XOR @39, @38, @10    ;; Check result (should match original -1 value)
     BNZ int_fail, @39
 
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: Rotate Operations
+    ;;
+    ;; FORMAT:
+    ;;   ROL dst, src1, src2   (Rotate Left)
+    ;;   ROR dst, src1, src2   (Rotate Right)
+    ;;   ROLI dst, src, imm    (Rotate Left Immediate)
+    ;;   RORI dst, src, imm    (Rotate Right Immediate)
+    ;;
+    ;; DESCRIPTION:
+    ;;   Perform circular shifts. The effective rotation amount is
+    ;;   determined by the last 5 bits of the rotation operand.
+    ;;   Bits shifted out on one side are shifted in on the other.
+    ;;
+    ;; EFFECT:
+    ;;   fp[dst] = fp[src1] <<< fp[src2]
+    ;;   fp[dst] = fp[src1] >>> fp[src2]
+    ;; ------------------------------------------------------------
+    ;; Test immediate rotate variants
+    ROLI @78, @3, #4     ;; 42 <<< 4 = 672
+    XORI @79, @78, #672  ;; Check result
+    BNZ int_fail, @79
+
+    RORI @80, @3, #4     ;; 42 >>> 4 = 0xA0000002
+    ROLI @81, @80, #4    ;; Rotate back to 42
+    XOR @82, @81, @3     ;; Check result
+    BNZ int_fail, @82
+
+    ;; Test VROM-based rotate variants
+    ROR @83, @4, @5      ;; 7 >>> 2 = 0xC0000001
+    ROL @84, @83, @5     ;; Rotate back to 7
+    XOR @85, @84, @4     ;; Check result
+    BNZ int_fail, @85
+
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: Bit Counting Operations
+    ;;
+    ;; FORMAT:
+    ;;   CLZ dst, src          (Count Leading Zeros)
+    ;;   CTZ dst, src          (Count Trailing Zeros)
+    ;;   POPCNT dst, src       (Population Count)
+    ;;
+    ;; DESCRIPTION:
+    ;;   Count the leading zero bits, the trailing zero bits or the
+    ;;   set bits of a value. CLZ and CTZ return 32 for a zero value.
+    ;;
+    ;; EFFECT:
+    ;;   fp[dst] = clz(fp[src])
+    ;;   fp[dst] = ctz(fp[src])
+    ;;   fp[dst] = popcount(fp[src])
+    ;; ------------------------------------------------------------
+    CLZ @86, @3          ;; 42 = 0b101010 has 26 leading zeros
+    XORI @87, @86, #26   ;; Check result
+    BNZ int_fail, @87
+
+    CTZ @88, @3          ;; 42 = 0b101010 has 1 trailing zero
+    XORI @89, @88, #1    ;; Check result
+    BNZ int_fail, @89
+
+    POPCNT @90, @3       ;; 42 = 0b101010 has 3 set bits
+    XORI @91, @90, #3    ;; Check result
+    BNZ int_fail, @91
+
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: Byte Operations
+    ;;
+    ;; FORMAT:
+    ;;   BSWAP dst, src        (Byte Swap)
+    ;;   SEXT.B dst, src       (Sign-Extend Byte)
+    ;;   SEXT.H dst, src       (Sign-Extend Half-word)
+    ;;
+    ;; DESCRIPTION:
+    ;;   Reverse the byte order of a value, or sign-extend its
+    ;;   lowest 8 or 16 bits to 32 bits.
+    ;;
+    ;; EFFECT:
+    ;;   fp[dst] = bswap(fp[src])
+    ;;   fp[dst] = fp[src] as i8 as i32
+    ;;   fp[dst] = fp[src] as i16 as i32
+    ;; ------------------------------------------------------------
+    BSWAP @92, @3        ;; 42 = 0x0000002A becomes 0x2A000000
+    BSWAP @93, @92       ;; Swap back to 42
+    XOR @94, @93, @3     ;; Check result
+    BNZ int_fail, @94
+
+    SEXT.B @95, @10      ;; The low byte of -1 is sign-extended to -1
+    XOR @96, @95, @10    ;; Check result
+    BNZ int_fail, @96
+
+    SEXT.H @97, @3       ;; 42 is positive and stays 42
+    XOR @98, @97, @3     ;; Check result
+    BNZ int_fail, @98
+
     ;; ------------------------------------------------------------
     ;; INSTRUCTION: MUL / MULI
     ;; 
@@ -497,6 +636,42 @@ test_integer_ops:
     XOR @63, @61, @10    ;; Compare with all 1s
     BNZ int_fail, @63
 
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: ADD.64 / SUB.64 / SLT.64
+    ;;
+    ;; FORMAT:
+    ;;   ADD.64 dst, src1, src2   (64-bit Addition)
+    ;;   SUB.64 dst, src1, src2   (64-bit Subtraction)
+    ;;   SLT.64 dst, src1, src2   (64-bit Set if Less Than, signed)
+    ;;
+    ;; DESCRIPTION:
+    ;;   Operate on 64-bit integers stored in pairs of aligned slots,
+    ;;   lower 32 bits first. SLT.64 writes a single slot.
+    ;;
+    ;; EFFECT:
+    ;;   fp[dst:dst+1] = fp[src1:src1+1] + fp[src2:src2+1]
+    ;;   fp[dst:dst+1] = fp[src1:src1+1] - fp[src2:src2+1]
+    ;;   fp[dst] = (fp[src1:src1+1] < fp[src2:src2+1]) ? 1 : 0
+    ;; ------------------------------------------------------------
+    ;; Reuse the MULU results: @46:@47 = 294 and @52:@53 = 0x1FFFFFFFE
+    ADD.64 @100, @52, @46 ;; 0x1FFFFFFFE + 294 = 0x200000124
+    XORI @106, @100, #292 ;; Check lower 32 bits
+    BNZ int_fail, @106
+    XORI @107, @101, #2  ;; Check upper 32 bits
+    BNZ int_fail, @107
+
+    SUB.64 @102, @100, @46 ;; 0x200000124 - 294 = 0x1FFFFFFFE
+    XOR @108, @102, @51  ;; Lower bits should match -2
+    BNZ int_fail, @108
+    XORI @109, @103, #1  ;; Upper bits should be 1
+    BNZ int_fail, @109
+
+    SLT.64 @104, @46, @52 ;; 294 < 0x1FFFFFFFE? = 1 (true)
+    XORI @110, @104, #1  ;; Check result
+    BNZ int_fail, @110
+    SLT.64 @105, @52, @46 ;; 0x1FFFFFFFE < 294? = 0 (false)
+    BNZ int_fail, @105
+
     ;; ------------------------------------------------------------
     ;; INSTRUCTION: SLT / SLTI / SLTU / SLTIU
     ;; 
@@ -692,6 +867,90 @@ test_move_ops:
     MVV.W @18[3], @19    ;; Set up return value location
     BNZ move_fail, @19   ;; Check if test failed
 
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: MVV.H (Move Value Half-word)
+    ;; 
+    ;; FORMAT: MVV.H dst[off], src
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Move the low 16 bits of a value to a VROM address,
+    ;;   zero-extending to 32 bits.
+    ;;
+    ;; EFFECT: VROM[fp[dst] + off] = ZeroExtend(fp[src] & 0xFFFF)
+    ;; ------------------------------------------------------------
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: MVI.B (Move Immediate Byte)
+    ;; 
+    ;; FORMAT: MVI.B dst[off], imm
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Move an 8-bit immediate value to a VROM address,
+    ;;   zero-extending to 32 bits.
+    ;;
+    ;; EFFECT: VROM[fp[dst] + off] = ZeroExtend(imm)
+    ;; ------------------------------------------------------------
+    LDI.W @20, #0x12345678 ;; Source value with a non-zero high half-word
+
+    ;; Call a test function with MVV.H and MVI.B to verify they work
+    ALLOCI! @21, #16
+    MVV.H @21[2], @20    ;; Pass the low half-word to the function
+    MVI.B @21[3], #171   ;; Pass the byte immediate to the function
+    CALLI test_move_call_sub, @21
+    MVV.W @21[4], @22    ;; Set up return value location
+    BNZ move_fail, @22   ;; Check if test failed
+
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: MEMCPY (Copy Memory Block)
+    ;; 
+    ;; FORMAT: MEMCPY dst, src, len
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Copy len words between the VROM regions whose addresses
+    ;;   are held in dst and src.
+    ;;
+    ;; EFFECT: VROM[fp[dst] + i] = VROM[fp[src] + i], for i < len
+    ;; ------------------------------------------------------------
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: MEMSET (Set Memory Block)
+    ;; 
+    ;; FORMAT: MEMSET dst, src, len
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Fill len words of the VROM region whose address is held
+    ;;   in dst with the value of src.
+    ;;
+    ;; EFFECT: VROM[fp[dst] + i] = fp[src], for i < len
+    ;; ------------------------------------------------------------
+    LDI.W @23, #4242     ;; 1st source word
+    LDI.W @24, #4343     ;; 2nd source word
+    LDI.W @25, #7        ;; Fill value
+
+    ;; Call a test function with MEMCPY and MEMSET to verify they work
+    ALLOCI! @26, #16
+    FP @27, #23          ;; Address of the source words
+    ADDI @28, @26, #2    ;; Address of the 1st argument of the function
+    MEMCPY @28, @27, #2  ;; Pass the source words to the function
+    ADDI @29, @26, #4    ;; Address of the 3rd argument of the function
+    MEMSET @29, @25, #2  ;; Pass two copies of the fill value to the function
+    CALLI test_move_call_block, @26
+    MVV.W @26[6], @30    ;; Set up return value location
+    BNZ move_fail, @30   ;; Check if test failed
+
+    ;; ------------------------------------------------------------
+    ;; INSTRUCTION: ALLOCH! (Allocate Heap Block, prover-only)
+    ;; 
+    ;; FORMAT: ALLOCH! dst, size
+    ;; 
+    ;; DESCRIPTION:
+    ;;   Allocate a block of fp[size] words in the heap region of
+    ;;   the VROM, and store its address in dst.
+    ;;
+    ;; EFFECT: fp[dst] = address of a new block of fp[size] words
+    ;; ------------------------------------------------------------
+    LDI.W @31, #2        ;; Block size
+    ALLOCH! @32, @31
+    MVV.W @32[1], @23    ;; Write the 2nd word of the block
+
     LDI.W @2, #0         ;; Set success flag (0 = success)
     RET
 move_fail:
@@ -746,6 +1005,44 @@ move_call_h_fail:
     LDI.W @3, #1         ;; Set failure flag in return value slot (slot 3, not 2)
     RET
 
+;; Helper function to test MVV.H and MVI.B
+#[framesize(0x10)]
+test_move_call_sub:
+    ;; Receive a half-word in @2 and a byte in @3 and check if they're what we expect
+    XORI @5, @2, #0x5678 ;; Check if the half-word was truncated and zero-extended
+    BNZ move_call_sub_fail, @5
+
+    XORI @6, @3, #171    ;; Check if the byte was zero-extended
+    BNZ move_call_sub_fail, @6
+
+    LDI.W @4, #0         ;; Set success flag in return value slot (slot 4)
+    RET
+move_call_sub_fail:
+    LDI.W @4, #1         ;; Set failure flag in return value slot (slot 4)
+    RET
+
+;; Helper function to test MEMCPY and MEMSET
+#[framesize(0x10)]
+test_move_call_block:
+    ;; Receive the copied words in @2-@3 and the set words in @4-@5
+    XORI @7, @2, #4242   ;; Check if the 1st copied word is correct
+    BNZ move_call_block_fail, @7
+
+    XORI @8, @3, #4343   ;; Check if the 2nd copied word is correct
+    BNZ move_call_block_fail, @8
+
+    XORI @9, @4, #7      ;; Check if the 1st set word is correct
+    BNZ move_call_block_fail, @9
+
+    XORI @10, @5, #7     ;; Check if the 2nd set word is correct
+    BNZ move_call_block_fail, @10
+
+    LDI.W @6, #0         ;; Set success flag in return value slot (slot 6)
+    RET
+move_call_block_fail:
+    LDI.W @6, #1         ;; Set failure flag in return value slot (slot 6)
+    RET
+
 ;; ============================================================================
 ;; JUMPS AND BRANCHES
 ;; ============================================================================
@@ -1000,14 +1297,154 @@ test_simple_fn:
 ;; EFFECT:
 ;;   fp[dst] = fp ^ imm
 ;; ============================================================================
-#[framesize(0x5)]
+#[framesize(0x7)]
 test_fp:
     FP @3, #1       ;; Set to FP[1] = 48 + 1
     XORI @4, @3, #49
     BNZ fp_fail, @4
+
+    ;; Assertions abort the execution when they fail
+    ASSERT_ZERO @4
+    ASSERT_EQ @3, @3
+    RANGE_CHECK @3, #8
+    RANGE_CHECK @3, #16
+    COMMIT @3       ;; Append FP[3] to the public outputs
+    NOP             ;; Does nothing
+
+    ;; Call the RAM test
+    ALLOCI! @5, #24
+    CALLI test_ram, @5
+    MVV.W @5[2], @6
+    BNZ fp_fail, @6
+
     LDI.W @2, #0    ;; Set success flag (0 = success)
     RET
 fp_fail:
     LDI.W @2, #1    ;; Set failure flag (1 = failure)
     RET
 
+;; ============================================================================
+;; RAM INSTRUCTIONS
+;;
+;; FORMAT:
+;;   LW dst, base, offset       (Load Word)
+;;   LH/LHU dst, base, offset   (Load Half-word, sign-/zero-extended)
+;;   LB/LBU dst, base, offset   (Load Byte, sign-/zero-extended)
+;;   SW src, base, offset       (Store Word)
+;;   SH src, base, offset       (Store the low Half-word)
+;;   SB src, base, offset       (Store the low Byte)
+;;
+;; DESCRIPTION:
+;;   Access the byte-addressable RAM at address fp[base] + offset, with a
+;;   sign-extended offset. Accesses must be aligned on their width.
+;;
+;; EFFECT:
+;;   fp[dst] = Extend(RAM[fp[base] + offset])
+;;   RAM[fp[base] + offset] = Truncate(fp[src])
+;; ============================================================================
+#[framesize(0x18)]
+test_ram:
+    LDI.W @3, #64           ;; Base address
+    LDI.W @4, #2164227841   ;; 0x80FF7F01
+    SW @4, @3, #0           ;; RAM[64..68] = 0x80FF7F01
+    LW @5, @3, #0
+    XOR @6, @5, @4          ;; Check result
+    BNZ ram_fail, @6
+
+    LB @7, @3, #2           ;; 0xFF is sign-extended to 0xFFFFFFFF
+    LDI.W @8, #4294967295
+    XOR @9, @7, @8          ;; Check result
+    BNZ ram_fail, @9
+
+    LBU @10, @3, #1         ;; 0x7F is zero-extended to 127
+    XORI @11, @10, #127     ;; Check result
+    BNZ ram_fail, @11
+
+    LH @12, @3, #2          ;; 0x80FF is sign-extended to 0xFFFF80FF
+    LDI.W @13, #4294934783
+    XOR @14, @12, @13       ;; Check result
+    BNZ ram_fail, @14
+
+    LHU @15, @3, #0         ;; 0x7F01 is zero-extended to 32513
+    XORI @16, @15, #32513   ;; Check result
+    BNZ ram_fail, @16
+
+    SB @4, @3, #5           ;; RAM[69] = 0x01
+    LW @17, @3, #4          ;; RAM[68..72] = 0x00000100
+    XORI @18, @17, #256     ;; Check result
+    BNZ ram_fail, @18
+
+    SH @4, @3, #10          ;; RAM[74..76] = 0x7F01
+    LW @19, @3, #8          ;; RAM[72..76] = 0x7F010000
+    LDI.W @20, #2130771968
+    XOR @21, @19, @20       ;; Check result
+    BNZ ram_fail, @21
+
+    ;; Call the comparison branches and CMOV test
+    ALLOCI! @22, #11
+    CALLI test_branch_cmp, @22
+    MVV.W @22[2], @23
+    BNZ ram_fail, @23
+
+    LDI.W @2, #0            ;; Set success flag (0 = success)
+    RET
+ram_fail:
+    LDI.W @2, #1            ;; Set failure flag (1 = failure)
+    RET
+
+;; ============================================================================
+;; COMPARISON BRANCHES AND CONDITIONAL MOVE
+;;
+;; FORMAT:
+;;   BEQ target, a, b           (Branch if Equal)
+;;   BNE target, a, b           (Branch if Not Equal)
+;;   BLT target, a, b           (Branch if Less Than, signed)
+;;   BGE target, a, b           (Branch if Greater or Equal, signed)
+;;   CMOV dst, cond, src1, src2 (Conditional Move)
+;;
+;; DESCRIPTION:
+;;   Branch to target when the comparison of fp[a] and fp[b] holds, or select
+;;   one of two values without branching.
+;;
+;; EFFECT:
+;;   if fp[a] op fp[b] then PC = target
+;;   else PC = PC * G (next instruction)
+;;   fp[dst] = if fp[cond] != 0 then fp[src1] else fp[src2]
+;; ============================================================================
+#[framesize(0xb)]
+test_branch_cmp:
+    LDI.W @3, #1
+    LDI.W @4, #2
+    LDI.W @5, #4294967295   ;; -1
+
+    BEQ cmp_fail, @3, @4    ;; Not taken: 1 != 2
+    BEQ beq_taken, @3, @3   ;; Taken: 1 == 1
+    J cmp_fail
+beq_taken:
+    BNE cmp_fail, @3, @3    ;; Not taken: 1 == 1
+    BNE bne_taken, @3, @4   ;; Taken: 1 != 2
+    J cmp_fail
+bne_taken:
+    BLT cmp_fail, @4, @3    ;; Not taken: 2 >= 1
+    BLT cmp_fail, @3, @5    ;; Not taken: 1 >= -1 as signed integers
+    BLT blt_taken, @5, @3   ;; Taken: -1 < 1 as signed integers
+    J cmp_fail
+blt_taken:
+    BGE cmp_fail, @5, @3    ;; Not taken: -1 < 1 as signed integers
+    BGE bge_taken, @3, @3   ;; Taken: 1 >= 1
+    J cmp_fail
+bge_taken:
+    CMOV @6, @3, @4, @5     ;; fp[3] != 0, so fp[6] = fp[4]
+    XORI @7, @6, #2         ;; Check result
+    BNZ cmp_fail, @7
+
+    LDI.W @8, #0
+    CMOV @9, @8, @4, @3     ;; fp[8] == 0, so fp[9] = fp[3]
+    XORI @10, @9, #1        ;; Check result
+    BNZ cmp_fail, @10
+
+    LDI.W @2, #0            ;; Set success flag (0 = success)
+    RET
+cmp_fail:
+    LDI.W @2, #1            ;; Set failure flag (1 = failure)
+    RET
@@ -0,0 +1,505 @@
+//! C bindings to PetraVM.
+//!
+//! This crate exposes an `extern "C"` API to assemble, execute and prove
+//! PetraVM programs, so that non-Rust stacks can embed the VM. Programs and
+//! executions are opaque handles, while traces, proofs and claims are
+//! exchanged as serialized byte buffers. A C header can be generated with
+//! `cbindgen`.
+//!
+//! All fallible functions return a [`PetraStatus`]. On failure, a description
+//! of the error can be retrieved with [`petravm_last_error`].
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use petravm_asm::{isa::GenericISA, AssembledProgram, Assembler};
+use petravm_prover::{
+    model::{Claim, Trace},
+    prover::{verify, Prover},
+    serialization::{deserialize_proof, serialize_proof},
+};
+
+/// Status returned by the fallible functions of this crate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PetraStatus {
+    Ok = 0,
+    /// A required pointer argument is null.
+    NullPointer,
+    /// The source code is not valid UTF-8.
+    InvalidUtf8,
+    AssemblyFailed,
+    ExecutionFailed,
+    /// The serialized trace is invalid.
+    DeserializationFailed,
+    ProvingFailed,
+    VerificationFailed,
+    /// The call panicked.
+    Panic,
+}
+
+/// An assembled program, to be released with [`petravm_program_free`].
+pub struct PetraProgram(AssembledProgram);
+
+/// The execution trace of a program, to be released with
+/// [`petravm_execution_free`].
+pub struct PetraExecution(Trace);
+
+/// A byte buffer allocated by this crate, to be released with
+/// [`petravm_buffer_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct PetraBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl PetraBuffer {
+    fn new(bytes: Vec<u8>) -> Self {
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Error of a call, with the status to return.
+struct Failure {
+    status: PetraStatus,
+    message: String,
+}
+
+impl Failure {
+    fn new(status: PetraStatus, message: impl Display) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    fn null_pointer() -> Self {
+        Self::new(PetraStatus::NullPointer, "Null pointer argument")
+    }
+}
+
+/// Runs `f`, catching its panics, and records its error, if any, as the last
+/// error of the thread.
+fn ffi_call(f: impl FnOnce() -> Result<(), Failure>) -> PetraStatus {
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        Err(Failure::new(PetraStatus::Panic, message))
+    });
+
+    match result {
+        Ok(()) => PetraStatus::Ok,
+        Err(failure) => {
+            let message = CString::new(failure.message.replace('\0', " ")).ok();
+            LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+            failure.status
+        }
+    }
+}
+
+/// Returns the slice of `len` values starting at `data`, which may be null if
+/// `len` is 0.
+///
+/// # Safety
+/// `data` must point to `len` readable values, valid for `'a`.
+unsafe fn array<'a, T>(data: *const T, len: usize) -> Result<&'a [T], Failure> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if data.is_null() {
+        return Err(Failure::null_pointer());
+    }
+    Ok(unsafe { slice::from_raw_parts(data, len) })
+}
+
+/// Returns the message of the last error of the calling thread, or null if
+/// no call failed.
+///
+/// The message remains valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn petravm_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Assembles the source code `code` into `*program`.
+///
+/// # Safety
+/// `code` must be a NUL-terminated string, and `program` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_assemble(
+    code: *const c_char,
+    program: *mut *mut PetraProgram,
+) -> PetraStatus {
+    ffi_call(|| {
+        if code.is_null() || program.is_null() {
+            return Err(Failure::null_pointer());
+        }
+        let code = unsafe { CStr::from_ptr(code) }
+            .to_str()
+            .map_err(|e| Failure::new(PetraStatus::InvalidUtf8, e))?;
+        let assembled =
+            Assembler::from_code(code).map_err(|e| Failure::new(PetraStatus::AssemblyFailed, e))?;
+        unsafe { *program = Box::into_raw(Box::new(PetraProgram(assembled))) };
+        Ok(())
+    })
+}
+
+/// Releases a program returned by [`petravm_assemble`]. Null is ignored.
+///
+/// # Safety
+/// `program` must be null or returned by [`petravm_assemble`], and not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_program_free(program: *mut PetraProgram) {
+    if !program.is_null() {
+        drop(unsafe { Box::from_raw(program) });
+    }
+}
+
+/// Executes `program` into `*execution`, with the VROM initialized with the
+/// `init_values_len` values at `init_values`.
+///
+/// Without initial values, the return PC and FP of the entry frame default to
+/// 0.
+///
+/// # Safety
+/// `program` must be a live program, `init_values` must point to
+/// `init_values_len` values, and `execution` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_run(
+    program: *const PetraProgram,
+    init_values: *const u32,
+    init_values_len: usize,
+    execution: *mut *mut PetraExecution,
+) -> PetraStatus {
+    ffi_call(|| {
+        if program.is_null() || execution.is_null() {
+            return Err(Failure::null_pointer());
+        }
+        let program = unsafe { &(*program).0 };
+        let init_values: &[u32] = match init_values_len {
+            0 => &[0, 0],
+            _ if init_values.is_null() => return Err(Failure::null_pointer()),
+            _ => unsafe { slice::from_raw_parts(init_values, init_values_len) },
+        };
+        let trace = Trace::from_program(program.clone(), init_values, Box::new(GenericISA))
+            .map_err(|e| Failure::new(PetraStatus::ExecutionFailed, e))?;
+        unsafe { *execution = Box::into_raw(Box::new(PetraExecution(trace))) };
+        Ok(())
+    })
+}
+
+/// Returns the exit code of an execution.
+///
+/// # Safety
+/// `execution` must be a live execution.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_execution_exit_code(execution: *const PetraExecution) -> u32 {
+    unsafe { (*execution).0.exit_code() }
+}
+
+/// Serializes an execution into `*bytes`, to be proven in another process.
+///
+/// # Safety
+/// `execution` must be a live execution, and `bytes` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_execution_to_bytes(
+    execution: *const PetraExecution,
+    bytes: *mut PetraBuffer,
+) -> PetraStatus {
+    ffi_call(|| {
+        if execution.is_null() || bytes.is_null() {
+            return Err(Failure::null_pointer());
+        }
+        let serialized = unsafe { &(*execution).0 }
+            .to_bytes()
+            .map_err(|e| Failure::new(PetraStatus::ExecutionFailed, e))?;
+        unsafe { *bytes = PetraBuffer::new(serialized) };
+        Ok(())
+    })
+}
+
+/// Deserializes an execution serialized by [`petravm_execution_to_bytes`]
+/// into `*execution`.
+///
+/// # Safety
+/// `data` must point to `len` bytes, and `execution` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_execution_from_bytes(
+    data: *const u8,
+    len: usize,
+    execution: *mut *mut PetraExecution,
+) -> PetraStatus {
+    ffi_call(|| {
+        if execution.is_null() {
+            return Err(Failure::null_pointer());
+        }
+        let trace = Trace::from_bytes(unsafe { array(data, len) }?)
+            .map_err(|e| Failure::new(PetraStatus::DeserializationFailed, e))?;
+        unsafe { *execution = Box::into_raw(Box::new(PetraExecution(trace))) };
+        Ok(())
+    })
+}
+
+/// Releases an execution. Null is ignored.
+///
+/// # Safety
+/// `execution` must be null or returned by this crate, and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_execution_free(execution: *mut PetraExecution) {
+    if !execution.is_null() {
+        drop(unsafe { Box::from_raw(execution) });
+    }
+}
+
+/// Proves an execution, writing the serialized proof and the claim of the
+/// proven execution, to be checked with [`petravm_verify`].
+///
+/// # Safety
+/// `execution` must be a live execution, and `proof` and `claim` must be valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_prove(
+    execution: *const PetraExecution,
+    proof: *mut PetraBuffer,
+    claim: *mut PetraBuffer,
+) -> PetraStatus {
+    ffi_call(|| {
+        if execution.is_null() || proof.is_null() || claim.is_null() {
+            return Err(Failure::null_pointer());
+        }
+        let failed = |e: anyhow::Error| Failure::new(PetraStatus::ProvingFailed, e);
+
+        let trace = unsafe { &(*execution).0 };
+        let proven = Prover::new(Box::new(GenericISA))
+            .prove(trace)
+            .map_err(failed)?;
+
+        let proof_bytes = serialize_proof(&proven).map_err(failed)?;
+        let claim_bytes = trace
+            .claim()
+            .and_then(|claim| claim.to_bytes())
+            .map_err(failed)?;
+        unsafe {
+            *proof = PetraBuffer::new(proof_bytes);
+            *claim = PetraBuffer::new(claim_bytes);
+        }
+        Ok(())
+    })
+}
+
+/// Verifies a proof returned by [`petravm_prove`], given its serialized
+/// claim, that `program` ran from the `init_values_len` values at
+/// `init_values`, committed the `outputs_len` values at `outputs` and exited
+/// with `exit_code`.
+///
+/// The verification key and the boundaries of the proven statement are built
+/// locally, from `program` and the claim, whose inputs, outputs and exit code
+/// are replaced by the expected ones. Without initial values, the return PC
+/// and FP of the entry frame default to 0, as in [`petravm_run`].
+///
+/// # Safety
+/// `program` must be a live program, and each other pointer must point to
+/// the given number of values.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn petravm_verify(
+    program: *const PetraProgram,
+    init_values: *const u32,
+    init_values_len: usize,
+    outputs: *const u32,
+    outputs_len: usize,
+    exit_code: u32,
+    claim: *const u8,
+    claim_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> PetraStatus {
+    ffi_call(|| {
+        if program.is_null() {
+            return Err(Failure::null_pointer());
+        }
+        let program = unsafe { &(*program).0 };
+        let init_values: &[u32] = match init_values_len {
+            0 => &[0, 0],
+            _ => unsafe { array(init_values, init_values_len) }?,
+        };
+        let outputs = unsafe { array(outputs, outputs_len) }?;
+        let claim = unsafe { array(claim, claim_len) }?;
+        let proof = unsafe { array(proof, proof_len) }?;
+
+        let failed = |e: anyhow::Error| Failure::new(PetraStatus::VerificationFailed, e);
+        let claim = Claim {
+            init_values: init_values.to_vec(),
+            outputs: outputs.to_vec(),
+            exit_code,
+            ..Claim::from_bytes(claim).map_err(failed)?
+        };
+        let prover = Prover::new(Box::new(GenericISA));
+        let vk = prover.verifying_key().map_err(failed)?;
+        let boundaries = prover.claim_boundaries(program, &claim).map_err(failed)?;
+        verify(&vk, &boundaries, deserialize_proof(proof).map_err(failed)?).map_err(failed)
+    })
+}
+
+/// Releases a buffer returned by this crate. Empty buffers are ignored.
+///
+/// # Safety
+/// `buffer` must have been returned by this crate, and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn petravm_buffer_free(buffer: PetraBuffer) {
+    if !buffer.data.is_null() {
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::null_mut;
+
+    use super::*;
+
+    const CODE: &CStr = c"#[framesize(0x10)]
+        _start:
+            LDI.W @2, #7
+            ADDI @3, @2, #5
+            RET
+        ";
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(petravm_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn empty_buffer() -> PetraBuffer {
+        PetraBuffer {
+            data: null_mut(),
+            len: 0,
+        }
+    }
+
+    #[test]
+    fn test_assemble_run_prove_verify() {
+        unsafe {
+            let mut program = null_mut();
+            assert_eq!(
+                petravm_assemble(CODE.as_ptr(), &mut program),
+                PetraStatus::Ok
+            );
+
+            let mut execution = null_mut();
+            assert_eq!(
+                petravm_run(program, ptr::null(), 0, &mut execution),
+                PetraStatus::Ok
+            );
+            assert_eq!(petravm_execution_exit_code(execution), 0);
+
+            // The execution can be proven in another process.
+            let mut trace = empty_buffer();
+            assert_eq!(
+                petravm_execution_to_bytes(execution, &mut trace),
+                PetraStatus::Ok
+            );
+            petravm_execution_free(execution);
+            let mut execution = null_mut();
+            assert_eq!(
+                petravm_execution_from_bytes(trace.data, trace.len, &mut execution),
+                PetraStatus::Ok
+            );
+            petravm_buffer_free(trace);
+
+            let (mut proof, mut claim) = (empty_buffer(), empty_buffer());
+            assert_eq!(
+                petravm_prove(execution, &mut proof, &mut claim),
+                PetraStatus::Ok
+            );
+            petravm_execution_free(execution);
+
+            let verify = |init_values: &[u32], exit_code, proof: &PetraBuffer| {
+                petravm_verify(
+                    program,
+                    init_values.as_ptr(),
+                    init_values.len(),
+                    ptr::null(),
+                    0,
+                    exit_code,
+                    claim.data,
+                    claim.len,
+                    proof.data,
+                    proof.len,
+                )
+            };
+            assert_eq!(verify(&[], 0, &proof), PetraStatus::Ok);
+            assert_eq!(verify(&[0, 0], 0, &proof), PetraStatus::Ok);
+            assert_eq!(
+                verify(
+                    &[],
+                    0,
+                    &PetraBuffer {
+                        data: proof.data,
+                        len: proof.len / 2,
+                    }
+                ),
+                PetraStatus::VerificationFailed
+            );
+
+            // The proof is bound to the expected inputs and exit code.
+            assert_eq!(verify(&[0, 1], 0, &proof), PetraStatus::VerificationFailed);
+            assert_eq!(verify(&[], 1, &proof), PetraStatus::VerificationFailed);
+
+            petravm_program_free(program);
+            petravm_buffer_free(proof);
+            petravm_buffer_free(claim);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            let mut program = null_mut();
+            assert_eq!(
+                petravm_assemble(ptr::null(), &mut program),
+                PetraStatus::NullPointer
+            );
+            assert_eq!(last_error(), "Null pointer argument");
+
+            assert_eq!(
+                petravm_assemble(c"_start: UNKNOWN @2".as_ptr(), &mut program),
+                PetraStatus::AssemblyFailed
+            );
+            assert!(program.is_null());
+            assert!(!last_error().is_empty());
+
+            let mut execution = null_mut();
+            assert_eq!(
+                petravm_execution_from_bytes(b"garbage".as_ptr(), 7, &mut execution),
+                PetraStatus::DeserializationFailed
+            );
+            assert_eq!(last_error(), "Not a serialized PetraVM trace");
+        }
+    }
+}
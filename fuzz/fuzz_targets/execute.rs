@@ -0,0 +1,10 @@
+//! Runs arbitrary PROMs on arbitrary initial VROM values.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: (Vec<u32>, Vec<u8>)| {
+    let (inputs, program) = data;
+    petravm_asm::fuzz::fuzz_execute(&program, &inputs);
+});
@@ -0,0 +1,7 @@
+//! Assembles arbitrary source code.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| petravm_asm::fuzz::fuzz_parse(data));
@@ -0,0 +1,60 @@
+//! Execution of the Collatz and Fibonacci examples by the interpreter, with
+//! and without recording the events of the trace.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use petravm_asm::{isa::GenericISA, AssembledProgram, Memory, PetraTrace, ValueRom};
+use petravm_prover::test_utils::{assemble_examples, fibonacci, fibonacci_init_values};
+
+const SAMPLE_SIZE: usize = 10;
+
+/// Benchmarks the execution of `program` with the initial VROM values
+/// `init_values`, `param` identifying them in the group `name`.
+fn bench_execution(
+    c: &mut Criterion,
+    name: &str,
+    param: u32,
+    program: &AssembledProgram,
+    init_values: &[u32],
+) {
+    let memory = || {
+        Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(init_values),
+        )
+    };
+
+    let mut group = c.benchmark_group(format!("Interpreter {name}"));
+    group.sample_size(SAMPLE_SIZE);
+    group.bench_with_input(BenchmarkId::new("Generate", param), &param, |b, _| {
+        b.iter(|| {
+            PetraTrace::generate(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+                .expect("Trace generation failed")
+        });
+    });
+    group.bench_with_input(BenchmarkId::new("Execute", param), &param, |b, _| {
+        b.iter(|| {
+            PetraTrace::execute(Box::new(GenericISA), memory(), program.frame_sizes.clone())
+                .expect("Execution failed")
+        });
+    });
+    group.finish();
+}
+
+fn bench_fibonacci(c: &mut Criterion) {
+    let program = assemble_examples(&["fib.asm"]).expect("The program should assemble");
+    for n in [1_000, 10_000] {
+        let init_values = fibonacci_init_values(n, fibonacci(n));
+        bench_execution(c, "Fibonacci", n, &program, &init_values);
+    }
+}
+
+fn bench_collatz(c: &mut Criterion) {
+    let program = assemble_examples(&["collatz.asm"]).expect("The program should assemble");
+    // Starting values with the longest sequences below 10^5 and 10^6.
+    for n in [77_031, 837_799] {
+        bench_execution(c, "Collatz", n, &program, &[0, 0, n]);
+    }
+}
+
+criterion_group!(benches, bench_fibonacci, bench_collatz);
+criterion_main!(benches);
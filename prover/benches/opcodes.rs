@@ -89,6 +89,12 @@ fn format_instruction(opcode: Opcode, dst: usize, src1: usize, src2: usize, imm:
         Mulu => format!("MULU   @{dst}, @{src1}, @{src2}"),
         Mulsu => format!("MULSU  @{dst}, @{src1}, @{src2}"),
 
+        // Division
+        Div => format!("DIV    @{dst}, @{src1}, @{src2}"),
+        Divu => format!("DIVU   @{dst}, @{src1}, @{src2}"),
+        Rem => format!("REM    @{dst}, @{src1}, @{src2}"),
+        Remu => format!("REMU   @{dst}, @{src1}, @{src2}"),
+
         // Comparisons
         Slt => format!("SLT    @{dst}, @{src1}, @{src2}"),
         Slti => format!("SLTI   @{dst}, @{src1}, #{imm}"),
@@ -129,6 +135,10 @@ fn all_opcodes() -> &'static [Opcode] {
         Opcode::Muli,
         Opcode::Mulu,
         Opcode::Mulsu,
+        Opcode::Div,
+        Opcode::Divu,
+        Opcode::Rem,
+        Opcode::Remu,
         Opcode::Slt,
         Opcode::Slti,
         Opcode::Sltu,
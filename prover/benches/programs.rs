@@ -0,0 +1,34 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use petravm_prover::bench::criterion_bench;
+use petravm_prover::test_utils::{fibonacci, fibonacci_init_values};
+
+/// Reads the file `file_name` located in the `examples` directory.
+fn read_example(file_name: &str) -> String {
+    let path = format!("{}/../examples/{file_name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(path).expect("Failed to read the example")
+}
+
+fn bench_fibonacci(c: &mut Criterion) {
+    let n = 1_000;
+    criterion_bench(
+        c,
+        "Program Fibonacci",
+        &read_example("fib.asm"),
+        &fibonacci_init_values(n, fibonacci(n)),
+    )
+    .expect("Fibonacci benchmark failed");
+}
+
+fn bench_collatz(c: &mut Criterion) {
+    // Return PC, return FP, and the starting value of the sequence.
+    criterion_bench(
+        c,
+        "Program Collatz",
+        &read_example("collatz.asm"),
+        &[0, 0, 77_031],
+    )
+    .expect("Collatz benchmark failed");
+}
+
+criterion_group!(benches, bench_fibonacci, bench_collatz);
+criterion_main!(benches);
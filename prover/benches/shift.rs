@@ -63,11 +63,11 @@ fn bench_shifts(c: &mut Criterion) {
 
         group.bench_with_input(BenchmarkId::new("Prove", n), &n, |b, _n_val| {
             b.iter(|| {
-                let (_proof, _statement, _compiled_cs) = prover.prove(&trace).unwrap();
+                let _proof = prover.prove(&trace).unwrap();
             });
         });
 
-        let (proof, statement, compiled_cs) = prover.prove(&trace).unwrap();
+        let (proof, statement, compiled_cs) = prover.prove_with_statement(&trace).unwrap();
 
         group.bench_with_input(BenchmarkId::new("Verify", n), &n, |b, _n_val| {
             b.iter(|| {
@@ -0,0 +1,95 @@
+//! Trace generation of programs stressing the VROM: one allocating large
+//! frames, to compare the memory held by the VROM with the size of its address
+//! space, and one moving blocks of words, to measure the batched accesses.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use petravm_asm::{isa::GenericISA, Assembler, Memory, PetraTrace, ValueRom};
+
+const SAMPLE_SIZE: usize = 10;
+
+/// Recurses down to the depth in slot 2, allocating a frame of 16K slots per
+/// call but only writing a few of them.
+const DEEP_FRAMES: &str = "
+#[framesize(0x4000)]
+recurse:
+    BNZ recurse_deeper, @2
+    RET
+recurse_deeper:
+    ALLOCI! @3, #16384
+    ADDI @4, @2, #-1
+    MVV.W @3[2], @4
+    CALLI recurse, @3
+    RET
+";
+
+/// Fills a block of 256 words with the counter in slot 2 and copies it to
+/// another block, then recurses until the counter reaches zero.
+const BULK_MOVES: &str = "
+#[framesize(0x10)]
+copy:
+    BNZ copy_more, @2
+    RET
+copy_more:
+    ALLOCI! @3, #256
+    ALLOCI! @4, #256
+    MEMSET @3, @2, #256
+    MEMCPY @4, @3, #256
+    ALLOCI! @5, #16
+    ADDI @6, @2, #-1
+    MVV.W @5[2], @6
+    CALLI copy, @5
+    RET
+";
+
+fn bench_deep_frames(c: &mut Criterion) {
+    let program = Assembler::from_code(DEEP_FRAMES).expect("The program should assemble");
+    let generate = |depth: u32| {
+        let memory = Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(&[0, 0, depth]),
+        );
+        let (trace, _) =
+            PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes.clone())
+                .expect("Trace generation failed");
+        trace
+    };
+
+    let mut group = c.benchmark_group("VROM deep frames");
+    group.sample_size(SAMPLE_SIZE);
+    for depth in [64, 512] {
+        let trace = generate(depth);
+        println!(
+            "Depth {depth}: {} words allocated, {} words resident",
+            trace.vrom_size(),
+            trace.vrom().resident_words()
+        );
+        group.bench_with_input(BenchmarkId::new("Generate", depth), &depth, |b, &depth| {
+            b.iter(|| generate(depth));
+        });
+    }
+    group.finish();
+}
+
+fn bench_bulk_moves(c: &mut Criterion) {
+    let program = Assembler::from_code(BULK_MOVES).expect("The program should assemble");
+    let generate = |count: u32| {
+        let memory = Memory::new(
+            program.prom.clone(),
+            ValueRom::new_with_init_vals(&[0, 0, count]),
+        );
+        PetraTrace::generate(Box::new(GenericISA), memory, program.frame_sizes.clone())
+            .expect("Trace generation failed")
+    };
+
+    let mut group = c.benchmark_group("VROM bulk moves");
+    group.sample_size(SAMPLE_SIZE);
+    for count in [16, 128] {
+        group.bench_with_input(BenchmarkId::new("Generate", count), &count, |b, &count| {
+            b.iter(|| generate(count));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_deep_frames, bench_bulk_moves);
+criterion_main!(benches);
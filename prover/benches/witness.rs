@@ -0,0 +1,53 @@
+use binius_compute::cpu::alloc::CpuComputeAllocator;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use petravm_asm::isa::GenericISA;
+use petravm_prover::model::Trace;
+use petravm_prover::prover::Prover;
+use petravm_prover::test_utils::{fibonacci, generate_collatz_trace, generate_fibonacci_trace};
+
+const SAMPLE_SIZE: usize = 10;
+
+/// Benchmarks the witness generation of `trace`, with a single thread and with
+/// the default thread pool, to measure the speedup of the parallel filling.
+fn bench_witness(c: &mut Criterion, name: &str, param: u32, trace: &Trace) {
+    let mut group = c.benchmark_group(format!("Witness {name}"));
+    group.sample_size(SAMPLE_SIZE);
+
+    let prover = Prover::new(Box::new(GenericISA));
+    let single_thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("Failed to build the thread pool");
+
+    let generate_witness = || {
+        let mut allocator = CpuComputeAllocator::new(1 << 25);
+        let allocator = allocator.into_bump_allocator();
+        prover.generate_witness(trace, &allocator).unwrap();
+    };
+
+    group.bench_with_input(BenchmarkId::new("Sequential", param), &param, |b, _| {
+        b.iter(|| single_thread_pool.install(generate_witness));
+    });
+    group.bench_with_input(BenchmarkId::new("Parallel", param), &param, |b, _| {
+        b.iter(generate_witness);
+    });
+    group.finish();
+}
+
+fn bench_fibonacci_witness(c: &mut Criterion) {
+    for n in [1_000, 10_000] {
+        let trace = generate_fibonacci_trace(n, fibonacci(n)).expect("Trace generation failed");
+        bench_witness(c, "Fibonacci", n, &trace);
+    }
+}
+
+fn bench_collatz_witness(c: &mut Criterion) {
+    // Starting values with the longest sequences below 10^5 and 10^6.
+    for n in [77_031, 837_799] {
+        let trace = generate_collatz_trace(n).expect("Trace generation failed");
+        bench_witness(c, "Collatz", n, &trace);
+    }
+}
+
+criterion_group!(benches, bench_fibonacci_witness, bench_collatz_witness);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Build script of the prover, exposing a hash of its sources as the
+//! `PETRAVM_PROVER_SOURCE_HASH` environment variable.
+//!
+//! The hash is part of the digest of the circuits, which keys the compiled
+//! constraint systems cached across runs: the constraints of the tables are
+//! not reachable from the constraint system builder, so any change to the
+//! sources defining them must invalidate the cached constraint systems.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// Prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Appends the paths of the files under `dir` to `files`, recursively.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut files = Vec::new();
+    collect_files(&manifest_dir.join("src"), &mut files)?;
+    files.sort();
+    println!("cargo:rerun-if-changed=src");
+
+    // The workspace manifest pins the revision of the Binius crates, whose
+    // constraints are compiled along with those of the prover.
+    let workspace_manifest = manifest_dir.join("../Cargo.toml");
+    if workspace_manifest.exists() {
+        files.push(workspace_manifest);
+        println!("cargo:rerun-if-changed=../Cargo.toml");
+    }
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for path in &files {
+        let relative = path.strip_prefix(&manifest_dir).unwrap_or(path);
+        hash = fnv1a(hash, relative.to_string_lossy().as_bytes());
+        hash = fnv1a(hash, &fs::read(path)?);
+    }
+    println!("cargo:rustc-env=PETRAVM_PROVER_SOURCE_HASH={hash:016x}");
+    Ok(())
+}
@@ -28,7 +28,7 @@ fn main() -> Result<()> {
     trace.validate()?;
 
     let prover = Prover::new(Box::new(GenericISA));
-    let (proof, statement, compiled_cs) = prover.prove(&trace)?;
+    let (proof, statement, compiled_cs) = prover.prove_with_statement(&trace)?;
 
     verify_proof(&statement, &compiled_cs, proof)?;
 
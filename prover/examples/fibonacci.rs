@@ -2,8 +2,8 @@ use anyhow::Result;
 use clap::{value_parser, Parser};
 use petravm_asm::isa::GenericISA;
 use petravm_prover::{
-    prover::{verify_proof, Prover},
-    test_utils::{fibonacci, generate_fibonacci_trace},
+    prover::{verify, Prover},
+    test_utils::{assemble_examples, fibonacci, fibonacci_init_values, generate_fibonacci_trace},
 };
 
 #[derive(Debug, Parser)]
@@ -25,9 +25,12 @@ fn main() -> Result<()> {
     trace.validate()?;
 
     let prover = Prover::new(Box::new(GenericISA));
-    let (proof, statement, compiled_cs) = prover.prove(&trace)?;
+    let proof = prover.prove(&trace)?;
 
-    verify_proof(&statement, &compiled_cs, proof)?;
+    let vk = prover.verifying_key()?;
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let init_values = fibonacci_init_values(args.n, res);
+    verify(&vk, &prover.boundaries(&program, &init_values), proof)?;
 
     Ok(())
 }
@@ -0,0 +1,207 @@
+//! Benchmarks of guest programs.
+//!
+//! This module is enabled by the `bench` feature, for downstream crates to
+//! track the performance of their programs across PetraVM versions. Each
+//! program is benchmarked on its three stages:
+//!
+//! - `Trace`: executing the assembled program, generating its trace;
+//! - `Witness`: allocating and filling the witness from the trace;
+//! - `Prove`: proving the trace, from the statement to the proof.
+//!
+//! [`bench_program`] runs the stages a few times and returns their timings as
+//! a [`BenchResult`], to be stored or compared between versions, while
+//! [`criterion_bench`] registers the same stages as Criterion benchmarks, for
+//! `benches/` targets with statistics and regression reports.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use binius_compute::cpu::alloc::CpuComputeAllocator;
+use criterion::Criterion;
+use petravm_asm::{isa::GenericISA, AssembledProgram, Assembler};
+
+use crate::{model::Trace, prover::Prover};
+
+/// Number of runs of each stage in [`bench_program`].
+pub const DEFAULT_SAMPLES: usize = 10;
+
+/// Options of [`bench_program_with_options`].
+#[derive(Clone, Debug)]
+pub struct BenchOptions {
+    /// Number of runs of each stage.
+    pub samples: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            samples: DEFAULT_SAMPLES,
+        }
+    }
+}
+
+/// Durations of the runs of a stage.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timing {
+    /// Duration of each run, in running order.
+    pub samples: Vec<Duration>,
+}
+
+impl Timing {
+    /// Returns the shortest run.
+    pub fn min(&self) -> Duration {
+        self.samples.iter().copied().min().unwrap_or_default()
+    }
+
+    /// Returns the longest run.
+    pub fn max(&self) -> Duration {
+        self.samples.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Returns the mean duration of the runs.
+    pub fn mean(&self) -> Duration {
+        if self.samples.is_empty() {
+            return Duration::ZERO;
+        }
+        self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+    }
+
+    /// Returns the median duration of the runs.
+    pub fn median(&self) -> Duration {
+        let mut samples = self.samples.clone();
+        samples.sort();
+        samples.get(samples.len() / 2).copied().unwrap_or_default()
+    }
+}
+
+/// Timings of a benchmarked program, see the [module-level
+/// documentation](self).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BenchResult {
+    /// Number of cycles of the execution.
+    pub cycles: u64,
+    /// Number of rows of the witness filled from the trace, over all tables.
+    pub rows: usize,
+    /// Timing of the trace generation.
+    pub trace: Timing,
+    /// Timing of the witness filling.
+    pub witness: Timing,
+    /// Timing of the proving, witness filling included.
+    pub prove: Timing,
+}
+
+impl fmt::Display for BenchResult {
+    /// Lists the median, minimum and maximum of each stage.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} cycles, {} rows", self.cycles, self.rows)?;
+        for (stage, timing) in [
+            ("Trace", &self.trace),
+            ("Witness", &self.witness),
+            ("Prove", &self.prove),
+        ] {
+            writeln!(
+                f,
+                "{stage:<12} {:>10.3} s (min {:.3} s, max {:.3} s)",
+                timing.median().as_secs_f64(),
+                timing.min().as_secs_f64(),
+                timing.max().as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Benchmarks the program `asm`, run with the initial VROM values `inputs`
+/// (return PC and FP included), with the default options.
+pub fn bench_program(asm: &str, inputs: &[u32]) -> Result<BenchResult> {
+    bench_program_with_options(asm, inputs, &BenchOptions::default())
+}
+
+/// Benchmarks the program `asm`, run with the initial VROM values `inputs`,
+/// with the given `options`.
+pub fn bench_program_with_options(
+    asm: &str,
+    inputs: &[u32],
+    options: &BenchOptions,
+) -> Result<BenchResult> {
+    let program = Assembler::from_code(asm)?;
+    let prover = Prover::new(Box::new(GenericISA));
+
+    let mut result = BenchResult::default();
+    for _ in 0..options.samples {
+        let start = Instant::now();
+        let trace = generate_trace(&program, inputs)?;
+        result.trace.samples.push(start.elapsed());
+
+        let (_, _, _, summary) = prover.prove_with_summary(&trace)?;
+        result.witness.samples.push(summary.witness);
+        result.prove.samples.push(summary.total());
+        result.cycles = trace.cycles();
+        result.rows = summary.total_rows();
+    }
+    Ok(result)
+}
+
+/// Registers the stages of the program `asm`, run with the initial VROM
+/// values `inputs`, as the benchmarks of the Criterion group `name`.
+///
+/// The program is run and proven once beforehand, so that failures are
+/// returned rather than panicking in the benchmarks.
+pub fn criterion_bench(c: &mut Criterion, name: &str, asm: &str, inputs: &[u32]) -> Result<()> {
+    let program = Assembler::from_code(asm)?;
+    let prover = Prover::new(Box::new(GenericISA));
+    let trace = generate_trace(&program, inputs)?;
+    let capacity = prover.estimate_memory(&trace)?.arena_capacity();
+    prover.prove(&trace)?;
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(DEFAULT_SAMPLES);
+    group.bench_function("Trace", |b| {
+        b.iter(|| generate_trace(&program, inputs).expect("Trace generation failed"));
+    });
+    group.bench_function("Witness", |b| {
+        b.iter(|| {
+            let mut allocator = CpuComputeAllocator::new(capacity);
+            let allocator = allocator.into_bump_allocator();
+            prover
+                .generate_witness(&trace, &allocator)
+                .expect("Witness generation failed");
+        });
+    });
+    group.bench_function("Prove", |b| {
+        b.iter(|| prover.prove(&trace).expect("Proving failed"));
+    });
+    group.finish();
+    Ok(())
+}
+
+/// Runs `program` with the initial VROM values `inputs`.
+fn generate_trace(program: &AssembledProgram, inputs: &[u32]) -> Result<Trace> {
+    Trace::from_program(program.clone(), inputs, Box::new(GenericISA))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{fibonacci, fibonacci_init_values};
+
+    #[test]
+    fn test_bench_program() -> Result<()> {
+        let asm =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/fib.asm"))?;
+        let options = BenchOptions { samples: 2 };
+        let result =
+            bench_program_with_options(&asm, &fibonacci_init_values(11, fibonacci(11)), &options)?;
+
+        assert!(result.cycles > 0);
+        assert!(result.rows > 0);
+        for timing in [&result.trace, &result.witness, &result.prove] {
+            assert_eq!(timing.samples.len(), 2);
+            assert!(timing.min() <= timing.median() && timing.median() <= timing.max());
+        }
+        assert!(result.witness.max() <= result.prove.max());
+        assert!(result.to_string().contains("Witness"));
+        Ok(())
+    }
+}
@@ -0,0 +1,469 @@
+//! Command-line interface to assemble, run, prove and verify PetraVM programs.
+
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use petravm_asm::{
+    commit_outputs,
+    execution::{Evaluation, Repl},
+    export::Export,
+    isa::{GenericISA, ISA},
+    Abi, AssembledProgram, Assembler, AssemblerOptions, Disassembler, GenerationOptions, ValueRom,
+};
+use petravm_prover::{
+    cache::ProvingCache,
+    model::{Claim, Trace},
+    prover::{verify, Prover, VerifyingKey},
+    serialization::{deserialize_proof, serialize_proof},
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "petravm",
+    about = "Assemble, run, prove and verify PetraVM programs"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Assembles source files into a program binary.
+    Asm {
+        /// Assembly source files.
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
+        /// Output program binary.
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Drops the code unreachable from the entry point.
+        #[arg(long)]
+        eliminate_dead_code: bool,
+        /// Sorts the moves populating the frame of a call, merging them where
+        /// possible.
+        #[arg(long)]
+        schedule_moves: bool,
+        /// Renumbers the slots of the functions, shrinking their frames.
+        #[arg(long)]
+        compact_frames: bool,
+        /// Output file of the source location of every instruction, as JSON.
+        #[arg(long)]
+        debug_info: Option<PathBuf>,
+        /// Initial RAM image, loaded after the static data of the program.
+        #[arg(long)]
+        ram_image: Option<PathBuf>,
+        /// RAM address of the initial RAM image, in decimal or hexadecimal
+        /// (`0x` prefix).
+        #[arg(long, default_value = "0", value_parser = parse_addr, requires = "ram_image")]
+        ram_base: u32,
+    },
+    /// Disassembles a program.
+    Disasm {
+        /// Program, as a binary or an assembly source file (`.asm`).
+        program: PathBuf,
+    },
+    /// Prints the calling convention of the VM as JSON, for front-ends.
+    Abi,
+    /// Runs a program, optionally saving its trace to be proven.
+    Run {
+        /// Program, as a binary or an assembly source file (`.asm`).
+        program: PathBuf,
+        /// Initial VROM values, starting with the return PC and FP of the entry
+        /// frame, followed by the arguments of the program.
+        #[arg(short, long, value_delimiter = ',', default_values_t = vec![0, 0])]
+        init: Vec<u32>,
+        /// Output file of the serialized trace.
+        #[arg(short, long)]
+        trace: Option<PathBuf>,
+        /// Output file of the execution statistics, as JSON.
+        #[arg(long)]
+        stats: Option<PathBuf>,
+        /// Initial RAM image, loaded after the static data of the program.
+        #[arg(long)]
+        ram_image: Option<PathBuf>,
+        /// RAM address of the initial RAM image, in decimal or hexadecimal
+        /// (`0x` prefix).
+        #[arg(long, default_value = "0", value_parser = parse_addr, requires = "ram_image")]
+        ram_base: u32,
+        /// Advice tape read by `READ_ADVICE`, as little-endian 32-bit words.
+        #[arg(long)]
+        advice: Option<PathBuf>,
+    },
+    /// Runs a program and reports the sizes of the tables proving it, without
+    /// proving it.
+    Preflight {
+        /// Program, as a binary or an assembly source file (`.asm`).
+        program: PathBuf,
+        /// Initial VROM values, starting with the return PC and FP of the entry
+        /// frame, followed by the arguments of the program.
+        #[arg(short, long, value_delimiter = ',', default_values_t = vec![0, 0])]
+        init: Vec<u32>,
+        /// Advice tape read by `READ_ADVICE`, as little-endian 32-bit words.
+        #[arg(long)]
+        advice: Option<PathBuf>,
+        /// Fails if a table has more rows than this once padded.
+        #[arg(long)]
+        max_rows: Option<usize>,
+    },
+    /// Starts an interactive session, typing instructions line by line or
+    /// single-stepping a program.
+    Repl {
+        /// Program to single-step, as a binary or an assembly source file
+        /// (`.asm`). Without a program, typed instructions are executed right
+        /// away.
+        program: Option<PathBuf>,
+        /// Initial VROM values, starting with the return PC and FP of the entry
+        /// frame, followed by the arguments of the program.
+        #[arg(short, long, value_delimiter = ',', default_values_t = vec![0, 0])]
+        init: Vec<u32>,
+    },
+    /// Proves a trace saved by `run`.
+    Prove {
+        /// Serialized trace.
+        trace: PathBuf,
+        /// Output file of the proof.
+        #[arg(short, long, default_value = "proof.bin")]
+        proof: PathBuf,
+        /// Output file of the verification key.
+        #[arg(long, default_value = "vk.bin")]
+        vk: PathBuf,
+        /// Output file of the claim of the proof, i.e. the outcome of the
+        /// proven execution.
+        #[arg(long, default_value = "claim.bin")]
+        claim: PathBuf,
+        /// Maximum memory of the witness, in MiB.
+        #[arg(long)]
+        memory_limit_mib: Option<usize>,
+        /// Directory caching the compiled constraint system and the program
+        /// boundaries across runs.
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+    },
+    /// Verifies a proof generated by `prove`.
+    Verify {
+        /// Proof to verify.
+        #[arg(short, long, default_value = "proof.bin")]
+        proof: PathBuf,
+        /// Verification key exported by `prove`, checked against the one of
+        /// the circuit, which is built locally.
+        #[arg(long)]
+        vk: Option<PathBuf>,
+        /// Claim of the proof, exported by `prove`, from which the boundaries
+        /// of the proven statement are rebuilt along with the program.
+        #[arg(long, default_value = "claim.bin")]
+        claim: PathBuf,
+        /// Program the proof must be bound to, as a binary or an assembly
+        /// source file (`.asm`).
+        #[arg(long)]
+        program: PathBuf,
+        /// Initial RAM image the proof must be bound to, loaded after the
+        /// static data of the program.
+        #[arg(long)]
+        ram_image: Option<PathBuf>,
+        /// RAM address of the initial RAM image, in decimal or hexadecimal
+        /// (`0x` prefix).
+        #[arg(long, default_value = "0", value_parser = parse_addr, requires = "ram_image")]
+        ram_base: u32,
+        /// Initial VROM values the proof must be bound to, instead of the
+        /// claimed ones.
+        #[arg(short, long, value_delimiter = ',')]
+        init: Option<Vec<u32>>,
+        /// Public outputs, committed by `COMMIT`, the proof must be bound to,
+        /// instead of the claimed ones.
+        #[arg(long, value_delimiter = ',')]
+        outputs: Option<Vec<u32>>,
+        /// Exit code the proven execution must have ended with.
+        #[arg(long, default_value = "0")]
+        exit_code: u32,
+    },
+}
+
+/// Assembles source files, printing the warnings about the program and
+/// rendering any error along with its source line.
+fn assemble(sources: &[PathBuf], options: &AssemblerOptions) -> Result<AssembledProgram> {
+    let (program, warnings) = Assembler::assemble_files_with_options(sources, options)
+        .map_err(|err| anyhow!(err.render()))?;
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+    Ok(program)
+}
+
+/// Loads a program from its binary, or assembles it with debug info if it is a
+/// source file.
+fn load_program(path: &Path) -> Result<AssembledProgram> {
+    let program = if path.extension().is_some_and(|ext| ext == "asm") {
+        let options = AssemblerOptions {
+            debug_info: true,
+            ..Default::default()
+        };
+        assemble(&[path.to_path_buf()], &options)?
+    } else {
+        AssembledProgram::deserialize(&read(path)?)?
+    };
+    Ok(program)
+}
+
+/// Loads the RAM image at `path`, if any, in `program` from `base_addr`.
+fn load_ram_image(
+    program: &mut AssembledProgram,
+    path: Option<&Path>,
+    base_addr: u32,
+) -> Result<()> {
+    if let Some(path) = path {
+        program.load_ram_image(&read(path)?, base_addr)?;
+    }
+    Ok(())
+}
+
+/// Executes `program` with the advice tape at `advice`, if any.
+fn execute(program: AssembledProgram, init: &[u32], advice: Option<&Path>) -> Result<Trace> {
+    let mut options = GenerationOptions::default();
+    if let Some(path) = advice {
+        let bytes = read(path)?;
+        if bytes.len() % 4 != 0 {
+            bail!("The advice tape {path:?} is not made of 32-bit words");
+        }
+        let words = bytes
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        options = options.advice_tape(words);
+    }
+    Trace::from_program_with_options(program, init, Box::new(GenericISA), options)
+}
+
+/// Parses an address, in decimal or hexadecimal with a `0x` prefix.
+fn parse_addr(addr: &str) -> Result<u32, std::num::ParseIntError> {
+    match addr.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => addr.parse(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read {path:?}"))
+}
+
+fn write(path: &Path, bytes: impl AsRef<[u8]>) -> Result<()> {
+    fs::write(path, bytes).with_context(|| format!("Failed to write {path:?}"))
+}
+
+/// Reads lines from the standard input and evaluates them with `repl`, until
+/// the end of the input or a `:quit` command.
+fn run_repl(mut repl: Repl) -> Result<()> {
+    println!("Type :help for the list of commands");
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("petravm> ");
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        match repl.eval(&line?) {
+            Ok(Evaluation::Output(output)) if output.is_empty() => {}
+            Ok(Evaluation::Output(output)) => println!("{output}"),
+            Ok(Evaluation::Quit) => return Ok(()),
+            Err(err) => println!("Error: {err}"),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let _guard = petravm_asm::init_logger();
+
+    match cli.command {
+        Command::Asm {
+            sources,
+            output,
+            eliminate_dead_code,
+            schedule_moves,
+            compact_frames,
+            debug_info,
+            ram_image,
+            ram_base,
+        } => {
+            let options = AssemblerOptions {
+                eliminate_dead_code,
+                schedule_moves,
+                compact_frames,
+                debug_info: debug_info.is_some(),
+            };
+            let mut program = assemble(&sources, &options)?;
+            load_ram_image(&mut program, ram_image.as_deref(), ram_base)?;
+            write(&output, program.serialize())?;
+            if let (Some(path), Some(debug_info)) = (debug_info, &program.debug_info) {
+                write(&path, debug_info.to_json()?)?;
+            }
+            println!("Assembled {} instructions", program.prom.len());
+            println!("Program digest: {}", hex(&program.prom.digest()));
+        }
+        Command::Disasm { program } => {
+            let program = load_program(&program)?;
+            print!("{}", Disassembler::from_program(&program).disassemble()?);
+        }
+        Command::Abi => println!("{}", Abi::STANDARD.to_json()?),
+        Command::Run {
+            program,
+            init,
+            trace,
+            stats,
+            ram_image,
+            ram_base,
+            advice,
+        } => {
+            let mut program = load_program(&program)?;
+            load_ram_image(&mut program, ram_image.as_deref(), ram_base)?;
+            let execution = execute(program, &init, advice.as_deref())?;
+
+            println!("Executed {} cycles", execution.cycles());
+            let vrom = execution.trace.vrom().allocator_stats();
+            println!(
+                "VROM: {} words allocated for {} requested ({:.1}% fragmentation)",
+                vrom.allocated_words,
+                vrom.requested_words,
+                100.0 * vrom.fragmentation()
+            );
+            println!("Exit code: {}", execution.exit_code());
+            println!("Inputs commitment: {}", hex(&execution.init_commitment()));
+            for (addr, value) in execution.public_values()? {
+                println!("Public value @{addr}: {value}");
+            }
+            if !execution.outputs().is_empty() {
+                println!("Outputs: {:?}", execution.outputs());
+                println!(
+                    "Outputs commitment: {}",
+                    hex(&execution.output_commitment())
+                );
+            }
+
+            if let Some(stats) = stats {
+                write(&stats, execution.trace.export().to_json()?)?;
+            }
+            if let Some(trace) = trace {
+                write(&trace, execution.to_bytes()?)?;
+            }
+        }
+        Command::Preflight {
+            program,
+            init,
+            advice,
+            max_rows,
+        } => {
+            let program = load_program(&program)?;
+            let execution = execute(program, &init, advice.as_deref())?;
+            let preflight = Prover::new(Box::new(GenericISA)).preflight(&execution)?;
+            println!("{preflight}");
+            if let Some(max_rows) = max_rows {
+                preflight.check_max_rows(max_rows)?;
+            }
+        }
+        Command::Repl { program, init } => {
+            let isa = || Box::new(GenericISA) as Box<dyn ISA>;
+            let repl = match program {
+                Some(program) => Repl::with_program(isa, load_program(&program)?, &init)?,
+                None => Repl::new(isa, &init),
+            };
+            run_repl(repl)?;
+        }
+        Command::Prove {
+            trace,
+            proof,
+            vk,
+            claim,
+            memory_limit_mib,
+            cache_dir,
+        } => {
+            let execution = Trace::from_bytes(&read(&trace)?)?;
+            let mut prover = Prover::new(Box::new(GenericISA));
+            if let Some(limit) = memory_limit_mib {
+                prover = prover.with_memory_limit(limit << 20);
+            }
+            if let Some(dir) = cache_dir {
+                prover = prover.with_cache(Arc::new(ProvingCache::on_disk(dir)?));
+            }
+            let (proven, _, compiled_cs, summary) = prover.prove_with_summary(&execution)?;
+            let verifying_key = VerifyingKey {
+                compiled_cs,
+                config: *prover.config(),
+            };
+
+            write(&proof, serialize_proof(&proven)?)?;
+            write(&vk, verifying_key.to_bytes()?)?;
+            write(&claim, execution.claim()?.to_bytes()?)?;
+            println!("{summary}");
+            println!("Proof written to {proof:?}");
+        }
+        Command::Verify {
+            proof,
+            vk,
+            claim,
+            program,
+            ram_image,
+            ram_base,
+            init,
+            outputs,
+            exit_code,
+        } => {
+            // The verification key is built locally, so that a proof can't
+            // come with a key of its own making.
+            let prover = Prover::new(Box::new(GenericISA));
+            let verifying_key = prover.verifying_key()?;
+            if let Some(vk) = vk {
+                if read(&vk)? != verifying_key.to_bytes()? {
+                    bail!("The verification key {vk:?} doesn't match the circuit");
+                }
+            }
+
+            // Likewise, the boundaries are rebuilt from the program and the
+            // claim, with the expected inputs, outputs and exit code, so that
+            // a proof can't come with boundaries of its own making.
+            let mut claim = Claim::from_bytes(&read(&claim)?)?;
+            if let Some(init) = init {
+                claim.init_values = init;
+            }
+            if let Some(outputs) = outputs {
+                claim.outputs = outputs;
+            }
+            claim.exit_code = exit_code;
+            let mut program = load_program(&program)?;
+            load_ram_image(&mut program, ram_image.as_deref(), ram_base)?;
+            let boundaries = prover.claim_boundaries(&program, &claim)?;
+            verify(
+                &verifying_key,
+                &boundaries,
+                deserialize_proof(&read(&proof)?)?,
+            )?;
+
+            println!("Proof verified");
+            println!("Program digest: {}", hex(&program.prom.digest()));
+            println!("Exit code: {}", claim.exit_code);
+            let commitment = ValueRom::commit_init_values(&claim.init_values);
+            println!("Inputs commitment: {}", hex(&commitment));
+            for (addr, value) in program.public_slots.iter().zip(&claim.public_values) {
+                println!("Public value @{addr}: {value}");
+            }
+            if !claim.outputs.is_empty() {
+                println!("Outputs: {:?}", claim.outputs);
+                println!(
+                    "Outputs commitment: {}",
+                    hex(&commit_outputs(&claim.outputs))
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
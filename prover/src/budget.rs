@@ -0,0 +1,167 @@
+//! Estimation of the memory held by the witness of a proof.
+//!
+//! The witness is filled in an arena allocated up front, which can't grow
+//! once the tables are being filled. [`WitnessMemory`] estimates its size from
+//! the shapes of the columns of each table and the number of rows of the
+//! tables, so that the arena is sized for the trace at hand, and that traces
+//! exceeding the memory of the prover are rejected before filling anything.
+//!
+//! The tables filled in parallel are split into segments by the witness
+//! builder, whose segments are views of the arena and take no extra memory.
+
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use binius_m3::builder::ConstraintSystem;
+
+use crate::{preflight::TableSize, types::ProverPackedField};
+
+/// Smallest capacity of the witness arena, in packed field elements.
+const MIN_ARENA_CAPACITY: usize = 1 << 16;
+
+/// Witness memory of a table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableMemory {
+    /// Name of the table.
+    pub name: String,
+    /// Number of rows of the table, once padded to a power of two.
+    pub padded_rows: usize,
+    /// Number of bytes of the columns of the table.
+    pub bytes: usize,
+}
+
+/// Estimated witness memory of a proof, see the [module-level
+/// documentation](self).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WitnessMemory {
+    /// Memory of each table, in order of table creation.
+    pub tables: Vec<TableMemory>,
+}
+
+impl WitnessMemory {
+    /// Estimates the witness memory of the tables of `cs`, with the sizes
+    /// `table_sizes` listed in order of table creation.
+    ///
+    /// Each column holds, per row, a number of field elements of some tower
+    /// height, and is allocated as a whole number of packed field elements.
+    pub fn estimate(cs: &ConstraintSystem, table_sizes: &[TableSize]) -> Self {
+        let packed_bytes = size_of::<ProverPackedField>();
+        let tables = cs
+            .tables
+            .iter()
+            .zip(table_sizes)
+            .map(|(table, size)| {
+                let padded_rows = size.padded_rows();
+                let bytes = if padded_rows == 0 {
+                    0
+                } else {
+                    table
+                        .columns
+                        .iter()
+                        .map(|column| {
+                            let bits_per_row =
+                                1 << (column.shape.tower_height + column.shape.log_values_per_row);
+                            (padded_rows * bits_per_row / 8).max(packed_bytes)
+                        })
+                        .sum()
+                };
+                TableMemory {
+                    name: size.name.clone(),
+                    padded_rows,
+                    bytes,
+                }
+            })
+            .collect();
+        Self { tables }
+    }
+
+    /// Returns the estimated number of bytes of the witness.
+    pub fn total_bytes(&self) -> usize {
+        self.tables.iter().map(|table| table.bytes).sum()
+    }
+
+    /// Returns the capacity, in packed field elements, of the arena holding
+    /// the witness, rounded up to a power of two to leave room for the
+    /// alignment of the columns.
+    pub fn arena_capacity(&self) -> usize {
+        self.total_bytes()
+            .div_ceil(size_of::<ProverPackedField>())
+            .next_power_of_two()
+            .max(MIN_ARENA_CAPACITY)
+    }
+
+    /// Returns the number of bytes of the arena holding the witness.
+    pub fn arena_bytes(&self) -> usize {
+        self.arena_capacity() * size_of::<ProverPackedField>()
+    }
+
+    /// Checks that the arena holding the witness fits in `limit` bytes.
+    pub fn check_limit(&self, limit: usize) -> Result<()> {
+        if self.arena_bytes() > limit {
+            let largest = self.tables.iter().max_by_key(|table| table.bytes);
+            return Err(anyhow!(
+                "The witness needs {} bytes, exceeding the limit of {limit} bytes (largest table: \
+                 {})",
+                self.arena_bytes(),
+                largest.map_or("none", |table| &table.name)
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WitnessMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1} MiB of witness, in an arena of {:.1} MiB",
+            self.total_bytes() as f64 / (1 << 20) as f64,
+            self.arena_bytes() as f64 / (1 << 20) as f64
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petravm_asm::isa::GenericISA;
+
+    use super::*;
+    use crate::{
+        circuit::Circuit,
+        model::Trace,
+        prover::Prover,
+        test_utils::{fibonacci, generate_fibonacci_trace},
+    };
+
+    #[test]
+    fn test_witness_memory() -> Result<()> {
+        let circuit = Circuit::new(Box::new(GenericISA));
+        let small = generate_fibonacci_trace(3, fibonacci(3))?;
+        let large = generate_fibonacci_trace(40, fibonacci(40))?;
+        let estimate = |trace: &Trace| -> Result<WitnessMemory> {
+            Ok(WitnessMemory::estimate(
+                &circuit.cs,
+                &circuit.table_sizes(trace)?,
+            ))
+        };
+
+        // Empty tables take no memory, and longer executions take more.
+        let (small, large) = (estimate(&small)?, estimate(&large)?);
+        assert_eq!(small.tables.len(), circuit.cs.tables.len());
+        assert!(small
+            .tables
+            .iter()
+            .all(|table| (table.padded_rows == 0) == (table.bytes == 0)));
+        assert!(small.total_bytes() < large.total_bytes());
+        assert!(large.total_bytes() <= large.arena_bytes());
+
+        large.check_limit(large.arena_bytes())?;
+        assert!(large.check_limit(large.arena_bytes() - 1).is_err());
+
+        // A prover with too little memory rejects the trace before proving it.
+        let trace = generate_fibonacci_trace(40, fibonacci(40))?;
+        let prover = Prover::new(Box::new(GenericISA)).with_memory_limit(1 << 10);
+        assert!(prover.prove(&trace).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+//! Cache of the proving artifacts shared by the proofs of a program.
+//!
+//! Compiling the constraint system of a circuit and building the program
+//! boundaries of a statement only depend on the circuit, the proving
+//! parameters and the proven program, and not on the executions.
+//! [`ProvingCache`] memoizes them, in memory and optionally on disk, so that
+//! repeated proofs of a program, including by provers created anew, skip
+//! them.
+//!
+//! The compiled constraint systems are keyed by the
+//! [`digest`](crate::circuit::Circuit::digest) of their circuit, or of their
+//! batch circuit, which covers the sources of the prover, the custom tables
+//! and the layout of its constraint system, and by the serialized
+//! [`ProverConfig`]. The program boundaries are keyed by the digest
+//! of their circuit and of the PROM table rows of the traces.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use binius_core::constraint_system::ConstraintSystem;
+use binius_m3::builder::{Boundary, B128};
+use petravm_asm::util::groestl256;
+use petravm_verifier::{ProverConfig, VerifyingKey};
+
+use crate::{
+    model::Instruction,
+    serialization::{deserialize_boundaries, serialize_boundaries},
+};
+
+/// Number of hits and misses of a [`ProvingCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of artifacts found in memory or on disk.
+    pub hits: u64,
+    /// Number of artifacts built.
+    pub misses: u64,
+}
+
+/// Cache of compiled constraint systems and program boundaries, see the
+/// [module-level documentation](self).
+///
+/// The cache is meant to be shared, through an [`Arc`], by the provers of an
+/// application, see
+/// [`Prover::with_cache`](crate::prover::Prover::with_cache).
+#[derive(Debug, Default)]
+pub struct ProvingCache {
+    /// Directory holding the artifacts, if persisted
+    dir: Option<PathBuf>,
+    compiled_cs: Mutex<HashMap<[u8; 32], Arc<ConstraintSystem<B128>>>>,
+    program_boundaries: Mutex<HashMap<[u8; 32], Arc<Vec<Boundary<B128>>>>>,
+    stats: Mutex<CacheStats>,
+}
+
+impl ProvingCache {
+    /// Creates an empty in-memory cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache persisted in the directory `dir`, created if needed,
+    /// reusing the artifacts stored by previous runs.
+    pub fn on_disk(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {dir:?}"))?;
+        Ok(Self {
+            dir: Some(dir),
+            ..Self::default()
+        })
+    }
+
+    /// Returns the number of hits and misses of the cache.
+    pub fn stats(&self) -> CacheStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Returns the compiled constraint system of the circuit of digest
+    /// `circuit`, proven with `config`, compiling it with `compile` on a miss.
+    pub(crate) fn compiled_cs(
+        &self,
+        circuit: [u8; 32],
+        config: ProverConfig,
+        compile: impl FnOnce() -> Result<ConstraintSystem<B128>>,
+    ) -> Result<Arc<ConstraintSystem<B128>>> {
+        let key = groestl256(&[circuit.as_slice(), &config.to_bytes()?].concat());
+        self.get_or_build(
+            &self.compiled_cs,
+            key,
+            "vk",
+            |bytes| Ok(VerifyingKey::from_bytes(bytes)?.compiled_cs),
+            |compiled_cs| {
+                VerifyingKey {
+                    compiled_cs: compiled_cs.clone(),
+                    config,
+                }
+                .to_bytes()
+            },
+            compile,
+        )
+    }
+
+    /// Returns the program boundaries of the circuit of digest `circuit` for
+    /// the PROM table rows `program`, building them with `build` on a miss.
+    pub(crate) fn program_boundaries(
+        &self,
+        circuit: [u8; 32],
+        program: &[(Instruction, u32)],
+        build: impl FnOnce() -> Vec<Boundary<B128>>,
+    ) -> Result<Arc<Vec<Boundary<B128>>>> {
+        let key = groestl256(&[circuit, program_digest(program)].concat());
+        self.get_or_build(
+            &self.program_boundaries,
+            key,
+            "boundaries",
+            deserialize_boundaries,
+            |boundaries| serialize_boundaries(boundaries),
+            || Ok(build()),
+        )
+    }
+
+    /// Returns the artifact of `key` from `entries` or from the file of
+    /// extension `extension` in the cache directory, or builds it with `build`
+    /// and stores it in both.
+    fn get_or_build<T>(
+        &self,
+        entries: &Mutex<HashMap<[u8; 32], Arc<T>>>,
+        key: [u8; 32],
+        extension: &str,
+        load: impl FnOnce(&[u8]) -> Result<T>,
+        store: impl FnOnce(&T) -> Result<Vec<u8>>,
+        build: impl FnOnce() -> Result<T>,
+    ) -> Result<Arc<T>> {
+        if let Some(entry) = entries.lock().unwrap().get(&key) {
+            self.stats.lock().unwrap().hits += 1;
+            return Ok(entry.clone());
+        }
+
+        let path = self.dir.as_ref().map(|dir| {
+            let name = key
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>();
+            dir.join(name).with_extension(extension)
+        });
+        let entry = match &path {
+            Some(path) if path.exists() => {
+                let bytes = fs::read(path).with_context(|| format!("Failed to read {path:?}"))?;
+                self.stats.lock().unwrap().hits += 1;
+                Arc::new(load(&bytes).with_context(|| format!("Corrupted cache file {path:?}"))?)
+            }
+            _ => {
+                let entry = Arc::new(build()?);
+                self.stats.lock().unwrap().misses += 1;
+                if let Some(path) = &path {
+                    fs::write(path, store(&entry)?)
+                        .with_context(|| format!("Failed to write {path:?}"))?;
+                }
+                entry
+            }
+        };
+        entries.lock().unwrap().insert(key, entry.clone());
+        Ok(entry)
+    }
+}
+
+/// Returns the digest of the PROM table rows `program`, each row being
+/// encoded as its PC followed by its opcode and arguments, all little-endian.
+fn program_digest(program: &[(Instruction, u32)]) -> [u8; 32] {
+    let bytes = program
+        .iter()
+        .flat_map(|(instr, _)| {
+            let args = (0..3).map(|i| instr.args.get(i).copied().unwrap_or(0));
+            instr
+                .pc
+                .val()
+                .to_le_bytes()
+                .into_iter()
+                .chain(instr.opcode.to_le_bytes())
+                .chain(args.flat_map(u16::to_le_bytes))
+        })
+        .collect::<Vec<_>>();
+    groestl256(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use petravm_asm::isa::GenericISA;
+
+    use super::*;
+    use crate::{
+        prover::Prover,
+        test_utils::{fibonacci, generate_fibonacci_trace},
+    };
+
+    #[test]
+    fn test_proving_cache() -> Result<()> {
+        let trace = generate_fibonacci_trace(11, fibonacci(11))?;
+        let cache = Arc::new(ProvingCache::new());
+        let prover = Prover::new(Box::new(GenericISA)).with_cache(cache.clone());
+        let vk = prover.verifying_key()?.to_bytes()?;
+        let statement = prover.create_statement(&trace)?;
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+
+        // Another prover of the same program reuses the artifacts.
+        let other = Prover::new(Box::new(GenericISA)).with_cache(cache.clone());
+        assert_eq!(other.verifying_key()?.to_bytes()?, vk);
+        let cached_statement = other.create_statement(&trace)?;
+        assert_eq!(
+            serialize_boundaries(&cached_statement.boundaries)?,
+            serialize_boundaries(&statement.boundaries)?
+        );
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2 });
+
+        // Other proving parameters compile the constraint system anew.
+        let config = ProverConfig {
+            log_inv_rate: 2,
+            ..Default::default()
+        };
+        let other = Prover::new(Box::new(GenericISA))
+            .with_config(config)
+            .with_cache(cache.clone());
+        other.verifying_key()?;
+        assert_eq!(cache.stats().misses, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_proving_cache_batch() -> Result<()> {
+        let cache = Arc::new(ProvingCache::new());
+        let prover = Prover::new(Box::new(GenericISA)).with_cache(cache.clone());
+        let vk = prover.batch_verifying_key(2)?.to_bytes()?;
+        assert_eq!(prover.batch_verifying_key(2)?.to_bytes()?, vk);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+
+        // A batch of a single execution has the layout of the circuit, and
+        // thus shares its constraint system.
+        let vk = prover.verifying_key()?.to_bytes()?;
+        assert_eq!(prover.batch_verifying_key(1)?.to_bytes()?, vk);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2 });
+        Ok(())
+    }
+
+    #[test]
+    fn test_proving_cache_on_disk() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("petravm-cache-{}", std::process::id()));
+        let cache = Arc::new(ProvingCache::on_disk(&dir)?);
+        let vk = Prover::new(Box::new(GenericISA))
+            .with_cache(cache)
+            .verifying_key()?
+            .to_bytes()?;
+
+        // A new cache loads the artifacts stored by the previous one.
+        let cache = Arc::new(ProvingCache::on_disk(&dir)?);
+        let prover = Prover::new(Box::new(GenericISA)).with_cache(cache.clone());
+        assert_eq!(prover.verifying_key()?.to_bytes()?, vk);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 0 });
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
@@ -9,8 +9,9 @@ use binius_m3::builder::ConstraintSystem;
 /// Holds all channel IDs used in the PetraVM proving system.
 #[derive(Debug, Clone)]
 pub struct Channels {
-    /// Channel for state transitions (PC, FP)
-    /// Follows format [PC, FP]
+    /// Channel for state transitions (PC, FP, Timestamp), the timestamp
+    /// counting the RAM accesses and COMMIT instructions
+    /// Follows format [PC, FP, Timestamp]
     pub state_channel: ChannelId,
 
     /// Channel connecting the PROM table to instruction tables
@@ -19,6 +20,12 @@ pub struct Channels {
     /// multiplicities (a lookup table)
     pub prom_channel: ChannelId,
 
+    /// Channel for the program, pushed by the verifier through the statement
+    /// boundaries and pulled by the PROM table, so that a proof is bound to
+    /// the program it executes
+    /// Follows format [PC, Opcode, Arg1, Arg2, Arg3]
+    pub program_channel: ChannelId,
+
     /// Channel for memory operations (VROM)
     /// Follows format [Address, Value]
     pub vrom_channel: ChannelId,
@@ -30,6 +37,69 @@ pub struct Channels {
     /// Channel for right logical shift operations
     /// Follows format [Input, ShiftAmount, Output]
     pub right_shifter_channel: ChannelId,
+
+    /// Channel for RAM accesses, balanced through timestamp-ordered
+    /// memory checking
+    /// Follows format [WordAddress, Value, Timestamp]
+    pub ram_channel: ChannelId,
+
+    /// Channel for the non-zero words of the initial RAM image, pushed by the
+    /// verifier through the statement boundaries
+    /// Follows format [WordAddress, Value]
+    pub ram_image_channel: ChannelId,
+
+    /// Channel for public values, pushed by the verifier through the
+    /// statement boundaries
+    /// Follows format [Address, Value]
+    pub public_channel: ChannelId,
+
+    /// Channel for the initial VROM values provided by the host, pushed by
+    /// the verifier through the statement boundaries
+    /// Follows format [Address, Value]
+    pub input_channel: ChannelId,
+
+    /// Channel for the public outputs committed by COMMIT, pulled by the
+    /// verifier through the statement boundaries, the index of the `n`-th
+    /// output being `G^n`
+    /// Follows format [Index, Value]
+    pub output_channel: ChannelId,
+
+    /// Channel chaining the COMMIT instructions in execution order, opened
+    /// and closed by the verifier through the statement boundaries
+    /// Follows format [Index, Timestamp]
+    pub commit_channel: ChannelId,
+
+    /// Channel chaining the words moved by MEMCPY and MEMSET, balanced by the
+    /// instruction tables pushing the start of a block and pulling its end
+    /// Follows format [DstAddress, SrcAddress, SrcStride, RemainingWords]
+    pub mem_word_channel: ChannelId,
+
+    /// Channel chaining the levels of the Merkle paths verified by
+    /// MERKLE_VERIFY, balanced by the instruction table pushing the leaf and
+    /// pulling the root
+    /// Follows format [Address, Node(8 words), Index]
+    pub merkle_channel: ChannelId,
+
+    /// Channel for range checks, pushed by the range-check lookup tables with
+    /// every value fitting in their width
+    /// Follows format [Bits, Value]
+    pub range_check_channel: ChannelId,
+
+    /// Channel for the value space of the range-check lookup tables, ensuring
+    /// they hold each value of their range exactly once
+    /// Follows format [Bits, Value]
+    pub range_check_space_channel: ChannelId,
+
+    /// Channel for the valid targets of the indirect calls, pushed by the
+    /// call target table and pulled by the CALLV and TAILV tables
+    /// Follows format [PC]
+    pub call_target_channel: ChannelId,
+
+    /// Channel for the function entry points of the program, pushed by the
+    /// verifier through the statement boundaries and pulled by the call target
+    /// table, so that indirect calls can only land on declared functions
+    /// Follows format [PC]
+    pub function_channel: ChannelId,
 }
 
 impl Channels {
@@ -38,9 +108,22 @@ impl Channels {
         Self {
             state_channel: cs.add_channel("state_channel"),
             prom_channel: cs.add_channel("prom_channel"),
+            program_channel: cs.add_channel("program_channel"),
             vrom_channel: cs.add_channel("vrom_channel"),
             vrom_addr_space_channel: cs.add_channel("vrom_addr_space_channel"),
             right_shifter_channel: cs.add_channel("right_shifter_channel"),
+            ram_channel: cs.add_channel("ram_channel"),
+            ram_image_channel: cs.add_channel("ram_image_channel"),
+            public_channel: cs.add_channel("public_channel"),
+            input_channel: cs.add_channel("input_channel"),
+            output_channel: cs.add_channel("output_channel"),
+            commit_channel: cs.add_channel("commit_channel"),
+            mem_word_channel: cs.add_channel("mem_word_channel"),
+            merkle_channel: cs.add_channel("merkle_channel"),
+            range_check_channel: cs.add_channel("range_check_channel"),
+            range_check_space_channel: cs.add_channel("range_check_space_channel"),
+            call_target_channel: cs.add_channel("call_target_channel"),
+            function_channel: cs.add_channel("function_channel"),
         }
     }
 }
@@ -3,41 +3,130 @@
 //! This module defines the complete M3 circuit for PetraVM, combining
 //! all the individual tables and channels.
 
-#[cfg(feature = "disable_state_channel")]
-use binius_m3::builder::{Boundary, ConstraintSystem, FlushDirection};
-#[cfg(not(feature = "disable_state_channel"))]
-use binius_m3::builder::{Boundary, ConstraintSystem, FlushDirection, B128};
-use petravm_asm::isa::ISA;
+use std::ops::Deref;
 
+use anyhow::anyhow;
+use binius_field::Field;
+use binius_m3::builder::{Boundary, ConstraintSystem, FlushDirection, TableFiller, B128, B16, B32};
+use petravm_asm::{
+    execution::CustomEvent, isa::ISA, util::groestl256, AssembledProgram, Opcode, ProgramRom,
+    RANGE_CHECK_WIDTHS,
+};
+
+use crate::preflight::TableSize;
 use crate::types::Statement;
 use crate::{
     channels::Channels,
-    gadgets::right_shifter_table::RightShifterTable,
-    memory::{PromTable, VromTable},
-    model::{build_table_for_opcode, Trace},
-    table::{FillableTable, Table},
+    gadgets::{
+        call_target_table::CallTargetTable, mem_word_table::MemWordTable,
+        merkle_node_table::MerkleNodeTable, range_lookup_table::RangeLookupTable,
+        right_shifter_table::RightShifterTable,
+    },
+    memory::{PromTable, PublicTable, RamImageTable, RamTable, VromTable},
+    model::{
+        build_table_for_opcode, call_targets, ram_image_words, verifier_program, Claim, Trace,
+    },
+    opcodes::output_index,
+    table::{CustomTableEntry, FillableTable, ProvableCustomInstruction, Table},
+    types::ProverPackedField,
+    utils::pack_instruction_b128,
 };
 
+/// Opcodes looking up the right shifter table.
+const RIGHT_SHIFTER_OPCODES: [Opcode; 4] = [Opcode::Srli, Opcode::Srl, Opcode::Sra, Opcode::Srai];
+
+/// Opcodes accessing the RAM table.
+const RAM_OPCODES: [Opcode; 8] = [
+    Opcode::Lw,
+    Opcode::Lh,
+    Opcode::Lhu,
+    Opcode::Lb,
+    Opcode::Lbu,
+    Opcode::Sw,
+    Opcode::Sh,
+    Opcode::Sb,
+];
+
+/// Opcodes moving words through the memory word table.
+const MEM_WORD_OPCODES: [Opcode; 2] = [Opcode::Memcpy, Opcode::Memset];
+
+/// Opcodes hashing Merkle paths through the Merkle node table.
+const MERKLE_OPCODES: [Opcode; 1] = [Opcode::MerkleVerify];
+
+/// Opcodes looking up the range-check lookup tables, which are only
+/// instantiated when the ISA supports one of them.
+const RANGE_CHECK_OPCODES: [Opcode; 1] = [Opcode::RangeCheck];
+
+/// Opcodes looking up the call target table.
+const INDIRECT_CALL_OPCODES: [Opcode; 2] = [Opcode::Callv, Opcode::Tailv];
+
+/// Returns whether the ISA supports any of the given opcodes.
+fn supports_any(isa: &dyn ISA, opcodes: &[Opcode]) -> bool {
+    opcodes.iter().any(|&op| isa.is_supported(op))
+}
+
+/// Builds the table of a custom instruction, given its opcode.
+type CustomTableBuilder = fn(&mut ConstraintSystem, &Channels, u16) -> Box<dyn FillableTable>;
+
 /// Arithmetic circuit for the PetraVM proving system.
 ///
 /// This struct represents the complete M3 arithmetization circuit for PetraVM.
 /// It contains all the tables and channels needed to encode program execution
-/// as arithmetic constraints.
+/// as arithmetic constraints, held by its [`CircuitInstance`], which it
+/// dereferences to.
+///
+/// Only the tables required by the targeted [`ISA`] are instantiated: besides
+/// one table per supported instruction, the auxiliary tables (right shifter,
+/// RAM, memory words, Merkle nodes, range-check lookups and call targets) are
+/// only added when the ISA supports an instruction relying on them. A reduced
+/// ISA thus yields a smaller constraint system and verification key.
 pub struct Circuit {
     /// The Instruction Set Architecture [`ISA`] targeted for this [`Circuit`]
     /// instance.
     pub isa: Box<dyn ISA>,
     /// Constraint system
     pub cs: ConstraintSystem,
+    /// Channels and tables proving an execution
+    pub instance: CircuitInstance,
+    /// Builders of the tables of the custom instructions, along with their
+    /// opcodes and the type names of the tables, to replicate them in batches
+    /// and to identify them in the digest of the circuit
+    custom_tables: Vec<(u16, &'static str, CustomTableBuilder)>,
+}
+
+/// Channels and tables proving an execution, within a [`Circuit`] or a
+/// [`BatchCircuit`].
+pub struct CircuitInstance {
     /// Channels for connecting tables
     pub channels: Channels,
     /// Program ROM table
     pub prom_table: PromTable,
     /// VROM table
     pub vrom_table: VromTable,
-    /// Right Logical Shifter table
-    pub right_shifter_table: RightShifterTable,
-    /// Instruction tables
+    /// Right Logical Shifter table, if the ISA supports right shifts
+    pub right_shifter_table: Option<RightShifterTable>,
+    /// RAM table, if the ISA supports RAM accesses
+    pub ram_table: Option<RamTable>,
+    /// Table of the non-zero words of the initial RAM image, if the ISA
+    /// supports RAM accesses
+    pub ram_image_table: Option<RamImageTable>,
+    /// Public values table
+    pub public_table: PublicTable,
+    /// Table of the initial VROM values provided by the host
+    pub input_table: PublicTable,
+    /// Table of the words moved by MEMCPY and MEMSET, if the ISA supports
+    /// either
+    pub mem_word_table: Option<MemWordTable>,
+    /// Table of the levels of the Merkle paths verified by MERKLE_VERIFY, if
+    /// the ISA supports it
+    pub merkle_node_table: Option<MerkleNodeTable>,
+    /// Range-check lookup tables, one per supported width, empty if the ISA
+    /// doesn't support RANGE_CHECK
+    pub range_lookup_tables: Vec<RangeLookupTable>,
+    /// Table of the valid targets of the indirect calls, if the ISA supports
+    /// CALLV or TAILV
+    pub call_target_table: Option<CallTargetTable>,
+    /// Instruction tables, including those of the custom instructions
     pub tables: Vec<Box<dyn FillableTable>>,
 }
 
@@ -45,15 +134,245 @@ impl Circuit {
     /// Create a new PetraVM circuit.
     ///
     /// This initializes the constraint system, channels, and all tables
-    /// needed for the PetraVM execution.
+    /// needed for the PetraVM execution of programs targeting `isa`.
     pub fn new(isa: Box<dyn ISA>) -> Self {
         let mut cs = ConstraintSystem::new();
-        let channels = Channels::new(&mut cs);
+        let instance = CircuitInstance::new(&mut cs, isa.as_ref());
+        Self {
+            isa,
+            cs,
+            instance,
+            custom_tables: Vec::new(),
+        }
+    }
+
+    /// Adds the table proving the custom instruction `instruction`, after all
+    /// the tables of the circuit.
+    pub fn add_custom_instruction<I: ProvableCustomInstruction>(&mut self, instruction: &I) {
+        let builder: CustomTableBuilder = custom_table::<I::Table>;
+        let table = builder(&mut self.cs, &self.instance.channels, instruction.opcode());
+        self.instance.tables.push(table);
+        self.custom_tables.push((
+            instruction.opcode(),
+            std::any::type_name::<I::Table>(),
+            builder,
+        ));
+    }
+
+    /// Returns a digest identifying the constraint system of the circuit, from
+    /// the version and a hash of the sources of the prover, the supported
+    /// opcodes, the custom instructions with the type names of their tables,
+    /// and the layout of the constraint system: its channels, tables and
+    /// columns.
+    ///
+    /// The hash of the sources, computed by the build script, covers the
+    /// constraints of the built-in tables, which are not reachable from the
+    /// constraint system builder. Those of the custom tables are only
+    /// identified by their type names: changing them without renaming their
+    /// table types leaves the digest unchanged.
+    pub fn digest(&self) -> [u8; 32] {
+        self.digest_of(&self.cs)
+    }
+
+    /// Returns a digest identifying the constraint system of `batch`, a batch
+    /// of this circuit returned by [`batch`](Self::batch), as
+    /// [`digest`](Self::digest) does for the circuit.
+    pub fn batch_digest(&self, batch: &BatchCircuit) -> [u8; 32] {
+        self.digest_of(&batch.cs)
+    }
+
+    fn digest_of(&self, cs: &ConstraintSystem) -> [u8; 32] {
+        let mut opcodes = self
+            .isa
+            .supported_opcodes()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        opcodes.sort_by_key(|op| *op as u16);
+        let mut bytes = env!("CARGO_PKG_VERSION").as_bytes().to_vec();
+        bytes.push(0);
+        bytes.extend(env!("PETRAVM_PROVER_SOURCE_HASH").as_bytes());
+        bytes.push(0);
+        bytes.extend((opcodes.len() as u32).to_le_bytes());
+        bytes.extend(opcodes.iter().flat_map(|op| (*op as u16).to_le_bytes()));
+        bytes.extend((self.custom_tables.len() as u32).to_le_bytes());
+        for (opcode, table, _) in &self.custom_tables {
+            bytes.extend(opcode.to_le_bytes());
+            bytes.extend((table.len() as u32).to_le_bytes());
+            bytes.extend(table.as_bytes());
+        }
+        bytes.extend(layout_bytes(cs));
+        groestl256(&bytes)
+    }
+
+    /// Returns the circuit proving `size` executions at once, each proven by
+    /// an instance of this circuit, see [`BatchCircuit`].
+    pub fn batch(&self, size: usize) -> BatchCircuit {
+        let mut cs = ConstraintSystem::new();
+        let instances = (0..size)
+            .map(|_| {
+                let mut instance = CircuitInstance::new(&mut cs, self.isa.as_ref());
+                for &(opcode, _, builder) in &self.custom_tables {
+                    let table = builder(&mut cs, &instance.channels, opcode);
+                    instance.tables.push(table);
+                }
+                instance
+            })
+            .collect();
+        BatchCircuit { cs, instances }
+    }
+}
+
+impl Deref for Circuit {
+    type Target = CircuitInstance;
+
+    fn deref(&self) -> &Self::Target {
+        &self.instance
+    }
+}
 
-        // Create all the tables
-        let prom_table = PromTable::new(&mut cs, &channels);
-        let vrom_table = VromTable::new(&mut cs, &channels);
-        let right_shifter_table = RightShifterTable::new(&mut cs, &channels);
+/// Builds the table of a custom instruction proven by `T`.
+fn custom_table<T>(
+    cs: &mut ConstraintSystem,
+    channels: &Channels,
+    opcode: u16,
+) -> Box<dyn FillableTable>
+where
+    T: Table + TableFiller<ProverPackedField> + Sync + 'static,
+    <T as TableFiller<ProverPackedField>>::Event: CustomEvent + Clone,
+{
+    Box::new(CustomTableEntry {
+        table: Box::new(T::new(cs, channels)),
+        opcode,
+    })
+}
+
+/// Encodes the layout of the constraint system `cs`: its number of channels,
+/// followed by every table with its name and its columns, each with its name,
+/// shape and whether it is constrained to be non-zero.
+///
+/// Names are encoded as their length followed by their bytes, and integers as
+/// little-endian 32-bit words. The constraints themselves are not reachable
+/// from the builder, but the columns they define are, while the constraints of
+/// a given column layout only change along with the sources of the prover.
+fn layout_bytes(cs: &ConstraintSystem) -> Vec<u8> {
+    fn push_name(bytes: &mut Vec<u8>, name: &str) {
+        bytes.extend((name.len() as u32).to_le_bytes());
+        bytes.extend(name.as_bytes());
+    }
+
+    let mut bytes = Vec::new();
+    bytes.extend((cs.channels.len() as u32).to_le_bytes());
+    bytes.extend((cs.tables.len() as u32).to_le_bytes());
+    for table in &cs.tables {
+        push_name(&mut bytes, &table.name);
+        bytes.extend((table.columns.len() as u32).to_le_bytes());
+        for column in &table.columns {
+            push_name(&mut bytes, &column.name);
+            bytes.extend((column.shape.tower_height as u32).to_le_bytes());
+            bytes.extend((column.shape.log_values_per_row as u32).to_le_bytes());
+            bytes.push(column.is_nonzero as u8);
+        }
+    }
+    bytes
+}
+
+/// Circuit proving a batch of executions with a single proof.
+///
+/// Each execution is proven by its own [`CircuitInstance`], with its own
+/// channels, all instances sharing the constraint system. The instances can't
+/// share their tables: the VROM and RAM tables hold each address once, while
+/// every execution has its own memory, starting at the same addresses.
+/// Batching still amortizes the commitment and the verification over the
+/// executions, at the cost of a verification key depending on the batch size.
+///
+/// NOTE: The rows of the executions are deliberately not concatenated into
+/// shared tables. That would require tagging every row and every flush with
+/// the index of its execution, i.e. an extra column in each table and an extra
+/// value in each channel, the single-execution circuit included. Instead, the
+/// tables are replicated per execution, so that the circuit of a single
+/// execution is left untouched, and each table is only padded to the size of
+/// its own execution.
+pub struct BatchCircuit {
+    /// Constraint system, holding the tables of all instances
+    pub cs: ConstraintSystem,
+    /// Instance proving each execution of the batch, in order
+    pub instances: Vec<CircuitInstance>,
+}
+
+impl BatchCircuit {
+    /// Returns the number of rows of each table of the circuit for the given
+    /// traces, one per instance, in order of table creation.
+    pub fn table_sizes(&self, traces: &[Trace]) -> anyhow::Result<Vec<TableSize>> {
+        self.check_batch_size(traces)?;
+        let mut table_sizes = Vec::new();
+        for (instance, trace) in self.instances.iter().zip(traces) {
+            table_sizes.extend(instance.table_sizes(trace)?);
+        }
+        Ok(table_sizes)
+    }
+
+    /// Create a circuit statement for the given traces, one per instance,
+    /// gathering the statements of the instances.
+    pub fn create_statement(&self, traces: &[Trace]) -> anyhow::Result<Statement> {
+        self.check_batch_size(traces)?;
+        let mut statement = Statement {
+            boundaries: Vec::new(),
+            table_sizes: Vec::new(),
+        };
+        for (instance, trace) in self.instances.iter().zip(traces) {
+            let Statement {
+                boundaries,
+                table_sizes,
+            } = instance.create_statement(trace)?;
+            statement.boundaries.extend(boundaries);
+            statement.table_sizes.extend(table_sizes);
+        }
+        Ok(statement)
+    }
+
+    fn check_batch_size(&self, traces: &[Trace]) -> anyhow::Result<()> {
+        if traces.len() != self.instances.len() {
+            return Err(anyhow!(
+                "Expected a batch of {} traces, got {}",
+                self.instances.len(),
+                traces.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl CircuitInstance {
+    /// Creates the channels and the tables proving an execution of programs
+    /// targeting `isa`, after the tables already held by `cs`.
+    pub fn new(cs: &mut ConstraintSystem, isa: &dyn ISA) -> Self {
+        let channels = Channels::new(cs);
+
+        // Create the memory tables, and the auxiliary tables required by the ISA
+        let prom_table = PromTable::new(cs, &channels);
+        let vrom_table = VromTable::new(cs, &channels);
+        let right_shifter_table = supports_any(isa, &RIGHT_SHIFTER_OPCODES)
+            .then(|| RightShifterTable::new(cs, &channels));
+        let ram_table = supports_any(isa, &RAM_OPCODES).then(|| RamTable::new(cs, &channels));
+        let ram_image_table =
+            supports_any(isa, &RAM_OPCODES).then(|| RamImageTable::new(cs, &channels));
+        let public_table = PublicTable::new(cs, &channels);
+        let input_table = PublicTable::inputs(cs, &channels);
+        let mem_word_table =
+            supports_any(isa, &MEM_WORD_OPCODES).then(|| MemWordTable::new(cs, &channels));
+        let merkle_node_table =
+            supports_any(isa, &MERKLE_OPCODES).then(|| MerkleNodeTable::new(cs, &channels));
+        let range_lookup_tables = if supports_any(isa, &RANGE_CHECK_OPCODES) {
+            RANGE_CHECK_WIDTHS
+                .iter()
+                .map(|&bits| RangeLookupTable::new(cs, &channels, bits))
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+        let call_target_table =
+            supports_any(isa, &INDIRECT_CALL_OPCODES).then(|| CallTargetTable::new(cs, &channels));
 
         // Generate all tables required to prove the instructions supported by this ISA.
         // Sort the opcodes to ensure deterministic table creation
@@ -61,33 +380,63 @@ impl Circuit {
         sorted_opcodes.sort_by_key(|op| *op as u16);
         let tables = sorted_opcodes
             .iter()
-            .filter_map(|op| build_table_for_opcode(*op, &mut cs, &channels))
+            .filter_map(|op| build_table_for_opcode(*op, cs, &channels))
             .collect::<Vec<_>>();
 
         Self {
-            isa,
-            cs,
             channels,
             prom_table,
             vrom_table,
             right_shifter_table,
+            ram_table,
+            ram_image_table,
+            public_table,
+            input_table,
+            mem_word_table,
+            merkle_node_table,
+            range_lookup_tables,
+            call_target_table,
             tables,
         }
     }
 
-    /// Create a circuit statement for a given trace.
-    ///
-    /// # Arguments
-    /// * `trace` - The PetraVM execution trace
+    /// Returns the channel boundaries of the circuit, for an execution of
+    /// `program` from the initial VROM values `init_values`, exiting with code
+    /// 0 without accessing the RAM.
+    pub fn boundaries(&self, program: &ProgramRom, init_values: &[u32]) -> Vec<Boundary<B128>> {
+        self.boundaries_with_exit_code(program, init_values, 0, 0)
+    }
+
+    /// Returns the channel boundaries of the circuit, for an execution of
+    /// `program` from the initial VROM values `init_values`, exiting with
+    /// `exit_code` at the system timestamp `final_timestamp`, i.e. after
+    /// `final_timestamp` RAM accesses and COMMIT instructions.
     ///
-    /// # Returns
-    /// * A Statement that defines boundaries and table sizes
-    pub fn create_statement(&self, trace: &Trace) -> anyhow::Result<Statement> {
-        // Build the statement with boundary values
+    /// These only depend on the circuit, the program, the inputs and the exit
+    /// state, and not on the proven trace, as every execution starts at (PC=1,
+    /// FP=0, Timestamp=0) and ends at (PC=0, FP=exit_code,
+    /// Timestamp=final_timestamp).
+    pub fn boundaries_with_exit_code(
+        &self,
+        program: &ProgramRom,
+        init_values: &[u32],
+        exit_code: u32,
+        final_timestamp: u32,
+    ) -> Vec<Boundary<B128>> {
+        let mut boundaries = self.state_boundaries(exit_code, final_timestamp);
+        boundaries.extend(self.program_boundaries(program));
+        boundaries.extend(self.input_boundaries(init_values));
+        boundaries
+    }
 
-        // Define the initial state boundary (program starts at PC=1, FP=0)
+    /// Returns the state channel boundaries of an execution exiting with
+    /// `exit_code` at the system timestamp `final_timestamp`.
+    #[cfg_attr(feature = "disable_state_channel", allow(unused_variables))]
+    fn state_boundaries(&self, exit_code: u32, final_timestamp: u32) -> Vec<Boundary<B128>> {
+        // Define the initial state boundary (program starts at PC=1, FP=0,
+        // Timestamp=0)
         #[cfg(not(feature = "disable_state_channel"))]
-        let init_values = vec![B128::new(1), B128::new(0)];
+        let init_values = vec![B128::new(1), B128::new(0), B128::new(0)];
         #[cfg(feature = "disable_state_channel")]
         let init_values = vec![];
         let initial_state = Boundary {
@@ -97,9 +446,14 @@ impl Circuit {
             multiplicity: 1,
         };
 
-        // Define the final state boundary (program ends with PC=0, FP=0)
+        // Define the final state boundary (program ends with PC=0, FP=exit_code,
+        // Timestamp=final_timestamp)
         #[cfg(not(feature = "disable_state_channel"))]
-        let final_values = vec![B128::new(0), B128::new(0)];
+        let final_values = vec![
+            B128::new(0),
+            B128::new(exit_code as u128),
+            B128::new(final_timestamp as u128),
+        ];
         #[cfg(feature = "disable_state_channel")]
         let final_values = vec![];
         let final_state = Boundary {
@@ -109,22 +463,209 @@ impl Circuit {
             multiplicity: 1,
         };
 
-        let prom_size = trace.program.len();
+        vec![initial_state, final_state]
+    }
+
+    /// Returns the channel boundaries of the circuit for an execution of
+    /// `program` from the initial VROM values `init_values`, exiting with code
+    /// 0 without accessing the RAM, and exposing `public_values`, as
+    /// `(address, value)` couples.
+    pub fn boundaries_with_public_values(
+        &self,
+        program: &ProgramRom,
+        init_values: &[u32],
+        public_values: &[(u32, u32)],
+    ) -> Vec<Boundary<B128>> {
+        let mut boundaries = self.boundaries(program, init_values);
+        boundaries.extend(self.public_value_boundaries(public_values));
+        boundaries
+    }
+
+    /// Returns the channel boundaries binding a proof to the execution of
+    /// `program`, pushing every row of the PROM table.
+    pub fn program_boundaries(&self, program: &ProgramRom) -> Vec<Boundary<B128>> {
+        verifier_program(program)
+            .iter()
+            .map(|instr| {
+                let [opcode, arg1, arg2, arg3] = instr.instruction;
+                self.program_boundary(pack_instruction_b128(
+                    instr.field_pc,
+                    opcode,
+                    arg1,
+                    arg2,
+                    arg3,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the program channel boundary pushing a packed instruction.
+    fn program_boundary(&self, instruction: B128) -> Boundary<B128> {
+        Boundary {
+            values: vec![instruction],
+            channel_id: self.channels.program_channel,
+            direction: FlushDirection::Push,
+            multiplicity: 1,
+        }
+    }
+
+    /// Returns the channel boundaries exposing `public_values`, as `(address,
+    /// value)` couples, to be added to the state boundaries of an execution.
+    pub fn public_value_boundaries(&self, public_values: &[(u32, u32)]) -> Vec<Boundary<B128>> {
+        public_values
+            .iter()
+            .map(|&(addr, value)| Boundary {
+                values: vec![B128::new(addr as u128), B128::new(value as u128)],
+                channel_id: self.channels.public_channel,
+                direction: FlushDirection::Push,
+                multiplicity: 1,
+            })
+            .collect()
+    }
+
+    /// Returns the channel boundaries binding a proof to the public outputs
+    /// `outputs`, in the order of the `COMMIT` instructions, to be added to the
+    /// boundaries of an execution committing outputs.
+    ///
+    /// Besides pulling every output, they open the commit channel at index 0
+    /// and close it at the number of outputs, with the timestamp
+    /// `last_timestamp` of the last COMMIT in the commit channel.
+    pub fn output_boundaries(&self, outputs: &[u32], last_timestamp: u32) -> Vec<Boundary<B128>> {
+        if outputs.is_empty() {
+            return Vec::new();
+        }
+
+        let commit_boundary = |index: usize, timestamp: u32, direction| Boundary {
+            values: vec![
+                B128::new(output_index(index).val() as u128),
+                B128::new(timestamp as u128),
+            ],
+            channel_id: self.channels.commit_channel,
+            direction,
+            multiplicity: 1,
+        };
+        outputs
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| Boundary {
+                values: vec![
+                    B128::new(output_index(index).val() as u128),
+                    B128::new(value as u128),
+                ],
+                channel_id: self.channels.output_channel,
+                direction: FlushDirection::Pull,
+                multiplicity: 1,
+            })
+            .chain([
+                commit_boundary(0, 0, FlushDirection::Push),
+                commit_boundary(outputs.len(), last_timestamp, FlushDirection::Pull),
+            ])
+            .collect()
+    }
+
+    /// Returns the channel boundaries restricting the indirect calls of a proof
+    /// to `targets`, as returned by
+    /// [`call_targets`](crate::model::call_targets), to be added to the
+    /// boundaries of an execution of a program with indirect calls.
+    pub fn call_target_boundaries(&self, targets: &[B32]) -> Vec<Boundary<B128>> {
+        targets
+            .iter()
+            .map(|&target| Boundary {
+                values: vec![B128::new(target.val() as u128)],
+                channel_id: self.channels.function_channel,
+                direction: FlushDirection::Push,
+                multiplicity: 1,
+            })
+            .collect()
+    }
+
+    /// Returns the channel boundaries binding a proof to the initial VROM
+    /// values `init_values`, held at the first addresses.
+    pub fn input_boundaries(&self, init_values: &[u32]) -> Vec<Boundary<B128>> {
+        (0..)
+            .zip(init_values)
+            .map(|(addr, &value)| Boundary {
+                values: vec![B128::new(addr), B128::new(value as u128)],
+                channel_id: self.channels.input_channel,
+                direction: FlushDirection::Push,
+                multiplicity: 1,
+            })
+            .collect()
+    }
+
+    /// Returns the channel boundaries binding a proof to the initial RAM
+    /// `image`, loaded from address 0, e.g. the static data of the program.
+    ///
+    /// Only the non-zero words of the image are pushed, see
+    /// [`ram_image_words`], the RAM table initializing every word to zero.
+    pub fn ram_image_boundaries(&self, image: &[u8]) -> Vec<Boundary<B128>> {
+        ram_image_words(image)
+            .into_iter()
+            .map(|(word_addr, value)| Boundary {
+                values: vec![B128::new(word_addr as u128), B128::new(value as u128)],
+                channel_id: self.channels.ram_image_channel,
+                direction: FlushDirection::Push,
+                multiplicity: 1,
+            })
+            .collect()
+    }
+
+    /// Returns the number of rows of each table of the circuit for the given
+    /// trace, in order of table creation.
+    pub fn table_sizes(&self, trace: &Trace) -> anyhow::Result<Vec<TableSize>> {
+        let mut table_sizes = Vec::new();
+        let mut push = |name: &str, rows: usize| table_sizes.push(TableSize::new(name, rows));
+
+        push("PromTable", trace.program.len());
 
         // By adding 1 to `max_vrom_addr`, `next_power_of_two()` will advance to the
         // next power of two even when `max_vrom_addr` is already a power of two,
         // ensuring the VROM address space includes the highest address.
-        let vrom_size = (trace.max_vrom_addr + 1).next_power_of_two();
+        push("VromTable", (trace.max_vrom_addr + 1).next_power_of_two());
 
         // Size of the right shifter table is the number of right shift events
-        let right_shifter_size = trace.right_shift_events().len();
+        if self.right_shifter_table.is_some() {
+            push("RightShifterTable", trace.right_shift_events().len());
+        }
 
-        // Define the table sizes in order of table creation
-        let mut table_sizes = vec![
-            prom_size,          // PROM table size
-            vrom_size,          // VROM table size
-            right_shifter_size, // Right shifter table size
-        ];
+        // The RAM table holds one row per RAM word
+        if self.ram_table.is_some() {
+            push("RamTable", trace.trace.ram().capacity() / 4);
+        }
+
+        // The RAM image table holds one row per non-zero word of the initial
+        // RAM image
+        if self.ram_image_table.is_some() {
+            push("RamImageTable", trace.ram_image().len());
+        }
+
+        // The public table holds one row per public value, and the input table
+        // one row per initial VROM value
+        push("PublicTable", trace.public_values()?.len());
+        push("InputTable", trace.trace.vrom().init_values().len());
+
+        // The memory word table holds one row per word moved by MEMCPY and MEMSET
+        if self.mem_word_table.is_some() {
+            push("MemWordTable", trace.mem_word_events().len());
+        }
+
+        // The Merkle node table holds one row per level of a verified path
+        if self.merkle_node_table.is_some() {
+            push("MerkleNodeTable", trace.merkle_node_events().len());
+        }
+
+        // Each range-check lookup table covers the values looked up in it
+        for table in &self.range_lookup_tables {
+            push(
+                &format!("RangeLookupTable{}", table.bits),
+                trace.range_lookups(table.bits).len(),
+            );
+        }
+
+        // The call target table holds one row per valid indirect call target
+        if self.call_target_table.is_some() {
+            push("CallTargetTable", trace.call_targets.len());
+        }
 
         // Add table sizes for each supported instruction
         for table in &self.tables {
@@ -134,15 +675,133 @@ impl Circuit {
                 table.name(),
                 num_events
             );
-            table_sizes.push(num_events);
+            push(table.name(), num_events);
         }
 
-        // Create the statement with all boundaries
+        Ok(table_sizes)
+    }
+
+    /// Create a circuit statement for a given trace.
+    ///
+    /// # Arguments
+    /// * `trace` - The PetraVM execution trace
+    ///
+    /// # Returns
+    /// * A Statement that defines boundaries and table sizes
+    pub fn create_statement(&self, trace: &Trace) -> anyhow::Result<Statement> {
+        self.create_statement_with_program(trace, self.trace_program_boundaries(trace))
+    }
+
+    /// Returns the program channel boundaries of the statement proving
+    /// `trace`, pushing every row of its PROM table.
+    pub fn trace_program_boundaries(&self, trace: &Trace) -> Vec<Boundary<B128>> {
+        trace
+            .program
+            .iter()
+            .map(|(instr, _)| {
+                let arg = |i: usize| instr.args.get(i).map_or(B16::ZERO, |&arg| B16::new(arg));
+                self.program_boundary(pack_instruction_b128(
+                    instr.pc,
+                    B16::new(instr.opcode),
+                    arg(0),
+                    arg(1),
+                    arg(2),
+                ))
+            })
+            .collect()
+    }
+
+    /// Create a circuit statement for a given trace, given the boundaries of
+    /// its program, as returned by
+    /// [`trace_program_boundaries`](Self::trace_program_boundaries).
+    pub fn create_statement_with_program(
+        &self,
+        trace: &Trace,
+        program_boundaries: Vec<Boundary<B128>>,
+    ) -> anyhow::Result<Statement> {
+        let table_sizes = self
+            .table_sizes(trace)?
+            .into_iter()
+            .map(|table| table.rows)
+            .collect();
+        let call_targets = trace
+            .call_targets
+            .iter()
+            .map(|&(target, _)| B32::new(target))
+            .collect::<Vec<_>>();
+
+        // Create the statement with all boundaries, the program being the one
+        // held by the PROM table
+        let boundaries = self.statement_boundaries(
+            program_boundaries,
+            &trace.public_values()?,
+            &call_targets,
+            trace.trace.ram().initial_data(),
+            &trace.claim()?,
+        );
         let statement = Statement {
-            boundaries: vec![initial_state, final_state],
+            boundaries,
             table_sizes,
         };
 
         Ok(statement)
     }
+
+    /// Returns every channel boundary of the statement proving an execution of
+    /// `program` with the outcome `claim`, as returned by [`Trace::claim`].
+    ///
+    /// Verifiers must check proofs against these boundaries, rather than
+    /// against boundaries received along with the proof, which could carry
+    /// extra flushes.
+    pub fn claim_boundaries(
+        &self,
+        program: &AssembledProgram,
+        claim: &Claim,
+    ) -> anyhow::Result<Vec<Boundary<B128>>> {
+        if claim.public_values.len() != program.public_slots.len() {
+            return Err(anyhow!(
+                "The claim has {} public values, but the program has {} public slots",
+                claim.public_values.len(),
+                program.public_slots.len()
+            ));
+        }
+        let public_values = program
+            .public_slots
+            .iter()
+            .copied()
+            .zip(claim.public_values.iter().copied())
+            .collect::<Vec<_>>();
+        Ok(self.statement_boundaries(
+            self.program_boundaries(&program.prom),
+            &public_values,
+            &call_targets(program),
+            &program.data,
+            claim,
+        ))
+    }
+
+    /// Returns the channel boundaries of a statement, given those of its
+    /// program, its public values, the valid targets of its indirect calls and
+    /// its initial RAM image.
+    fn statement_boundaries(
+        &self,
+        program_boundaries: Vec<Boundary<B128>>,
+        public_values: &[(u32, u32)],
+        call_targets: &[B32],
+        ram_image: &[u8],
+        claim: &Claim,
+    ) -> Vec<Boundary<B128>> {
+        let mut boundaries = self.state_boundaries(claim.exit_code, claim.final_timestamp);
+        boundaries.extend(program_boundaries);
+        boundaries.extend(self.input_boundaries(&claim.init_values));
+        boundaries.extend(self.public_value_boundaries(public_values));
+        boundaries.extend(self.output_boundaries(&claim.outputs, claim.commit_timestamp));
+        if self.call_target_table.is_some() {
+            boundaries.extend(self.call_target_boundaries(call_targets));
+        }
+        if self.ram_image_table.is_some() {
+            boundaries.extend(self.ram_image_boundaries(ram_image));
+        }
+        boundaries
+    }
 }
@@ -0,0 +1,212 @@
+//! Differential testing of the emulator against the circuit.
+//!
+//! Random programs are generated from sequences of [`Op`]s, executed by the
+//! interpreter, and the tables are filled from their trace before validating
+//! the witness. Any disagreement between the semantics of the emulator and the
+//! constraints of the tables fails the validation, and proptest then shrinks
+//! the sequence down to a minimal failing program.
+//!
+//! Unlike the per-opcode tests, the programs mix instructions and calls, so
+//! that values flow across frames: through absolute addresses written by a
+//! callee, possibly after a tail call, and through moves copying the result of
+//! a callee back into the slot of its caller.
+
+use anyhow::{anyhow, Result};
+use petravm_asm::isa::GenericISA;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::{prover::Prover, test_utils::generate_trace};
+
+/// Slot of the entry frame holding the input of the program.
+const INPUT_SLOT: u32 = 2;
+
+/// Instructions with two source slots.
+const BINARY_OPS: &[&str] = &[
+    "ADD", "SUB", "AND", "OR", "XOR", "B32_MUL", "SLL", "SRL", "SRA", "ROL", "ROR", "SLT", "SLTU",
+    "SLE", "SLEU",
+];
+
+/// Instructions with a source slot and an immediate, along with the bound of
+/// their immediate.
+const IMM_OPS: &[(&str, u32)] = &[
+    ("ADDI", 1 << 16),
+    ("ANDI", 1 << 16),
+    ("ORI", 1 << 16),
+    ("XORI", 1 << 16),
+    ("SLTI", 1 << 16),
+    ("SLTIU", 1 << 16),
+    ("SLEI", 1 << 16),
+    ("SLEIU", 1 << 16),
+    ("SLLI", 32),
+    ("SRLI", 32),
+    ("SRAI", 32),
+    ("ROLI", 32),
+    ("RORI", 32),
+];
+
+/// Instructions with a single source slot.
+const UNARY_OPS: &[&str] = &["CLZ", "CTZ", "POPCNT", "BSWAP", "SEXT.B", "SEXT.H"];
+
+/// Functions called by the generated programs, each taking an argument in
+/// slot 2 and returning a result.
+const CALLEES: &[&str] = &["branchy", "forward", "double"];
+
+/// Code of the [`CALLEES`].
+const CALLEES_CODE: &str = "
+#[framesize(0x10)]
+branchy:
+    ;; Slot 2: Arg
+    ;; Slot 3: Result absolute address
+    BNZ branchy_nonzero, @2
+    LDI.W @4, #7
+    MVV.W @3[0], @4
+    RET
+branchy_nonzero:
+    ADDI @4, @2, #1
+    MVV.W @3[0], @4
+    RET
+
+#[framesize(0x10)]
+forward:
+    ;; Slot 2: Arg
+    ;; Slot 3: Result absolute address, written by `branchy`
+    ALLOCI! @4, #16
+    XORI @5, @2, #85
+    MVV.W @4[2], @5
+    MVV.W @4[3], @3
+    TAILI branchy, @4
+
+#[framesize(0x10)]
+double:
+    ;; Slot 2: Arg
+    ;; Slot 3: Result, moved back by the caller
+    ADD @3, @2, @2
+    RET
+";
+
+/// A step of a generated program, writing a fresh slot of the entry frame.
+///
+/// Source slots are given as indices into the slots written so far, wrapping
+/// around, so that any sequence of steps is a valid program and removing steps
+/// while shrinking keeps it valid.
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    /// Loads an immediate.
+    Ldi(u32),
+    /// Applies an instruction to two written slots.
+    Binary(&'static str, usize, usize),
+    /// Applies an instruction to a written slot and an immediate.
+    Imm(&'static str, usize, u32),
+    /// Applies an instruction to a written slot.
+    Unary(&'static str, usize),
+    /// Calls a callee with a written slot, and collects its result.
+    Call(&'static str, usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        any::<u32>().prop_map(Op::Ldi),
+        (select(BINARY_OPS), any::<usize>(), any::<usize>())
+            .prop_map(|(name, src1, src2)| Op::Binary(name, src1, src2)),
+        (select(IMM_OPS), any::<usize>(), any::<u32>())
+            .prop_map(|((name, bound), src, imm)| Op::Imm(name, src, imm % bound)),
+        (select(UNARY_OPS), any::<usize>()).prop_map(|(name, src)| Op::Unary(name, src)),
+        (select(CALLEES), any::<usize>()).prop_map(|(callee, src)| Op::Call(callee, src)),
+    ]
+}
+
+/// Returns the assembly code of the program running `ops` in its entry frame.
+fn program(ops: &[Op]) -> String {
+    let mut written = vec![INPUT_SLOT];
+    let mut next_slot = INPUT_SLOT + 1;
+    let mut fresh = || {
+        next_slot += 1;
+        next_slot - 1
+    };
+
+    let mut code = String::new();
+    for &op in ops {
+        let src = |index: usize| written[index % written.len()];
+        let dst = match op {
+            Op::Ldi(imm) => {
+                let dst = fresh();
+                code += &format!("    LDI.W @{dst}, #{imm}\n");
+                dst
+            }
+            Op::Binary(name, src1, src2) => {
+                let (src1, src2, dst) = (src(src1), src(src2), fresh());
+                code += &format!("    {name} @{dst}, @{src1}, @{src2}\n");
+                dst
+            }
+            Op::Imm(name, src_index, imm) => {
+                let (src, dst) = (src(src_index), fresh());
+                code += &format!("    {name} @{dst}, @{src}, #{imm}\n");
+                dst
+            }
+            Op::Unary(name, src_index) => {
+                let (src, dst) = (src(src_index), fresh());
+                code += &format!("    {name} @{dst}, @{src}\n");
+                dst
+            }
+            Op::Call("double", src_index) => {
+                let (src, next_fp, dst) = (src(src_index), fresh(), fresh());
+                code += &format!(
+                    "    ALLOCI! @{next_fp}, #16\n    MVV.W @{next_fp}[2], @{src}\n    \
+                     CALLI double, @{next_fp}\n    MVV.W @{next_fp}[3], @{dst}\n"
+                );
+                dst
+            }
+            Op::Call(callee, src_index) => {
+                let (src, next_fp, addr, dst) = (src(src_index), fresh(), fresh(), fresh());
+                code += &format!(
+                    "    ALLOCI! @{next_fp}, #16\n    FP @{addr}, #{dst}\n    \
+                     MVV.W @{next_fp}[2], @{src}\n    MVV.W @{next_fp}[3], @{addr}\n    \
+                     CALLI {callee}, @{next_fp}\n"
+                );
+                dst
+            }
+        };
+        written.push(dst);
+    }
+
+    format!("#[framesize({next_slot:#x})]\n_start:\n{code}    RET\n{CALLEES_CODE}")
+}
+
+/// Runs the program of `ops` on `input`, checks its trace against the
+/// interpreter channels, then fills the tables and validates the witness.
+fn check_program(ops: &[Op], input: u32) -> Result<()> {
+    let isa = Box::new(GenericISA);
+    let trace = generate_trace(program(ops), Some(vec![0, 0, input]), None, isa)?;
+    trace.validate()?;
+    Prover::new(Box::new(GenericISA)).diagnose_witness(&trace)
+}
+
+#[test]
+fn test_calls_across_frames() -> Result<()> {
+    let ops = [
+        Op::Call("branchy", 0),
+        Op::Call("forward", 1),
+        Op::Binary("XOR", 1, 2),
+        Op::Call("double", 3),
+        Op::Imm("SRLI", 4, 3),
+        Op::Call("branchy", 5),
+    ];
+    for input in [0, 1, u32::MAX] {
+        check_program(&ops, input).map_err(|e| anyhow!("Input {input}: {e}"))?;
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(proptest::test_runner::Config::with_cases(16))]
+
+    #[test]
+    fn test_random_programs(
+        ops in prop::collection::vec(op(), 1..24),
+        input in prop_oneof![Just(0u32), any::<u32>()],
+    ) {
+        check_program(&ops, input)
+            .map_err(|e| TestCaseError::fail(format!("{e:?}\n{}", program(&ops))))?;
+    }
+}
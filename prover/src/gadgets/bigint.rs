@@ -0,0 +1,496 @@
+//! Gadgets for arithmetic over 256-bit integers.
+//!
+//! Integers are represented as 8 columns of 32-bit limbs, least significant
+//! limb first, as in [`petravm_asm::bigint`]. The gadgets only chain the
+//! 32-bit addition, subtraction and multiplication gadgets, so they can be
+//! combined into larger arithmetic circuits, e.g. for elliptic curve
+//! precompiles. The sum and comparison gadgets take any number of limbs, and
+//! also serve the 64-bit integer instructions.
+
+use std::{array::from_fn, cell::RefMut};
+
+use binius_field::Field;
+use binius_m3::{
+    builder::{upcast_col, Col, TableBuilder, TableWitnessSegment, B1, B32},
+    gadgets::{
+        add::{U32Add, U32AddFlags},
+        mul::MulUU32,
+        sub::{U32Sub, U32SubFlags},
+    },
+};
+use petravm_asm::bigint::{div_rem, mul, LIMBS};
+
+use crate::types::ProverPackedField;
+
+/// A committed copy of a carry (or borrow) bit, in the form expected as input
+/// by the 32-bit addition and subtraction gadgets: a 32-bit column where only
+/// the lowest bit may be set.
+struct CarryColumns {
+    bit: Col<B1, 32>,
+}
+
+impl CarryColumns {
+    fn new(table: &mut TableBuilder, name: &str, carry_out: Col<B1>) -> Self {
+        let bit = table.add_committed(name);
+        let packed: Col<B32> = table.add_packed(format!("{name}_packed"), bit);
+        let carry_out: Col<B32> = upcast_col(carry_out);
+        table.assert_zero(name, packed - carry_out);
+
+        Self { bit }
+    }
+}
+
+/// Multi-operand addition of limbed integers.
+///
+/// Each limb of the output is the sum of the terms of that limb and of the
+/// carries out of the previous limb, computed by a chain of 32-bit additions.
+/// Each addition of the chain consumes one of the incoming carries, and adds a
+/// zero term when the terms of the limb are exhausted.
+///
+/// The carries out of the last limb are dropped, so the sum must not overflow.
+pub(crate) struct LimbSumColumns {
+    zero: Col<B1, 32>,
+    /// The additions of each limb, with their carry input.
+    limbs: Vec<Vec<(U32Add, Option<CarryColumns>)>>,
+    /// The limbs of the sum.
+    pub(crate) output: Vec<Col<B1, 32>>,
+}
+
+impl LimbSumColumns {
+    /// Creates the sum of the given terms, grouped by limb. Every limb needs at
+    /// least one term.
+    pub fn new(table: &mut TableBuilder, name: &str, terms: &[Vec<Col<B1, 32>>]) -> Self {
+        let zero = table.add_constant(format!("{name}_zero"), [B1::ZERO; 32]);
+
+        let mut limbs = Vec::with_capacity(terms.len());
+        let mut output = Vec::with_capacity(terms.len());
+        let mut carries: Vec<Col<B1>> = Vec::new();
+        for (i, limb_terms) in terms.iter().enumerate() {
+            let is_last = i + 1 == terms.len();
+            let num_adds = (limb_terms.len() - 1).max(carries.len());
+
+            let mut acc = limb_terms[0];
+            let mut adds = Vec::with_capacity(num_adds);
+            let mut carries_out = Vec::with_capacity(num_adds);
+            for j in 0..num_adds {
+                let carry_in = carries.get(j).map(|&carry| {
+                    CarryColumns::new(table, &format!("{name}_carry_{i}_{j}"), carry)
+                });
+                let add = U32Add::new(
+                    table,
+                    acc,
+                    limb_terms.get(j + 1).copied().unwrap_or(zero),
+                    U32AddFlags {
+                        carry_in_bit: carry_in.as_ref().map(|carry| carry.bit),
+                        expose_final_carry: !is_last,
+                        ..U32AddFlags::default()
+                    },
+                );
+                acc = add.zout;
+                carries_out.extend(add.final_carry);
+                adds.push((add, carry_in));
+            }
+
+            output.push(acc);
+            limbs.push(adds);
+            carries = carries_out;
+        }
+
+        Self {
+            zero,
+            limbs,
+            output,
+        }
+    }
+
+    /// Populates the sum from the values of its terms, grouped by limb as in
+    /// [`LimbSumColumns::new`].
+    ///
+    /// The term columns must have been populated beforehand.
+    pub fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = Vec<Vec<u32>>>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut zero: RefMut<'_, [u32]> = witness.get_mut_as(self.zero)?;
+            let mut carry_bits = self
+                .limbs
+                .iter()
+                .flatten()
+                .filter_map(|(_, carry_in)| carry_in.as_ref())
+                .map(|carry| witness.get_mut_as(carry.bit))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, terms) in rows.enumerate() {
+                zero[i] = 0;
+
+                // Replay the additions to get the carries.
+                let mut bits = carry_bits.iter_mut();
+                let mut carries: Vec<u32> = Vec::new();
+                for (adds, limb_terms) in self.limbs.iter().zip(&terms) {
+                    let mut acc = limb_terms[0];
+                    let mut carries_out = Vec::with_capacity(adds.len());
+                    for (j, (_, carry_in)) in adds.iter().enumerate() {
+                        let carry = carries.get(j).copied().unwrap_or(0);
+                        if carry_in.is_some() {
+                            bits.next().expect("one view per carry")[i] = carry;
+                        }
+                        let term = limb_terms.get(j + 1).copied().unwrap_or(0);
+                        let sum = acc as u64 + term as u64 + carry as u64;
+                        acc = sum as u32;
+                        carries_out.push((sum >> 32) as u32);
+                    }
+                    carries = carries_out;
+                }
+            }
+        }
+
+        // Populate the additions in the order they were created, as each one
+        // depends on the output of the previous one.
+        for (add, _) in self.limbs.iter().flatten() {
+            add.populate(witness)?;
+        }
+        Ok(())
+    }
+}
+
+/// Unsigned comparison of `N`-limb integers, as the final borrow of their
+/// subtraction.
+pub(crate) struct LimbLtColumns<const N: usize> {
+    subs: [(U32Sub, Option<CarryColumns>); N],
+    /// Set if and only if the first operand is less than the second.
+    pub(crate) lt: Col<B1>,
+}
+
+impl<const N: usize> LimbLtColumns<N> {
+    pub fn new(
+        table: &mut TableBuilder,
+        name: &str,
+        x: [Col<B1, 32>; N],
+        y: [Col<B1, 32>; N],
+    ) -> Self {
+        let mut subs: Vec<(U32Sub, Option<CarryColumns>)> = Vec::with_capacity(N);
+        for i in 0..N {
+            let borrow_in = subs.last().map(|(sub, _)| {
+                let borrow = sub
+                    .final_borrow
+                    .expect("Flag `expose_final_borrow` was set to `true`");
+                CarryColumns::new(table, &format!("{name}_borrow_{i}"), borrow)
+            });
+            let sub = U32Sub::new(
+                table,
+                x[i],
+                y[i],
+                U32SubFlags {
+                    borrow_in_bit: borrow_in.as_ref().map(|borrow| borrow.bit),
+                    expose_final_borrow: true,
+                    commit_zout: false,
+                },
+            );
+            subs.push((sub, borrow_in));
+        }
+
+        let lt = subs[N - 1]
+            .0
+            .final_borrow
+            .expect("Flag `expose_final_borrow` was set to `true`");
+        let subs = subs
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("one subtraction per limb"));
+
+        Self { subs, lt }
+    }
+
+    /// Populates the comparison from the values of its operands, whose columns
+    /// must have been populated beforehand.
+    pub fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = ([u32; N], [u32; N])>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut borrow_bits = self
+                .subs
+                .iter()
+                .filter_map(|(_, borrow_in)| borrow_in.as_ref())
+                .map(|borrow| witness.get_mut_as(borrow.bit))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, (x, y)) in rows.enumerate() {
+                let mut borrow = false;
+                for j in 0..N {
+                    if j > 0 {
+                        borrow_bits[j - 1][i] = borrow as u32;
+                    }
+                    let (diff, b0) = x[j].overflowing_sub(y[j]);
+                    let (_, b1) = diff.overflowing_sub(borrow as u32);
+                    borrow = b0 || b1;
+                }
+            }
+        }
+
+        for (sub, _) in &self.subs {
+            sub.populate(witness)?;
+        }
+        Ok(())
+    }
+}
+
+/// The 64-bit product of two 32-bit limbs.
+struct LimbProductColumns {
+    mul_op: MulUU32,
+    low: Col<B1, 32>,
+    high: Col<B1, 32>,
+}
+
+/// Returns the products `x[i] * y[j]` of all pairs of limbs, ordered by `i`
+/// and then `j`.
+fn limb_products(x: [u32; LIMBS], y: [u32; LIMBS]) -> impl Iterator<Item = u64> {
+    (0..LIMBS).flat_map(move |i| (0..LIMBS).map(move |j| x[i] as u64 * y[j] as u64))
+}
+
+/// Computes `x * y + z` for 256-bit integers, as a 512-bit integer.
+///
+/// The 64 products of limbs are computed with 32-bit multiplications, and then
+/// summed with [`LimbSumColumns`].
+pub(crate) struct U256MulAddColumns {
+    products: Vec<LimbProductColumns>,
+    has_addend: bool,
+    sum: LimbSumColumns,
+    /// The limbs of the result.
+    pub(crate) output: [Col<B1, 32>; 2 * LIMBS],
+}
+
+impl U256MulAddColumns {
+    pub fn new(
+        table: &mut TableBuilder,
+        name: &str,
+        x: [Col<B1, 32>; LIMBS],
+        y: [Col<B1, 32>; LIMBS],
+        z: Option<[Col<B1, 32>; LIMBS]>,
+    ) -> Self {
+        let x_packed: [Col<B32>; LIMBS] =
+            from_fn(|i| table.add_packed(format!("{name}_x_packed_{i}"), x[i]));
+        let y_packed: [Col<B32>; LIMBS] =
+            from_fn(|i| table.add_packed(format!("{name}_y_packed_{i}"), y[i]));
+
+        // The terms of each limb of the result.
+        let mut terms = vec![Vec::new(); 2 * LIMBS];
+        if let Some(z) = z {
+            for i in 0..LIMBS {
+                terms[i].push(z[i]);
+            }
+        }
+
+        let mut products = Vec::with_capacity(LIMBS * LIMBS);
+        for i in 0..LIMBS {
+            for j in 0..LIMBS {
+                let mul_op = MulUU32::new(table);
+                table.assert_zero(format!("{name}_xin_{i}_{j}"), mul_op.xin - x_packed[i]);
+                table.assert_zero(format!("{name}_yin_{i}_{j}"), mul_op.yin - y_packed[j]);
+
+                let low = table.add_committed(format!("{name}_low_{i}_{j}"));
+                let low_packed: Col<B32> =
+                    table.add_packed(format!("{name}_low_packed_{i}_{j}"), low);
+                table.assert_zero(
+                    format!("{name}_out_low_{i}_{j}"),
+                    mul_op.out_low - low_packed,
+                );
+                let high = table.add_committed(format!("{name}_high_{i}_{j}"));
+                let high_packed: Col<B32> =
+                    table.add_packed(format!("{name}_high_packed_{i}_{j}"), high);
+                table.assert_zero(
+                    format!("{name}_out_high_{i}_{j}"),
+                    mul_op.out_high - high_packed,
+                );
+
+                terms[i + j].push(low);
+                terms[i + j + 1].push(high);
+                products.push(LimbProductColumns { mul_op, low, high });
+            }
+        }
+
+        let sum = LimbSumColumns::new(table, name, &terms);
+        let output = from_fn(|i| sum.output[i]);
+
+        Self {
+            products,
+            has_addend: z.is_some(),
+            sum,
+            output,
+        }
+    }
+
+    /// Populates the gadget from the values of `(x, y, z)`, where `z` is
+    /// ignored if the gadget has no addend.
+    ///
+    /// The input columns must have been populated beforehand.
+    pub fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = ([u32; LIMBS], [u32; LIMBS], [u32; LIMBS])> + Clone,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut low = self
+                .products
+                .iter()
+                .map(|product| witness.get_mut_as(product.low))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut high = self
+                .products
+                .iter()
+                .map(|product| witness.get_mut_as(product.high))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, (x, y, _)) in rows.clone().enumerate() {
+                for (k, product) in limb_products(x, y).enumerate() {
+                    low[k][i] = product as u32;
+                    high[k][i] = (product >> 32) as u32;
+                }
+            }
+        }
+
+        for (k, product) in self.products.iter().enumerate() {
+            let (i, j) = (k / LIMBS, k % LIMBS);
+            let x_vals = rows.clone().map(|(x, _, _)| B32::new(x[i]));
+            let y_vals = rows.clone().map(|(_, y, _)| B32::new(y[j]));
+            product
+                .mul_op
+                .populate_with_inputs(witness, x_vals, y_vals)?;
+        }
+
+        // Group the values of the terms by limb, in the order of creation.
+        let terms = rows.map(|(x, y, z)| {
+            let mut terms = vec![Vec::new(); 2 * LIMBS];
+            if self.has_addend {
+                for i in 0..LIMBS {
+                    terms[i].push(z[i]);
+                }
+            }
+            for (k, product) in limb_products(x, y).enumerate() {
+                let (i, j) = (k / LIMBS, k % LIMBS);
+                terms[i + j].push(product as u32);
+                terms[i + j + 1].push((product >> 32) as u32);
+            }
+            terms
+        });
+        self.sum.populate(witness, terms)
+    }
+}
+
+/// Computes `a * b mod m` for 256-bit integers.
+///
+/// The quotient `q` and the remainder `r` are committed, and constrained by `a
+/// * b = q * m + r` and `r < m`. The quotient must fit in 256 bits, which holds
+/// when `a` is reduced modulo `m`.
+pub(crate) struct U256MulModColumns {
+    quotient: [Col<B1, 32>; LIMBS],
+    /// The limbs of the result.
+    pub(crate) remainder: [Col<B1, 32>; LIMBS],
+    product: U256MulAddColumns,
+    reduced: U256MulAddColumns,
+    range_check: LimbLtColumns<LIMBS>,
+}
+
+impl U256MulModColumns {
+    pub fn new(
+        table: &mut TableBuilder,
+        name: &str,
+        a: [Col<B1, 32>; LIMBS],
+        b: [Col<B1, 32>; LIMBS],
+        m: [Col<B1, 32>; LIMBS],
+    ) -> Self {
+        let quotient = from_fn(|i| table.add_committed(format!("{name}_quotient_{i}")));
+        let remainder = from_fn(|i| table.add_committed(format!("{name}_remainder_{i}")));
+
+        // a * b = q * m + r. Both sides are less than 2^512, so they can't
+        // overflow.
+        let product = U256MulAddColumns::new(table, &format!("{name}_product"), a, b, None);
+        let reduced = U256MulAddColumns::new(
+            table,
+            &format!("{name}_reduced"),
+            quotient,
+            m,
+            Some(remainder),
+        );
+        for i in 0..2 * LIMBS {
+            table.assert_zero(
+                format!("{name}_limb_{i}"),
+                product.output[i] - reduced.output[i],
+            );
+        }
+
+        // r < m, which also rules out a zero modulus.
+        let range_check = LimbLtColumns::new(table, &format!("{name}_range"), remainder, m);
+        table.assert_zero(
+            format!("{name}_remainder_lt_modulus"),
+            range_check.lt - B1::ONE,
+        );
+
+        Self {
+            quotient,
+            remainder,
+            product,
+            reduced,
+            range_check,
+        }
+    }
+
+    /// Populates the gadget from the values of `(a, b, m)`.
+    ///
+    /// The input columns must have been populated beforehand.
+    pub fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = ([u32; LIMBS], [u32; LIMBS], [u32; LIMBS])> + Clone,
+    ) -> Result<(), anyhow::Error> {
+        let divisions = rows
+            .clone()
+            .map(|(a, b, m)| {
+                let (q, r) = div_rem(&mul(&a, &b), &m)
+                    .ok_or_else(|| anyhow::anyhow!("The modulus is zero."))?;
+                let (q_low, q_high) = q.split_at(LIMBS);
+                anyhow::ensure!(
+                    q_high.iter().all(|&limb| limb == 0),
+                    "The quotient doesn't fit in 256 bits."
+                );
+                Ok((from_fn(|i| q_low[i]), r))
+            })
+            .collect::<Result<Vec<([u32; LIMBS], [u32; LIMBS])>, anyhow::Error>>()?;
+
+        {
+            let mut quotient = self
+                .quotient
+                .iter()
+                .map(|&col| witness.get_mut_as(col))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut remainder = self
+                .remainder
+                .iter()
+                .map(|&col| witness.get_mut_as(col))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, (q, r)) in divisions.iter().enumerate() {
+                for j in 0..LIMBS {
+                    quotient[j][i] = q[j];
+                    remainder[j][i] = r[j];
+                }
+            }
+        }
+
+        self.product
+            .populate(witness, rows.clone().map(|(a, b, _)| (a, b, [0; LIMBS])))?;
+        self.reduced.populate(
+            witness,
+            rows.clone()
+                .zip(divisions.iter())
+                .map(|((_, _, m), &(q, r))| (q, m, r)),
+        )?;
+        self.range_check.populate(
+            witness,
+            rows.zip(divisions.iter())
+                .map(|((_, _, m), &(_, r))| (r, m)),
+        )
+    }
+}
@@ -0,0 +1,81 @@
+use binius_m3::builder::{Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32};
+use binius_m3::gadgets::lookup::LookupProducer;
+
+use crate::channels::Channels;
+use crate::prover::CALL_TARGET_MULTIPLICITY_BITS;
+use crate::types::ProverPackedField;
+
+/// Lookup table of the valid targets of the indirect calls.
+///
+/// Each row is pulled from the function channel, pushed by the verifier with
+/// the function entry points of the program, so that the table only holds
+/// declared functions. It pushes each target to the call target channel with
+/// the number of CALLV and TAILV landing on it, so that indirect calls can't
+/// jump in the middle of a function. The rows are collected by
+/// [`Trace::call_targets`](crate::model::Trace::call_targets).
+///
+/// Format: [Target]
+pub struct CallTargetTable {
+    /// Table ID
+    pub id: TableId,
+    /// Target column (sorted by multiplicity)
+    target: Col<B32>,
+    /// To support multiple lookups, we need to create a lookup producer
+    lookup_producer: LookupProducer,
+}
+
+impl CallTargetTable {
+    /// Create a new call target table with the given constraint system and
+    /// channels.
+    ///
+    /// # Arguments
+    /// * `cs` - [`ConstraintSystem`] to add the table to
+    /// * `channels` - [`Channels`] IDs for communication with other tables
+    pub fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("call_target");
+        table.require_power_of_two_size();
+
+        let target = table.add_committed("target");
+        table.pull(channels.function_channel, [target]);
+
+        let lookup_producer = LookupProducer::new(
+            &mut table,
+            channels.call_target_channel,
+            &[target],
+            CALL_TARGET_MULTIPLICITY_BITS,
+        );
+
+        Self {
+            id: table.id(),
+            target,
+            lookup_producer,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for CallTargetTable {
+    type Event = (u32, u32);
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut target_col = witness.get_scalars_mut(self.target)?;
+            for (i, (target, _)) in rows.clone().enumerate() {
+                target_col[i] = B32::new(*target);
+            }
+        }
+
+        // Populate lookup producer with multiplicity iterator
+        self.lookup_producer
+            .populate(witness, rows.map(|(_, multiplicity)| *multiplicity))?;
+
+        Ok(())
+    }
+}
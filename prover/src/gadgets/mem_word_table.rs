@@ -0,0 +1,140 @@
+use binius_m3::builder::{
+    Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+};
+use binius_m3::gadgets::add::{U32Add, U32AddFlags};
+use petravm_asm::event::MemWordGadgetEvent;
+
+use crate::channels::Channels;
+use crate::table::Table;
+use crate::types::ProverPackedField;
+use crate::utils::pull_vrom_channel;
+
+/// Table moving the words of the MEMCPY and MEMSET instructions, one row per
+/// moved word.
+///
+/// Each row pulls `[dst, src, stride, remaining + 1]` from the memory word
+/// channel, moves `VROM[src]` into `VROM[dst]`, and pushes
+/// `[dst + 1, src + stride, stride, remaining]` for the next word. The
+/// instruction tables push the first tuple of a block and pull the tuple left
+/// after its last word, whose remaining count is zero.
+pub struct MemWordTable {
+    id: TableId,
+    dst_addr: Col<B1, 32>,   // Destination address in unpacked form
+    src_addr: Col<B1, 32>,   // Source address in unpacked form
+    src_stride: Col<B1, 32>, // Source address increment in unpacked form
+    remaining: Col<B1, 32>,  // Remaining words after this one in unpacked form
+    val: Col<B32>,
+    one: Col<B1, 32>, // Constant
+    /// Number of remaining words, including this one.
+    cur_remaining: U32Add,
+    next_dst_addr: U32Add,
+    next_src_addr: U32Add,
+}
+
+impl Table for MemWordTable {
+    type Event = MemWordGadgetEvent;
+
+    fn name(&self) -> &'static str {
+        "MemWordTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("mem_word");
+
+        // Define columns
+        let dst_addr: Col<B1, 32> = table.add_committed("dst_addr");
+        let dst_addr_packed: Col<B32> = table.add_packed("dst_addr_packed", dst_addr);
+        let src_addr: Col<B1, 32> = table.add_committed("src_addr");
+        let src_addr_packed: Col<B32> = table.add_packed("src_addr_packed", src_addr);
+        let src_stride: Col<B1, 32> = table.add_committed("src_stride");
+        let src_stride_packed: Col<B32> = table.add_packed("src_stride_packed", src_stride);
+        let remaining: Col<B1, 32> = table.add_committed("remaining");
+        let remaining_packed: Col<B32> = table.add_packed("remaining_packed", remaining);
+        let val = table.add_committed("val");
+
+        let mut one_bits = [B1::ZERO; 32];
+        one_bits[0] = B1::ONE;
+        let one = table.add_constant("one", one_bits);
+
+        // Step to the next word
+        let cur_remaining = U32Add::new(&mut table, remaining, one, U32AddFlags::default());
+        let cur_remaining_packed = table.add_packed("cur_remaining_packed", cur_remaining.zout);
+        let next_dst_addr = U32Add::new(&mut table, dst_addr, one, U32AddFlags::default());
+        let next_dst_addr_packed = table.add_packed("next_dst_addr_packed", next_dst_addr.zout);
+        let next_src_addr = U32Add::new(&mut table, src_addr, src_stride, U32AddFlags::default());
+        let next_src_addr_packed = table.add_packed("next_src_addr_packed", next_src_addr.zout);
+
+        // Read the source word and write it to the destination
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_addr_packed, val]);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_addr_packed, val]);
+
+        // Chain the words of a block
+        table.pull(
+            channels.mem_word_channel,
+            [
+                dst_addr_packed,
+                src_addr_packed,
+                src_stride_packed,
+                cur_remaining_packed,
+            ],
+        );
+        table.push(
+            channels.mem_word_channel,
+            [
+                next_dst_addr_packed,
+                next_src_addr_packed,
+                src_stride_packed,
+                remaining_packed,
+            ],
+        );
+
+        Self {
+            id: table.id(),
+            dst_addr,
+            src_addr,
+            src_stride,
+            remaining,
+            val,
+            one,
+            cur_remaining,
+            next_dst_addr,
+            next_src_addr,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MemWordTable {
+    type Event = MemWordGadgetEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a MemWordGadgetEvent> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_addr = witness.get_mut_as(self.dst_addr)?;
+            let mut src_addr = witness.get_mut_as(self.src_addr)?;
+            let mut src_stride = witness.get_mut_as(self.src_stride)?;
+            let mut remaining = witness.get_mut_as(self.remaining)?;
+            let mut val = witness.get_scalars_mut(self.val)?;
+            let mut one = witness.get_mut_as(self.one)?;
+
+            for (i, ev) in rows.enumerate() {
+                dst_addr[i] = ev.dst_addr;
+                src_addr[i] = ev.src_addr;
+                src_stride[i] = ev.src_stride;
+                remaining[i] = ev.remaining;
+                val[i] = B32::new(ev.val);
+                one[i] = 1u32;
+            }
+        }
+
+        self.cur_remaining.populate(witness)?;
+        self.next_dst_addr.populate(witness)?;
+        self.next_src_addr.populate(witness)
+    }
+}
@@ -0,0 +1,342 @@
+use std::{array::from_fn, cell::RefMut, iter::once};
+
+use binius_core::oracle::ShiftVariant;
+use binius_field::{packed::set_packed_slice, AESTowerField8b, Field};
+use binius_hash::groestl::{GroestlShortImpl, GroestlShortInternal};
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, Expr, TableFiller, TableId, TableWitnessSegment, B1, B32, B8,
+};
+use binius_m3::gadgets::{
+    add::{U32Add, U32AddFlags},
+    hash::groestl::{Permutation, PermutationVariant},
+};
+use petravm_asm::{event::MerkleNodeGadgetEvent, util::u32_to_bytes};
+
+use crate::channels::Channels;
+use crate::gadgets::{aes_to_bin::AesBinTransformColumns, transpose::TransposeColumns};
+use crate::opcodes::groestl::get_all_addresses;
+use crate::table::Table;
+use crate::types::ProverPackedField;
+
+/// Table hashing the levels of the Merkle paths verified by MERKLE_VERIFY, one
+/// row per level.
+///
+/// Each row pulls `[addr, node, index]` from the Merkle channel, reads the
+/// sibling of the node at `addr + 8`, and pushes `[addr + 8, parent, index >>
+/// 1]` for the next level, the parent being the Groestl-256 output
+/// transformation of the children ordered by the lowest bit of the index. The
+/// MERKLE_VERIFY table pushes the leaf of a path and pulls its root.
+///
+/// The children are committed in the AES basis, in the transposed order of the
+/// P permutation, and the output is truncated and transposed back like in the
+/// [`Groestl256OutputTable`](crate::opcodes::Groestl256OutputTable).
+pub struct MerkleNodeTable {
+    id: TableId,
+    addr: Col<B1, 32>,
+    eight: Col<B1, 32>, // Constant
+    next_addr: U32Add,
+    sibling_addrs: [Col<B32>; 8], // Virtual
+    index: Col<B1, 32>,
+    /// Whether the node is the right child of its parent.
+    bit: Col<B1>, // Virtual
+    next_index: Col<B1, 32>, // Virtual
+    node: [Col<B32>; 8],
+    sibling: [Col<B32>; 8],
+    /// Concatenation of the children, transposed and in the AES basis.
+    state_bits: [Col<B1, 64>; 8],
+    /// Columns reading the words of the children from the state.
+    state_transposition: TransposeColumns,
+    /// Columns to switch the state from the AES basis to the binary basis.
+    state_in_columns: [AesBinTransformColumns<true>; 8],
+    /// Output of the P permutation XORed with its input, in the binary basis.
+    out: [Col<B8, 8>; 8],
+    out_bits: [Col<B1, 64>; 8],
+    /// Columns to get the output in the AES basis.
+    out_aes_columns: [AesBinTransformColumns<false>; 8],
+    /// Columns transposing and truncating the output into the parent.
+    projected_out: [Col<B8>; 64],
+    zero_padded_out: [Col<B8, 4>; 32],
+    parent: [Col<B32>; 8],
+    /// P permutation.
+    p_op: Permutation,
+}
+
+impl Table for MerkleNodeTable {
+    type Event = MerkleNodeGadgetEvent;
+
+    fn name(&self) -> &'static str {
+        "MerkleNodeTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("merkle_node");
+
+        // Step to the next level
+        let addr: Col<B1, 32> = table.add_committed("addr");
+        let addr_packed: Col<B32> = table.add_packed("addr_packed", addr);
+        let mut eight_bits = [B1::ZERO; 32];
+        eight_bits[3] = B1::ONE;
+        let eight = table.add_constant("eight", eight_bits);
+        let next_addr = U32Add::new(&mut table, addr, eight, U32AddFlags::default());
+        let next_addr_packed: Col<B32> = table.add_packed("next_addr_packed", next_addr.zout);
+
+        let index: Col<B1, 32> = table.add_committed("index");
+        let index_packed: Col<B32> = table.add_packed("index_packed", index);
+        let bit = table.add_selected("bit", index, 0);
+        let next_index = table.add_shifted("next_index", index, 5, 1, ShiftVariant::LogicalRight);
+        let next_index_packed: Col<B32> = table.add_packed("next_index_packed", next_index);
+
+        // Read the sibling
+        let node: [Col<B32>; 8] = from_fn(|i| table.add_committed(format!("node_{i}")));
+        let sibling: [Col<B32>; 8] = from_fn(|i| table.add_committed(format!("sibling_{i}")));
+        let sibling_addrs = get_all_addresses(&mut table, next_addr_packed.into(), "sibling_addr");
+        for i in 0..8 {
+            table.pull(channels.vrom_channel, [sibling_addrs[i], sibling[i]]);
+        }
+
+        // Order the children: left = bit ? sibling : node, right = bit ? node :
+        // sibling
+        let state_bits: [Col<B1, 64>; 8] =
+            from_fn(|i| table.add_committed(format!("state_bits_{i}")));
+        let state = from_fn(|i| table.add_packed(format!("state_{i}"), state_bits[i]));
+        let state_transposition = TransposeColumns::new(&mut table, state);
+        let (left, right) = state_transposition.output.split_at(8);
+        for i in 0..8 {
+            let swap = upcast_col(bit) * (node[i] + sibling[i]);
+            table.assert_zero("left_child", left[i] - (node[i] + swap.clone()));
+            table.assert_zero("right_child", right[i] - (sibling[i] + swap));
+        }
+
+        // Carry out the output transformation in the binary basis.
+        let state_in_columns = from_fn(|i| {
+            AesBinTransformColumns::<true>::new(&mut table, state_bits[i], "merkle_node_state_in")
+        });
+        let state_in: [Col<B8, 8>; 8] = from_fn(|i| state_in_columns[i].reshaped_outputs);
+        let p_op = Permutation::new(&mut table, PermutationVariant::P, state_in);
+        let p_out_array = p_op.state_out();
+        let out: [Col<B8, 8>; 8] =
+            from_fn(|i| table.add_computed(format!("out_{i}"), p_out_array[i] + state_in[i]));
+
+        // Go from the binary basis to the AES basis.
+        let out_bits: [Col<B1, 64>; 8] = from_fn(|i| table.add_committed(format!("out_bits_{i}")));
+        let out_aes_columns = from_fn(|i| {
+            AesBinTransformColumns::<false>::new(&mut table, out_bits[i], "merkle_node_out_aes")
+        });
+        let out_aes: [Col<B8, 8>; 8] = from_fn(|i| out_aes_columns[i].reshaped_outputs);
+        for i in 0..8 {
+            let out_bits_packed: Col<B8, 8> = table.add_packed("out_bits_packed", out_bits[i]);
+            table.assert_zero("Check out_bits_packed", out_bits_packed - out[i]);
+        }
+
+        // Transpose the output and keep its last 32 bytes, packed into words.
+        let projected_out_temp: [[Col<B8>; 8]; 8] = from_fn(|i| {
+            from_fn(|j| table.add_selected(format!("projected_out_{i}_{j}"), out_aes[i], j))
+        });
+        let projected_out = from_fn(|i| projected_out_temp[i % 8][i / 8]);
+        let zero_padded_out = from_fn(|i| {
+            table.add_zero_pad::<_, 1, 4>(
+                format!("zero_padded_out_{i}"),
+                projected_out[32 + i],
+                i % 4,
+            )
+        });
+        let parent: [Col<B32>; 8] = from_fn(|i| {
+            let expr: Expr<B8, 4> = zero_padded_out[4 * i..4 * (i + 1)]
+                .iter()
+                .map(|&col| col.into())
+                .reduce(|acc, item| acc + item)
+                .expect("The iterator is not empty");
+            let sum = table.add_computed(format!("zero_padded_sums_out_{i}"), expr);
+            table.add_packed(format!("parent_{i}"), sum)
+        });
+
+        // Chain the levels of a path
+        table.pull(
+            channels.merkle_channel,
+            once(addr_packed).chain(node).chain([index_packed]),
+        );
+        table.push(
+            channels.merkle_channel,
+            once(next_addr_packed)
+                .chain(parent)
+                .chain([next_index_packed]),
+        );
+
+        Self {
+            id: table.id(),
+            addr,
+            eight,
+            next_addr,
+            sibling_addrs,
+            index,
+            bit,
+            next_index,
+            node,
+            sibling,
+            state_bits,
+            state_transposition,
+            state_in_columns,
+            out,
+            out_bits,
+            out_aes_columns,
+            projected_out,
+            zero_padded_out,
+            parent,
+            p_op,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MerkleNodeTable {
+    type Event = MerkleNodeGadgetEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a MerkleNodeGadgetEvent> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        // Concatenation of the children of each row, in the AES basis.
+        let states = rows
+            .clone()
+            .map(|ev| {
+                let (left, right) = ev.children();
+                <[u8; 64]>::try_from([u32_to_bytes(&left), u32_to_bytes(&right)].concat())
+                    .expect("The children contain 64 bytes")
+            })
+            .collect::<Vec<_>>();
+        {
+            let mut addr = witness.get_mut_as(self.addr)?;
+            let mut eight = witness.get_mut_as(self.eight)?;
+            let mut sibling_addrs = (0..8)
+                .map(|i| witness.get_scalars_mut(self.sibling_addrs[i]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut index = witness.get_mut_as(self.index)?;
+            let mut bit = witness.get_mut(self.bit)?;
+            let mut next_index = witness.get_mut_as(self.next_index)?;
+            let mut node = (0..8)
+                .map(|i| witness.get_scalars_mut(self.node[i]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut sibling = (0..8)
+                .map(|i| witness.get_scalars_mut(self.sibling[i]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut out = (0..8)
+                .map(|i| witness.get_scalars_mut(self.out[i]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut out_bits = (0..8)
+                .map(|i| witness.get_mut_as(self.out_bits[i]))
+                .collect::<Result<Vec<RefMut<'_, [[u8; 8]]>>, _>>()?;
+            let mut projected_out = (0..64)
+                .map(|i| witness.get_mut_as(self.projected_out[i]))
+                .collect::<Result<Vec<RefMut<'_, [u8]>>, _>>()?;
+            let mut zero_padded_out = (0..32)
+                .map(|i| witness.get_mut_as(self.zero_padded_out[i]))
+                .collect::<Result<Vec<RefMut<'_, [[u8; 4]]>>, _>>()?;
+            let mut parent = (0..8)
+                .map(|i| witness.get_mut_as(self.parent[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, (ev, state)) in rows.clone().zip(&states).enumerate() {
+                addr[i] = ev.addr;
+                eight[i] = 8u32;
+                index[i] = ev.index;
+                set_packed_slice(&mut bit, i, B1::from((ev.index & 1) as u8));
+                next_index[i] = ev.index >> 1;
+
+                // Compute P(h) XOR h, in the binary basis.
+                let mut p_state = GroestlShortImpl::state_from_bytes(state);
+                GroestlShortImpl::p_perm(&mut p_state);
+                let p_out = GroestlShortImpl::state_to_bytes(&p_state);
+                let parent_bytes = u32_to_bytes(&ev.parent);
+
+                for j in 0..8 {
+                    sibling_addrs[j][i] = B32::new(ev.sibling_addr() + j as u32);
+                    node[j][i] = B32::new(ev.node[j]);
+                    sibling[j][i] = B32::new(ev.sibling[j]);
+                    parent[j][i] = ev.parent[j];
+
+                    for k in 0..8 {
+                        let pos = k * 8 + j;
+                        let out_val = B8::from(AESTowerField8b::new(p_out[pos]))
+                            + B8::from(AESTowerField8b::new(state[pos]));
+                        out[j][i * 8 + k] = out_val;
+                        out_bits[j][i][k] = out_val.val();
+                        projected_out[pos][i] = AESTowerField8b::from(out_val).val();
+                    }
+                }
+                for (j, &byte) in parent_bytes.iter().enumerate() {
+                    zero_padded_out[j][i][j % 4] = byte;
+                }
+            }
+        }
+
+        self.next_addr.populate(witness)?;
+
+        // Populate the state and its switch to the binary basis.
+        self.state_transposition
+            .populate(witness, states.iter().copied())?;
+        for (i, col) in self.state_in_columns.iter().enumerate() {
+            let chunks = states.iter().map(|state| from_fn(|k| state[k * 8 + i]));
+            col.populate(witness, chunks)?;
+        }
+
+        // Populate the switch of the output to the AES basis.
+        {
+            let outs = (0..8)
+                .map(|i| Ok(witness.get_as(self.out[i])?.to_vec()))
+                .collect::<anyhow::Result<Vec<Vec<[u8; 8]>>>>()?;
+            for (col, out) in self.out_aes_columns.iter().zip(outs) {
+                col.populate(witness, out.into_iter())?;
+            }
+        }
+
+        // The input of the P permutation is populated with the state.
+        self.p_op.populate(witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::{
+        isa::GenericISA,
+        merkle_root,
+        util::{bytes_to_u32, groestl256},
+    };
+
+    use crate::{prover::Prover, test_utils::generate_trace};
+
+    #[test]
+    fn test_merkle_node_sides() -> Result<()> {
+        // Verify the paths of all the leaves of a tree of depth 2.
+        let leaves: Vec<[u32; 8]> = (0..4u8)
+            .map(|i| bytes_to_u32(&groestl256(&[i])).try_into().unwrap())
+            .collect();
+        let level1 = [
+            merkle_root(leaves[0], 0, &[leaves[1]]),
+            merkle_root(leaves[2], 0, &[leaves[3]]),
+        ];
+        let root = merkle_root(level1[0], 0, &[level1[1]]);
+
+        let mut asm_code = "#[framesize(0x100)]\n_start:\n".to_string();
+        let mut init_values = vec![0; 8];
+        init_values.extend(root);
+        for (index, leaf) in leaves.iter().enumerate() {
+            let offset = 16 + 32 * index;
+            let path = [leaves[index ^ 1], level1[(index >> 1) ^ 1]];
+            asm_code += &format!("MERKLE_VERIFY @8, @{offset}, @{}\n", offset + 8);
+            init_values.extend(leaf);
+            init_values.extend([index as u32, 2, 0, 0, 0, 0, 0, 0]);
+            init_values.extend(path.iter().flatten());
+        }
+        asm_code += "RET\n";
+
+        let trace = generate_trace(asm_code, Some(init_values), None, Box::new(GenericISA))?;
+        trace.validate()?;
+        assert_eq!(trace.merkle_node_events().len(), 8);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+}
@@ -1,5 +1,10 @@
 pub mod aes_to_bin;
+pub mod bigint;
+pub mod call_target_table;
+pub mod mem_word_table;
+pub mod merkle_node_table;
 pub mod multiple_lookup;
+pub mod range_lookup_table;
 pub mod right_shifter_table;
 pub mod state;
 pub mod transpose;
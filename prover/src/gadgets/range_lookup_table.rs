@@ -0,0 +1,112 @@
+use binius_m3::builder::{
+    Col, ConstraintSystem, StructuredDynSize, TableFiller, TableId, TableWitnessSegment, B32,
+};
+use binius_m3::gadgets::lookup::LookupProducer;
+use binius_m3::gadgets::structured::fill_incrementing_b32;
+
+use crate::channels::Channels;
+use crate::prover::RANGE_CHECK_MULTIPLICITY_BITS;
+use crate::types::ProverPackedField;
+
+/// Range-check lookup table, shared by all the tables needing to check that a
+/// value fits in `bits` bits.
+///
+/// The table holds each value `0, 1, ..., size - 1` exactly once, where `size`
+/// is a power of two no greater than `2^bits`, as guaranteed by the
+/// permutation of its value column with an incrementing value space. It
+/// pushes `[bits, value]` to the range-check channel with the number of
+/// lookups of each value, so a table checks that `value < 2^bits` by pulling
+/// `[bits, value]` from the channel. The multiplicities are collected by
+/// [`Trace::range_lookups`](crate::model::Trace::range_lookups).
+///
+/// The lookup is meant for values committed as packed field elements, which
+/// carry no range constraint of their own. It is only looked up by RANGE_CHECK
+/// for now: the integer instructions (MULU, DIVU, ...) must decompose their
+/// operands into bits anyway for the 32-bit arithmetic gadgets, as integer
+/// carries can't be expressed over a binary field, and those committed bits
+/// already bound their limbs.
+///
+/// Format: [Bits, Value]
+pub struct RangeLookupTable {
+    /// Table ID
+    pub id: TableId,
+    /// Width of the range, in bits
+    pub bits: u16,
+    /// Sorted value space
+    value_space: Col<B32>,
+    /// Value column (sorted by multiplicity)
+    value: Col<B32>,
+    /// To support multiple lookups, we need to create a lookup producer
+    lookup_producer: LookupProducer,
+}
+
+impl RangeLookupTable {
+    /// Create a new range-check lookup table for values of `bits` bits.
+    ///
+    /// # Arguments
+    /// * `cs` - [`ConstraintSystem`] to add the table to
+    /// * `channels` - [`Channels`] IDs for communication with other tables
+    /// * `bits` - Width of the range, in bits
+    pub fn new(cs: &mut ConstraintSystem, channels: &Channels, bits: u16) -> Self {
+        let mut table = cs.add_table(format!("range_lookup_{bits}"));
+        table.require_power_of_two_size();
+
+        let value_space = table.add_structured::<B32>(
+            "value_space",
+            StructuredDynSize::Incrementing {
+                max_size_log: bits as usize,
+            },
+        );
+        let value = table.add_committed("value");
+        let bits_col = table.add_constant("bits", [B32::new(bits as u32)]);
+
+        // Assess that the value space and value columns are permuted
+        table.push(channels.range_check_space_channel, [bits_col, value_space]);
+        table.pull(channels.range_check_space_channel, [bits_col, value]);
+
+        let lookup_producer = LookupProducer::new(
+            &mut table,
+            channels.range_check_channel,
+            &[bits_col, value],
+            RANGE_CHECK_MULTIPLICITY_BITS,
+        );
+
+        Self {
+            id: table.id(),
+            bits,
+            value_space,
+            value,
+            lookup_producer,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for RangeLookupTable {
+    type Event = (u32, u32);
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut value_col = witness.get_scalars_mut(self.value)?;
+            for (i, (value, _)) in rows.clone().enumerate() {
+                value_col[i] = B32::new(*value);
+            }
+        }
+
+        // Fill value space column
+        fill_incrementing_b32(witness, self.value_space)?;
+
+        // Populate lookup producer with multiplicity iterator
+        self.lookup_producer
+            .populate(witness, rows.map(|(_, multiplicity)| *multiplicity))?;
+
+        Ok(())
+    }
+}
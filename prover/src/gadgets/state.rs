@@ -1,5 +1,6 @@
 use binius_core::constraint_system::channel::ChannelId;
 use binius_m3::builder::{Col, TableBuilder, TableWitnessSegment, B1, B128, B16, B32};
+use petravm_asm::FieldPc;
 
 use crate::opcodes::G;
 use crate::utils::{pull_prom_channel, pull_state_channel, push_state_channel};
@@ -11,19 +12,22 @@ use crate::{
 /// A gadget for reading an instruction and its operands from the PROM and
 /// setting the next program counter.
 #[derive(Default)]
-pub(crate) struct StateGadget {
+pub struct StateGadget {
     /// Current program counter
-    pub(crate) pc: u32,
+    pub pc: u32,
     /// Next program counter
-    pub(crate) next_pc: Option<u32>,
+    pub next_pc: Option<u32>,
     /// Current frame pointer
-    pub(crate) fp: u32,
+    pub fp: u32,
+    /// Current system timestamp, i.e. the number of RAM accesses and COMMIT
+    /// instructions so far
+    pub timestamp: u32,
     /// First 16-bit operand
-    pub(crate) arg0: u16,
+    pub arg0: u16,
     /// Second 16-bit operand
-    pub(crate) arg1: u16,
+    pub arg1: u16,
     /// Third 16-bit operand
-    pub(crate) arg2: u16,
+    pub arg2: u16,
 }
 
 /// Column view for instruction operands.
@@ -34,16 +38,17 @@ type OpcodeArg = Col<B16>;
 type OpcodeArgUnpacked = Col<B1, 16>;
 
 /// The columns associated with the [`StateGadget`].
-pub(crate) struct StateColumns<const OPCODE: u16> {
-    pub(crate) pc: Col<B32>,
+pub struct StateColumns<const OPCODE: u16> {
+    pub pc: Col<B32>,
     // TODO: next pc can be set to anything, so shouldn't be virtual?
-    pub(crate) next_pc: Col<B32>, // Virtual
-    pub(crate) fp: Col<B32>,
-    pub(crate) arg0: OpcodeArg,
-    pub(crate) arg1: OpcodeArg,
+    pub next_pc: Col<B32>, // Virtual
+    pub fp: Col<B32>,
+    pub timestamp: Col<B32>,
+    pub arg0: OpcodeArg,
+    pub arg1: OpcodeArg,
     // This field will be used for opcodes like SRLI
-    pub(crate) arg2_unpacked: OpcodeArgUnpacked,
-    pub(crate) arg2: OpcodeArg, // Virtual,
+    pub arg2_unpacked: OpcodeArgUnpacked,
+    pub arg2: OpcodeArg, // Virtual,
 
     options: StateColumnsOptions,
     // Virtual columns for communication with the channels
@@ -51,7 +56,7 @@ pub(crate) struct StateColumns<const OPCODE: u16> {
 }
 
 #[derive(Default)]
-pub(crate) enum NextPc {
+pub enum NextPc {
     /// `next_pc` is `current_pc * G`.
     #[default]
     Increment,
@@ -62,9 +67,12 @@ pub(crate) enum NextPc {
 }
 
 #[derive(Default)]
-pub(crate) struct StateColumnsOptions {
-    pub(crate) next_pc: NextPc,
-    pub(crate) next_fp: Option<Col<B32>>,
+pub struct StateColumnsOptions {
+    pub next_pc: NextPc,
+    pub next_fp: Option<Col<B32>>,
+    /// Next system timestamp, left unchanged if `None`. Only the RAM accesses
+    /// and COMMIT set it.
+    pub next_timestamp: Option<Col<B32>>,
 }
 
 impl<const OPCODE: u16> StateColumns<OPCODE> {
@@ -76,6 +84,7 @@ impl<const OPCODE: u16> StateColumns<OPCODE> {
     ) -> Self {
         let pc = table.add_committed("pc");
         let fp = table.add_committed("fp");
+        let timestamp = table.add_committed("timestamp");
         let arg0 = table.add_committed("arg0");
         let arg1 = table.add_committed("arg1");
         let arg2_unpacked = table.add_committed("arg2");
@@ -86,20 +95,23 @@ impl<const OPCODE: u16> StateColumns<OPCODE> {
             pack_instruction_with_fixed_opcode(table, "prom_pull", pc, OPCODE, [arg0, arg1, arg2]);
         pull_prom_channel(table, prom_channel, [prom_pull]);
 
-        // Pull/Push the current/next pc and fp from from/to the state channel
+        // Pull/Push the current/next pc, fp and timestamp from/to the state
+        // channel
         let next_pc = match options.next_pc {
             NextPc::Increment => table.add_computed("next_pc", pc * G),
             NextPc::Target(target) => target,
             NextPc::Immediate => table.add_computed("next_pc", pack_b16_into_b32(arg0, arg1)),
         };
         let next_fp = options.next_fp.unwrap_or(fp);
-        pull_state_channel(table, state_channel, [pc, fp]);
-        push_state_channel(table, state_channel, [next_pc, next_fp]);
+        let next_timestamp = options.next_timestamp.unwrap_or(timestamp);
+        pull_state_channel(table, state_channel, [pc, fp, timestamp]);
+        push_state_channel(table, state_channel, [next_pc, next_fp, next_timestamp]);
 
         Self {
             pc,
             next_pc,
             fp,
+            timestamp,
             arg0,
             arg1,
             arg2_unpacked,
@@ -119,6 +131,7 @@ impl<const OPCODE: u16> StateColumns<OPCODE> {
     {
         let mut pc_col = index.get_scalars_mut(self.pc)?;
         let mut fp_col = index.get_scalars_mut(self.fp)?;
+        let mut timestamp_col = index.get_scalars_mut(self.timestamp)?;
         let mut next_pc_col = index.get_scalars_mut(self.next_pc)?;
 
         let mut arg0_col = index.get_scalars_mut(self.arg0)?;
@@ -133,6 +146,7 @@ impl<const OPCODE: u16> StateColumns<OPCODE> {
                 pc,
                 next_pc,
                 fp,
+                timestamp,
                 arg0,
                 arg1,
                 arg2,
@@ -141,12 +155,13 @@ impl<const OPCODE: u16> StateColumns<OPCODE> {
         {
             pc_col[i] = B32::new(pc);
             fp_col[i] = B32::new(fp);
+            timestamp_col[i] = B32::new(timestamp);
             arg0_col[i] = B16::new(arg0);
             arg1_col[i] = B16::new(arg1);
             arg2_col[i] = B16::new(arg2);
 
             next_pc_col[i] = match self.options.next_pc {
-                NextPc::Increment => B32::new(pc) * G,
+                NextPc::Increment => FieldPc::from(B32::new(pc)).next().field(),
                 NextPc::Target(_) => {
                     B32::new(next_pc.expect("next_pc must be Some when NextPc::Target"))
                 }
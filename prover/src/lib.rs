@@ -4,17 +4,27 @@
 //! arithmetization. The design is modular, with each opcode
 //! instruction having its own M3 table implementation.
 
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod budget;
+pub mod cache;
 pub mod channels;
 pub mod circuit;
 pub mod gadgets;
 pub mod memory;
 pub mod model;
 pub mod opcodes;
+pub mod preflight;
 pub mod prover;
+pub mod serialization;
 pub mod table;
+pub mod telemetry;
 pub mod types;
 #[macro_use]
 pub mod utils;
 
 /// Publicly exported module for testing purposes only
 pub mod test_utils;
+
+#[cfg(test)]
+mod differential;
@@ -3,6 +3,7 @@
 //! This module contains the definitions of all the memory tables needed
 //! to represent the PetraVM execution in the M3 arithmetization system.
 
+use binius_core::constraint_system::channel::ChannelId;
 use binius_field::Field;
 use binius_m3::builder::{Col, ConstraintSystem, TableFiller, TableId, B128, B16, B32};
 use binius_m3::builder::{StructuredDynSize, TableWitnessSegment};
@@ -18,13 +19,15 @@ use crate::{
     channels::Channels,
     model::Instruction,
     types::ProverPackedField,
-    utils::{pack_instruction, pack_instruction_b128},
+    utils::{pack_instruction, pack_instruction_b128, pull_vrom_channel},
 };
 
 /// PROM (Program ROM) table for storing program instructions.
 ///
 /// This table stores all the instructions in the program and makes them
-/// available to the instruction-specific tables.
+/// available to the instruction-specific tables. Each row is pulled from the
+/// program channel, pushed by the verifier, so that the table holds exactly
+/// the instructions of the expected program.
 ///
 /// Format: [PC, Opcode, Arg1, Arg2, Arg3] packed into B128
 pub struct PromTable {
@@ -68,6 +71,8 @@ impl PromTable {
         let instruction =
             pack_instruction(&mut table, "instruction", pc, opcode, [arg1, arg2, arg3]);
 
+        table.pull(channels.program_channel, [instruction]);
+
         #[cfg(not(feature = "disable_prom_channel"))]
         let lookup_producer = LookupProducer::new(
             &mut table,
@@ -75,7 +80,6 @@ impl PromTable {
             &[instruction],
             PROM_MULTIPLICITY_BITS,
         );
-        let _ = channels;
 
         Self {
             id: table.id(),
@@ -113,7 +117,7 @@ impl TableFiller<ProverPackedField> for PromTable {
 
             for (i, (instr, _)) in rows.clone().enumerate() {
                 pc_col[i] = B32::new(instr.pc.val());
-                opcode_col[i] = B16::new(instr.opcode as u16);
+                opcode_col[i] = B16::new(instr.opcode);
 
                 // Fill arguments, using ZERO if the argument doesn't exist
                 arg1_col[i] = instr.args.first().map_or(B16::ZERO, |&arg| B16::new(arg));
@@ -238,3 +242,246 @@ impl TableFiller<ProverPackedField> for VromTable {
         Ok(())
     }
 }
+
+/// RAM table for the initial and final states of the RAM words.
+///
+/// Consistency of the RAM is checked with timestamp-ordered multiset
+/// checking: every word starts with its initial value at timestamp 0, and
+/// every access pulls the previous `[Address, Value, Timestamp]` tuple of the
+/// word it touches before pushing its updated one, with a strictly greater
+/// timestamp.
+/// This table pushes a zero initial state for every word and pulls its final
+/// state, so that the RAM channel balances if and only if every read returns
+/// the last value written to the same word. The words of the initial RAM image
+/// are then set by the [`RamImageTable`].
+///
+/// Format: [WordAddress, Value, Timestamp]
+pub struct RamTable {
+    /// Table ID
+    pub id: TableId,
+    /// Word address column
+    pub addr: Col<B32>,
+    /// Constant zero column, used for the initial value and timestamp
+    pub zero: Col<B32>,
+    /// Final value of each word
+    pub final_value: Col<B32>,
+    /// Timestamp of the last access to each word
+    pub final_timestamp: Col<B32>,
+}
+
+impl RamTable {
+    /// Create a new RAM table with the given constraint system and channels.
+    ///
+    /// # Arguments
+    /// * `cs` - [`ConstraintSystem`] to add the table to
+    /// * `channels` - [`Channels`] IDs for communication with other tables
+    pub fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("ram");
+        table.require_power_of_two_size();
+
+        // Every word of the RAM appears exactly once
+        let addr = table
+            .add_structured::<B32>("addr", StructuredDynSize::Incrementing { max_size_log: 32 });
+        let zero = table.add_constant("zero", [B32::ZERO]);
+
+        let final_value = table.add_committed("final_value");
+        let final_timestamp = table.add_committed("final_timestamp");
+
+        // Initialize every word to zero, and collect its final state
+        table.push(channels.ram_channel, [addr, zero, zero]);
+        table.pull(channels.ram_channel, [addr, final_value, final_timestamp]);
+
+        Self {
+            id: table.id(),
+            addr,
+            zero,
+            final_value,
+            final_timestamp,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for RamTable {
+    type Event = (u32, u32, u32);
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut final_value_col = witness.get_scalars_mut(self.final_value)?;
+            let mut final_timestamp_col = witness.get_scalars_mut(self.final_timestamp)?;
+
+            for (i, (_, value, timestamp)) in rows.enumerate() {
+                final_value_col[i] = B32::new(*value);
+                final_timestamp_col[i] = B32::new(*timestamp);
+            }
+        }
+
+        // Fill the word address column
+        fill_incrementing_b32(witness, self.addr)?;
+
+        Ok(())
+    }
+}
+
+/// RAM image table, setting the initial value of the non-zero words of the
+/// initial RAM image.
+///
+/// Each row pulls an `[Address, Value]` couple pushed by the verifier through
+/// the statement boundaries, so that the initial image is that of the program
+/// rather than left to the prover. It then overwrites the zero initial state
+/// pushed by the [`RamTable`] for the word, pulling `[Address, 0, 0]` and
+/// pushing `[Address, Value, 0]` on the RAM channel.
+///
+/// Format: [WordAddress, Value]
+pub struct RamImageTable {
+    /// Table ID
+    pub id: TableId,
+    /// Word address column
+    pub addr: Col<B32>,
+    /// Constant zero column, used for the overwritten state
+    pub zero: Col<B32>,
+    /// Initial value of the word
+    pub value: Col<B32>,
+}
+
+impl RamImageTable {
+    /// Create a new RAM image table with the given constraint system and
+    /// channels.
+    ///
+    /// # Arguments
+    /// * `cs` - [`ConstraintSystem`] to add the table to
+    /// * `channels` - [`Channels`] IDs for communication with other tables
+    pub fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("ram_image");
+
+        let addr = table.add_committed("addr");
+        let zero = table.add_constant("zero", [B32::ZERO]);
+        let value = table.add_committed("value");
+
+        table.pull(channels.ram_image_channel, [addr, value]);
+        table.pull(channels.ram_channel, [addr, zero, zero]);
+        table.push(channels.ram_channel, [addr, value, zero]);
+
+        Self {
+            id: table.id(),
+            addr,
+            zero,
+            value,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for RamImageTable {
+    type Event = (u32, u32);
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        let mut addr_col = witness.get_scalars_mut(self.addr)?;
+        let mut value_col = witness.get_scalars_mut(self.value)?;
+        for (i, (addr, value)) in rows.enumerate() {
+            addr_col[i] = B32::new(*addr);
+            value_col[i] = B32::new(*value);
+        }
+
+        Ok(())
+    }
+}
+
+/// Public values table.
+///
+/// This table pulls every public `[Address, Value]` couple pushed by the
+/// verifier through the statement boundaries, and looks it up in the VROM, so
+/// that the public values are those of the execution.
+///
+/// The same table binds the initial VROM values provided by the host, pulled
+/// from the input channel instead, so that they are not left to the prover.
+///
+/// Format: [Address, Value]
+pub struct PublicTable {
+    /// Table ID
+    pub id: TableId,
+    /// VROM address column
+    pub addr: Col<B32>,
+    /// Value column
+    pub value: Col<B32>,
+}
+
+impl PublicTable {
+    /// Create a new public values table with the given constraint system and
+    /// channels.
+    ///
+    /// # Arguments
+    /// * `cs` - [`ConstraintSystem`] to add the table to
+    /// * `channels` - [`Channels`] IDs for communication with other tables
+    pub fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        Self::with_channel(cs, "public", channels.public_channel, channels)
+    }
+
+    /// Create a new table of the initial VROM values, pulled from the input
+    /// channel.
+    ///
+    /// # Arguments
+    /// * `cs` - [`ConstraintSystem`] to add the table to
+    /// * `channels` - [`Channels`] IDs for communication with other tables
+    pub fn inputs(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        Self::with_channel(cs, "inputs", channels.input_channel, channels)
+    }
+
+    fn with_channel(
+        cs: &mut ConstraintSystem,
+        name: &str,
+        channel: ChannelId,
+        channels: &Channels,
+    ) -> Self {
+        let mut table = cs.add_table(name);
+
+        let addr = table.add_committed("addr");
+        let value = table.add_committed("value");
+
+        table.pull(channel, [addr, value]);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [addr, value]);
+
+        Self {
+            id: table.id(),
+            addr,
+            value,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for PublicTable {
+    type Event = (u32, u32);
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        let mut addr_col = witness.get_scalars_mut(self.addr)?;
+        let mut value_col = witness.get_scalars_mut(self.value)?;
+        for (i, (addr, value)) in rows.enumerate() {
+            addr_col[i] = B32::new(*addr);
+            value_col[i] = B32::new(*value);
+        }
+
+        Ok(())
+    }
+}
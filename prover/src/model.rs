@@ -3,14 +3,47 @@
 //! This module contains the data structures used to represent execution traces
 //! and events needed for the proving system.
 
-use std::iter::repeat_n;
+use std::{collections::HashMap, iter::repeat_n};
 
 use anyhow::Result;
+use binius_field::Field;
 use binius_m3::builder::B32;
 use paste::paste;
-use petravm_asm::{event::*, InterpreterInstruction, Opcode, PetraTrace};
+use petravm_asm::{
+    event::*, execution::CustomInstructions, isa::ISA, AssembledProgram, FieldPc,
+    GenerationOptions, InterpreterInstruction, Memory, Opcode, PetraTrace, ProgramRom, ValueRom,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::table::*;
+use crate::{circuit::Circuit, preflight::TableSize, table::*};
+
+/// Implements the event accessors of the main [`Trace`], returning the events
+/// logged in the given field of the [`PetraTrace`].
+///
+/// # Example
+///
+/// ```ignore
+/// define_event_accessors!(
+///     (lb, Lb),
+///     (sb, Sb),
+/// );
+/// ```
+macro_rules! define_event_accessors {
+    (
+        $(($func_name:ident, $opcode_variant:ident)),* $(,)?
+    ) => {
+        $(
+            paste! {
+                impl Trace {
+                    #[doc = concat!("Returns a reference to the logged `", stringify!([<$opcode_variant Event>]), "`s from the trace.")]
+                    pub fn [<$func_name _events>](&self) -> &[ [<$opcode_variant Event>] ] {
+                        &self.trace.$func_name
+                    }
+                }
+            }
+        )*
+    };
+}
 
 /// Implements the [`TableInfo`] trait that lifts
 /// [`InstructionInfo`](petravm_asm::InstructionInfo) and maps events to
@@ -20,6 +53,11 @@ use crate::table::*;
 /// It will also implement the mapping between an [`Opcode`] and its associated
 /// [`Table`].
 ///
+/// It is invoked with the recorded instructions of the instruction
+/// specification of the assembler, by
+/// [`for_each_recorded_instruction!
+/// `](petravm_asm::for_each_recorded_instruction).
+///
 /// # Example
 ///
 /// ```ignore
@@ -32,15 +70,10 @@ macro_rules! define_table_registry_and_accessors {
     (
         $(($func_name:ident, $opcode_variant:ident)),* $(,)?
     ) => {
+        define_event_accessors!($(($func_name, $opcode_variant)),*);
+
         $(
             paste! {
-                impl Trace {
-                    #[doc = concat!("Returns a reference to the logged `", stringify!([<$opcode_variant Event>]), "`s from the trace.")]
-                    pub fn [<$func_name _events>](&self) -> &[ [<$opcode_variant Event>] ] {
-                        &self.trace.$func_name
-                    }
-                }
-
                 impl TableInfo for [<$opcode_variant Event>] {
                     type Table = [<$opcode_variant Table>];
 
@@ -80,12 +113,13 @@ macro_rules! define_table_registry_and_accessors {
 /// This is a simplified representation of the instruction format used in the
 /// proving system, where the arguments are stored in a more convenient form for
 /// the prover.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Instruction {
     /// PC value as a field element
+    #[serde(with = "petravm_asm::util::serde_b32")]
     pub pc: B32,
-    /// Opcode of the instruction
-    pub opcode: Opcode,
+    /// Opcode of the instruction, which may be a custom one
+    pub opcode: u16,
     /// Arguments to the instruction (up to 3)
     pub args: Vec<u16>,
     /// Optional advice. Used for providing the PROM index and the discrete
@@ -101,13 +135,82 @@ impl From<InterpreterInstruction> for Instruction {
 
         Self {
             pc: instr.field_pc,
-            opcode: instr.opcode(),
+            opcode: instr.instruction[0].val(),
             args: args_array.iter().map(|arg| arg.val()).collect(),
             advice: instr.advice,
         }
     }
 }
 
+/// Returns the program as proven: the instructions of `prom` but the
+/// prover-only ones, padded with invalid instructions to a power of two of at
+/// least 128 rows, as required by the PROM lookup gadget.
+pub fn verifier_program(prom: &ProgramRom) -> Vec<InterpreterInstruction> {
+    let mut verifier_program = prom
+        .iter()
+        .filter(|instr| !instr.prover_only)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let prom_size = verifier_program.len().next_power_of_two().max(128);
+    let mut max_pc = FieldPc::from(
+        verifier_program
+            .last()
+            .map_or(B32::ZERO, |instr| instr.field_pc),
+    );
+
+    for _ in verifier_program.len()..prom_size {
+        max_pc.incr();
+        verifier_program.push(InterpreterInstruction::new(
+            petravm_asm::Instruction::default(),
+            max_pc.field(),
+            None,
+            false,
+        ));
+    }
+    verifier_program
+}
+
+/// Returns the non-zero words of the initial RAM `image`, loaded from address
+/// 0, as `(word_addr, value)` couples ordered by address.
+///
+/// The other words of the RAM are initially zero, and need not be bound to the
+/// program.
+pub fn ram_image_words(image: &[u8]) -> Vec<(u32, u32)> {
+    (0..)
+        .zip(image.chunks(4))
+        .map(|(word_addr, bytes)| {
+            let mut word = [0; 4];
+            word[..bytes.len()].copy_from_slice(bytes);
+            (word_addr, u32::from_le_bytes(word))
+        })
+        .filter(|&(_, value)| value != 0)
+        .collect()
+}
+
+/// Returns the valid targets of the indirect calls of `program`, as proven: its
+/// function entry points, see [`AssembledProgram::call_targets`], padded to a
+/// power of two by repeating the last one, as required by the call target
+/// table.
+///
+/// Programs without indirect calls have none, leaving the call target table
+/// empty.
+pub fn call_targets(program: &AssembledProgram) -> Vec<B32> {
+    let has_indirect_calls = program
+        .prom
+        .iter()
+        .any(|instr| !instr.prover_only && matches!(instr.opcode(), Opcode::Callv | Opcode::Tailv));
+    if !has_indirect_calls {
+        return Vec::new();
+    }
+
+    let mut targets = program.call_targets();
+    if let Some(&last) = targets.last() {
+        targets.resize(targets.len().next_power_of_two(), last);
+    }
+    targets
+}
+
 /// Execution trace containing a program and all execution events.
 ///
 /// This is a wrapper around PetraTrace that provides a simplified interface
@@ -115,7 +218,7 @@ impl From<InterpreterInstruction> for Instruction {
 /// 1. The program instructions in a format optimized for the prover
 /// 2. The original PetraTrace with all execution events and memory state
 /// 3. A list of VROM writes (address, value) pairs
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Trace {
     /// The underlying PetraTrace containing all execution events
     pub trace: PetraTrace,
@@ -125,6 +228,11 @@ pub struct Trace {
     pub vrom_writes: Vec<(u32, u32, u32)>,
     /// Maximum VROM address in the trace
     pub max_vrom_addr: usize,
+    /// VROM addresses exposed as public values, in declaration order
+    pub public_slots: Vec<u32>,
+    /// Valid targets of the indirect calls, with the number of CALLV and TAILV
+    /// landing on each, sorted by decreasing multiplicity
+    pub call_targets: Vec<(u32, u32)>,
 }
 
 impl Default for Trace {
@@ -141,6 +249,8 @@ impl Trace {
             program: Vec::new(),
             vrom_writes: Vec::new(),
             max_vrom_addr: 0,
+            public_slots: Vec::new(),
+            call_targets: Vec::new(),
         }
     }
 
@@ -171,6 +281,75 @@ impl Trace {
         zkvm_trace
     }
 
+    /// Executes `program` with the VROM initialized with `init_values`, and
+    /// returns its trace in the proving format.
+    ///
+    /// The initial values usually hold the return PC and FP of the entry
+    /// frame, i.e. `[0, 0]`, followed by the arguments of the program.
+    pub fn from_program(
+        program: AssembledProgram,
+        init_values: &[u32],
+        isa: Box<dyn ISA>,
+    ) -> Result<Self> {
+        Self::from_program_with_custom_instructions(
+            program,
+            init_values,
+            isa,
+            CustomInstructions::new(),
+        )
+    }
+
+    /// Executes `program` like [`Trace::from_program`], with
+    /// `custom_instructions` executable by the program.
+    pub fn from_program_with_custom_instructions(
+        program: AssembledProgram,
+        init_values: &[u32],
+        isa: Box<dyn ISA>,
+        custom_instructions: CustomInstructions,
+    ) -> Result<Self> {
+        Self::from_program_with_options(
+            program,
+            init_values,
+            isa,
+            GenerationOptions::default().custom_instructions(custom_instructions),
+        )
+    }
+
+    /// Executes `program` like [`Trace::from_program`], with the given trace
+    /// generation `options`, e.g. to provide an advice tape.
+    pub fn from_program_with_options(
+        program: AssembledProgram,
+        init_values: &[u32],
+        isa: Box<dyn ISA>,
+        options: GenerationOptions<'_>,
+    ) -> Result<Self> {
+        let verifier_program = verifier_program(&program.prom);
+        let call_targets = call_targets(&program);
+        let vrom = ValueRom::new_with_init_vals(init_values);
+        let memory = Memory::new(program.prom, vrom).with_ram_data(&program.data);
+
+        // Generate the trace from the compiled program
+        let (petra_trace, _) =
+            PetraTrace::generate_with_options(isa, memory, program.frame_sizes, options)
+                .map_err(|e| anyhow::anyhow!("Failed to generate trace: {:?}", e))?;
+
+        // Convert to Trace format for the prover
+        let mut zkvm_trace = Self::from_petra_trace(verifier_program, petra_trace);
+        zkvm_trace.expose_public_slots(&program.public_slots)?;
+        zkvm_trace.set_call_targets(&call_targets)?;
+
+        // Add the VROM writes
+        let mut max_dst = 0;
+        for (dst, val, multiplicity) in zkvm_trace.trace.vrom().sorted_access_counts() {
+            zkvm_trace.add_vrom_write(dst, val, multiplicity);
+            max_dst = max_dst.max(dst as usize);
+        }
+
+        // The inputs are looked up in the VROM even if never accessed
+        zkvm_trace.max_vrom_addr = max_dst.max(init_values.len().saturating_sub(1));
+        Ok(zkvm_trace)
+    }
+
     /// Add multiple interpreter instructions to the program.
     ///
     /// Instructions are added in descending order of their execution count.
@@ -206,17 +385,239 @@ impl Trace {
         self.vrom_writes.push((addr, value, multiplicity));
     }
 
+    /// Exposes the values at the VROM addresses `public_slots` as public
+    /// values.
+    ///
+    /// Each public value is looked up once more in the VROM, so this must be
+    /// called before the VROM writes and their multiplicities are collected.
+    pub fn expose_public_slots(&mut self, public_slots: &[u32]) -> Result<()> {
+        for &addr in public_slots {
+            // Reading records the lookup of the public table.
+            self.trace
+                .vrom()
+                .read::<u32>(addr)
+                .map_err(|e| anyhow::anyhow!("Public slot @{addr} has no value: {e}"))?;
+        }
+        self.public_slots.extend_from_slice(public_slots);
+        Ok(())
+    }
+
+    /// Sets the valid targets of the indirect calls of the trace, as returned
+    /// by [`call_targets`], counting the CALLV and TAILV landing on each.
+    ///
+    /// Fails if an indirect call lands on another PC, as it couldn't be
+    /// proven.
+    pub fn set_call_targets(&mut self, targets: &[B32]) -> Result<()> {
+        let mut rows = targets
+            .iter()
+            .map(|target| (target.val(), 0))
+            .collect::<Vec<_>>();
+        let mut indices = HashMap::new();
+        for (i, &(target, _)) in rows.iter().enumerate() {
+            indices.entry(target).or_insert(i);
+        }
+
+        let calls = self
+            .callv_events()
+            .iter()
+            .map(|event| (event.pc, event.target))
+            .chain(
+                self.tailv_events()
+                    .iter()
+                    .map(|event| (event.pc, event.target)),
+            );
+        for (pc, target) in calls {
+            let i = indices.get(&target).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "The indirect call at PC {pc} targets {target:#x}, which is not a function entry point"
+                )
+            })?;
+            rows[*i].1 += 1;
+        }
+
+        rows.sort_by_key(|(_, multiplicity)| std::cmp::Reverse(*multiplicity));
+        self.call_targets = rows;
+        Ok(())
+    }
+
+    /// Returns the public values of the execution, as `(address, value)`
+    /// couples in declaration order.
+    ///
+    /// Verifiers need them, in this order, to rebuild the boundaries of the
+    /// statement.
+    pub fn public_values(&self) -> Result<Vec<(u32, u32)>> {
+        self.public_slots
+            .iter()
+            .map(|&addr| {
+                let value = self
+                    .trace
+                    .vrom()
+                    .peek::<u32>(addr)
+                    .map_err(|e| anyhow::anyhow!("Public slot @{addr} has no value: {e}"))?;
+                Ok((addr, value))
+            })
+            .collect()
+    }
+
+    /// Returns the initial VROM values provided by the host, as `(address,
+    /// value)` couples.
+    ///
+    /// Verifiers need them to rebuild the boundaries of the statement, which
+    /// bind the execution to these inputs.
+    pub fn inputs(&self) -> Vec<(u32, u32)> {
+        (0..)
+            .zip(self.trace.vrom().init_values().iter().copied())
+            .collect()
+    }
+
+    /// Returns the commitment to the initial VROM values of the execution, see
+    /// [`ValueRom::commit_init_values`].
+    pub fn init_commitment(&self) -> [u8; 32] {
+        self.trace.vrom().init_commitment()
+    }
+
+    /// Returns the number of executed instructions.
+    pub fn cycles(&self) -> u64 {
+        self.trace
+            .instruction_counter
+            .iter()
+            .map(|&count| count as u64)
+            .sum()
+    }
+
+    /// Returns the number of rows of each table of the circuit proving this
+    /// trace for `isa`, see [`Circuit::table_sizes`]. The tables of custom
+    /// instructions are left out, use
+    /// [`Prover::preflight`](crate::prover::Prover::preflight) to include
+    /// them.
+    pub fn table_sizes(&self, isa: Box<dyn ISA>) -> Result<Vec<TableSize>> {
+        Circuit::new(isa).table_sizes(self)
+    }
+
+    /// Returns the public outputs of the execution, committed by `COMMIT`.
+    pub fn outputs(&self) -> &[u32] {
+        self.trace.outputs()
+    }
+
+    /// Returns the commitment to the public outputs of the execution, see
+    /// [`commit_outputs`].
+    pub fn output_commitment(&self) -> [u8; 32] {
+        commit_outputs(self.outputs())
+    }
+
+    /// Returns the exit code of the execution, set by its final HALT if any,
+    /// and 0 otherwise.
+    pub fn exit_code(&self) -> u32 {
+        self.halt_events()
+            .last()
+            .map_or(0, |event| event.exit_code as u32)
+    }
+
+    /// Returns the final system timestamp of the execution, i.e. its number of
+    /// RAM accesses and COMMIT instructions.
+    pub fn final_timestamp(&self) -> u32 {
+        (self.trace.ram().access_history().len() + self.commit_events().len()) as u32
+    }
+
+    /// Returns the claim of the execution, from which verifiers rebuild the
+    /// boundaries of the statement proving it, see [`Claim`].
+    pub fn claim(&self) -> Result<Claim> {
+        Ok(Claim {
+            init_values: self.trace.vrom().init_values().to_vec(),
+            public_values: self
+                .public_values()?
+                .into_iter()
+                .map(|(_, value)| value)
+                .collect(),
+            outputs: self.outputs().to_vec(),
+            exit_code: self.exit_code(),
+            final_timestamp: self.final_timestamp(),
+            commit_timestamp: ram_channel_timestamp(
+                self.commit_events().last().map(|event| event.timestamp),
+            ),
+        })
+    }
+
+    /// Returns the logged events of type `E` from the trace, e.g.
+    /// `trace.events_of::<AddEvent>()`.
+    ///
+    /// Unlike the accessors dedicated to each event type, this lets external
+    /// tools and custom tables consume any event type generically.
+    pub fn events_of<E: TraceEvent>(&self) -> &[E] {
+        self.trace.events_of()
+    }
+
     /// Returns a reference to the right shift events from the trace.
     pub fn right_shift_events(&self) -> &[RightLogicShiftGadgetEvent] {
         &self.trace.right_logic_shift_gadget
     }
 
+    /// Returns a reference to the words moved by MEMCPY and MEMSET from the
+    /// trace.
+    pub fn mem_word_events(&self) -> &[MemWordGadgetEvent] {
+        &self.trace.mem_word_gadget
+    }
+
+    /// Returns a reference to the levels of the Merkle paths verified by
+    /// MERKLE_VERIFY from the trace.
+    pub fn merkle_node_events(&self) -> &[MerkleNodeGadgetEvent] {
+        &self.trace.merkle_node_gadget
+    }
+
+    /// Returns the rows of the `bits`-bit range-check lookup table, as `(value,
+    /// multiplicity)` couples sorted by decreasing multiplicity.
+    ///
+    /// The table covers the values from 0 to the smallest power of two above
+    /// all the looked up values, and each multiplicity counts the lookups of
+    /// its value by the tables pulling from the range-check channel.
+    pub fn range_lookups(&self, bits: u16) -> Vec<(u32, u32)> {
+        let values = self
+            .range_check_events()
+            .iter()
+            .filter(|event| event.bits == bits)
+            .map(|event| event.val);
+
+        let size = values
+            .clone()
+            .max()
+            .map_or(1, |max| (max as usize + 1).next_power_of_two());
+        let mut lookups = (0..size as u32).map(|value| (value, 0)).collect::<Vec<_>>();
+        for value in values {
+            lookups[value as usize].1 += 1;
+        }
+        lookups.sort_by_key(|(_, multiplicity)| std::cmp::Reverse(*multiplicity));
+        lookups
+    }
+
+    /// Returns the final states of every RAM word, as
+    /// `(word_addr, final_value, final_timestamp)` tuples ordered by address.
+    ///
+    /// Timestamps are shifted by one to match the RAM channel, where timestamp
+    /// 0 is reserved for the initial state of the words.
+    pub fn ram_word_states(&self) -> Vec<(u32, u32, u32)> {
+        self.trace
+            .ram()
+            .final_words()
+            .map(|(addr, value, timestamp)| (addr, value, ram_channel_timestamp(timestamp)))
+            .collect()
+    }
+
+    /// Returns the non-zero words of the initial RAM image of the execution,
+    /// see [`ram_image_words`].
+    pub fn ram_image(&self) -> Vec<(u32, u32)> {
+        ram_image_words(self.trace.ram().initial_data())
+    }
+
     /// Ensures the trace has enough data for proving.
     ///
     /// This will verify that:
     /// 1. The program has at least one instruction
-    /// 2. The trace has at least one RET event
+    /// 2. The trace has at least one RET or HALT event
     /// 3. The trace has at least one VROM write
+    /// 4. The calls of the trace follow the calling convention, see
+    ///    [`PetraTrace::check_call_convention`]
+    /// 5. The indirect calls of the trace land on its call targets, see
+    ///    [`Trace::set_call_targets`]
     ///
     /// # Returns
     /// * Ok(()) if the trace is valid, or an error with a description of what's
@@ -228,8 +629,10 @@ impl Trace {
             ));
         }
 
-        if self.ret_events().is_empty() {
-            return Err(anyhow::anyhow!("Trace must contain at least one RET event"));
+        if self.ret_events().is_empty() && self.halt_events().is_empty() {
+            return Err(anyhow::anyhow!(
+                "Trace must contain at least one RET or HALT event"
+            ));
         }
 
         if self.vrom_writes.is_empty() {
@@ -238,57 +641,53 @@ impl Trace {
             ));
         }
 
+        self.trace.check_call_convention()?;
+
+        let indirect_calls = self.callv_events().len() + self.tailv_events().len();
+        let call_target_lookups = self
+            .call_targets
+            .iter()
+            .map(|&(_, multiplicity)| multiplicity as usize)
+            .sum::<usize>();
+        if call_target_lookups != indirect_calls {
+            return Err(anyhow::anyhow!(
+                "Trace must set the targets of its indirect calls"
+            ));
+        }
+
         Ok(())
     }
 }
 
+/// The public outcome of an execution of a program, as claimed by its proof.
+///
+/// Along with the program, it determines every boundary of the proven
+/// statement, see [`Circuit::claim_boundaries`], so that verifiers don't have
+/// to trust boundaries received along with a proof: those could carry extra
+/// flushes, e.g. a push on the VROM channel forging a value never written.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    /// Initial VROM values provided by the host
+    pub init_values: Vec<u32>,
+    /// Values of the public slots of the program, in declaration order
+    pub public_values: Vec<u32>,
+    /// Public outputs committed by `COMMIT`
+    pub outputs: Vec<u32>,
+    /// Exit code of the execution
+    pub exit_code: u32,
+    /// Final system timestamp of the execution
+    pub final_timestamp: u32,
+    /// Timestamp of the last COMMIT in the commit channel, 0 without outputs
+    pub commit_timestamp: u32,
+}
+
+/// Maps the timestamp of a RAM access to its timestamp in the RAM channel.
+///
+/// Accesses are shifted by one, since timestamp 0 denotes the initial state of
+/// the RAM words.
+pub(crate) fn ram_channel_timestamp(timestamp: Option<u32>) -> u32 {
+    timestamp.map_or(0, |timestamp| timestamp + 1)
+}
+
 // Generate event accessors and table info.
-define_table_registry_and_accessors!(
-    (ldi, Ldi),
-    (ret, Ret),
-    (bz, Bz),
-    (bnz, Bnz),
-    (fp, Fp),
-    (b32_mul, B32Mul),
-    (b32_muli, B32Muli),
-    (b128_add, B128Add),
-    (b128_mul, B128Mul),
-    (andi, Andi),
-    (xori, Xori),
-    (add, Add),
-    (addi, Addi),
-    (sub, Sub),
-    (mulu, Mulu),
-    (mul, Mul),
-    (muli, Muli),
-    (mulsu, Mulsu),
-    (taili, Taili),
-    (tailv, Tailv),
-    (calli, Calli),
-    (callv, Callv),
-    (mvvw, Mvvw),
-    (mvih, Mvih),
-    (mvvl, Mvvl),
-    (and, And),
-    (xor, Xor),
-    (or, Or),
-    (ori, Ori),
-    (jumpi, Jumpi),
-    (jumpv, Jumpv),
-    (srli, Srli),
-    (slli, Slli),
-    (srl, Srl),
-    (sll, Sll),
-    (srai, Srai),
-    (sra, Sra),
-    (sltu, Sltu),
-    (slt, Slt),
-    (slti, Slti),
-    (sle, Sle),
-    (slei, Slei),
-    (sltiu, Sltiu),
-    (sleu, Sleu),
-    (sleiu, Sleiu),
-    (groestl_compress, Groestl256Compress),
-    (groestl_output, Groestl256Output),
-);
+petravm_asm::for_each_recorded_instruction!(define_table_registry_and_accessors);
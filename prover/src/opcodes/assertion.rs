@@ -0,0 +1,217 @@
+//! Assertion tables implementation for the PetraVM M3 circuit.
+//!
+//! A failed assertion stops the execution, so these tables only need to
+//! enforce the asserted condition on the VROM values.
+
+use binius_field::Field;
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32,
+};
+use petravm_asm::{AssertEqEvent, AssertZeroEvent, Opcode};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// ASSERT_EQ table.
+///
+/// This table handles the ASSERT_EQ instruction, which asserts that two slots
+/// hold the same value. Both slots are looked up with the same value.
+pub struct AssertEqTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::AssertEq as u16 }>,
+    src1_abs: Col<B32>, // Virtual
+    src2_abs: Col<B32>, // Virtual
+    val: Col<B32>,
+}
+
+impl Table for AssertEqTable {
+    type Event = AssertEqEvent;
+
+    fn name(&self) -> &'static str {
+        "AssertEqTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("assert_eq");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        let val = table.add_committed("val");
+
+        let src1_abs = table.add_computed("src1_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let src2_abs = table.add_computed("src2_abs", state_cols.fp + upcast_col(state_cols.arg1));
+
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src1_abs, val]);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src2_abs, val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            src1_abs,
+            src2_abs,
+            val,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for AssertEqTable {
+    type Event = AssertEqEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+            let mut val = witness.get_scalars_mut(self.val)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                src1_abs[i] = B32::new(event.fp.addr(event.src1));
+                src2_abs[i] = B32::new(event.fp.addr(event.src2));
+                val[i] = B32::new(event.val);
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.src1,
+            arg1: event.src2,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// ASSERT_ZERO table.
+///
+/// This table handles the ASSERT_ZERO instruction, which asserts that a slot
+/// holds zero. The slot is looked up with a constant zero value.
+pub struct AssertZeroTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::AssertZero as u16 }>,
+    src_abs: Col<B32>, // Virtual
+}
+
+impl Table for AssertZeroTable {
+    type Event = AssertZeroEvent;
+
+    fn name(&self) -> &'static str {
+        "AssertZeroTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("assert_zero");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        let zero = table.add_constant("zero", [B32::ZERO]);
+
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg0));
+
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs, zero]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            src_abs,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for AssertZeroTable {
+    type Event = AssertZeroEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                src_abs[i] = B32::new(event.fp.addr(event.src));
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.src,
+            arg1: 0,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_assertions_with_value(val: u32) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+            _start:\n\
+            LDI.W @2, #{val}\n\
+            LDI.W @3, #{val}\n\
+            XOR @4, @2, @3\n\
+            ASSERT_EQ @2, @3\n\
+            ASSERT_EQ @3, @2\n\
+            ASSERT_ZERO @4\n\
+            RET\n"
+        );
+
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.assert_eq_events().len(), 2);
+        assert_eq!(trace.assert_zero_events().len(), 1);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_assertions(val in any::<u32>()) {
+            prop_assert!(test_assertions_with_value(val).is_ok());
+        }
+    }
+}
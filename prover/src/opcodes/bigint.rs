@@ -0,0 +1,214 @@
+use std::{array::from_fn, cell::RefMut};
+
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+};
+use petravm_asm::{bigint::LIMBS, opcodes::Opcode, BigMulModEvent};
+
+use super::groestl::get_all_addresses;
+use crate::{
+    channels::Channels,
+    gadgets::{
+        bigint::U256MulModColumns,
+        state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    },
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+const BIG_MUL_MOD_OPCODE: u16 = Opcode::BigMulMod as u16;
+
+/// BIGMULMOD table.
+///
+/// This table handles the BIGMULMOD instruction, which multiplies two 256-bit
+/// integers modulo a third one.
+pub struct BigMulModTable {
+    id: TableId,
+    state_cols: StateColumns<BIG_MUL_MOD_OPCODE>,
+    dst_addresses: [Col<B32>; LIMBS],
+    src1_addresses: [Col<B32>; 2 * LIMBS],
+    src1_vals: [Col<B1, 32>; 2 * LIMBS],
+    src2_addresses: [Col<B32>; LIMBS],
+    src2_vals: [Col<B1, 32>; LIMBS],
+    mul_mod: U256MulModColumns,
+}
+
+impl Table for BigMulModTable {
+    type Event = BigMulModEvent;
+
+    fn name(&self) -> &'static str {
+        "BigMulModTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("big_mul_mod");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            vrom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // Pull the factors and the modulus from the VROM.
+        let src1_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg1),
+            "src1_addr",
+        );
+        let src1_vals: [Col<B1, 32>; 2 * LIMBS] =
+            from_fn(|i| table.add_committed(format!("src1_val_{i}")));
+        let src2_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg2),
+            "src2_addr",
+        );
+        let src2_vals: [Col<B1, 32>; LIMBS] =
+            from_fn(|i| table.add_committed(format!("src2_val_{i}")));
+
+        for i in 0..2 * LIMBS {
+            let packed = table.add_packed(format!("src1_val_packed_{i}"), src1_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [src1_addresses[i], packed]);
+        }
+        for i in 0..LIMBS {
+            let packed = table.add_packed(format!("src2_val_packed_{i}"), src2_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [src2_addresses[i], packed]);
+        }
+
+        let mul_mod = U256MulModColumns::new(
+            &mut table,
+            "mul_mod",
+            from_fn(|i| src1_vals[i]),
+            from_fn(|i| src1_vals[LIMBS + i]),
+            src2_vals,
+        );
+
+        // Pull the result from the VROM.
+        let dst_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg0),
+            "dst_addr",
+        );
+        for i in 0..LIMBS {
+            let packed = table.add_packed(format!("dst_val_packed_{i}"), mul_mod.remainder[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [dst_addresses[i], packed]);
+        }
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_addresses,
+            src1_addresses,
+            src1_vals,
+            src2_addresses,
+            src2_vals,
+            mul_mod,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for BigMulModTable {
+    type Event = BigMulModEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_addresses = (0..LIMBS)
+                .map(|i| witness.get_mut_as(self.dst_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src1_addresses = (0..2 * LIMBS)
+                .map(|i| witness.get_mut_as(self.src1_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src1_vals = (0..2 * LIMBS)
+                .map(|i| witness.get_mut_as(self.src1_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src2_addresses = (0..LIMBS)
+                .map(|i| witness.get_mut_as(self.src2_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src2_vals = (0..LIMBS)
+                .map(|i| witness.get_mut_as(self.src2_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, event) in rows.clone().enumerate() {
+                for j in 0..LIMBS {
+                    dst_addresses[j][i] = event.fp.addr(event.dst + j as u16);
+                    src2_addresses[j][i] = event.fp.addr(event.src2 + j as u16);
+                    src2_vals[j][i] = event.src2_val[j];
+                }
+                for j in 0..2 * LIMBS {
+                    src1_addresses[j][i] = event.fp.addr(event.src1 + j as u16);
+                    src1_vals[j][i] = event.src1_val[j];
+                }
+            }
+        }
+
+        let mul_mod_rows = rows.clone().map(|event| {
+            let (a, b) = event.factors();
+            (a, b, event.src2_val)
+        });
+        self.mul_mod.populate(witness, mul_mod_rows)?;
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::{bigint, isa::GenericISA};
+    use proptest::prelude::*;
+
+    use crate::{prover::Prover, test_utils::generate_big_mul_mod_ret_trace};
+
+    fn test_big_mul_mod_with_values(a: [u32; 8], b: [u32; 8], m: [u32; 8]) -> Result<()> {
+        let trace = generate_big_mul_mod_ret_trace(a, b, m)?;
+        trace.validate()?;
+        assert_eq!(trace.big_mul_mod_events().len(), 1);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(5))]
+
+        #[test]
+        fn test_big_mul_mod(
+            a in any::<[u32; 8]>(),
+            b in any::<[u32; 8]>(),
+            m in any::<[u32; 8]>(),
+        ) {
+            // The factors must be reduced modulo m.
+            prop_assume!(bigint::lt(&a, &m) && bigint::lt(&b, &m));
+            prop_assert!(test_big_mul_mod_with_values(a, b, m).is_ok());
+        }
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! This module contains tables for binary field arithmetic operations.
 
-use binius_field::underlier::Divisible;
+use binius_field::{packed::set_packed_slice, underlier::Divisible, Field};
 use binius_m3::builder::{
-    upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B128, B32,
+    upcast_col, upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1,
+    B128, B32,
 };
-use petravm_asm::{opcodes::Opcode, B128AddEvent, B128MulEvent};
+use petravm_asm::{opcodes::Opcode, B128AddEvent, B128InvEvent, B128MulEvent};
 
 use crate::{
     channels::Channels,
@@ -21,6 +22,7 @@ use crate::{
 // Constants for opcodes
 const B128_ADD_OPCODE: u16 = Opcode::B128Add as u16;
 const B128_MUL_OPCODE: u16 = Opcode::B128Mul as u16;
+const B128_INV_OPCODE: u16 = Opcode::B128Inv as u16;
 
 /// Expands to a `TableFiller<ProverPackedField>` impl for a given B128
 /// instruction table.
@@ -61,6 +63,7 @@ macro_rules! impl_b128_table_filler {
                     pc: ev.pc.val(),
                     next_pc: None,
                     fp: *ev.fp,
+                    timestamp: ev.timestamp,
                     arg0: ev.dst,
                     arg1: ev.src1,
                     arg2: ev.src2,
@@ -146,6 +149,7 @@ impl Table for B128AddTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -261,6 +265,7 @@ impl Table for B128MulTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -330,3 +335,175 @@ impl Table for B128MulTable {
 }
 
 impl_b128_table_filler!(B128MulTable, B128MulEvent);
+
+/// B128_INV (Binary Field Inversion) table.
+///
+/// This table handles the B128_INV instruction, which performs inversion
+/// in the binary field GF(2^128), mapping zero to zero.
+pub struct B128InvTable {
+    /// Table ID
+    pub id: TableId,
+    /// State columns
+    state_cols: StateColumns<{ B128_INV_OPCODE }>,
+    /// Source value
+    pub src_val: Col<B128>,
+    pub src_val_unpacked: Col<B32, 4>,
+    /// Lookup for source
+    src_lookup: MultipleLookupColumns<4>,
+    /// Set if and only if the source value is not zero
+    pub src_nonzero: Col<B1>,
+    /// Result value
+    pub result_val: Col<B128>,
+    pub result_val_unpacked: Col<B32, 4>,
+    /// Lookup for result
+    result_lookup: MultipleLookupColumns<4>,
+    /// Source absolute address
+    pub src_abs_addr: Col<B32>,
+    /// Destination absolute address
+    pub dst_abs_addr: Col<B32>,
+}
+
+impl Table for B128InvTable {
+    type Event = B128InvEvent;
+
+    fn name(&self) -> &'static str {
+        "B128InvTable"
+    }
+
+    /// Create a new B128_INV table with the given constraint system and
+    /// channels.
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("b128_inv");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let StateColumns {
+            fp,
+            arg0: dst,
+            arg1: src,
+            ..
+        } = state_cols;
+
+        let src_val_unpacked = table.add_committed("b128_inv_src_val_unpacked");
+        let src_val = table.add_packed("b128_inv_src_val", src_val_unpacked);
+        let result_val_unpacked = table.add_committed("b128_inv_result_val_unpacked");
+        let result_val = table.add_packed("b128_inv_result_val", result_val_unpacked);
+        let src_nonzero = table.add_committed("b128_inv_src_nonzero");
+
+        // Pull source value from VROM channel
+        let src_abs_addr = table.add_computed("src_addr", fp + upcast_expr(src.into()));
+        let src_lookup = MultipleLookupColumns::new(
+            &mut table,
+            channels.vrom_channel,
+            src_abs_addr,
+            src_val_unpacked,
+            "b128_inv_src",
+        );
+
+        // The result is the inverse of a non-zero source, and zero otherwise.
+        table.assert_zero(
+            "check_b128_inv_result",
+            src_val * result_val - upcast_col(src_nonzero),
+        );
+        table.assert_zero(
+            "check_b128_inv_src_nonzero",
+            src_val * (upcast_col(src_nonzero) - B128::ONE),
+        );
+        table.assert_zero(
+            "check_b128_inv_zero",
+            result_val * (upcast_col(src_nonzero) - B128::ONE),
+        );
+
+        // Pull result from VROM channel
+        let dst_abs_addr = table.add_computed("dst_addr", fp + upcast_expr(dst.into()));
+        let result_lookup = MultipleLookupColumns::new(
+            &mut table,
+            channels.vrom_channel,
+            dst_abs_addr,
+            result_val_unpacked,
+            "b128_inv_dst",
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            src_val,
+            src_val_unpacked,
+            src_lookup,
+            src_nonzero,
+            result_val,
+            result_val_unpacked,
+            result_lookup,
+            src_abs_addr,
+            dst_abs_addr,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for B128InvTable {
+    type Event = B128InvEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut src_val_col_unpacked = witness.get_mut_as(self.src_val_unpacked)?;
+            let mut result_val_col_unpacked = witness.get_mut_as(self.result_val_unpacked)?;
+            let mut src_nonzero_col = witness.get_mut(self.src_nonzero)?;
+            let mut src_abs_addr_col = witness.get_scalars_mut(self.src_abs_addr)?;
+            let mut dst_abs_addr_col = witness.get_scalars_mut(self.dst_abs_addr)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                src_val_col_unpacked[i] = B128::new(event.src_val);
+                result_val_col_unpacked[i] = B128::new(event.dst_val);
+                set_packed_slice(&mut src_nonzero_col, i, B1::from(event.src_val != 0));
+                src_abs_addr_col[i] = B32::new(event.fp.addr(event.src));
+                dst_abs_addr_col[i] = B32::new(event.fp.addr(event.dst));
+            }
+        }
+
+        let state_iter = rows.clone().map(|ev| StateGadget {
+            pc: ev.pc.val(),
+            next_pc: None,
+            fp: *ev.fp,
+            timestamp: ev.timestamp,
+            arg0: ev.dst,
+            arg1: ev.src,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_iter)?;
+
+        let src_iter = rows.clone().map(|ev| {
+            let vals: [u32; 4] = <u128 as Divisible<u32>>::split_val(ev.src_val);
+            MultipleLookupGadget {
+                addr: ev.fp.addr(ev.src),
+                vals,
+            }
+        });
+        self.src_lookup.populate(witness, src_iter)?;
+
+        let result_iter = rows.map(|ev| {
+            let vals: [u32; 4] = <u128 as Divisible<u32>>::split_val(ev.dst_val);
+            MultipleLookupGadget {
+                addr: ev.fp.addr(ev.dst),
+                vals,
+            }
+        });
+        self.result_lookup.populate(witness, result_iter)
+    }
+}
@@ -2,14 +2,14 @@
 //!
 //! This module contains tables for binary field arithmetic operations.
 
-use binius_field::Field;
+use binius_field::{packed::set_packed_slice, Field};
 use binius_m3::builder::{
     upcast_col, upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1,
     B128, B16, B32,
 };
 use petravm_asm::{
-    opcodes::Opcode, AndEvent, AndiEvent, B32MulEvent, B32MuliEvent, OrEvent, OriEvent, XorEvent,
-    XoriEvent,
+    opcodes::Opcode, AndEvent, AndiEvent, B32InvEvent, B32MulEvent, B32MuliEvent, FieldPc, OrEvent,
+    OriEvent, XorEvent, XoriEvent,
 };
 
 use crate::{
@@ -24,6 +24,7 @@ use crate::{opcodes::G, utils::pack_instruction_with_32bits_imm_b128};
 // Constants for opcodes
 const B32_MUL_OPCODE: u16 = Opcode::B32Mul as u16;
 const B32_MULI_OPCODE: u16 = Opcode::B32Muli as u16;
+const B32_INV_OPCODE: u16 = Opcode::B32Inv as u16;
 const XOR_OPCODE: u16 = Opcode::Xor as u16;
 const XORI_OPCODE: u16 = Opcode::Xori as u16;
 const AND_OPCODE: u16 = Opcode::And as u16;
@@ -69,6 +70,7 @@ macro_rules! impl_b32_table_filler {
                     pc: event.pc.into(),
                     next_pc: None,
                     fp: *event.fp,
+                    timestamp: event.timestamp,
                     arg0: event.dst,
                     arg1: event.src1,
                     arg2: event.src2,
@@ -119,6 +121,7 @@ impl Table for B32MulTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -162,6 +165,132 @@ impl Table for B32MulTable {
 
 impl_b32_table_filler!(B32MulTable, B32MulEvent);
 
+/// B32_INV (Binary Field Inversion) table.
+///
+/// This table handles the B32_INV instruction, which performs inversion
+/// in the binary field GF(2^32), mapping zero to zero.
+pub struct B32InvTable {
+    /// Table ID
+    pub id: TableId,
+    /// State columns
+    state_cols: StateColumns<{ B32_INV_OPCODE }>,
+    /// Source value
+    pub src_val: Col<B32>,
+    /// Set if and only if the source value is not zero
+    pub src_nonzero: Col<B1>,
+    /// Result value
+    pub dst_val: Col<B32>,
+    /// Source absolute address
+    pub src_abs_addr: Col<B32>,
+    /// Destination absolute address
+    pub dst_abs_addr: Col<B32>,
+}
+
+impl Table for B32InvTable {
+    type Event = B32InvEvent;
+
+    fn name(&self) -> &'static str {
+        "B32InvTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("b32_inv");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let StateColumns {
+            fp,
+            arg0: dst,
+            arg1: src,
+            ..
+        } = state_cols;
+
+        let src_val = table.add_committed("b32_inv_src_val");
+        let src_nonzero = table.add_committed("b32_inv_src_nonzero");
+        let dst_val = table.add_committed("b32_inv_dst_val");
+
+        // The result is the inverse of a non-zero source, and zero otherwise.
+        table.assert_zero(
+            "b32_inv_dst_val",
+            src_val * dst_val - upcast_col(src_nonzero),
+        );
+        table.assert_zero(
+            "b32_inv_src_nonzero",
+            src_val * (upcast_col(src_nonzero) - B32::ONE),
+        );
+        table.assert_zero(
+            "b32_inv_zero",
+            dst_val * (upcast_col(src_nonzero) - B32::ONE),
+        );
+
+        // Pull the source value and the result from VROM channel
+        let src_abs_addr = table.add_computed("src_addr", fp + upcast_expr(src.into()));
+        let dst_abs_addr = table.add_computed("dst_addr", fp + upcast_expr(dst.into()));
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs_addr, src_val]);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs_addr, dst_val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            src_val,
+            src_nonzero,
+            dst_val,
+            src_abs_addr,
+            dst_abs_addr,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for B32InvTable {
+    type Event = B32InvEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs_addr = witness.get_scalars_mut(self.dst_abs_addr)?;
+            let mut dst_val = witness.get_scalars_mut(self.dst_val)?;
+            let mut src_abs_addr = witness.get_scalars_mut(self.src_abs_addr)?;
+            let mut src_val = witness.get_scalars_mut(self.src_val)?;
+            let mut src_nonzero = witness.get_mut(self.src_nonzero)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs_addr[i] = B32::new(event.fp.addr(event.dst));
+                dst_val[i] = B32::new(event.dst_val);
+                src_abs_addr[i] = B32::new(event.fp.addr(event.src));
+                src_val[i] = B32::new(event.src_val);
+                set_packed_slice(&mut src_nonzero, i, B1::from(event.src_val != 0));
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
 pub struct XorTable {
     /// Table ID
     id: TableId,
@@ -493,6 +622,7 @@ impl TableFiller<ProverPackedField> for OriTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -580,6 +710,7 @@ impl TableFiller<ProverPackedField> for XoriTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -683,6 +814,7 @@ impl TableFiller<ProverPackedField> for AndiTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -739,6 +871,7 @@ impl Table for B32MuliTable {
             StateColumnsOptions {
                 next_pc: NextPc::Target(next_pc),
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -830,7 +963,7 @@ impl TableFiller<ProverPackedField> for B32MuliTable {
                 dst_val_col[i] = B32::new(event.dst_val);
                 src_abs_addr_col[i] = B32::new(event.fp.addr(event.src));
                 dst_abs_addr_col[i] = B32::new(event.fp.addr(event.dst));
-                second_instruction_pc_col[i] = event.pc * G;
+                second_instruction_pc_col[i] = FieldPc::from(event.pc).next().field();
                 imm_high_col[i] = B16::new((event.imm >> 16) as u16);
                 second_instruction_packed_col[i] = pack_instruction_with_32bits_imm_b128(
                     second_instruction_pc_col[i],
@@ -844,8 +977,9 @@ impl TableFiller<ProverPackedField> for B32MuliTable {
         // Populate the first instruction State rows
         let state_rows = rows.clone().map(|event| StateGadget {
             pc: event.pc.val(),
-            next_pc: Some((event.pc * G * G).val()),
+            next_pc: Some(FieldPc::from(event.pc).next().next().val()),
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm as u16, // imm_low
@@ -0,0 +1,262 @@
+//! Binary field operation tables over GF(2^64) for the PetraVM M3 circuit.
+
+use binius_m3::builder::{
+    upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32, B64,
+};
+use petravm_asm::{opcodes::Opcode, B64MulEvent};
+
+use crate::{
+    channels::Channels,
+    gadgets::{
+        multiple_lookup::{MultipleLookupColumns, MultipleLookupGadget},
+        state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    },
+    opcodes::integer_ops::u64_limbs,
+    table::Table,
+    types::ProverPackedField,
+};
+
+const B64_MUL_OPCODE: u16 = Opcode::B64Mul as u16;
+
+/// B64_MUL (Binary Field Multiplication) table.
+///
+/// This table handles the B64_MUL instruction, which performs multiplication
+/// in the binary field GF(2^64).
+pub struct B64MulTable {
+    /// Table ID
+    pub id: TableId,
+    /// State columns
+    state_cols: StateColumns<{ B64_MUL_OPCODE }>,
+    /// First source value
+    pub src1_val: Col<B64>,
+    pub src1_val_unpacked: Col<B32, 2>,
+    /// Lookup for first source
+    src1_lookup: MultipleLookupColumns<2>,
+    /// Second source value
+    pub src2_val: Col<B64>,
+    pub src2_val_unpacked: Col<B32, 2>,
+    /// Lookup for second source
+    src2_lookup: MultipleLookupColumns<2>,
+    /// Result value
+    pub result_val: Col<B64>,
+    pub result_val_unpacked: Col<B32, 2>,
+    /// Lookup for result
+    result_lookup: MultipleLookupColumns<2>,
+    /// First source absolute address
+    pub src1_abs_addr: Col<B32>,
+    /// Second source absolute address
+    pub src2_abs_addr: Col<B32>,
+    /// Destination absolute address
+    pub dst_abs_addr: Col<B32>,
+}
+
+impl Table for B64MulTable {
+    type Event = B64MulEvent;
+
+    fn name(&self) -> &'static str {
+        "B64MulTable"
+    }
+
+    /// Create a new B64_MUL table with the given constraint system and
+    /// channels.
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("b64_mul");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let StateColumns {
+            fp,
+            arg0: dst,
+            arg1: src1,
+            arg2: src2,
+            ..
+        } = state_cols;
+
+        let src1_val_unpacked = table.add_committed("b64_mul_src1_val_unpacked");
+        let src1_val = table.add_packed("b64_mul_src1_val", src1_val_unpacked);
+        let src2_val_unpacked = table.add_committed("b64_mul_src2_val_unpacked");
+        let src2_val = table.add_packed("b64_mul_src2_val", src2_val_unpacked);
+        let result_val_unpacked = table.add_committed("b64_mul_result_val_unpacked");
+        let result_val = table.add_packed("b64_mul_result_val", result_val_unpacked);
+
+        // Pull source values from VROM channel
+        let src1_abs_addr = table.add_computed("src1_addr", fp + upcast_expr(src1.into()));
+        let src1_lookup = MultipleLookupColumns::new(
+            &mut table,
+            channels.vrom_channel,
+            src1_abs_addr,
+            src1_val_unpacked,
+            "b64_mul_src1",
+        );
+
+        let src2_abs_addr = table.add_computed("src2_addr", fp + upcast_expr(src2.into()));
+        let src2_lookup = MultipleLookupColumns::new(
+            &mut table,
+            channels.vrom_channel,
+            src2_abs_addr,
+            src2_val_unpacked,
+            "b64_mul_src2",
+        );
+
+        table.assert_zero("check_b64_mul_result", src1_val * src2_val - result_val);
+
+        // Pull result from VROM channel
+        let dst_abs_addr = table.add_computed("dst_addr", fp + upcast_expr(dst.into()));
+        let result_lookup = MultipleLookupColumns::new(
+            &mut table,
+            channels.vrom_channel,
+            dst_abs_addr,
+            result_val_unpacked,
+            "b64_mul_dst",
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            src1_val,
+            src1_val_unpacked,
+            src1_lookup,
+            src2_val,
+            src2_val_unpacked,
+            src2_lookup,
+            result_val,
+            result_val_unpacked,
+            result_lookup,
+            src1_abs_addr,
+            src2_abs_addr,
+            dst_abs_addr,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for B64MulTable {
+    type Event = B64MulEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut src1_val_col_unpacked = witness.get_mut_as(self.src1_val_unpacked)?;
+            let mut src2_val_col_unpacked = witness.get_mut_as(self.src2_val_unpacked)?;
+            let mut result_val_col_unpacked = witness.get_mut_as(self.result_val_unpacked)?;
+            let mut src1_abs_addr_col = witness.get_scalars_mut(self.src1_abs_addr)?;
+            let mut src2_abs_addr_col = witness.get_scalars_mut(self.src2_abs_addr)?;
+            let mut dst_abs_addr_col = witness.get_scalars_mut(self.dst_abs_addr)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                src1_val_col_unpacked[i] = B64::new(event.src1_val);
+                src2_val_col_unpacked[i] = B64::new(event.src2_val);
+                result_val_col_unpacked[i] = B64::new(event.dst_val);
+                src1_abs_addr_col[i] = B32::new(event.fp.addr(event.src1));
+                src2_abs_addr_col[i] = B32::new(event.fp.addr(event.src2));
+                dst_abs_addr_col[i] = B32::new(event.fp.addr(event.dst));
+            }
+        }
+
+        let state_iter = rows.clone().map(|ev| StateGadget {
+            pc: ev.pc.val(),
+            next_pc: None,
+            fp: *ev.fp,
+            timestamp: ev.timestamp,
+            arg0: ev.dst,
+            arg1: ev.src1,
+            arg2: ev.src2,
+        });
+        self.state_cols.populate(witness, state_iter)?;
+
+        let src1_iter = rows.clone().map(|ev| MultipleLookupGadget {
+            addr: ev.fp.addr(ev.src1),
+            vals: u64_limbs(ev.src1_val),
+        });
+        self.src1_lookup.populate(witness, src1_iter)?;
+
+        let src2_iter = rows.clone().map(|ev| MultipleLookupGadget {
+            addr: ev.fp.addr(ev.src2),
+            vals: u64_limbs(ev.src2_val),
+        });
+        self.src2_lookup.populate(witness, src2_iter)?;
+
+        let result_iter = rows.map(|ev| MultipleLookupGadget {
+            addr: ev.fp.addr(ev.dst),
+            vals: u64_limbs(ev.dst_val),
+        });
+        self.result_lookup.populate(witness, result_iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_binary_field_extension_ops_with_values(val1: u64, val2: u64, val3: u128) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x20)]\n\
+            _start:\n\
+            LDI.W @2, #{}\n\
+            LDI.W @3, #{}\n\
+            LDI.W @4, #{}\n\
+            LDI.W @5, #{}\n\
+            LDI.W @8, #{}\n\
+            LDI.W @9, #{}\n\
+            LDI.W @10, #{}\n\
+            LDI.W @11, #{}\n\
+            B64_MUL @6, @2, @4\n\
+            B64_MUL @12, @6, @6\n\
+            B128_INV @16, @8\n\
+            B128_MUL @20, @16, @8\n\
+            B32_INV @24, @2\n\
+            B32_INV @25, @3\n\
+            RET\n",
+            val1 as u32,
+            (val1 >> 32) as u32,
+            val2 as u32,
+            (val2 >> 32) as u32,
+            val3 as u32,
+            (val3 >> 32) as u32,
+            (val3 >> 64) as u32,
+            (val3 >> 96) as u32,
+        );
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+
+        assert_eq!(trace.b64_mul_events().len(), 2);
+        assert_eq!(trace.b128_inv_events().len(), 1);
+        assert_eq!(trace.b32_inv_events().len(), 2);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_binary_field_extension_ops(
+            val1 in prop_oneof![Just(0u64), Just(1u64), any::<u64>()],
+            val2 in any::<u64>(),
+            val3 in prop_oneof![Just(0u128), Just(1u128), any::<u128>()],
+        ) {
+            prop_assert!(test_binary_field_extension_ops_with_values(val1, val2, val3).is_ok());
+        }
+    }
+}
@@ -1,7 +1,10 @@
 mod b128;
 mod b32;
+mod b64;
 
-pub use b128::{B128AddTable, B128MulTable};
+pub use b128::{B128AddTable, B128InvTable, B128MulTable};
 pub use b32::{
-    AndTable, AndiTable, B32MulTable, B32MuliTable, OrTable, OriTable, XorTable, XoriTable,
+    AndTable, AndiTable, B32InvTable, B32MulTable, B32MuliTable, OrTable, OriTable, XorTable,
+    XoriTable,
 };
+pub use b64::B64MulTable;
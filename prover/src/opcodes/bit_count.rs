@@ -0,0 +1,467 @@
+use std::{array::from_fn, cell::RefMut};
+
+use binius_core::oracle::ShiftVariant;
+use binius_field::{packed::set_packed_slice, Field};
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, Expr, TableBuilder, TableFiller, TableId,
+    TableWitnessSegment, B1, B32,
+};
+use petravm_asm::{ClzEvent, CtzEvent, Opcode, PopcntEvent};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{StateColumns, StateColumnsOptions, StateGadget},
+    opcodes::G,
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// Number of bits needed to represent a count between 0 and 32.
+const COUNT_BITS: usize = 6;
+
+/// Returns `g_pow` if `bit` is set, and one otherwise.
+fn bit_factor(bit: Col<B1>, g_pow: B32) -> Expr<B32, 1> {
+    upcast_col(bit) * (g_pow - B32::ONE) + B32::ONE
+}
+
+/// Columns counting the set bits of a 32-bit value.
+///
+/// Integer additions are not native to binary fields, so the count is
+/// accumulated in the multiplicative group instead: `G^count` is the product of
+/// `G` over all set bits. It is then matched against the bit decomposition of
+/// `count`, whose `j`-th bit contributes a factor `G^(2^j)`.
+struct PopcountColumns {
+    bits: [Col<B1>; 32],
+    /// Running products of the bit factors, two bits at a time.
+    products: [Col<B32>; 16],
+    count_bits: [Col<B1>; COUNT_BITS],
+    /// Product of the factors of the three lowest count bits.
+    count_low_product: Col<B32>,
+    count: Col<B32>,
+    g_powers: [B32; COUNT_BITS],
+}
+
+impl PopcountColumns {
+    fn new(table: &mut TableBuilder, input: Col<B1, 32>) -> Self {
+        let bits = from_fn(|i| table.add_selected(format!("bit_{i}"), input, i));
+
+        let products: [Col<B32>; 16] = from_fn(|i| table.add_committed(format!("product_{i}")));
+        for i in 0..16 {
+            let factors = bit_factor(bits[2 * i], G) * bit_factor(bits[2 * i + 1], G);
+            if i == 0 {
+                table.assert_zero("product_0", products[0] - factors);
+            } else {
+                table.assert_zero(
+                    format!("product_{i}"),
+                    products[i] - products[i - 1] * factors,
+                );
+            }
+        }
+
+        let g_powers: [B32; COUNT_BITS] = from_fn(|j| (0..j).fold(G, |g, _| g.square()));
+        let count_bits: [Col<B1>; COUNT_BITS] =
+            from_fn(|j| table.add_committed(format!("count_bit_{j}")));
+        let count_low_product = table.add_committed("count_low_product");
+        table.assert_zero(
+            "count_low_product",
+            count_low_product
+                - bit_factor(count_bits[0], g_powers[0])
+                    * bit_factor(count_bits[1], g_powers[1])
+                    * bit_factor(count_bits[2], g_powers[2]),
+        );
+        table.assert_zero(
+            "count_product",
+            products[15]
+                - count_low_product
+                    * bit_factor(count_bits[3], g_powers[3])
+                    * bit_factor(count_bits[4], g_powers[4])
+                    * bit_factor(count_bits[5], g_powers[5]),
+        );
+
+        let count = table.add_computed(
+            "count",
+            (1..COUNT_BITS).fold(upcast_col(count_bits[0]), |acc, j| {
+                acc + upcast_col(count_bits[j]) * B32::new(1 << j)
+            }),
+        );
+
+        Self {
+            bits,
+            products,
+            count_bits,
+            count_low_product,
+            count,
+            g_powers,
+        }
+    }
+
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        inputs: impl Iterator<Item = u32>,
+    ) -> Result<(), anyhow::Error> {
+        let mut bits = self
+            .bits
+            .iter()
+            .map(|&col| witness.get_mut(col))
+            .collect::<Result<Vec<RefMut<'_, [ProverPackedField]>>, _>>()?;
+        let mut products = self
+            .products
+            .iter()
+            .map(|&col| witness.get_scalars_mut(col))
+            .collect::<Result<Vec<RefMut<'_, [B32]>>, _>>()?;
+        let mut count_bits = self
+            .count_bits
+            .iter()
+            .map(|&col| witness.get_mut(col))
+            .collect::<Result<Vec<RefMut<'_, [ProverPackedField]>>, _>>()?;
+        let mut count_low_product = witness.get_scalars_mut(self.count_low_product)?;
+        let mut count = witness.get_scalars_mut(self.count)?;
+
+        for (i, input) in inputs.enumerate() {
+            let mut product = B32::ONE;
+            for (j, bit) in bits.iter_mut().enumerate() {
+                let is_set = (input >> j) & 1 == 1;
+                set_packed_slice(bit, i, B1::from(is_set));
+                if is_set {
+                    product *= G;
+                }
+                if j % 2 == 1 {
+                    products[j / 2][i] = product;
+                }
+            }
+
+            let input_count = input.count_ones();
+            let mut low_product = B32::ONE;
+            for (j, bit) in count_bits.iter_mut().enumerate() {
+                let is_set = (input_count >> j) & 1 == 1;
+                set_packed_slice(bit, i, B1::from(is_set));
+                if is_set && j < 3 {
+                    low_product *= self.g_powers[j];
+                }
+            }
+            count_low_product[i] = low_product;
+            count[i] = B32::new(input_count);
+        }
+
+        Ok(())
+    }
+}
+
+/// Bit counting operation of a [`BitCountColumns`] gadget.
+#[derive(Clone, Copy)]
+enum BitCount {
+    LeadingZeros,
+    TrailingZeros,
+    Population,
+}
+
+/// Columns computing the zero bits left of the highest set bit (for
+/// [`BitCount::LeadingZeros`]) or right of the lowest one (for
+/// [`BitCount::TrailingZeros`]).
+///
+/// Each bit of `or_acc` is the OR of the matching input bit and all the bits
+/// above it (resp. below it), so that the count of zero bits in `or_acc` is
+/// the count of leading (resp. trailing) zeros.
+struct PrefixOrColumns {
+    or_acc: Col<B1, 32>,
+    shifted: Col<B1, 32>,
+    inverted: Col<B1, 32>,
+}
+
+/// Row data for the [`BitCountColumns`] gadget.
+struct BitCountRow {
+    pc: u32,
+    fp: u32,
+    timestamp: u32,
+    dst: u16,
+    dst_abs: u32,
+    src: u16,
+    src_abs: u32,
+    src_val: u32,
+}
+
+/// Columns shared by the bit counting tables.
+struct BitCountColumns<const OPCODE: u16> {
+    state_cols: StateColumns<OPCODE>,
+    dst_abs: Col<B32>,
+    src_abs: Col<B32>,
+    src_val_unpacked: Col<B1, 32>,
+    prefix_or: Option<PrefixOrColumns>,
+    popcount: PopcountColumns,
+    op: BitCount,
+}
+
+impl<const OPCODE: u16> BitCountColumns<OPCODE> {
+    fn new(table: &mut TableBuilder, channels: &Channels, op: BitCount) -> Self {
+        let state_cols = StateColumns::new(
+            table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        // Source value columns
+        let src_val_unpacked: Col<B1, 32> = table.add_committed("src_val_unpacked");
+        let src_val: Col<B32> = table.add_packed("src_val", src_val_unpacked);
+
+        // Address calculations
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg1));
+
+        let variant = match op {
+            BitCount::LeadingZeros => Some(ShiftVariant::LogicalRight),
+            BitCount::TrailingZeros => Some(ShiftVariant::LogicalLeft),
+            BitCount::Population => None,
+        };
+        let prefix_or = variant.map(|variant| {
+            let or_acc = table.add_committed("or_acc");
+            let shifted = table.add_shifted("or_acc_shifted", or_acc, 5, 1, variant);
+            // or_acc = src | shifted
+            table.assert_zero(
+                "or_acc",
+                or_acc - (src_val_unpacked + shifted + src_val_unpacked * shifted),
+            );
+            let inverted = table.add_computed("or_acc_inverted", or_acc + B1::ONE);
+            PrefixOrColumns {
+                or_acc,
+                shifted,
+                inverted,
+            }
+        });
+
+        let counted = prefix_or
+            .as_ref()
+            .map_or(src_val_unpacked, |prefix_or| prefix_or.inverted);
+        let popcount = PopcountColumns::new(table, counted);
+
+        // Pull memory access data from VROM channel
+        pull_vrom_channel(table, channels.vrom_channel, [dst_abs, popcount.count]);
+        pull_vrom_channel(table, channels.vrom_channel, [src_abs, src_val]);
+
+        Self {
+            state_cols,
+            dst_abs,
+            src_abs,
+            src_val_unpacked,
+            prefix_or,
+            popcount,
+            op,
+        }
+    }
+
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = BitCountRow> + Clone,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut src_unpacked = witness.get_mut_as(self.src_val_unpacked)?;
+
+            for (i, row) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(row.dst_abs);
+                src_abs[i] = B32::new(row.src_abs);
+                src_unpacked[i] = row.src_val;
+            }
+        }
+
+        // Fill the prefix OR, and get the values whose set bits are counted.
+        let counted = rows.clone().map(|row| match self.op {
+            BitCount::LeadingZeros => !prefix_or(row.src_val, |acc| acc >> 1),
+            BitCount::TrailingZeros => !prefix_or(row.src_val, |acc| acc << 1),
+            BitCount::Population => row.src_val,
+        });
+        if let Some(cols) = &self.prefix_or {
+            let mut or_acc = witness.get_mut_as(cols.or_acc)?;
+            let mut shifted = witness.get_mut_as(cols.shifted)?;
+            let mut inverted = witness.get_mut_as(cols.inverted)?;
+
+            for (i, value) in counted.clone().enumerate() {
+                or_acc[i] = !value;
+                shifted[i] = match self.op {
+                    BitCount::LeadingZeros => !value >> 1,
+                    _ => !value << 1,
+                };
+                inverted[i] = value;
+            }
+        }
+        self.popcount.populate(witness, counted)?;
+
+        let state_rows = rows.map(|row| StateGadget {
+            pc: row.pc,
+            next_pc: None,
+            fp: row.fp,
+            timestamp: row.timestamp,
+            arg0: row.dst,
+            arg1: row.src,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// Returns the solution of `acc = value | shift(acc)`, i.e. the OR of each bit
+/// of `value` with all the bits that `shift` moves onto it.
+fn prefix_or(value: u32, shift: impl Fn(u32) -> u32) -> u32 {
+    (1..32).fold(value, |acc, _| acc | shift(acc))
+}
+
+/// Defines a table for a bit counting instruction on top of
+/// [`BitCountColumns`].
+macro_rules! define_bit_count_table {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $event:ty,
+        $opcode:path,
+        $table_name:literal,
+        $op:ident
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            id: TableId,
+            bit_count_cols: BitCountColumns<{ $opcode as u16 }>,
+        }
+
+        impl Table for $name {
+            type Event = $event;
+
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+                let mut table = cs.add_table($table_name);
+
+                let bit_count_cols = BitCountColumns::new(&mut table, channels, BitCount::$op);
+
+                Self {
+                    id: table.id(),
+                    bit_count_cols,
+                }
+            }
+        }
+
+        impl TableFiller<ProverPackedField> for $name {
+            type Event = $event;
+
+            fn id(&self) -> TableId {
+                self.id
+            }
+
+            fn fill<'a>(
+                &'a self,
+                rows: impl Iterator<Item = &'a Self::Event> + Clone,
+                witness: &'a mut TableWitnessSegment<ProverPackedField>,
+            ) -> anyhow::Result<()> {
+                let bit_count_rows = rows.map(|ev| BitCountRow {
+                    pc: ev.pc.val(),
+                    fp: *ev.fp,
+                    timestamp: ev.timestamp,
+                    dst: ev.dst,
+                    dst_abs: ev.fp.addr(ev.dst),
+                    src: ev.src,
+                    src_abs: ev.fp.addr(ev.src),
+                    src_val: ev.src_val,
+                });
+                self.bit_count_cols.populate(witness, bit_count_rows)
+            }
+        }
+    };
+}
+
+define_bit_count_table!(
+    /// Table for CLZ.
+    ///
+    /// Counts the leading zero bits of the source value.
+    ClzTable,
+    ClzEvent,
+    Opcode::Clz,
+    "clz",
+    LeadingZeros
+);
+
+define_bit_count_table!(
+    /// Table for CTZ.
+    ///
+    /// Counts the trailing zero bits of the source value.
+    CtzTable,
+    CtzEvent,
+    Opcode::Ctz,
+    "ctz",
+    TrailingZeros
+);
+
+define_bit_count_table!(
+    /// Table for POPCNT.
+    ///
+    /// Counts the set bits of the source value.
+    PopcntTable,
+    PopcntEvent,
+    Opcode::Popcnt,
+    "popcnt",
+    Population
+);
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+    use proptest::prop_oneof;
+
+    use super::prefix_or;
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_bit_count_with_value(val: u32) -> Result<()> {
+        let asm_code = "#[framesize(0x10)]\n\
+            _start:\n\
+            CLZ @3, @2\n\
+            CTZ @4, @2\n\
+            POPCNT @5, @2\n\
+            CLZ @6, @2\n\
+            CTZ @7, @2\n\
+            POPCNT @8, @2\n\
+            RET\n"
+            .to_string();
+
+        let init_values = vec![0, 0, val];
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, Some(init_values), None, isa)?;
+        trace.validate()?;
+
+        assert_eq!(trace.clz_events().len(), 2);
+        assert_eq!(trace.ctz_events().len(), 2);
+        assert_eq!(trace.popcnt_events().len(), 2);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    #[test]
+    fn test_prefix_or() {
+        assert_eq!(prefix_or(0, |acc| acc << 1), 0);
+        assert_eq!(prefix_or(0x0010_0100, |acc| acc << 1), 0xffff_ff00);
+        assert_eq!(prefix_or(0x0010_0100, |acc| acc >> 1), 0x001f_ffff);
+        assert_eq!(prefix_or(0x8000_0000, |acc| acc >> 1), u32::MAX);
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_bit_count(
+            val in prop_oneof![
+                Just(0u32),                     // No set bits
+                Just(u32::MAX),                 // All bits set
+                Just(1u32 << 31),               // Only the top bit set
+                any::<u32>()                    // Random values
+            ]
+        ) {
+            prop_assert!(test_bit_count_with_value(val).is_ok());
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use std::{array::from_fn, cell::RefMut};
+
+use binius_core::oracle::ShiftVariant;
+use binius_field::Field;
+use binius_m3::{
+    builder::{
+        upcast_col, Col, ConstraintSystem, TableBuilder, TableFiller, TableId, TableWitnessSegment,
+        B1, B32,
+    },
+    gadgets::add::{U32Add, U32AddFlags},
+};
+use petravm_asm::{
+    opcodes::Opcode,
+    util::{blake3_g, BLAKE3_G_INDICES, BLAKE3_G_ROTATIONS, BLAKE3_IV, BLAKE3_MSG_PERMUTATION},
+    Blake3CompressEvent,
+};
+
+use super::groestl::get_all_addresses;
+use crate::{
+    channels::Channels,
+    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+const BLAKE3_COMPRESS_OPCODE: u16 = Opcode::Blake3Compress as u16;
+
+/// Number of rounds of the compression function.
+const NUM_ROUNDS: usize = 7;
+
+/// Adds a column holding `lhs ^ rhs` and a column holding that value rotated
+/// right by `n`.
+fn add_xor_rotr(
+    table: &mut TableBuilder,
+    name: &str,
+    lhs: Col<B1, 32>,
+    rhs: Col<B1, 32>,
+    n: u32,
+) -> (Col<B1, 32>, Col<B1, 32>) {
+    let xor = table.add_computed(format!("{name}_xor"), lhs + rhs);
+    // Rotating right by `n` is rotating left by `32 - n`.
+    let rotated = table.add_shifted(
+        format!("{name}_rotated"),
+        xor,
+        5,
+        32 - n as usize,
+        ShiftVariant::CircularLeft,
+    );
+    (xor, rotated)
+}
+
+/// Columns for one application of the G function, which alternates additions,
+/// XORs and rotations:
+///
+/// ```text
+/// a = a + b + x; d = (d ^ a) >>> 16; c = c + d; b = (b ^ c) >>> 12;
+/// a = a + b + y; d = (d ^ a) >>> 8;  c = c + d; b = (b ^ c) >>> 7;
+/// ```
+struct GColumns {
+    /// Additions, in the order they are carried out.
+    adds: [U32Add; 6],
+    /// The four XORs, in the order they are carried out.
+    xors: [Col<B1, 32>; 4],
+    /// The XORs rotated by [`BLAKE3_G_ROTATIONS`].
+    rotated: [Col<B1, 32>; 4],
+}
+
+impl GColumns {
+    fn new(
+        table: &mut TableBuilder,
+        name: &str,
+        [a, b, c, d]: [Col<B1, 32>; 4],
+        x: Col<B1, 32>,
+        y: Col<B1, 32>,
+    ) -> Self {
+        let add_ab0 = U32Add::new(table, a, b, U32AddFlags::default());
+        let add_x = U32Add::new(table, add_ab0.zout, x, U32AddFlags::default());
+        let a = add_x.zout;
+        let (xor_d0, d) = add_xor_rotr(table, &format!("{name}_d0"), d, a, BLAKE3_G_ROTATIONS[0]);
+        let add_c0 = U32Add::new(table, c, d, U32AddFlags::default());
+        let c = add_c0.zout;
+        let (xor_b0, b) = add_xor_rotr(table, &format!("{name}_b0"), b, c, BLAKE3_G_ROTATIONS[1]);
+        let rotated_d0 = d;
+        let rotated_b0 = b;
+
+        let add_ab1 = U32Add::new(table, a, b, U32AddFlags::default());
+        let add_y = U32Add::new(table, add_ab1.zout, y, U32AddFlags::default());
+        let a = add_y.zout;
+        let (xor_d1, d) = add_xor_rotr(table, &format!("{name}_d1"), d, a, BLAKE3_G_ROTATIONS[2]);
+        let add_c1 = U32Add::new(table, c, d, U32AddFlags::default());
+        let c = add_c1.zout;
+        let (xor_b1, b) = add_xor_rotr(table, &format!("{name}_b1"), b, c, BLAKE3_G_ROTATIONS[3]);
+
+        Self {
+            adds: [add_ab0, add_x, add_c0, add_ab1, add_y, add_c1],
+            xors: [xor_d0, xor_b0, xor_d1, xor_b1],
+            rotated: [rotated_d0, rotated_b0, d, b],
+        }
+    }
+
+    /// Returns the columns holding the new `a`, `b`, `c` and `d`.
+    const fn outputs(&self) -> [Col<B1, 32>; 4] {
+        [
+            self.adds[4].zout,
+            self.rotated[3],
+            self.adds[5].zout,
+            self.rotated[2],
+        ]
+    }
+
+    /// Populates the XOR and rotation columns, given the inputs `[a, b, c, d,
+    /// x, y]` of the G function for each row. The additions are populated
+    /// separately.
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        inputs: impl Iterator<Item = [u32; 6]>,
+    ) -> Result<(), anyhow::Error> {
+        let mut xors = self
+            .xors
+            .iter()
+            .map(|&col| witness.get_mut_as(col))
+            .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+        let mut rotated = self
+            .rotated
+            .iter()
+            .map(|&col| witness.get_mut_as(col))
+            .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+        for (i, [a, b, c, d, x, y]) in inputs.enumerate() {
+            let a = a.wrapping_add(b).wrapping_add(x);
+            xors[0][i] = d ^ a;
+            let d = xors[0][i].rotate_right(BLAKE3_G_ROTATIONS[0]);
+            rotated[0][i] = d;
+            let c = c.wrapping_add(d);
+            xors[1][i] = b ^ c;
+            let b = xors[1][i].rotate_right(BLAKE3_G_ROTATIONS[1]);
+            rotated[1][i] = b;
+
+            let a = a.wrapping_add(b).wrapping_add(y);
+            xors[2][i] = d ^ a;
+            let d = xors[2][i].rotate_right(BLAKE3_G_ROTATIONS[2]);
+            rotated[2][i] = d;
+            let c = c.wrapping_add(d);
+            xors[3][i] = b ^ c;
+            rotated[3][i] = xors[3][i].rotate_right(BLAKE3_G_ROTATIONS[3]);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the inputs `[a, b, c, d, x, y]` of each application of the G
+/// function during the compression of `event`.
+fn g_inputs(event: &Blake3CompressEvent) -> Vec<[u32; 6]> {
+    let mut state = [0; 16];
+    state[..8].copy_from_slice(&event.src1_val[..8]);
+    state[8..12].copy_from_slice(&BLAKE3_IV[..4]);
+    state[12..].copy_from_slice(&event.src1_val[8..]);
+
+    let mut inputs = Vec::with_capacity(NUM_ROUNDS * 8);
+    let mut m = event.src2_val;
+    for round in 0..NUM_ROUNDS {
+        for (i, indices) in BLAKE3_G_INDICES.iter().enumerate() {
+            let [a, b, c, d] = indices.map(|j| state[j]);
+            let (x, y) = (m[2 * i], m[2 * i + 1]);
+            inputs.push([a, b, c, d, x, y]);
+            for (&j, word) in indices.iter().zip(blake3_g([a, b, c, d], x, y)) {
+                state[j] = word;
+            }
+        }
+        if round < NUM_ROUNDS - 1 {
+            m = BLAKE3_MSG_PERMUTATION.map(|j| m[j]);
+        }
+    }
+
+    inputs
+}
+
+/// BLAKE3_COMPRESS table.
+///
+/// This table handles the BLAKE3_COMPRESS instruction, which applies the
+/// BLAKE3 compression function. Each row carries out a full compression: the
+/// 7 rounds of 8 G functions, followed by the output XORs.
+///
+/// Bitwise operations are carried out on unpacked 32-bit columns: rotations
+/// are shifted columns and XORs are additions. Additions modulo 2^32 use the
+/// `U32Add` gadget. The message permutation between rounds only reorders
+/// columns and costs nothing.
+pub struct Blake3CompressTable {
+    id: TableId,
+    state_cols: StateColumns<BLAKE3_COMPRESS_OPCODE>,
+    dst_addresses: [Col<B32>; 16],
+    /// Output values.
+    dst_vals: [Col<B1, 32>; 16],
+    src1_addresses: [Col<B32>; 12],
+    /// Chaining value, block counter, block length and flags.
+    src1_vals: [Col<B1, 32>; 12],
+    src2_addresses: [Col<B32>; 16],
+    /// Message block.
+    src2_vals: [Col<B1, 32>; 16],
+    /// First four words of the initial value.
+    iv: [Col<B1, 32>; 4],
+    g_cols: Vec<GColumns>,
+}
+
+impl Table for Blake3CompressTable {
+    type Event = Blake3CompressEvent;
+
+    fn name(&self) -> &'static str {
+        "Blake3Compress"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("Blake3Compress");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            vrom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // Pull the inputs from the VROM.
+        let src1_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg1),
+            "src1_addr",
+        );
+        let src1_vals: [Col<B1, 32>; 12] =
+            from_fn(|i| table.add_committed(format!("src1_val_{i}")));
+        let src2_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg2),
+            "src2_addr",
+        );
+        let src2_vals: [Col<B1, 32>; 16] =
+            from_fn(|i| table.add_committed(format!("src2_val_{i}")));
+
+        for i in 0..12 {
+            let packed = table.add_packed(format!("src1_val_packed_{i}"), src1_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [src1_addresses[i], packed]);
+        }
+        for i in 0..16 {
+            let packed = table.add_packed(format!("src2_val_packed_{i}"), src2_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [src2_addresses[i], packed]);
+        }
+
+        let iv: [Col<B1, 32>; 4] = from_fn(|i| {
+            table.add_constant(
+                format!("iv_{i}"),
+                from_fn(|j| {
+                    if (BLAKE3_IV[i] >> j) & 1 == 1 {
+                        B1::ONE
+                    } else {
+                        B1::ZERO
+                    }
+                }),
+            )
+        });
+
+        // Carry out the rounds.
+        // The state starts with the chaining value, the first four words of the
+        // initial value, the block counter, the block length and the flags.
+        let mut state: [Col<B1, 32>; 16] = from_fn(|i| {
+            if (8..12).contains(&i) {
+                iv[i - 8]
+            } else if i < 8 {
+                src1_vals[i]
+            } else {
+                src1_vals[i - 4]
+            }
+        });
+        let mut m = src2_vals;
+        let mut g_cols = Vec::with_capacity(NUM_ROUNDS * 8);
+        for round in 0..NUM_ROUNDS {
+            for (i, indices) in BLAKE3_G_INDICES.iter().enumerate() {
+                let g = GColumns::new(
+                    &mut table,
+                    &format!("round_{round}_g_{i}"),
+                    indices.map(|j| state[j]),
+                    m[2 * i],
+                    m[2 * i + 1],
+                );
+                for (&j, col) in indices.iter().zip(g.outputs()) {
+                    state[j] = col;
+                }
+                g_cols.push(g);
+            }
+            if round < NUM_ROUNDS - 1 {
+                m = BLAKE3_MSG_PERMUTATION.map(|j| m[j]);
+            }
+        }
+
+        // Compute the output and write it to the VROM.
+        let dst_vals: [Col<B1, 32>; 16] = from_fn(|i| {
+            let rhs = if i < 8 {
+                state[i + 8]
+            } else {
+                src1_vals[i - 8]
+            };
+            table.add_computed(format!("dst_val_{i}"), state[i] + rhs)
+        });
+        let dst_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg0),
+            "dst_addr",
+        );
+        for i in 0..16 {
+            let packed = table.add_packed(format!("dst_val_packed_{i}"), dst_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [dst_addresses[i], packed]);
+        }
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_addresses,
+            dst_vals,
+            src1_addresses,
+            src1_vals,
+            src2_addresses,
+            src2_vals,
+            iv,
+            g_cols,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for Blake3CompressTable {
+    type Event = Blake3CompressEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        let inputs = rows.clone().map(g_inputs).collect::<Vec<_>>();
+
+        {
+            let mut dst_addresses = (0..16)
+                .map(|i| witness.get_mut_as(self.dst_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut dst_vals = (0..16)
+                .map(|i| witness.get_mut_as(self.dst_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src1_addresses = (0..12)
+                .map(|i| witness.get_mut_as(self.src1_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src1_vals = (0..12)
+                .map(|i| witness.get_mut_as(self.src1_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src2_addresses = (0..16)
+                .map(|i| witness.get_mut_as(self.src2_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src2_vals = (0..16)
+                .map(|i| witness.get_mut_as(self.src2_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut iv = (0..4)
+                .map(|i| witness.get_mut_as(self.iv[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, event) in rows.clone().enumerate() {
+                for j in 0..16 {
+                    dst_addresses[j][i] = event.fp.addr(event.dst + j as u16);
+                    dst_vals[j][i] = event.dst_val[j];
+                    src2_addresses[j][i] = event.fp.addr(event.src2 + j as u16);
+                    src2_vals[j][i] = event.src2_val[j];
+                }
+                for j in 0..12 {
+                    src1_addresses[j][i] = event.fp.addr(event.src1 + j as u16);
+                    src1_vals[j][i] = event.src1_val[j];
+                }
+                for j in 0..4 {
+                    iv[j][i] = BLAKE3_IV[j];
+                }
+            }
+        }
+
+        // Populate the XORs and rotations, then the additions in the order they
+        // were created, as each one may depend on the outputs of the previous
+        // ones.
+        for (i, g) in self.g_cols.iter().enumerate() {
+            g.populate(witness, inputs.iter().map(|row| row[i]))?;
+        }
+        for g in &self.g_cols {
+            for add in &g.adds {
+                add.populate(witness)?;
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::{prover::Prover, test_utils::generate_blake3_ret_trace};
+
+    fn test_blake3_with_values(input: [u32; 12], block: [u32; 16]) -> Result<()> {
+        let trace = generate_blake3_ret_trace(input, block)?;
+        trace.validate()?;
+        assert_eq!(trace.blake3_compress_events().len(), 1);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(5))]
+
+        #[test]
+        fn test_blake3(
+            input in any::<[u32; 12]>(),
+            block in any::<[u32; 16]>(),
+        ) {
+            prop_assert!(test_blake3_with_values(input, block).is_ok());
+        }
+    }
+}
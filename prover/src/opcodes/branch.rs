@@ -1,11 +1,16 @@
-use binius_field::{Field, PackedField};
-use binius_m3::builder::{
-    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32,
+use binius_field::{packed::set_packed_slice, Field, PackedField};
+use binius_m3::{
+    builder::{
+        upcast_col, Col, ConstraintSystem, TableBuilder, TableFiller, TableId, TableWitnessSegment,
+        B1, B32, B8,
+    },
+    gadgets::sub::{U32Sub, U32SubFlags},
 };
-use petravm_asm::{BnzEvent, BzEvent, Opcode};
+use petravm_asm::{BeqEvent, BgeEvent, BltEvent, BneEvent, BnzEvent, BzEvent, Opcode};
 
 use crate::gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget};
-use crate::utils::pull_vrom_channel;
+use crate::opcodes::G;
+use crate::utils::{pack_b16_into_b32, pack_b8_into_b16, pull_vrom_channel};
 use crate::{channels::Channels, table::Table, types::ProverPackedField};
 
 /// Table for BNZ in the non-zero case.
@@ -41,6 +46,7 @@ impl Table for BnzTable {
             StateColumnsOptions {
                 next_pc: NextPc::Immediate,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -89,6 +95,7 @@ impl TableFiller<ProverPackedField> for BnzTable {
             pc: event.pc.val(),
             next_pc: Some(event.target.val()),
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.target.val() as u16,
             arg1: (event.target.val() >> 16) as u16,
             arg2: event.cond,
@@ -121,6 +128,7 @@ impl Table for BzTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -159,6 +167,7 @@ impl TableFiller<ProverPackedField> for BzTable {
             pc: event.pc.val(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.target.val() as u16,
             arg1: (event.target.val() >> 16) as u16,
             arg2: event.cond,
@@ -166,3 +175,369 @@ impl TableFiller<ProverPackedField> for BzTable {
         self.state_cols.populate(witness, state_rows)
     }
 }
+
+/// Witness data shared by all branch-on-compare instructions.
+struct BranchCmpRow {
+    pc: u32,
+    next_pc: u32,
+    fp: u32,
+    timestamp: u32,
+    target: u32,
+    src1: u16,
+    src1_abs: u32,
+    src1_val: u32,
+    src2: u16,
+    src2_abs: u32,
+    src2_val: u32,
+}
+
+/// Columns reading the two operands of a branch-on-compare instruction, and
+/// selecting the next program counter.
+///
+/// Both operand slots are packed in the third argument of the instruction, as
+/// 8-bit values.
+struct BranchCmpColumns<const OPCODE: u16> {
+    state_cols: StateColumns<OPCODE>,
+    next_pc: Col<B32>,
+    src1: Col<B8>,
+    src1_abs: Col<B32>, // Virtual
+    src1_val: Col<B1, 32>,
+    src1_val_packed: Col<B32>, // Virtual
+    src2: Col<B8>,
+    src2_abs: Col<B32>, // Virtual
+    src2_val: Col<B1, 32>,
+    src2_val_packed: Col<B32>, // Virtual
+}
+
+impl<const OPCODE: u16> BranchCmpColumns<OPCODE> {
+    fn new(table: &mut TableBuilder, channels: &Channels) -> Self {
+        let next_pc = table.add_committed("next_pc");
+        let state_cols = StateColumns::new(
+            table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Target(next_pc),
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let src1 = table.add_committed("src1");
+        let src2 = table.add_committed("src2");
+        table.assert_zero("srcs", state_cols.arg2 - pack_b8_into_b16(src1, src2));
+
+        // Read both operands.
+        let src1_abs = table.add_computed("src1_abs", state_cols.fp + upcast_col(src1));
+        let src1_val = table.add_committed("src1_val");
+        let src1_val_packed = table.add_packed("src1_val_packed", src1_val);
+        pull_vrom_channel(table, channels.vrom_channel, [src1_abs, src1_val_packed]);
+
+        let src2_abs = table.add_computed("src2_abs", state_cols.fp + upcast_col(src2));
+        let src2_val = table.add_committed("src2_val");
+        let src2_val_packed = table.add_packed("src2_val_packed", src2_val);
+        pull_vrom_channel(table, channels.vrom_channel, [src2_abs, src2_val_packed]);
+
+        Self {
+            state_cols,
+            next_pc,
+            src1,
+            src1_abs,
+            src1_val,
+            src1_val_packed,
+            src2,
+            src2_abs,
+            src2_val,
+            src2_val_packed,
+        }
+    }
+
+    /// Constrains the next program counter to be the branch target when `bit`
+    /// equals `taken_if_set`, and the following instruction otherwise.
+    fn assert_next_pc(&self, table: &mut TableBuilder, bit: Col<B1>, taken_if_set: bool) {
+        let state_cols = &self.state_cols;
+        let target = || pack_b16_into_b32(state_cols.arg0, state_cols.arg1);
+        let incr_pc = || state_cols.pc * G;
+        let constraint = if taken_if_set {
+            self.next_pc - incr_pc() - upcast_col(bit) * (target() - incr_pc())
+        } else {
+            self.next_pc - target() - upcast_col(bit) * (incr_pc() - target())
+        };
+        table.assert_zero("next_pc", constraint);
+    }
+
+    fn populate<T>(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: T,
+    ) -> Result<(), anyhow::Error>
+    where
+        T: Iterator<Item = BranchCmpRow> + Clone,
+    {
+        {
+            let mut src1 = witness.get_scalars_mut(self.src1)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src1_val = witness.get_mut_as(self.src1_val)?;
+            let mut src2 = witness.get_scalars_mut(self.src2)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+            let mut src2_val = witness.get_mut_as(self.src2_val)?;
+
+            for (i, row) in rows.clone().enumerate() {
+                src1[i] = B8::new(row.src1 as u8);
+                src1_abs[i] = B32::new(row.src1_abs);
+                src1_val[i] = row.src1_val;
+                src2[i] = B8::new(row.src2 as u8);
+                src2_abs[i] = B32::new(row.src2_abs);
+                src2_val[i] = row.src2_val;
+            }
+        }
+
+        let state_rows = rows.map(|row| StateGadget {
+            pc: row.pc,
+            next_pc: Some(row.next_pc),
+            fp: row.fp,
+            timestamp: row.timestamp,
+            arg0: row.target as u16,
+            arg1: (row.target >> 16) as u16,
+            arg2: row.src1 | (row.src2 << 8),
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// Columns checking whether the two operands are equal.
+struct EqualColumns {
+    diff_inv: Col<B32>,
+    equal: Col<B1>,
+}
+
+impl EqualColumns {
+    fn new<const OPCODE: u16>(
+        table: &mut TableBuilder,
+        operands: &BranchCmpColumns<OPCODE>,
+    ) -> Self {
+        // `equal` is set if and only if the difference of the operands is zero.
+        let diff = || operands.src1_val_packed + operands.src2_val_packed;
+        let diff_inv = table.add_committed("diff_inv");
+        let equal = table.add_committed("equal");
+        table.assert_zero("diff_inv", diff() * diff_inv + upcast_col(equal) - B32::ONE);
+        table.assert_zero("equal", diff() * upcast_col(equal));
+
+        Self { diff_inv, equal }
+    }
+
+    fn populate<T>(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: T,
+    ) -> Result<(), anyhow::Error>
+    where
+        T: Iterator<Item = (u32, u32)>,
+    {
+        let mut diff_inv = witness.get_scalars_mut(self.diff_inv)?;
+        let mut equal = witness.get_mut(self.equal)?;
+        for (i, (src1_val, src2_val)) in rows.enumerate() {
+            diff_inv[i] = B32::new(src1_val ^ src2_val).invert_or_zero();
+            set_packed_slice(&mut equal, i, B1::from(src1_val == src2_val));
+        }
+        Ok(())
+    }
+}
+
+/// Columns checking whether the first operand is less than the second one,
+/// both being interpreted as signed integers.
+struct SignedLtColumns {
+    src1_sign: Col<B1>,
+    src2_sign: Col<B1>,
+    subber: U32Sub,
+    lt: Col<B1>,
+}
+
+impl SignedLtColumns {
+    fn new<const OPCODE: u16>(
+        table: &mut TableBuilder,
+        operands: &BranchCmpColumns<OPCODE>,
+    ) -> Self {
+        let src1_sign = table.add_selected("src1_sign", operands.src1_val, 31);
+        let src2_sign = table.add_selected("src2_sign", operands.src2_val, 31);
+
+        let subber = U32Sub::new(
+            table,
+            operands.src1_val,
+            operands.src2_val,
+            U32SubFlags {
+                borrow_in_bit: None,
+                expose_final_borrow: true,
+                commit_zout: false,
+            },
+        );
+        let final_borrow = subber
+            .final_borrow
+            .expect("Flag `expose_final_borrow` was set to `true`");
+
+        // As for SLT, the unsigned comparison holds when both signs are equal,
+        // and the sign of the first operand decides otherwise.
+        let lt = table.add_committed("lt");
+        table.assert_zero(
+            "lt",
+            lt - (src1_sign + src2_sign) * (src1_sign + final_borrow) - final_borrow,
+        );
+
+        Self {
+            src1_sign,
+            src2_sign,
+            subber,
+            lt,
+        }
+    }
+
+    fn populate<T>(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: T,
+    ) -> Result<(), anyhow::Error>
+    where
+        T: Iterator<Item = (u32, u32)>,
+    {
+        {
+            let mut src1_sign = witness.get_mut(self.src1_sign)?;
+            let mut src2_sign = witness.get_mut(self.src2_sign)?;
+            let mut lt = witness.get_mut(self.lt)?;
+            for (i, (src1_val, src2_val)) in rows.enumerate() {
+                set_packed_slice(&mut src1_sign, i, B1::from(src1_val >> 31 == 1));
+                set_packed_slice(&mut src2_sign, i, B1::from(src2_val >> 31 == 1));
+                set_packed_slice(&mut lt, i, B1::from((src1_val as i32) < (src2_val as i32)));
+            }
+        }
+        self.subber.populate(witness)
+    }
+}
+
+/// Defines a branch-on-compare table, and implements the [`Table`] and
+/// [`TableFiller`] traits for it.
+///
+/// It takes as argument the table, with optional Rust documentation, its
+/// event, opcode and name, the columns comparing the operands together with
+/// their output bit, and whether the branch is taken when that bit is set.
+macro_rules! define_branch_cmp_table {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $event:ty,
+        $opcode:path,
+        $table_name:literal,
+        $cmp:ident.$bit:ident,
+        $taken_if_set:literal
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            id: TableId,
+            branch_cols: BranchCmpColumns<{ $opcode as u16 }>,
+            cmp: $cmp,
+        }
+
+        impl Table for $name {
+            type Event = $event;
+
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+                let mut table = cs.add_table($table_name);
+
+                let branch_cols = BranchCmpColumns::new(&mut table, channels);
+                let cmp = $cmp::new(&mut table, &branch_cols);
+                branch_cols.assert_next_pc(&mut table, cmp.$bit, $taken_if_set);
+
+                Self {
+                    id: table.id(),
+                    branch_cols,
+                    cmp,
+                }
+            }
+        }
+
+        impl TableFiller<ProverPackedField> for $name {
+            type Event = $event;
+
+            fn id(&self) -> TableId {
+                self.id
+            }
+
+            fn fill<'a>(
+                &self,
+                rows: impl Iterator<Item = &'a Self::Event> + Clone,
+                witness: &'a mut TableWitnessSegment<ProverPackedField>,
+            ) -> Result<(), anyhow::Error> {
+                let branch_rows = rows.clone().map(|event| BranchCmpRow {
+                    pc: event.pc.val(),
+                    next_pc: event.next_pc().val(),
+                    fp: *event.fp,
+                    timestamp: event.timestamp,
+                    target: event.target.val(),
+                    src1: event.src1,
+                    src1_abs: event.fp.addr(event.src1),
+                    src1_val: event.src1_val,
+                    src2: event.src2,
+                    src2_abs: event.fp.addr(event.src2),
+                    src2_val: event.src2_val,
+                });
+                // The operands must be filled before comparing them.
+                self.branch_cols.populate(witness, branch_rows)?;
+                self.cmp
+                    .populate(witness, rows.map(|event| (event.src1_val, event.src2_val)))
+            }
+        }
+    };
+}
+
+define_branch_cmp_table!(
+    /// Table for BEQ.
+    ///
+    /// Branches to the target address if both operands are equal.
+    BeqTable,
+    BeqEvent,
+    Opcode::Beq,
+    "beq",
+    EqualColumns.equal,
+    true
+);
+
+define_branch_cmp_table!(
+    /// Table for BNE.
+    ///
+    /// Branches to the target address if the operands differ.
+    BneTable,
+    BneEvent,
+    Opcode::Bne,
+    "bne",
+    EqualColumns.equal,
+    false
+);
+
+define_branch_cmp_table!(
+    /// Table for BLT.
+    ///
+    /// Branches to the target address if the first operand is less than the
+    /// second one, both being interpreted as signed integers.
+    BltTable,
+    BltEvent,
+    Opcode::Blt,
+    "blt",
+    SignedLtColumns.lt,
+    true
+);
+
+define_branch_cmp_table!(
+    /// Table for BGE.
+    ///
+    /// Branches to the target address if the first operand is greater than or
+    /// equal to the second one, both being interpreted as signed integers.
+    BgeTable,
+    BgeEvent,
+    Opcode::Bge,
+    "bge",
+    SignedLtColumns.lt,
+    false
+);
@@ -0,0 +1,326 @@
+use std::{array::from_fn, cell::RefMut};
+
+use binius_field::packed::set_packed_slice;
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableBuilder, TableFiller, TableId, TableWitnessSegment, B1,
+    B32,
+};
+use petravm_asm::{BswapEvent, Opcode, SextbEvent, SexthEvent};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// Byte operation of a [`BytePermutationColumns`] gadget.
+#[derive(Clone, Copy)]
+enum ByteOp {
+    Swap,
+    SignExtendByte,
+    SignExtendHalf,
+}
+
+impl ByteOp {
+    /// Returns the index of the source bit copied into the `i`-th bit of the
+    /// destination.
+    const fn source_bit(self, i: usize) -> usize {
+        match self {
+            Self::Swap => (3 - i / 8) * 8 + i % 8,
+            Self::SignExtendByte if i > 7 => 7,
+            Self::SignExtendHalf if i > 15 => 15,
+            _ => i,
+        }
+    }
+
+    fn apply(self, val: u32) -> u32 {
+        match self {
+            Self::Swap => val.swap_bytes(),
+            Self::SignExtendByte => val as i8 as i32 as u32,
+            Self::SignExtendHalf => val as i16 as i32 as u32,
+        }
+    }
+}
+
+/// Row data for the [`BytePermutationColumns`] gadget.
+struct BytePermutationRow {
+    pc: u32,
+    fp: u32,
+    timestamp: u32,
+    dst: u16,
+    dst_abs: u32,
+    src: u16,
+    src_abs: u32,
+    src_val: u32,
+}
+
+/// Columns shared by the byte operation tables.
+///
+/// Each destination bit is a copy of a source bit, so the destination value is
+/// a linear combination of the selected source bits. The coefficient of a
+/// source bit has a set bit for each destination bit it is copied into.
+struct BytePermutationColumns<const OPCODE: u16> {
+    state_cols: StateColumns<OPCODE>,
+    dst_abs: Col<B32>,
+    dst_val: Col<B32>,
+    src_abs: Col<B32>,
+    src_val_unpacked: Col<B1, 32>,
+    /// Selected source bits, for each source bit appearing in the destination.
+    src_bits: Vec<(usize, Col<B1>)>,
+    op: ByteOp,
+}
+
+impl<const OPCODE: u16> BytePermutationColumns<OPCODE> {
+    fn new(table: &mut TableBuilder, channels: &Channels, op: ByteOp) -> Self {
+        let state_cols = StateColumns::new(
+            table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        // Source value columns
+        let src_val_unpacked: Col<B1, 32> = table.add_committed("src_val_unpacked");
+        let src_val: Col<B32> = table.add_packed("src_val", src_val_unpacked);
+
+        // Address calculations
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg1));
+
+        let coefficients: [u32; 32] = from_fn(|j| {
+            (0..32)
+                .filter(|&i| op.source_bit(i) == j)
+                .fold(0, |acc, i| acc | (1 << i))
+        });
+        let src_bits = (0..32)
+            .filter(|&j| coefficients[j] != 0)
+            .map(|j| {
+                (
+                    j,
+                    table.add_selected(format!("src_bit_{j}"), src_val_unpacked, j),
+                )
+            })
+            .collect::<Vec<_>>();
+        let dst_val = table.add_computed(
+            "dst_val",
+            src_bits
+                .iter()
+                .map(|&(j, bit)| upcast_col(bit) * B32::new(coefficients[j]))
+                .reduce(|acc, term| acc + term)
+                .expect("every destination bit has a source bit"),
+        );
+
+        // Pull memory access data from VROM channel
+        pull_vrom_channel(table, channels.vrom_channel, [dst_abs, dst_val]);
+        pull_vrom_channel(table, channels.vrom_channel, [src_abs, src_val]);
+
+        Self {
+            state_cols,
+            dst_abs,
+            dst_val,
+            src_abs,
+            src_val_unpacked,
+            src_bits,
+            op,
+        }
+    }
+
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = BytePermutationRow> + Clone,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut dst_val = witness.get_scalars_mut(self.dst_val)?;
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut src_unpacked = witness.get_mut_as(self.src_val_unpacked)?;
+            let mut src_bits = self
+                .src_bits
+                .iter()
+                .map(|&(_, col)| witness.get_mut(col))
+                .collect::<Result<Vec<RefMut<'_, [ProverPackedField]>>, _>>()?;
+
+            for (i, row) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(row.dst_abs);
+                dst_val[i] = B32::new(self.op.apply(row.src_val));
+                src_abs[i] = B32::new(row.src_abs);
+                src_unpacked[i] = row.src_val;
+                for (&(j, _), bit) in self.src_bits.iter().zip(src_bits.iter_mut()) {
+                    set_packed_slice(bit, i, B1::from((row.src_val >> j) & 1 == 1));
+                }
+            }
+        }
+
+        let state_rows = rows.map(|row| StateGadget {
+            pc: row.pc,
+            next_pc: None,
+            fp: row.fp,
+            timestamp: row.timestamp,
+            arg0: row.dst,
+            arg1: row.src,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// Defines a table for a byte operation instruction on top of
+/// [`BytePermutationColumns`].
+macro_rules! define_byte_op_table {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $event:ty,
+        $opcode:path,
+        $table_name:literal,
+        $op:ident
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            id: TableId,
+            byte_cols: BytePermutationColumns<{ $opcode as u16 }>,
+        }
+
+        impl Table for $name {
+            type Event = $event;
+
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+                let mut table = cs.add_table($table_name);
+
+                let byte_cols = BytePermutationColumns::new(&mut table, channels, ByteOp::$op);
+
+                Self {
+                    id: table.id(),
+                    byte_cols,
+                }
+            }
+        }
+
+        impl TableFiller<ProverPackedField> for $name {
+            type Event = $event;
+
+            fn id(&self) -> TableId {
+                self.id
+            }
+
+            fn fill<'a>(
+                &'a self,
+                rows: impl Iterator<Item = &'a Self::Event> + Clone,
+                witness: &'a mut TableWitnessSegment<ProverPackedField>,
+            ) -> anyhow::Result<()> {
+                let byte_rows = rows.map(|ev| BytePermutationRow {
+                    pc: ev.pc.val(),
+                    fp: *ev.fp,
+                    timestamp: ev.timestamp,
+                    dst: ev.dst,
+                    dst_abs: ev.fp.addr(ev.dst),
+                    src: ev.src,
+                    src_abs: ev.fp.addr(ev.src),
+                    src_val: ev.src_val,
+                });
+                self.byte_cols.populate(witness, byte_rows)
+            }
+        }
+    };
+}
+
+define_byte_op_table!(
+    /// Table for BSWAP.
+    ///
+    /// Reverses the byte order of the source value.
+    BswapTable,
+    BswapEvent,
+    Opcode::Bswap,
+    "bswap",
+    Swap
+);
+
+define_byte_op_table!(
+    /// Table for SEXT.B.
+    ///
+    /// Sign-extends the lowest byte of the source value.
+    SextbTable,
+    SextbEvent,
+    Opcode::Sextb,
+    "sextb",
+    SignExtendByte
+);
+
+define_byte_op_table!(
+    /// Table for SEXT.H.
+    ///
+    /// Sign-extends the lowest half-word of the source value.
+    SexthTable,
+    SexthEvent,
+    Opcode::Sexth,
+    "sexth",
+    SignExtendHalf
+);
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+    use proptest::prop_oneof;
+
+    use super::ByteOp;
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_byte_ops_with_value(val: u32) -> Result<()> {
+        let asm_code = "#[framesize(0x10)]\n\
+            _start:\n\
+            BSWAP @3, @2\n\
+            SEXT.B @4, @2\n\
+            SEXT.H @5, @2\n\
+            BSWAP @6, @3\n\
+            RET\n"
+            .to_string();
+
+        let init_values = vec![0, 0, val];
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, Some(init_values), None, isa)?;
+        trace.validate()?;
+
+        assert_eq!(trace.bswap_events().len(), 2);
+        assert_eq!(trace.sextb_events().len(), 1);
+        assert_eq!(trace.sexth_events().len(), 1);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    #[test]
+    fn test_source_bits() {
+        for op in [ByteOp::Swap, ByteOp::SignExtendByte, ByteOp::SignExtendHalf] {
+            for val in [0, u32::MAX, 0x0000_0080, 0x1234_8081, 0xfedc_7f01] {
+                let permuted =
+                    (0..32).fold(0, |acc, i| acc | (((val >> op.source_bit(i)) & 1) << i));
+                assert_eq!(permuted, op.apply(val));
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_byte_ops(
+            val in prop_oneof![
+                Just(0x0000_0080u32),           // Negative low byte
+                Just(0x0000_8000u32),           // Negative low half-word
+                Just(0x1234_5678u32),           // Distinct bytes
+                any::<u32>()                    // Random values
+            ]
+        ) {
+            prop_assert!(test_byte_ops_with_value(val).is_ok());
+        }
+    }
+}
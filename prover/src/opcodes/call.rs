@@ -3,7 +3,7 @@
 use binius_m3::builder::{
     upcast_col, upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32,
 };
-use petravm_asm::{opcodes::Opcode, CalliEvent, CallvEvent, TailiEvent, TailvEvent};
+use petravm_asm::{opcodes::Opcode, CalliEvent, CallvEvent, FieldPc, TailiEvent, TailvEvent};
 
 use crate::gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget};
 use crate::table::Table;
@@ -51,6 +51,7 @@ impl Table for TailiTable {
             StateColumnsOptions {
                 next_pc: NextPc::Immediate, // Jump directly to target address
                 next_fp: Some(next_fp_val), // Update frame pointer
+                next_timestamp: None,
             },
         );
 
@@ -151,6 +152,7 @@ impl TableFiller<ProverPackedField> for TailiTable {
             pc: event.pc.val(),
             next_pc: Some(event.target), // Jump to target address
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.target as u16,         // target_low (lower 16 bits)
             arg1: (event.target >> 16) as u16, // target_high (upper 16 bits)
             arg2: event.next_fp,               // next_fp address
@@ -209,6 +211,7 @@ impl Table for TailvTable {
             StateColumnsOptions {
                 next_pc: NextPc::Target(target_val), // Jump to target address from VROM
                 next_fp: Some(next_fp_val),          // Update frame pointer
+                next_timestamp: None,
             },
         );
 
@@ -250,6 +253,9 @@ impl Table for TailvTable {
             [next_fp_plus_1, old_fp_val],
         );
 
+        // The target must be a function entry point of the program
+        table.pull(channels.call_target_channel, [target_val]);
+
         Self {
             id: table.id(),
             state_cols,
@@ -307,6 +313,7 @@ impl TableFiller<ProverPackedField> for TailvTable {
             pc: event.pc.val(),
             next_pc: Some(event.target), // Jump to target address
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.offset,  // offset for reading target
             arg1: event.next_fp, // next_fp address
             arg2: 0,             // unused
@@ -354,6 +361,7 @@ impl Table for CalliTable {
             StateColumnsOptions {
                 next_pc: NextPc::Immediate, // Jump directly to target address
                 next_fp: Some(next_fp_val), // Update frame pointer
+                next_timestamp: None,
             },
         );
 
@@ -429,7 +437,7 @@ impl TableFiller<ProverPackedField> for CalliTable {
                 next_fp_val[i] = B32::new(event.next_fp_val);
                 next_fp_abs_addr[i] = B32::new(event.fp.addr(event.next_fp));
                 // Calculate next PC as return address
-                next_pc_val[i] = event.pc * G;
+                next_pc_val[i] = FieldPc::from(event.pc).next().field();
                 next_fp_slot_1[i] = B32::new(event.next_fp_val + 1);
             }
         }
@@ -439,6 +447,7 @@ impl TableFiller<ProverPackedField> for CalliTable {
             pc: event.pc.val(),
             next_pc: Some(event.target), // Jump to target address
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.target as u16,         // target_low (lower 16 bits)
             arg1: (event.target >> 16) as u16, // target_high (upper 16 bits)
             arg2: event.next_fp,               // next_fp address
@@ -491,6 +500,7 @@ impl Table for CallvTable {
             StateColumnsOptions {
                 next_pc: NextPc::Target(target_val), // Jump to target address from VROM
                 next_fp: Some(next_fp_val),          // Update frame pointer
+                next_timestamp: None,
             },
         );
 
@@ -536,6 +546,9 @@ impl Table for CallvTable {
         // Verify current frame pointer is stored at slot 1 of new frame
         pull_vrom_channel(&mut table, channels.vrom_channel, [next_fp_slot_1, cur_fp]);
 
+        // The target must be a function entry point of the program
+        table.pull(channels.call_target_channel, [target_val]);
+
         Self {
             id: table.id(),
             state_cols,
@@ -577,7 +590,7 @@ impl TableFiller<ProverPackedField> for CallvTable {
                 next_fp_abs_addr[i] = B32::new(event.fp.addr(event.next_fp));
                 offset_abs_addr[i] = B32::new(event.fp.addr(event.offset));
                 target_val[i] = B32::new(event.target);
-                next_pc_val[i] = event.pc * G;
+                next_pc_val[i] = FieldPc::from(event.pc).next().field();
                 next_fp_slot_1[i] = B32::new(event.next_fp_val + 1);
             }
         }
@@ -587,6 +600,7 @@ impl TableFiller<ProverPackedField> for CallvTable {
             pc: event.pc.val(),
             next_pc: Some(event.target), // Jump to target address
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.offset,  // offset for reading target
             arg1: event.next_fp, // next_fp address
             arg2: 0,             // unused
@@ -685,6 +699,29 @@ mod tests {
         assert_eq!(trace.calli_events().len(), 1);
         assert_eq!(trace.callv_events().len(), 1);
         assert_eq!(trace.ret_events().len(), 3);
+        // Both functions are valid targets, and the CALLV lands on `loop`.
+        assert_eq!(trace.call_targets.len(), 2);
+        assert!(trace.call_targets.contains(&(G.pow(5).val(), 1)));
         Prover::new(Box::new(GenericISA)).validate_witness(&trace)
     }
+
+    #[test]
+    fn test_callv_undeclared_target() {
+        // Indirect calls can't land in the middle of a function.
+        let asm_code = "#[framesize(0x10)]
+            _start:
+                ALLOCI! @4, #16
+                LDI.W @3, #pc(inner)
+                CALLV @3, @4
+                RET
+            #[framesize(0x10)]
+            f:
+                LDI.W @2, #1
+            inner:
+                RET
+            "
+        .to_string();
+        let err = generate_trace(asm_code, None, None, Box::new(GenericISA)).unwrap_err();
+        assert!(err.to_string().contains("not a function entry point"));
+    }
 }
@@ -0,0 +1,216 @@
+use binius_field::{packed::set_packed_slice, Field};
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32, B8,
+};
+use petravm_asm::{CmovEvent, Opcode};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::{pack_b8_into_b16, pull_vrom_channel, setup_mux_constraint},
+};
+
+const CMOV_OPCODE: u16 = Opcode::Cmov as u16;
+
+/// CMOV table.
+///
+/// This table handles the CMOV instruction, which selects the first source
+/// value if the condition is not zero, and the second one otherwise.
+pub struct CmovTable {
+    id: TableId,
+    state_cols: StateColumns<CMOV_OPCODE>,
+    dst_abs: Col<B32>, // Virtual
+    dst_val: Col<B1, 32>,
+    cond_abs: Col<B32>, // Virtual
+    cond_val: Col<B32>,
+    // cond_inv is the precomputed inverse of cond_val, or zero.
+    cond_inv: Col<B32>,
+    cond_nonzero: Col<B1>,
+    src1: Col<B8>,
+    src1_abs: Col<B32>, // Virtual
+    src1_val: Col<B1, 32>,
+    src2: Col<B8>,
+    src2_abs: Col<B32>, // Virtual
+    src2_val: Col<B1, 32>,
+}
+
+impl Table for CmovTable {
+    type Event = CmovEvent;
+
+    fn name(&self) -> &'static str {
+        "CmovTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("cmov");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            vrom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // Both source slots are packed in the third argument.
+        let src1 = table.add_committed("src1");
+        let src2 = table.add_committed("src2");
+        table.assert_zero("srcs", state_cols.arg2 - pack_b8_into_b16(src1, src2));
+
+        // `cond_nonzero` is set if and only if the condition is not zero.
+        let cond_val = table.add_committed("cond_val");
+        let cond_inv = table.add_committed("cond_inv");
+        let cond_nonzero = table.add_committed("cond_nonzero");
+        table.assert_zero("cond_inv", cond_val * cond_inv - upcast_col(cond_nonzero));
+        table.assert_zero(
+            "cond_nonzero",
+            cond_val * (upcast_col(cond_nonzero) - B32::ONE),
+        );
+
+        let src1_val = table.add_committed("src1_val");
+        let src2_val = table.add_committed("src2_val");
+        let dst_val = table.add_committed("dst_val");
+        setup_mux_constraint(&mut table, &dst_val, &src1_val, &src2_val, &cond_nonzero);
+
+        // Read the condition and both sources, and write the destination.
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let cond_abs = table.add_computed("cond_abs", state_cols.fp + upcast_col(state_cols.arg1));
+        let src1_abs = table.add_computed("src1_abs", state_cols.fp + upcast_col(src1));
+        let src2_abs = table.add_computed("src2_abs", state_cols.fp + upcast_col(src2));
+
+        let dst_val_packed = table.add_packed("dst_val_packed", dst_val);
+        let src1_val_packed = table.add_packed("src1_val_packed", src1_val);
+        let src2_val_packed = table.add_packed("src2_val_packed", src2_val);
+
+        pull_vrom_channel(&mut table, vrom_channel, [cond_abs, cond_val]);
+        pull_vrom_channel(&mut table, vrom_channel, [src1_abs, src1_val_packed]);
+        pull_vrom_channel(&mut table, vrom_channel, [src2_abs, src2_val_packed]);
+        pull_vrom_channel(&mut table, vrom_channel, [dst_abs, dst_val_packed]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            dst_val,
+            cond_abs,
+            cond_val,
+            cond_inv,
+            cond_nonzero,
+            src1,
+            src1_abs,
+            src1_val,
+            src2,
+            src2_abs,
+            src2_val,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for CmovTable {
+    type Event = CmovEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut dst_val = witness.get_mut_as(self.dst_val)?;
+            let mut cond_abs = witness.get_scalars_mut(self.cond_abs)?;
+            let mut cond_val = witness.get_scalars_mut(self.cond_val)?;
+            let mut cond_inv = witness.get_scalars_mut(self.cond_inv)?;
+            let mut cond_nonzero = witness.get_mut(self.cond_nonzero)?;
+            let mut src1 = witness.get_scalars_mut(self.src1)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src1_val = witness.get_mut_as(self.src1_val)?;
+            let mut src2 = witness.get_scalars_mut(self.src2)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+            let mut src2_val = witness.get_mut_as(self.src2_val)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                dst_val[i] = event.dst_val;
+                cond_abs[i] = B32::new(event.fp.addr(event.cond));
+                cond_val[i] = B32::new(event.cond_val);
+                cond_inv[i] = cond_val[i].invert_or_zero();
+                set_packed_slice(&mut cond_nonzero, i, B1::from(event.cond_val != 0));
+                src1[i] = B8::new(event.src1 as u8);
+                src1_abs[i] = B32::new(event.fp.addr(event.src1));
+                src1_val[i] = event.src1_val;
+                src2[i] = B8::new(event.src2 as u8);
+                src2_abs[i] = B32::new(event.fp.addr(event.src2));
+                src2_val[i] = event.src2_val;
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.cond,
+            arg2: event.src1 | (event.src2 << 8),
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_cmov_with_values(cond: u32, src1_val: u32, src2_val: u32) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+                _start:
+                    LDI.W @2, #{cond}\n\
+                    LDI.W @3, #0\n\
+                    LDI.W @4, #{src1_val}\n\
+                    LDI.W @5, #{src2_val}\n\
+                    CMOV @6, @2, @4, @5\n\
+                    CMOV @7, @3, @4, @5\n\
+                    RET\n",
+        );
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.cmov_events().len(), 2);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_cmov(
+            cond in any::<u32>(),
+            src1_val in any::<u32>(),
+            src2_val in any::<u32>(),
+        ) {
+            prop_assert!(test_cmov_with_values(cond, src1_val, src2_val).is_ok());
+        }
+    }
+}
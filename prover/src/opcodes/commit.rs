@@ -0,0 +1,263 @@
+//! COMMIT table implementation for the PetraVM M3 circuit.
+
+use binius_field::Field;
+use binius_m3::{
+    builder::{
+        upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+    },
+    gadgets::{
+        add::{U32Add, U32AddFlags},
+        sub::{U32Sub, U32SubFlags},
+    },
+};
+use petravm_asm::{CommitEvent, Opcode};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    model::ram_channel_timestamp,
+    opcodes::G,
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// Returns the index of the `n`-th public output in the output and commit
+/// channels, i.e. `G^n`.
+pub fn output_index(n: usize) -> B32 {
+    G.pow([n as u64])
+}
+
+/// COMMIT table.
+///
+/// This table handles the COMMIT instruction, which appends the value of a
+/// slot to the public outputs. Each row pushes the value, along with its
+/// index among the outputs, to the output channel, balanced by the statement
+/// boundaries pulling every output.
+///
+/// The indices follow the execution order: like a RAM access, a COMMIT at
+/// system timestamp `t` moves it to `t + 1`, and the rows are chained on the
+/// commit channel, each one pulling the index and timestamp left by the
+/// previous COMMIT and pushing the next index along with its own timestamp.
+/// Requiring the timestamps to strictly increase along the chain, which the
+/// statement boundaries open at index 0 and close at the number of outputs,
+/// forbids any reordering of the outputs.
+pub struct CommitTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Commit as u16 }>,
+    timestamp: Col<B1, 32>,
+    one: Col<B1, 32>,
+    incr_timestamp: U32Add,
+    prev_timestamp: Col<B1, 32>,
+    timestamp_op: U32Sub,
+    src_abs: Col<B32>, // Virtual
+    val: Col<B32>,
+    index: Col<B32>,
+    next_index: Col<B32>, // Virtual
+}
+
+impl Table for CommitTable {
+    type Event = CommitEvent;
+
+    fn name(&self) -> &'static str {
+        "CommitTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("commit");
+
+        // The COMMIT moves the system timestamp from t to t + 1, its own
+        // timestamp in the commit channel.
+        let timestamp = table.add_committed("timestamp_unpacked");
+        let timestamp_packed = table.add_packed("timestamp_packed", timestamp);
+        let mut one_bits = [B1::ZERO; 32];
+        one_bits[0] = B1::ONE;
+        let one = table.add_constant("one", one_bits);
+        let incr_timestamp = U32Add::new(&mut table, timestamp, one, U32AddFlags::default());
+        let commit_timestamp = table.add_packed("commit_timestamp", incr_timestamp.zout);
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: Some(commit_timestamp),
+            },
+        );
+        table.assert_zero("timestamp", state_cols.timestamp - timestamp_packed);
+
+        // prev_timestamp < commit_timestamp
+        let prev_timestamp = table.add_committed("prev_timestamp");
+        let timestamp_op = U32Sub::new(
+            &mut table,
+            prev_timestamp,
+            incr_timestamp.zout,
+            U32SubFlags {
+                borrow_in_bit: None,
+                expose_final_borrow: true,
+                commit_zout: false,
+            },
+        );
+        let final_borrow = timestamp_op
+            .final_borrow
+            .expect("Flag `expose_final_borrow` was set to `true`");
+        table.assert_zero("prev_timestamp_lt_timestamp", final_borrow - B1::ONE);
+        let prev_timestamp_packed = table.add_packed("prev_timestamp_packed", prev_timestamp);
+
+        let val = table.add_committed("val");
+        let index = table.add_committed("index");
+        let next_index = table.add_computed("next_index", index * G);
+
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg0));
+
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs, val]);
+        table.pull(channels.commit_channel, [index, prev_timestamp_packed]);
+        table.push(channels.commit_channel, [next_index, commit_timestamp]);
+        table.push(channels.output_channel, [index, val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            timestamp,
+            one,
+            incr_timestamp,
+            prev_timestamp,
+            timestamp_op,
+            src_abs,
+            val,
+            index,
+            next_index,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for CommitTable {
+    type Event = CommitEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut timestamp = witness.get_mut_as(self.timestamp)?;
+            let mut one = witness.get_mut_as(self.one)?;
+            let mut prev_timestamp = witness.get_mut_as(self.prev_timestamp)?;
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut val = witness.get_scalars_mut(self.val)?;
+            let mut index = witness.get_scalars_mut(self.index)?;
+            let mut next_index = witness.get_scalars_mut(self.next_index)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                timestamp[i] = event.timestamp;
+                one[i] = 1u32;
+                prev_timestamp[i] = ram_channel_timestamp(event.prev_timestamp);
+                src_abs[i] = B32::new(event.fp.addr(event.src));
+                val[i] = B32::new(event.val);
+                index[i] = output_index(event.index as usize);
+                next_index[i] = index[i] * G;
+            }
+        }
+
+        self.incr_timestamp.populate(witness)?;
+        self.timestamp_op.populate(witness)?;
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.src,
+            arg1: 0,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::{commit_outputs, isa::GenericISA};
+
+    use crate::{
+        circuit::Circuit, model::ram_channel_timestamp, prover::Prover, test_utils::generate_trace,
+    };
+
+    #[test]
+    fn test_commit() -> Result<()> {
+        let asm_code = "#[framesize(0x10)]\n\
+            _start:\n\
+            LDI.W @3, #7\n\
+            COMMIT @2\n\
+            COMMIT @3\n\
+            COMMIT @2\n\
+            RET\n"
+            .to_string();
+
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, Some(vec![0, 0, 42]), None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.commit_events().len(), 3);
+        assert_eq!(trace.outputs(), [42, 7, 42]);
+        assert_eq!(trace.output_commitment(), commit_outputs(&[42, 7, 42]));
+
+        let prover = Prover::new(Box::new(GenericISA));
+        prover.validate_witness(&trace)?;
+
+        // The statement binds the proof to the committed outputs.
+        let statement = Circuit::new(Box::new(GenericISA)).create_statement(&trace)?;
+        prover.check_outputs(&statement.boundaries, &[42, 7, 42])?;
+        assert!(prover
+            .check_outputs(&statement.boundaries, &[42, 42, 7])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_swapped_outputs() -> Result<()> {
+        let asm_code = "#[framesize(0x10)]\n\
+            _start:\n\
+            LDI.W @3, #7\n\
+            COMMIT @2\n\
+            COMMIT @3\n\
+            RET\n"
+            .to_string();
+
+        let isa = Box::new(GenericISA);
+        let mut trace = generate_trace(asm_code, Some(vec![0, 0, 42]), None, isa)?;
+        assert_eq!(trace.outputs(), [42, 7]);
+
+        let circuit = Circuit::new(Box::new(GenericISA));
+        let prover = Prover::new(Box::new(GenericISA));
+        let statement = circuit.instance.create_statement(&trace)?;
+        prover.validate_witness_with_boundaries(&trace, &statement.boundaries)?;
+
+        // A dishonest prover swaps the indices of the two COMMITs, so that the
+        // output rows match the outputs [7, 42].
+        let commit = &mut trace.trace.commit;
+        (commit[0].index, commit[1].index) = (commit[1].index, commit[0].index);
+
+        let channels = &circuit.instance.channels;
+        let last_timestamp = ram_channel_timestamp(Some(commit[1].timestamp));
+        let mut boundaries = statement.boundaries;
+        boundaries.retain(|boundary| {
+            boundary.channel_id != channels.output_channel
+                && boundary.channel_id != channels.commit_channel
+        });
+        boundaries.extend(circuit.instance.output_boundaries(&[7, 42], last_timestamp));
+
+        // The commit channel, chaining the COMMITs in execution order, rejects
+        // the reordered indices.
+        assert!(prover
+            .validate_witness_with_boundaries(&trace, &boundaries)
+            .is_err());
+        Ok(())
+    }
+}
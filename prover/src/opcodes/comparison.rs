@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use std::{array::from_fn, ops::Deref};
 
 use binius_field::{packed::set_packed_slice, Field, PackedField};
 use binius_m3::{
@@ -8,14 +8,17 @@ use binius_m3::{
     gadgets::sub::{U32Sub, U32SubFlags},
 };
 use petravm_asm::{
-    opcodes::Opcode, SleEvent, SleiEvent, SleiuEvent, SleuEvent, SltEvent, SltiEvent, SltiuEvent,
-    SltuEvent,
+    opcodes::Opcode, SleEvent, SleiEvent, SleiuEvent, SleuEvent, Slt64Event, SltEvent, SltiEvent,
+    SltiuEvent, SltuEvent,
 };
 
-use super::integer_ops::{setup_sign_extended_immediate, SignExtendedImmediateOutput};
+use super::integer_ops::{setup_sign_extended_immediate, u64_limbs, SignExtendedImmediateOutput};
 use crate::{
     channels::Channels,
-    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    gadgets::{
+        bigint::LimbLtColumns,
+        state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    },
     table::Table,
     types::ProverPackedField,
     utils::pull_vrom_channel,
@@ -29,6 +32,7 @@ const SLT_OPCODE: u16 = Opcode::Slt as u16;
 const SLTI_OPCODE: u16 = Opcode::Slti as u16;
 const SLE_OPCODE: u16 = Opcode::Sle as u16;
 const SLEI_OPCODE: u16 = Opcode::Slei as u16;
+const SLT64_OPCODE: u16 = Opcode::Slt64 as u16;
 
 /// SLTU table.
 ///
@@ -68,6 +72,7 @@ impl Table for SltuTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -154,6 +159,7 @@ impl TableFiller<ProverPackedField> for SltuTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -201,6 +207,7 @@ impl Table for SltiuTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -274,6 +281,7 @@ impl TableFiller<ProverPackedField> for SltiuTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -322,6 +330,7 @@ impl Table for SleuTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -416,6 +425,7 @@ impl TableFiller<ProverPackedField> for SleuTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -464,6 +474,7 @@ impl Table for SleiuTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -545,6 +556,7 @@ impl TableFiller<ProverPackedField> for SleiuTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -595,6 +607,7 @@ impl Table for SltTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -720,6 +733,7 @@ impl TableFiller<ProverPackedField> for SltTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp.deref(),
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -729,6 +743,181 @@ impl TableFiller<ProverPackedField> for SltTable {
     }
 }
 
+/// SLT.64 table.
+///
+/// This table handles the SLT.64 instruction, which performs signed integer
+/// comparison (set if less than) between two 64-bit elements, each stored in
+/// a pair of slots.
+pub struct Slt64Table {
+    id: TableId,
+    state_cols: StateColumns<SLT64_OPCODE>,
+    dst_abs: Col<B32>,
+    src1_abs: [Col<B32>; 2],
+    src1_val: [Col<B1, 32>; 2],
+    src1_sign: Col<B1>,
+    src2_abs: [Col<B32>; 2],
+    src2_val: [Col<B1, 32>; 2],
+    src2_sign: Col<B1>,
+    dst_bit: Col<B1>,
+    lt: LimbLtColumns<2>,
+}
+
+impl Table for Slt64Table {
+    type Event = Slt64Event;
+
+    fn name(&self) -> &'static str {
+        "Slt64Table"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("slt64");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // 64-bit values are aligned, so the address of the high limb is the
+        // address of the low limb with its lowest bit set.
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src1_abs = [
+            src1_abs,
+            table.add_computed("src1_plus_1", src1_abs + B32::ONE),
+        ];
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+        let src2_abs = [
+            src2_abs,
+            table.add_computed("src2_plus_1", src2_abs + B32::ONE),
+        ];
+
+        let src1_val: [Col<B1, 32>; 2] = from_fn(|i| table.add_committed(format!("src1_val_{i}")));
+        let src2_val: [Col<B1, 32>; 2] = from_fn(|i| table.add_committed(format!("src2_val_{i}")));
+
+        // Get the sign bits of src1 and src2, in their high limbs
+        let src1_sign = table.add_selected("src1_sign", src1_val[1], 31);
+        let src2_sign = table.add_selected("src2_sign", src2_val[1], 31);
+
+        // `lt` is set exactly when src1_val < src2_val as unsigned integers
+        let lt = LimbLtColumns::new(&mut table, "lt", src1_val, src2_val);
+
+        // As for SLT, the unsigned comparison is only overridden when the signs
+        // differ, in which case src1_val is less than src2_val if and only if it
+        // is negative.
+        let dst_bit = table.add_committed("dst bit");
+        table.assert_zero(
+            "check dst_bit",
+            dst_bit - (src1_sign + src2_sign) * (src1_sign + lt.lt) - lt.lt,
+        );
+        let dst_val = upcast_col(dst_bit);
+
+        // Read src1 and src2
+        for i in 0..2 {
+            let src1_val_packed = table.add_packed(format!("src1_val_packed_{i}"), src1_val[i]);
+            let src2_val_packed = table.add_packed(format!("src2_val_packed_{i}"), src2_val[i]);
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [src1_abs[i], src1_val_packed],
+            );
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [src2_abs[i], src2_val_packed],
+            );
+        }
+
+        // Read dst
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, dst_val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src1_val,
+            src1_sign,
+            src2_abs,
+            src2_val,
+            src2_sign,
+            dst_bit,
+            lt,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for Slt64Table {
+    type Event = Slt64Event;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src1_abs_0 = witness.get_scalars_mut(self.src1_abs[0])?;
+            let mut src1_abs_1 = witness.get_scalars_mut(self.src1_abs[1])?;
+            let mut src1_val_0 = witness.get_mut_as(self.src1_val[0])?;
+            let mut src1_val_1 = witness.get_mut_as(self.src1_val[1])?;
+            let mut src1_sign = witness.get_mut(self.src1_sign)?;
+            let mut src2_abs_0 = witness.get_scalars_mut(self.src2_abs[0])?;
+            let mut src2_abs_1 = witness.get_scalars_mut(self.src2_abs[1])?;
+            let mut src2_val_0 = witness.get_mut_as(self.src2_val[0])?;
+            let mut src2_val_1 = witness.get_mut_as(self.src2_val[1])?;
+            let mut src2_sign = witness.get_mut(self.src2_sign)?;
+            let mut dst_bit = witness.get_mut(self.dst_bit)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                // Set the values of the first operand
+                src1_abs_0[i] = B32::new(event.fp.addr(event.src1));
+                src1_abs_1[i] = B32::new(event.fp.addr(event.src1 + 1));
+                [src1_val_0[i], src1_val_1[i]] = u64_limbs(event.src1_val);
+                set_packed_slice(&mut src1_sign, i, B1::from(event.src1_val >> 63 == 1));
+
+                // Set the values of the second operand
+                src2_abs_0[i] = B32::new(event.fp.addr(event.src2));
+                src2_abs_1[i] = B32::new(event.fp.addr(event.src2 + 1));
+                [src2_val_0[i], src2_val_1[i]] = u64_limbs(event.src2_val);
+                set_packed_slice(&mut src2_sign, i, B1::from(event.src2_val >> 63 == 1));
+
+                // Set the destination
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                set_packed_slice(&mut dst_bit, i, B1::from(event.dst_val == 1));
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp.deref(),
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        let operands = rows.map(|event| (u64_limbs(event.src1_val), u64_limbs(event.src2_val)));
+        self.lt.populate(witness, operands)
+    }
+}
+
 /// SLTI table.
 ///
 /// This table handles the SLTI instruction, which performs signed
@@ -775,6 +964,7 @@ impl Table for SltiTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -904,6 +1094,7 @@ impl TableFiller<ProverPackedField> for SltiTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp.deref(),
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -954,6 +1145,7 @@ impl Table for SleTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -1075,6 +1267,7 @@ impl TableFiller<ProverPackedField> for SleTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp.deref(),
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -1128,6 +1321,7 @@ impl Table for SleiTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -1259,6 +1453,7 @@ impl TableFiller<ProverPackedField> for SleiTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp.deref(),
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -1331,6 +1526,52 @@ mod tests {
         Prover::new(Box::new(GenericISA)).validate_witness(&trace)
     }
 
+    fn test_slt64_with_values(src1_val: u64, src2_val: u64) -> Result<()> {
+        let asm_code = "#[framesize(0x10)]\n\
+            _start:\n\
+            SLT.64 @6, @2, @4\n\
+            SLT.64 @7, @4, @2\n\
+            RET\n"
+            .to_string();
+
+        let init_values = vec![
+            0,
+            0,
+            src1_val as u32,
+            (src1_val >> 32) as u32,
+            src2_val as u32,
+            (src2_val >> 32) as u32,
+        ];
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, Some(init_values), None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.slt64_events().len(), 2);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_slt64_operations(
+            src1_val in prop_oneof![
+                any::<u64>(),
+
+                // Edge cases for signed comparison
+                Just(u64::MAX),
+                Just(1 << 63),
+            ],
+            src2_val in prop_oneof![
+                any::<u64>(),
+
+                // Values with a zero high limb
+                any::<u32>().prop_map(u64::from),
+            ],
+        ) {
+            prop_assert!(test_slt64_with_values(src1_val, src2_val).is_ok());
+        }
+    }
+
     proptest! {
         #![proptest_config(proptest::test_runner::Config::with_cases(20))]
 
@@ -36,6 +36,7 @@ impl Table for FpTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -82,6 +83,7 @@ impl TableFiller<ProverPackedField> for FpTable {
             pc: event.pc.val(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.imm,
             ..Default::default()
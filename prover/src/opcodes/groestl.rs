@@ -95,6 +95,7 @@ impl Table for Groestl256CompressTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
         // Get source values.
@@ -376,6 +377,7 @@ impl TableFiller<ProverPackedField> for Groestl256CompressTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -461,6 +463,7 @@ impl Table for Groestl256OutputTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -795,6 +798,7 @@ impl TableFiller<ProverPackedField> for Groestl256OutputTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -806,7 +810,7 @@ impl TableFiller<ProverPackedField> for Groestl256OutputTable {
 }
 
 /// Returns `N` addresses starting at `base_addr`.
-fn get_all_addresses<const N: usize>(
+pub(crate) fn get_all_addresses<const N: usize>(
     table: &mut TableBuilder,
     base_addr: Expr<B32, 1>,
     label: &str,
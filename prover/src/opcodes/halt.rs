@@ -0,0 +1,139 @@
+//! HALT table implementation for the PetraVM M3 circuit.
+//!
+//! This module contains the HALT table which handles the explicit termination
+//! of the PetraVM execution.
+
+use binius_field::Field;
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32,
+};
+use petravm_asm::{opcodes::Opcode, HaltEvent};
+
+use crate::gadgets::state::{NextPc, StateColumns, StateColumnsOptions};
+use crate::{
+    channels::Channels, gadgets::state::StateGadget, table::Table, types::ProverPackedField,
+};
+
+/// HALT table.
+///
+/// This table handles the HALT instruction, which ends the execution with an
+/// exit code.
+///
+/// Logic:
+/// 1. Load the current PC and FP from the state channel
+/// 2. Get the instruction from PROM channel
+/// 3. Verify this is a HALT instruction
+/// 4. Update the state with PC = 0 and FP = exit_code, matching the final state
+///    boundary of the execution
+pub struct HaltTable {
+    /// Table ID
+    id: TableId,
+    /// State columns
+    state_cols: StateColumns<{ Opcode::Halt as u16 }>,
+    exit_code: Col<B32>,
+}
+
+impl Table for HaltTable {
+    type Event = HaltEvent;
+
+    fn name(&self) -> &'static str {
+        "HaltTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("halt");
+        let next_pc = table.add_constant("next_pc", [B32::ZERO]);
+        let exit_code = table.add_committed("exit_code");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Target(next_pc),
+                next_fp: Some(exit_code),
+                next_timestamp: None,
+            },
+        );
+
+        // The exit code is the immediate argument
+        table.assert_zero("exit_code", exit_code - upcast_col(state_cols.arg0));
+
+        Self {
+            id: table.id(),
+            state_cols,
+            exit_code,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for HaltTable {
+    type Event = HaltEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut exit_code = witness.get_scalars_mut(self.exit_code)?;
+            for (i, event) in rows.clone().enumerate() {
+                exit_code[i] = B32::new(event.exit_code as u32);
+            }
+        }
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: Some(0),
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.exit_code,
+            ..Default::default()
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_halt_with_exit_code(exit_code: u16) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x4)]\n\
+            _start:\n\
+            ALLOCI! @3, #4\n\
+            CALLI callee, @3\n\
+            RET\n\
+            #[framesize(0x4)]\n\
+            callee:\n\
+            HALT #{exit_code}\n"
+        );
+
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.halt_events().len(), 1);
+        assert!(trace.ret_events().is_empty());
+        assert_eq!(trace.exit_code(), exit_code as u32);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_halt(exit_code in prop_oneof![Just(0u16), any::<u16>()]) {
+            prop_assert!(test_halt_with_exit_code(exit_code).is_ok());
+        }
+    }
+}
@@ -1,20 +1,28 @@
+use std::array::from_fn;
+
 use binius_field::{Field, PackedBinaryField32x1b};
 use binius_m3::{
     builder::{
-        upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+        upcast_col, Col, ConstraintSystem, TableBuilder, TableFiller, TableId, TableWitnessSegment,
+        B1, B32,
     },
     gadgets::{
         add::{U32Add, U32AddFlags},
         mul::{MulSS32, MulSU32, MulUU32},
+        sub::{U32Sub, U32SubFlags},
     },
 };
 use petravm_asm::{
-    opcodes::Opcode, AddEvent, AddiEvent, MulEvent, MuliEvent, MulsuEvent, MuluEvent, SubEvent,
+    opcodes::Opcode, Add64Event, AddEvent, AddiEvent, DivEvent, DivuEvent, MulEvent, MuliEvent,
+    MulsuEvent, MuluEvent, RemEvent, RemuEvent, Sub64Event, SubEvent,
 };
 
 use crate::{
     channels::Channels,
-    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    gadgets::{
+        bigint::LimbSumColumns,
+        state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    },
     table::Table,
     types::ProverPackedField,
     utils::{pull_vrom_channel, setup_mux_constraint},
@@ -110,6 +118,7 @@ impl Table for AddTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -188,6 +197,7 @@ impl TableFiller<ProverPackedField> for AddTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -235,6 +245,7 @@ impl Table for SubTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -314,6 +325,7 @@ impl TableFiller<ProverPackedField> for SubTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -323,6 +335,333 @@ impl TableFiller<ProverPackedField> for SubTable {
     }
 }
 
+/// Splits a 64-bit value into its two 32-bit limbs, least significant first.
+pub(crate) const fn u64_limbs(val: u64) -> [u32; 2] {
+    [val as u32, (val >> 32) as u32]
+}
+
+/// ADD.64 table.
+///
+/// This table handles the ADD.64 instruction, which performs integer
+/// addition between two 64-bit elements, each stored in a pair of slots.
+pub struct Add64Table {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Add64 as u16 }>,
+    dst_abs: [Col<B32>; 2],  // Virtual
+    src1_abs: [Col<B32>; 2], // Virtual
+    src1_val: [Col<B1, 32>; 2],
+    src2_abs: [Col<B32>; 2], // Virtual
+    src2_val: [Col<B1, 32>; 2],
+    sum: LimbSumColumns,
+}
+
+impl Table for Add64Table {
+    type Event = Add64Event;
+
+    fn name(&self) -> &'static str {
+        "Add64Table"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("add64");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // 64-bit values are aligned, so the address of the high limb is the
+        // address of the low limb with its lowest bit set.
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let dst_abs = [
+            dst_abs,
+            table.add_computed("dst_plus_1", dst_abs + B32::ONE),
+        ];
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src1_abs = [
+            src1_abs,
+            table.add_computed("src1_plus_1", src1_abs + B32::ONE),
+        ];
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+        let src2_abs = [
+            src2_abs,
+            table.add_computed("src2_plus_1", src2_abs + B32::ONE),
+        ];
+
+        let src1_val = from_fn(|i| table.add_committed(format!("src1_val_{i}")));
+        let src2_val = from_fn(|i| table.add_committed(format!("src2_val_{i}")));
+
+        // Carry out the addition, limb by limb.
+        let sum = LimbSumColumns::new(
+            &mut table,
+            "sum",
+            &[
+                vec![src1_val[0], src2_val[0]],
+                vec![src1_val[1], src2_val[1]],
+            ],
+        );
+
+        // Read src1 and src2, and write dst.
+        for i in 0..2 {
+            let src1_val_packed = table.add_packed(format!("src1_val_packed_{i}"), src1_val[i]);
+            let src2_val_packed = table.add_packed(format!("src2_val_packed_{i}"), src2_val[i]);
+            let dst_val_packed = table.add_packed(format!("dst_val_packed_{i}"), sum.output[i]);
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [src1_abs[i], src1_val_packed],
+            );
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [src2_abs[i], src2_val_packed],
+            );
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [dst_abs[i], dst_val_packed],
+            );
+        }
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src1_val,
+            src2_abs,
+            src2_val,
+            sum,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for Add64Table {
+    type Event = Add64Event;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs_0 = witness.get_scalars_mut(self.dst_abs[0])?;
+            let mut dst_abs_1 = witness.get_scalars_mut(self.dst_abs[1])?;
+            let mut src1_abs_0 = witness.get_scalars_mut(self.src1_abs[0])?;
+            let mut src1_abs_1 = witness.get_scalars_mut(self.src1_abs[1])?;
+            let mut src1_val_0 = witness.get_mut_as(self.src1_val[0])?;
+            let mut src1_val_1 = witness.get_mut_as(self.src1_val[1])?;
+            let mut src2_abs_0 = witness.get_scalars_mut(self.src2_abs[0])?;
+            let mut src2_abs_1 = witness.get_scalars_mut(self.src2_abs[1])?;
+            let mut src2_val_0 = witness.get_mut_as(self.src2_val[0])?;
+            let mut src2_val_1 = witness.get_mut_as(self.src2_val[1])?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs_0[i] = B32::new(event.fp.addr(event.dst));
+                dst_abs_1[i] = B32::new(event.fp.addr(event.dst + 1));
+                src1_abs_0[i] = B32::new(event.fp.addr(event.src1));
+                src1_abs_1[i] = B32::new(event.fp.addr(event.src1 + 1));
+                [src1_val_0[i], src1_val_1[i]] = u64_limbs(event.src1_val);
+                src2_abs_0[i] = B32::new(event.fp.addr(event.src2));
+                src2_abs_1[i] = B32::new(event.fp.addr(event.src2 + 1));
+                [src2_val_0[i], src2_val_1[i]] = u64_limbs(event.src2_val);
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        let terms = rows.map(|event| {
+            let (src1, src2) = (u64_limbs(event.src1_val), u64_limbs(event.src2_val));
+            vec![vec![src1[0], src2[0]], vec![src1[1], src2[1]]]
+        });
+        self.sum.populate(witness, terms)
+    }
+}
+
+/// SUB.64 table.
+///
+/// This table handles the SUB.64 instruction, which performs integer
+/// subtraction between two 64-bit elements, each stored in a pair of slots.
+/// As for SUB, the subtraction is checked as `dst + src2 = src1`.
+pub struct Sub64Table {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Sub64 as u16 }>,
+    dst_abs: [Col<B32>; 2], // Virtual
+    dst_val: [Col<B1, 32>; 2],
+    src1_abs: [Col<B32>; 2], // Virtual
+    src2_abs: [Col<B32>; 2], // Virtual
+    src2_val: [Col<B1, 32>; 2],
+    sum: LimbSumColumns,
+}
+
+impl Table for Sub64Table {
+    type Event = Sub64Event;
+
+    fn name(&self) -> &'static str {
+        "Sub64Table"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("sub64");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // 64-bit values are aligned, so the address of the high limb is the
+        // address of the low limb with its lowest bit set.
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let dst_abs = [
+            dst_abs,
+            table.add_computed("dst_plus_1", dst_abs + B32::ONE),
+        ];
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src1_abs = [
+            src1_abs,
+            table.add_computed("src1_plus_1", src1_abs + B32::ONE),
+        ];
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+        let src2_abs = [
+            src2_abs,
+            table.add_computed("src2_plus_1", src2_abs + B32::ONE),
+        ];
+
+        let dst_val = from_fn(|i| table.add_committed(format!("dst_val_{i}")));
+        let src2_val = from_fn(|i| table.add_committed(format!("src2_val_{i}")));
+
+        // Carry out the subtraction, limb by limb.
+        let sum = LimbSumColumns::new(
+            &mut table,
+            "sum",
+            &[vec![dst_val[0], src2_val[0]], vec![dst_val[1], src2_val[1]]],
+        );
+
+        // Read src1 and src2, and write dst.
+        for i in 0..2 {
+            let src1_val_packed = table.add_packed(format!("src1_val_packed_{i}"), sum.output[i]);
+            let src2_val_packed = table.add_packed(format!("src2_val_packed_{i}"), src2_val[i]);
+            let dst_val_packed = table.add_packed(format!("dst_val_packed_{i}"), dst_val[i]);
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [src1_abs[i], src1_val_packed],
+            );
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [src2_abs[i], src2_val_packed],
+            );
+            pull_vrom_channel(
+                &mut table,
+                channels.vrom_channel,
+                [dst_abs[i], dst_val_packed],
+            );
+        }
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            dst_val,
+            src1_abs,
+            src2_abs,
+            src2_val,
+            sum,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for Sub64Table {
+    type Event = Sub64Event;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs_0 = witness.get_scalars_mut(self.dst_abs[0])?;
+            let mut dst_abs_1 = witness.get_scalars_mut(self.dst_abs[1])?;
+            let mut dst_val_0 = witness.get_mut_as(self.dst_val[0])?;
+            let mut dst_val_1 = witness.get_mut_as(self.dst_val[1])?;
+            let mut src1_abs_0 = witness.get_scalars_mut(self.src1_abs[0])?;
+            let mut src1_abs_1 = witness.get_scalars_mut(self.src1_abs[1])?;
+            let mut src2_abs_0 = witness.get_scalars_mut(self.src2_abs[0])?;
+            let mut src2_abs_1 = witness.get_scalars_mut(self.src2_abs[1])?;
+            let mut src2_val_0 = witness.get_mut_as(self.src2_val[0])?;
+            let mut src2_val_1 = witness.get_mut_as(self.src2_val[1])?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs_0[i] = B32::new(event.fp.addr(event.dst));
+                dst_abs_1[i] = B32::new(event.fp.addr(event.dst + 1));
+                [dst_val_0[i], dst_val_1[i]] = u64_limbs(event.dst_val);
+                src1_abs_0[i] = B32::new(event.fp.addr(event.src1));
+                src1_abs_1[i] = B32::new(event.fp.addr(event.src1 + 1));
+                src2_abs_0[i] = B32::new(event.fp.addr(event.src2));
+                src2_abs_1[i] = B32::new(event.fp.addr(event.src2 + 1));
+                [src2_val_0[i], src2_val_1[i]] = u64_limbs(event.src2_val);
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        let terms = rows.map(|event| {
+            let (dst, src2) = (u64_limbs(event.dst_val), u64_limbs(event.src2_val));
+            vec![vec![dst[0], src2[0]], vec![dst[1], src2[1]]]
+        });
+        self.sum.populate(witness, terms)
+    }
+}
+
 /// ADDI table.
 ///
 /// This table handles the ADDI instruction, which performs signed integer
@@ -364,6 +703,7 @@ impl Table for AddiTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -457,6 +797,7 @@ impl TableFiller<ProverPackedField> for AddiTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -508,6 +849,7 @@ impl Table for MuluTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -589,6 +931,7 @@ impl TableFiller<ProverPackedField> for MuluTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -645,6 +988,7 @@ impl Table for MulTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -727,6 +1071,7 @@ impl TableFiller<ProverPackedField> for MulTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -784,6 +1129,7 @@ impl Table for MuliTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -908,6 +1254,7 @@ impl TableFiller<ProverPackedField> for MuliTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src,
             arg2: event.imm,
@@ -970,6 +1317,7 @@ impl Table for MulsuTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -1052,6 +1400,7 @@ impl TableFiller<ProverPackedField> for MulsuTable {
             pc: event.pc.into(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.src1,
             arg2: event.src2,
@@ -1064,69 +1413,867 @@ impl TableFiller<ProverPackedField> for MulsuTable {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use anyhow::Result;
-    use petravm_asm::isa::GenericISA;
-    use proptest::prelude::*;
+/// Returns the unsigned quotient and remainder of `dividend / divisor`.
+///
+/// A zero divisor yields a quotient of `u32::MAX` and a remainder equal to the
+/// dividend, matching the DIVU and REMU semantics.
+fn unsigned_div_rem(dividend: u32, divisor: u32) -> (u32, u32) {
+    match divisor {
+        0 => (u32::MAX, dividend),
+        _ => (dividend / divisor, dividend % divisor),
+    }
+}
 
-    use crate::model::Trace;
-    use crate::prover::Prover;
-    use crate::test_utils::generate_trace;
+/// Gadget for unsigned 32-bit division.
+///
+/// Given a divisor, it commits to a quotient and a remainder and constrains
+///   - `dividend = quotient * divisor + remainder`, without overflow,
+///   - `remainder < divisor` whenever `divisor != 0`,
+///   - `quotient = u32::MAX` whenever `divisor == 0`.
+///
+/// The dividend is an output of the gadget, so that callers can pull it from
+/// the VROM channel or compare it against another column.
+struct DivRemU32 {
+    divisor: Col<B1, 32>,
+    quotient: Col<B1, 32>,
+    quotient_packed: Col<B32>, // Virtual
+    remainder: Col<B1, 32>,
+    remainder_packed: Col<B32>, // Virtual
+    product_low: Col<B1, 32>,
+    divisor_inv: Col<B32>,
+    divisor_is_zero: Col<B1>,
+    mul_op: MulUU32,
+    add_op: U32Add,
+    sub_op: U32Sub,
+    dividend: Col<B1, 32>,
+    dividend_packed: Col<B32>, // Virtual
+}
 
-    /// Creates an execution trace for a simple program that uses the immediate
-    /// integer operations.
-    fn generate_imm_integer_ops_trace(src_value: u32, imm_value: u16) -> Result<Trace> {
-        let asm_code = format!(
-            "#[framesize(0x10)]\n\
-             _start: 
-                LDI.W @2, #{src_value}\n\
-                ADDI @3, @2, #{imm_value}\n\
-                MULI @4, @2, #{imm_value}\n\
-                RET\n"
+impl DivRemU32 {
+    fn new(table: &mut TableBuilder, divisor: Col<B1, 32>) -> Self {
+        let divisor_packed = table.add_packed("divisor_packed", divisor);
+        let quotient = table.add_committed("quotient");
+        let quotient_packed = table.add_packed("quotient_packed", quotient);
+        let remainder = table.add_committed("remainder");
+        let remainder_packed = table.add_packed("remainder_packed", remainder);
+        let product_low = table.add_committed("product_low");
+        let product_low_packed = table.add_packed("product_low_packed", product_low);
+
+        // The product `quotient * divisor` must fit in 32 bits.
+        let mul_op = MulUU32::new(table);
+        table.assert_zero("mul_xin", mul_op.xin - quotient_packed);
+        table.assert_zero("mul_yin", mul_op.yin - divisor_packed);
+        table.assert_zero("mul_out_low", mul_op.out_low - product_low_packed);
+        table.assert_zero("mul_out_high", mul_op.out_high.into());
+
+        // dividend = quotient * divisor + remainder, without overflow.
+        let add_op = U32Add::new(
+            table,
+            product_low,
+            remainder,
+            U32AddFlags {
+                expose_final_carry: true,
+                ..U32AddFlags::default()
+            },
+        );
+        let final_carry = add_op
+            .final_carry
+            .expect("Flag `expose_final_carry` was set to `true`");
+        table.assert_zero("add_final_carry", final_carry.into());
+        let dividend = add_op.zout;
+        let dividend_packed = table.add_packed("dividend_packed", dividend);
+
+        // `divisor_is_zero` is set if and only if the divisor is zero.
+        let divisor_inv = table.add_committed("divisor_inv");
+        let divisor_is_zero = table.add_committed("divisor_is_zero");
+        table.assert_zero(
+            "divisor_inv",
+            divisor_packed * divisor_inv + upcast_col(divisor_is_zero) - B32::ONE,
+        );
+        table.assert_zero(
+            "divisor_is_zero",
+            divisor_packed * upcast_col(divisor_is_zero),
         );
 
-        let addi_result = src_value.wrapping_add((imm_value as i16 as i32) as u32);
-        let muli_result = ((src_value as i32 as i64) * (imm_value as i16 as i64)) as u64;
+        // remainder < divisor, unless the divisor is zero.
+        let sub_op = U32Sub::new(
+            table,
+            remainder,
+            divisor,
+            U32SubFlags {
+                borrow_in_bit: None,
+                expose_final_borrow: true,
+                commit_zout: false,
+            },
+        );
+        let final_borrow = sub_op
+            .final_borrow
+            .expect("Flag `expose_final_borrow` was set to `true`");
+        table.assert_zero(
+            "remainder_lt_divisor",
+            (final_borrow - B1::ONE) * (divisor_is_zero - B1::ONE),
+        );
 
-        // Add VROM writes from LDI, ADDI, and MULI events
-        let vrom_writes = vec![
-            // LDI event
-            (2, src_value, 3),
-            // Initial values
-            (0, 0, 1),
-            (1, 0, 1),
-            // ADDI event
-            (3, addi_result, 1),
-            // MULI event
-            (4, muli_result as u32, 1),
-            (5, (muli_result >> 32) as u32, 1),
-        ];
+        // quotient = u32::MAX when dividing by zero.
+        table.assert_zero(
+            "zero_divisor_quotient",
+            upcast_col(divisor_is_zero) * (quotient_packed - B32::new(u32::MAX)),
+        );
 
-        let isa = Box::new(GenericISA);
-        generate_trace(asm_code, None, Some(vrom_writes), isa)
+        Self {
+            divisor,
+            quotient,
+            quotient_packed,
+            remainder,
+            remainder_packed,
+            product_low,
+            divisor_inv,
+            divisor_is_zero,
+            mul_op,
+            add_op,
+            sub_op,
+            dividend,
+            dividend_packed,
+        }
     }
 
-    /// Creates an execution trace for a simple program that uses the unsigned
-    /// integer operations.
-    fn generate_vrom_integer_ops_trace_unsigned(src1_value: u32, src2_value: u32) -> Result<Trace> {
-        let asm_code = format!(
-            "#[framesize(0x10)]\n\
-             _start: 
-                LDI.W @2, #{src1_value}\n\
-                LDI.W @3, #{src2_value}\n\
-                SUB @4, @2, @3\n\
-                ADD @5, @2, @3\n\
-                MULU @6, @2, @3\n\
-                RET\n"
+    /// Populates the gadget from `(dividend, divisor)` pairs.
+    ///
+    /// The divisor column must have been populated beforehand.
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = (u32, u32)> + Clone,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut quotient = witness.get_mut_as(self.quotient)?;
+            let mut remainder = witness.get_mut_as(self.remainder)?;
+            let mut product_low = witness.get_mut_as(self.product_low)?;
+            let mut divisor_inv = witness.get_scalars_mut(self.divisor_inv)?;
+            let mut divisor_is_zero: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.divisor_is_zero)?;
+
+            for (i, (dividend, divisor)) in rows.clone().enumerate() {
+                let (q, r) = unsigned_div_rem(dividend, divisor);
+                quotient[i] = q;
+                remainder[i] = r;
+                product_low[i] = q.wrapping_mul(divisor);
+                divisor_inv[i] = B32::new(divisor).invert_or_zero();
+                binius_field::packed::set_packed_slice(
+                    &mut divisor_is_zero,
+                    i,
+                    B1::from(divisor == 0),
+                );
+            }
+        }
+
+        let x_vals = rows
+            .clone()
+            .map(|(dividend, divisor)| B32::new(unsigned_div_rem(dividend, divisor).0));
+        let y_vals = rows.map(|(_, divisor)| B32::new(divisor));
+        self.mul_op.populate_with_inputs(witness, x_vals, y_vals)?;
+        self.add_op.populate(witness)?;
+        self.sub_op.populate(witness)
+    }
+}
+
+/// Gadget for conditional two's complement negation of a 32-bit value.
+///
+/// The output is `(input ^ mask) + negate`, where `mask` is all ones when
+/// `negate` is set and all zeros otherwise.
+struct ConditionalNeg {
+    flipped: Col<B1, 32>,
+    carry: Col<B1, 32>, // Virtual
+    add_op: U32Add,
+    output: Col<B1, 32>, // Virtual
+}
+
+impl ConditionalNeg {
+    fn new(table: &mut TableBuilder, name: &str, input: Col<B1, 32>, negate: Col<B1>) -> Self {
+        let input_packed = table.add_packed(format!("{name}_packed"), input);
+        let flipped = table.add_committed(format!("{name}_flipped"));
+        let flipped_packed = table.add_packed(format!("{name}_flipped_packed"), flipped);
+        table.assert_zero(
+            format!("{name}_flipped"),
+            flipped_packed - (input_packed + upcast_col(negate) * B32::new(u32::MAX)),
         );
 
-        let mulu_result = src1_value as u64 * src2_value as u64;
+        let carry = table.add_zero_pad(format!("{name}_carry"), negate, 0);
+        let add_op = U32Add::new(table, flipped, carry, U32AddFlags::default());
 
-        // Add VROM writes from all events
-        let vrom_writes = vec![
-            // LDI events
-            (2, src1_value, 4),
+        Self {
+            flipped,
+            carry,
+            output: add_op.zout,
+            add_op,
+        }
+    }
+
+    /// Populates the gadget from `(input, negate)` pairs.
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = (u32, bool)>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut flipped = witness.get_mut_as(self.flipped)?;
+            let mut carry = witness.get_mut_as(self.carry)?;
+            for (i, (input, negate)) in rows.enumerate() {
+                flipped[i] = if negate { !input } else { input };
+                carry[i] = negate as u32;
+            }
+        }
+        self.add_op.populate(witness)
+    }
+}
+
+/// Gadget for signed 32-bit division.
+///
+/// The division is carried out on absolute values with [`DivRemU32`], and the
+/// signs are restored afterwards: the quotient is negative when exactly one of
+/// the operands is (and the divisor is non-zero), while the remainder has the
+/// sign of the dividend. This yields -1 on a division by zero and `i32::MIN`
+/// for `i32::MIN / -1`, as the DIV and REM events do.
+struct SignedDivRem {
+    dividend: Col<B1, 32>,
+    divisor: Col<B1, 32>,
+    dividend_neg: Col<B1>, // Virtual
+    divisor_neg: Col<B1>,  // Virtual
+    quotient_neg: Col<B1>,
+    abs_dividend: ConditionalNeg,
+    abs_divisor: ConditionalNeg,
+    div_op: DivRemU32,
+    quotient: ConditionalNeg,
+    remainder: ConditionalNeg,
+}
+
+impl SignedDivRem {
+    fn new(table: &mut TableBuilder) -> Self {
+        let dividend = table.add_committed("dividend");
+        let divisor = table.add_committed("divisor");
+        let dividend_neg = table.add_selected("dividend_neg", dividend, 31);
+        let divisor_neg = table.add_selected("divisor_neg", divisor, 31);
+
+        let abs_dividend = ConditionalNeg::new(table, "abs_dividend", dividend, dividend_neg);
+        let abs_divisor = ConditionalNeg::new(table, "abs_divisor", divisor, divisor_neg);
+
+        let div_op = DivRemU32::new(table, abs_divisor.output);
+        let abs_dividend_packed = table.add_packed("abs_dividend_packed", abs_dividend.output);
+        table.assert_zero("abs_dividend", div_op.dividend_packed - abs_dividend_packed);
+
+        let quotient_neg = table.add_committed("quotient_neg");
+        table.assert_zero(
+            "quotient_neg",
+            quotient_neg - (dividend_neg + divisor_neg) * (div_op.divisor_is_zero + B1::ONE),
+        );
+
+        let quotient = ConditionalNeg::new(table, "quotient", div_op.quotient, quotient_neg);
+        let remainder = ConditionalNeg::new(table, "remainder", div_op.remainder, dividend_neg);
+
+        Self {
+            dividend,
+            divisor,
+            dividend_neg,
+            divisor_neg,
+            quotient_neg,
+            abs_dividend,
+            abs_divisor,
+            div_op,
+            quotient,
+            remainder,
+        }
+    }
+
+    /// Populates the gadget from `(dividend, divisor)` pairs.
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = (u32, u32)> + Clone,
+    ) -> Result<(), anyhow::Error> {
+        let is_neg = |val: u32| (val as i32) < 0;
+        {
+            let mut dividend = witness.get_mut_as(self.dividend)?;
+            let mut divisor = witness.get_mut_as(self.divisor)?;
+            let mut dividend_neg: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.dividend_neg)?;
+            let mut divisor_neg: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.divisor_neg)?;
+            let mut quotient_neg: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.quotient_neg)?;
+
+            for (i, (a, b)) in rows.clone().enumerate() {
+                dividend[i] = a;
+                divisor[i] = b;
+                let q_neg = b != 0 && (is_neg(a) != is_neg(b));
+                binius_field::packed::set_packed_slice(&mut dividend_neg, i, B1::from(is_neg(a)));
+                binius_field::packed::set_packed_slice(&mut divisor_neg, i, B1::from(is_neg(b)));
+                binius_field::packed::set_packed_slice(&mut quotient_neg, i, B1::from(q_neg));
+            }
+        }
+
+        self.abs_dividend
+            .populate(witness, rows.clone().map(|(a, _)| (a, is_neg(a))))?;
+        self.abs_divisor
+            .populate(witness, rows.clone().map(|(_, b)| (b, is_neg(b))))?;
+
+        let abs_rows = rows
+            .clone()
+            .map(|(a, b)| ((a as i32).unsigned_abs(), (b as i32).unsigned_abs()));
+        self.div_op.populate(witness, abs_rows.clone())?;
+
+        let quotient_rows = rows
+            .clone()
+            .zip(abs_rows.clone())
+            .map(|((a, b), (abs_a, abs_b))| {
+                let q_neg = b != 0 && (is_neg(a) != is_neg(b));
+                (unsigned_div_rem(abs_a, abs_b).0, q_neg)
+            });
+        self.quotient.populate(witness, quotient_rows)?;
+
+        let remainder_rows = rows
+            .zip(abs_rows)
+            .map(|((a, _), (abs_a, abs_b))| (unsigned_div_rem(abs_a, abs_b).1, is_neg(a)));
+        self.remainder.populate(witness, remainder_rows)
+    }
+}
+
+/// DIVU table.
+///
+/// This table handles the DIVU instruction, which performs unsigned
+/// integer division between two 32-bit elements.
+pub struct DivuTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Divu as u16 }>,
+    dst_abs: Col<B32>,  // Virtual
+    src1_abs: Col<B32>, // Virtual
+    src2_abs: Col<B32>, // Virtual
+    div_op: DivRemU32,
+}
+
+impl Table for DivuTable {
+    type Event = DivuEvent;
+
+    fn name(&self) -> &'static str {
+        "DivuTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("divu");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+
+        // Carry out the division.
+        let src2_val = table.add_committed("src2_val");
+        let src2_val_packed = table.add_packed("src2_val_packed", src2_val);
+        let div_op = DivRemU32::new(&mut table, src2_val);
+
+        // Read src1
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src1_abs, div_op.dividend_packed],
+        );
+
+        // Read src2
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src2_abs, src2_val_packed],
+        );
+
+        // Write dst
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [dst_abs, div_op.quotient_packed],
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src2_abs,
+            div_op,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for DivuTable {
+    type Event = DivuEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+            let mut src2_val = witness.get_mut_as(self.div_op.divisor)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                src1_abs[i] = B32::new(event.fp.addr(event.src1));
+                src2_abs[i] = B32::new(event.fp.addr(event.src2));
+                src2_val[i] = event.src2_val;
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.div_op
+            .populate(witness, rows.map(|event| (event.src1_val, event.src2_val)))
+    }
+}
+
+/// REMU table.
+///
+/// This table handles the REMU instruction, which computes the unsigned
+/// remainder of the division between two 32-bit elements.
+pub struct RemuTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Remu as u16 }>,
+    dst_abs: Col<B32>,  // Virtual
+    src1_abs: Col<B32>, // Virtual
+    src2_abs: Col<B32>, // Virtual
+    div_op: DivRemU32,
+}
+
+impl Table for RemuTable {
+    type Event = RemuEvent;
+
+    fn name(&self) -> &'static str {
+        "RemuTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("remu");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+
+        // Carry out the division.
+        let src2_val = table.add_committed("src2_val");
+        let src2_val_packed = table.add_packed("src2_val_packed", src2_val);
+        let div_op = DivRemU32::new(&mut table, src2_val);
+
+        // Read src1
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src1_abs, div_op.dividend_packed],
+        );
+
+        // Read src2
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src2_abs, src2_val_packed],
+        );
+
+        // Write dst
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [dst_abs, div_op.remainder_packed],
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src2_abs,
+            div_op,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for RemuTable {
+    type Event = RemuEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+            let mut src2_val = witness.get_mut_as(self.div_op.divisor)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                src1_abs[i] = B32::new(event.fp.addr(event.src1));
+                src2_abs[i] = B32::new(event.fp.addr(event.src2));
+                src2_val[i] = event.src2_val;
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.div_op
+            .populate(witness, rows.map(|event| (event.src1_val, event.src2_val)))
+    }
+}
+
+/// DIV table.
+///
+/// This table handles the DIV instruction, which performs signed integer
+/// division between two 32-bit elements, rounding towards zero.
+pub struct DivTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Div as u16 }>,
+    dst_abs: Col<B32>,  // Virtual
+    src1_abs: Col<B32>, // Virtual
+    src2_abs: Col<B32>, // Virtual
+    div_op: SignedDivRem,
+}
+
+impl Table for DivTable {
+    type Event = DivEvent;
+
+    fn name(&self) -> &'static str {
+        "DivTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("div");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+
+        // Carry out the division.
+        let div_op = SignedDivRem::new(&mut table);
+        let src1_val_packed = table.add_packed("src1_val_packed", div_op.dividend);
+        let src2_val_packed = table.add_packed("src2_val_packed", div_op.divisor);
+        let dst_val_packed = table.add_packed("dst_val_packed", div_op.quotient.output);
+
+        // Read src1
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src1_abs, src1_val_packed],
+        );
+
+        // Read src2
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src2_abs, src2_val_packed],
+        );
+
+        // Write dst
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, dst_val_packed]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src2_abs,
+            div_op,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for DivTable {
+    type Event = DivEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                src1_abs[i] = B32::new(event.fp.addr(event.src1));
+                src2_abs[i] = B32::new(event.fp.addr(event.src2));
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.div_op
+            .populate(witness, rows.map(|event| (event.src1_val, event.src2_val)))
+    }
+}
+
+/// REM table.
+///
+/// This table handles the REM instruction, which computes the signed
+/// remainder of the division between two 32-bit elements.
+pub struct RemTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Rem as u16 }>,
+    dst_abs: Col<B32>,  // Virtual
+    src1_abs: Col<B32>, // Virtual
+    src2_abs: Col<B32>, // Virtual
+    div_op: SignedDivRem,
+}
+
+impl Table for RemTable {
+    type Event = RemEvent;
+
+    fn name(&self) -> &'static str {
+        "RemTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("rem");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        let src1_abs = table.add_computed("src1", state_cols.fp + upcast_col(state_cols.arg1));
+        let src2_abs = table.add_computed("src2", state_cols.fp + upcast_col(state_cols.arg2));
+
+        // Carry out the division.
+        let div_op = SignedDivRem::new(&mut table);
+        let src1_val_packed = table.add_packed("src1_val_packed", div_op.dividend);
+        let src2_val_packed = table.add_packed("src2_val_packed", div_op.divisor);
+        let dst_val_packed = table.add_packed("dst_val_packed", div_op.remainder.output);
+
+        // Read src1
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src1_abs, src1_val_packed],
+        );
+
+        // Read src2
+        pull_vrom_channel(
+            &mut table,
+            channels.vrom_channel,
+            [src2_abs, src2_val_packed],
+        );
+
+        // Write dst
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, dst_val_packed]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src2_abs,
+            div_op,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for RemTable {
+    type Event = RemEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                src1_abs[i] = B32::new(event.fp.addr(event.src1));
+                src2_abs[i] = B32::new(event.fp.addr(event.src2));
+            }
+        }
+        let state_rows = rows.clone().map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.div_op
+            .populate(witness, rows.map(|event| (event.src1_val, event.src2_val)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::model::Trace;
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    /// Creates an execution trace for a simple program that uses the immediate
+    /// integer operations.
+    fn generate_imm_integer_ops_trace(src_value: u32, imm_value: u16) -> Result<Trace> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+             _start: 
+                LDI.W @2, #{src_value}\n\
+                ADDI @3, @2, #{imm_value}\n\
+                MULI @4, @2, #{imm_value}\n\
+                RET\n"
+        );
+
+        let addi_result = src_value.wrapping_add((imm_value as i16 as i32) as u32);
+        let muli_result = ((src_value as i32 as i64) * (imm_value as i16 as i64)) as u64;
+
+        // Add VROM writes from LDI, ADDI, and MULI events
+        let vrom_writes = vec![
+            // LDI event
+            (2, src_value, 3),
+            // Initial values
+            (0, 0, 1),
+            (1, 0, 1),
+            // ADDI event
+            (3, addi_result, 1),
+            // MULI event
+            (4, muli_result as u32, 1),
+            (5, (muli_result >> 32) as u32, 1),
+        ];
+
+        let isa = Box::new(GenericISA);
+        generate_trace(asm_code, None, Some(vrom_writes), isa)
+    }
+
+    /// Creates an execution trace for a simple program that uses the unsigned
+    /// integer operations.
+    fn generate_vrom_integer_ops_trace_unsigned(src1_value: u32, src2_value: u32) -> Result<Trace> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+             _start: 
+                LDI.W @2, #{src1_value}\n\
+                LDI.W @3, #{src2_value}\n\
+                SUB @4, @2, @3\n\
+                ADD @5, @2, @3\n\
+                MULU @6, @2, @3\n\
+                RET\n"
+        );
+
+        let mulu_result = src1_value as u64 * src2_value as u64;
+
+        // Add VROM writes from all events
+        let vrom_writes = vec![
+            // LDI events
+            (2, src1_value, 4),
             (3, src2_value, 4),
             // Initial values
             (0, 0, 1),
@@ -1209,6 +2356,101 @@ mod tests {
         generate_trace(asm_code, None, Some(vrom_writes), isa)
     }
 
+    /// Creates an execution trace for a simple program that uses the division
+    /// and remainder operations.
+    fn generate_div_rem_trace(src1_value: u32, src2_value: u32) -> Result<Trace> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+             _start: 
+                LDI.W @2, #{src1_value}\n\
+                LDI.W @3, #{src2_value}\n\
+                DIV @4, @2, @3\n\
+                DIVU @5, @2, @3\n\
+                REM @6, @2, @3\n\
+                REMU @7, @2, @3\n\
+                RET\n"
+        );
+
+        let (a, b) = (src1_value as i32, src2_value as i32);
+        let div_result = if b == 0 { -1 } else { a.wrapping_div(b) };
+        let rem_result = if b == 0 { a } else { a.wrapping_rem(b) };
+        let divu_result = src1_value.checked_div(src2_value).unwrap_or(u32::MAX);
+        let remu_result = src1_value.checked_rem(src2_value).unwrap_or(src1_value);
+
+        // Add VROM writes from all events
+        let vrom_writes = vec![
+            // LDI events
+            (2, src1_value, 5),
+            (3, src2_value, 5),
+            // Initial values
+            (0, 0, 1),
+            (1, 0, 1),
+            // DIV, DIVU, REM and REMU events
+            (4, div_result as u32, 1),
+            (5, divu_result, 1),
+            (6, rem_result as u32, 1),
+            (7, remu_result, 1),
+        ];
+
+        let isa = Box::new(GenericISA);
+        generate_trace(asm_code, None, Some(vrom_writes), isa)
+    }
+
+    fn test_div_rem_with_values(src1_value: u32, src2_value: u32) -> Result<()> {
+        let trace = generate_div_rem_trace(src1_value, src2_value)?;
+        trace.validate()?;
+        assert_eq!(trace.div_events().len(), 1);
+        assert_eq!(trace.divu_events().len(), 1);
+        assert_eq!(trace.rem_events().len(), 1);
+        assert_eq!(trace.remu_events().len(), 1);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    #[test]
+    fn test_div_rem_edge_cases() -> Result<()> {
+        // Division by zero
+        test_div_rem_with_values(42, 0)?;
+        test_div_rem_with_values(i32::MIN as u32, 0)?;
+        // Signed overflow
+        test_div_rem_with_values(i32::MIN as u32, u32::MAX)?;
+        // Exact division of negative values
+        test_div_rem_with_values(-42i32 as u32, -7i32 as u32)
+    }
+
+    fn test_64bit_integer_ops_with_values(src1_value: u64, src2_value: u64) -> Result<()> {
+        let asm_code = "#[framesize(0x10)]\n\
+            _start:\n\
+            ADD.64 @6, @2, @4\n\
+            SUB.64 @8, @2, @4\n\
+            SUB.64 @10, @4, @2\n\
+            RET\n"
+            .to_string();
+
+        let init_values = vec![
+            0,
+            0,
+            src1_value as u32,
+            (src1_value >> 32) as u32,
+            src2_value as u32,
+            (src2_value >> 32) as u32,
+        ];
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, Some(init_values), None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.add64_events().len(), 1);
+        assert_eq!(trace.sub64_events().len(), 2);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    #[test]
+    fn test_64bit_integer_ops_carries() -> Result<()> {
+        // Carry and borrow across limbs
+        test_64bit_integer_ops_with_values(0xFFFF_FFFF, 1)?;
+        test_64bit_integer_ops_with_values(0x1_0000_0000, 1)?;
+        // Overflow
+        test_64bit_integer_ops_with_values(u64::MAX, u64::MAX)
+    }
+
     fn test_imm_integer_ops_with_values(src_value: u32, imm: u16) -> Result<()> {
         let trace = generate_imm_integer_ops_trace(src_value, imm)?;
         trace.validate()?;
@@ -1239,6 +2481,18 @@ mod tests {
         Prover::new(Box::new(GenericISA)).validate_witness(&trace)
     }
 
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_64bit_integer_ops(
+            src1_value in any::<u64>(),
+            src2_value in any::<u64>(),
+        ) {
+            prop_assert!(test_64bit_integer_ops_with_values(src1_value, src2_value).is_ok());
+        }
+    }
+
     proptest! {
         #![proptest_config(proptest::test_runner::Config::with_cases(20))]
 
@@ -1266,6 +2520,14 @@ mod tests {
             prop_assert!(test_imm_integer_ops_with_values(src_value, imm).is_ok());
         }
 
+        #[test]
+        fn test_div_rem_ops(
+            src1_value in any::<u32>(),
+            src2_value in any::<u32>(),
+        ) {
+            prop_assert!(test_div_rem_with_values(src1_value, src2_value).is_ok());
+        }
+
         #[test]
         fn test_mulsu_op(
             src1_value in any::<i32>(),
@@ -33,6 +33,7 @@ impl Table for JumpiTable {
             StateColumnsOptions {
                 next_pc: NextPc::Immediate,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -59,6 +60,7 @@ impl TableFiller<ProverPackedField> for JumpiTable {
             pc: event.pc.val(),
             next_pc: Some(event.target.val()),
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.target.val() as u16,
             arg1: (event.target.val() >> 16) as u16,
             arg2: 0, // Unused for jumpi
@@ -98,6 +100,7 @@ impl Table for JumpvTable {
             StateColumnsOptions {
                 next_pc: NextPc::Target(target_val),
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -140,6 +143,7 @@ impl TableFiller<ProverPackedField> for JumpvTable {
             pc: event.pc.val(),
             next_pc: Some(event.target),
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.offset,
             arg1: 0, // Unused for jumpv
             arg2: 0, // Unused for jumpv
@@ -50,6 +50,7 @@ impl Table for LdiTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -100,6 +101,7 @@ impl TableFiller<ProverPackedField> for LdiTable {
             pc: event.pc.val(),
             next_pc: None,
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.imm as u16,
             arg2: (event.imm >> 16) as u16,
@@ -0,0 +1,313 @@
+//! Block move tables implementation for the PetraVM M3 circuit.
+//!
+//! The MEMCPY and MEMSET tables only hold one row per instruction. They push
+//! the first word of the moved block to the memory word channel, and pull the
+//! state left after its last word, while the words themselves are moved by the
+//! [`MemWordTable`](crate::gadgets::mem_word_table::MemWordTable).
+
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+};
+use binius_m3::gadgets::add::{U32Add, U32AddFlags};
+use petravm_asm::{MemcpyEvent, MemsetEvent, Opcode};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// MEMCPY table.
+///
+/// This table handles the MEMCPY instruction, which copies `len` words from
+/// the VROM region starting at `FP[src]` to the one starting at `FP[dst]`.
+pub struct MemcpyTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Memcpy as u16 }>,
+    dst_abs: Col<B32>, // Virtual
+    dst_ptr: Col<B1, 32>,
+    src_abs: Col<B32>, // Virtual
+    src_ptr: Col<B1, 32>,
+    len: Col<B1, 32>,
+    src_stride: Col<B32>, // Constant
+    dst_end: U32Add,
+    src_end: U32Add,
+}
+
+impl Table for MemcpyTable {
+    type Event = MemcpyEvent;
+
+    fn name(&self) -> &'static str {
+        "MemcpyTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("memcpy");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        // Read the addresses of both regions
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let dst_ptr = table.add_committed("dst_ptr");
+        let dst_ptr_packed = table.add_packed("dst_ptr_packed", dst_ptr);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, dst_ptr_packed]);
+
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg1));
+        let src_ptr = table.add_committed("src_ptr");
+        let src_ptr_packed = table.add_packed("src_ptr_packed", src_ptr);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs, src_ptr_packed]);
+
+        // The block length is the immediate argument
+        let len = table.add_committed("len");
+        let len_packed = table.add_packed("len_packed", len);
+        table.assert_zero("len", len_packed - upcast_col(state_cols.arg2));
+
+        // Compute the end of both regions
+        let dst_end = U32Add::new(&mut table, dst_ptr, len, U32AddFlags::default());
+        let dst_end_packed = table.add_packed("dst_end_packed", dst_end.zout);
+        let src_end = U32Add::new(&mut table, src_ptr, len, U32AddFlags::default());
+        let src_end_packed = table.add_packed("src_end_packed", src_end.zout);
+
+        let src_stride = table.add_constant("src_stride", [B32::ONE]);
+        let zero = table.add_constant("zero", [B32::ZERO]);
+
+        // Move the words of the block through the memory word table
+        table.push(
+            channels.mem_word_channel,
+            [dst_ptr_packed, src_ptr_packed, src_stride, len_packed],
+        );
+        table.pull(
+            channels.mem_word_channel,
+            [dst_end_packed, src_end_packed, src_stride, zero],
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            dst_ptr,
+            src_abs,
+            src_ptr,
+            len,
+            src_stride,
+            dst_end,
+            src_end,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MemcpyTable {
+    type Event = MemcpyEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut dst_ptr = witness.get_mut_as(self.dst_ptr)?;
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut src_ptr = witness.get_mut_as(self.src_ptr)?;
+            let mut len = witness.get_mut_as(self.len)?;
+            let mut src_stride = witness.get_scalars_mut(self.src_stride)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                dst_ptr[i] = event.dst_ptr;
+                src_abs[i] = B32::new(event.fp.addr(event.src));
+                src_ptr[i] = event.src_ptr;
+                len[i] = event.len as u32;
+                src_stride[i] = B32::ONE;
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src,
+            arg2: event.len,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.dst_end.populate(witness)?;
+        self.src_end.populate(witness)
+    }
+}
+
+/// MEMSET table.
+///
+/// This table handles the MEMSET instruction, which fills `len` words of the
+/// VROM region starting at `FP[dst]` with the value of the slot `src`.
+pub struct MemsetTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Memset as u16 }>,
+    dst_abs: Col<B32>, // Virtual
+    dst_ptr: Col<B1, 32>,
+    src_abs: Col<B32>, // Virtual
+    len: Col<B1, 32>,
+    dst_end: U32Add,
+}
+
+impl Table for MemsetTable {
+    type Event = MemsetEvent;
+
+    fn name(&self) -> &'static str {
+        "MemsetTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("memset");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        // Read the address of the destination region
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let dst_ptr = table.add_committed("dst_ptr");
+        let dst_ptr_packed = table.add_packed("dst_ptr_packed", dst_ptr);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, dst_ptr_packed]);
+
+        // Every word is read from the same source slot
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg1));
+
+        // The block length is the immediate argument
+        let len = table.add_committed("len");
+        let len_packed = table.add_packed("len_packed", len);
+        table.assert_zero("len", len_packed - upcast_col(state_cols.arg2));
+
+        // Compute the end of the destination region
+        let dst_end = U32Add::new(&mut table, dst_ptr, len, U32AddFlags::default());
+        let dst_end_packed = table.add_packed("dst_end_packed", dst_end.zout);
+
+        let zero = table.add_constant("zero", [B32::ZERO]);
+
+        // Move the words of the block through the memory word table
+        table.push(
+            channels.mem_word_channel,
+            [dst_ptr_packed, src_abs, zero, len_packed],
+        );
+        table.pull(
+            channels.mem_word_channel,
+            [dst_end_packed, src_abs, zero, zero],
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            dst_ptr,
+            src_abs,
+            len,
+            dst_end,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MemsetTable {
+    type Event = MemsetEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut dst_ptr = witness.get_mut_as(self.dst_ptr)?;
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut len = witness.get_mut_as(self.len)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                dst_ptr[i] = event.dst_ptr;
+                src_abs[i] = B32::new(event.fp.addr(event.src));
+                len[i] = event.len as u32;
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src,
+            arg2: event.len,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.dst_end.populate(witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_memcpy_memset_with_values(vals: [u32; 3], fill: u32, len: u16) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x20)]\n\
+            _start:\n\
+            LDI.W @2, #{}\n\
+            LDI.W @3, #{}\n\
+            LDI.W @4, #{}\n\
+            LDI.W @5, #{fill}\n\
+            FP @6, #2\n\
+            FP @7, #16\n\
+            FP @8, #24\n\
+            MEMCPY @7, @6, #3\n\
+            MEMSET @8, @5, #{len}\n\
+            MEMCPY @7, @6, #0\n\
+            RET\n",
+            vals[0], vals[1], vals[2],
+        );
+
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.memcpy_events().len(), 2);
+        assert_eq!(trace.memset_events().len(), 1);
+        assert_eq!(trace.mem_word_events().len(), 3 + len as usize);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_memcpy_memset(
+            vals in any::<[u32; 3]>(),
+            fill in any::<u32>(),
+            len in 0u16..8,
+        ) {
+            prop_assert!(test_memcpy_memset_with_values(vals, fill, len).is_ok());
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Merkle path verification table implementation for the PetraVM M3 circuit.
+//!
+//! The MERKLE_VERIFY table only holds one row per instruction. It pushes the
+//! leaf of the verified path to the Merkle channel, and pulls the root left
+//! after its last level, while the levels themselves are hashed by the
+//! [`MerkleNodeTable`](crate::gadgets::merkle_node_table::MerkleNodeTable).
+
+use std::{array::from_fn, iter::once};
+
+use binius_core::oracle::ShiftVariant;
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+};
+use binius_m3::gadgets::add::{U32Add, U32AddFlags};
+use petravm_asm::{MerkleVerifyEvent, Opcode};
+
+use super::groestl::get_all_addresses;
+use crate::{
+    channels::Channels,
+    gadgets::state::{StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+};
+
+/// MERKLE_VERIFY table.
+///
+/// This table handles the MERKLE_VERIFY instruction, which verifies that the
+/// leaf `FP[leaf]` belongs to the Merkle tree of root `FP[root]`, along the
+/// path stored at `FP[path]`.
+pub struct MerkleVerifyTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::MerkleVerify as u16 }>,
+    root_addrs: [Col<B32>; 8], // Virtual
+    root: [Col<B32>; 8],
+    leaf_addrs: [Col<B32>; 8], // Virtual
+    leaf: [Col<B32>; 8],
+    path_abs: Col<B1, 32>,
+    depth_addr: Col<B32>, // Virtual
+    index: Col<B32>,
+    depth: Col<B1, 32>,
+    depth_offset: Col<B1, 32>, // Virtual
+    /// Address 8 words before the last sibling.
+    end: U32Add,
+}
+
+impl Table for MerkleVerifyTable {
+    type Event = MerkleVerifyEvent;
+
+    fn name(&self) -> &'static str {
+        "MerkleVerifyTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("merkle_verify");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        // Read the root and the leaf
+        let root_addrs = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg0),
+            "root_addr",
+        );
+        let root = from_fn(|i| table.add_committed(format!("root_{i}")));
+        let leaf_addrs = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg1),
+            "leaf_addr",
+        );
+        let leaf = from_fn(|i| table.add_committed(format!("leaf_{i}")));
+        for i in 0..8 {
+            table.pull(channels.vrom_channel, [root_addrs[i], root[i]]);
+            table.pull(channels.vrom_channel, [leaf_addrs[i], leaf[i]]);
+        }
+
+        // Read the index of the leaf and the depth of the tree
+        let path_abs = table.add_committed("path_abs");
+        let path_abs_packed = table.add_packed("path_abs_packed", path_abs);
+        table.assert_zero(
+            "path_abs",
+            path_abs_packed - (state_cols.fp + upcast_col(state_cols.arg2)),
+        );
+        let depth_addr = table.add_computed("depth_addr", path_abs_packed + B32::ONE);
+        let index = table.add_committed("index");
+        let depth = table.add_committed("depth");
+        let depth_packed = table.add_packed("depth_packed", depth);
+        table.pull(channels.vrom_channel, [path_abs_packed, index]);
+        table.pull(channels.vrom_channel, [depth_addr, depth_packed]);
+
+        // Each level of the path moves 8 words further
+        let depth_offset =
+            table.add_shifted("depth_offset", depth, 5, 3, ShiftVariant::LogicalLeft);
+        let end = U32Add::new(&mut table, path_abs, depth_offset, U32AddFlags::default());
+        let end_packed = table.add_packed("end_packed", end.zout);
+
+        let zero = table.add_constant("zero", [B32::ZERO]);
+
+        // Hash the levels of the path through the Merkle node table
+        table.push(
+            channels.merkle_channel,
+            once(path_abs_packed).chain(leaf).chain([index]),
+        );
+        table.pull(
+            channels.merkle_channel,
+            once(end_packed).chain(root).chain([zero]),
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+            root_addrs,
+            root,
+            leaf_addrs,
+            leaf,
+            path_abs,
+            depth_addr,
+            index,
+            depth,
+            depth_offset,
+            end,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MerkleVerifyTable {
+    type Event = MerkleVerifyEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut root_addrs = (0..8)
+                .map(|j| witness.get_scalars_mut(self.root_addrs[j]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut root = (0..8)
+                .map(|j| witness.get_scalars_mut(self.root[j]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut leaf_addrs = (0..8)
+                .map(|j| witness.get_scalars_mut(self.leaf_addrs[j]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut leaf = (0..8)
+                .map(|j| witness.get_scalars_mut(self.leaf[j]))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut path_abs = witness.get_mut_as(self.path_abs)?;
+            let mut depth_addr = witness.get_scalars_mut(self.depth_addr)?;
+            let mut index = witness.get_scalars_mut(self.index)?;
+            let mut depth = witness.get_mut_as(self.depth)?;
+            let mut depth_offset = witness.get_mut_as(self.depth_offset)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                let root_addr = event.fp.addr(event.root);
+                let leaf_addr = event.fp.addr(event.leaf);
+                for j in 0..8 {
+                    root_addrs[j][i] = B32::new(root_addr + j as u32);
+                    root[j][i] = B32::new(event.root_val[j]);
+                    leaf_addrs[j][i] = B32::new(leaf_addr + j as u32);
+                    leaf[j][i] = B32::new(event.leaf_val[j]);
+                }
+                path_abs[i] = event.fp.addr(event.path);
+                depth_addr[i] = B32::new(event.fp.addr(event.path) + 1);
+                index[i] = B32::new(event.index);
+                depth[i] = event.depth;
+                depth_offset[i] = event.depth << 3;
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.root,
+            arg1: event.leaf,
+            arg2: event.path,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+        self.end.populate(witness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::{
+        isa::GenericISA,
+        merkle_root,
+        util::{bytes_to_u32, groestl256},
+    };
+
+    use crate::{model::Trace, prover::Prover, test_utils::generate_trace};
+
+    fn digest(data: &[u8]) -> [u32; 8] {
+        bytes_to_u32(&groestl256(data)).try_into().unwrap()
+    }
+
+    /// Returns the trace of two MERKLE_VERIFY of the leaf of index `index`
+    /// along the siblings `path`.
+    fn generate_merkle_verify_trace(index: u32, path: &[[u32; 8]]) -> Result<Trace> {
+        let asm_code = "#[framesize(0x80)]\n\
+            _start:\n\
+            MERKLE_VERIFY @8, @16, @24\n\
+            MERKLE_VERIFY @8, @16, @24\n\
+            RET\n"
+            .to_string();
+        let leaf = digest(b"leaf");
+        let root = merkle_root(leaf, index, path);
+
+        let mut init_values = vec![0; 8];
+        init_values.extend(root);
+        init_values.extend(leaf);
+        init_values.extend([index, path.len() as u32, 0, 0, 0, 0, 0, 0]);
+        init_values.extend(path.iter().flatten());
+        let trace = generate_trace(asm_code, Some(init_values), None, Box::new(GenericISA))?;
+        trace.validate()?;
+        Ok(trace)
+    }
+
+    #[test]
+    fn test_merkle_verify() -> Result<()> {
+        let path = (0..5).map(|i| digest(&[i])).collect::<Vec<_>>();
+        let trace = generate_merkle_verify_trace(0b10110, &path)?;
+        assert_eq!(trace.merkle_verify_events().len(), 2);
+        assert_eq!(trace.merkle_node_events().len(), 10);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    #[test]
+    fn test_merkle_verify_empty_path() -> Result<()> {
+        // A tree of depth 0 is its leaf.
+        let trace = generate_merkle_verify_trace(0, &[])?;
+        assert!(trace.merkle_node_events().is_empty());
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+}
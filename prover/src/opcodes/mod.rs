@@ -5,29 +5,60 @@
 use binius_field::BinaryField;
 use binius_m3::builder::B32;
 
+pub mod assertion;
+pub mod bigint;
 pub mod binary;
+pub mod bit_count;
+pub mod blake3;
 pub mod branch;
+pub mod bytes;
 pub mod call;
+pub mod cmov;
+pub mod commit;
 pub mod comparison;
 pub mod fp;
 pub mod groestl;
+pub mod halt;
 pub mod integer_ops;
 pub mod jump;
 pub mod ldi;
+pub mod memcpy;
+pub mod merkle;
 pub mod mv;
+pub mod nop;
+pub mod ram;
+pub mod range_check;
 pub mod ret;
+pub mod sha256;
 pub mod shift;
 
+pub use assertion::{AssertEqTable, AssertZeroTable};
+pub use bigint::BigMulModTable;
 pub use binary::*;
-pub use branch::{BnzTable, BzTable};
+pub use bit_count::{ClzTable, CtzTable, PopcntTable};
+pub use blake3::Blake3CompressTable;
+pub use branch::{BeqTable, BgeTable, BltTable, BneTable, BnzTable, BzTable};
+pub use bytes::{BswapTable, SextbTable, SexthTable};
 pub use call::{CalliTable, CallvTable, TailiTable, TailvTable};
+pub use cmov::CmovTable;
+pub use commit::{output_index, CommitTable};
 pub use comparison::*;
 pub use fp::FpTable;
 pub use groestl::{Groestl256CompressTable, Groestl256OutputTable};
+pub use halt::HaltTable;
 pub use integer_ops::*;
 pub use jump::{JumpiTable, JumpvTable};
 pub use ldi::LdiTable;
-pub use mv::{MvihTable, MvvlTable, MvvwTable};
+pub use memcpy::{MemcpyTable, MemsetTable};
+pub use merkle::MerkleVerifyTable;
+pub use mv::{MvibTable, MvihTable, MvvhTable, MvvlTable, MvvwTable};
+pub use nop::NopTable;
+pub use ram::{LbTable, LbuTable, LhTable, LhuTable, LwTable, SbTable, ShTable, SwTable};
+pub use range_check::RangeCheckTable;
 pub use ret::RetTable;
-pub use shift::{SllTable, SlliTable, SraTable, SraiTable, SrlTable, SrliTable};
+pub use sha256::Sha256CompressTable;
+pub use shift::{
+    RolTable, RoliTable, RorTable, RoriTable, SllTable, SlliTable, SraTable, SraiTable, SrlTable,
+    SrliTable,
+};
 pub(crate) const G: B32 = B32::MULTIPLICATIVE_GENERATOR;
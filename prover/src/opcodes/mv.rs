@@ -3,16 +3,17 @@
 use binius_field::underlier::Divisible;
 use binius_m3::builder::B128;
 use binius_m3::builder::{
-    upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32,
+    upcast_expr, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B16, B32, B8,
 };
 use petravm_asm::MvihEvent;
 use petravm_asm::MvvlEvent;
 use petravm_asm::{opcodes::Opcode, MvvwEvent};
+use petravm_asm::{MvibEvent, MvvhEvent};
 
 use crate::gadgets::multiple_lookup::{MultipleLookupColumns, MultipleLookupGadget};
 use crate::gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget};
 use crate::table::Table;
-use crate::utils::pull_vrom_channel;
+use crate::utils::{pack_b16_into_b32, pull_vrom_channel};
 use crate::{channels::Channels, types::ProverPackedField};
 
 /// MVV.W (Move Value to Value) table implementation.
@@ -55,6 +56,7 @@ impl Table for MvvwTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -140,6 +142,7 @@ impl TableFiller<ProverPackedField> for MvvwTable {
             pc: event.pc.val(),
             next_pc: None, // NextPc::Increment handled by State columns
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.offset,
             arg2: event.src,
@@ -180,6 +183,7 @@ impl Table for MvihTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -251,6 +255,7 @@ impl TableFiller<ProverPackedField> for MvihTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.offset,
             arg2: ev.imm,
@@ -260,6 +265,278 @@ impl TableFiller<ProverPackedField> for MvihTable {
     }
 }
 
+/// MVV.H (Move Value to Value Half-word) table implementation.
+///
+/// This table verifies the Move Value to Value (half-word) instruction, which
+/// moves the zero-extended low 16 bits of a value from one VROM location to
+/// another, with optional offset addressing.
+pub struct MvvhTable {
+    /// Table identifier
+    pub id: TableId,
+    /// State-related columns for instruction handling
+    state_cols: StateColumns<{ Opcode::Mvvh as u16 }>,
+    /// Base destination address (FP + dst)
+    dst_abs_addr: Col<B32>,
+    /// Base source address (FP + src)
+    src_abs_addr: Col<B32>,
+    /// Final destination address with offset (dst_addr + offset)
+    final_dst_addr: Col<B32>,
+    /// Destination address value from VROM
+    dst_addr: Col<B32>,
+    /// Low half-word of the source value
+    src_low: Col<B16>,
+    /// High half-word of the source value
+    src_high: Col<B16>,
+    /// Source value (from src_abs_addr)
+    src_val: Col<B32>,
+    /// Moved value, i.e. the zero-extended low half-word of the source
+    dst_val: Col<B32>,
+}
+
+impl Table for MvvhTable {
+    type Event = MvvhEvent;
+
+    fn name(&self) -> &'static str {
+        "MvvhTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("mvvh");
+
+        // Set up State columns with standard instruction handling
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // Extract instruction arguments from State columns
+        let StateColumns {
+            fp,
+            arg0: dst,
+            arg1: offset,
+            arg2: src,
+            ..
+        } = state_cols;
+
+        // Compute absolute addresses for source and destination
+        let dst_abs_addr = table.add_computed("dst_abs_addr", fp + upcast_expr(dst.into()));
+        let src_abs_addr = table.add_computed("src_abs_addr", fp + upcast_expr(src.into()));
+
+        // Split the source value into half-words and keep the low one
+        let src_low = table.add_committed("src_low");
+        let src_high = table.add_committed("src_high");
+        let src_val = table.add_computed("src_val", pack_b16_into_b32(src_low, src_high));
+        let dst_val = table.add_computed("dst_val", upcast_expr(src_low.into()));
+
+        // Read the destination address from VROM
+        let dst_addr = table.add_committed("dst_addr");
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs_addr, dst_addr]);
+
+        // Compute final destination address with offset
+        let final_dst_addr =
+            table.add_computed("final_dst_addr", dst_addr + upcast_expr(offset.into()));
+
+        // Read source value from VROM
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs_addr, src_val]);
+
+        // Verify the truncated value is written to the final destination address
+        pull_vrom_channel(&mut table, channels.vrom_channel, [final_dst_addr, dst_val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs_addr,
+            src_abs_addr,
+            final_dst_addr,
+            dst_addr,
+            src_low,
+            src_high,
+            src_val,
+            dst_val,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MvvhTable {
+    type Event = MvvhEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    /// Fill the table witness with data from MVV.H events
+    ///
+    /// This populates the witness data based on the execution events from
+    /// the corresponding assembly MVV.H operations.
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            // Get mutable references to witness columns
+            let mut dst_abs_addr = witness.get_scalars_mut(self.dst_abs_addr)?;
+            let mut src_abs_addr = witness.get_scalars_mut(self.src_abs_addr)?;
+            let mut final_dst_addr = witness.get_scalars_mut(self.final_dst_addr)?;
+            let mut dst_addr = witness.get_scalars_mut(self.dst_addr)?;
+            let mut src_low = witness.get_scalars_mut(self.src_low)?;
+            let mut src_high = witness.get_scalars_mut(self.src_high)?;
+            let mut src_val = witness.get_scalars_mut(self.src_val)?;
+            let mut dst_val = witness.get_scalars_mut(self.dst_val)?;
+
+            // Fill the witness columns with values from each event
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs_addr[i] = B32::new(event.fp.addr(event.dst));
+                src_abs_addr[i] = B32::new(event.fp.addr(event.src));
+                dst_addr[i] = B32::new(event.dst_addr);
+                final_dst_addr[i] = B32::new(event.dst_addr ^ event.offset as u32);
+                src_low[i] = B16::new(event.src_val as u16);
+                src_high[i] = B16::new((event.src_val >> 16) as u16);
+                src_val[i] = B32::new(event.src_val);
+                dst_val[i] = B32::new(event.dst_val());
+            }
+        }
+
+        // Create StateGadget rows from events
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None, // NextPc::Increment handled by State columns
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.offset,
+            arg2: event.src,
+        });
+
+        // Populate State columns with the gadget rows
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// MVI.B (Move Immediate Byte) table implementation.
+///
+/// VROM[ fp[dst] + offset ] = zero_extend(imm), with imm < 256
+pub struct MvibTable {
+    pub id: TableId,
+    state_cols: StateColumns<{ Opcode::Mvib as u16 }>,
+    dst_abs_addr: Col<B32>,
+    dst_addr: Col<B32>,
+    final_dst_addr: Col<B32>,
+    imm_byte: Col<B8>,
+    imm_val: Col<B32>,
+}
+
+impl Table for MvibTable {
+    type Event = MvibEvent;
+
+    fn name(&self) -> &'static str {
+        "MvibTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("mvib");
+
+        // State columns (pc, fp, args)
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        let StateColumns {
+            fp,
+            arg0: dst,
+            arg1: offset,
+            arg2: imm,
+            ..
+        } = state_cols;
+
+        // Compute base address
+        let dst_abs_addr = table.add_computed("dst_abs_addr", fp + upcast_expr(dst.into()));
+
+        // Pull the base pointer from VROM
+        let dst_addr = table.add_committed("dst_addr");
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs_addr, dst_addr]);
+
+        // Compute actual destination slot
+        let final_dst_addr =
+            table.add_computed("final_dst_addr", dst_addr + upcast_expr(offset.into()));
+
+        // Lift the 8-bit immediate to 32 bits, checking that it fits in a byte
+        let imm_byte = table.add_committed("imm_byte");
+        let imm_val = table.add_computed("imm_val", upcast_expr(imm_byte.into()));
+        table.assert_zero("imm_is_byte", upcast_expr(imm.into()) - imm_val);
+
+        // Verify the immediate write into VROM
+        pull_vrom_channel(&mut table, channels.vrom_channel, [final_dst_addr, imm_val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs_addr,
+            dst_addr,
+            final_dst_addr,
+            imm_byte,
+            imm_val,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for MvibTable {
+    type Event = MvibEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            // Fill VROM reads/writes
+            let mut dst_abs_addr_col = witness.get_scalars_mut(self.dst_abs_addr)?;
+            let mut dst_addr_col = witness.get_scalars_mut(self.dst_addr)?;
+            let mut final_dst_addr_col = witness.get_scalars_mut(self.final_dst_addr)?;
+            let mut imm_byte_col = witness.get_scalars_mut(self.imm_byte)?;
+            let mut imm_col = witness.get_scalars_mut(self.imm_val)?;
+
+            for (i, ev) in rows.clone().enumerate() {
+                dst_abs_addr_col[i] = B32::new(ev.fp.addr(ev.dst));
+                dst_addr_col[i] = B32::new(ev.dst_addr);
+                final_dst_addr_col[i] = B32::new(ev.dst_addr ^ ev.offset as u32);
+                imm_byte_col[i] = B8::new(ev.imm);
+                imm_col[i] = B32::new(ev.imm as u32);
+            }
+        }
+
+        // Fill State‐side columns (pc, fp, dst, offset, imm)
+        let state_rows = rows.map(|ev| StateGadget {
+            pc: ev.pc.val(),
+            next_pc: None,
+            fp: *ev.fp,
+            timestamp: ev.timestamp,
+            arg0: ev.dst,
+            arg1: ev.offset,
+            arg2: ev.imm as u16,
+        });
+
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
 /// MVV.L (Move Value to Value Long) table implementation.
 ///
 /// This table verifies the Move Value to Value (long) instruction, which moves
@@ -304,6 +581,7 @@ impl Table for MvvlTable {
             StateColumnsOptions {
                 next_pc: NextPc::Increment,
                 next_fp: None,
+                next_timestamp: None,
             },
         );
 
@@ -420,6 +698,7 @@ impl TableFiller<ProverPackedField> for MvvlTable {
             pc: event.pc.val(),
             next_pc: None, // NextPc::Increment handled by State columns
             fp: *event.fp,
+            timestamp: event.timestamp,
             arg0: event.dst,
             arg1: event.offset,
             arg2: event.src,
@@ -435,6 +714,7 @@ mod tests {
     use anyhow::Result;
     use binius_field::PackedField;
     use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
 
     use crate::model::Trace;
     use crate::prover::Prover;
@@ -556,4 +836,35 @@ mod tests {
         assert_eq!(trace.trace.calli.len(), 1);
         Prover::new(Box::new(GenericISA)).validate_witness(&trace)
     }
+
+    fn test_sub_word_moves_with_value(val: u32, imm: u8) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+            _start:\n\
+            ALLOCI! @3, #16\n\
+            MVV.H @3[2], @2\n\
+            MVI.B @3[3], #{imm}\n\
+            RET\n"
+        );
+
+        let init_values = vec![0, 0, val];
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, Some(init_values), None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.trace.mvvh.len(), 1);
+        assert_eq!(trace.trace.mvib.len(), 1);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_sub_word_moves(
+            val in prop_oneof![Just(0u32), Just(0xffff_0000u32), any::<u32>()],
+            imm in any::<u8>(),
+        ) {
+            prop_assert!(test_sub_word_moves_with_value(val, imm).is_ok());
+        }
+    }
 }
@@ -0,0 +1,92 @@
+use binius_m3::builder::{ConstraintSystem, TableFiller, TableId, TableWitnessSegment};
+use petravm_asm::{NopEvent, Opcode};
+
+use crate::gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget};
+use crate::{channels::Channels, table::Table, types::ProverPackedField};
+
+/// Table for NOP instruction.
+///
+/// Only advances the PC, without any other effect on the state.
+/// Logic: PC = PC * G
+pub struct NopTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::Nop as u16 }>,
+}
+
+impl Table for NopTable {
+    type Event = NopEvent;
+
+    fn name(&self) -> &'static str {
+        "NopTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("nop");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        Self {
+            id: table.id(),
+            state_cols,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for NopTable {
+    type Event = NopEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            ..Default::default()
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    #[test]
+    fn test_nop() -> Result<()> {
+        let asm_code = "#[framesize(0x4)]\n\
+            _start:\n\
+            LDI.W @2, #1\n\
+            .align 4\n\
+            NOP\n\
+            RET\n"
+            .to_string();
+
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.nop_events().len(), 4);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+}
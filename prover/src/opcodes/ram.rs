@@ -0,0 +1,941 @@
+//! RAM access tables.
+//!
+//! Word accesses to the RAM are checked with timestamp-ordered multiset
+//! checking over the RAM channel: each access pulls the
+//! `[WordAddress, Value, Timestamp]` tuple left by the previous access to the
+//! same word (or by the [`RamTable`](crate::memory::RamTable) for the first
+//! one), and pushes the updated tuple with a strictly greater timestamp.
+//!
+//! The timestamps are bound to the execution order by the state channel,
+//! which carries the system timestamp: an access at system timestamp `t` has
+//! timestamp `t + 1` in the RAM channel, timestamp 0 being reserved for the
+//! initial state of the words, and moves the system timestamp to `t + 1`.
+//!
+//! Byte and half-word accesses read and write the aligned word containing
+//! them, the accessed lane of the word being selected by the low bits of the
+//! address.
+
+use std::array::from_fn;
+
+use binius_core::oracle::ShiftVariant;
+use binius_field::{packed::set_packed_slice, Field, PackedBinaryField32x1b};
+use binius_m3::{
+    builder::{
+        upcast_col, Col, ConstraintSystem, Expr, TableBuilder, TableFiller, TableId,
+        TableWitnessSegment, B1, B32,
+    },
+    gadgets::{
+        add::{U32Add, U32AddFlags},
+        sub::{U32Sub, U32SubFlags},
+    },
+};
+use petravm_asm::{
+    opcodes::Opcode, LbEvent, LbuEvent, LhEvent, LhuEvent, LwEvent, SbEvent, ShEvent, SwEvent,
+};
+
+use super::integer_ops::{setup_sign_extended_immediate, SignExtendedImmediateOutput};
+use crate::{
+    channels::Channels,
+    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    model::ram_channel_timestamp,
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// Width of a RAM access.
+#[derive(Clone, Copy)]
+enum AccessWidth {
+    Byte,
+    HalfWord,
+    Word,
+}
+
+impl AccessWidth {
+    /// Returns the number of bits accessed.
+    const fn bits(self) -> usize {
+        match self {
+            Self::Byte => 8,
+            Self::HalfWord => 16,
+            Self::Word => 32,
+        }
+    }
+
+    /// Returns the value of the word `word` after writing the low bits of
+    /// `value` at byte address `addr`.
+    const fn store(self, word: u32, addr: u32, value: u32) -> u32 {
+        let bits = self.bits() as u32;
+        let shift = (addr & 3) * 8;
+        let mask = (u32::MAX >> (32 - bits)) << shift;
+        (word & !mask) | ((value << shift) & mask)
+    }
+}
+
+/// Witness data shared by all RAM accesses.
+struct RamAccessRow {
+    pc: u32,
+    fp: u32,
+    timestamp: u32,
+    arg0: u16,
+    base: u16,
+    base_abs: u32,
+    base_val: u32,
+    offset: u16,
+    addr: u32,
+    prev_timestamp: Option<u32>,
+}
+
+/// Returns the [`RamAccessRow`] of a RAM event, whose first argument is the
+/// field `$arg0`.
+macro_rules! ram_access_row {
+    ($event:expr, $arg0:ident) => {
+        RamAccessRow {
+            pc: $event.pc.val(),
+            fp: *$event.fp,
+            timestamp: $event.timestamp,
+            arg0: $event.$arg0,
+            base: $event.base,
+            base_abs: $event.fp.addr($event.base),
+            base_val: $event.base_val,
+            offset: $event.offset,
+            addr: $event.addr,
+            prev_timestamp: $event.prev_timestamp,
+        }
+    };
+}
+
+/// Columns reading the instruction of a RAM access, computing the word
+/// address it accesses, and checking its consistency with the previous access
+/// to the same word and with the system timestamp.
+struct RamAccessColumns<const OPCODE: u16> {
+    state_cols: StateColumns<OPCODE>,
+    timestamp: Col<B1, 32>,
+    one: Col<B1, 32>,
+    incr_timestamp: U32Add,
+    base_abs: Col<B32>, // Virtual
+    base_val: Col<B1, 32>,
+    offset: SignExtendedImmediateOutput,
+    addr_op: U32Add,
+    addr_bit_0: Col<B1>,    // Virtual
+    addr_bit_1: Col<B1>,    // Virtual
+    word_addr: Col<B1, 32>, // Virtual
+    prev_timestamp: Col<B1, 32>,
+    timestamp_op: U32Sub,
+}
+
+impl<const OPCODE: u16> RamAccessColumns<OPCODE> {
+    /// Adds the columns and constraints for an access of `width`, where
+    /// `prev_value` is the value of the accessed word before the access and
+    /// `value` its value after the access.
+    fn new(
+        table: &mut TableBuilder,
+        channels: &Channels,
+        width: AccessWidth,
+        prev_value: Col<B32>,
+        value: Col<B32>,
+    ) -> Self {
+        // The access moves the system timestamp from t to t + 1, its own
+        // timestamp in the RAM channel.
+        let timestamp = table.add_committed("timestamp_unpacked");
+        let timestamp_packed = table.add_packed("timestamp_packed", timestamp);
+        let mut one_bits = [B1::ZERO; 32];
+        one_bits[0] = B1::ONE;
+        let one = table.add_constant("one", one_bits);
+        let incr_timestamp = U32Add::new(table, timestamp, one, U32AddFlags::default());
+        let access_timestamp = table.add_packed("access_timestamp", incr_timestamp.zout);
+
+        let state_cols = StateColumns::new(
+            table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: Some(access_timestamp),
+            },
+        );
+        table.assert_zero("timestamp", state_cols.timestamp - timestamp_packed);
+
+        // Read the base address.
+        let base_abs = table.add_computed("base", state_cols.fp + upcast_col(state_cols.arg1));
+        let base_val = table.add_committed("base_val");
+        let base_val_packed = table.add_packed("base_val_packed", base_val);
+        pull_vrom_channel(table, channels.vrom_channel, [base_abs, base_val_packed]);
+
+        // addr = base_val + SignExtend(offset)
+        let offset = setup_sign_extended_immediate(table, state_cols.arg2_unpacked);
+        let addr_op = U32Add::new(
+            table,
+            base_val,
+            offset.signed_imm_unpacked,
+            U32AddFlags::default(),
+        );
+
+        // Accesses must be aligned on their width.
+        let addr_bit_0 = table.add_selected("addr_bit_0", addr_op.zout, 0);
+        let addr_bit_1 = table.add_selected("addr_bit_1", addr_op.zout, 1);
+        if !matches!(width, AccessWidth::Byte) {
+            table.assert_zero("addr_aligned_0", addr_bit_0.into());
+        }
+        if matches!(width, AccessWidth::Word) {
+            table.assert_zero("addr_aligned_1", addr_bit_1.into());
+        }
+
+        let word_addr =
+            table.add_shifted("word_addr", addr_op.zout, 5, 2, ShiftVariant::LogicalRight);
+        let word_addr_packed = table.add_packed("word_addr_packed", word_addr);
+
+        // prev_timestamp < access_timestamp
+        let prev_timestamp = table.add_committed("prev_timestamp");
+        let timestamp_op = U32Sub::new(
+            table,
+            prev_timestamp,
+            incr_timestamp.zout,
+            U32SubFlags {
+                borrow_in_bit: None,
+                expose_final_borrow: true,
+                commit_zout: false,
+            },
+        );
+        let final_borrow = timestamp_op
+            .final_borrow
+            .expect("Flag `expose_final_borrow` was set to `true`");
+        table.assert_zero("prev_timestamp_lt_timestamp", final_borrow - B1::ONE);
+
+        let prev_timestamp_packed = table.add_packed("prev_timestamp_packed", prev_timestamp);
+        table.pull(
+            channels.ram_channel,
+            [word_addr_packed, prev_value, prev_timestamp_packed],
+        );
+        table.push(
+            channels.ram_channel,
+            [word_addr_packed, value, access_timestamp],
+        );
+
+        Self {
+            state_cols,
+            timestamp,
+            one,
+            incr_timestamp,
+            base_abs,
+            base_val,
+            offset,
+            addr_op,
+            addr_bit_0,
+            addr_bit_1,
+            word_addr,
+            prev_timestamp,
+            timestamp_op,
+        }
+    }
+
+    /// Returns the selectors of the lanes of `width` bits of the accessed
+    /// word, from the least significant one. Only the selector of the lane
+    /// holding the accessed address is set.
+    fn lane_selectors(&self, width: AccessWidth) -> Vec<Expr<B32, 1>> {
+        let bit = |col: Col<B1>, set: bool| -> Expr<B32, 1> {
+            let col: Col<B32> = upcast_col(col);
+            if set {
+                col.into()
+            } else {
+                col + B32::ONE
+            }
+        };
+        match width {
+            AccessWidth::Byte => (0..4)
+                .map(|k| bit(self.addr_bit_0, k & 1 == 1) * bit(self.addr_bit_1, k & 2 == 2))
+                .collect(),
+            AccessWidth::HalfWord => (0..2).map(|k| bit(self.addr_bit_1, k == 1)).collect(),
+            // The address of a word access is aligned, so its lowest bit is
+            // unset.
+            AccessWidth::Word => vec![bit(self.addr_bit_0, false)],
+        }
+    }
+
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = RamAccessRow> + Clone,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut timestamp = witness.get_mut_as(self.timestamp)?;
+            let mut one = witness.get_mut_as(self.one)?;
+            let mut base_abs = witness.get_scalars_mut(self.base_abs)?;
+            let mut base_val = witness.get_mut_as(self.base_val)?;
+            let mut imm = witness.get_mut_as(self.offset.imm_unpacked)?;
+            let mut msb: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.offset.msb)?;
+            let mut negative = witness.get_mut_as(self.offset.negative_unpacked)?;
+            let mut signed_imm = witness.get_mut_as(self.offset.signed_imm_unpacked)?;
+            let mut ones = witness.get_mut_as(self.offset.ones)?;
+            let mut addr_bit_0: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.addr_bit_0)?;
+            let mut addr_bit_1: std::cell::RefMut<'_, [PackedBinaryField32x1b]> =
+                witness.get_mut_as(self.addr_bit_1)?;
+            let mut word_addr = witness.get_mut_as(self.word_addr)?;
+            let mut prev_timestamp = witness.get_mut_as(self.prev_timestamp)?;
+
+            for (i, row) in rows.clone().enumerate() {
+                timestamp[i] = row.timestamp;
+                one[i] = 1u32;
+                base_abs[i] = B32::new(row.base_abs);
+                base_val[i] = row.base_val;
+
+                let imm_val = row.offset as u32;
+                imm[i] = imm_val;
+                set_packed_slice(&mut msb, i, B1::from((imm_val >> 15) & 1 == 1));
+                ones[i] = 0xFFFF0000u32;
+                negative[i] = 0xFFFF0000u32 | imm_val;
+                signed_imm[i] = row.offset as i16 as i32 as u32;
+
+                set_packed_slice(&mut addr_bit_0, i, B1::from(row.addr & 1 == 1));
+                set_packed_slice(&mut addr_bit_1, i, B1::from((row.addr >> 1) & 1 == 1));
+                word_addr[i] = row.addr >> 2;
+
+                prev_timestamp[i] = ram_channel_timestamp(row.prev_timestamp);
+            }
+        }
+
+        self.incr_timestamp.populate(witness)?;
+        self.addr_op.populate(witness)?;
+        self.timestamp_op.populate(witness)?;
+
+        let state_rows = rows.map(|row| StateGadget {
+            pc: row.pc,
+            next_pc: None,
+            fp: row.fp,
+            timestamp: row.timestamp,
+            arg0: row.arg0,
+            arg1: row.base,
+            arg2: row.offset,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// Returns the packed value of the bits `bits`, shifted left by `shift`.
+fn pack_bits(bits: &[Col<B1>], shift: usize) -> Expr<B32, 1> {
+    bits.iter()
+        .enumerate()
+        .map(|(j, &bit)| upcast_col(bit) * B32::new(1 << (j + shift)))
+        .reduce(|acc, term| acc + term)
+        .expect("a lane has at least one bit")
+}
+
+/// Sets the `i`-th row of the selected bit columns `bits` to the bits of
+/// `value`.
+fn set_bits(bits: &mut [std::cell::RefMut<'_, [ProverPackedField]>], i: usize, value: u32) {
+    for (j, bit) in bits.iter_mut().enumerate() {
+        set_packed_slice(bit, i, B1::from((value >> j) & 1 == 1));
+    }
+}
+
+/// LW table.
+///
+/// This table handles the LW instruction, which loads a 32-bit word from the
+/// RAM into the VROM.
+pub struct LwTable {
+    id: TableId,
+    ram_cols: RamAccessColumns<{ Opcode::Lw as u16 }>,
+    dst_abs: Col<B32>, // Virtual
+    value: Col<B32>,
+}
+
+impl Table for LwTable {
+    type Event = LwEvent;
+
+    fn name(&self) -> &'static str {
+        "LwTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("lw");
+
+        // The loaded word is left unchanged in RAM, and written to dst.
+        let value = table.add_committed("value");
+        let ram_cols = RamAccessColumns::new(&mut table, channels, AccessWidth::Word, value, value);
+
+        let state_cols = &ram_cols.state_cols;
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, value]);
+
+        Self {
+            id: table.id(),
+            ram_cols,
+            dst_abs,
+            value,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for LwTable {
+    type Event = LwEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut value = witness.get_scalars_mut(self.value)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                value[i] = B32::new(event.dst_val);
+            }
+        }
+
+        let ram_rows = rows.map(|event| ram_access_row!(event, dst));
+        self.ram_cols.populate(witness, ram_rows)
+    }
+}
+
+/// SW table.
+///
+/// This table handles the SW instruction, which stores a 32-bit word from the
+/// VROM into the RAM.
+pub struct SwTable {
+    id: TableId,
+    ram_cols: RamAccessColumns<{ Opcode::Sw as u16 }>,
+    src_abs: Col<B32>, // Virtual
+    value: Col<B32>,
+    prev_value: Col<B32>,
+}
+
+impl Table for SwTable {
+    type Event = SwEvent;
+
+    fn name(&self) -> &'static str {
+        "SwTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("sw");
+
+        // The stored word is read from src, and overwrites the previous one.
+        let value = table.add_committed("value");
+        let prev_value = table.add_committed("prev_value");
+        let ram_cols =
+            RamAccessColumns::new(&mut table, channels, AccessWidth::Word, prev_value, value);
+
+        let state_cols = &ram_cols.state_cols;
+        let src_abs = table.add_computed("src", state_cols.fp + upcast_col(state_cols.arg0));
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs, value]);
+
+        Self {
+            id: table.id(),
+            ram_cols,
+            src_abs,
+            value,
+            prev_value,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for SwTable {
+    type Event = SwEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        {
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut value = witness.get_scalars_mut(self.value)?;
+            let mut prev_value = witness.get_scalars_mut(self.prev_value)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                src_abs[i] = B32::new(event.fp.addr(event.src));
+                value[i] = B32::new(event.src_val);
+                prev_value[i] = B32::new(event.prev_word);
+            }
+        }
+
+        let ram_rows = rows.map(|event| ram_access_row!(event, src));
+        self.ram_cols.populate(witness, ram_rows)
+    }
+}
+
+/// Columns of a byte or half-word load, which reads the accessed lane of the
+/// word containing it and extends it to 32 bits.
+struct SubwordLoadColumns<const OPCODE: u16> {
+    ram_cols: RamAccessColumns<OPCODE>,
+    dst_abs: Col<B32>, // Virtual
+    dst_val: Col<B32>,
+    word: Col<B1, 32>,
+    word_bits: [Col<B1>; 32], // Virtual
+}
+
+impl<const OPCODE: u16> SubwordLoadColumns<OPCODE> {
+    fn new(
+        table: &mut TableBuilder,
+        channels: &Channels,
+        width: AccessWidth,
+        signed: bool,
+    ) -> Self {
+        // The accessed word is left unchanged in RAM.
+        let word = table.add_committed("word");
+        let word_packed = table.add_packed("word_packed", word);
+        let ram_cols = RamAccessColumns::new(table, channels, width, word_packed, word_packed);
+
+        // dst_val = Extend(lane of the word holding the address)
+        let bits = width.bits();
+        let word_bits = from_fn(|j| table.add_selected(format!("word_bit_{j}"), word, j));
+        let loaded = ram_cols
+            .lane_selectors(width)
+            .into_iter()
+            .zip(word_bits.chunks(bits))
+            .map(|(selector, lane)| {
+                let mut value = pack_bits(lane, 0);
+                if signed {
+                    value = value + upcast_col(lane[bits - 1]) * B32::new(u32::MAX << bits);
+                }
+                selector * value
+            })
+            .reduce(|acc, term| acc + term)
+            .expect("a word has at least one lane");
+        let dst_val = table.add_committed("dst_val");
+        table.assert_zero("dst_val", dst_val - loaded);
+
+        let state_cols = &ram_cols.state_cols;
+        let dst_abs = table.add_computed("dst", state_cols.fp + upcast_col(state_cols.arg0));
+        pull_vrom_channel(table, channels.vrom_channel, [dst_abs, dst_val]);
+
+        Self {
+            ram_cols,
+            dst_abs,
+            dst_val,
+            word,
+            word_bits,
+        }
+    }
+}
+
+/// Columns of a byte or half-word store, which overwrites the accessed lane
+/// of the word containing it with the low bits of the source value.
+struct SubwordStoreColumns<const OPCODE: u16> {
+    ram_cols: RamAccessColumns<OPCODE>,
+    src_abs: Col<B32>, // Virtual
+    src_val: Col<B1, 32>,
+    src_bits: Vec<Col<B1>>, // Virtual
+    prev_word: Col<B1, 32>,
+    prev_word_bits: [Col<B1>; 32], // Virtual
+    word: Col<B32>,
+    width: AccessWidth,
+}
+
+impl<const OPCODE: u16> SubwordStoreColumns<OPCODE> {
+    fn new(table: &mut TableBuilder, channels: &Channels, width: AccessWidth) -> Self {
+        let prev_word = table.add_committed("prev_word");
+        let prev_word_packed = table.add_packed("prev_word_packed", prev_word);
+        let word = table.add_committed("word");
+        let ram_cols = RamAccessColumns::new(table, channels, width, prev_word_packed, word);
+
+        // word = prev_word, with the lane holding the address replaced by the
+        // low bits of src_val
+        let bits = width.bits();
+        let src_val = table.add_committed("src_val");
+        let src_bits = (0..bits)
+            .map(|j| table.add_selected(format!("src_bit_{j}"), src_val, j))
+            .collect::<Vec<_>>();
+        let prev_word_bits =
+            from_fn(|j| table.add_selected(format!("prev_word_bit_{j}"), prev_word, j));
+        let stored = ram_cols
+            .lane_selectors(width)
+            .into_iter()
+            .zip(prev_word_bits.chunks(bits))
+            .enumerate()
+            .map(|(k, (selector, lane))| {
+                selector * (pack_bits(lane, k * bits) + pack_bits(&src_bits, k * bits))
+            })
+            .reduce(|acc, term| acc + term)
+            .expect("a word has at least one lane");
+        table.assert_zero("word", word - (prev_word_packed + stored));
+
+        let src_val_packed = table.add_packed("src_val_packed", src_val);
+        let state_cols = &ram_cols.state_cols;
+        let src_abs = table.add_computed("src", state_cols.fp + upcast_col(state_cols.arg0));
+        pull_vrom_channel(table, channels.vrom_channel, [src_abs, src_val_packed]);
+
+        Self {
+            ram_cols,
+            src_abs,
+            src_val,
+            src_bits,
+            prev_word,
+            prev_word_bits,
+            word,
+            width,
+        }
+    }
+}
+
+/// Defines a table for a byte or half-word load instruction on top of
+/// [`SubwordLoadColumns`].
+macro_rules! define_ram_load_table {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $event:ty,
+        $opcode:path,
+        $table_name:literal,
+        $width:ident,
+        $signed:literal
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            id: TableId,
+            load_cols: SubwordLoadColumns<{ $opcode as u16 }>,
+        }
+
+        impl Table for $name {
+            type Event = $event;
+
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+                let mut table = cs.add_table($table_name);
+
+                let load_cols =
+                    SubwordLoadColumns::new(&mut table, channels, AccessWidth::$width, $signed);
+
+                Self {
+                    id: table.id(),
+                    load_cols,
+                }
+            }
+        }
+
+        impl TableFiller<ProverPackedField> for $name {
+            type Event = $event;
+
+            fn id(&self) -> TableId {
+                self.id
+            }
+
+            fn fill<'a>(
+                &self,
+                rows: impl Iterator<Item = &'a Self::Event> + Clone,
+                witness: &'a mut TableWitnessSegment<ProverPackedField>,
+            ) -> Result<(), anyhow::Error> {
+                let cols = &self.load_cols;
+                {
+                    let mut dst_abs = witness.get_scalars_mut(cols.dst_abs)?;
+                    let mut dst_val = witness.get_scalars_mut(cols.dst_val)?;
+                    let mut word = witness.get_mut_as(cols.word)?;
+                    let mut word_bits = cols
+                        .word_bits
+                        .iter()
+                        .map(|&col| witness.get_mut(col))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    for (i, event) in rows.clone().enumerate() {
+                        dst_abs[i] = B32::new(event.fp.addr(event.dst));
+                        dst_val[i] = B32::new(event.dst_val);
+                        word[i] = event.prev_word;
+                        set_bits(&mut word_bits, i, event.prev_word);
+                    }
+                }
+
+                let ram_rows = rows.map(|event| ram_access_row!(event, dst));
+                cols.ram_cols.populate(witness, ram_rows)
+            }
+        }
+    };
+}
+
+/// Defines a table for a byte or half-word store instruction on top of
+/// [`SubwordStoreColumns`].
+macro_rules! define_ram_store_table {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $event:ty,
+        $opcode:path,
+        $table_name:literal,
+        $width:ident
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            id: TableId,
+            store_cols: SubwordStoreColumns<{ $opcode as u16 }>,
+        }
+
+        impl Table for $name {
+            type Event = $event;
+
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+                let mut table = cs.add_table($table_name);
+
+                let store_cols =
+                    SubwordStoreColumns::new(&mut table, channels, AccessWidth::$width);
+
+                Self {
+                    id: table.id(),
+                    store_cols,
+                }
+            }
+        }
+
+        impl TableFiller<ProverPackedField> for $name {
+            type Event = $event;
+
+            fn id(&self) -> TableId {
+                self.id
+            }
+
+            fn fill<'a>(
+                &self,
+                rows: impl Iterator<Item = &'a Self::Event> + Clone,
+                witness: &'a mut TableWitnessSegment<ProverPackedField>,
+            ) -> Result<(), anyhow::Error> {
+                let cols = &self.store_cols;
+                {
+                    let mut src_abs = witness.get_scalars_mut(cols.src_abs)?;
+                    let mut src_val = witness.get_mut_as(cols.src_val)?;
+                    let mut src_bits = cols
+                        .src_bits
+                        .iter()
+                        .map(|&col| witness.get_mut(col))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let mut prev_word = witness.get_mut_as(cols.prev_word)?;
+                    let mut prev_word_bits = cols
+                        .prev_word_bits
+                        .iter()
+                        .map(|&col| witness.get_mut(col))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let mut word = witness.get_scalars_mut(cols.word)?;
+
+                    for (i, event) in rows.clone().enumerate() {
+                        src_abs[i] = B32::new(event.fp.addr(event.src));
+                        src_val[i] = event.src_val;
+                        set_bits(&mut src_bits, i, event.src_val);
+                        prev_word[i] = event.prev_word;
+                        set_bits(&mut prev_word_bits, i, event.prev_word);
+                        word[i] = B32::new(cols.width.store(
+                            event.prev_word,
+                            event.addr,
+                            event.src_val,
+                        ));
+                    }
+                }
+
+                let ram_rows = rows.map(|event| ram_access_row!(event, src));
+                cols.ram_cols.populate(witness, ram_rows)
+            }
+        }
+    };
+}
+
+define_ram_load_table!(
+    /// LB table.
+    ///
+    /// This table handles the LB instruction, which loads a byte from the RAM
+    /// into the VROM and sign-extends it.
+    LbTable,
+    LbEvent,
+    Opcode::Lb,
+    "lb",
+    Byte,
+    true
+);
+
+define_ram_load_table!(
+    /// LBU table.
+    ///
+    /// This table handles the LBU instruction, which loads a byte from the
+    /// RAM into the VROM and zero-extends it.
+    LbuTable,
+    LbuEvent,
+    Opcode::Lbu,
+    "lbu",
+    Byte,
+    false
+);
+
+define_ram_load_table!(
+    /// LH table.
+    ///
+    /// This table handles the LH instruction, which loads a half-word from the
+    /// RAM into the VROM and sign-extends it.
+    LhTable,
+    LhEvent,
+    Opcode::Lh,
+    "lh",
+    HalfWord,
+    true
+);
+
+define_ram_load_table!(
+    /// LHU table.
+    ///
+    /// This table handles the LHU instruction, which loads a half-word from
+    /// the RAM into the VROM and zero-extends it.
+    LhuTable,
+    LhuEvent,
+    Opcode::Lhu,
+    "lhu",
+    HalfWord,
+    false
+);
+
+define_ram_store_table!(
+    /// SB table.
+    ///
+    /// This table handles the SB instruction, which stores the low byte of a
+    /// word from the VROM into the RAM.
+    SbTable,
+    SbEvent,
+    Opcode::Sb,
+    "sb",
+    Byte
+);
+
+define_ram_store_table!(
+    /// SH table.
+    ///
+    /// This table handles the SH instruction, which stores the low half-word
+    /// of a word from the VROM into the RAM.
+    ShTable,
+    ShEvent,
+    Opcode::Sh,
+    "sh",
+    HalfWord
+);
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::model::Trace;
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    /// Creates an execution trace for a simple program that stores a word in
+    /// RAM, loads it back, and overwrites a previously untouched word.
+    fn generate_ram_trace(value: u32, offset: i16) -> Result<Trace> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+             _start:\n\
+                LDI.W @2, #256\n\
+                LDI.W @3, #{value}\n\
+                SW @3, @2, #{offset}\n\
+                LW @4, @2, #{offset}\n\
+                LW @5, @2, #512\n\
+                SW @4, @2, #512\n\
+                LW @6, @2, #512\n\
+                RET\n"
+        );
+
+        generate_trace(asm_code, None, None, Box::new(GenericISA))
+    }
+
+    fn test_ram_with_values(value: u32, offset: i16) -> Result<()> {
+        let trace = generate_ram_trace(value, offset)?;
+        trace.validate()?;
+        assert_eq!(trace.sw_events().len(), 2);
+        assert_eq!(trace.lw_events().len(), 3);
+        assert_eq!(trace.lw_events()[0].dst_val, value);
+        assert_eq!(trace.lw_events()[1].dst_val, 0);
+        assert_eq!(trace.lw_events()[2].dst_val, value);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    /// Creates an execution trace for a program that stores a word in RAM,
+    /// loads each of its bytes and half-words, and overwrites some of the
+    /// lanes of other words.
+    fn generate_subword_trace(value: u32) -> Result<Trace> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+             _start:\n\
+                LDI.W @2, #256\n\
+                LDI.W @3, #{value}\n\
+                SW @3, @2, #0\n\
+                LB @4, @2, #1\n\
+                LBU @5, @2, #3\n\
+                LH @6, @2, #2\n\
+                LHU @7, @2, #0\n\
+                SB @3, @2, #5\n\
+                SH @3, @2, #10\n\
+                SB @3, @2, #0\n\
+                LW @8, @2, #4\n\
+                LW @9, @2, #8\n\
+                LW @10, @2, #0\n\
+                RET\n"
+        );
+
+        generate_trace(asm_code, None, None, Box::new(GenericISA))
+    }
+
+    fn test_subword_with_value(value: u32) -> Result<()> {
+        let trace = generate_subword_trace(value)?;
+        trace.validate()?;
+        let bytes = value.to_le_bytes();
+        assert_eq!(trace.lb_events()[0].dst_val, bytes[1] as i8 as i32 as u32);
+        assert_eq!(trace.lbu_events()[0].dst_val, bytes[3] as u32);
+        assert_eq!(
+            trace.lh_events()[0].dst_val,
+            (value >> 16) as i16 as i32 as u32
+        );
+        assert_eq!(trace.lhu_events()[0].dst_val, value & 0xFFFF);
+        assert_eq!(trace.lw_events()[0].dst_val, (value & 0xFF) << 8);
+        assert_eq!(trace.lw_events()[1].dst_val, (value & 0xFFFF) << 16);
+        assert_eq!(trace.lw_events()[2].dst_val, value);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    #[test]
+    fn test_ram_negative_offset() -> Result<()> {
+        test_ram_with_values(0xDEAD_BEEF, -256)
+    }
+
+    #[test]
+    fn test_ram_timestamps_follow_execution() -> Result<()> {
+        let mut trace = generate_ram_trace(0xDEAD_BEEF, 4)?;
+        let prover = Prover::new(Box::new(GenericISA));
+        prover.validate_witness(&trace)?;
+
+        // The accesses cannot be reordered, as their timestamps are pinned by
+        // the state channel.
+        let (first, second) = (trace.trace.lw[0].timestamp, trace.trace.lw[1].timestamp);
+        trace.trace.lw[0].timestamp = second;
+        trace.trace.lw[1].timestamp = first;
+        assert!(prover.diagnose_witness(&trace).is_err());
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_ram_ops(
+            value in any::<u32>(),
+            offset in (-64i16..64).prop_map(|offset| offset * 4),
+        ) {
+            prop_assert!(test_ram_with_values(value, offset).is_ok());
+        }
+
+        #[test]
+        fn test_subword_ram_ops(value in any::<u32>()) {
+            prop_assert!(test_subword_with_value(value).is_ok());
+        }
+    }
+}
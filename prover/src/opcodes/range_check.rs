@@ -0,0 +1,148 @@
+//! RANGE_CHECK table implementation for the PetraVM M3 circuit.
+//!
+//! The range constraint itself is enforced by a lookup in the range-check
+//! lookup table of the checked width.
+
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B32,
+};
+use petravm_asm::{Opcode, RangeCheckEvent};
+
+use crate::{
+    channels::Channels,
+    gadgets::state::{StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+/// RANGE_CHECK table.
+///
+/// This table handles the RANGE_CHECK instruction, which asserts that a slot
+/// holds a value fitting in the number of bits given as immediate. The value
+/// is looked up in the range-check channel with that number of bits, which
+/// only holds values for the supported widths.
+pub struct RangeCheckTable {
+    id: TableId,
+    state_cols: StateColumns<{ Opcode::RangeCheck as u16 }>,
+    src_abs: Col<B32>, // Virtual
+    bits: Col<B32>,    // Virtual
+    val: Col<B32>,
+}
+
+impl Table for RangeCheckTable {
+    type Event = RangeCheckEvent;
+
+    fn name(&self) -> &'static str {
+        "RangeCheckTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("range_check");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        let val = table.add_committed("val");
+
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let bits = table.add_computed("bits", upcast_col(state_cols.arg1));
+
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src_abs, val]);
+        table.pull(channels.range_check_channel, [bits, val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            src_abs,
+            bits,
+            val,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for RangeCheckTable {
+    type Event = RangeCheckEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut bits = witness.get_scalars_mut(self.bits)?;
+            let mut val = witness.get_scalars_mut(self.val)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                src_abs[i] = B32::new(event.fp.addr(event.src));
+                bits[i] = B32::new(event.bits as u32);
+                val[i] = B32::new(event.val);
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.src,
+            arg1: event.bits,
+            arg2: 0,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::prover::Prover;
+    use crate::test_utils::generate_trace;
+
+    fn test_range_check_with_values(byte: u8, half: u16) -> Result<()> {
+        let asm_code = format!(
+            "#[framesize(0x10)]\n\
+            _start:\n\
+            LDI.W @2, #{byte}\n\
+            LDI.W @3, #{half}\n\
+            RANGE_CHECK @2, #8\n\
+            RANGE_CHECK @2, #16\n\
+            RANGE_CHECK @3, #16\n\
+            RANGE_CHECK @3, #16\n\
+            RET\n"
+        );
+
+        let isa = Box::new(GenericISA);
+        let trace = generate_trace(asm_code, None, None, isa)?;
+        trace.validate()?;
+        assert_eq!(trace.range_check_events().len(), 4);
+
+        let lookups = trace.range_lookups(16);
+        assert!(lookups.len().is_power_of_two());
+        assert!(lookups.len() > half as usize);
+        assert_eq!(lookups.iter().map(|(_, mult)| mult).sum::<u32>(), 3);
+
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(20))]
+
+        #[test]
+        fn test_range_check(byte in any::<u8>(), half in any::<u16>()) {
+            prop_assert!(test_range_check_with_values(byte, half).is_ok());
+        }
+    }
+}
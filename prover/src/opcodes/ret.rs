@@ -52,6 +52,7 @@ impl Table for RetTable {
             StateColumnsOptions {
                 next_pc: NextPc::Target(next_pc),
                 next_fp: Some(next_fp),
+                next_timestamp: None,
             },
         );
 
@@ -100,6 +101,7 @@ impl TableFiller<ProverPackedField> for RetTable {
             pc: event.pc.into(),
             next_pc: Some(event.pc_next),
             fp: *event.fp,
+            timestamp: event.timestamp,
             ..Default::default()
         });
         self.state_cols.populate(witness, state_rows)
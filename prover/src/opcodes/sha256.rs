@@ -0,0 +1,497 @@
+use std::{array::from_fn, cell::RefMut};
+
+use binius_core::oracle::ShiftVariant;
+use binius_field::Field;
+use binius_m3::{
+    builder::{
+        upcast_col, Col, ConstraintSystem, TableBuilder, TableFiller, TableId, TableWitnessSegment,
+        B1, B32,
+    },
+    gadgets::add::{U32Add, U32AddFlags},
+};
+use petravm_asm::{
+    opcodes::Opcode,
+    util::{sha256_schedule, SHA256_K},
+    Sha256CompressEvent,
+};
+
+use super::groestl::get_all_addresses;
+use crate::{
+    channels::Channels,
+    gadgets::state::{NextPc, StateColumns, StateColumnsOptions, StateGadget},
+    table::Table,
+    types::ProverPackedField,
+    utils::pull_vrom_channel,
+};
+
+const SHA256_COMPRESS_OPCODE: u16 = Opcode::Sha256Compress as u16;
+
+/// A term of a SHA-256 sigma function.
+#[derive(Clone, Copy)]
+enum Shift {
+    Rotr(u32),
+    Shr(u32),
+}
+
+impl Shift {
+    const fn apply(self, x: u32) -> u32 {
+        match self {
+            Self::Rotr(n) => x.rotate_right(n),
+            Self::Shr(n) => x >> n,
+        }
+    }
+}
+
+const BIG_SIGMA0: [Shift; 3] = [Shift::Rotr(2), Shift::Rotr(13), Shift::Rotr(22)];
+const BIG_SIGMA1: [Shift; 3] = [Shift::Rotr(6), Shift::Rotr(11), Shift::Rotr(25)];
+const SMALL_SIGMA0: [Shift; 3] = [Shift::Rotr(7), Shift::Rotr(18), Shift::Shr(3)];
+const SMALL_SIGMA1: [Shift; 3] = [Shift::Rotr(17), Shift::Rotr(19), Shift::Shr(10)];
+
+fn sigma(shifts: [Shift; 3], x: u32) -> u32 {
+    shifts.iter().fold(0, |acc, shift| acc ^ shift.apply(x))
+}
+
+/// Columns computing one of the SHA-256 sigma functions, i.e. the XOR of three
+/// shifts or rotations of a 32-bit value.
+struct SigmaColumns {
+    shifts: [Shift; 3],
+    shifted: [Col<B1, 32>; 3],
+    output: Col<B1, 32>,
+}
+
+impl SigmaColumns {
+    fn new(table: &mut TableBuilder, name: &str, input: Col<B1, 32>, shifts: [Shift; 3]) -> Self {
+        let shifted = from_fn(|i| {
+            // Rotating right by `n` is rotating left by `32 - n`.
+            let (offset, variant) = match shifts[i] {
+                Shift::Rotr(n) => (32 - n, ShiftVariant::CircularLeft),
+                Shift::Shr(n) => (n, ShiftVariant::LogicalRight),
+            };
+            table.add_shifted(
+                format!("{name}_shifted_{i}"),
+                input,
+                5,
+                offset as usize,
+                variant,
+            )
+        });
+        let output = table.add_computed(name, shifted[0] + shifted[1] + shifted[2]);
+
+        Self {
+            shifts,
+            shifted,
+            output,
+        }
+    }
+
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        inputs: impl Iterator<Item = u32>,
+    ) -> Result<(), anyhow::Error> {
+        let mut shifted = self
+            .shifted
+            .iter()
+            .map(|&col| witness.get_mut_as(col))
+            .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+        let mut output = witness.get_mut_as(self.output)?;
+
+        for (i, x) in inputs.enumerate() {
+            for (col, shift) in shifted.iter_mut().zip(self.shifts) {
+                col[i] = shift.apply(x);
+            }
+            output[i] = sigma(self.shifts, x);
+        }
+        Ok(())
+    }
+}
+
+/// Columns computing the word `w[i] = w[i-16] + σ0(w[i-15]) + w[i-7] +
+/// σ1(w[i-2])` of the message schedule, for `i >= 16`.
+struct ScheduleColumns {
+    sigma0: SigmaColumns,
+    sigma1: SigmaColumns,
+    adds: [U32Add; 3],
+}
+
+/// Columns for one of the 64 rounds of the compression function.
+struct RoundColumns {
+    sigma0: SigmaColumns,
+    sigma1: SigmaColumns,
+    ch: Col<B1, 32>,
+    maj: Col<B1, 32>,
+    k: Col<B1, 32>,
+    /// Additions computing `t1 = h + Σ1(e) + ch + k + w`.
+    t1_adds: [U32Add; 4],
+    /// Addition computing `t2 = Σ0(a) + maj`.
+    t2_add: U32Add,
+    /// Addition computing the new `e = d + t1`.
+    e_add: U32Add,
+    /// Addition computing the new `a = t1 + t2`.
+    a_add: U32Add,
+}
+
+/// Intermediate values of a compression, used to fill the table.
+struct CompressionTrace {
+    /// Message schedule.
+    w: [u32; 64],
+    /// Working variables `a` to `h` at the start of each round.
+    states: [[u32; 8]; 64],
+}
+
+impl CompressionTrace {
+    fn new(event: &Sha256CompressEvent) -> Self {
+        let w = sha256_schedule(&event.src2_val);
+        let mut states = [[0; 8]; 64];
+        let mut vars = event.src1_val;
+        for (round, state) in states.iter_mut().enumerate() {
+            *state = vars;
+            let [a, b, c, d, e, f, g, h] = vars;
+            let ch = (e & f) ^ (!e & g);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t1 = h
+                .wrapping_add(sigma(BIG_SIGMA1, e))
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[round])
+                .wrapping_add(w[round]);
+            let t2 = sigma(BIG_SIGMA0, a).wrapping_add(maj);
+            vars = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+        }
+
+        Self { w, states }
+    }
+}
+
+/// SHA256_COMPRESS table.
+///
+/// This table handles the SHA256_COMPRESS instruction, which applies the
+/// SHA-256 compression function to an 8-word state and a 16-word message
+/// block. Each row carries out a full compression: the 48 remaining words of
+/// the message schedule, the 64 rounds and the final additions to the input
+/// state.
+///
+/// Bitwise operations are carried out on unpacked 32-bit columns: rotations
+/// and shifts are shifted columns, XORs are additions and ANDs are
+/// multiplications. Additions modulo 2^32 use the `U32Add` gadget.
+pub struct Sha256CompressTable {
+    id: TableId,
+    state_cols: StateColumns<SHA256_COMPRESS_OPCODE>,
+    dst_addresses: [Col<B32>; 8],
+    src1_addresses: [Col<B32>; 8],
+    /// Input state.
+    src1_vals: [Col<B1, 32>; 8],
+    src2_addresses: [Col<B32>; 16],
+    /// Message block.
+    src2_vals: [Col<B1, 32>; 16],
+    schedule: Vec<ScheduleColumns>,
+    rounds: Vec<RoundColumns>,
+    /// Additions of the input state and the working variables.
+    out_adds: [U32Add; 8],
+}
+
+impl Table for Sha256CompressTable {
+    type Event = Sha256CompressEvent;
+
+    fn name(&self) -> &'static str {
+        "Sha256Compress"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("Sha256Compress");
+
+        let Channels {
+            state_channel,
+            prom_channel,
+            vrom_channel,
+            ..
+        } = *channels;
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            state_channel,
+            prom_channel,
+            StateColumnsOptions {
+                next_pc: NextPc::Increment,
+                next_fp: None,
+                next_timestamp: None,
+            },
+        );
+
+        // Pull the input state and the message block from the VROM.
+        let src1_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg1),
+            "src1_addr",
+        );
+        let src1_vals: [Col<B1, 32>; 8] = from_fn(|i| table.add_committed(format!("src1_val_{i}")));
+        let src2_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg2),
+            "src2_addr",
+        );
+        let src2_vals: [Col<B1, 32>; 16] =
+            from_fn(|i| table.add_committed(format!("src2_val_{i}")));
+
+        for i in 0..8 {
+            let packed = table.add_packed(format!("src1_val_packed_{i}"), src1_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [src1_addresses[i], packed]);
+        }
+        for i in 0..16 {
+            let packed = table.add_packed(format!("src2_val_packed_{i}"), src2_vals[i]);
+            pull_vrom_channel(&mut table, vrom_channel, [src2_addresses[i], packed]);
+        }
+
+        // Expand the message schedule.
+        let mut w = src2_vals.to_vec();
+        let schedule = (16..64)
+            .map(|i| {
+                let sigma0 = SigmaColumns::new(
+                    &mut table,
+                    &format!("w_{i}_sigma0"),
+                    w[i - 15],
+                    SMALL_SIGMA0,
+                );
+                let sigma1 =
+                    SigmaColumns::new(&mut table, &format!("w_{i}_sigma1"), w[i - 2], SMALL_SIGMA1);
+                let add0 =
+                    U32Add::new(&mut table, w[i - 16], sigma0.output, U32AddFlags::default());
+                let add1 = U32Add::new(&mut table, add0.zout, w[i - 7], U32AddFlags::default());
+                let add2 =
+                    U32Add::new(&mut table, add1.zout, sigma1.output, U32AddFlags::default());
+                w.push(add2.zout);
+
+                ScheduleColumns {
+                    sigma0,
+                    sigma1,
+                    adds: [add0, add1, add2],
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Carry out the rounds.
+        let mut vars = src1_vals;
+        let rounds = (0..64)
+            .map(|i| {
+                let [a, b, c, d, e, f, g, h] = vars;
+
+                let sigma1 =
+                    SigmaColumns::new(&mut table, &format!("round_{i}_sigma1"), e, BIG_SIGMA1);
+                // ch = (e & f) ^ (!e & g) = g + e * f + e * g
+                let ch = table.add_committed(format!("round_{i}_ch"));
+                table.assert_zero(format!("round_{i}_ch"), ch - g - (e * f) - (e * g));
+                let k = table.add_constant(
+                    format!("round_{i}_k"),
+                    from_fn(|j| {
+                        if (SHA256_K[i] >> j) & 1 == 1 {
+                            B1::ONE
+                        } else {
+                            B1::ZERO
+                        }
+                    }),
+                );
+
+                let add_h = U32Add::new(&mut table, h, sigma1.output, U32AddFlags::default());
+                let add_ch = U32Add::new(&mut table, add_h.zout, ch, U32AddFlags::default());
+                let add_k = U32Add::new(&mut table, add_ch.zout, k, U32AddFlags::default());
+                let add_w = U32Add::new(&mut table, add_k.zout, w[i], U32AddFlags::default());
+                let t1 = add_w.zout;
+
+                let sigma0 =
+                    SigmaColumns::new(&mut table, &format!("round_{i}_sigma0"), a, BIG_SIGMA0);
+                // maj = (a & b) ^ (a & c) ^ (b & c)
+                let maj = table.add_committed(format!("round_{i}_maj"));
+                table.assert_zero(format!("round_{i}_maj"), maj - (a * b) - (a * c) - (b * c));
+                let t2_add = U32Add::new(&mut table, sigma0.output, maj, U32AddFlags::default());
+
+                let e_add = U32Add::new(&mut table, d, t1, U32AddFlags::default());
+                let a_add = U32Add::new(&mut table, t1, t2_add.zout, U32AddFlags::default());
+
+                vars = [a_add.zout, a, b, c, e_add.zout, e, f, g];
+
+                RoundColumns {
+                    sigma0,
+                    sigma1,
+                    ch,
+                    maj,
+                    k,
+                    t1_adds: [add_h, add_ch, add_k, add_w],
+                    t2_add,
+                    e_add,
+                    a_add,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Add the working variables to the input state and write the result.
+        let out_adds: [U32Add; 8] =
+            from_fn(|i| U32Add::new(&mut table, src1_vals[i], vars[i], U32AddFlags::default()));
+        let dst_addresses = get_all_addresses(
+            &mut table,
+            state_cols.fp + upcast_col(state_cols.arg0),
+            "dst_addr",
+        );
+        for i in 0..8 {
+            let packed = table.add_packed(format!("dst_val_packed_{i}"), out_adds[i].zout);
+            pull_vrom_channel(&mut table, vrom_channel, [dst_addresses[i], packed]);
+        }
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_addresses,
+            src1_addresses,
+            src1_vals,
+            src2_addresses,
+            src2_vals,
+            schedule,
+            rounds,
+            out_adds,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for Sha256CompressTable {
+    type Event = Sha256CompressEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> Result<(), anyhow::Error> {
+        let traces = rows.clone().map(CompressionTrace::new).collect::<Vec<_>>();
+
+        {
+            let mut dst_addresses = (0..8)
+                .map(|i| witness.get_mut_as(self.dst_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src1_addresses = (0..8)
+                .map(|i| witness.get_mut_as(self.src1_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src1_vals = (0..8)
+                .map(|i| witness.get_mut_as(self.src1_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src2_addresses = (0..16)
+                .map(|i| witness.get_mut_as(self.src2_addresses[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut src2_vals = (0..16)
+                .map(|i| witness.get_mut_as(self.src2_vals[i]))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut ch = self
+                .rounds
+                .iter()
+                .map(|round| witness.get_mut_as(round.ch))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut maj = self
+                .rounds
+                .iter()
+                .map(|round| witness.get_mut_as(round.maj))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+            let mut k = self
+                .rounds
+                .iter()
+                .map(|round| witness.get_mut_as(round.k))
+                .collect::<Result<Vec<RefMut<'_, [u32]>>, _>>()?;
+
+            for (i, (event, trace)) in rows.clone().zip(&traces).enumerate() {
+                for j in 0..8 {
+                    dst_addresses[j][i] = event.fp.addr(event.dst + j as u16);
+                    src1_addresses[j][i] = event.fp.addr(event.src1 + j as u16);
+                    src1_vals[j][i] = event.src1_val[j];
+                }
+                for j in 0..16 {
+                    src2_addresses[j][i] = event.fp.addr(event.src2 + j as u16);
+                    src2_vals[j][i] = event.src2_val[j];
+                }
+                for (round, &[a, b, c, _, e, f, g, _]) in trace.states.iter().enumerate() {
+                    ch[round][i] = (e & f) ^ (!e & g);
+                    maj[round][i] = (a & b) ^ (a & c) ^ (b & c);
+                    k[round][i] = SHA256_K[round];
+                }
+            }
+        }
+
+        // Populate the sigma functions of the message schedule and the rounds.
+        for (j, schedule) in self.schedule.iter().enumerate() {
+            let i = j + 16;
+            schedule
+                .sigma0
+                .populate(witness, traces.iter().map(|trace| trace.w[i - 15]))?;
+            schedule
+                .sigma1
+                .populate(witness, traces.iter().map(|trace| trace.w[i - 2]))?;
+        }
+        for (i, round) in self.rounds.iter().enumerate() {
+            round
+                .sigma0
+                .populate(witness, traces.iter().map(|trace| trace.states[i][0]))?;
+            round
+                .sigma1
+                .populate(witness, traces.iter().map(|trace| trace.states[i][4]))?;
+        }
+
+        // Populate the additions in the order they were created, as each one may
+        // depend on the outputs of the previous ones.
+        for schedule in &self.schedule {
+            for add in &schedule.adds {
+                add.populate(witness)?;
+            }
+        }
+        for round in &self.rounds {
+            for add in round
+                .t1_adds
+                .iter()
+                .chain([&round.t2_add, &round.e_add, &round.a_add])
+            {
+                add.populate(witness)?;
+            }
+        }
+        for add in &self.out_adds {
+            add.populate(witness)?;
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.into(),
+            next_pc: None,
+            fp: *event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+    use proptest::prelude::*;
+
+    use crate::{prover::Prover, test_utils::generate_sha256_ret_trace};
+
+    fn test_sha256_with_values(state: [u32; 8], block: [u32; 16]) -> Result<()> {
+        let trace = generate_sha256_ret_trace(state, block)?;
+        trace.validate()?;
+        assert_eq!(trace.sha256_compress_events().len(), 1);
+        Prover::new(Box::new(GenericISA)).validate_witness(&trace)
+    }
+
+    proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(5))]
+
+        #[test]
+        fn test_sha256(
+            state in any::<[u32; 8]>(),
+            block in any::<[u32; 16]>(),
+        ) {
+            prop_assert!(test_sha256_with_values(state, block).is_ok());
+        }
+    }
+}
@@ -2,11 +2,15 @@ use binius_core::oracle::ShiftVariant;
 use binius_field::Field;
 use binius_m3::{
     builder::{
-        upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B1, B32,
+        upcast_col, Col, ConstraintSystem, TableBuilder, TableFiller, TableId, TableWitnessSegment,
+        B1, B32,
     },
     gadgets::barrel_shifter::BarrelShifter,
 };
-use petravm_asm::{Opcode, SllEvent, SlliEvent, SraEvent, SraiEvent, SrlEvent, SrliEvent};
+use petravm_asm::{
+    Opcode, RolEvent, RoliEvent, RorEvent, RoriEvent, SllEvent, SlliEvent, SraEvent, SraiEvent,
+    SrlEvent, SrliEvent,
+};
 
 use crate::{
     channels::Channels,
@@ -99,6 +103,7 @@ impl TableFiller<ProverPackedField> for SrliTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.src,
             arg2: ev.shift_amount as u16,
@@ -193,6 +198,7 @@ impl TableFiller<ProverPackedField> for SlliTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.src,
             arg2: ev.shift_amount as u16,
@@ -300,6 +306,7 @@ impl TableFiller<ProverPackedField> for SrlTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.src,
             arg2: ev.shift,
@@ -419,6 +426,7 @@ impl TableFiller<ProverPackedField> for SllTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.src,
             arg2: ev.shift,
@@ -625,6 +633,7 @@ impl TableFiller<ProverPackedField> for SraTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.src,
             arg2: ev.shift,
@@ -815,6 +824,7 @@ impl TableFiller<ProverPackedField> for SraiTable {
             pc: ev.pc.val(),
             next_pc: None,
             fp: *ev.fp,
+            timestamp: ev.timestamp,
             arg0: ev.dst,
             arg1: ev.src,
             arg2: ev.shift_amount as u16,
@@ -823,6 +833,332 @@ impl TableFiller<ProverPackedField> for SraiTable {
     }
 }
 
+/// Source of the rotation amount of a rotate instruction.
+#[derive(Clone, Copy)]
+enum RotateSource {
+    Immediate,
+    Vrom,
+}
+
+/// Direction of a rotate instruction.
+#[derive(Clone, Copy)]
+enum RotateDirection {
+    Left,
+    Right,
+}
+
+/// Columns reading the rotation amount from VROM.
+struct VromShiftColumns {
+    shift_abs: Col<B32>,
+    shift_amount_unpacked: Col<B1, 32>,
+    shift_amount_low: Col<B1, 16>,
+}
+
+/// Row data for the [`RotateColumns`] gadget.
+struct RotateRow {
+    pc: u32,
+    fp: u32,
+    timestamp: u32,
+    dst: u16,
+    dst_abs: u32,
+    src: u16,
+    src_abs: u32,
+    src_val: u32,
+    shift: u16,
+    shift_abs: u32,
+    shift_amount: u32,
+}
+
+/// Columns shared by the rotate tables.
+///
+/// The rotation is computed by a [`BarrelShifter`] in its circular variant,
+/// which only rotates left. A right rotation by `s` is computed as a left
+/// rotation by one followed by a left rotation by `!s`, since `1 + !s = -s`
+/// modulo 32.
+struct RotateColumns<const OPCODE: u16> {
+    state_cols: StateColumns<OPCODE>,
+    shifter: BarrelShifter,
+    dst_abs: Col<B32>,
+    src_abs: Col<B32>,
+    src_val_unpacked: Col<B1, 32>,
+    vrom_shift: Option<VromShiftColumns>,
+    // Source rotated left by one and inverted rotation amount, for right
+    // rotations.
+    right: Option<(Col<B1, 32>, Col<B1, 16>)>,
+}
+
+impl<const OPCODE: u16> RotateColumns<OPCODE> {
+    fn new(
+        table: &mut TableBuilder,
+        channels: &Channels,
+        source: RotateSource,
+        direction: RotateDirection,
+    ) -> Self {
+        let state_cols = StateColumns::new(
+            table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        // Source value columns
+        let src_val_unpacked: Col<B1, 32> = table.add_committed("src_val_unpacked");
+        let src_val: Col<B32> = table.add_packed("src_val", src_val_unpacked);
+
+        // Address calculations
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let src_abs = table.add_computed("src_abs", state_cols.fp + upcast_col(state_cols.arg1));
+
+        // Rotation amount columns
+        let (shift_amount, vrom_shift) = match source {
+            RotateSource::Immediate => (state_cols.arg2_unpacked, None),
+            RotateSource::Vrom => {
+                let shift_abs =
+                    table.add_computed("shift_abs", state_cols.fp + upcast_col(state_cols.arg2));
+                let shift_amount_unpacked: Col<B1, 32> =
+                    table.add_committed("shift_amount_unpacked");
+                let shift_amount_packed: Col<B32> =
+                    table.add_packed("shift_amount", shift_amount_unpacked);
+                let shift_amount_low: Col<B1, 16> =
+                    table.add_selected_block("shift_amount_low", shift_amount_unpacked, 0);
+                pull_vrom_channel(
+                    table,
+                    channels.vrom_channel,
+                    [shift_abs, shift_amount_packed],
+                );
+
+                let vrom_shift = VromShiftColumns {
+                    shift_abs,
+                    shift_amount_unpacked,
+                    shift_amount_low,
+                };
+                (shift_amount_low, Some(vrom_shift))
+            }
+        };
+
+        // Barrel shifter for the actual rotation
+        let (shifter_input, shifter_amount, right) = match direction {
+            RotateDirection::Left => (src_val_unpacked, shift_amount, None),
+            RotateDirection::Right => {
+                let src_rotated = table.add_shifted(
+                    "src_rotated",
+                    src_val_unpacked,
+                    5,
+                    1,
+                    ShiftVariant::CircularLeft,
+                );
+                let inverted_shift = table.add_computed("inverted_shift", shift_amount + B1::ONE);
+                (
+                    src_rotated,
+                    inverted_shift,
+                    Some((src_rotated, inverted_shift)),
+                )
+            }
+        };
+        let shifter = BarrelShifter::new(
+            table,
+            shifter_input,
+            shifter_amount,
+            ShiftVariant::CircularLeft,
+        );
+        let dst_val = table.add_packed("dst_val", shifter.output);
+
+        // Pull memory access data from VROM channel
+        pull_vrom_channel(table, channels.vrom_channel, [dst_abs, dst_val]);
+        pull_vrom_channel(table, channels.vrom_channel, [src_abs, src_val]);
+
+        Self {
+            state_cols,
+            shifter,
+            dst_abs,
+            src_abs,
+            src_val_unpacked,
+            vrom_shift,
+            right,
+        }
+    }
+
+    fn populate(
+        &self,
+        witness: &mut TableWitnessSegment<ProverPackedField>,
+        rows: impl Iterator<Item = RotateRow> + Clone,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src_abs = witness.get_scalars_mut(self.src_abs)?;
+            let mut src_unpacked = witness.get_mut_as(self.src_val_unpacked)?;
+
+            for (i, row) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(row.dst_abs);
+                src_abs[i] = B32::new(row.src_abs);
+                src_unpacked[i] = row.src_val;
+            }
+        }
+
+        if let Some(vrom_shift) = &self.vrom_shift {
+            let mut shift_abs = witness.get_scalars_mut(vrom_shift.shift_abs)?;
+            let mut shift_unpacked = witness.get_mut_as(vrom_shift.shift_amount_unpacked)?;
+            let mut shift_amount_low = witness.get_mut_as(vrom_shift.shift_amount_low)?;
+
+            for (i, row) in rows.clone().enumerate() {
+                shift_abs[i] = B32::new(row.shift_abs);
+                shift_unpacked[i] = row.shift_amount;
+                shift_amount_low[i] = row.shift_amount as u16;
+            }
+        }
+
+        if let Some((src_rotated, inverted_shift)) = self.right {
+            let mut src_rotated = witness.get_mut_as(src_rotated)?;
+            let mut inverted_shift = witness.get_mut_as(inverted_shift)?;
+
+            for (i, row) in rows.clone().enumerate() {
+                src_rotated[i] = row.src_val.rotate_left(1);
+                inverted_shift[i] = !(row.shift_amount as u16);
+            }
+        }
+
+        // Immediate rotations hold their amount in the third argument.
+        let immediate = self.vrom_shift.is_none();
+        let state_rows = rows.map(|row| StateGadget {
+            pc: row.pc,
+            next_pc: None,
+            fp: row.fp,
+            timestamp: row.timestamp,
+            arg0: row.dst,
+            arg1: row.src,
+            arg2: if immediate {
+                row.shift_amount as u16
+            } else {
+                row.shift
+            },
+        });
+        self.state_cols.populate(witness, state_rows)?;
+
+        // Populate barrel shifter columns
+        self.shifter.populate(witness)
+    }
+}
+
+/// Defines a table for a rotate instruction on top of [`RotateColumns`].
+macro_rules! define_rotate_table {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $event:ty,
+        $opcode:path,
+        $table_name:literal,
+        $source:ident,
+        $direction:ident
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            id: TableId,
+            rotate_cols: RotateColumns<{ $opcode as u16 }>,
+        }
+
+        impl Table for $name {
+            type Event = $event;
+
+            fn name(&self) -> &'static str {
+                stringify!($name)
+            }
+
+            fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+                let mut table = cs.add_table($table_name);
+
+                let rotate_cols = RotateColumns::new(
+                    &mut table,
+                    channels,
+                    RotateSource::$source,
+                    RotateDirection::$direction,
+                );
+
+                Self {
+                    id: table.id(),
+                    rotate_cols,
+                }
+            }
+        }
+
+        impl TableFiller<ProverPackedField> for $name {
+            type Event = $event;
+
+            fn id(&self) -> TableId {
+                self.id
+            }
+
+            fn fill<'a>(
+                &'a self,
+                rows: impl Iterator<Item = &'a Self::Event> + Clone,
+                witness: &'a mut TableWitnessSegment<ProverPackedField>,
+            ) -> anyhow::Result<()> {
+                let rotate_rows = rows.map(|ev| RotateRow {
+                    pc: ev.pc.val(),
+                    fp: *ev.fp,
+                    timestamp: ev.timestamp,
+                    dst: ev.dst,
+                    dst_abs: ev.fp.addr(ev.dst),
+                    src: ev.src,
+                    src_abs: ev.fp.addr(ev.src),
+                    src_val: ev.src_val,
+                    shift: ev.shift,
+                    shift_abs: ev.fp.addr(ev.shift),
+                    shift_amount: ev.shift_amount,
+                });
+                self.rotate_cols.populate(witness, rotate_rows)
+            }
+        }
+    };
+}
+
+define_rotate_table!(
+    /// Table for ROLI.
+    ///
+    /// Rotates the source value left by an immediate amount.
+    RoliTable,
+    RoliEvent,
+    Opcode::Roli,
+    "roli",
+    Immediate,
+    Left
+);
+
+define_rotate_table!(
+    /// Table for RORI.
+    ///
+    /// Rotates the source value right by an immediate amount.
+    RoriTable,
+    RoriEvent,
+    Opcode::Rori,
+    "rori",
+    Immediate,
+    Right
+);
+
+define_rotate_table!(
+    /// Table for ROL.
+    ///
+    /// Rotates the source value left by an amount read from VROM.
+    RolTable,
+    RolEvent,
+    Opcode::Rol,
+    "rol",
+    Vrom,
+    Left
+);
+
+define_rotate_table!(
+    /// Table for ROR.
+    ///
+    /// Rotates the source value right by an amount read from VROM.
+    RorTable,
+    RorEvent,
+    Opcode::Ror,
+    "ror",
+    Vrom,
+    Right
+);
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -848,6 +1184,10 @@ mod tests {
             SLL  @7, @2, @3 \n\
             SRAI @8, @2, #{imm}\n\
             SRA  @9, @2, @3 \n\
+            ROLI @10, @2, #{imm}\n\
+            ROL  @11, @2, @3 \n\
+            RORI @12, @2, #{imm}\n\
+            ROR  @13, @2, @3 \n\
             RET\n"
         );
 
@@ -867,6 +1207,10 @@ mod tests {
         assert_eq!(trace.sll_events().len(), 1);
         assert_eq!(trace.srai_events().len(), 1);
         assert_eq!(trace.sra_events().len(), 1);
+        assert_eq!(trace.roli_events().len(), 1);
+        assert_eq!(trace.rol_events().len(), 1);
+        assert_eq!(trace.rori_events().len(), 1);
+        assert_eq!(trace.ror_events().len(), 1);
 
         // Validate the witness
         Prover::new(Box::new(GenericISA)).validate_witness(&trace)
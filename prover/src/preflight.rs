@@ -0,0 +1,164 @@
+//! Preflight estimation of the size of a proof job.
+//!
+//! The time and memory needed to prove an execution grow with the number of
+//! rows of the circuit tables, each table being padded to a power of two.
+//! [`Prover::preflight`](crate::prover::Prover::preflight) computes these
+//! sizes from a trace, without building the witness, so that provers can pick
+//! a machine for a job, or reject it, before spending time proving it.
+
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+
+use crate::budget::WitnessMemory;
+
+/// Number of rows of a table of the circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableSize {
+    /// Name of the table.
+    pub name: String,
+    /// Number of rows filled from the trace.
+    pub rows: usize,
+}
+
+impl TableSize {
+    pub(crate) fn new(name: &str, rows: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            rows,
+        }
+    }
+
+    /// Returns the number of rows of the table once padded to a power of two,
+    /// empty tables costing nothing.
+    pub const fn padded_rows(&self) -> usize {
+        if self.rows == 0 {
+            0
+        } else {
+            self.rows.next_power_of_two()
+        }
+    }
+}
+
+/// Sizes of the tables proving an execution, see the [module-level
+/// documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preflight {
+    /// Number of executed instructions.
+    pub cycles: u64,
+    /// Size of each table, in order of table creation.
+    pub tables: Vec<TableSize>,
+    /// Estimated memory of the witness.
+    pub memory: WitnessMemory,
+}
+
+impl Preflight {
+    /// Returns the number of rows filled from the trace, over all tables.
+    pub fn total_rows(&self) -> usize {
+        self.tables.iter().map(|table| table.rows).sum()
+    }
+
+    /// Returns the number of padded rows over all tables, an estimate of the
+    /// proving cost: the work of the prover is roughly linear in it.
+    pub fn padded_rows(&self) -> usize {
+        self.tables.iter().map(TableSize::padded_rows).sum()
+    }
+
+    /// Returns the table with the most rows, if any.
+    pub fn largest_table(&self) -> Option<&TableSize> {
+        self.tables.iter().max_by_key(|table| table.rows)
+    }
+
+    /// Checks that no table exceeds `max_rows` rows once padded, e.g. to
+    /// reject the jobs too large for a prover.
+    pub fn check_max_rows(&self, max_rows: usize) -> Result<()> {
+        match self.largest_table() {
+            Some(table) if table.padded_rows() > max_rows => Err(anyhow!(
+                "Table {} has {} padded rows, exceeding the limit of {max_rows}",
+                table.name,
+                table.padded_rows()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Preflight {
+    /// Lists the non-empty tables by decreasing number of rows, followed by
+    /// the totals and the witness memory.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} cycles", self.cycles)?;
+        let mut tables = self
+            .tables
+            .iter()
+            .filter(|table| table.rows > 0)
+            .collect::<Vec<_>>();
+        tables.sort_by_key(|table| std::cmp::Reverse(table.rows));
+        for table in tables {
+            writeln!(
+                f,
+                "{:<24} {:>12} rows {:>12} padded",
+                table.name,
+                table.rows,
+                table.padded_rows()
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<24} {:>12} rows {:>12} padded",
+            "Total",
+            self.total_rows(),
+            self.padded_rows()
+        )?;
+        write!(f, "{}", self.memory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use petravm_asm::isa::GenericISA;
+
+    use super::*;
+    use crate::{
+        circuit::Circuit,
+        prover::Prover,
+        test_utils::{fibonacci, generate_fibonacci_trace},
+    };
+
+    #[test]
+    fn test_preflight() -> Result<()> {
+        let trace = generate_fibonacci_trace(11, fibonacci(11))?;
+        let prover = Prover::new(Box::new(GenericISA));
+        let preflight = prover.preflight(&trace)?;
+
+        // The sizes are those of the proven statement.
+        let statement = Circuit::new(Box::new(GenericISA)).create_statement(&trace)?;
+        assert_eq!(
+            preflight
+                .tables
+                .iter()
+                .map(|table| table.rows)
+                .collect::<Vec<_>>(),
+            statement.table_sizes
+        );
+        assert_eq!(preflight.cycles, trace.cycles());
+        assert_eq!(trace.table_sizes(Box::new(GenericISA))?, preflight.tables);
+
+        let largest = preflight.largest_table().unwrap();
+        assert!(largest.rows <= largest.padded_rows());
+        assert!(preflight.total_rows() <= preflight.padded_rows());
+        preflight.check_max_rows(largest.padded_rows())?;
+        assert!(preflight.check_max_rows(largest.padded_rows() - 1).is_err());
+        assert!(preflight.to_string().contains("PromTable"));
+        assert_eq!(preflight.memory, prover.estimate_memory(&trace)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_padded_rows() {
+        assert_eq!(TableSize::new("empty", 0).padded_rows(), 0);
+        assert_eq!(TableSize::new("one", 1).padded_rows(), 1);
+        assert_eq!(TableSize::new("odd", 5).padded_rows(), 8);
+        assert_eq!(TableSize::new("full", 64).padded_rows(), 64);
+    }
+}
@@ -3,46 +3,177 @@
 //! This module provides the main entry point for creating proofs from
 //! PetraVM execution traces.
 
+use std::sync::Arc;
+
 use anyhow::{anyhow, Result};
 use binius_compute::{alloc::HostBumpAllocator, cpu::alloc::CpuComputeAllocator, ComputeHolder};
 use binius_core::{
-    constraint_system::{prove, verify, ConstraintSystem, Proof},
+    constraint_system::{channel::ChannelId, prove, ConstraintSystem, Proof},
     fiat_shamir::HasherChallenger,
 };
 use binius_fast_compute::layer::FastCpuLayerHolder;
 use binius_field::arch::OptimalUnderlier;
 use binius_field::tower::CanonicalTowerFamily;
 use binius_hal::make_portable_backend;
-use binius_hash::groestl::{Groestl256, Groestl256ByteCompression};
-use binius_m3::builder::{WitnessIndex, B128};
-use petravm_asm::isa::ISA;
+use binius_m3::builder::{
+    Boundary, ConstraintSystem as M3ConstraintSystem, FlushDirection, WitnessIndex, B128,
+};
+use petravm_asm::{isa::ISA, AssembledProgram, ProgramRom};
+use petravm_verifier::verify_with_compiled_cs;
+pub use petravm_verifier::{verify, FiatShamirHash, ProverConfig, VerifyingKey};
+use rayon::prelude::*;
 use tracing::instrument;
 
 use crate::types::Statement;
-use crate::{circuit::Circuit, model::Trace, types::ProverPackedField};
+use crate::{
+    budget::WitnessMemory,
+    cache::ProvingCache,
+    channels::Channels,
+    circuit::{BatchCircuit, Circuit, CircuitInstance},
+    model::{call_targets, Claim, Trace},
+    preflight::Preflight,
+    table::ProvableCustomInstruction,
+    telemetry::{FillRecorder, ProvingSummary, Stage},
+    types::ProverPackedField,
+};
 
-const LOG_INV_RATE: usize = 1;
-const SECURITY_BITS: usize = 100;
 #[cfg(not(feature = "disable_prom_channel"))]
 pub(crate) const PROM_MULTIPLICITY_BITS: usize = 32;
 #[cfg(not(feature = "disable_vrom_channel"))]
 pub(crate) const VROM_MULTIPLICITY_BITS: usize = 8;
+pub(crate) const RANGE_CHECK_MULTIPLICITY_BITS: usize = 32;
+pub(crate) const CALL_TARGET_MULTIPLICITY_BITS: usize = 32;
 
 /// Main prover for PetraVM.
 pub struct Prover {
     /// Arithmetic circuit for PetraVM
     circuit: Circuit,
+    /// Proving parameters
+    config: ProverConfig,
+    /// Maximum number of bytes of the witness, if any
+    memory_limit: Option<usize>,
+    /// Cache of the compiled constraint system and program boundaries, if any
+    cache: Option<Arc<ProvingCache>>,
 }
 
 impl Prover {
-    /// Create a new PetraVM prover.
+    /// Create a new PetraVM prover, with the default [`ProverConfig`].
     pub fn new(isa: Box<dyn ISA>) -> Self {
         Self {
             circuit: Circuit::new(isa),
+            config: ProverConfig::default(),
+            memory_limit: None,
+            cache: None,
         }
     }
 
-    #[instrument(level = "info", skip_all)]
+    /// Sets the proving parameters.
+    pub const fn with_config(mut self, config: ProverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns the proving parameters.
+    pub const fn config(&self) -> &ProverConfig {
+        &self.config
+    }
+
+    /// Limits the memory of the witness to `bytes`, so that traces too large
+    /// to be proven are rejected before filling the witness.
+    pub const fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Memoizes the compiled constraint system and the program boundaries of
+    /// the statements in `cache`, which may be shared with other provers.
+    pub fn with_cache(mut self, cache: Arc<ProvingCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Returns the compiled constraint system of the circuit, from the cache
+    /// if any.
+    fn compiled_cs(&self) -> Result<ConstraintSystem<B128>> {
+        self.compile(&self.circuit.cs, || self.circuit.digest())
+    }
+
+    /// Compiles the constraint system `cs`, of digest `digest`, or returns it
+    /// from the cache if any.
+    fn compile(
+        &self,
+        cs: &M3ConstraintSystem,
+        digest: impl FnOnce() -> [u8; 32],
+    ) -> Result<ConstraintSystem<B128>> {
+        let compile = || cs.compile().map_err(|e| anyhow!(e));
+        match &self.cache {
+            Some(cache) => {
+                let compiled_cs = cache.compiled_cs(digest(), self.config, compile)?;
+                Ok(compiled_cs.as_ref().clone())
+            }
+            None => compile(),
+        }
+    }
+
+    /// Creates the statement proving `trace`, with the program boundaries
+    /// from the cache if any.
+    pub fn create_statement(&self, trace: &Trace) -> Result<Statement> {
+        match &self.cache {
+            Some(cache) => {
+                let program_boundaries =
+                    cache.program_boundaries(self.circuit.digest(), &trace.program, || {
+                        self.circuit.trace_program_boundaries(trace)
+                    })?;
+                self.circuit
+                    .create_statement_with_program(trace, program_boundaries.to_vec())
+            }
+            None => self.circuit.create_statement(trace),
+        }
+    }
+
+    /// Estimates the memory of the witness proving `trace`, see
+    /// [`WitnessMemory`].
+    pub fn estimate_memory(&self, trace: &Trace) -> Result<WitnessMemory> {
+        Ok(WitnessMemory::estimate(
+            &self.circuit.cs,
+            &self.circuit.table_sizes(trace)?,
+        ))
+    }
+
+    /// Returns the capacity of the arena holding the witness proving `trace`,
+    /// checking that it fits in the memory limit.
+    fn witness_capacity(&self, trace: &Trace) -> Result<usize> {
+        self.arena_capacity(self.estimate_memory(trace)?)
+    }
+
+    /// Returns the capacity of the arena holding a witness of estimated
+    /// memory `memory`, checking that it fits in the memory limit.
+    fn arena_capacity(&self, memory: WitnessMemory) -> Result<usize> {
+        tracing::info!("Witness memory: {memory}");
+        if let Some(limit) = self.memory_limit {
+            memory.check_limit(limit)?;
+        }
+        Ok(memory.arena_capacity())
+    }
+
+    /// Adds the table of a custom instruction to the circuit, so that the
+    /// prover can prove executions using it.
+    ///
+    /// Verifiers must register the same custom instructions, in the same
+    /// order, to derive the same verification key.
+    pub fn with_custom_instruction<I: ProvableCustomInstruction>(
+        mut self,
+        instruction: &I,
+    ) -> Self {
+        self.circuit.add_custom_instruction(instruction);
+        self
+    }
+
+    /// Generates the witness of the circuit for the given trace.
+    ///
+    /// The rows of the auxiliary tables are computed concurrently, and the
+    /// segments of each table are filled in parallel, except for the tables
+    /// with structured columns, whose values depend on the global row index.
     pub fn generate_witness<'a>(
         &self,
         trace: &Trace,
@@ -50,40 +181,198 @@ impl Prover {
     ) -> Result<WitnessIndex<'_, 'a, ProverPackedField>> {
         // Build the witness structure
         let mut witness = WitnessIndex::new(&self.circuit.cs, allocator);
+        fill_witness(
+            &self.circuit.instance,
+            &mut witness,
+            trace,
+            &mut FillRecorder::default(),
+        )?;
+        Ok(witness)
+    }
 
-        // Fill all table witnesses in sequence
+    /// Returns the verification key associated to this prover.
+    ///
+    /// The key can be exported with [`VerifyingKey::to_bytes`], to verify
+    /// proofs with the standalone [`petravm_verifier`] crate.
+    pub fn verifying_key(&self) -> Result<VerifyingKey> {
+        let compiled_cs = self.compiled_cs()?;
+        Ok(VerifyingKey {
+            compiled_cs,
+            config: self.config,
+        })
+    }
 
-        // 1. Fill PROM table with program instructions
-        witness.fill_table_parallel(&self.circuit.prom_table, &trace.program)?;
+    /// Returns the channel boundaries that a proof generated by this prover
+    /// must satisfy, for an execution of `program` from the initial VROM
+    /// values `init_values`, without public values, exiting with code 0
+    /// without accessing the RAM.
+    pub fn boundaries(&self, program: &ProgramRom, init_values: &[u32]) -> Vec<Boundary<B128>> {
+        self.circuit.boundaries(program, init_values)
+    }
 
-        // 2. Fill VROM table with VROM addresses and values
-        let vrom_addr_space_size = (trace.max_vrom_addr + 1).next_power_of_two();
-        let mut vrom_with_multiplicities = (0..vrom_addr_space_size)
-            .map(|addr| (addr as u32, 0u32, 0u32))
-            .collect::<Vec<_>>();
-        for &(addr, val, mul) in trace.vrom_writes.iter() {
-            vrom_with_multiplicities[addr as usize] = (addr, val, mul);
+    /// Returns the channel boundaries that a proof generated by this prover
+    /// must satisfy, for an execution of `program` from the initial VROM
+    /// values `init_values`, without public values, exiting with `exit_code`
+    /// at the system timestamp `final_timestamp`, as returned by
+    /// [`Trace::exit_code`] and [`Trace::final_timestamp`].
+    pub fn boundaries_with_exit_code(
+        &self,
+        program: &ProgramRom,
+        init_values: &[u32],
+        exit_code: u32,
+        final_timestamp: u32,
+    ) -> Vec<Boundary<B128>> {
+        self.circuit
+            .boundaries_with_exit_code(program, init_values, exit_code, final_timestamp)
+    }
+
+    /// Returns the channel boundaries that a proof generated by this prover
+    /// must satisfy, for an execution of `program` from the initial VROM
+    /// values `init_values`, exposing `public_values`, as returned by
+    /// [`Trace::public_values`].
+    pub fn boundaries_with_public_values(
+        &self,
+        program: &ProgramRom,
+        init_values: &[u32],
+        public_values: &[(u32, u32)],
+    ) -> Vec<Boundary<B128>> {
+        self.circuit
+            .boundaries_with_public_values(program, init_values, public_values)
+    }
+
+    /// Returns the channel boundaries restricting the indirect calls of a proof
+    /// to the function entry points of `program`, to be added to its other
+    /// boundaries, see [`call_targets`](crate::model::call_targets).
+    pub fn call_target_boundaries(&self, program: &AssembledProgram) -> Vec<Boundary<B128>> {
+        self.circuit.call_target_boundaries(&call_targets(program))
+    }
+
+    /// Returns every channel boundary that a proof generated by this prover
+    /// must satisfy, for an execution of `program` with the outcome `claim`,
+    /// as returned by [`Trace::claim`].
+    ///
+    /// Verifiers should rebuild the boundaries this way, rather than trust
+    /// boundaries received along with a proof.
+    pub fn claim_boundaries(
+        &self,
+        program: &AssembledProgram,
+        claim: &Claim,
+    ) -> Result<Vec<Boundary<B128>>> {
+        self.circuit.claim_boundaries(program, claim)
+    }
+
+    /// Returns the sizes of the tables proving `trace`, without building the
+    /// witness, see [`Preflight`].
+    pub fn preflight(&self, trace: &Trace) -> Result<Preflight> {
+        let tables = self.circuit.table_sizes(trace)?;
+        Ok(Preflight {
+            cycles: trace.cycles(),
+            memory: WitnessMemory::estimate(&self.circuit.cs, &tables),
+            tables,
+        })
+    }
+
+    /// Checks that `boundaries`, e.g. received along with a proof, bind the
+    /// proof to the execution of `program`, whatever its inputs, exit code and
+    /// public values.
+    pub fn check_program(&self, boundaries: &[Boundary<B128>], program: &ProgramRom) -> Result<()> {
+        let channel = self.circuit.channels.program_channel;
+        if channel_values(boundaries, channel)
+            != channel_values(&self.circuit.program_boundaries(program), channel)
+        {
+            return Err(anyhow!("The boundaries are those of another program"));
         }
-        vrom_with_multiplicities.sort_by_key(|(_, _, mul)| *mul);
-        vrom_with_multiplicities.reverse();
-        witness.fill_table_sequential(&self.circuit.vrom_table, &vrom_with_multiplicities)?;
-
-        // 3. Fill the right shifter table
-        witness.fill_table_sequential(
-            &self.circuit.right_shifter_table,
-            trace.right_shift_events(),
-        )?;
+        Ok(())
+    }
 
-        // 4. Fill all event tables
-        for table in &self.circuit.tables {
-            table.fill(&mut witness, trace)?;
+    /// Checks that `boundaries`, e.g. received along with a proof, restrict
+    /// the indirect calls of the proof to the function entry points of
+    /// `program`.
+    pub fn check_call_targets(
+        &self,
+        boundaries: &[Boundary<B128>],
+        program: &AssembledProgram,
+    ) -> Result<()> {
+        let channel = self.circuit.channels.function_channel;
+        if channel_values(boundaries, channel)
+            != channel_values(&self.call_target_boundaries(program), channel)
+        {
+            return Err(anyhow!("The boundaries are those of other call targets"));
         }
+        Ok(())
+    }
 
-        Ok(witness)
+    /// Checks that `boundaries`, e.g. received along with a proof, bind the
+    /// proof to an execution starting from the initial VROM values
+    /// `init_values`, whose commitment is reported in the
+    /// [`BoundaryValues`](petravm_asm::BoundaryValues) of the execution.
+    pub fn check_inputs(&self, boundaries: &[Boundary<B128>], init_values: &[u32]) -> Result<()> {
+        let channel = self.circuit.channels.input_channel;
+        if channel_values(boundaries, channel)
+            != channel_values(&self.circuit.input_boundaries(init_values), channel)
+        {
+            return Err(anyhow!("The boundaries are those of other inputs"));
+        }
+        Ok(())
+    }
+
+    /// Checks that `boundaries`, e.g. received along with a proof, bind the
+    /// proof to an execution starting from the initial RAM `image`, e.g. the
+    /// static data of the program.
+    pub fn check_ram_image(&self, boundaries: &[Boundary<B128>], image: &[u8]) -> Result<()> {
+        let channel = self.circuit.channels.ram_image_channel;
+        if channel_values(boundaries, channel)
+            != channel_values(&self.circuit.ram_image_boundaries(image), channel)
+        {
+            return Err(anyhow!("The boundaries are those of another RAM image"));
+        }
+        Ok(())
+    }
+
+    /// Checks that `boundaries`, e.g. received along with a proof, bind the
+    /// proof to an execution committing the public outputs `outputs`, whose
+    /// commitment is reported in the
+    /// [`BoundaryValues`](petravm_asm::BoundaryValues) of the execution.
+    ///
+    /// The commit channel must be opened at index 0 and closed at the number
+    /// of outputs, at any timestamp, so that the outputs follow the execution
+    /// order of the COMMIT instructions.
+    pub fn check_outputs(&self, boundaries: &[Boundary<B128>], outputs: &[u32]) -> Result<()> {
+        let Channels {
+            output_channel,
+            commit_channel,
+            ..
+        } = self.circuit.channels;
+        let last_timestamp = boundaries
+            .iter()
+            .find(|boundary| {
+                boundary.channel_id == commit_channel
+                    && matches!(boundary.direction, FlushDirection::Pull)
+            })
+            .and_then(|boundary| boundary.values.get(1))
+            .map_or(0, |timestamp| timestamp.val() as u32);
+        let expected = self.circuit.output_boundaries(outputs, last_timestamp);
+        for channel in [output_channel, commit_channel] {
+            if channel_values(boundaries, channel) != channel_values(&expected, channel) {
+                return Err(anyhow!("The boundaries are those of other outputs"));
+            }
+        }
+        Ok(())
     }
 
     /// Prove a PetraVM execution trace.
     ///
+    /// The resulting proof can be checked with [`verify`], given the
+    /// [`VerifyingKey`] and the [`boundaries`](Self::boundaries) of this
+    /// prover.
+    pub fn prove(&self, trace: &Trace) -> Result<Proof> {
+        let (proof, _, _) = self.prove_with_statement(trace)?;
+        Ok(proof)
+    }
+
+    /// Prove a PetraVM execution trace, also returning the proven statement
+    /// and the compiled constraint system.
+    ///
     /// This function:
     /// 1. Creates a statement from the trace
     /// 2. Compiles the constraint system
@@ -96,26 +385,143 @@ impl Prover {
     ///
     /// # Returns
     /// * Result containing the proof, statement, and compiled constraint system
+    pub fn prove_with_statement(
+        &self,
+        trace: &Trace,
+    ) -> Result<(Proof, Statement, ConstraintSystem<B128>)> {
+        let (proof, statement, compiled_cs, _) = self.prove_with_summary(trace)?;
+        Ok((proof, statement, compiled_cs))
+    }
+
+    /// Prove a PetraVM execution trace like
+    /// [`prove_with_statement`](Self::prove_with_statement), also returning
+    /// the times, sizes and memory of the proving stages, see
+    /// [`ProvingSummary`].
     #[instrument(level = "info", skip_all)]
-    pub fn prove(&self, trace: &Trace) -> Result<(Proof, Statement, ConstraintSystem<B128>)> {
-        // Create a statement from the trace
-        let statement = self.circuit.create_statement(trace)?;
+    pub fn prove_with_summary(
+        &self,
+        trace: &Trace,
+    ) -> Result<(Proof, Statement, ConstraintSystem<B128>, ProvingSummary)> {
+        let mut summary = ProvingSummary::default();
+
+        // Create a statement from the trace, and compile the constraint system
+        let stage = Stage::start("Statement");
+        let statement = self.create_statement(trace)?;
+        summary.statement = stage.finish();
+        let stage = Stage::start("Compile");
+        let compiled_cs = self.compiled_cs()?;
+        summary.compile = stage.finish();
+        summary.memory = self.estimate_memory(trace)?;
+        let capacity = self.arena_capacity(summary.memory.clone())?;
+        let mut fills = FillRecorder::default();
+        let (proof, compiled_cs) = self.prove_statement(
+            &self.circuit.cs,
+            compiled_cs,
+            &statement,
+            capacity,
+            &mut summary,
+            |witness| fill_witness(&self.circuit.instance, witness, trace, &mut fills),
+        )?;
+        summary.tables = fills.tables;
+        tracing::info!(
+            elapsed_ms = summary.total().as_millis() as u64,
+            rows = summary.total_rows(),
+            witness_bytes = summary.memory.total_bytes(),
+            "Proof generated"
+        );
+        Ok((proof, statement, compiled_cs, summary))
+    }
 
-        // Compile the constraint system
-        let compiled_cs = self.circuit.cs.compile().map_err(|e| anyhow!(e))?;
+    /// Returns the circuit proving `size` executions at once, to compute the
+    /// boundaries of the proofs of [`prove_batch`](Self::prove_batch).
+    pub fn batch_circuit(&self, size: usize) -> BatchCircuit {
+        self.circuit.batch(size)
+    }
+
+    /// Returns the verification key of the proofs of batches of `size`
+    /// executions, generated by [`prove_batch`](Self::prove_batch).
+    pub fn batch_verifying_key(&self, size: usize) -> Result<VerifyingKey> {
+        let batch = self.batch_circuit(size);
+        let compiled_cs = self.compile(&batch.cs, || self.circuit.batch_digest(&batch))?;
+        Ok(VerifyingKey {
+            compiled_cs,
+            config: self.config,
+        })
+    }
+
+    /// Prove several independent PetraVM execution traces with a single
+    /// proof, see [`BatchCircuit`].
+    ///
+    /// The proof can be checked with [`verify`], given the
+    /// [`batch_verifying_key`](Self::batch_verifying_key) for the number of
+    /// traces and the boundaries of each instance of the
+    /// [`batch_circuit`](Self::batch_circuit), in order.
+    pub fn prove_batch(&self, traces: &[Trace]) -> Result<Proof> {
+        let (proof, _, _) = self.prove_batch_with_statement(traces)?;
+        Ok(proof)
+    }
 
+    /// Prove several independent PetraVM execution traces with a single
+    /// proof, also returning the proven statement and the compiled
+    /// constraint system.
+    #[instrument(level = "info", skip_all, fields(size = traces.len()))]
+    pub fn prove_batch_with_statement(
+        &self,
+        traces: &[Trace],
+    ) -> Result<(Proof, Statement, ConstraintSystem<B128>)> {
+        let batch = self.batch_circuit(traces.len());
+        let statement = batch.create_statement(traces)?;
+        let compiled_cs = self.compile(&batch.cs, || self.circuit.batch_digest(&batch))?;
+        let capacity = self.arena_capacity(WitnessMemory::estimate(
+            &batch.cs,
+            &batch.table_sizes(traces)?,
+        ))?;
+        let (proof, compiled_cs) = self.prove_statement(
+            &batch.cs,
+            compiled_cs,
+            &statement,
+            capacity,
+            &mut ProvingSummary::default(),
+            |witness| {
+                let mut fills = FillRecorder::default();
+                for (instance, trace) in batch.instances.iter().zip(traces) {
+                    fill_witness(instance, witness, trace, &mut fills)?;
+                }
+                Ok(())
+            },
+        )?;
+        Ok((proof, statement, compiled_cs))
+    }
+
+    /// Proves `statement` for the constraint system `cs`, compiled as
+    /// `compiled_cs`, with the witness filled by `fill` in an arena of
+    /// `capacity` packed field elements, returning the proof along with the
+    /// compiled constraint system.
+    ///
+    /// The times of the witness and proving stages are recorded in `summary`.
+    fn prove_statement(
+        &self,
+        cs: &M3ConstraintSystem,
+        compiled_cs: ConstraintSystem<B128>,
+        statement: &Statement,
+        capacity: usize,
+        summary: &mut ProvingSummary,
+        fill: impl FnOnce(&mut WitnessIndex<'_, '_, ProverPackedField>) -> Result<()>,
+    ) -> Result<(Proof, ConstraintSystem<B128>)> {
+        let stage = Stage::start("Witness");
         let witness_allocator_span = tracing::info_span!("Witness Alloc").entered();
 
-        // Create a memory allocator for the witness
-        let mut allocator = CpuComputeAllocator::new(1 << 25);
+        // Create a memory allocator for the witness, sized for the trace
+        let mut allocator = CpuComputeAllocator::new(capacity);
         let allocator = allocator.into_bump_allocator();
 
         drop(witness_allocator_span);
 
-        // Convert witness to multilinear extension format
-        let witness = self
-            .generate_witness(trace, &allocator)?
-            .into_multilinear_extension_index();
+        // Fill the witness, and convert it to multilinear extension format
+        let mut witness = WitnessIndex::new(cs, &allocator);
+        fill(&mut witness)?;
+        let witness = witness.into_multilinear_extension_index();
+        summary.witness = stage.finish();
 
         // Validate the witness against the constraint system in debug mode only
         #[cfg(debug_assertions)]
@@ -126,37 +532,91 @@ impl Prover {
             &witness,
         )?;
 
-        let ccs_digest = compiled_cs.digest::<Groestl256>();
-
+        let stage = Stage::start("Prove");
         let hal_span = tracing::info_span!("HAL Setup").entered();
         let mut compute_holder =
             FastCpuLayerHolder::<CanonicalTowerFamily, ProverPackedField>::new(1 << 20, 1 << 26);
         drop(hal_span);
 
         // Generate the proof
-        let proof = prove::<
-            _,
-            OptimalUnderlier,
-            CanonicalTowerFamily,
-            Groestl256,
-            Groestl256ByteCompression,
-            HasherChallenger<Groestl256>,
-            _,
-            _,
-            _,
-        >(
-            &mut compute_holder.to_data(),
+        let proof = petravm_verifier::with_hash!(self.config, |Hash, Compression| {
+            let ccs_digest = compiled_cs.digest::<Hash>();
+            prove::<
+                _,
+                OptimalUnderlier,
+                CanonicalTowerFamily,
+                Hash,
+                Compression,
+                HasherChallenger<Hash>,
+                _,
+                _,
+                _,
+            >(
+                &mut compute_holder.to_data(),
+                &compiled_cs,
+                self.config.log_inv_rate,
+                self.config.security_bits,
+                &ccs_digest,
+                &statement.boundaries,
+                &statement.table_sizes,
+                witness,
+                &make_portable_backend(),
+            )?
+        });
+        summary.prove = stage.finish();
+
+        Ok((proof, compiled_cs))
+    }
+
+    /// Validates the witness of a PetraVM execution trace, reporting the
+    /// events unbalancing the state channel, if any.
+    ///
+    /// The state flushes of the events are replayed first, the event at some
+    /// index in the trace flushing from the row at the same index of its
+    /// table, so that an imbalance is reported along with the events causing
+    /// it rather than as a raw constraint system error. The witness is then
+    /// validated against the constraint system.
+    #[instrument(level = "info", skip_all)]
+    pub fn diagnose_witness(&self, trace: &Trace) -> Result<()> {
+        #[cfg(not(feature = "disable_state_channel"))]
+        {
+            use binius_field::Field;
+            use binius_m3::builder::B32;
+            use petravm_asm::execution::{Flush, FlushDirection, FlushOrigin, FlushRecorder};
+
+            let mut recorder = FlushRecorder::new("state");
+            recorder.record(Flush {
+                value: (B32::ONE, 0, 0),
+                direction: FlushDirection::Push,
+                origin: FlushOrigin::boundary(),
+            });
+            recorder.record(Flush {
+                value: (B32::ZERO, trace.exit_code(), trace.final_timestamp()),
+                direction: FlushDirection::Pull,
+                origin: FlushOrigin::boundary(),
+            });
+            for flush in trace.trace.state_flushes() {
+                recorder.record(flush);
+            }
+            recorder.check(|&(_, fp, timestamp)| (timestamp, fp))?;
+        }
+
+        let statement = self.circuit.create_statement(trace)?;
+        let compiled_cs = self.compiled_cs()?;
+
+        let mut allocator = CpuComputeAllocator::new(self.witness_capacity(trace)?);
+        let allocator = allocator.into_bump_allocator();
+        let witness = self
+            .generate_witness(trace, &allocator)?
+            .into_multilinear_extension_index();
+
+        binius_core::constraint_system::validate::validate_witness(
             &compiled_cs,
-            LOG_INV_RATE,
-            SECURITY_BITS,
-            &ccs_digest,
             &statement.boundaries,
             &statement.table_sizes,
-            witness,
-            &make_portable_backend(),
-        )?;
-
-        Ok((proof, statement, compiled_cs))
+            &witness,
+        )
+        .map_err(|e| anyhow!("Invalid witness, with balanced state flushes: {e}"))
     }
 
     /// Validate a PetraVM execution trace.
@@ -166,7 +626,7 @@ impl Prover {
         let statement = self.circuit.create_statement(trace)?;
 
         // Create a memory allocator for the witness
-        let mut allocator = CpuComputeAllocator::new(1 << 25);
+        let mut allocator = CpuComputeAllocator::new(self.witness_capacity(trace)?);
         let allocator = allocator.into_bump_allocator();
 
         // Fill all table witnesses in sequence
@@ -180,9 +640,195 @@ impl Prover {
 
         Ok(())
     }
+
+    /// Validate a PetraVM execution trace against the statement `boundaries`
+    /// rather than those derived from the trace.
+    #[cfg(test)]
+    pub(crate) fn validate_witness_with_boundaries(
+        &self,
+        trace: &Trace,
+        boundaries: &[Boundary<B128>],
+    ) -> Result<()> {
+        let statement = self.circuit.create_statement(trace)?;
+        let compiled_cs = self.compiled_cs()?;
+
+        let mut allocator = CpuComputeAllocator::new(self.witness_capacity(trace)?);
+        let allocator = allocator.into_bump_allocator();
+        let witness = self
+            .generate_witness(trace, &allocator)?
+            .into_multilinear_extension_index();
+
+        binius_core::constraint_system::validate::validate_witness(
+            &compiled_cs,
+            boundaries,
+            &statement.table_sizes,
+            &witness,
+        )
+        .map_err(|e| anyhow!("Invalid witness: {e}"))
+    }
+}
+
+/// Fills the tables of `instance` in `witness`, with the rows proving `trace`,
+/// recording the fill of each table in `fills`.
+#[instrument(level = "info", skip_all)]
+fn fill_witness(
+    instance: &CircuitInstance,
+    witness: &mut WitnessIndex<'_, '_, ProverPackedField>,
+    trace: &Trace,
+    fills: &mut FillRecorder,
+) -> Result<()> {
+    // Compute the rows of the auxiliary tables, which are independent
+    let ram_table = &instance.ram_table;
+    let range_lookup_tables = &instance.range_lookup_tables;
+    let ((vrom_rows, ram_rows), (public_rows, range_lookup_rows)) = rayon::join(
+        || {
+            rayon::join(
+                || vrom_rows(trace),
+                || ram_table.as_ref().map(|_| trace.ram_word_states()),
+            )
+        },
+        || {
+            rayon::join(
+                || trace.public_values(),
+                || {
+                    range_lookup_tables
+                        .par_iter()
+                        .map(|table| trace.range_lookups(table.bits))
+                        .collect::<Vec<_>>()
+                },
+            )
+        },
+    );
+
+    // 1. Fill PROM table with program instructions
+    fills.fill("PromTable", trace.program.len(), || {
+        witness.fill_table_parallel(&instance.prom_table, &trace.program)
+    })?;
+
+    // 2. Fill VROM table with VROM addresses and values
+    fills.fill("VromTable", vrom_rows.len(), || {
+        witness.fill_table_sequential(&instance.vrom_table, &vrom_rows)
+    })?;
+
+    // 3. Fill the right shifter table
+    if let Some(right_shifter_table) = &instance.right_shifter_table {
+        let events = trace.right_shift_events();
+        fills.fill("RightShifterTable", events.len(), || {
+            witness.fill_table_parallel(right_shifter_table, events)
+        })?;
+    }
+
+    // 4. Fill the RAM table with the final states of every RAM word, and the
+    // RAM image table with the non-zero words of the initial RAM
+    if let (Some(ram_table), Some(ram_rows)) = (ram_table, ram_rows) {
+        fills.fill("RamTable", ram_rows.len(), || {
+            witness.fill_table_sequential(ram_table, &ram_rows)
+        })?;
+    }
+    if let Some(ram_image_table) = &instance.ram_image_table {
+        let rows = trace.ram_image();
+        fills.fill("RamImageTable", rows.len(), || {
+            witness.fill_table_parallel(ram_image_table, &rows)
+        })?;
+    }
+
+    // 5. Fill the public values and inputs tables
+    let public_rows = public_rows?;
+    fills.fill("PublicTable", public_rows.len(), || {
+        witness.fill_table_parallel(&instance.public_table, &public_rows)
+    })?;
+    let inputs = trace.inputs();
+    fills.fill("InputTable", inputs.len(), || {
+        witness.fill_table_parallel(&instance.input_table, &inputs)
+    })?;
+
+    // 6. Fill the table of the words moved by MEMCPY and MEMSET
+    if let Some(mem_word_table) = &instance.mem_word_table {
+        let events = trace.mem_word_events();
+        fills.fill("MemWordTable", events.len(), || {
+            witness.fill_table_parallel(mem_word_table, events)
+        })?;
+    }
+
+    // 7. Fill the table of the levels of the Merkle paths
+    if let Some(merkle_node_table) = &instance.merkle_node_table {
+        let events = trace.merkle_node_events();
+        fills.fill("MerkleNodeTable", events.len(), || {
+            witness.fill_table_parallel(merkle_node_table, events)
+        })?;
+    }
+
+    // 8. Fill the range-check lookup tables
+    for (table, rows) in range_lookup_tables.iter().zip(range_lookup_rows) {
+        fills.fill(
+            &format!("RangeLookupTable{}", table.bits),
+            rows.len(),
+            || witness.fill_table_sequential(table, &rows),
+        )?;
+    }
+
+    // 9. Fill the call target table
+    if let Some(call_target_table) = &instance.call_target_table {
+        fills.fill("CallTargetTable", trace.call_targets.len(), || {
+            witness.fill_table_sequential(call_target_table, &trace.call_targets)
+        })?;
+    }
+
+    // 10. Fill all event tables
+    for table in &instance.tables {
+        fills.fill(table.name(), table.num_events(trace), || {
+            table.fill(witness, trace)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Returns the rows of the VROM table: every address of the VROM address
+/// space with its value and multiplicity, sorted by decreasing multiplicity.
+fn vrom_rows(trace: &Trace) -> Vec<(u32, u32, u32)> {
+    let vrom_addr_space_size = (trace.max_vrom_addr + 1).next_power_of_two();
+    let mut vrom_with_multiplicities = (0..vrom_addr_space_size)
+        .map(|addr| (addr as u32, 0u32, 0u32))
+        .collect::<Vec<_>>();
+    for &(addr, val, mul) in trace.vrom_writes.iter() {
+        vrom_with_multiplicities[addr as usize] = (addr, val, mul);
+    }
+    // Each input is looked up once more by the input table
+    for (addr, val) in trace.inputs() {
+        let (_, value, multiplicity) = &mut vrom_with_multiplicities[addr as usize];
+        *value = val;
+        *multiplicity += 1;
+    }
+    vrom_with_multiplicities.par_sort_by_key(|(_, _, mul)| *mul);
+    vrom_with_multiplicities.reverse();
+    vrom_with_multiplicities
 }
 
-/// Verify a PetraVM execution proof.
+/// Returns the values flushed on `channel` by `boundaries`, along with their
+/// direction (`true` for a push) and multiplicity, sorted so that they can be
+/// compared regardless of their order.
+fn channel_values(
+    boundaries: &[Boundary<B128>],
+    channel: ChannelId,
+) -> Vec<(Vec<u128>, bool, u64)> {
+    let mut values = boundaries
+        .iter()
+        .filter(|boundary| boundary.channel_id == channel)
+        .map(|boundary| {
+            (
+                boundary.values.iter().map(|value| value.val()).collect(),
+                matches!(boundary.direction, FlushDirection::Push),
+                boundary.multiplicity,
+            )
+        })
+        .collect::<Vec<_>>();
+    values.sort_unstable();
+    values
+}
+
+/// Verify a PetraVM execution proof generated with the default
+/// [`ProverConfig`].
 ///
 /// This function:
 /// 1. Uses the provided compiled constraint system
@@ -195,28 +841,15 @@ impl Prover {
 ///
 /// # Returns
 /// * Result indicating success or error
-#[instrument(level = "info", skip_all)]
 pub fn verify_proof(
     statement: &Statement,
     compiled_cs: &ConstraintSystem<B128>,
     proof: Proof,
 ) -> Result<()> {
-    let ccs_digest = compiled_cs.digest::<Groestl256>();
-
-    verify::<
-        OptimalUnderlier,
-        CanonicalTowerFamily,
-        Groestl256,
-        Groestl256ByteCompression,
-        HasherChallenger<Groestl256>,
-    >(
+    verify_with_compiled_cs(
         compiled_cs,
-        LOG_INV_RATE,
-        SECURITY_BITS,
-        &ccs_digest,
+        &ProverConfig::default(),
         &statement.boundaries,
         proof,
-    )?;
-
-    Ok(())
+    )
 }
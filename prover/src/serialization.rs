@@ -0,0 +1,78 @@
+//! Binary serialization of the artifacts exchanged between a prover and a
+//! verifier.
+//!
+//! The verification key, proofs and boundaries are serialized by the
+//! [`petravm_verifier`] crate, whose encodings are re-exported here.
+//!
+//! Execution traces can also be serialized, so that a program can be executed
+//! on one machine and proven on another one. They are encoded with `bincode`,
+//! whose variable-length integers keep the event vectors compact, after a
+//! versioned header. So are the [`Claim`]s of the proofs, from which verifiers
+//! rebuild the boundaries of the proven statements.
+
+use anyhow::{bail, ensure, Result};
+use bincode::Options;
+pub use petravm_verifier::serialization::{
+    deserialize_boundaries, deserialize_proof, serialize_boundaries, serialize_proof,
+};
+
+use crate::model::{Claim, Trace};
+
+/// Magic bytes starting a serialized [`Trace`].
+const TRACE_MAGIC: &[u8; 8] = b"PETRATRC";
+/// Version of the trace encoding, to be bumped whenever the events change.
+pub const TRACE_FORMAT_VERSION: u32 = 2;
+/// Magic bytes starting a serialized [`Claim`].
+const CLAIM_MAGIC: &[u8; 8] = b"PETRACLM";
+
+impl Trace {
+    /// Serializes the trace, to be proven in another process.
+    ///
+    /// The events of custom instructions cannot be serialized, so this fails
+    /// if the trace holds any.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        ensure!(
+            self.trace.custom.values().all(Vec::is_empty),
+            "Traces with custom instruction events cannot be serialized"
+        );
+        let mut bytes = Vec::with_capacity(TRACE_MAGIC.len() + 4);
+        bytes.extend_from_slice(TRACE_MAGIC);
+        bytes.extend_from_slice(&TRACE_FORMAT_VERSION.to_le_bytes());
+        bincode::DefaultOptions::new().serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes a trace produced by [`Trace::to_bytes`], with the same
+    /// [`TRACE_FORMAT_VERSION`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let Some(bytes) = bytes.strip_prefix(TRACE_MAGIC) else {
+            bail!("Not a serialized PetraVM trace");
+        };
+        let Some((version, bytes)) = bytes.split_first_chunk::<4>() else {
+            bail!("Truncated trace header");
+        };
+        let version = u32::from_le_bytes(*version);
+        ensure!(
+            version == TRACE_FORMAT_VERSION,
+            "Unsupported trace format version {version}, expected {TRACE_FORMAT_VERSION}"
+        );
+        Ok(bincode::DefaultOptions::new().deserialize(bytes)?)
+    }
+}
+
+impl Claim {
+    /// Serializes the claim, to be sent along with its proof.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = CLAIM_MAGIC.to_vec();
+        bincode::DefaultOptions::new().serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes a claim produced by [`Claim::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let Some(bytes) = bytes.strip_prefix(CLAIM_MAGIC) else {
+            bail!("Not a serialized PetraVM claim");
+        };
+        Ok(bincode::DefaultOptions::new().deserialize(bytes)?)
+    }
+}
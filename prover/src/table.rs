@@ -10,6 +10,7 @@ use anyhow::anyhow;
 use binius_m3::builder::ConstraintSystem;
 use binius_m3::builder::TableFiller;
 use binius_m3::builder::WitnessIndex;
+use petravm_asm::execution::{CustomEvent, CustomInstruction};
 use petravm_asm::opcodes::InstructionInfo;
 use tracing::instrument;
 
@@ -19,7 +20,7 @@ pub use crate::opcodes::*;
 use crate::{channels::Channels, types::ProverPackedField};
 
 pub trait TableInfo: InstructionInfo {
-    type Table: TableFiller<ProverPackedField> + Table + 'static;
+    type Table: TableFiller<ProverPackedField> + Table + Sync + 'static;
 
     fn accessor() -> fn(&Trace) -> &[<Self::Table as Table>::Event];
 }
@@ -55,9 +56,9 @@ pub trait Table {
 /// NOTE: This is necessary to "hide" the associated [`Event`](Table::Event)
 /// type of the [`Table`] trait, so that it can be used within the definition of
 /// [`ISA`](petravm_asm::isa::ISA).
-pub trait FillableTable {
+pub trait FillableTable: Sync {
     /// Fills the table's witness rows with data from the corresponding events
-    /// prevent in the provided [`Trace`].
+    /// prevent in the provided [`Trace`], filling its segments in parallel.
     fn fill(
         &self,
         witness: &mut WitnessIndex<'_, '_, ProverPackedField>,
@@ -86,7 +87,8 @@ pub struct TableEntry<T: Table + TableFiller<ProverPackedField> + 'static> {
 
 impl<T> FillableTable for TableEntry<T>
 where
-    T: Table + TableFiller<ProverPackedField> + 'static,
+    T: Table + TableFiller<ProverPackedField> + Sync + 'static,
+    <T as TableFiller<ProverPackedField>>::Event: Sync,
 {
     #[instrument(level = "debug", skip_all, fields(table = %self.table.name()))]
     fn fill(
@@ -95,7 +97,7 @@ where
         trace: &Trace,
     ) -> anyhow::Result<()> {
         witness
-            .fill_table_sequential(&*self.table, (self.get_events)(trace))
+            .fill_table_parallel(&*self.table, (self.get_events)(trace))
             .map_err(|e| anyhow!(e))
     }
 
@@ -107,3 +109,55 @@ where
         self.table.name()
     }
 }
+
+/// Prover counterpart of a [`CustomInstruction`], bundling it with the
+/// [`Table`] proving its events.
+///
+/// Registering it with
+/// [`Prover::with_custom_instruction`](crate::prover::Prover::with_custom_instruction)
+/// adds its table to the circuit.
+pub trait ProvableCustomInstruction: CustomInstruction {
+    /// The event recorded by the instruction.
+    type Event: CustomEvent + Clone;
+
+    /// The table proving the instruction, filled with its events.
+    type Table: Table + TableFiller<ProverPackedField, Event = Self::Event> + Sync + 'static;
+}
+
+/// A dynamic table entry binding the [`Table`] of a custom instruction with
+/// its opcode, to fetch its events from the trace.
+pub struct CustomTableEntry<T: Table + TableFiller<ProverPackedField> + 'static> {
+    pub table: Box<T>,
+    pub opcode: u16,
+}
+
+impl<T> FillableTable for CustomTableEntry<T>
+where
+    T: Table + TableFiller<ProverPackedField> + Sync + 'static,
+    <T as TableFiller<ProverPackedField>>::Event: CustomEvent + Clone,
+{
+    #[instrument(level = "debug", skip_all, fields(table = %self.table.name()))]
+    fn fill(
+        &self,
+        witness: &mut WitnessIndex<'_, '_, ProverPackedField>,
+        trace: &Trace,
+    ) -> anyhow::Result<()> {
+        let events = trace
+            .trace
+            .custom_events::<<T as TableFiller<ProverPackedField>>::Event>(self.opcode)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        witness
+            .fill_table_parallel(&*self.table, &events)
+            .map_err(|e| anyhow!(e))
+    }
+
+    fn num_events(&self, trace: &Trace) -> usize {
+        trace.trace.custom.get(&self.opcode).map_or(0, Vec::len)
+    }
+
+    fn name(&self) -> &'static str {
+        self.table.name()
+    }
+}
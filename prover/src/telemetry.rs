@@ -0,0 +1,202 @@
+//! Telemetry of proof jobs.
+//!
+//! Proving an execution goes through four stages, each running in a
+//! `Proving Stage` span of `tracing` whose `stage` field names it:
+//!
+//! - `Statement`: computing the boundaries and table sizes of the statement;
+//! - `Compile`: compiling the constraint system, or fetching it from the cache;
+//! - `Witness`: allocating and filling the witness, each table being filled in
+//!   a nested `Fill Table` span;
+//! - `Prove`: committing to the witness and running the proving protocol,
+//!   broken down further by the spans of Binius.
+//!
+//! The end of each stage and of each table fill is reported by an event
+//! holding its duration and, for tables, its number of rows, so that any
+//! subscriber can export them as metrics. The same figures are gathered in a
+//! [`ProvingSummary`], returned by
+//! [`Prover::prove_with_summary`](crate::prover::Prover::prove_with_summary),
+//! for services monitoring their proof jobs without a subscriber.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use tracing::span::EnteredSpan;
+
+use crate::budget::WitnessMemory;
+
+/// Time spent filling a table of the witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableFill {
+    /// Name of the table.
+    pub name: String,
+    /// Number of rows filled from the trace.
+    pub rows: usize,
+    /// Time spent filling the table.
+    pub duration: Duration,
+}
+
+/// Times, sizes and memory of a proof job, see the [module-level
+/// documentation](self).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProvingSummary {
+    /// Time spent computing the statement.
+    pub statement: Duration,
+    /// Time spent compiling the constraint system.
+    pub compile: Duration,
+    /// Time spent allocating and filling the witness.
+    pub witness: Duration,
+    /// Time spent committing to the witness and proving.
+    pub prove: Duration,
+    /// Fill of each table, in filling order.
+    pub tables: Vec<TableFill>,
+    /// Estimated memory of the witness.
+    pub memory: WitnessMemory,
+}
+
+impl ProvingSummary {
+    /// Returns the total time of the job.
+    pub fn total(&self) -> Duration {
+        self.statement + self.compile + self.witness + self.prove
+    }
+
+    /// Returns the number of rows filled from the trace, over all tables.
+    pub fn total_rows(&self) -> usize {
+        self.tables.iter().map(|table| table.rows).sum()
+    }
+}
+
+impl fmt::Display for ProvingSummary {
+    /// Lists the stages, then the non-empty tables by decreasing fill time.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stage, duration) in [
+            ("Statement", self.statement),
+            ("Compile", self.compile),
+            ("Witness", self.witness),
+            ("Prove", self.prove),
+            ("Total", self.total()),
+        ] {
+            writeln!(f, "{stage:<24} {:>12.3} s", duration.as_secs_f64())?;
+        }
+        let mut tables = self
+            .tables
+            .iter()
+            .filter(|table| table.rows > 0)
+            .collect::<Vec<_>>();
+        tables.sort_by_key(|table| std::cmp::Reverse(table.duration));
+        for table in tables {
+            writeln!(
+                f,
+                "{:<24} {:>12} rows {:>9.3} s",
+                table.name,
+                table.rows,
+                table.duration.as_secs_f64()
+            )?;
+        }
+        write!(f, "{}", self.memory)
+    }
+}
+
+/// Proving stage in progress, running in a span of its own until it is
+/// finished.
+pub(crate) struct Stage {
+    name: &'static str,
+    start: Instant,
+    _span: EnteredSpan,
+}
+
+impl Stage {
+    /// Starts the stage `name`.
+    pub(crate) fn start(name: &'static str) -> Self {
+        Self {
+            name,
+            start: Instant::now(),
+            _span: tracing::info_span!("Proving Stage", stage = name).entered(),
+        }
+    }
+
+    /// Finishes the stage, returning its duration.
+    pub(crate) fn finish(self) -> Duration {
+        let duration = self.start.elapsed();
+        tracing::info!(
+            stage = self.name,
+            elapsed_us = duration.as_micros() as u64,
+            "Proving stage done"
+        );
+        duration
+    }
+}
+
+/// Records the fill of every table of a witness.
+#[derive(Debug, Default)]
+pub(crate) struct FillRecorder {
+    pub(crate) tables: Vec<TableFill>,
+}
+
+impl FillRecorder {
+    /// Fills the table `name`, of `rows` rows, with `fill` in a span of its
+    /// own, recording the time it took.
+    pub(crate) fn fill<E>(
+        &mut self,
+        name: &str,
+        rows: usize,
+        fill: impl FnOnce() -> Result<(), E>,
+    ) -> Result<(), E> {
+        let _span = tracing::debug_span!("Fill Table", table = name, rows).entered();
+        let start = Instant::now();
+        fill()?;
+        let duration = start.elapsed();
+        tracing::debug!(
+            table = name,
+            rows,
+            elapsed_us = duration.as_micros() as u64,
+            "Table filled"
+        );
+        self.tables.push(TableFill {
+            name: name.to_string(),
+            rows,
+            duration,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use petravm_asm::isa::GenericISA;
+
+    use crate::{
+        prover::Prover,
+        test_utils::{fibonacci, generate_fibonacci_trace},
+    };
+
+    #[test]
+    fn test_proving_summary() -> Result<()> {
+        let trace = generate_fibonacci_trace(11, fibonacci(11))?;
+        let prover = Prover::new(Box::new(GenericISA));
+        let (_, statement, _, summary) = prover.prove_with_summary(&trace)?;
+
+        // Every table of the statement is filled, in order of table creation.
+        let fills = summary
+            .tables
+            .iter()
+            .map(|table| (table.name.as_str(), table.rows))
+            .collect::<Vec<_>>();
+        let table_sizes = trace.table_sizes(Box::new(GenericISA))?;
+        assert_eq!(
+            fills,
+            table_sizes
+                .iter()
+                .map(|table| (table.name.as_str(), table.rows))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            summary.total_rows(),
+            statement.table_sizes.iter().sum::<usize>()
+        );
+        assert_eq!(summary.memory, prover.estimate_memory(&trace)?);
+        assert!(summary.total() >= summary.prove);
+        assert!(summary.to_string().contains("PromTable"));
+        Ok(())
+    }
+}
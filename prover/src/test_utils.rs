@@ -4,10 +4,12 @@ use binius_hash::groestl::{GroestlShortImpl, GroestlShortInternal};
 use binius_m3::builder::B32;
 use log::trace;
 use petravm_asm::{
+    bigint::mul_mod,
+    execution::CustomInstructions,
     isa::{GenericISA, RecursionISA, ISA},
     transpose_in_aes, transpose_in_bin,
-    util::{bytes_to_u32, u32_to_bytes},
-    Assembler, Instruction, InterpreterInstruction, Memory, PetraTrace, ValueRom,
+    util::{blake3_compress, bytes_to_u32, sha256_compress, u32_to_bytes},
+    AssembledProgram, Assembler,
 };
 use tracing::instrument;
 
@@ -40,9 +42,20 @@ pub fn generate_asm_trace(
     init_values: Vec<u32>,
     isa: Box<dyn ISA>,
 ) -> Result<Trace> {
-    // Read the assembly code from the specified files
+    generate_trace(read_examples(files)?, Some(init_values), None, isa)
+}
+
+/// Assembles the files `files` located in the `examples` directory, e.g. to
+/// compute the boundaries of the traces of [`generate_asm_trace`].
+pub fn assemble_examples(files: &[&str]) -> Result<AssembledProgram> {
+    Ok(Assembler::from_code(&read_examples(files)?)?)
+}
+
+/// Reads and concatenates the files `files` located in the `examples`
+/// directory.
+fn read_examples(files: &[&str]) -> Result<String> {
     #[allow(clippy::manual_try_fold)]
-    let asm_code = files
+    files
         .iter()
         .fold(Ok(String::new()), |acc: Result<String>, &file_name| {
             let mut acc = acc?;
@@ -51,9 +64,7 @@ pub fn generate_asm_trace(
                 .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file_name, e))?;
             acc.push_str(&asm_code);
             Ok(acc)
-        })?;
-
-    generate_trace(asm_code, Some(init_values), None, isa)
+        })
 }
 
 /// Creates an execution trace for a Fibonacci program.
@@ -66,6 +77,13 @@ pub fn generate_asm_trace(
 /// * A trace containing the Fibonacci program execution
 #[instrument(level = "info", skip(res))]
 pub fn generate_fibonacci_trace(n: u32, res: u32) -> Result<Trace> {
+    let isa = Box::new(GenericISA);
+    generate_asm_trace(&["fib.asm"], fibonacci_init_values(n, res), isa)
+}
+
+/// Returns the initial VROM values of the Fibonacci program computing the
+/// `n`-th Fibonacci number `res`, as bound by the proofs of its execution.
+pub fn fibonacci_init_values(n: u32, res: u32) -> Vec<u32> {
     let n = B32::MULTIPLICATIVE_GENERATOR.pow([n as u64]).val();
     // Initialize memory with:
     // Slot 0: Return PC = 0
@@ -73,9 +91,7 @@ pub fn generate_fibonacci_trace(n: u32, res: u32) -> Result<Trace> {
     // Slot 2: Arg: n
     // Slot 3: Arg: Result address
     // Slot 4: Arg: Result
-    let init_values = vec![0, 0, n, 4, res];
-    let isa = Box::new(GenericISA);
-    generate_asm_trace(&["fib.asm"], init_values, isa)
+    vec![0, 0, n, 4, res]
 }
 
 pub const fn collatz(mut n: u32) -> usize {
@@ -125,66 +141,44 @@ pub fn generate_trace(
     init_values: Option<Vec<u32>>,
     vrom_writes: Option<Vec<(u32, u32, u32)>>,
     isa: Box<dyn ISA>,
+) -> Result<Trace> {
+    generate_trace_with_custom_instructions(
+        asm_code,
+        init_values,
+        vrom_writes,
+        isa,
+        CustomInstructions::new(),
+    )
+}
+
+/// Creates an execution trace like [`generate_trace`], with
+/// `custom_instructions` executable by the program.
+pub fn generate_trace_with_custom_instructions(
+    asm_code: String,
+    init_values: Option<Vec<u32>>,
+    vrom_writes: Option<Vec<(u32, u32, u32)>>,
+    isa: Box<dyn ISA>,
+    custom_instructions: CustomInstructions,
 ) -> Result<Trace> {
     // Compile the assembly code
     let compiled_program = Assembler::from_code(&asm_code)?;
     trace!("compiled program = {compiled_program:?}");
 
-    // Remove prover-only instructions for the verifier
-    let mut verifier_program = compiled_program
-        .prom
-        .clone()
-        .into_iter()
-        .filter(|instr| !instr.prover_only)
-        .collect::<Vec<_>>();
-
-    // TODO: pad program to 128 instructions required by lookup gadget
-    let prom_size = verifier_program.len().next_power_of_two().max(128);
-    let mut max_pc = verifier_program
-        .last()
-        .map_or(B32::ZERO, |instr| instr.field_pc);
-
-    for _ in verifier_program.len()..prom_size {
-        max_pc *= B32::MULTIPLICATIVE_GENERATOR;
-        verifier_program.push(InterpreterInstruction::new(
-            Instruction::default(),
-            max_pc,
-            None,
-            false,
-        ));
-    }
-
     // Initialize memory with return PC = 0, return FP = 0 if not provided
-    let vrom = ValueRom::new_with_init_vals(&init_values.unwrap_or_else(|| vec![0, 0]));
-    let memory = Memory::new(compiled_program.prom, vrom);
-
-    // Generate the trace from the compiled program
-    let (petra_trace, _) = PetraTrace::generate(
+    let init_values = init_values.unwrap_or_else(|| vec![0, 0]);
+    let zkvm_trace = Trace::from_program_with_custom_instructions(
+        compiled_program,
+        &init_values,
         isa,
-        memory,
-        compiled_program.frame_sizes,
-        compiled_program.pc_field_to_index_pc,
-    )
-    .map_err(|e| anyhow::anyhow!("Failed to generate trace: {:?}", e))?;
-
-    // Convert to Trace format for the prover
-    let mut zkvm_trace = Trace::from_petra_trace(verifier_program, petra_trace);
-    let actual_vrom_writes = zkvm_trace.trace.vrom().sorted_access_counts();
+        custom_instructions,
+    )?;
 
     // Validate that manually specified multiplicities match the actual ones if
     // provided.
     if let Some(vrom_writes) = vrom_writes {
-        assert_eq!(actual_vrom_writes, vrom_writes);
+        assert_eq!(zkvm_trace.vrom_writes, vrom_writes);
     }
 
-    // Add other VROM writes
-    let mut max_dst = 0;
-    for (dst, val, multiplicity) in actual_vrom_writes {
-        zkvm_trace.add_vrom_write(dst, val, multiplicity);
-        max_dst = max_dst.max(dst);
-    }
-
-    zkvm_trace.max_vrom_addr = max_dst as usize;
     Ok(zkvm_trace)
 }
 
@@ -312,3 +306,191 @@ pub fn generate_groestl_ret_trace(src1_val: [u32; 16], src2_val: [u32; 16]) -> R
     let isa = Box::new(RecursionISA);
     generate_trace(asm_code, Some(init_values), Some(vrom_writes), isa)
 }
+
+/// Creates an execution trace for a simple program that uses the
+/// SHA256_COMPRESS instruction.
+///
+/// # Arguments
+/// * `state` - The input state of the compression.
+/// * `block` - The message block to compress.
+///
+/// # Returns
+/// * A Trace containing a SHA256_COMPRESS instruction followed by a RET.
+pub fn generate_sha256_ret_trace(state: [u32; 8], block: [u32; 16]) -> Result<Trace> {
+    // Frame:
+    // Slot 0: PC
+    // Slot 1: FP
+    // Slots 2-7: Padding
+    // Slots 8-15: state
+    // Slots 16-31: block
+    // Slots 32-39: output
+    let state_offset = 8;
+    let block_offset = 16;
+    let output_offset = 32;
+    let asm_code = format!(
+        "#[framesize(0x28)]\n\
+         _start:
+            SHA256_COMPRESS @{output_offset}, @{state_offset}, @{block_offset}\n\
+            RET\n"
+    );
+
+    let mut init_values = vec![0; 32];
+    init_values[state_offset..state_offset + 8].copy_from_slice(&state);
+    init_values[block_offset..block_offset + 16].copy_from_slice(&block);
+
+    let mut output = state;
+    sha256_compress(&mut output, &block);
+
+    // Add VROM writes from SHA256_COMPRESS and RET events.
+    let mut vrom_writes = vec![];
+    // FP and PC.
+    vrom_writes.extend_from_slice(&[(0, 0, 1), (1, 0, 1)]);
+    // Inputs.
+    vrom_writes.extend(
+        state
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + state_offset) as u32, *v, 1)),
+    );
+    vrom_writes.extend(
+        block
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + block_offset) as u32, *v, 1)),
+    );
+    // Output.
+    vrom_writes.extend(
+        output
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + output_offset) as u32, *v, 1)),
+    );
+
+    let isa = Box::new(GenericISA);
+    generate_trace(asm_code, Some(init_values), Some(vrom_writes), isa)
+}
+
+/// Creates an execution trace for a simple program that uses the
+/// BLAKE3_COMPRESS instruction.
+///
+/// # Arguments
+/// * `input` - The chaining value, block counter, block length and flags.
+/// * `block` - The message block to compress.
+///
+/// # Returns
+/// * A Trace containing a BLAKE3_COMPRESS instruction followed by a RET.
+pub fn generate_blake3_ret_trace(input: [u32; 12], block: [u32; 16]) -> Result<Trace> {
+    // Frame:
+    // Slot 0: PC
+    // Slot 1: FP
+    // Slots 2-15: Padding
+    // Slots 16-27: input
+    // Slots 28-31: Padding
+    // Slots 32-47: block
+    // Slots 48-63: output
+    let input_offset = 16;
+    let block_offset = 32;
+    let output_offset = 48;
+    let asm_code = format!(
+        "#[framesize(0x40)]\n\
+         _start:
+            BLAKE3_COMPRESS @{output_offset}, @{input_offset}, @{block_offset}\n\
+            RET\n"
+    );
+
+    let mut init_values = vec![0; 48];
+    init_values[input_offset..input_offset + 12].copy_from_slice(&input);
+    init_values[block_offset..block_offset + 16].copy_from_slice(&block);
+
+    let output = blake3_compress(&input, &block);
+
+    // Add VROM writes from BLAKE3_COMPRESS and RET events.
+    let mut vrom_writes = vec![];
+    // FP and PC.
+    vrom_writes.extend_from_slice(&[(0, 0, 1), (1, 0, 1)]);
+    // Inputs.
+    vrom_writes.extend(
+        input
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + input_offset) as u32, *v, 1)),
+    );
+    vrom_writes.extend(
+        block
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + block_offset) as u32, *v, 1)),
+    );
+    // Output.
+    vrom_writes.extend(
+        output
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + output_offset) as u32, *v, 1)),
+    );
+
+    let isa = Box::new(GenericISA);
+    generate_trace(asm_code, Some(init_values), Some(vrom_writes), isa)
+}
+
+/// Creates an execution trace for a simple program that uses the BIGMULMOD
+/// instruction.
+///
+/// # Arguments
+/// * `a` - The first factor, reduced modulo `m`.
+/// * `b` - The second factor, reduced modulo `m`.
+/// * `m` - The modulus.
+///
+/// # Returns
+/// * A Trace containing a BIGMULMOD instruction followed by a RET.
+pub fn generate_big_mul_mod_ret_trace(a: [u32; 8], b: [u32; 8], m: [u32; 8]) -> Result<Trace> {
+    // Frame:
+    // Slot 0: PC
+    // Slot 1: FP
+    // Slots 2-7: Padding
+    // Slots 8-15: m
+    // Slots 16-23: a
+    // Slots 24-31: b
+    // Slots 32-39: output
+    let m_offset = 8;
+    let a_offset = 16;
+    let b_offset = 24;
+    let output_offset = 32;
+    let asm_code = format!(
+        "#[framesize(0x28)]\n\
+         _start:
+            BIGMULMOD @{output_offset}, @{a_offset}, @{m_offset}\n\
+            RET\n"
+    );
+
+    let mut init_values = vec![0; 32];
+    init_values[m_offset..m_offset + 8].copy_from_slice(&m);
+    init_values[a_offset..a_offset + 8].copy_from_slice(&a);
+    init_values[b_offset..b_offset + 8].copy_from_slice(&b);
+
+    let output = mul_mod(&a, &b, &m).expect("The modulus should not be zero");
+
+    // Add VROM writes from BIGMULMOD and RET events.
+    let mut vrom_writes = vec![];
+    // FP and PC.
+    vrom_writes.extend_from_slice(&[(0, 0, 1), (1, 0, 1)]);
+    // Inputs.
+    for (offset, values) in [(m_offset, m), (a_offset, a), (b_offset, b)] {
+        vrom_writes.extend(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| ((i + offset) as u32, *v, 1)),
+        );
+    }
+    // Output.
+    vrom_writes.extend(
+        output
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i + output_offset) as u32, *v, 1)),
+    );
+
+    let isa = Box::new(GenericISA);
+    generate_trace(asm_code, Some(init_values), Some(vrom_writes), isa)
+}
@@ -169,6 +169,11 @@ pub fn pack_instruction_with_32bits_imm_b128(pc: B32, opcode: B16, arg: B16, imm
     b1 + b2 + b3 + b4
 }
 
+/// Packs two 8-bit limbs into a single 16-bit value.
+pub(crate) fn pack_b8_into_b16(low: Col<B8, 1>, high: Col<B8, 1>) -> Expr<B16, 1> {
+    upcast_expr(high.into()) * <B16 as ExtensionField<B8>>::basis(1) + upcast_expr(low.into())
+}
+
 /// Packs two 16-bit limbs into a single 32-bit value.
 pub(crate) fn pack_b16_into_b32(low: Col<B16, 1>, high: Col<B16, 1>) -> Expr<B32, 1> {
     upcast_expr(high.into()) * <B32 as ExtensionField<B16>>::basis(1) + upcast_expr(low.into())
@@ -198,11 +203,7 @@ pub(crate) fn setup_mux_constraint(
 }
 
 /// Pulls a value from the VROM channel.
-pub(crate) fn pull_vrom_channel(
-    table: &mut TableBuilder,
-    channel: ChannelId,
-    value: [Col<B32>; 2],
-) {
+pub fn pull_vrom_channel(table: &mut TableBuilder, channel: ChannelId, value: [Col<B32>; 2]) {
     #[cfg(not(feature = "disable_vrom_channel"))]
     table.pull(channel, value);
 
@@ -229,7 +230,7 @@ pub(crate) fn pull_prom_channel(
 pub(crate) fn pull_state_channel(
     table: &mut TableBuilder,
     channel: ChannelId,
-    value: [Col<B32>; 2],
+    value: [Col<B32>; 3],
 ) {
     #[cfg(not(feature = "disable_state_channel"))]
     table.pull(channel, value);
@@ -243,7 +244,7 @@ pub(crate) fn pull_state_channel(
 pub(crate) fn push_state_channel(
     table: &mut TableBuilder,
     channel: ChannelId,
-    value: [Col<B32>; 2],
+    value: [Col<B32>; 3],
 ) {
     #[cfg(not(feature = "disable_state_channel"))]
     table.push(channel, value);
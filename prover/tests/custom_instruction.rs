@@ -0,0 +1,299 @@
+//! Test the proving of an instruction defined outside of the PetraVM crates.
+//!
+//! This file defines an XNOR custom instruction, with its event generator for
+//! the emulator and its table for the prover, and proves a program using it.
+
+use anyhow::Result;
+use binius_m3::builder::{
+    upcast_col, Col, ConstraintSystem, TableFiller, TableId, TableWitnessSegment, B16, B32,
+};
+use petravm_asm::execution::{
+    CustomEvent, CustomInstruction, CustomInstructions, InterpreterError, CUSTOM_OPCODE_BASE,
+};
+use petravm_asm::{isa::GenericISA, EventContext};
+use petravm_prover::channels::Channels;
+use petravm_prover::circuit::Circuit;
+use petravm_prover::gadgets::state::{StateColumns, StateColumnsOptions, StateGadget};
+use petravm_prover::prover::{verify_proof, Prover};
+use petravm_prover::table::{ProvableCustomInstruction, Table};
+use petravm_prover::test_utils::generate_trace_with_custom_instructions;
+use petravm_prover::types::ProverPackedField;
+use petravm_prover::utils::pull_vrom_channel;
+
+const XNOR_OPCODE: u16 = CUSTOM_OPCODE_BASE;
+
+/// XNOR custom instruction, setting FP[dst] to !(FP[src1] ^ FP[src2]).
+struct Xnor;
+
+#[derive(Debug, Clone)]
+struct XnorEvent {
+    pc: B32,
+    fp: u32,
+    timestamp: u32,
+    dst: u16,
+    dst_val: u32,
+    src1: u16,
+    src1_val: u32,
+    src2: u16,
+    src2_val: u32,
+}
+
+impl CustomEvent for XnorEvent {
+    fn state(&self) -> (B32, u32, u32) {
+        (self.pc, self.fp, self.timestamp)
+    }
+}
+
+impl CustomInstruction for Xnor {
+    fn opcode(&self) -> u16 {
+        XNOR_OPCODE
+    }
+
+    fn generate(
+        &self,
+        ctx: &mut EventContext,
+        dst: B16,
+        src1: B16,
+        src2: B16,
+    ) -> Result<(), InterpreterError> {
+        let src1_val = ctx.vrom_read::<u32>(ctx.addr(src1.val()))?;
+        let src2_val = ctx.vrom_read::<u32>(ctx.addr(src2.val()))?;
+        let dst_val = !(src1_val ^ src2_val);
+        ctx.vrom_write(ctx.addr(dst.val()), dst_val)?;
+
+        let (_pc, field_pc, fp, timestamp) = ctx.program_state();
+        ctx.record_custom_event(
+            XNOR_OPCODE,
+            XnorEvent {
+                pc: field_pc,
+                fp: *fp,
+                timestamp,
+                dst: dst.val(),
+                dst_val,
+                src1: src1.val(),
+                src1_val,
+                src2: src2.val(),
+                src2_val,
+            },
+        );
+        ctx.incr_counters();
+        Ok(())
+    }
+}
+
+impl ProvableCustomInstruction for Xnor {
+    type Event = XnorEvent;
+    type Table = XnorTable;
+}
+
+/// XNOR table.
+///
+/// In the binary field, XNOR is the sum of both operands and the all-ones
+/// word.
+struct XnorTable {
+    id: TableId,
+    state_cols: StateColumns<XNOR_OPCODE>,
+    dst_abs: Col<B32>,  // Virtual
+    src1_abs: Col<B32>, // Virtual
+    src1_val: Col<B32>,
+    src2_abs: Col<B32>, // Virtual
+    src2_val: Col<B32>,
+    dst_val: Col<B32>, // Virtual
+}
+
+impl Table for XnorTable {
+    type Event = XnorEvent;
+
+    fn name(&self) -> &'static str {
+        "XnorTable"
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        let mut table = cs.add_table("xnor");
+
+        let state_cols = StateColumns::new(
+            &mut table,
+            channels.state_channel,
+            channels.prom_channel,
+            StateColumnsOptions::default(),
+        );
+
+        let src1_val = table.add_committed("src1_val");
+        let src2_val = table.add_committed("src2_val");
+        let ones = table.add_constant("ones", [B32::new(u32::MAX)]);
+        let dst_val = table.add_computed("dst_val", src1_val + src2_val + ones);
+
+        let dst_abs = table.add_computed("dst_abs", state_cols.fp + upcast_col(state_cols.arg0));
+        let src1_abs = table.add_computed("src1_abs", state_cols.fp + upcast_col(state_cols.arg1));
+        let src2_abs = table.add_computed("src2_abs", state_cols.fp + upcast_col(state_cols.arg2));
+
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src1_abs, src1_val]);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [src2_abs, src2_val]);
+        pull_vrom_channel(&mut table, channels.vrom_channel, [dst_abs, dst_val]);
+
+        Self {
+            id: table.id(),
+            state_cols,
+            dst_abs,
+            src1_abs,
+            src1_val,
+            src2_abs,
+            src2_val,
+            dst_val,
+        }
+    }
+}
+
+impl TableFiller<ProverPackedField> for XnorTable {
+    type Event = XnorEvent;
+
+    fn id(&self) -> TableId {
+        self.id
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        {
+            let mut dst_abs = witness.get_scalars_mut(self.dst_abs)?;
+            let mut src1_abs = witness.get_scalars_mut(self.src1_abs)?;
+            let mut src1_val = witness.get_scalars_mut(self.src1_val)?;
+            let mut src2_abs = witness.get_scalars_mut(self.src2_abs)?;
+            let mut src2_val = witness.get_scalars_mut(self.src2_val)?;
+            let mut dst_val = witness.get_scalars_mut(self.dst_val)?;
+
+            for (i, event) in rows.clone().enumerate() {
+                dst_abs[i] = B32::new(event.fp + event.dst as u32);
+                src1_abs[i] = B32::new(event.fp + event.src1 as u32);
+                src1_val[i] = B32::new(event.src1_val);
+                src2_abs[i] = B32::new(event.fp + event.src2 as u32);
+                src2_val[i] = B32::new(event.src2_val);
+                dst_val[i] = B32::new(event.dst_val);
+            }
+        }
+
+        let state_rows = rows.map(|event| StateGadget {
+            pc: event.pc.val(),
+            next_pc: None,
+            fp: event.fp,
+            timestamp: event.timestamp,
+            arg0: event.dst,
+            arg1: event.src1,
+            arg2: event.src2,
+        });
+        self.state_cols.populate(witness, state_rows)
+    }
+}
+
+/// XNOR custom instruction, proven by another table with the same layout as
+/// [`XnorTable`], standing for a table whose constraints changed.
+struct OtherXnor;
+
+impl CustomInstruction for OtherXnor {
+    fn opcode(&self) -> u16 {
+        XNOR_OPCODE
+    }
+
+    fn generate(
+        &self,
+        ctx: &mut EventContext,
+        dst: B16,
+        src1: B16,
+        src2: B16,
+    ) -> Result<(), InterpreterError> {
+        Xnor.generate(ctx, dst, src1, src2)
+    }
+}
+
+impl ProvableCustomInstruction for OtherXnor {
+    type Event = XnorEvent;
+    type Table = OtherXnorTable;
+}
+
+struct OtherXnorTable(XnorTable);
+
+impl Table for OtherXnorTable {
+    type Event = XnorEvent;
+
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    fn new(cs: &mut ConstraintSystem, channels: &Channels) -> Self {
+        Self(XnorTable::new(cs, channels))
+    }
+}
+
+impl TableFiller<ProverPackedField> for OtherXnorTable {
+    type Event = XnorEvent;
+
+    fn id(&self) -> TableId {
+        self.0.id()
+    }
+
+    fn fill<'a>(
+        &'a self,
+        rows: impl Iterator<Item = &'a Self::Event> + Clone,
+        witness: &'a mut TableWitnessSegment<ProverPackedField>,
+    ) -> anyhow::Result<()> {
+        self.0.fill(rows, witness)
+    }
+}
+
+#[test]
+fn test_custom_instruction_proving() -> Result<()> {
+    let asm_code = "#[framesize(0x10)]\n\
+        _start:\n\
+            LDI.W @2, #0xff00\n\
+            LDI.W @3, #0x0ff0\n\
+            CUSTOM #0x8000, @4, @2, @3\n\
+            CUSTOM #0x8000, @5, @4, @2\n\
+            RET\n"
+        .to_string();
+
+    let mut custom_instructions = CustomInstructions::new();
+    custom_instructions.register(Xnor);
+    let trace = generate_trace_with_custom_instructions(
+        asm_code,
+        None,
+        None,
+        Box::new(GenericISA),
+        custom_instructions,
+    )?;
+    trace.validate()?;
+
+    let events = trace.trace.custom_events::<XnorEvent>(XNOR_OPCODE);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].dst_val, 0x0ff0);
+
+    let prover = Prover::new(Box::new(GenericISA)).with_custom_instruction(&Xnor);
+    let (proof, statement, compiled_cs) = prover.prove_with_statement(&trace)?;
+    verify_proof(&statement, &compiled_cs, proof)?;
+
+    // Without the XNOR table, the custom instructions can't be proven.
+    assert!(Prover::new(Box::new(GenericISA))
+        .diagnose_witness(&trace)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_custom_table_digest() {
+    let digest = |add_custom_instruction: fn(&mut Circuit)| {
+        let mut circuit = Circuit::new(Box::new(GenericISA));
+        add_custom_instruction(&mut circuit);
+        circuit.digest()
+    };
+
+    // Tables of the same layout on the same opcode are told apart by their
+    // types, so that their compiled constraint systems aren't mixed up.
+    let xnor = digest(|circuit| circuit.add_custom_instruction(&Xnor));
+    let other_xnor = digest(|circuit| circuit.add_custom_instruction(&OtherXnor));
+    assert_ne!(xnor, other_xnor);
+    assert_eq!(
+        xnor,
+        digest(|circuit| circuit.add_custom_instruction(&Xnor))
+    );
+}
@@ -1,8 +1,14 @@
 use anyhow::Result;
 use petravm_asm::init_logger;
 use petravm_asm::isa::GenericISA;
-use petravm_prover::prover::{verify_proof, Prover};
-use petravm_prover::test_utils::{fibonacci, generate_fibonacci_trace};
+use petravm_prover::model::Trace;
+use petravm_prover::prover::{verify, Prover, ProverConfig, VerifyingKey};
+use petravm_prover::serialization::{
+    deserialize_boundaries, deserialize_proof, serialize_boundaries, serialize_proof,
+};
+use petravm_prover::test_utils::{
+    assemble_examples, fibonacci, fibonacci_init_values, generate_fibonacci_trace,
+};
 
 #[test]
 fn test_fibonacci() -> Result<()> {
@@ -20,8 +26,211 @@ fn test_fibonacci() -> Result<()> {
     let prover = Prover::new(Box::new(GenericISA));
 
     // Step 4: Generate proof
-    let (proof, statement, compiled_cs) = prover.prove(&trace)?;
+    let proof = prover.prove(&trace)?;
 
     // Step 5: Verify proof
-    verify_proof(&statement, &compiled_cs, proof)
+    let vk = prover.verifying_key()?;
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let init_values = fibonacci_init_values(n, res);
+    verify(&vk, &prover.boundaries(&program, &init_values), proof)
+}
+
+#[test]
+fn test_fibonacci_diagnose_witness() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let mut trace = generate_fibonacci_trace(n, fibonacci(n))?;
+    let prover = Prover::new(Box::new(GenericISA));
+    prover.diagnose_witness(&trace)?;
+
+    // A tampered event is reported along with the state it unbalances.
+    trace.trace.ret[0].fp_next = u32::MAX;
+    let report = prover.diagnose_witness(&trace).unwrap_err().to_string();
+    assert!(report.contains("Unbalanced state channel"));
+    assert!(report.contains("ret[0]"));
+    Ok(())
+}
+
+#[test]
+fn test_fibonacci_with_config() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let trace = generate_fibonacci_trace(n, fibonacci(n))?;
+
+    let config = ProverConfig {
+        log_inv_rate: 2,
+        security_bits: 64,
+        ..Default::default()
+    };
+    let prover = Prover::new(Box::new(GenericISA)).with_config(config);
+    let proof = prover.prove(&trace)?;
+
+    // The configuration is carried by the verification key.
+    let vk = VerifyingKey::from_bytes(&prover.verifying_key()?.to_bytes()?)?;
+    assert_eq!(vk.config, config);
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let boundaries = prover.boundaries(&program, &fibonacci_init_values(n, fibonacci(n)));
+    verify(&vk, &boundaries, proof.clone())?;
+
+    // The proof can't be verified with other parameters.
+    let default_vk = Prover::new(Box::new(GenericISA)).verifying_key()?;
+    assert!(verify(&default_vk, &boundaries, proof).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_fibonacci_serialized_proof() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let trace = generate_fibonacci_trace(n, fibonacci(n))?;
+
+    // Prover side: generate the proof and serialize all verification inputs.
+    let prover = Prover::new(Box::new(GenericISA));
+    let proof_bytes = serialize_proof(&prover.prove(&trace)?)?;
+    let vk_bytes = prover.verifying_key()?.to_bytes()?;
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let init_values = fibonacci_init_values(n, fibonacci(n));
+    let boundaries_bytes = serialize_boundaries(&prover.boundaries(&program, &init_values))?;
+
+    // Verifier side: only rely on the serialized artifacts, which must not be
+    // followed by trailing bytes.
+    let padded_vk_bytes = [vk_bytes.as_slice(), &[0]].concat();
+    assert!(VerifyingKey::from_bytes(&padded_vk_bytes).is_err());
+    let vk = VerifyingKey::from_bytes(&vk_bytes)?;
+    let boundaries = deserialize_boundaries(&boundaries_bytes)?;
+    let proof = deserialize_proof(&proof_bytes)?;
+    verify(&vk, &boundaries, proof)
+}
+
+#[test]
+fn test_fibonacci_standalone_verifier() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let trace = generate_fibonacci_trace(n, fibonacci(n))?;
+
+    let prover = Prover::new(Box::new(GenericISA));
+    let proof_bytes = serialize_proof(&prover.prove(&trace)?)?;
+    let vk_bytes = prover.verifying_key()?.to_bytes()?;
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let init_values = fibonacci_init_values(n, fibonacci(n));
+    let boundaries_bytes = serialize_boundaries(&prover.boundaries(&program, &init_values))?;
+
+    // The verifier crate only needs the serialized artifacts.
+    let vk = petravm_verifier::VerifyingKey::from_bytes(&vk_bytes)?;
+    petravm_verifier::verify_bytes(&vk, &boundaries_bytes, &proof_bytes)?;
+
+    // Proofs of other statements are rejected.
+    let boundaries_bytes =
+        serialize_boundaries(&prover.boundaries_with_exit_code(&program, &init_values, 1, 0))?;
+    assert!(petravm_verifier::verify_bytes(&vk, &boundaries_bytes, &proof_bytes).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_fibonacci_program_binding() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let trace = generate_fibonacci_trace(n, fibonacci(n))?;
+    let prover = Prover::new(Box::new(GenericISA));
+    let (proof, statement, _) = prover.prove_with_statement(&trace)?;
+
+    // The proven statement binds the proof to the executed program.
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    prover.check_program(&statement.boundaries, &program)?;
+
+    // Proofs can't be passed off as executions of another program.
+    let other = assemble_examples(&["collatz.asm"])?.prom;
+    assert!(prover.check_program(&statement.boundaries, &other).is_err());
+    let init_values = fibonacci_init_values(n, fibonacci(n));
+    let boundaries = prover.boundaries(&other, &init_values);
+    assert!(verify(&prover.verifying_key()?, &boundaries, proof).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_fibonacci_input_binding() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let res = fibonacci(n);
+    let trace = generate_fibonacci_trace(n, res)?;
+    let prover = Prover::new(Box::new(GenericISA));
+    let (proof, statement, _) = prover.prove_with_statement(&trace)?;
+
+    // The proven statement binds the proof to the inputs of the host.
+    let init_values = fibonacci_init_values(n, res);
+    assert_eq!(trace.trace.vrom().init_values(), init_values);
+    prover.check_inputs(&statement.boundaries, &init_values)?;
+
+    // Proofs can't be passed off as executions from other inputs.
+    let other = fibonacci_init_values(n, res + 1);
+    assert!(prover.check_inputs(&statement.boundaries, &other).is_err());
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let boundaries = prover.boundaries(&program, &other);
+    assert!(verify(&prover.verifying_key()?, &boundaries, proof).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_fibonacci_serialized_trace() -> Result<()> {
+    init_logger();
+    let n = 11;
+    let trace = generate_fibonacci_trace(n, fibonacci(n))?;
+
+    // Execution side: only ship the serialized trace.
+    let trace_bytes = trace.to_bytes()?;
+
+    // Proving side: rebuild the trace and prove it.
+    let trace = Trace::from_bytes(&trace_bytes)?;
+    assert_eq!(trace.to_bytes()?, trace_bytes);
+    trace.validate()?;
+    let prover = Prover::new(Box::new(GenericISA));
+    let proof = prover.prove(&trace)?;
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    verify(
+        &prover.verifying_key()?,
+        &prover.boundaries(&program, &fibonacci_init_values(n, fibonacci(n))),
+        proof,
+    )?;
+
+    // Traces of other format versions are rejected.
+    let mut outdated_bytes = trace_bytes;
+    outdated_bytes[8] ^= 1;
+    assert!(Trace::from_bytes(&outdated_bytes).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_fibonacci_batch() -> Result<()> {
+    init_logger();
+    let ns = [5, 11];
+    let traces = ns
+        .iter()
+        .map(|&n| generate_fibonacci_trace(n, fibonacci(n)))
+        .collect::<Result<Vec<_>>>()?;
+    let prover = Prover::new(Box::new(GenericISA));
+    let proof = prover.prove_batch(&traces)?;
+
+    // The verifier gathers the boundaries of each execution, in order.
+    let program = assemble_examples(&["fib.asm"])?.prom;
+    let batch = prover.batch_circuit(ns.len());
+    let boundaries = |ns: &[u32]| {
+        batch
+            .instances
+            .iter()
+            .zip(ns)
+            .flat_map(|(instance, &n)| {
+                instance.boundaries(&program, &fibonacci_init_values(n, fibonacci(n)))
+            })
+            .collect::<Vec<_>>()
+    };
+    let vk = prover.batch_verifying_key(ns.len())?;
+    verify(&vk, &boundaries(&ns), proof.clone())?;
+
+    // Each execution is bound to its own instance.
+    assert!(verify(&vk, &boundaries(&[11, 5]), proof.clone()).is_err());
+    assert!(verify(&prover.verifying_key()?, &boundaries(&ns), proof).is_err());
+
+    // A batch holds one trace per instance.
+    assert!(batch.create_statement(&traces[..1]).is_err());
+    Ok(())
 }
@@ -34,7 +34,7 @@ fn test_all_opcodes() -> Result<()> {
     let prover = Prover::new(Box::new(GenericISA));
 
     // Step 4: Generate proof
-    let (proof, statement, compiled_cs) = prover.prove(&trace)?;
+    let (proof, statement, compiled_cs) = prover.prove_with_statement(&trace)?;
 
     // Step 5: Verify proof
     verify_proof(&statement, &compiled_cs, proof)
@@ -1,5 +1,9 @@
 use anyhow::Result;
-use petravm_asm::{init_logger, isa::GenericISA};
+use petravm_asm::{
+    groestl256_initial_chaining_value, init_logger,
+    isa::GenericISA,
+    util::{bytes_to_u32, groestl256, groestl256_pad, u32_to_bytes},
+};
 use petravm_prover::{
     prover::{verify_proof, Prover},
     test_utils::generate_asm_trace,
@@ -16,7 +20,7 @@ fn run_test(files: &[&str], init_values: Vec<u32>) -> Result<()> {
     let prover = Prover::new(Box::new(GenericISA));
 
     // Step 4: Generate proof
-    let (proof, statement, compiled_cs) = prover.prove(&trace)?;
+    let (proof, statement, compiled_cs) = prover.prove_with_statement(&trace)?;
 
     // Step 5: Verify proof
     verify_proof(&statement, &compiled_cs, proof)
@@ -111,6 +115,25 @@ fn test_branch() {
     run_test(&files, init_values(3)).unwrap();
 }
 
+#[test]
+fn test_branch_cmp() {
+    let files = ["branch_cmp.asm"];
+    // Initialize memory with:
+    // Slot 0: Return PC = 0
+    // Slot 1: Return FP = 0
+    // Slot 2: Arg: a
+    // Slot 3: Arg: b
+    let init_values = |a: i32, b: i32| vec![0, 0, a as u32, b as u32];
+    // Test case 1: a == b
+    run_test(&files, init_values(7, 7)).unwrap();
+
+    // Test case 2: a < b
+    run_test(&files, init_values(-1, 1)).unwrap();
+
+    // Test case 3: a > b
+    run_test(&files, init_values(1, -1)).unwrap();
+}
+
 #[test]
 fn test_func_call() {
     let files = ["func_call.asm"];
@@ -197,3 +220,30 @@ fn test_tail_long_div() {
     // Test case 5: b = 1
     run_test(&files, init_values(9, 2)).unwrap();
 }
+
+#[test]
+fn test_groestl() -> Result<()> {
+    init_logger();
+    let files = ["groestl.asm"];
+    let message = (0..100).collect::<Vec<u8>>();
+    let blocks = groestl256_pad(&message);
+    assert_eq!(blocks.len(), 2);
+
+    // Initialize memory with:
+    // Slot 0: Return PC = 0
+    // Slot 1: Return FP = 0
+    // Slots 16-31: Arg: initial chaining value
+    // Slots 32-63: Arg: padded message blocks
+    let mut init_values = vec![0; 16];
+    init_values.extend(bytes_to_u32(&groestl256_initial_chaining_value()));
+    init_values.extend(blocks.iter().flat_map(|block| bytes_to_u32(block)));
+
+    // The digest computed inside the VM is the Groestl-256 digest of the message.
+    let trace = generate_asm_trace(&files, init_values.clone(), Box::new(GenericISA))?;
+    let digest = (96..104)
+        .map(|addr| trace.trace.vrom().read::<u32>(addr).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(u32_to_bytes(&digest), groestl256(&message));
+
+    run_test(&files, init_values)
+}
@@ -5,11 +5,13 @@
 
 use anyhow::Result;
 use binius_field::underlier::Divisible;
-use binius_m3::builder::B128;
+use binius_m3::builder::{Boundary, FlushDirection, B128};
 use log::trace;
 use petravm_asm::isa::{GenericISA, RecursionISA, ISA};
-use petravm_prover::model::Trace;
-use petravm_prover::prover::{verify_proof, Prover};
+use petravm_asm::{define_isa, AddEvent, Assembler, GenerationOptions, LdiEvent, RetEvent};
+use petravm_prover::circuit::Circuit;
+use petravm_prover::model::{Claim, Trace};
+use petravm_prover::prover::{verify, verify_proof, Prover};
 use petravm_prover::test_utils::{generate_groestl_ret_trace, generate_trace};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
@@ -38,7 +40,7 @@ where
 
     // Step 4: Generate proof
     trace!("Generating proof...");
-    let (proof, statement, compiled_cs) = prover.prove(&trace)?;
+    let (proof, statement, compiled_cs) = prover.prove_with_statement(&trace)?;
 
     // Step 5: Verify proof
     trace!("Verifying proof...");
@@ -210,6 +212,17 @@ fn test_integer_ops() -> Result<()> {
                 1,
                 "Should have exacly one MULU event"
             );
+            // The generic accessor retrieves the same events.
+            assert_eq!(
+                trace.events_of::<AddEvent>().len(),
+                1,
+                "Should have exactly one ADD event"
+            );
+            assert_eq!(
+                trace.events_of::<LdiEvent>()[0].dst,
+                trace.ldi_events()[0].dst
+            );
+            assert!(trace.lb_events().is_empty());
         },
         Box::new(GenericISA),
     )
@@ -372,3 +385,252 @@ fn test_groestl_proving() -> Result<()> {
         Box::new(RecursionISA),
     )
 }
+
+#[test]
+fn test_public_values() -> Result<()> {
+    let asm_code = "
+        .public @3
+        .public @2
+        #[framesize(0x4)]
+        _start:
+            LDI.W @2, #42
+            ADDI @3, @2, #1
+            RET
+    "
+    .to_string();
+    let trace = generate_trace(asm_code.clone(), None, None, Box::new(GenericISA))?;
+    let public_values = trace.public_values()?;
+    assert_eq!(public_values, [(3, 43), (2, 42)]);
+
+    let prover = Prover::new(Box::new(GenericISA));
+    let proof = prover.prove(&trace)?;
+    let vk = prover.verifying_key()?;
+    let program = Assembler::from_code(&asm_code)?.prom;
+    verify(
+        &vk,
+        &prover.boundaries_with_public_values(&program, &[0, 0], &public_values),
+        proof.clone(),
+    )?;
+
+    // Claiming other public values must fail.
+    assert!(verify(
+        &vk,
+        &prover.boundaries_with_public_values(&program, &[0, 0], &[(3, 44), (2, 42)]),
+        proof
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_halt_exit_code() -> Result<()> {
+    let asm_code = "
+        #[framesize(0x4)]
+        _start:
+            ALLOCI! @3, #4
+            CALLI callee, @3
+            RET
+
+        #[framesize(0x4)]
+        callee:
+            HALT #3
+    "
+    .to_string();
+    let trace = generate_trace(asm_code.clone(), None, None, Box::new(GenericISA))?;
+    assert_eq!(trace.exit_code(), 3);
+
+    let prover = Prover::new(Box::new(GenericISA));
+    let proof = prover.prove(&trace)?;
+    let vk = prover.verifying_key()?;
+    let program = Assembler::from_code(&asm_code)?.prom;
+    verify(
+        &vk,
+        &prover.boundaries_with_exit_code(&program, &[0, 0], 3, 0),
+        proof.clone(),
+    )?;
+
+    // Claiming a successful execution must fail.
+    assert!(verify(&vk, &prover.boundaries(&program, &[0, 0]), proof).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_advice_tape() -> Result<()> {
+    let asm_code = "#[framesize(0x10)]\n\
+        _start:\n\
+            READ_ADVICE! @2\n\
+            READ_ADVICE! @3\n\
+            ADD @4, @2, @3\n\
+            RET\n";
+
+    test_from_trace_generator(
+        || {
+            Trace::from_program_with_options(
+                Assembler::from_code(asm_code)?,
+                &[0, 0],
+                Box::new(GenericISA),
+                GenerationOptions::default().advice_tape(vec![40, 2]),
+            )
+        },
+        |trace| {
+            assert_eq!(trace.add_events().len(), 1);
+            assert_eq!(trace.trace.vrom().peek::<u32>(4).unwrap(), 42);
+        },
+        Box::new(GenericISA),
+    )
+}
+
+#[test]
+fn test_ram_image_binding() -> Result<()> {
+    let asm_code = "
+        .data
+        table:
+            .word 7, 0, 0xdeadbeef
+        .text
+
+        #[framesize(0x8)]
+        _start:
+            LDI.W @2, #0
+            LW @3, @2, #0
+            LW @4, @2, #8
+            ADD @5, @3, @4
+            RET
+    ";
+    let program = Assembler::from_code(asm_code)?;
+    let trace = Trace::from_program(program.clone(), &[0, 0], Box::new(GenericISA))?;
+    assert_eq!(trace.ram_image(), [(0, 7), (2, 0xdeadbeef)]);
+
+    let prover = Prover::new(Box::new(GenericISA));
+    let (proof, statement, _) = prover.prove_with_statement(&trace)?;
+    let vk = prover.verifying_key()?;
+    prover.check_ram_image(&statement.boundaries, &program.data)?;
+    verify(&vk, &statement.boundaries, proof.clone())?;
+
+    // Proofs can't be passed off as executions from another initial RAM image.
+    let mut tampered = program.data.clone();
+    tampered[0] = 8;
+    assert!(prover
+        .check_ram_image(&statement.boundaries, &tampered)
+        .is_err());
+    let circuit = Circuit::new(Box::new(GenericISA));
+    let mut boundaries = statement
+        .boundaries
+        .into_iter()
+        .filter(|boundary| boundary.channel_id != circuit.channels.ram_image_channel)
+        .collect::<Vec<_>>();
+    boundaries.extend(circuit.ram_image_boundaries(&tampered));
+    assert!(verify(&vk, &boundaries, proof).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_claim_binding() -> Result<()> {
+    let asm_code = "
+        .public @5
+        .data
+        table:
+            .word 7, 0, 0xdeadbeef
+        .text
+
+        #[framesize(0x8)]
+        _start:
+            LDI.W @2, #0
+            LW @3, @2, #0
+            LW @4, @2, #8
+            ADD @5, @3, @4
+            COMMIT @3
+            RET
+    ";
+    let program = Assembler::from_code(asm_code)?;
+    let trace = Trace::from_program(program.clone(), &[0, 0], Box::new(GenericISA))?;
+    let claim = Claim::from_bytes(&trace.claim()?.to_bytes()?)?;
+    assert_eq!(claim.public_values, [0xdeadbef6]);
+    assert_eq!(claim.outputs, [7]);
+    assert_eq!(claim.final_timestamp, 3);
+
+    // The boundaries rebuilt from the program and the claim are those of the
+    // proven statement.
+    let prover = Prover::new(Box::new(GenericISA));
+    let (proof, statement, _) = prover.prove_with_statement(&trace)?;
+    let vk = prover.verifying_key()?;
+    let boundaries = prover.claim_boundaries(&program, &claim)?;
+    assert_eq!(boundaries.len(), statement.boundaries.len());
+    verify(&vk, &boundaries, proof.clone())?;
+
+    // Tampered claims are rejected, starting with those of the final state.
+    let tampered_claims = [
+        Claim {
+            exit_code: 1,
+            ..claim.clone()
+        },
+        Claim {
+            final_timestamp: 4,
+            ..claim.clone()
+        },
+        Claim {
+            init_values: vec![0, 1],
+            ..claim.clone()
+        },
+        Claim {
+            public_values: vec![8],
+            ..claim.clone()
+        },
+        Claim {
+            outputs: vec![8],
+            ..claim.clone()
+        },
+    ];
+    for tampered in tampered_claims {
+        let boundaries = prover.claim_boundaries(&program, &tampered)?;
+        assert!(verify(&vk, &boundaries, proof.clone()).is_err());
+    }
+
+    // So are extra boundaries, e.g. a push on the VROM channel forging a value
+    // that was never written.
+    let circuit = Circuit::new(Box::new(GenericISA));
+    let mut forged = boundaries;
+    forged.push(Boundary {
+        values: vec![B128::new(6), B128::new(1)],
+        channel_id: circuit.channels.vrom_channel,
+        direction: FlushDirection::Push,
+        multiplicity: 1,
+    });
+    assert!(verify(&vk, &forged, proof).is_err());
+    Ok(())
+}
+
+define_isa!(
+    /// A reduced ISA, without any shift, RAM, memory copy or range check
+    /// instruction.
+    ArithmeticISA => [AddEvent, LdiEvent, RetEvent]
+);
+
+#[test]
+fn test_reduced_isa() -> Result<()> {
+    let asm_code = "#[framesize(0x10)]\n\
+        _start:\n\
+            LDI.W @2, #2\n\
+            LDI.W @3, #40\n\
+            ADD @4, @2, @3\n\
+            RET\n"
+        .to_string();
+
+    test_from_trace_generator(
+        || generate_trace(asm_code, None, None, Box::new(ArithmeticISA)),
+        |trace| assert_eq!(trace.add_events().len(), 1),
+        Box::new(ArithmeticISA),
+    )?;
+
+    // The reduced ISA gets rid of the unused instruction and auxiliary tables.
+    let reduced_vk = Prover::new(Box::new(ArithmeticISA)).verifying_key()?;
+    let generic_vk = Prover::new(Box::new(GenericISA)).verifying_key()?;
+    assert!(reduced_vk.to_bytes()?.len() < generic_vk.to_bytes()?.len());
+
+    // In particular, the range-check lookup tables are only instantiated for
+    // the ISAs supporting RANGE_CHECK.
+    let reduced = Circuit::new(Box::new(ArithmeticISA));
+    assert!(reduced.instance.range_lookup_tables.is_empty());
+    let generic = Circuit::new(Box::new(GenericISA));
+    assert_eq!(generic.instance.range_lookup_tables.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! Parameters of PetraVM proofs, shared by the prover and the verifier.
+
+const LOG_INV_RATE: usize = 1;
+const SECURITY_BITS: usize = 100;
+
+/// Hash function used by the Fiat-Shamir transform and the Merkle tree
+/// commitments of a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum FiatShamirHash {
+    /// Grøstl-256, with its byte compression function for the Merkle trees.
+    #[default]
+    Groestl256,
+}
+
+/// Binds the hash and compression types selected by a [`ProverConfig`], to be
+/// used as type parameters in `$body`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! with_hash {
+    ($config:expr, |$hash:ident, $compression:ident| $body:expr) => {
+        match $config.hash {
+            $crate::FiatShamirHash::Groestl256 => {
+                type $hash = $crate::__private::Groestl256;
+                type $compression = $crate::__private::Groestl256ByteCompression;
+                $body
+            }
+        }
+    };
+}
+
+/// Parameters of the proofs generated by a PetraVM prover.
+///
+/// A higher inverse rate yields smaller proofs at the cost of a slower
+/// commitment, while fewer security bits yield both smaller and faster
+/// proofs. A proof can only be verified with the configuration it was
+/// generated with, which is part of the
+/// [`VerifyingKey`](crate::VerifyingKey).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProverConfig {
+    /// Base-2 logarithm of the inverse rate of the Reed-Solomon code.
+    pub log_inv_rate: usize,
+    /// Target security level, in bits.
+    pub security_bits: usize,
+    /// Hash function of the Fiat-Shamir transform and the commitments.
+    pub hash: FiatShamirHash,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            log_inv_rate: LOG_INV_RATE,
+            security_bits: SECURITY_BITS,
+            hash: FiatShamirHash::default(),
+        }
+    }
+}
@@ -1 +1,20 @@
 //! PetraVM verifier.
+//!
+//! This crate checks PetraVM execution proofs given only a [`VerifyingKey`],
+//! the channel boundaries of the circuit and the proof, without depending on
+//! the assembler, the emulator or the prover. Verification keys are exported
+//! by the prover, and can be shipped in their serialized form.
+//!
+//! Like the Binius crates it builds on, it requires the standard library.
+
+pub mod config;
+pub mod serialization;
+mod verify;
+
+pub use config::{FiatShamirHash, ProverConfig};
+pub use verify::{verify, verify_bytes, verify_with_compiled_cs, VerifyingKey};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use binius_hash::groestl::{Groestl256, Groestl256ByteCompression};
+}
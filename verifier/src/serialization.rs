@@ -0,0 +1,143 @@
+//! Binary serialization of the artifacts exchanged between a prover and a
+//! verifier.
+//!
+//! This allows a proof to be generated in one process and verified in another
+//! one. All encodings rely on the [`SerializeBytes`] and [`DeserializeBytes`]
+//! traits from Binius, in canonical tower mode. Encodings followed by trailing
+//! bytes are rejected.
+
+use anyhow::{bail, ensure, Result};
+use binius_core::constraint_system::{channel::Boundary, ConstraintSystem, Proof};
+use binius_field::BinaryField128b as B128;
+use binius_utils::{DeserializeBytes, SerializationMode, SerializeBytes};
+
+use crate::{FiatShamirHash, ProverConfig, VerifyingKey};
+
+const MODE: SerializationMode = SerializationMode::CanonicalTower;
+
+/// Checks that the whole encoding of `what` was consumed.
+fn ensure_consumed(bytes: &[u8], what: &str) -> Result<()> {
+    ensure!(
+        bytes.is_empty(),
+        "{} trailing bytes after the {what}",
+        bytes.len()
+    );
+    Ok(())
+}
+
+impl ProverConfig {
+    /// Serializes the proving parameters, as they prefix a serialized
+    /// [`VerifyingKey`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        (self.log_inv_rate as u32).serialize(&mut bytes, MODE)?;
+        (self.security_bits as u32).serialize(&mut bytes, MODE)?;
+        let hash: u8 = match self.hash {
+            FiatShamirHash::Groestl256 => 0,
+        };
+        hash.serialize(&mut bytes, MODE)?;
+        Ok(bytes)
+    }
+}
+
+impl VerifyingKey {
+    /// Serializes the verification key.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = self.config.to_bytes()?;
+        self.compiled_cs.serialize(&mut bytes, MODE)?;
+        Ok(bytes)
+    }
+
+    /// Deserializes a verification key produced by
+    /// [`VerifyingKey::to_bytes`].
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+        let log_inv_rate = u32::deserialize(&mut bytes, MODE)? as usize;
+        let security_bits = u32::deserialize(&mut bytes, MODE)? as usize;
+        let hash = match u8::deserialize(&mut bytes, MODE)? {
+            0 => FiatShamirHash::Groestl256,
+            hash => bail!("Unknown Fiat-Shamir hash {hash}"),
+        };
+        let compiled_cs = ConstraintSystem::<B128>::deserialize(&mut bytes, MODE)?;
+        ensure_consumed(bytes, "verifying key")?;
+        Ok(Self {
+            compiled_cs,
+            config: ProverConfig {
+                log_inv_rate,
+                security_bits,
+                hash,
+            },
+        })
+    }
+}
+
+/// Serializes a proof.
+pub fn serialize_proof(proof: &Proof) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    proof.transcript.serialize(&mut bytes, MODE)?;
+    Ok(bytes)
+}
+
+/// Deserializes a proof produced by [`serialize_proof`].
+pub fn deserialize_proof(mut bytes: &[u8]) -> Result<Proof> {
+    let transcript = Vec::<u8>::deserialize(&mut bytes, MODE)?;
+    ensure_consumed(bytes, "proof")?;
+    Ok(Proof { transcript })
+}
+
+/// Serializes the channel boundaries of a circuit.
+pub fn serialize_boundaries(boundaries: &[Boundary<B128>]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    boundaries.to_vec().serialize(&mut bytes, MODE)?;
+    Ok(bytes)
+}
+
+/// Deserializes channel boundaries produced by [`serialize_boundaries`].
+pub fn deserialize_boundaries(mut bytes: &[u8]) -> Result<Vec<Boundary<B128>>> {
+    let boundaries = Vec::<Boundary<B128>>::deserialize(&mut bytes, MODE)?;
+    ensure_consumed(bytes, "boundaries")?;
+    Ok(boundaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_hash() {
+        let mut bytes = Vec::new();
+        1u32.serialize(&mut bytes, MODE).unwrap();
+        100u32.serialize(&mut bytes, MODE).unwrap();
+        0xffu8.serialize(&mut bytes, MODE).unwrap();
+        let err = VerifyingKey::from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown Fiat-Shamir hash 255");
+    }
+
+    #[test]
+    fn test_truncated_proof() {
+        let proof = Proof {
+            transcript: vec![1, 2, 3, 4],
+        };
+        let bytes = serialize_proof(&proof).unwrap();
+        assert_eq!(
+            deserialize_proof(&bytes).unwrap().transcript,
+            proof.transcript
+        );
+        assert!(deserialize_proof(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        let proof = Proof {
+            transcript: vec![1, 2, 3, 4],
+        };
+        let mut bytes = serialize_proof(&proof).unwrap();
+        bytes.push(0);
+        let err = deserialize_proof(&bytes).unwrap_err();
+        assert_eq!(err.to_string(), "1 trailing bytes after the proof");
+
+        let mut bytes = serialize_boundaries(&[]).unwrap();
+        bytes.extend_from_slice(&[0, 0]);
+        let err = deserialize_boundaries(&bytes).unwrap_err();
+        assert_eq!(err.to_string(), "2 trailing bytes after the boundaries");
+    }
+}
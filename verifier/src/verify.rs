@@ -0,0 +1,90 @@
+//! Verification of PetraVM execution proofs.
+
+use anyhow::Result;
+use binius_core::{
+    constraint_system::{self, channel::Boundary, ConstraintSystem, Proof},
+    fiat_shamir::HasherChallenger,
+};
+use binius_field::{arch::OptimalUnderlier, tower::CanonicalTowerFamily, BinaryField128b as B128};
+use tracing::instrument;
+
+use crate::{
+    serialization::{deserialize_boundaries, deserialize_proof},
+    ProverConfig,
+};
+
+/// Verification key of a PetraVM circuit.
+///
+/// It only depends on the ISA targeted by the prover and on its
+/// [`ProverConfig`], and can be reused to verify any proof generated by a
+/// prover targeting the same ISA with the same configuration.
+#[derive(Debug, Clone)]
+pub struct VerifyingKey {
+    /// The compiled constraint system of the circuit.
+    pub compiled_cs: ConstraintSystem<B128>,
+    /// The proving parameters.
+    pub config: ProverConfig,
+}
+
+/// Verify a PetraVM execution proof.
+///
+/// # Arguments
+/// * `vk` - The verification key of the circuit
+/// * `boundaries` - The channel boundaries of the circuit
+/// * `proof` - The proof to verify (taken by value)
+///
+/// # Returns
+/// * Result indicating success or error
+pub fn verify(vk: &VerifyingKey, boundaries: &[Boundary<B128>], proof: Proof) -> Result<()> {
+    verify_with_compiled_cs(&vk.compiled_cs, &vk.config, boundaries, proof)
+}
+
+/// Verify a serialized PetraVM execution proof, given the serialized channel
+/// boundaries of the circuit.
+///
+/// # Arguments
+/// * `vk` - The verification key of the circuit
+/// * `boundaries` - The channel boundaries, as serialized by
+///   [`serialize_boundaries`](crate::serialization::serialize_boundaries)
+/// * `proof` - The proof, as serialized by
+///   [`serialize_proof`](crate::serialization::serialize_proof)
+///
+/// # Returns
+/// * Result indicating success or error
+pub fn verify_bytes(vk: &VerifyingKey, boundaries: &[u8], proof: &[u8]) -> Result<()> {
+    verify(
+        vk,
+        &deserialize_boundaries(boundaries)?,
+        deserialize_proof(proof)?,
+    )
+}
+
+/// Verify a PetraVM execution proof against a compiled constraint system,
+/// with the given proving parameters.
+#[instrument(level = "info", skip_all)]
+pub fn verify_with_compiled_cs(
+    compiled_cs: &ConstraintSystem<B128>,
+    config: &ProverConfig,
+    boundaries: &[Boundary<B128>],
+    proof: Proof,
+) -> Result<()> {
+    crate::with_hash!(config, |Hash, Compression| {
+        let ccs_digest = compiled_cs.digest::<Hash>();
+        constraint_system::verify::<
+            OptimalUnderlier,
+            CanonicalTowerFamily,
+            Hash,
+            Compression,
+            HasherChallenger<Hash>,
+        >(
+            compiled_cs,
+            config.log_inv_rate,
+            config.security_bits,
+            &ccs_digest,
+            boundaries,
+            proof,
+        )?
+    });
+
+    Ok(())
+}